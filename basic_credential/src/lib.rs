@@ -50,12 +50,32 @@ impl Signer for SignatureKeyPair {
                 let signature = k.sign(payload);
                 Ok(signature.to_der().to_bytes().into())
             }
+            SignatureScheme::ECDSA_SECP384R1_SHA384 => {
+                let k = p384::ecdsa::SigningKey::from_bytes(&self.private)
+                    .map_err(|_| Error::SigningError)?;
+                let signature: p384::ecdsa::Signature = k.sign(payload);
+                Ok(signature.to_der().to_bytes().into())
+            }
+            SignatureScheme::ECDSA_SECP521R1_SHA512 => {
+                use p521::ecdsa::signature::Signer;
+
+                let k = p521::ecdsa::SigningKey::from_slice(&self.private)
+                    .map_err(|_| Error::SigningError)?;
+                let signature: p521::ecdsa::Signature = k.sign(payload);
+                Ok(signature.to_der().to_bytes().into())
+            }
             SignatureScheme::ED25519 => {
                 let k = ed25519_dalek::Keypair::from_bytes(&self.private)
                     .map_err(|_| Error::SigningError)?;
                 let signature = k.sign(payload);
                 Ok(signature.to_bytes().into())
             }
+            SignatureScheme::ED448 => {
+                let k = ed448_rust::PrivateKey::try_from(self.private.as_slice())
+                    .map_err(|_| Error::SigningError)?;
+                let signature = k.sign(payload, None).map_err(|_| Error::SigningError)?;
+                Ok(signature.into())
+            }
             _ => Err(Error::SigningError),
         }
     }
@@ -88,6 +108,16 @@ impl SignatureKeyPair {
                 let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
                 (k.to_bytes().as_slice().into(), pk)
             }
+            SignatureScheme::ECDSA_SECP384R1_SHA384 => {
+                let k = p384::ecdsa::SigningKey::random(&mut OsRng);
+                let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
+                (k.to_bytes().as_slice().into(), pk)
+            }
+            SignatureScheme::ECDSA_SECP521R1_SHA512 => {
+                let k = p521::ecdsa::SigningKey::random(&mut OsRng);
+                let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
+                (k.to_bytes().as_slice().into(), pk)
+            }
             SignatureScheme::ED25519 => {
                 let k = ed25519_dalek::Keypair::generate(&mut rand_07::rngs::OsRng).to_bytes();
                 let pk = k[ed25519_dalek::SECRET_KEY_LENGTH..].to_vec();
@@ -95,6 +125,11 @@ impl SignatureKeyPair {
                 let sk_pk = k.into();
                 (sk_pk, pk)
             }
+            SignatureScheme::ED448 => {
+                let k = ed448_rust::PrivateKey::new(&mut OsRng);
+                let pk = ed448_rust::PublicKey::from(&k).as_byte().into();
+                (k.as_bytes().as_slice().into(), pk)
+            }
             _ => return Err(CryptoError::UnsupportedSignatureScheme),
         };
 
@@ -0,0 +1,346 @@
+//! # Encrypted Keystore
+//!
+//! A wrapper around any [`OpenMlsKeyStore`] that encrypts all values before
+//! they are handed to the wrapped key store, such that private keys, e.g.,
+//! HPKE and signature keys, are protected at rest.
+//!
+//! The encryption key is derived from a passphrase with Argon2id, by default
+//! with the parameters recommended by OWASP, i.e., 19 MiB of memory and two
+//! iterations. The salt and the parameters of the key derivation are stored in
+//! the wrapped key store when it is opened for the first time, such that the
+//! same passphrase opens it again later. Values are encrypted with
+//! ChaCha20Poly1305 under a fresh random nonce, and are bound to their ID in
+//! the key store, such that encrypted values can't be swapped.
+//!
+//! ```
+//! use openmls_encrypted_keystore::EncryptedKeyStore;
+//! use openmls_memory_keystore::MemoryKeyStore;
+//!
+//! let key_store = EncryptedKeyStore::open(MemoryKeyStore::default(), b"passphrase")
+//!     .expect("Error opening the key store.");
+//! ```
+
+use std::marker::PhantomData;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{Aead, NewAead, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use openmls_traits::key_store::{MlsEntity, MlsEntityId, OpenMlsKeyStore};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+pub use argon2;
+
+/// The ID of the [`KeyStoreHeader`] in the wrapped key store.
+const HEADER_ID: &[u8] = b"OpenMlsEncryptedKeyStoreHeader";
+const SALT_LENGTH: usize = 16;
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+/// The minimum memory size of the key derivation in KiB, as recommended by
+/// OWASP for Argon2id.
+pub const MIN_M_COST: u32 = 19 * 1024;
+/// The minimum number of iterations of the key derivation, as recommended by
+/// OWASP for Argon2id.
+pub const MIN_T_COST: u32 = 2;
+
+/// The salt and the parameters of the key derivation, along with a value to
+/// check the passphrase.
+#[derive(Serialize, Deserialize)]
+struct KeyStoreHeader {
+    salt: Vec<u8>,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    // An encryption of the empty string under the derived key.
+    check: Vec<u8>,
+}
+
+impl MlsEntity for KeyStoreHeader {
+    const ID: MlsEntityId = MlsEntityId::ClientState;
+}
+
+/// An encrypted value of type `V` in the wrapped key store. It keeps the
+/// [`MlsEntityId`] of `V`, such that the wrapped key store can still tell the
+/// entities apart.
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+struct EncryptedValue<V> {
+    ciphertext: Vec<u8>,
+    #[serde(skip)]
+    _value: PhantomData<V>,
+}
+
+impl<V: MlsEntity> MlsEntity for EncryptedValue<V> {
+    const ID: MlsEntityId = V::ID;
+}
+
+/// A key store that encrypts all values under a key derived from a
+/// passphrase before delegating to the wrapped key store.
+pub struct EncryptedKeyStore<KeyStore: OpenMlsKeyStore> {
+    inner: KeyStore,
+    cipher: ChaCha20Poly1305,
+}
+
+impl<KeyStore: OpenMlsKeyStore> std::fmt::Debug for EncryptedKeyStore<KeyStore> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Don't leak the key into logs.
+        f.debug_struct("EncryptedKeyStore").finish_non_exhaustive()
+    }
+}
+
+impl<KeyStore: OpenMlsKeyStore> EncryptedKeyStore<KeyStore> {
+    /// Opens the encrypted key store in `inner` with `passphrase`. If `inner`
+    /// wasn't opened before, a new salt is generated and the key is derived
+    /// with the Argon2id parameters recommended by OWASP, i.e.,
+    /// [`MIN_M_COST`] and [`MIN_T_COST`] with a single lane.
+    ///
+    /// Returns [`EncryptedKeyStoreError::WrongPassphrase`] if `inner` was
+    /// opened with a different passphrase before.
+    pub fn open(
+        inner: KeyStore,
+        passphrase: &[u8],
+    ) -> Result<Self, EncryptedKeyStoreError<KeyStore::Error>> {
+        let params = Params::new(MIN_M_COST, MIN_T_COST, 1, None)
+            .map_err(|_| EncryptedKeyStoreError::KeyDerivationError)?;
+        Self::open_with_params(inner, passphrase, params)
+    }
+
+    /// Opens the encrypted key store in `inner` with `passphrase`. If `inner`
+    /// wasn't opened before, a new salt is generated and the key is derived
+    /// with the Argon2id parameters `params`. Otherwise, `params` are ignored
+    /// and the parameters that were used when `inner` was opened for the
+    /// first time are used.
+    ///
+    /// Returns [`EncryptedKeyStoreError::WeakKeyDerivationParams`] if `inner`
+    /// wasn't opened before and `params` use less memory than [`MIN_M_COST`]
+    /// or fewer iterations than [`MIN_T_COST`].
+    /// Returns [`EncryptedKeyStoreError::WrongPassphrase`] if `inner` was
+    /// opened with a different passphrase before.
+    pub fn open_with_params(
+        inner: KeyStore,
+        passphrase: &[u8],
+        params: Params,
+    ) -> Result<Self, EncryptedKeyStoreError<KeyStore::Error>> {
+        if let Some(header) = inner.read::<KeyStoreHeader>(HEADER_ID) {
+            let params = Params::new(header.m_cost, header.t_cost, header.p_cost, None)
+                .map_err(|_| EncryptedKeyStoreError::KeyDerivationError)?;
+            let cipher = derive_cipher(passphrase, &header.salt, params)?;
+            let key_store = Self { inner, cipher };
+            key_store
+                .decrypt(HEADER_ID, &header.check)
+                .map_err(|_| EncryptedKeyStoreError::WrongPassphrase)?;
+            return Ok(key_store);
+        }
+
+        if params.m_cost() < MIN_M_COST || params.t_cost() < MIN_T_COST {
+            return Err(EncryptedKeyStoreError::WeakKeyDerivationParams);
+        }
+
+        let mut salt = vec![0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let (m_cost, t_cost, p_cost) = (params.m_cost(), params.t_cost(), params.p_cost());
+        let cipher = derive_cipher(passphrase, &salt, params)?;
+        let key_store = Self { inner, cipher };
+        let header = KeyStoreHeader {
+            salt,
+            m_cost,
+            t_cost,
+            p_cost,
+            check: key_store.encrypt(HEADER_ID, &[])?,
+        };
+        key_store
+            .inner
+            .store(HEADER_ID, &header)
+            .map_err(EncryptedKeyStoreError::InnerError)?;
+        Ok(key_store)
+    }
+
+    /// Returns a reference to the wrapped key store.
+    pub fn inner(&self) -> &KeyStore {
+        &self.inner
+    }
+
+    /// Returns the wrapped key store.
+    pub fn into_inner(self) -> KeyStore {
+        self.inner
+    }
+
+    /// Encrypts `plaintext` under a fresh nonce and binds it to the ID `k`.
+    /// The nonce is prepended to the ciphertext.
+    fn encrypt(
+        &self,
+        k: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, EncryptedKeyStoreError<KeyStore::Error>> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: k,
+                },
+            )
+            .map_err(|_| EncryptedKeyStoreError::EncryptionError)?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    /// Decrypts a value that was encrypted with [`Self::encrypt()`] for the
+    /// ID `k`.
+    fn decrypt(
+        &self,
+        k: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, EncryptedKeyStoreError<KeyStore::Error>> {
+        if ciphertext.len() < NONCE_LENGTH {
+            return Err(EncryptedKeyStoreError::DecryptionError);
+        }
+        let (nonce, ciphertext) = ciphertext.split_at(NONCE_LENGTH);
+        self.cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: k,
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| EncryptedKeyStoreError::DecryptionError)
+    }
+}
+
+/// Derives the encryption key from `passphrase` with Argon2id.
+fn derive_cipher<E>(
+    passphrase: &[u8],
+    salt: &[u8],
+    params: Params,
+) -> Result<ChaCha20Poly1305, EncryptedKeyStoreError<E>> {
+    // Don't leave a copy of the key in memory.
+    let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase, salt, key.as_mut_slice())
+        .map_err(|_| EncryptedKeyStoreError::KeyDerivationError)?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(key.as_slice())))
+}
+
+impl<KeyStore: OpenMlsKeyStore> OpenMlsKeyStore for EncryptedKeyStore<KeyStore> {
+    /// The error type returned by the [`OpenMlsKeyStore`].
+    type Error = EncryptedKeyStoreError<KeyStore::Error>;
+
+    /// Encrypt and store a value `v` that implements the [`MlsEntity`] trait
+    /// for serialization for ID `k` in the wrapped key store.
+    ///
+    /// Returns an error if storing fails.
+    fn store<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), Self::Error> {
+        // Don't leave a copy of the serialized value, e.g., a private key, in
+        // memory.
+        let value = Zeroizing::new(
+            serde_json::to_vec(v).map_err(|_| EncryptedKeyStoreError::SerializationError)?,
+        );
+        let encrypted_value = EncryptedValue::<V> {
+            ciphertext: self.encrypt(k, &value)?,
+            _value: PhantomData,
+        };
+        self.inner
+            .store(k, &encrypted_value)
+            .map_err(EncryptedKeyStoreError::InnerError)
+    }
+
+    /// Read, decrypt and return a value stored for ID `k` that implements the
+    /// [`MlsEntity`] trait for deserialization.
+    ///
+    /// Returns [`None`] if no value is stored for `k` or reading or decrypting
+    /// fails.
+    fn read<V: MlsEntity>(&self, k: &[u8]) -> Option<V> {
+        let encrypted_value = self.inner.read::<EncryptedValue<V>>(k)?;
+        let value = self.decrypt(k, &encrypted_value.ciphertext).ok()?;
+        serde_json::from_slice(&value).ok()
+    }
+
+    /// Delete a value stored for ID `k` from the wrapped key store.
+    ///
+    /// Returns an error if deleting fails.
+    fn delete<V: MlsEntity>(&self, k: &[u8]) -> Result<(), Self::Error> {
+        self.inner
+            .delete::<EncryptedValue<V>>(k)
+            .map_err(EncryptedKeyStoreError::InnerError)
+    }
+}
+
+/// Errors thrown by the encrypted key store.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum EncryptedKeyStoreError<E> {
+    #[error("Error in the wrapped key store: {0}")]
+    InnerError(E),
+    #[error("The passphrase doesn't match the key store.")]
+    WrongPassphrase,
+    #[error("Error deriving the key from the passphrase.")]
+    KeyDerivationError,
+    #[error("The parameters of the key derivation are weaker than recommended.")]
+    WeakKeyDerivationParams,
+    #[error("Error serializing value.")]
+    SerializationError,
+    #[error("Error encrypting value.")]
+    EncryptionError,
+    #[error("Error decrypting value.")]
+    DecryptionError,
+}
+
+#[cfg(test)]
+mod tests {
+    use openmls_memory_keystore::MemoryKeyStore;
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct PrivateKey(Vec<u8>);
+
+    impl MlsEntity for PrivateKey {
+        const ID: MlsEntityId = MlsEntityId::HpkePrivateKey;
+    }
+
+    #[test]
+    fn store_and_read() {
+        let key_store = EncryptedKeyStore::open(MemoryKeyStore::default(), b"passphrase")
+            .expect("Error opening the key store.");
+        let private_key = PrivateKey(vec![0x42; 32]);
+        key_store.store(b"id", &private_key).unwrap();
+        assert_eq!(
+            key_store.read::<PrivateKey>(b"id"),
+            Some(private_key.clone())
+        );
+
+        // The wrapped key store only sees the ciphertext.
+        let inner = key_store.into_inner();
+        assert_ne!(inner.read::<PrivateKey>(b"id"), Some(private_key.clone()));
+
+        // The key store can be opened again with the same passphrase, but not
+        // with a different one.
+        let key_store = EncryptedKeyStore::open(inner, b"passphrase").unwrap();
+        assert_eq!(key_store.read::<PrivateKey>(b"id"), Some(private_key));
+        key_store.delete::<PrivateKey>(b"id").unwrap();
+        assert!(key_store.read::<PrivateKey>(b"id").is_none());
+
+        let err = EncryptedKeyStore::open(key_store.into_inner(), b"wrong passphrase")
+            .expect_err("Opened the key store with a wrong passphrase.");
+        assert_eq!(err, EncryptedKeyStoreError::WrongPassphrase);
+    }
+
+    #[test]
+    fn weak_params() {
+        let weak_params = Params::new(4096, 3, 1, None).unwrap();
+        let err = EncryptedKeyStore::open_with_params(
+            MemoryKeyStore::default(),
+            b"passphrase",
+            weak_params,
+        )
+        .expect_err("Opened the key store with weak parameters.");
+        assert_eq!(err, EncryptedKeyStoreError::WeakKeyDerivationParams);
+    }
+}
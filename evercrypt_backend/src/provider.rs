@@ -260,14 +260,14 @@ impl OpenMlsCrypto for EvercryptProvider {
         info: &[u8],
         aad: &[u8],
         ptxt: &[u8],
-    ) -> openmls_traits::types::HpkeCiphertext {
-        let (kem_output, ciphertext) = hpke_from_config(config)
+    ) -> Result<openmls_traits::types::HpkeCiphertext, CryptoError> {
+        let (kem_output, ciphertext) = hpke_from_config(config)?
             .seal(&pk_r.into(), info, aad, ptxt, None, None, None)
-            .unwrap();
-        HpkeCiphertext {
+            .map_err(|_| CryptoError::HpkeEncryptionError)?;
+        Ok(HpkeCiphertext {
             kem_output: kem_output.into(),
             ciphertext: ciphertext.into(),
-        }
+        })
     }
 
     fn hpke_open(
@@ -278,7 +278,7 @@ impl OpenMlsCrypto for EvercryptProvider {
         info: &[u8],
         aad: &[u8],
     ) -> Result<Vec<u8>, CryptoError> {
-        hpke_from_config(config)
+        hpke_from_config(config)?
             .open(
                 input.kem_output.as_slice(),
                 &sk_r.into(),
@@ -300,7 +300,7 @@ impl OpenMlsCrypto for EvercryptProvider {
         exporter_context: &[u8],
         exporter_length: usize,
     ) -> Result<(KemOutput, ExporterSecret), CryptoError> {
-        let (kem_output, context) = hpke_from_config(config)
+        let (kem_output, context) = hpke_from_config(config)?
             .setup_sender(&pk_r.into(), info, None, None, None)
             .map_err(|_| CryptoError::SenderSetupError)?;
         let exported_secret = context
@@ -318,7 +318,7 @@ impl OpenMlsCrypto for EvercryptProvider {
         exporter_context: &[u8],
         exporter_length: usize,
     ) -> Result<ExporterSecret, CryptoError> {
-        let context = hpke_from_config(config)
+        let context = hpke_from_config(config)?
             .setup_receiver(enc, &sk_r.into(), info, None, None, None)
             .map_err(|_| CryptoError::ReceiverSetupError)?;
         let exported_secret = context
@@ -333,6 +333,7 @@ impl OpenMlsCrypto for EvercryptProvider {
         ikm: &[u8],
     ) -> openmls_traits::types::HpkeKeyPair {
         let kp = hpke_from_config(config)
+            .unwrap()
             .derive_key_pair(ikm)
             .unwrap()
             .into_keys();
@@ -343,23 +344,25 @@ impl OpenMlsCrypto for EvercryptProvider {
     }
 }
 
-fn hpke_from_config(config: HpkeConfig) -> Hpke<HpkeEvercrypt> {
-    Hpke::<HpkeEvercrypt>::new(
+fn hpke_from_config(config: HpkeConfig) -> Result<Hpke<HpkeEvercrypt>, CryptoError> {
+    Ok(Hpke::<HpkeEvercrypt>::new(
         hpke::Mode::Base,
-        kem_mode(config.0),
+        kem_mode(config.0)?,
         kdf_mode(config.1),
         aead_mode(config.2),
-    )
+    ))
 }
 
 #[inline(always)]
-fn kem_mode(kem: HpkeKemType) -> hpke_types::KemAlgorithm {
+fn kem_mode(kem: HpkeKemType) -> Result<hpke_types::KemAlgorithm, CryptoError> {
     match kem {
-        HpkeKemType::DhKemP256 => hpke_types::KemAlgorithm::DhKemP256,
-        HpkeKemType::DhKemP384 => hpke_types::KemAlgorithm::DhKemP384,
-        HpkeKemType::DhKemP521 => hpke_types::KemAlgorithm::DhKemP521,
-        HpkeKemType::DhKem25519 => hpke_types::KemAlgorithm::DhKem25519,
-        HpkeKemType::DhKem448 => hpke_types::KemAlgorithm::DhKem448,
+        HpkeKemType::DhKemP256 => Ok(hpke_types::KemAlgorithm::DhKemP256),
+        HpkeKemType::DhKemP384 => Ok(hpke_types::KemAlgorithm::DhKemP384),
+        HpkeKemType::DhKemP521 => Ok(hpke_types::KemAlgorithm::DhKemP521),
+        HpkeKemType::DhKem25519 => Ok(hpke_types::KemAlgorithm::DhKem25519),
+        HpkeKemType::DhKem448 => Ok(hpke_types::KemAlgorithm::DhKem448),
+        // Hybrid KEMs are not supported by this backend.
+        HpkeKemType::X25519Kyber768Draft00 => Err(CryptoError::UnsupportedKem),
     }
 }
 
@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use openmls::prelude::*;
+use openmls_rust_crypto::OpenMlsRustCrypto;
+
+fuzz_target!(|key_package: KeyPackageIn| {
+    let backend = OpenMlsRustCrypto::default();
+    let _ = key_package.validate(backend.crypto());
+});
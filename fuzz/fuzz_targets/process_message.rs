@@ -0,0 +1,35 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use openmls::prelude::*;
+use openmls_basic_credential::SignatureKeyPair;
+use openmls_rust_crypto::OpenMlsRustCrypto;
+
+fuzz_target!(|message: MlsMessageIn| {
+    let message = match message.into_protocol_message() {
+        Some(message) => message,
+        None => return,
+    };
+
+    let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+    let backend = OpenMlsRustCrypto::default();
+    let signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+    signer.store(backend.key_store()).unwrap();
+    let credential_with_key = CredentialWithKey {
+        credential: Credential::new(b"Fuzzer".to_vec(), CredentialType::Basic).unwrap(),
+        signature_key: signer.public().into(),
+    };
+
+    // A fixed group ID and the first epoch make it easy for the fuzzer to get
+    // past the framing checks.
+    let mut group = MlsGroup::new_with_group_id(
+        &backend,
+        &signer,
+        &MlsGroupConfig::default(),
+        GroupId::from_slice(b"fuzz"),
+        credential_with_key,
+    )
+    .unwrap();
+
+    let _ = group.process_message(&backend, message);
+});
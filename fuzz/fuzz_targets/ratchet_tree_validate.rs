@@ -0,0 +1,15 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use openmls::prelude::*;
+use openmls_rust_crypto::OpenMlsRustCrypto;
+
+fuzz_target!(|input: (RatchetTreeIn, GroupId)| {
+    let (ratchet_tree, group_id) = input;
+    let backend = OpenMlsRustCrypto::default();
+    let _ = ratchet_tree.into_verified(
+        Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+        backend.crypto(),
+        &group_id,
+    );
+});
@@ -6,6 +6,31 @@ pub struct MemoryKeyStore {
     values: RwLock<HashMap<Vec<u8>, Vec<u8>>>,
 }
 
+/// A copy of all values of a [`MemoryKeyStore`] at a point in time. See
+/// [`MemoryKeyStore::snapshot()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryKeyStoreSnapshot {
+    values: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl MemoryKeyStore {
+    /// Returns a copy of all values in the key store, e.g., to run several
+    /// test scenarios from the same state. The snapshot can be restored with
+    /// [`MemoryKeyStore::restore()`].
+    pub fn snapshot(&self) -> MemoryKeyStoreSnapshot {
+        let values = self.values.read().unwrap();
+        MemoryKeyStoreSnapshot {
+            values: values.clone(),
+        }
+    }
+
+    /// Replaces all values in the key store with the values of `snapshot`.
+    pub fn restore(&self, snapshot: &MemoryKeyStoreSnapshot) {
+        let mut values = self.values.write().unwrap();
+        *values = snapshot.values.clone();
+    }
+}
+
 impl OpenMlsKeyStore for MemoryKeyStore {
     /// The error type returned by the [`OpenMlsKeyStore`].
     type Error = MemoryKeyStoreError;
@@ -46,12 +46,223 @@ fn criterion_kp_bundle(c: &mut Criterion, backend: &impl OpenMlsCryptoProvider)
     }
 }
 
+fn criterion_serialization(c: &mut Criterion, backend: &impl OpenMlsCryptoProvider) {
+    for &ciphersuite in backend.crypto().supported_ciphersuites().iter() {
+        let credential = Credential::new(vec![1, 2, 3], CredentialType::Basic).unwrap();
+        let signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+        let credential_with_key = CredentialWithKey {
+            credential,
+            signature_key: signer.to_public_vec().into(),
+        };
+        let config = MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .build();
+        let mut group = MlsGroup::new(backend, &signer, &config, credential_with_key)
+            .expect("An unexpected error occurred.");
+        let payload = vec![0u8; 1024];
+
+        c.bench_function(
+            &format!(
+                "Create and serialize message (fresh buffer) with ciphersuite: {ciphersuite:?}"
+            ),
+            |b| {
+                b.iter(|| {
+                    let _bytes = group
+                        .create_message(backend, &signer, &payload)
+                        .expect("An unexpected error occurred.")
+                        .to_bytes()
+                        .expect("An unexpected error occurred.");
+                });
+            },
+        );
+
+        let mut buffer = Vec::new();
+        c.bench_function(
+            &format!(
+                "Create and serialize message (reused buffer) with ciphersuite: {ciphersuite:?}"
+            ),
+            |b| {
+                b.iter(|| {
+                    buffer.clear();
+                    group
+                        .create_message_into(backend, &signer, &payload, &mut buffer)
+                        .expect("An unexpected error occurred.");
+                });
+            },
+        );
+
+        let message = group
+            .create_message(backend, &signer, &payload)
+            .expect("An unexpected error occurred.");
+        c.bench_function(
+            &format!("Serialize message (fresh buffer) with ciphersuite: {ciphersuite:?}"),
+            |b| {
+                b.iter(|| {
+                    let _bytes = message.to_bytes().expect("An unexpected error occurred.");
+                });
+            },
+        );
+        c.bench_function(
+            &format!("Serialize message (reused buffer) with ciphersuite: {ciphersuite:?}"),
+            |b| {
+                b.iter(|| {
+                    buffer.clear();
+                    message
+                        .serialize_into(&mut buffer)
+                        .expect("An unexpected error occurred.");
+                });
+            },
+        );
+    }
+}
+
+fn criterion_large_group(c: &mut Criterion, backend: &impl OpenMlsCryptoProvider) {
+    const GROUP_SIZES: [usize; 3] = [1_000, 10_000, 50_000];
+
+    let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+    let crypto_config = CryptoConfig::with_default_version(ciphersuite);
+    let new_key_package = |identity: Vec<u8>| {
+        let credential = Credential::new(identity, CredentialType::Basic).unwrap();
+        let signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+        let credential_with_key = CredentialWithKey {
+            credential,
+            signature_key: signer.to_public_vec().into(),
+        };
+        KeyPackage::builder()
+            .build(crypto_config, backend, &signer, credential_with_key)
+            .expect("An unexpected error occurred.")
+    };
+
+    for group_size in GROUP_SIZES {
+        let credential = Credential::new(b"Alice".to_vec(), CredentialType::Basic).unwrap();
+        let signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+        let credential_with_key = CredentialWithKey {
+            credential,
+            signature_key: signer.to_public_vec().into(),
+        };
+        // Commits are sent as public messages, so that the same commit can be
+        // processed repeatedly.
+        let config = MlsGroupConfig::builder()
+            .crypto_config(crypto_config)
+            .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+            .build();
+        let mut group = MlsGroup::new(backend, &signer, &config, credential_with_key)
+            .expect("An unexpected error occurred.");
+
+        // Add all members in a single commit to keep the setup fast.
+        let key_packages: Vec<KeyPackage> = (1..group_size)
+            .map(|i| new_key_package(format!("Member {i}").into_bytes()))
+            .collect();
+        let (_, welcome, _) = group
+            .add_members(backend, &signer, &key_packages)
+            .expect("An unexpected error occurred.");
+        group
+            .merge_pending_commit(backend)
+            .expect("An unexpected error occurred.");
+        let mut member_group = MlsGroup::new_from_welcome(
+            backend,
+            &config,
+            welcome.into_welcome().expect("Expected a Welcome message."),
+            Some(group.export_ratchet_tree().into()),
+        )
+        .expect("An unexpected error occurred.");
+
+        // The commit is discarded after each iteration, so that every
+        // iteration commits to the same epoch.
+        c.bench_function(
+            &format!("Create a commit in a group with {group_size} members"),
+            |b| {
+                b.iter(|| {
+                    group
+                        .self_update(backend, &signer)
+                        .expect("An unexpected error occurred.");
+                    group.clear_pending_commit();
+                });
+            },
+        );
+
+        let (commit, _, _) = group
+            .add_members(backend, &signer, &[new_key_package(b"New member".to_vec())])
+            .expect("An unexpected error occurred.");
+        let commit = commit
+            .into_protocol_message()
+            .expect("Expected a protocol message.");
+
+        // Staging the commit requires computing the tree hash of the new tree.
+        c.bench_function(
+            &format!("Process a commit in a group with {group_size} members"),
+            |b| {
+                b.iter(|| {
+                    member_group
+                        .process_message(backend, commit.clone())
+                        .expect("An unexpected error occurred.");
+                });
+            },
+        );
+    }
+}
+
+fn criterion_add_members(c: &mut Criterion, backend: &impl OpenMlsCryptoProvider) {
+    let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+    let crypto_config = CryptoConfig::with_default_version(ciphersuite);
+
+    let credential = Credential::new(b"Alice".to_vec(), CredentialType::Basic).unwrap();
+    let signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+    let credential_with_key = CredentialWithKey {
+        credential,
+        signature_key: signer.to_public_vec().into(),
+    };
+    let config = MlsGroupConfig::builder()
+        .crypto_config(crypto_config)
+        .build();
+    let mut group = MlsGroup::new(backend, &signer, &config, credential_with_key)
+        .expect("An unexpected error occurred.");
+
+    for members in [10, 100, 500] {
+        let key_packages: Vec<KeyPackage> = (0..members)
+            .map(|i| {
+                let credential =
+                    Credential::new(format!("Member {i}").into_bytes(), CredentialType::Basic)
+                        .unwrap();
+                let signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+                let credential_with_key = CredentialWithKey {
+                    credential,
+                    signature_key: signer.to_public_vec().into(),
+                };
+                KeyPackage::builder()
+                    .build(crypto_config, backend, &signer, credential_with_key)
+                    .expect("An unexpected error occurred.")
+            })
+            .collect();
+
+        // The commit is discarded after each iteration, so that every
+        // iteration adds the members to the same group.
+        c.bench_function(
+            &format!("Create a commit and welcome adding {members} members"),
+            |b| {
+                b.iter(|| {
+                    group
+                        .add_members(backend, &signer, &key_packages)
+                        .expect("An unexpected error occurred.");
+                    group.clear_pending_commit();
+                });
+            },
+        );
+    }
+}
+
 fn kp_bundle_rust_crypto(c: &mut Criterion) {
     let backend = &OpenMlsRustCrypto::default();
     println!("Backend: RustCrypto");
     criterion_kp_bundle(c, backend);
 }
 
+fn serialization_rust_crypto(c: &mut Criterion) {
+    let backend = &OpenMlsRustCrypto::default();
+    println!("Backend: RustCrypto");
+    criterion_serialization(c, backend);
+}
+
 #[cfg(feature = "evercrypt")]
 fn kp_bundle_evercrypt(c: &mut Criterion) {
     use openmls_evercrypt::OpenMlsEvercrypt;
@@ -60,10 +271,25 @@ fn kp_bundle_evercrypt(c: &mut Criterion) {
     criterion_kp_bundle(c, backend);
 }
 
+fn large_group_rust_crypto(c: &mut Criterion) {
+    let backend = &OpenMlsRustCrypto::default();
+    println!("Backend: RustCrypto");
+    criterion_large_group(c, backend);
+}
+
+fn add_members_rust_crypto(c: &mut Criterion) {
+    let backend = &OpenMlsRustCrypto::default();
+    println!("Backend: RustCrypto");
+    criterion_add_members(c, backend);
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     kp_bundle_rust_crypto(c);
     #[cfg(feature = "evercrypt")]
     kp_bundle_evercrypt(c);
+    serialization_rust_crypto(c);
+    large_group_rust_crypto(c);
+    add_members_rust_crypto(c);
 }
 
 criterion_group!(benches, criterion_benchmark);
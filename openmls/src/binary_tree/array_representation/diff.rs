@@ -26,8 +26,8 @@ use super::{
     sorted_iter::sorted_iter,
     tree::{ABinaryTree, ABinaryTreeError},
     treemath::{
-        copath, direct_path, left, lowest_common_ancestor, right, root, LeafNodeIndex,
-        ParentNodeIndex, TreeNodeIndex, TreeSize, MAX_TREE_SIZE, MIN_TREE_SIZE,
+        copath, direct_path, left, lowest_common_ancestor, right, root, subtree_leaves,
+        LeafNodeIndex, ParentNodeIndex, TreeNodeIndex, TreeSize, MAX_TREE_SIZE, MIN_TREE_SIZE,
     },
 };
 
@@ -303,6 +303,25 @@ impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> AbDiff<'a, L, P
         right(node_index)
     }
 
+    /// Returns `true` if neither the referenced node nor any node below it
+    /// was changed in this diff.
+    pub(crate) fn is_subtree_unchanged(&self, node_index: TreeNodeIndex) -> bool {
+        let (first_leaf, last_leaf) = subtree_leaves(node_index);
+        // The parents in the subtree are the ones between its first and its
+        // last leaf.
+        let first_parent = ParentNodeIndex::new(first_leaf.u32());
+        let last_parent = ParentNodeIndex::new(last_leaf.u32());
+        self.leaf_diff
+            .range(first_leaf..=last_leaf)
+            .next()
+            .is_none()
+            && self
+                .parent_diff
+                .range(first_parent..last_parent)
+                .next()
+                .is_none()
+    }
+
     // Private helper functions below.
     //////////////////////////////////
 
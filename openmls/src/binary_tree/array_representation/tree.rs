@@ -27,17 +27,102 @@ where
     Parent(P),
 }
 
+/// Nodes that can be blank. When an [`ABinaryTree`] is serialized, runs of
+/// blank nodes are stored as their length, which keeps the serialized state of
+/// large, mostly blank trees small.
+pub(crate) trait BlankNode: Default {
+    /// Returns `true` if the node is blank.
+    fn is_blank(&self) -> bool;
+}
+
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "L: Serialize + BlankNode, P: Serialize + BlankNode",
+    deserialize = "L: Deserialize<'de> + BlankNode, P: Deserialize<'de> + BlankNode"
+))]
 /// A representation of a full, left-balanced binary tree that uses a simple
 /// vector to store nodes. Each tree has to consist of at least one node.
 pub(crate) struct ABinaryTree<L: Clone + Debug + Default, P: Clone + Debug + Default> {
+    #[serde(with = "blank_runs")]
     leaf_nodes: Vec<L>,
+    #[serde(with = "blank_runs")]
     parent_nodes: Vec<P>,
+    #[serde(skip)]
     default_leaf: L,
+    #[serde(skip)]
     default_parent: P,
 }
 
+/// (De)serialization of node vectors in which runs of blank nodes are
+/// replaced by their length.
+///
+/// Trees that were serialized by earlier versions store every node as is.
+/// Such entries are accepted as well, such that persisted groups can still be
+/// loaded.
+mod blank_runs {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{BlankNode, MAX_TREE_SIZE};
+
+    #[derive(Serialize)]
+    enum NodeRunRef<'a, N> {
+        Node(&'a N),
+        Blanks(u32),
+    }
+
+    #[derive(Deserialize)]
+    enum NodeRun<N> {
+        Node(N),
+        Blanks(u32),
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NodeEntry<N> {
+        Run(NodeRun<N>),
+        Legacy(N),
+    }
+
+    pub(super) fn serialize<S, N>(nodes: &[N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        N: Serialize + BlankNode,
+    {
+        let mut runs = Vec::new();
+        for node in nodes {
+            if !node.is_blank() {
+                runs.push(NodeRunRef::Node(node));
+            } else if let Some(NodeRunRef::Blanks(count)) = runs.last_mut() {
+                *count += 1;
+            } else {
+                runs.push(NodeRunRef::Blanks(1));
+            }
+        }
+        serializer.collect_seq(runs)
+    }
+
+    pub(super) fn deserialize<'de, D, N>(deserializer: D) -> Result<Vec<N>, D::Error>
+    where
+        D: Deserializer<'de>,
+        N: Deserialize<'de> + BlankNode,
+    {
+        let mut nodes = Vec::new();
+        for entry in Vec::<NodeEntry<N>>::deserialize(deserializer)? {
+            match entry {
+                NodeEntry::Run(NodeRun::Node(node)) | NodeEntry::Legacy(node) => nodes.push(node),
+                NodeEntry::Run(NodeRun::Blanks(count)) => {
+                    if nodes.len() + count as usize > MAX_TREE_SIZE as usize {
+                        return Err(D::Error::custom("Too many nodes in the tree."));
+                    }
+                    nodes.extend(std::iter::repeat_with(N::default).take(count as usize));
+                }
+            }
+        }
+        Ok(nodes)
+    }
+}
+
 impl<L: Clone + Debug + Default, P: Clone + Debug + Default> ABinaryTree<L, P> {
     /// Create a tree from the given vector of nodes. The vector of nodes can't
     /// be empty and has to yield a full, left-balanced binary tree. The nodes
@@ -22,6 +22,7 @@ pub(crate) const MIN_TREE_SIZE: u32 = 1;
     TlsSerialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct LeafNodeIndex(u32);
 
 impl LeafNodeIndex {
@@ -341,6 +342,17 @@ pub(crate) fn test_sibling(index: TreeNodeIndex) -> TreeNodeIndex {
     sibling(index)
 }
 
+/// Returns the leftmost and the rightmost leaf in the subtree rooted at the
+/// given node.
+pub(crate) fn subtree_leaves(node_index: TreeNodeIndex) -> (LeafNodeIndex, LeafNodeIndex) {
+    let x = node_index.u32();
+    let offset = (1 << level(x)) - 1;
+    (
+        LeafNodeIndex::from_tree_index(x - offset),
+        LeafNodeIndex::from_tree_index(x + offset),
+    )
+}
+
 /// Direct path from a node to the root.
 /// Does not include the node itself.
 pub(crate) fn direct_path(node_index: LeafNodeIndex, size: TreeSize) -> Vec<ParentNodeIndex> {
@@ -459,3 +471,21 @@ fn test_node_not_in_tree() {
         ));
     }
 }
+
+#[test]
+fn test_subtree_leaves() {
+    let tests = [
+        (0u32, 0u32, 0u32),
+        (1, 0, 1),
+        (3, 0, 3),
+        (5, 2, 3),
+        (7, 0, 7),
+        (10, 5, 5),
+    ];
+    for test in tests.iter() {
+        assert_eq!(
+            subtree_leaves(TreeNodeIndex::new(test.0)),
+            (LeafNodeIndex::new(test.1), LeafNodeIndex::new(test.2))
+        );
+    }
+}
@@ -51,6 +51,7 @@ const PROPOSAL_REF_LABEL: &[u8; 26] = b"MLS 1.0 Proposal Reference";
     TlsSerialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct HashReference {
     value: VLBytes,
 }
@@ -66,7 +67,7 @@ pub type ProposalRef = HashReference;
 #[derive(TlsSerialize, TlsSize)]
 struct HashReferenceInput<'a> {
     label: VLByteSlice<'a>,
-    value: VLBytes,
+    value: VLByteSlice<'a>,
 }
 
 /// Compute a new [`ProposalRef`] value for a `value`.
@@ -97,7 +98,7 @@ impl HashReference {
     ) -> Result<Self, CryptoError> {
         let input = HashReferenceInput {
             label: VLByteSlice(label),
-            value: VLBytes::new(value.to_vec()),
+            value: VLByteSlice(value),
         };
         let payload = input
             .tls_serialize_detached()
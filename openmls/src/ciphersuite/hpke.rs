@@ -54,6 +54,9 @@ pub(crate) enum Error {
     /// Decryption failed.
     #[error("Decryption failed.")]
     DecryptionFailed,
+    /// Encryption failed.
+    #[error("Encryption failed.")]
+    EncryptionFailed,
 }
 
 impl From<tls_codec::Error> for Error {
@@ -109,13 +112,15 @@ pub(crate) fn encrypt_with_label(
     log_crypto!(debug, "* public key:  {public_key:x?}");
     log_crypto!(debug, "* plaintext:   {plaintext:x?}");
 
-    let cipher = crypto.hpke_seal(
-        ciphersuite.hpke_config(),
-        public_key,
-        &context,
-        &[],
-        plaintext,
-    );
+    let cipher = crypto
+        .hpke_seal(
+            ciphersuite.hpke_config(),
+            public_key,
+            &context,
+            &[],
+            plaintext,
+        )
+        .map_err(|_| Error::EncryptionFailed)?;
 
     log_crypto!(debug, "* ciphertext:  {:x?}", cipher);
 
@@ -1,4 +1,6 @@
-use tls_codec::Serialize;
+use std::{cell::RefCell, fmt::Write};
+
+use tls_codec::{Serialize, VLByteSlice};
 
 use super::*;
 
@@ -6,29 +8,48 @@ use super::*;
 /// `kdf_expand_label`.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// struct {
 ///     uint16 length = Length;
 ///     opaque label<V> = "MLS 1.0 " + Label;
 ///     opaque context<V> = Context;
 /// } KDFLabel;
 /// ```
-#[derive(Debug, TlsSerialize, TlsSize)]
-pub(in crate::ciphersuite) struct KdfLabel {
+#[derive(TlsSerialize, TlsSize)]
+pub(in crate::ciphersuite) struct KdfLabel<'a> {
     length: u16,
-    label: VLBytes,
-    context: VLBytes,
+    label: VLByteSlice<'a>,
+    context: VLByteSlice<'a>,
+}
+
+/// Scratch space for the serialization of [`KdfLabel`]s.
+///
+/// Key derivations happen many times when processing a single message. The
+/// buffers are kept per thread and reused, so that serializing the label
+/// doesn't allocate once they have grown to their working size.
+#[derive(Default)]
+struct KdfLabelScratch {
+    full_label: String,
+    serialized: Vec<u8>,
+}
+
+thread_local! {
+    static KDF_LABEL_SCRATCH: RefCell<KdfLabelScratch> = RefCell::new(KdfLabelScratch::default());
 }
 
-impl KdfLabel {
-    /// Serialize this label.
-    /// Returns the serialized label as byte vector or returns a [`CryptoError`]
-    /// if the parameters are invalid.
-    pub(in crate::ciphersuite) fn serialized_label(
+impl KdfLabel<'_> {
+    /// Serialize the label `"<mls_version> <label>"` with the given `context`
+    /// and `length` and call `f` with the serialized label.
+    ///
+    /// Returns the result of `f` or a [`CryptoError`] if the parameters are
+    /// invalid.
+    pub(in crate::ciphersuite) fn with_serialized_label<T>(
+        mls_version: ProtocolVersion,
+        label: &str,
         context: &[u8],
-        label: String,
         length: usize,
-    ) -> Result<Vec<u8>, CryptoError> {
+        f: impl FnOnce(&[u8]) -> Result<T, CryptoError>,
+    ) -> Result<T, CryptoError> {
         if length > u16::MAX.into() {
             debug_assert!(
                 false,
@@ -36,14 +57,36 @@ impl KdfLabel {
             );
             return Err(CryptoError::KdfLabelTooLarge);
         }
-        let kdf_label = KdfLabel {
-            length: length as u16,
-            label: label.as_bytes().into(),
-            context: context.into(),
-        };
-        log::trace!("{kdf_label:?}");
-        kdf_label
-            .tls_serialize_detached()
-            .map_err(|_| CryptoError::KdfSerializationError)
+        KDF_LABEL_SCRATCH.with(|scratch| {
+            // Fall back to fresh buffers if the scratch space is in use, e.g.,
+            // because the crypto provider derives keys itself.
+            let mut fresh = KdfLabelScratch::default();
+            let mut borrowed = scratch.try_borrow_mut();
+            let scratch = match borrowed.as_deref_mut() {
+                Ok(scratch) => scratch,
+                Err(_) => &mut fresh,
+            };
+
+            scratch.full_label.clear();
+            write!(scratch.full_label, "{mls_version} {label}")
+                .map_err(|_| CryptoError::KdfSerializationError)?;
+            log::trace!(
+                "KDF label \"{}\" with context {:x?}",
+                scratch.full_label,
+                context
+            );
+
+            scratch.serialized.clear();
+            KdfLabel {
+                length: length as u16,
+                label: VLByteSlice(scratch.full_label.as_bytes()),
+                context: VLByteSlice(context),
+            }
+            .tls_serialize(&mut scratch.serialized)
+            .map_err(|_| CryptoError::KdfSerializationError)?;
+            log::trace!("  serialized info: {:x?}", scratch.serialized);
+
+            f(&scratch.serialized)
+        })
     }
 }
@@ -4,6 +4,7 @@ use super::*;
 ///
 /// opaque MAC<V>;
 #[derive(Debug, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct Mac {
     pub(crate) mac_value: VLBytes,
 }
@@ -2,6 +2,9 @@ use std::fmt::{Debug, Formatter};
 
 use super::{kdf_label::KdfLabel, *};
 
+/// An all zero secret of the maximum hash length of all ciphersuites.
+const ZERO_SECRET: [u8; 64] = [0u8; 64];
+
 /// A struct to contain secrets. This is to provide better visibility into where
 /// and how secrets are used and to avoid passing secrets in their raw
 /// representation.
@@ -122,31 +125,42 @@ impl Secret {
     ) -> Result<Self, CryptoError> {
         log::trace!("HKDF extract with {:?}", self.ciphersuite);
         log_crypto!(trace, "  salt: {:x?}", self.value);
-        let zero_secret = Self::zero(self.ciphersuite, self.mls_version);
-        let ikm = ikm_option.into().unwrap_or(&zero_secret);
-        log_crypto!(trace, "  ikm:  {:x?}", ikm.value);
-
-        // We don't return an error here to keep the error propagation from
-        // blowing up. If this fails, something in the library is really wrong
-        // and we can't recover from it.
-        assert!(
-            self.mls_version == ikm.mls_version,
-            "{} != {}",
-            self.mls_version,
-            ikm.mls_version
-        );
-        assert!(
-            self.ciphersuite == ikm.ciphersuite,
-            "{} != {}",
-            self.ciphersuite,
-            ikm.ciphersuite
-        );
+        let zero_ikm;
+        let ikm = match ikm_option.into() {
+            Some(ikm) => {
+                // We don't return an error here to keep the error propagation from
+                // blowing up. If this fails, something in the library is really wrong
+                // and we can't recover from it.
+                assert!(
+                    self.mls_version == ikm.mls_version,
+                    "{} != {}",
+                    self.mls_version,
+                    ikm.mls_version
+                );
+                assert!(
+                    self.ciphersuite == ikm.ciphersuite,
+                    "{} != {}",
+                    self.ciphersuite,
+                    ikm.ciphersuite
+                );
+                ikm.value.as_slice()
+            }
+            // Use a static all zero secret instead of allocating one.
+            None => match ZERO_SECRET.get(..self.ciphersuite.hash_length()) {
+                Some(zero) => zero,
+                None => {
+                    zero_ikm = vec![0u8; self.ciphersuite.hash_length()];
+                    zero_ikm.as_slice()
+                }
+            },
+        };
+        log_crypto!(trace, "  ikm:  {:x?}", ikm);
 
         Ok(Self {
             value: backend.crypto().hkdf_extract(
                 self.ciphersuite.hash_algorithm(),
                 self.value.as_slice(),
-                ikm.value.as_slice(),
+                ikm,
             )?,
             mls_version: self.mls_version,
             ciphersuite: self.ciphersuite,
@@ -188,17 +202,11 @@ impl Secret {
         context: &[u8],
         length: usize,
     ) -> Result<Secret, CryptoError> {
-        let full_label = format!("{} {}", self.mls_version, label);
-        log::trace!(
-            "KDF expand with label \"{}\" and {:?} with context {:x?}",
-            &full_label,
-            self.ciphersuite,
-            context
-        );
-        let info = KdfLabel::serialized_label(context, full_label, length)?;
-        log::trace!("  serialized info: {:x?}", info);
+        log::trace!("KDF expand with {:?}", self.ciphersuite);
         log_crypto!(trace, "  secret: {:x?}", self.value);
-        self.hkdf_expand(backend, &info, length)
+        KdfLabel::with_serialized_label(self.mls_version, label, context, length, |info| {
+            self.hkdf_expand(backend, info, length)
+        })
     }
 
     /// Derive a new `Secret` from the this one by expanding it with the given
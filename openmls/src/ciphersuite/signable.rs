@@ -30,10 +30,47 @@
 
 use openmls_traits::{crypto::OpenMlsCrypto, signatures::Signer};
 use thiserror::Error;
-use tls_codec::Serialize;
 
 use crate::ciphersuite::{OpenMlsSignaturePublicKey, SignContent, Signature};
 
+/// The labels of all structures that are signed by OpenMLS, i.e., the labels
+/// that appear in the `SignContent` passed to [`Signer::sign()`], where they
+/// are prefixed with `"MLS 1.0 "`.
+///
+/// A [`Signer`] that requires the user's confirmation for every signature can
+/// use them, e.g., to describe what is signed or to refuse unexpected labels.
+pub mod signature_labels {
+    /// Proposals, commits and application messages (`FramedContentTBS`).
+    pub const FRAMED_CONTENT: &str = "FramedContentTBS";
+    /// Leaf nodes of key packages, updates and commits (`LeafNodeTBS`).
+    pub const LEAF_NODE: &str = "LeafNodeTBS";
+    /// Key packages (`KeyPackageTBS`).
+    pub const KEY_PACKAGE: &str = "KeyPackageTBS";
+    /// Group infos of Welcome messages and for External Commits
+    /// (`GroupInfoTBS`).
+    pub const GROUP_INFO: &str = "GroupInfoTBS";
+    /// Targeted messages (`TargetedMessageTBS`).
+    pub const TARGETED_MESSAGE: &str = "TargetedMessageTBS";
+    /// Exported commit audit logs (`CommitAuditLogTBS`).
+    pub const COMMIT_AUDIT_LOG: &str = "CommitAuditLogTBS";
+    /// Group state transfers to a new device (`GroupStateTransferTBS`).
+    pub const GROUP_STATE_TRANSFER: &str = "GroupStateTransferTBS";
+    /// Membership tokens (`MembershipTokenTBS`).
+    pub const MEMBERSHIP_TOKEN: &str = "MembershipTokenTBS";
+
+    /// All of the above labels.
+    pub const ALL: [&str; 8] = [
+        FRAMED_CONTENT,
+        LEAF_NODE,
+        KEY_PACKAGE,
+        GROUP_INFO,
+        TARGETED_MESSAGE,
+        COMMIT_AUDIT_LOG,
+        GROUP_STATE_TRANSFER,
+        MEMBERSHIP_TOKEN,
+    ];
+}
+
 /// Signature generation and verification errors.
 /// The only information relayed with this error is whether the signature
 /// verification or generation failed.
@@ -45,6 +82,10 @@ pub enum SignatureError {
     /// Signature generation failed
     #[error("Signature generation failed.")]
     SigningError,
+    /// The signer returned an error, e.g., because it is unavailable or the
+    /// signing was cancelled.
+    #[error("The signer returned an error: {0:?}")]
+    SignerError(openmls_traits::types::Error),
 }
 
 /// This trait must be implemented by all structs that contain a self-signature.
@@ -101,20 +142,32 @@ pub trait Signable: Sized {
         let payload = self
             .unsigned_payload()
             .map_err(|_| SignatureError::SigningError)?;
-        let payload = match SignContent::new(self.label(), payload.into()).tls_serialize_detached()
-        {
-            Ok(p) => p,
-            Err(e) => {
-                log::error!("Serializing SignContent failed, {:?}", e);
-                return Err(SignatureError::SigningError);
-            }
-        };
-        let signature = signer
-            .sign(&payload)
-            .map_err(|_| SignatureError::SigningError)?;
+        let signature = sign_with_label(signer, self.label(), &payload)?;
+
+        Ok(Self::SignedOutput::from_payload(self, signature))
+    }
+}
 
-        Ok(Self::SignedOutput::from_payload(self, signature.into()))
+/// Sign the serialized `payload` with the given `label`.
+///
+/// This is the signing step of [`Signable::sign()`]. It can be used directly
+/// to re-sign a [`SignedStruct`] in place when the payload can be serialized
+/// by reference, which avoids cloning the payload into a [`Signable`] struct.
+pub(crate) fn sign_with_label(
+    signer: &impl Signer,
+    label: &str,
+    payload: &[u8],
+) -> Result<Signature, SignatureError> {
+    let mut sign_content = Vec::new();
+    if let Err(e) = SignContent::serialize_into(label, payload, &mut sign_content) {
+        log::error!("Serializing SignContent failed, {:?}", e);
+        return Err(SignatureError::SigningError);
     }
+    let signature = signer
+        .sign(&sign_content)
+        .map_err(SignatureError::SignerError)?;
+
+    Ok(signature.into())
 }
 
 /// The verifiable trait must be implemented by any struct that is signed with
@@ -177,18 +230,15 @@ fn verify(
     let payload = verifiable
         .unsigned_payload()
         .map_err(|_| SignatureError::VerificationError)?;
-    let sign_content = SignContent::new(verifiable.label(), payload.into());
-    let payload = match sign_content.tls_serialize_detached() {
-        Ok(p) => p,
-        Err(e) => {
-            log::error!("Serializing SignContent failed, {:?}", e);
-            return Err(SignatureError::VerificationError);
-        }
-    };
+    let mut sign_content = Vec::new();
+    if let Err(e) = SignContent::serialize_into(verifiable.label(), &payload, &mut sign_content) {
+        log::error!("Serializing SignContent failed, {:?}", e);
+        return Err(SignatureError::VerificationError);
+    }
     crypto
         .verify_signature(
             pk.signature_scheme(),
-            &payload,
+            &sign_content,
             pk.as_slice(),
             verifiable.signature().value(),
         )
@@ -2,7 +2,9 @@
 //!
 //! This module contains structs for creating signature keys, issuing signatures and verifying them.
 
-use tls_codec::Serialize;
+use std::sync::Arc;
+
+use tls_codec::{Serialize, Size, VLByteSlice};
 
 use super::{LABEL_PREFIX, *};
 
@@ -10,6 +12,7 @@ use super::{LABEL_PREFIX, *};
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Signature {
     value: VLBytes,
 }
@@ -43,6 +46,29 @@ impl SignContent {
         let label = label_string.as_bytes().into();
         Self { label, content }
     }
+
+    /// Serializes the [`SignContent`] for the given `label` and `content`
+    /// into `buffer`, without copying `content` into a [`SignContent`] first.
+    pub(crate) fn serialize_into(
+        label: &str,
+        content: &[u8],
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), tls_codec::Error> {
+        let label = LABEL_PREFIX.to_owned() + label;
+        let sign_content = SignContentRef {
+            label: VLByteSlice(label.as_bytes()),
+            content: VLByteSlice(content),
+        };
+        buffer.reserve(sign_content.tls_serialized_len());
+        sign_content.tls_serialize(buffer).map(|_| ())
+    }
+}
+
+/// Borrowed version of [`SignContent`] used for serialization.
+#[derive(TlsSerialize, TlsSize)]
+struct SignContentRef<'a> {
+    label: VLByteSlice<'a>,
+    content: VLByteSlice<'a>,
 }
 
 impl From<(&str, &[u8])> for SignContent {
@@ -52,17 +78,19 @@ impl From<(&str, &[u8])> for SignContent {
 }
 
 /// A public signature key.
-#[derive(
-    Eq, PartialEq, Hash, Debug, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
-)]
+///
+/// The key bytes are shared between clones, since the same key is usually
+/// held by the leaf node, proposals and processed messages of a member.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SignaturePublicKey {
-    pub(in crate::ciphersuite) value: VLBytes,
+    pub(in crate::ciphersuite) value: Arc<VLBytes>,
 }
 
 impl From<Vec<u8>> for SignaturePublicKey {
     fn from(value: Vec<u8>) -> Self {
         Self {
-            value: value.into(),
+            value: Arc::new(value.into()),
         }
     }
 }
@@ -70,11 +98,32 @@ impl From<Vec<u8>> for SignaturePublicKey {
 impl From<&[u8]> for SignaturePublicKey {
     fn from(value: &[u8]) -> Self {
         Self {
-            value: value.into(),
+            value: Arc::new(value.into()),
         }
     }
 }
 
+impl Size for SignaturePublicKey {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        self.value.tls_serialized_len()
+    }
+}
+
+impl Serialize for SignaturePublicKey {
+    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
+        self.value.tls_serialize(writer)
+    }
+}
+
+impl tls_codec::Deserialize for SignaturePublicKey {
+    fn tls_deserialize<R: std::io::Read>(bytes: &mut R) -> Result<Self, tls_codec::Error> {
+        Ok(Self {
+            value: Arc::new(<VLBytes as tls_codec::Deserialize>::tls_deserialize(bytes)?),
+        })
+    }
+}
+
 impl SignaturePublicKey {
     /// Convert the "raw" signature into an enriched form, [OpenMlsSignaturePublicKey], that
     /// already contains the signature scheme.
@@ -90,7 +139,7 @@ impl SignaturePublicKey {
 
     /// Returns the bytes of the signature public key.
     pub fn as_slice(&self) -> &[u8] {
-        self.value.as_ref()
+        self.value.as_slice()
     }
 }
 
@@ -106,7 +155,7 @@ impl From<OpenMlsSignaturePublicKey> for SignaturePublicKey {
 #[derive(Eq, PartialEq, Hash, Debug, Clone, Serialize, Deserialize)]
 pub struct OpenMlsSignaturePublicKey {
     signature_scheme: SignatureScheme,
-    pub(in crate::ciphersuite) value: VLBytes,
+    pub(in crate::ciphersuite) value: Arc<VLBytes>,
 }
 
 #[cfg(test)]
@@ -124,6 +173,19 @@ impl Signature {
     pub(super) fn value(&self) -> &[u8] {
         self.value.as_slice()
     }
+
+    /// Returns the maximum length in bytes of a signature with the given
+    /// `signature_scheme`. ECDSA signatures are DER encoded and may be
+    /// shorter.
+    pub(crate) fn max_length(signature_scheme: SignatureScheme) -> usize {
+        match signature_scheme {
+            SignatureScheme::ECDSA_SECP256R1_SHA256 => 72,
+            SignatureScheme::ECDSA_SECP384R1_SHA384 => 104,
+            SignatureScheme::ECDSA_SECP521R1_SHA512 => 141,
+            SignatureScheme::ED25519 => 64,
+            SignatureScheme::ED448 => 114,
+        }
+    }
 }
 
 impl<T> SignedStruct<T> for Signature {
@@ -136,7 +198,7 @@ impl OpenMlsSignaturePublicKey {
     /// Create a new signature public key from raw key bytes.
     pub fn new(value: VLBytes, signature_scheme: SignatureScheme) -> Result<Self, CryptoError> {
         Ok(Self {
-            value,
+            value: Arc::new(value),
             signature_scheme,
         })
     }
@@ -169,7 +231,7 @@ impl OpenMlsSignaturePublicKey {
             .verify_signature(
                 self.signature_scheme,
                 &payload,
-                self.value.as_ref(),
+                self.value.as_slice(),
                 signature.value.as_slice(),
             )
             .map_err(|_| CryptoError::InvalidSignature)
@@ -188,7 +250,7 @@ impl OpenMlsSignaturePublicKey {
             .verify_signature(
                 self.signature_scheme,
                 payload,
-                self.value.as_ref(),
+                self.value.as_slice(),
                 signature.value.as_slice(),
             )
             .map_err(|_| CryptoError::InvalidSignature)
@@ -201,6 +263,6 @@ impl OpenMlsSignaturePublicKey {
 
     /// Returns the bytes of the signature public key.
     pub fn as_slice(&self) -> &[u8] {
-        self.value.as_ref()
+        self.value.as_slice()
     }
 }
@@ -0,0 +1,233 @@
+//! # Basic Identities
+//!
+//! The identity of a [`BasicCredential`](super::BasicCredential) is an opaque
+//! byte string. This module contains the [`BasicIdentitySchema`], which allows
+//! deployments to handle these identities consistently. A schema checks that
+//! an identity is valid UTF-8 of a maximum length without control characters
+//! and optionally parses it as a URI or as `user@domain`. The result is a
+//! [`BasicIdentity`] with typed accessors.
+//!
+//! When set with
+//! [`MlsGroupConfigBuilder::basic_identity_schema()`](crate::group::MlsGroupConfigBuilder::basic_identity_schema()),
+//! the schema is enforced for the basic credentials of new members when
+//! adding them, of all members when joining a group and of members in
+//! received messages. Credentials of all other types are not affected.
+//!
+//! ```
+//! use openmls::prelude::*;
+//!
+//! let schema = BasicIdentitySchema::new(IdentityFormat::UserAtDomain, 64);
+//! let identity = schema.parse(b"alice@example.com").unwrap();
+//! assert_eq!(identity.user(), Some("alice"));
+//! assert_eq!(identity.domain(), Some("example.com"));
+//! assert!(schema.parse(b"alice").is_err());
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{errors::BasicIdentityError, Credential, CredentialType};
+
+/// The default maximum length of an identity in bytes.
+pub const DEFAULT_MAX_IDENTITY_LENGTH: usize = 256;
+
+/// The format of the identities of basic credentials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IdentityFormat {
+    /// Any UTF-8 string.
+    Utf8,
+    /// A URI with a scheme as defined in RFC 3986, e.g.,
+    /// `sip:alice@example.com` or `https://example.com/alice`.
+    Uri,
+    /// An identity of the form `user@domain`, e.g., `alice@example.com`.
+    UserAtDomain,
+}
+
+/// A schema for the identities of basic credentials.
+///
+/// By default, identities must be UTF-8 strings of at most
+/// [`DEFAULT_MAX_IDENTITY_LENGTH`] bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BasicIdentitySchema {
+    format: IdentityFormat,
+    max_length: usize,
+}
+
+impl Default for BasicIdentitySchema {
+    fn default() -> Self {
+        Self::new(IdentityFormat::Utf8, DEFAULT_MAX_IDENTITY_LENGTH)
+    }
+}
+
+impl BasicIdentitySchema {
+    /// Creates a new schema for identities of the given `format` with at most
+    /// `max_length` bytes.
+    pub fn new(format: IdentityFormat, max_length: usize) -> Self {
+        Self { format, max_length }
+    }
+
+    /// Returns the format of the identities.
+    pub fn format(&self) -> IdentityFormat {
+        self.format
+    }
+
+    /// Returns the maximum length of the identities in bytes.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    /// Parses the `identity` according to the schema.
+    ///
+    /// Returns an error if the identity is not valid UTF-8, is empty, is
+    /// longer than the maximum length, contains control characters or
+    /// doesn't match the format of the schema.
+    pub fn parse(&self, identity: &[u8]) -> Result<BasicIdentity, BasicIdentityError> {
+        let identity =
+            std::str::from_utf8(identity).map_err(|_| BasicIdentityError::InvalidUtf8)?;
+        if identity.is_empty() {
+            return Err(BasicIdentityError::Empty);
+        }
+        if identity.len() > self.max_length {
+            return Err(BasicIdentityError::TooLong);
+        }
+        if identity.chars().any(char::is_control) {
+            return Err(BasicIdentityError::InvalidCharacter);
+        }
+
+        let separator = match self.format {
+            IdentityFormat::Utf8 => 0,
+            IdentityFormat::Uri => parse_uri(identity).ok_or(BasicIdentityError::InvalidUri)?,
+            IdentityFormat::UserAtDomain => {
+                parse_user_at_domain(identity).ok_or(BasicIdentityError::InvalidUserAtDomain)?
+            }
+        };
+        Ok(BasicIdentity {
+            identity: identity.to_string(),
+            format: self.format,
+            separator,
+        })
+    }
+
+    /// Parses the identity of the basic `credential` according to the schema.
+    ///
+    /// Returns [`BasicIdentityError::NotBasicCredential`] if the credential
+    /// is not a basic credential and the errors of [`Self::parse()`]
+    /// otherwise.
+    pub fn parse_credential(
+        &self,
+        credential: &Credential,
+    ) -> Result<BasicIdentity, BasicIdentityError> {
+        if credential.credential_type() != CredentialType::Basic {
+            return Err(BasicIdentityError::NotBasicCredential);
+        }
+        self.parse(credential.identity())
+    }
+
+    /// Returns `true` if the `credential` is not a basic credential or if its
+    /// identity matches the schema, and `false` otherwise.
+    pub(crate) fn validate(&self, credential: &Credential) -> bool {
+        credential.credential_type() != CredentialType::Basic
+            || self.parse(credential.identity()).is_ok()
+    }
+}
+
+/// Returns the position of the `:` after the scheme of the `uri`, or `None`
+/// if the scheme or the remainder of the URI is invalid.
+fn parse_uri(uri: &str) -> Option<usize> {
+    let (scheme, rest) = uri.split_once(':')?;
+    let mut scheme_chars = scheme.chars();
+    let valid_scheme = scheme_chars
+        .next()
+        .map(|c| c.is_ascii_alphabetic())
+        .unwrap_or(false)
+        && scheme_chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !valid_scheme || rest.is_empty() || rest.chars().any(char::is_whitespace) {
+        return None;
+    }
+    Some(scheme.len())
+}
+
+/// Returns the position of the `@` in `identity`, or `None` if the user or
+/// the domain are invalid.
+fn parse_user_at_domain(identity: &str) -> Option<usize> {
+    let (user, domain) = identity.split_once('@')?;
+    if user.is_empty() || user.chars().any(char::is_whitespace) {
+        return None;
+    }
+    let valid_domain = domain.split('.').all(|label| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    if !valid_domain {
+        return None;
+    }
+    Some(user.len())
+}
+
+/// An identity of a basic credential that was parsed with a
+/// [`BasicIdentitySchema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicIdentity {
+    identity: String,
+    format: IdentityFormat,
+    // The position of the `:` of a URI or the `@` of a `user@domain`.
+    separator: usize,
+}
+
+impl BasicIdentity {
+    /// Returns the identity as string.
+    pub fn as_str(&self) -> &str {
+        &self.identity
+    }
+
+    /// Returns the format the identity was parsed with.
+    pub fn format(&self) -> IdentityFormat {
+        self.format
+    }
+
+    /// Returns the scheme of a URI, e.g., `sip` for `sip:alice@example.com`,
+    /// or `None` if the identity is not a URI.
+    pub fn scheme(&self) -> Option<&str> {
+        match self.format {
+            IdentityFormat::Uri => Some(&self.identity[..self.separator]),
+            _ => None,
+        }
+    }
+
+    /// Returns the part of a URI after the scheme, e.g.,
+    /// `alice@example.com` for `sip:alice@example.com`, or `None` if the
+    /// identity is not a URI.
+    pub fn scheme_specific_part(&self) -> Option<&str> {
+        match self.format {
+            IdentityFormat::Uri => Some(&self.identity[self.separator + 1..]),
+            _ => None,
+        }
+    }
+
+    /// Returns the user of a `user@domain` identity, or `None` if the
+    /// identity is not of this format.
+    pub fn user(&self) -> Option<&str> {
+        match self.format {
+            IdentityFormat::UserAtDomain => Some(&self.identity[..self.separator]),
+            _ => None,
+        }
+    }
+
+    /// Returns the domain of a `user@domain` identity, or `None` if the
+    /// identity is not of this format.
+    pub fn domain(&self) -> Option<&str> {
+        match self.format {
+            IdentityFormat::UserAtDomain => Some(&self.identity[self.separator + 1..]),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BasicIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.identity)
+    }
+}
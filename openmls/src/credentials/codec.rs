@@ -6,24 +6,32 @@ impl tls_codec::Size for Credential {
     #[inline]
     fn tls_serialized_len(&self) -> usize {
         self.credential_type.tls_serialized_len()
-            + match &self.credential {
+            + match self.credential.as_ref() {
                 MlsCredentialType::Basic(c) => c.tls_serialized_len(),
-                MlsCredentialType::X509(_) => unimplemented!(),
+                MlsCredentialType::X509(c) => c.tls_serialized_len(),
+                MlsCredentialType::Custom(c) => c.data.tls_serialized_len(),
             }
     }
 }
 
 impl tls_codec::Serialize for Credential {
     fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
-        match &self.credential {
+        match self.credential.as_ref() {
             MlsCredentialType::Basic(basic_credential) => {
                 let written = CredentialType::Basic.tls_serialize(writer)?;
                 basic_credential.tls_serialize(writer).map(|l| l + written)
             }
-            // TODO #134: implement encoding for X509 certificates
-            MlsCredentialType::X509(_) => Err(tls_codec::Error::EncodingError(
-                "X509 certificates are not yet implemented.".to_string(),
-            )),
+            MlsCredentialType::Custom(opaque_credential) => {
+                let written = self.credential_type.tls_serialize(writer)?;
+                opaque_credential
+                    .data
+                    .tls_serialize(writer)
+                    .map(|l| l + written)
+            }
+            MlsCredentialType::X509(certificate_chain) => {
+                let written = CredentialType::X509.tls_serialize(writer)?;
+                certificate_chain.tls_serialize(writer).map(|l| l + written)
+            }
         }
     }
 }
@@ -37,9 +45,31 @@ impl tls_codec::Deserialize for Credential {
             CredentialType::Basic => Ok(Credential::from(MlsCredentialType::Basic(
                 BasicCredential::tls_deserialize(bytes)?,
             ))),
+            CredentialType::X509 => Ok(Credential::from(MlsCredentialType::X509(
+                CertificateChain::tls_deserialize(bytes)?,
+            ))),
+            CredentialType::Unknown(value) if credential_type.is_private_use() => Ok(
+                Credential::from(MlsCredentialType::Custom(OpaqueCredential {
+                    credential_type: value,
+                    data: VLBytes::tls_deserialize(bytes)?,
+                })),
+            ),
             _ => Err(tls_codec::Error::DecodingError(format!(
                 "{credential_type:?} can not be deserialized."
             ))),
         }
     }
 }
+
+impl tls_codec::Deserialize for CertificateChain {
+    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, tls_codec::Error> {
+        let certificates = Vec::<Certificate>::tls_deserialize(bytes)?;
+        // The chain must contain at least the leaf certificate.
+        if certificates.is_empty() {
+            return Err(tls_codec::Error::DecodingError(
+                "Empty certificate chain.".to_string(),
+            ));
+        }
+        Ok(Self { certificates })
+    }
+}
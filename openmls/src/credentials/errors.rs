@@ -1,6 +1,6 @@
 //! Credential errors
 //!
-//! This module exposes [`CredentialError`].
+//! This module exposes [`CredentialError`] and [`BasicIdentityError`].
 
 use crate::error::LibraryError;
 use thiserror::Error;
@@ -17,4 +17,37 @@ pub enum CredentialError {
     /// Verifying the signature with this credential failed.
     #[error("Invalid signature.")]
     InvalidSignature,
+    /// The application-defined credential could not be deserialized.
+    #[error("Invalid custom credential.")]
+    InvalidCustomCredential,
+    /// An X.509 credential must contain at least the leaf certificate.
+    #[error("Empty certificate chain.")]
+    EmptyCertificateChain,
+}
+
+/// An error that occurs when parsing the identity of a basic credential with a
+/// [`BasicIdentitySchema`](super::basic_identity::BasicIdentitySchema).
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum BasicIdentityError {
+    /// The credential is not a basic credential.
+    #[error("The credential is not a basic credential.")]
+    NotBasicCredential,
+    /// The identity is not valid UTF-8.
+    #[error("The identity is not valid UTF-8.")]
+    InvalidUtf8,
+    /// The identity is empty.
+    #[error("The identity is empty.")]
+    Empty,
+    /// The identity is longer than the maximum length of the schema.
+    #[error("The identity is longer than the maximum length.")]
+    TooLong,
+    /// The identity contains control characters.
+    #[error("The identity contains control characters.")]
+    InvalidCharacter,
+    /// The identity is not a valid URI.
+    #[error("The identity is not a valid URI.")]
+    InvalidUri,
+    /// The identity is not of the form `user@domain`.
+    #[error("The identity is not of the form user@domain.")]
+    InvalidUserAtDomain,
 }
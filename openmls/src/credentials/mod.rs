@@ -19,12 +19,18 @@
 //! When receiving a credential update from another member, applications must
 //! query the Authentication Service to ensure that the new credential is valid.
 //!
-//! There are multiple [`CredentialType`]s, although OpenMLS currently only
-//! supports the [`BasicCredential`].
+//! There are multiple [`CredentialType`]s. OpenMLS supports the
+//! [`BasicCredential`] as well as X.509 credentials, whose
+//! [`CertificateChain`] is exposed through [`Credential::certificate_chain()`]
+//! but has to be verified by the application. Applications can define their own
+//! credential types from the private use range through the
+//! [`CustomCredential`] trait.
 
 use std::{
+    collections::HashMap,
     convert::TryFrom,
     io::{Read, Write},
+    sync::Arc,
 };
 
 use serde::{Deserialize, Serialize};
@@ -39,7 +45,9 @@ use errors::*;
 use crate::ciphersuite::SignaturePublicKey;
 
 // Public
+pub mod basic_identity;
 pub mod errors;
+pub mod verifiable_credential;
 
 /// CredentialType.
 ///
@@ -54,30 +62,31 @@ pub mod errors;
 ///
 /// | Value            | Name                     | R | Ref      |
 /// |:-----------------|:-------------------------|:--|:---------|
-/// | 0x0000           | RESERVED                 | - | RFC XXXX |
-/// | 0x0001           | basic                    | Y | RFC XXXX |
-/// | 0x0002           | x509                     | Y | RFC XXXX |
-/// | 0x0A0A           | GREASE                   | Y | RFC XXXX |
-/// | 0x1A1A           | GREASE                   | Y | RFC XXXX |
-/// | 0x2A2A           | GREASE                   | Y | RFC XXXX |
-/// | 0x3A3A           | GREASE                   | Y | RFC XXXX |
-/// | 0x4A4A           | GREASE                   | Y | RFC XXXX |
-/// | 0x5A5A           | GREASE                   | Y | RFC XXXX |
-/// | 0x6A6A           | GREASE                   | Y | RFC XXXX |
-/// | 0x7A7A           | GREASE                   | Y | RFC XXXX |
-/// | 0x8A8A           | GREASE                   | Y | RFC XXXX |
-/// | 0x9A9A           | GREASE                   | Y | RFC XXXX |
-/// | 0xAAAA           | GREASE                   | Y | RFC XXXX |
-/// | 0xBABA           | GREASE                   | Y | RFC XXXX |
-/// | 0xCACA           | GREASE                   | Y | RFC XXXX |
-/// | 0xDADA           | GREASE                   | Y | RFC XXXX |
-/// | 0xEAEA           | GREASE                   | Y | RFC XXXX |
-/// | 0xF000  - 0xFFFF | Reserved for Private Use | - | RFC XXXX |
+/// | 0x0000           | RESERVED                 | - | RFC 9420 |
+/// | 0x0001           | basic                    | Y | RFC 9420 |
+/// | 0x0002           | x509                     | Y | RFC 9420 |
+/// | 0x0A0A           | GREASE                   | Y | RFC 9420 |
+/// | 0x1A1A           | GREASE                   | Y | RFC 9420 |
+/// | 0x2A2A           | GREASE                   | Y | RFC 9420 |
+/// | 0x3A3A           | GREASE                   | Y | RFC 9420 |
+/// | 0x4A4A           | GREASE                   | Y | RFC 9420 |
+/// | 0x5A5A           | GREASE                   | Y | RFC 9420 |
+/// | 0x6A6A           | GREASE                   | Y | RFC 9420 |
+/// | 0x7A7A           | GREASE                   | Y | RFC 9420 |
+/// | 0x8A8A           | GREASE                   | Y | RFC 9420 |
+/// | 0x9A9A           | GREASE                   | Y | RFC 9420 |
+/// | 0xAAAA           | GREASE                   | Y | RFC 9420 |
+/// | 0xBABA           | GREASE                   | Y | RFC 9420 |
+/// | 0xCACA           | GREASE                   | Y | RFC 9420 |
+/// | 0xDADA           | GREASE                   | Y | RFC 9420 |
+/// | 0xEAEA           | GREASE                   | Y | RFC 9420 |
+/// | 0xF000  - 0xFFFF | Reserved for Private Use | - | RFC 9420 |
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum CredentialType {
     /// A [`BasicCredential`]
     Basic,
-    /// An X.509 [`Certificate`]
+    /// An X.509 [`CertificateChain`]
     X509,
     /// A currently unknown credential.
     Unknown(u16),
@@ -109,6 +118,14 @@ impl tls_codec::Serialize for CredentialType {
     }
 }
 
+impl CredentialType {
+    /// Returns `true` if the credential type is in the range reserved for
+    /// private use (`0xF000 - 0xFFFF`) and `false` otherwise.
+    pub fn is_private_use(&self) -> bool {
+        matches!(self, CredentialType::Unknown(value) if *value >= 0xF000)
+    }
+}
+
 impl From<u16> for CredentialType {
     fn from(value: u16) -> Self {
         match value {
@@ -131,28 +148,217 @@ impl From<CredentialType> for u16 {
 
 /// X.509 Certificate.
 ///
-/// This struct contains an X.509 certificate chain.  Note that X.509
-/// certificates are not yet supported by OpenMLS.
+/// This struct contains a single DER-encoded X.509 certificate of a
+/// [`CertificateChain`].
 ///
 /// ```c
 /// struct {
 ///     opaque cert_data<V>;
 /// } Certificate;
 /// ```
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(
+    Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Certificate {
-    cert_data: Vec<u8>,
+    cert_data: VLBytes,
+}
+
+impl Certificate {
+    /// Returns the DER encoding of the certificate.
+    pub fn cert_data(&self) -> &[u8] {
+        self.cert_data.as_slice()
+    }
+}
+
+/// X.509 certificate chain.
+///
+/// This struct contains the certificate chain of an X.509 credential. The
+/// first certificate is the leaf certificate, which certifies the signature
+/// key of the client, and each following certificate certifies the one
+/// before it. A chain always contains at least the leaf certificate.
+///
+/// Note that OpenMLS does not parse or verify the certificates. Applications
+/// must validate the chain, e.g., through a [`CredentialValidator`], before
+/// displaying the identity of the leaf certificate.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsSerialize, TlsSize)]
+#[serde(try_from = "SerializedCertificateChain")]
+pub struct CertificateChain {
+    certificates: Vec<Certificate>,
+}
+
+/// Helper struct to check that a deserialized [`CertificateChain`] is not
+/// empty.
+#[derive(Deserialize)]
+struct SerializedCertificateChain {
+    certificates: Vec<Certificate>,
+}
+
+impl TryFrom<SerializedCertificateChain> for CertificateChain {
+    type Error = CredentialError;
+
+    fn try_from(value: SerializedCertificateChain) -> Result<Self, Self::Error> {
+        // The chain must contain at least the leaf certificate.
+        if value.certificates.is_empty() {
+            return Err(CredentialError::EmptyCertificateChain);
+        }
+        Ok(Self {
+            certificates: value.certificates,
+        })
+    }
+}
+
+impl CertificateChain {
+    /// Returns the leaf certificate of the chain.
+    pub fn leaf(&self) -> &Certificate {
+        // The chain is never empty.
+        &self.certificates[0]
+    }
+
+    /// Returns the intermediate certificates of the chain, i.e., all
+    /// certificates after the leaf certificate.
+    pub fn intermediates(&self) -> &[Certificate] {
+        &self.certificates[1..]
+    }
+
+    /// Returns all certificates of the chain, starting with the leaf
+    /// certificate.
+    pub fn certificates(&self) -> &[Certificate] {
+        &self.certificates
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for CertificateChain {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // The chain always contains the leaf certificate.
+        let mut certificates = vec![Certificate::arbitrary(u)?];
+        certificates.extend(<Vec<Certificate> as arbitrary::Arbitrary>::arbitrary(u)?);
+        Ok(Self { certificates })
+    }
+}
+
+/// Opaque credential.
+///
+/// This struct contains the serialized content of an application-defined
+/// credential with a [`CredentialType`] from the private use range. Its content
+/// is only interpreted by the application through the [`CustomCredential`]
+/// trait.
+///
+/// ```c
+/// struct {
+///     opaque data<V>;
+/// } OpaqueCredential;
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct OpaqueCredential {
+    credential_type: u16,
+    data: VLBytes,
+}
+
+impl OpaqueCredential {
+    /// Returns the serialized content of the credential.
+    pub fn data(&self) -> &[u8] {
+        self.data.as_slice()
+    }
 }
 
 /// MlsCredentialType.
 ///
 /// This enum contains variants containing the different available credentials.
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum MlsCredentialType {
     /// A [`BasicCredential`]
     Basic(BasicCredential),
-    /// An X.509 [`Certificate`]
-    X509(Certificate),
+    /// An X.509 [`CertificateChain`]
+    X509(CertificateChain),
+    /// An application-defined [`OpaqueCredential`]
+    Custom(OpaqueCredential),
+}
+
+/// An application-defined credential.
+///
+/// Applications can define their own credential types (e.g. tokens or JWTs)
+/// with a [`CredentialType`] from the private use range (`0xF000 - 0xFFFF`).
+/// OpenMLS transports such credentials as opaque bytes and uses the
+/// serialization callbacks of this trait to convert them. The validation
+/// callback is consulted for every credential of this type when the type is
+/// registered with a [`CustomCredentialRegistry`] that is used as the
+/// [`CredentialValidator`] of a group.
+///
+/// Note that all members of a group must list the custom credential type in
+/// the [`Capabilities`](crate::treesync::node::leaf_node::Capabilities) of
+/// their leaf node.
+pub trait CustomCredential: Sized {
+    /// The credential type. Must be in the private use range `0xF000 - 0xFFFF`.
+    const CREDENTIAL_TYPE: u16;
+
+    /// Serializes the credential.
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Deserializes a credential. Returns `None` if the `bytes` are not a valid
+    /// encoding of the credential.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+
+    /// Returns `true` if the credential is acceptable and `false` otherwise.
+    fn validate(&self) -> bool {
+        true
+    }
+}
+
+/// A registry of [`CustomCredential`] types.
+///
+/// When used as [`CredentialValidator`], credentials of registered custom
+/// types are deserialized and validated with the callbacks of the respective
+/// [`CustomCredential`]. Credentials of unregistered custom types are
+/// rejected, while all other credentials are accepted.
+#[derive(Debug, Default, Clone)]
+pub struct CustomCredentialRegistry {
+    validators: HashMap<u16, fn(&[u8]) -> bool>,
+}
+
+impl CustomCredentialRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the custom credential type `C`.
+    ///
+    /// Returns an error if the credential type of `C` is not in the private
+    /// use range.
+    pub fn register<C: CustomCredential>(mut self) -> Result<Self, CredentialError> {
+        if !CredentialType::from(C::CREDENTIAL_TYPE).is_private_use() {
+            return Err(CredentialError::UnsupportedCredentialType);
+        }
+        self.validators
+            .insert(C::CREDENTIAL_TYPE, |bytes: &[u8]| -> bool {
+                C::from_bytes(bytes)
+                    .map(|credential| credential.validate())
+                    .unwrap_or(false)
+            });
+        Ok(self)
+    }
+
+    /// Returns `true` if the credential type is registered.
+    pub fn is_registered(&self, credential_type: CredentialType) -> bool {
+        self.validators.contains_key(&u16::from(credential_type))
+    }
+}
+
+impl CredentialValidator for CustomCredentialRegistry {
+    fn validate(&self, credential: &Credential) -> bool {
+        match credential.credential.as_ref() {
+            MlsCredentialType::Custom(opaque_credential) => self
+                .validators
+                .get(&opaque_credential.credential_type)
+                .map(|validate| validate(opaque_credential.data()))
+                .unwrap_or(false),
+            _ => true,
+        }
+    }
 }
 
 /// Credential.
@@ -168,14 +374,17 @@ pub enum MlsCredentialType {
 ///             opaque identity<V>;
 ///
 ///         case x509:
-///             Certificate chain<V>;
+///             Certificate certificates<V>;
 ///     };
 /// } Credential;
 /// ```
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Credential {
     credential_type: CredentialType,
-    credential: MlsCredentialType,
+    // The content is shared between clones, since the same credential is
+    // usually held by the leaf node, proposals and processed messages of a
+    // member.
+    credential: Arc<MlsCredentialType>,
 }
 
 impl Credential {
@@ -202,17 +411,88 @@ impl Credential {
         };
         let credential = Credential {
             credential_type,
-            credential: MlsCredentialType::Basic(mls_credential),
+            credential: Arc::new(MlsCredentialType::Basic(mls_credential)),
         };
         Ok(credential)
     }
 
+    /// Creates a new [`Credential`] from the application-defined credential
+    /// `custom_credential`.
+    ///
+    /// Returns an error if the credential type of `C` is not in the private
+    /// use range.
+    pub fn from_custom<C: CustomCredential>(
+        custom_credential: &C,
+    ) -> Result<Self, CredentialError> {
+        let credential_type = CredentialType::from(C::CREDENTIAL_TYPE);
+        if !credential_type.is_private_use() {
+            return Err(CredentialError::UnsupportedCredentialType);
+        }
+        Ok(Credential {
+            credential_type,
+            credential: Arc::new(MlsCredentialType::Custom(OpaqueCredential {
+                credential_type: C::CREDENTIAL_TYPE,
+                data: custom_credential.to_bytes().into(),
+            })),
+        })
+    }
+
+    /// Converts this [`Credential`] into the application-defined credential
+    /// `C`.
+    ///
+    /// Returns an error if this credential is not of the credential type of `C`
+    /// or if it can't be deserialized.
+    pub fn to_custom<C: CustomCredential>(&self) -> Result<C, CredentialError> {
+        match self.credential.as_ref() {
+            MlsCredentialType::Custom(opaque_credential)
+                if opaque_credential.credential_type == C::CREDENTIAL_TYPE =>
+            {
+                C::from_bytes(opaque_credential.data())
+                    .ok_or(CredentialError::InvalidCustomCredential)
+            }
+            _ => Err(CredentialError::UnsupportedCredentialType),
+        }
+    }
+
+    /// Creates a new X.509 [`Credential`] from the DER-encoded `certificates`
+    /// of a certificate chain, starting with the leaf certificate.
+    ///
+    /// Returns [`CredentialError::EmptyCertificateChain`] if no certificate is
+    /// given.
+    pub fn from_certificate_chain(certificates: Vec<Vec<u8>>) -> Result<Self, CredentialError> {
+        if certificates.is_empty() {
+            return Err(CredentialError::EmptyCertificateChain);
+        }
+        let certificates = certificates
+            .into_iter()
+            .map(|cert_data| Certificate {
+                cert_data: cert_data.into(),
+            })
+            .collect();
+        Ok(Credential::from(MlsCredentialType::X509(
+            CertificateChain { certificates },
+        )))
+    }
+
+    /// Returns the certificate chain of an X.509 credential, or `None` for
+    /// all other credential types.
+    pub fn certificate_chain(&self) -> Option<&CertificateChain> {
+        match self.credential.as_ref() {
+            MlsCredentialType::X509(certificate_chain) => Some(certificate_chain),
+            _ => None,
+        }
+    }
+
     /// Returns the identity of a given credential.
+    ///
+    /// For application-defined credentials, this is the serialized credential.
+    /// For X.509 credentials, this is the DER encoding of the leaf certificate.
+    /// Use [`Credential::certificate_chain()`] to access the whole chain.
     pub fn identity(&self) -> &[u8] {
-        match &self.credential {
+        match self.credential.as_ref() {
             MlsCredentialType::Basic(basic_credential) => basic_credential.identity.as_slice(),
-            // TODO: implement getter for identity for X509 certificates. See issue #134.
-            MlsCredentialType::X509(_) => panic!("X509 certificates are not yet implemented."),
+            MlsCredentialType::Custom(opaque_credential) => opaque_credential.data(),
+            MlsCredentialType::X509(certificate_chain) => certificate_chain.leaf().cert_data(),
         }
     }
 }
@@ -220,33 +500,46 @@ impl Credential {
 impl From<MlsCredentialType> for Credential {
     fn from(mls_credential_type: MlsCredentialType) -> Self {
         Credential {
-            credential_type: match mls_credential_type {
+            credential_type: match &mls_credential_type {
                 MlsCredentialType::Basic(_) => CredentialType::Basic,
                 MlsCredentialType::X509(_) => CredentialType::X509,
+                MlsCredentialType::Custom(opaque_credential) => {
+                    CredentialType::from(opaque_credential.credential_type)
+                }
             },
-            credential: mls_credential_type,
+            credential: Arc::new(mls_credential_type),
         }
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Credential {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // The credential type always matches the credential.
+        <MlsCredentialType as arbitrary::Arbitrary>::arbitrary(u).map(Credential::from)
+    }
+}
+
 /// Basic Credential.
 ///
 /// A `BasicCredential` as defined in the MLS protocol spec. It exposes only an
 /// `identity` to represent the client.
 ///
 /// Note that this credential does not contain any key material or any other
-/// information.
+/// information. The identity can be checked and parsed with a
+/// [`BasicIdentitySchema`](basic_identity::BasicIdentitySchema).
 ///
 /// OpenMLS provides an implementation of signature keys for convenience in the
 /// `openmls_basic_credential` crate.
 #[derive(
     Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct BasicCredential {
     identity: VLBytes,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// A wrapper around a credential with a corresponding public key.
 pub struct CredentialWithKey {
     /// The [`Credential`].
@@ -255,6 +548,40 @@ pub struct CredentialWithKey {
     pub signature_key: SignaturePublicKey,
 }
 
+/// A validator for [`Credential`]s.
+///
+/// OpenMLS consults the validator configured in the
+/// [`MlsGroupConfig`](crate::group::MlsGroupConfig) for every credential it
+/// encounters when processing messages, joining a group from a
+/// [`Welcome`](crate::messages::Welcome) or adding members. This includes the
+/// credentials of message senders (members, new members and external senders),
+/// of new joiners in Add proposals and of members updating their leaf node.
+///
+/// This allows applications to enforce their identity model, e.g. by checking
+/// the signature of an Authentication Service or by maintaining an allow list.
+pub trait CredentialValidator: Send + Sync {
+    /// Returns `true` if the `credential` is acceptable and `false` otherwise.
+    fn validate(&self, credential: &Credential) -> bool;
+
+    /// Returns the time (in seconds since the UNIX epoch) after which the
+    /// `credential` is no longer valid, or `None` if it doesn't expire.
+    ///
+    /// Expired credentials of members are treated like revoked credentials.
+    fn not_after(&self, _credential: &Credential) -> Option<u64> {
+        None
+    }
+
+    /// Returns `true` if the `credential` has been revoked and `false`
+    /// otherwise.
+    ///
+    /// This is checked for the senders of proposals and commits and for
+    /// members updating their leaf node, so that compromised members can be
+    /// filtered before they are removed from the group.
+    fn is_revoked(&self, _credential: &Credential) -> bool {
+        false
+    }
+}
+
 #[cfg(test)]
 impl CredentialWithKey {
     pub fn from_parts(credential: Credential, key: &[u8]) -> Self {
@@ -48,3 +48,229 @@ fn that_unknown_credential_types_are_de_serialized_correctly() {
         assert_eq!(test, got_serialized);
     }
 }
+
+#[test]
+fn cloned_credentials_share_content() {
+    let credential = Credential::new(b"Alice".to_vec(), CredentialType::Basic).unwrap();
+    let clone = credential.clone();
+    assert!(Arc::ptr_eq(&credential.credential, &clone.credential));
+
+    // Sharing the content must not change the encoding.
+    let encoded = credential.tls_serialize_detached().unwrap();
+    let decoded = Credential::tls_deserialize_exact(&encoded).unwrap();
+    assert_eq!(decoded, clone);
+    assert_eq!(encoded, decoded.tls_serialize_detached().unwrap());
+}
+
+#[derive(Debug, PartialEq)]
+struct TokenCredential {
+    token: Vec<u8>,
+}
+
+impl CustomCredential for TokenCredential {
+    const CREDENTIAL_TYPE: u16 = 0xF00D;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.token.clone()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(Self {
+            token: bytes.to_vec(),
+        })
+    }
+
+    fn validate(&self) -> bool {
+        self.token.starts_with(b"valid")
+    }
+}
+
+#[test]
+fn custom_credentials_are_de_serialized_and_validated() {
+    let token = TokenCredential {
+        token: b"valid token".to_vec(),
+    };
+    let credential = Credential::from_custom(&token).unwrap();
+    assert_eq!(
+        credential.credential_type(),
+        CredentialType::Unknown(0xF00D)
+    );
+    assert!(credential.credential_type().is_private_use());
+
+    // Test serialization.
+    let serialized = credential.tls_serialize_detached().unwrap();
+    let deserialized = Credential::tls_deserialize_exact(&serialized).unwrap();
+    assert_eq!(credential, deserialized);
+    assert_eq!(deserialized.to_custom::<TokenCredential>().unwrap(), token);
+
+    // Test validation.
+    let registry = CustomCredentialRegistry::new()
+        .register::<TokenCredential>()
+        .unwrap();
+    assert!(registry.is_registered(CredentialType::Unknown(0xF00D)));
+    assert!(registry.validate(&credential));
+    let invalid_credential = Credential::from_custom(&TokenCredential {
+        token: b"invalid token".to_vec(),
+    })
+    .unwrap();
+    assert!(!registry.validate(&invalid_credential));
+    assert!(!CustomCredentialRegistry::new().validate(&credential));
+    let basic_credential = Credential::new(b"Alice".to_vec(), CredentialType::Basic).unwrap();
+    assert!(registry.validate(&basic_credential));
+    assert_eq!(
+        basic_credential.to_custom::<TokenCredential>(),
+        Err(CredentialError::UnsupportedCredentialType)
+    );
+}
+
+struct TrustedIssuer(&'static [u8]);
+
+impl verifiable_credential::VerifiableCredentialVerifier for TrustedIssuer {
+    fn verify(&self, verifiable_credential: &verifiable_credential::VerifiableCredential) -> bool {
+        verifiable_credential.data().starts_with(self.0)
+    }
+}
+
+#[test]
+fn verifiable_credentials() {
+    use verifiable_credential::*;
+
+    let verifiable_credential =
+        VerifiableCredential::new("application/vc+jwt", b"issuer-a.payload.signature".to_vec());
+    let credential = Credential::from_custom(&verifiable_credential).unwrap();
+    assert_eq!(
+        credential.credential_type(),
+        CredentialType::Unknown(VERIFIABLE_CREDENTIAL_TYPE)
+    );
+
+    // Test serialization.
+    let serialized = credential.tls_serialize_detached().unwrap();
+    let deserialized = Credential::tls_deserialize_exact(&serialized).unwrap();
+    let got = deserialized.to_custom::<VerifiableCredential>().unwrap();
+    assert_eq!(got, verifiable_credential);
+    assert_eq!(got.media_type(), Some("application/vc+jwt"));
+
+    // Test validation.
+    let validator = VerifiableCredentialValidator::new(TrustedIssuer(b"issuer-a."));
+    assert!(validator.validate(&credential));
+    let untrusted_credential = Credential::from_custom(&VerifiableCredential::new(
+        "application/vc+jwt",
+        b"issuer-b.payload.signature".to_vec(),
+    ))
+    .unwrap();
+    assert!(!validator.validate(&untrusted_credential));
+    let basic_credential = Credential::new(b"Alice".to_vec(), CredentialType::Basic).unwrap();
+    assert!(!validator.validate(&basic_credential));
+}
+
+#[test]
+fn x509_credentials() {
+    let leaf = b"leaf certificate".to_vec();
+    let intermediate = b"intermediate certificate".to_vec();
+    let credential =
+        Credential::from_certificate_chain(vec![leaf.clone(), intermediate.clone()]).unwrap();
+    assert_eq!(credential.credential_type(), CredentialType::X509);
+    assert_eq!(credential.identity(), leaf.as_slice());
+
+    // Test serialization.
+    let serialized = credential.tls_serialize_detached().unwrap();
+    let deserialized = Credential::tls_deserialize_exact(&serialized).unwrap();
+    assert_eq!(credential, deserialized);
+
+    // Test the accessors.
+    let certificate_chain = deserialized.certificate_chain().unwrap();
+    assert_eq!(certificate_chain.leaf().cert_data(), leaf.as_slice());
+    assert_eq!(certificate_chain.intermediates().len(), 1);
+    assert_eq!(
+        certificate_chain.intermediates()[0].cert_data(),
+        intermediate.as_slice()
+    );
+    assert_eq!(certificate_chain.certificates().len(), 2);
+    let basic_credential = Credential::new(b"Alice".to_vec(), CredentialType::Basic).unwrap();
+    assert!(basic_credential.certificate_chain().is_none());
+
+    // Empty chains are rejected.
+    assert_eq!(
+        Credential::from_certificate_chain(vec![]),
+        Err(CredentialError::EmptyCertificateChain)
+    );
+    // The X.509 credential type, followed by a chain of length zero.
+    let empty_chain = [0x00, 0x02, 0x00];
+    assert!(Credential::tls_deserialize_exact(&empty_chain).is_err());
+
+    // Empty chains are rejected when loading a serialized credential.
+    let mut serialized = serde_json::to_value(&credential).unwrap();
+    assert!(serde_json::from_value::<Credential>(serialized.clone()).is_ok());
+    let certificate_chain = &mut serialized["credential"]["X509"]["certificates"];
+    *certificate_chain = serde_json::Value::Array(vec![]);
+    assert!(serde_json::from_value::<Credential>(serialized).is_err());
+}
+
+#[test]
+fn basic_identities() {
+    use basic_identity::*;
+
+    // Test the default schema.
+    let schema = BasicIdentitySchema::default();
+    let identity = schema.parse("Alice Ämmälä".as_bytes()).unwrap();
+    assert_eq!(identity.as_str(), "Alice Ämmälä");
+    assert_eq!(identity.format(), IdentityFormat::Utf8);
+    assert_eq!(identity.user(), None);
+    assert_eq!(identity.scheme(), None);
+    assert_eq!(
+        schema.parse(&[0xFF, 0xFE]),
+        Err(BasicIdentityError::InvalidUtf8)
+    );
+    assert_eq!(schema.parse(b""), Err(BasicIdentityError::Empty));
+    assert_eq!(
+        schema.parse(b"Alice\n"),
+        Err(BasicIdentityError::InvalidCharacter)
+    );
+    assert_eq!(
+        BasicIdentitySchema::new(IdentityFormat::Utf8, 4).parse(b"Alice"),
+        Err(BasicIdentityError::TooLong)
+    );
+
+    // Test URIs.
+    let schema = BasicIdentitySchema::new(IdentityFormat::Uri, DEFAULT_MAX_IDENTITY_LENGTH);
+    let identity = schema.parse(b"sip:alice@example.com").unwrap();
+    assert_eq!(identity.scheme(), Some("sip"));
+    assert_eq!(identity.scheme_specific_part(), Some("alice@example.com"));
+    assert_eq!(identity.domain(), None);
+    for invalid_uri in [&b"alice"[..], b"sip:", b"1sip:alice", b"sip:alice bob"] {
+        assert_eq!(
+            schema.parse(invalid_uri),
+            Err(BasicIdentityError::InvalidUri)
+        );
+    }
+
+    // Test user@domain identities.
+    let schema =
+        BasicIdentitySchema::new(IdentityFormat::UserAtDomain, DEFAULT_MAX_IDENTITY_LENGTH);
+    let identity = schema.parse(b"alice@example.com").unwrap();
+    assert_eq!(identity.user(), Some("alice"));
+    assert_eq!(identity.domain(), Some("example.com"));
+    assert_eq!(identity.to_string(), "alice@example.com");
+    for invalid_identity in [
+        &b"alice"[..],
+        b"@example.com",
+        b"alice@",
+        b"alice@example..com",
+        b"alice@-example.com",
+        b"alice@bob@example.com",
+    ] {
+        assert_eq!(
+            schema.parse(invalid_identity),
+            Err(BasicIdentityError::InvalidUserAtDomain)
+        );
+    }
+
+    // Test credentials.
+    let credential = Credential::new(b"alice@example.com".to_vec(), CredentialType::Basic).unwrap();
+    assert_eq!(schema.parse_credential(&credential).unwrap(), identity);
+    let x509_credential = Credential::from_certificate_chain(vec![b"leaf".to_vec()]).unwrap();
+    assert_eq!(
+        schema.parse_credential(&x509_credential),
+        Err(BasicIdentityError::NotBasicCredential)
+    );
+}
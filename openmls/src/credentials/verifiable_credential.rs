@@ -0,0 +1,116 @@
+//! # Verifiable Credentials
+//!
+//! This module contains the [`VerifiableCredential`], an application-defined
+//! credential that carries a [W3C Verifiable Credential or Verifiable
+//! Presentation](https://www.w3.org/TR/vc-data-model/). Verifiable credentials
+//! allow groups to authenticate members through (possibly selectively
+//! disclosed) attributes rather than raw identities.
+//!
+//! OpenMLS does not interpret the content of a verifiable credential. Its
+//! verification is delegated to the application through a
+//! [`VerifiableCredentialVerifier`], which is consulted for every credential
+//! in the group when used through a [`VerifiableCredentialValidator`].
+
+use serde::{Deserialize, Serialize};
+use tls_codec::{
+    Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, TlsDeserialize,
+    TlsSerialize, TlsSize, VLBytes,
+};
+
+use super::{Credential, CredentialValidator, CustomCredential};
+
+/// The [`CredentialType`](super::CredentialType) of a [`VerifiableCredential`].
+/// This value is taken from the private use range.
+pub const VERIFIABLE_CREDENTIAL_TYPE: u16 = 0xF0C1;
+
+/// Verifiable Credential.
+///
+/// A W3C Verifiable Credential or Verifiable Presentation together with its
+/// media type, e.g. `application/vc+ld+json`, `application/vc+jwt` or
+/// `application/vp+ld+json`.
+///
+/// ```c
+/// struct {
+///     opaque media_type<V>;
+///     opaque data<V>;
+/// } VerifiableCredential;
+/// ```
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct VerifiableCredential {
+    media_type: VLBytes,
+    data: VLBytes,
+}
+
+impl VerifiableCredential {
+    /// Creates a new [`VerifiableCredential`] from the encoded credential or
+    /// presentation `data` with the given `media_type`.
+    pub fn new(media_type: &str, data: Vec<u8>) -> Self {
+        Self {
+            media_type: media_type.as_bytes().into(),
+            data: data.into(),
+        }
+    }
+
+    /// Returns the media type of the credential. Returns `None` if the media
+    /// type is not valid UTF-8.
+    pub fn media_type(&self) -> Option<&str> {
+        std::str::from_utf8(self.media_type.as_slice()).ok()
+    }
+
+    /// Returns the encoded credential or presentation.
+    pub fn data(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+}
+
+impl CustomCredential for VerifiableCredential {
+    const CREDENTIAL_TYPE: u16 = VERIFIABLE_CREDENTIAL_TYPE;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        // Serializing a struct of two variable-length vectors only fails if a
+        // vector exceeds the maximum length, which can't be constructed.
+        self.tls_serialize_detached().unwrap_or_default()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::tls_deserialize_exact(bytes).ok()
+    }
+}
+
+/// A verifier for [`VerifiableCredential`]s.
+///
+/// The verifier is implemented by the application and is responsible for
+/// checking the proof of the credential or presentation, its issuer and the
+/// disclosed attributes.
+pub trait VerifiableCredentialVerifier: Send + Sync {
+    /// Returns `true` if the `verifiable_credential` is acceptable and `false`
+    /// otherwise.
+    fn verify(&self, verifiable_credential: &VerifiableCredential) -> bool;
+}
+
+/// A [`CredentialValidator`] that only accepts [`VerifiableCredential`]s
+/// accepted by the given [`VerifiableCredentialVerifier`]. Credentials of all
+/// other types are rejected.
+#[derive(Debug, Clone)]
+pub struct VerifiableCredentialValidator<V: VerifiableCredentialVerifier> {
+    verifier: V,
+}
+
+impl<V: VerifiableCredentialVerifier> VerifiableCredentialValidator<V> {
+    /// Creates a new [`VerifiableCredentialValidator`] from the given
+    /// `verifier`.
+    pub fn new(verifier: V) -> Self {
+        Self { verifier }
+    }
+}
+
+impl<V: VerifiableCredentialVerifier> CredentialValidator for VerifiableCredentialValidator<V> {
+    fn validate(&self, credential: &Credential) -> bool {
+        credential
+            .to_custom::<VerifiableCredential>()
+            .map(|verifiable_credential| self.verifier.verify(&verifiable_credential))
+            .unwrap_or(false)
+    }
+}
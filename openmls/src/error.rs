@@ -35,6 +35,16 @@
 //!
 //! All errors derive [`thiserror::Error`](https://docs.rs/thiserror/latest/thiserror/) as well as
 //! [`Debug`](`std::fmt::Debug`), [`PartialEq`](`std::cmp::PartialEq`), and [`Clone`](`std::clone::Clone`).
+//!
+//! ## Error codes
+//!
+//! All public errors implement [`HasErrorCode`], which maps every error to a
+//! stable numeric code and an [`ErrorCategory`]. FFI layers and telemetry can
+//! use the codes to handle errors without matching on error messages. Errors
+//! that wrap another error return the code of the wrapped error, i.e., the
+//! code identifies the root cause of the error.
+//!
+//! Codes are never reused or changed once assigned.
 
 use backtrace::Backtrace;
 use openmls_traits::types::CryptoError;
@@ -42,6 +52,8 @@ use std::fmt::Display;
 use thiserror::Error;
 use tls_codec::Error as TlsCodecError;
 
+mod codes;
+
 /// Generic error type that indicates unrecoverable errors in the library.
 ///
 /// This error has 3 subtypes:
@@ -132,6 +144,94 @@ impl std::fmt::Debug for InternalLibraryError {
     }
 }
 
+impl HasErrorCode for LibraryError {
+    fn error_code(&self) -> ErrorCode {
+        match self.internal {
+            InternalLibraryError::Custom(_) => ErrorCode::library(1),
+            InternalLibraryError::MissingBoundsCheck(_) => ErrorCode::library(2),
+            InternalLibraryError::CryptoError(_) => ErrorCode::crypto(3),
+        }
+    }
+}
+
+/// The category of an [`ErrorCode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// Invalid input, e.g., an invalid message from another member or a
+    /// function that was called in the wrong state of the group.
+    Validation,
+    /// A cryptographic check failed, e.g., a signature could not be verified
+    /// or a ciphertext could not be decrypted.
+    Crypto,
+    /// The key store returned an error.
+    Storage,
+    /// An internal error of the library, see [`LibraryError`].
+    Library,
+}
+
+/// A stable numeric code and the [`ErrorCategory`] of an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorCode {
+    code: u32,
+    category: ErrorCategory,
+}
+
+impl ErrorCode {
+    pub(crate) const fn validation(code: u32) -> Self {
+        Self {
+            code,
+            category: ErrorCategory::Validation,
+        }
+    }
+
+    pub(crate) const fn crypto(code: u32) -> Self {
+        Self {
+            code,
+            category: ErrorCategory::Crypto,
+        }
+    }
+
+    pub(crate) const fn storage(code: u32) -> Self {
+        Self {
+            code,
+            category: ErrorCategory::Storage,
+        }
+    }
+
+    pub(crate) const fn library(code: u32) -> Self {
+        Self {
+            code,
+            category: ErrorCategory::Library,
+        }
+    }
+
+    /// Returns the numeric code.
+    pub fn code(&self) -> u32 {
+        self.code
+    }
+
+    /// Returns the [`ErrorCategory`].
+    pub fn category(&self) -> ErrorCategory {
+        self.category
+    }
+}
+
+/// Errors with a stable [`ErrorCode`].
+pub trait HasErrorCode {
+    /// Returns the [`ErrorCode`] of the error.
+    fn error_code(&self) -> ErrorCode;
+
+    /// Returns the numeric code of the error.
+    fn code(&self) -> u32 {
+        self.error_code().code()
+    }
+
+    /// Returns the [`ErrorCategory`] of the error.
+    fn category(&self) -> ErrorCategory {
+        self.error_code().category()
+    }
+}
+
 /// A wrapper struct for an error string. This can be used when no complex error
 /// variant is needed.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -0,0 +1,1281 @@
+//! Stable error codes of all public errors.
+//!
+//! The codes 1 to 99 are used by [`LibraryError`](super::LibraryError). Every
+//! other error enum has a block of 100 codes, starting at a multiple of 100,
+//! and every variant that doesn't wrap another error has its own code in the
+//! block of its enum.
+//!
+//! Codes must never be reused or changed. New variants get the next free code
+//! in the block of their enum and new error enums get the next free block.
+
+use crate::{
+    ciphersuite::signable::SignatureError,
+    credentials::errors::{BasicIdentityError, CredentialError},
+    extensions::errors::{
+        CapabilitiesExtensionError, ExtensionError, InvalidExtensionError, KeyPackageIdError,
+        ParentHashError, RatchetTreeError as RatchetTreeExtensionError,
+    },
+    framing::errors::{
+        ComputeProposalRefError, MessageDecryptionError, MlsMessageError, SenderError,
+        SyntacticValidationError,
+    },
+    group::errors::{
+        CreateAddProposalError, CreateCommitError, CreateGroupContextExtProposalError,
+        CreateRemoveProposalError, ExternalCommitError, ExternalCommitValidationError,
+        MergeCommitError, ProposalValidationError, StageCommitError, ValidationError, WelcomeError,
+    },
+    group::mls_client::errors::MlsClientError,
+    group::mls_group::errors::{
+        AddMembersError, CommitToPendingProposalsError, CreateFragmentedMessageError,
+        CreateMembershipTokenError, CreateMessageError, CreateTargetedMessageError,
+        CryptoProviderError, EmptyInputError, ExportCommitAuditLogError, ExportGroupInfoError,
+        ExportSecretError, GroupStateTransferError, InvalidConfigError, LeaveGroupError,
+        MergePendingCommitError, MessageStreamError, MlsGroupStateError, NewGroupError,
+        ProcessMessageError, ProcessTargetedMessageError, ProposalError, ProposeAddMemberError,
+        ProposeExternalSenderError, ProposePskError, ProposeRemoveMemberError,
+        ProposeSelfUpdateError, ReassemblyError, RemoveMembersError, RollbackPendingCommitError,
+        SafeExtensionError, SelfUpdateError, SenderRatchetStateError, UpdateCapabilitiesError,
+        UpdateLeafExtensionsError, VerifyMembershipTokenError,
+    },
+    group::public_group::errors::{
+        AddProposalError, CreationFromExternalError, PublicGroupBuildError, SnapshotImportError,
+    },
+    key_packages::errors::{
+        KeyPackageDeleteError, KeyPackageExtensionSupportError, KeyPackageNewError,
+        KeyPackageVerifyError,
+    },
+    messages::group_info::GroupInfoError,
+    messages::GroupSecretsError,
+    schedule::errors::PskError,
+    tree::secret_tree::SecretTreeError,
+    treesync::errors::{
+        ApplyUpdatePathError, LeafNodeValidationError, LifetimeError, PublicTreeError,
+        RatchetTreeDeltaError, RatchetTreeVerificationError, TreeSyncFromNodesError,
+        UpdatePathError,
+    },
+    treesync::RatchetTreeError,
+    versions::VersionError,
+};
+
+use super::{ErrorCode, HasErrorCode};
+
+impl HasErrorCode for SignatureError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::VerificationError => ErrorCode::crypto(101),
+            Self::SigningError => ErrorCode::crypto(102),
+            Self::SignerError(_) => ErrorCode::crypto(103),
+        }
+    }
+}
+
+impl HasErrorCode for CredentialError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::UnsupportedCredentialType => ErrorCode::validation(201),
+            Self::InvalidSignature => ErrorCode::crypto(202),
+            Self::InvalidCustomCredential => ErrorCode::validation(203),
+            Self::EmptyCertificateChain => ErrorCode::validation(204),
+        }
+    }
+}
+
+impl HasErrorCode for BasicIdentityError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::NotBasicCredential => ErrorCode::validation(8001),
+            Self::InvalidUtf8 => ErrorCode::validation(8002),
+            Self::Empty => ErrorCode::validation(8003),
+            Self::TooLong => ErrorCode::validation(8004),
+            Self::InvalidCharacter => ErrorCode::validation(8005),
+            Self::InvalidUri => ErrorCode::validation(8006),
+            Self::InvalidUserAtDomain => ErrorCode::validation(8007),
+        }
+    }
+}
+
+impl HasErrorCode for ExtensionError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::UnsupportedProposalType => ErrorCode::validation(301),
+            Self::UnsupportedExtensionType => ErrorCode::validation(302),
+            Self::LibraryError(e) => e.error_code(),
+            Self::InvalidExtensionType(_) => ErrorCode::validation(303),
+            Self::Capabilities(e) => e.error_code(),
+            Self::KeyPackageId(e) => e.error_code(),
+            Self::ParentHash(e) => e.error_code(),
+            Self::RatchetTree(e) => e.error_code(),
+            Self::InvalidExtension(e) => e.error_code(),
+            Self::InvalidCustomExtension => ErrorCode::validation(304),
+        }
+    }
+}
+
+impl HasErrorCode for CapabilitiesExtensionError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Invalid => ErrorCode::validation(401),
+            Self::EmptyVersionsField => ErrorCode::validation(402),
+            Self::UnsupportedCiphersuite => ErrorCode::validation(403),
+        }
+    }
+}
+
+impl HasErrorCode for KeyPackageIdError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Invalid => ErrorCode::validation(501),
+        }
+    }
+}
+
+impl HasErrorCode for ParentHashError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Invalid => ErrorCode::validation(601),
+        }
+    }
+}
+
+impl HasErrorCode for RatchetTreeExtensionError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Invalid => ErrorCode::validation(701),
+        }
+    }
+}
+
+impl HasErrorCode for InvalidExtensionError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Duplicate => ErrorCode::validation(801),
+            Self::NotFound => ErrorCode::validation(802),
+        }
+    }
+}
+
+impl HasErrorCode for MessageDecryptionError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::GenerationOutOfBound => ErrorCode::validation(901),
+            Self::AeadError => ErrorCode::crypto(902),
+            Self::WrongWireFormat => ErrorCode::validation(903),
+            Self::MalformedContent => ErrorCode::validation(904),
+            Self::SecretTreeError(e) => e.error_code(),
+            Self::SenderError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for SenderError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NotAMember => ErrorCode::validation(1001),
+            Self::UnknownSender => ErrorCode::validation(1002),
+        }
+    }
+}
+
+impl HasErrorCode for MlsMessageError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::UnableToDecode => ErrorCode::validation(1101),
+            Self::UnableToEncode => ErrorCode::validation(1102),
+            Self::MessageTooLarge => ErrorCode::validation(1103),
+        }
+    }
+}
+
+impl HasErrorCode for SyntacticValidationError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::UnableToDecode => ErrorCode::validation(1201),
+            Self::UnsupportedMlsVersion => ErrorCode::validation(1202),
+            Self::NotAProtocolMessage => ErrorCode::validation(1203),
+            Self::WrongGroupId => ErrorCode::validation(1204),
+            Self::WrongEpoch => ErrorCode::validation(1205),
+            Self::IncompatibleWireFormat => ErrorCode::validation(1206),
+            Self::UnencryptedApplicationMessage => ErrorCode::validation(1207),
+            Self::MissingMembershipTag => ErrorCode::validation(1208),
+            Self::MissingConfirmationTag => ErrorCode::validation(1209),
+            Self::NotACommit => ErrorCode::validation(1210),
+            Self::NoPath => ErrorCode::validation(1211),
+            Self::NotAnExternalAddProposal => ErrorCode::validation(1212),
+            Self::InvalidSenderType => ErrorCode::validation(1213),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for WelcomeError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::GroupSecrets(e) => e.error_code(),
+            Self::PrivateInitKeyNotFound => ErrorCode::validation(1301),
+            Self::LibraryError(e) => e.error_code(),
+            Self::CiphersuiteMismatch => ErrorCode::validation(1302),
+            Self::GroupInfo(e) => e.error_code(),
+            Self::JoinerSecretNotFound => ErrorCode::validation(1303),
+            Self::MissingRatchetTree => ErrorCode::validation(1304),
+            Self::ConfirmationTagMismatch => ErrorCode::crypto(1305),
+            Self::InvalidGroupInfoSignature => ErrorCode::crypto(1306),
+            Self::UnsupportedMlsVersion => ErrorCode::validation(1307),
+            Self::UnsupportedCapability => ErrorCode::validation(1308),
+            Self::UnsupportedMediaTypes => ErrorCode::validation(1309),
+            Self::UnknownSender => ErrorCode::validation(1310),
+            Self::MalformedWelcomeMessage => ErrorCode::validation(1311),
+            Self::UnableToDecrypt => ErrorCode::crypto(1312),
+            Self::UnsupportedExtensions => ErrorCode::validation(1313),
+            Self::Psk(e) => e.error_code(),
+            Self::NoMatchingEncryptionKey => ErrorCode::validation(1314),
+            Self::NoMatchingKeyPackage => ErrorCode::validation(1315),
+            Self::KeyStoreError(_) => ErrorCode::storage(1316),
+            Self::PublicTreeError(e) => e.error_code(),
+            Self::PublicGroupError(e) => e.error_code(),
+            Self::LeafNodeValidation(e) => e.error_code(),
+            Self::InvalidCredential => ErrorCode::validation(1317),
+            Self::KeyPackageAlreadyConsumed => ErrorCode::validation(1318),
+            Self::InvalidExtension(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ExternalCommitError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::MissingRatchetTree => ErrorCode::validation(1401),
+            Self::MissingExternalPub => ErrorCode::validation(1402),
+            Self::UnsupportedCiphersuite => ErrorCode::validation(1403),
+            Self::UnknownSender => ErrorCode::validation(1404),
+            Self::InvalidGroupInfoSignature => ErrorCode::crypto(1405),
+            Self::CommitError => ErrorCode::validation(1406),
+            Self::PublicGroupError(e) => e.error_code(),
+            Self::MissingCredential => ErrorCode::validation(1407),
+        }
+    }
+}
+
+impl HasErrorCode for StageCommitError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::EpochMismatch => ErrorCode::validation(1501),
+            Self::OwnCommit => ErrorCode::validation(1502),
+            Self::WrongPlaintextContentType => ErrorCode::validation(1503),
+            Self::PathLeafNodeVerificationFailure => ErrorCode::validation(1504),
+            Self::RequiredPathNotFound => ErrorCode::validation(1505),
+            Self::ConfirmationTagMissing => ErrorCode::validation(1506),
+            Self::ConfirmationTagMismatch => ErrorCode::crypto(1507),
+            Self::AttemptedSelfRemoval => ErrorCode::validation(1508),
+            Self::MissingProposal => ErrorCode::validation(1509),
+            Self::OwnKeyNotFound => ErrorCode::validation(1510),
+            Self::InconsistentSenderIndex => ErrorCode::validation(1511),
+            Self::SenderTypeExternal => ErrorCode::validation(1512),
+            Self::SenderTypeNewMemberProposal => ErrorCode::validation(1513),
+            Self::TooManyNewMembers => ErrorCode::validation(1514),
+            Self::ProposalValidationError(e) => e.error_code(),
+            Self::PskError(e) => e.error_code(),
+            Self::ExternalCommitValidation(e) => e.error_code(),
+            Self::UpdatePathError(e) => e.error_code(),
+            Self::MissingDecryptionKey => ErrorCode::validation(1515),
+            Self::VerifiedUpdatePathError(e) => e.error_code(),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for CreateCommitError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::OwnKeyNotFound => ErrorCode::validation(1601),
+            Self::CannotRemoveSelf => ErrorCode::validation(1602),
+            Self::MissingProposal => ErrorCode::validation(1603),
+            Self::WrongProposalSenderType => ErrorCode::validation(1604),
+            Self::PskError(e) => e.error_code(),
+            Self::ProposalValidationError(e) => e.error_code(),
+            Self::KeyStoreError(_) => ErrorCode::storage(1605),
+            Self::KeyPackageGenerationError(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+            Self::MissingCredential => ErrorCode::validation(1606),
+            Self::PublicTreeError(e) => e.error_code(),
+            Self::InvalidExtensionError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ValidationError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::WrongGroupId => ErrorCode::validation(1701),
+            Self::WrongEpoch => ErrorCode::validation(1702),
+            Self::NotACommit => ErrorCode::validation(1703),
+            Self::NotAnExternalAddProposal => ErrorCode::validation(1704),
+            Self::NoPath => ErrorCode::validation(1705),
+            Self::UnencryptedApplicationMessage => ErrorCode::validation(1706),
+            Self::UnknownMember => ErrorCode::validation(1707),
+            Self::MissingMembershipTag => ErrorCode::validation(1708),
+            Self::InvalidMembershipTag => ErrorCode::crypto(1709),
+            Self::MissingConfirmationTag => ErrorCode::validation(1710),
+            Self::WrongWireFormat => ErrorCode::validation(1711),
+            Self::InvalidSignature => ErrorCode::crypto(1712),
+            Self::NonMemberApplicationMessage => ErrorCode::validation(1713),
+            Self::UnableToDecrypt(e) => e.error_code(),
+            Self::NoPastEpochData => ErrorCode::validation(1714),
+            Self::UnauthorizedExternalSender => ErrorCode::validation(1715),
+            Self::NoExternalSendersExtension => ErrorCode::validation(1716),
+            Self::KeyPackageVerifyError(e) => e.error_code(),
+            Self::UpdatePathError(e) => e.error_code(),
+            Self::InvalidLeafNodeSignature => ErrorCode::crypto(1717),
+            Self::InvalidLeafNodeSourceType => ErrorCode::validation(1718),
+            Self::InvalidSenderType => ErrorCode::validation(1719),
+            Self::CommitterIncludedOwnUpdate => ErrorCode::validation(1720),
+        }
+    }
+}
+
+impl HasErrorCode for ProposalValidationError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::UnknownMember => ErrorCode::validation(1801),
+            Self::DuplicateIdentityAddProposal => ErrorCode::validation(1802),
+            Self::DuplicateSignatureKeyAddProposal => ErrorCode::validation(1803),
+            Self::DuplicatePublicKeyAddProposal => ErrorCode::validation(1804),
+            Self::ExistingIdentityAddProposal => ErrorCode::validation(1805),
+            Self::ExistingSignatureKeyAddProposal => ErrorCode::validation(1806),
+            Self::ExistingPublicKeyAddProposal => ErrorCode::validation(1807),
+            Self::InitEncryptionKeyCollision => ErrorCode::validation(1808),
+            Self::UpdateProposalIdentityMismatch => ErrorCode::validation(1809),
+            Self::ExistingSignatureKeyUpdateProposal => ErrorCode::validation(1810),
+            Self::ExistingPublicKeyUpdateProposal => ErrorCode::validation(1811),
+            Self::DuplicateMemberRemoval => ErrorCode::validation(1812),
+            Self::UnknownMemberRemoval => ErrorCode::validation(1813),
+            Self::UpdateFromNonMember => ErrorCode::validation(1814),
+            Self::CommitterIncludedOwnUpdate => ErrorCode::validation(1815),
+            Self::InsufficientCapabilities => ErrorCode::validation(1816),
+            Self::UnsupportedMediaTypes => ErrorCode::validation(1817),
+            Self::InsufficientRole => ErrorCode::validation(1818),
+            Self::UpdatePathIdentityMismatch => ErrorCode::validation(1819),
+            Self::MultipleGroupContextExtensionsProposals => ErrorCode::validation(1820),
+            Self::Psk(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ExternalCommitValidationError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoExternalInitProposals => ErrorCode::validation(1901),
+            Self::MultipleExternalInitProposals => ErrorCode::validation(1902),
+            Self::InvalidInlineProposals => ErrorCode::validation(1903),
+            Self::MultipleRemoveProposals => ErrorCode::validation(1904),
+            Self::InvalidRemoveProposal => ErrorCode::validation(1905),
+            Self::NoPath => ErrorCode::validation(1906),
+            Self::UnknownMemberRemoval => ErrorCode::validation(1907),
+            Self::ReferencedProposal => ErrorCode::validation(1908),
+            Self::NotAnExternalCommit => ErrorCode::validation(1909),
+            Self::GroupInfoMismatch => ErrorCode::validation(1910),
+            Self::MissingExternalPub => ErrorCode::validation(1911),
+        }
+    }
+}
+
+impl HasErrorCode for CreateAddProposalError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::LeafNodeValidation(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for CreateRemoveProposalError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::UnknownMember => ErrorCode::validation(7901),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for CreateGroupContextExtProposalError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::KeyPackageExtensionSupport(e) => e.error_code(),
+            Self::Extension(e) => e.error_code(),
+            Self::LeafNodeValidation(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for MergeCommitError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::KeyStoreError(_) => ErrorCode::storage(2201),
+            Self::WrongEpoch => ErrorCode::validation(2202),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for MlsClientError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingGroup => ErrorCode::validation(2301),
+            Self::DuplicateGroup => ErrorCode::validation(2302),
+            Self::FutureEpoch => ErrorCode::validation(2303),
+            Self::ClientStateNotFound => ErrorCode::validation(2304),
+            Self::GroupStateNotFound => ErrorCode::validation(2305),
+            Self::ProcessMessageError(e) => e.error_code(),
+            Self::WelcomeError(e) => e.error_code(),
+            Self::NewGroupError(e) => e.error_code(),
+            Self::KeyPackageNewError(e) => e.error_code(),
+            Self::KeyStoreError(_) => ErrorCode::storage(2306),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for NewGroupError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingKeyPackage => ErrorCode::validation(2401),
+            Self::NoMatchingCredentialBundle => ErrorCode::validation(2402),
+            Self::KeyStoreError(_) => ErrorCode::storage(2403),
+            Self::UnsupportedProposalType => ErrorCode::validation(2404),
+            Self::UnsupportedExtensionType => ErrorCode::validation(2405),
+            Self::UnsupportedMediaTypes => ErrorCode::validation(2406),
+            Self::InvalidExtensions(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for EmptyInputError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::AddMembers => ErrorCode::validation(2501),
+            Self::RemoveMembers => ErrorCode::validation(2502),
+        }
+    }
+}
+
+impl HasErrorCode for MlsGroupStateError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::UseAfterEviction => ErrorCode::validation(2601),
+            Self::PendingProposal => ErrorCode::validation(2602),
+            Self::PendingCommit => ErrorCode::validation(2603),
+            Self::NoPendingCommit => ErrorCode::validation(2604),
+            Self::PendingProposalNotFound => ErrorCode::validation(2605),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for MergePendingCommitError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::MlsGroupStateError(e) => e.error_code(),
+            Self::MergeCommitError(e) => e.error_code(),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for RollbackPendingCommitError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::GroupStateError(e) => e.error_code(),
+            Self::KeyStoreError(_) => ErrorCode::storage(2801),
+        }
+    }
+}
+
+impl HasErrorCode for ProcessMessageError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::IncompatibleWireFormat => ErrorCode::validation(2901),
+            Self::ValidationError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::InvalidSignature => ErrorCode::crypto(2902),
+            Self::InvalidCommit(e) => e.error_code(),
+            Self::UnauthorizedExternalApplicationMessage => ErrorCode::validation(2903),
+            Self::UnsupportedProposalType => ErrorCode::validation(2904),
+            Self::InvalidCredential => ErrorCode::validation(2905),
+            Self::RevokedCredential(_) => ErrorCode::validation(2906),
+            Self::LeafNodeValidation(e) => e.error_code(),
+            Self::InvalidAad => ErrorCode::validation(2907),
+            Self::ProposalStoreFull => ErrorCode::validation(2908),
+            Self::InvalidProposal(e) => e.error_code(),
+            Self::Desynced(_) => ErrorCode::validation(2909),
+            Self::InvalidExtension(e) => e.error_code(),
+            Self::MissingCredentialValidator => ErrorCode::validation(2910),
+        }
+    }
+}
+
+impl HasErrorCode for CreateMessageError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingCredentialBundle => ErrorCode::validation(3001),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for CreateFragmentedMessageError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::MaxMessageSizeTooSmall => ErrorCode::validation(3101),
+            Self::TooManyFragments => ErrorCode::validation(3102),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ReassemblyError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::NotAnApplicationMessage => ErrorCode::validation(3201),
+            Self::MalformedFragmentHeader => ErrorCode::validation(3202),
+            Self::InconsistentFragment => ErrorCode::validation(3203),
+            Self::DuplicateFragment => ErrorCode::validation(3204),
+            Self::MemoryLimitExceeded => ErrorCode::validation(3205),
+            Self::TooManyPendingMessages => ErrorCode::validation(3206),
+        }
+    }
+}
+
+impl HasErrorCode for MessageStreamError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::WriteFailed => ErrorCode::validation(3301),
+            Self::MalformedHeader => ErrorCode::validation(3302),
+            Self::WrongGroupId => ErrorCode::validation(3303),
+            Self::WrongEpoch => ErrorCode::validation(3304),
+        }
+    }
+}
+
+impl HasErrorCode for SenderRatchetStateError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::WrongGroupId => ErrorCode::validation(3401),
+            Self::UnknownEpoch => ErrorCode::validation(3402),
+            Self::MalformedState => ErrorCode::validation(3403),
+            Self::SecretTreeError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for CreateTargetedMessageError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::UnknownRecipient => ErrorCode::validation(3501),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ProcessTargetedMessageError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::WrongGroupId => ErrorCode::validation(3601),
+            Self::WrongEpoch => ErrorCode::validation(3602),
+            Self::WrongRecipient => ErrorCode::validation(3603),
+            Self::MissingDecryptionKey => ErrorCode::validation(3604),
+            Self::DecryptionFailed => ErrorCode::crypto(3605),
+            Self::MalformedContent => ErrorCode::validation(3606),
+            Self::UnknownSender => ErrorCode::validation(3607),
+            Self::InvalidSignature => ErrorCode::crypto(3608),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for AddMembersError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingCredentialBundle => ErrorCode::validation(3701),
+            Self::EmptyInput(e) => e.error_code(),
+            Self::CreateCommitError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::InvalidCredential => ErrorCode::validation(3702),
+            Self::MissingCredentialValidator => ErrorCode::validation(3703),
+        }
+    }
+}
+
+impl HasErrorCode for ProposeAddMemberError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingCredentialBundle => ErrorCode::validation(3801),
+            Self::UnsupportedExtensions => ErrorCode::validation(3802),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::LeafNodeValidation(e) => e.error_code(),
+            Self::ProposalValidationError(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ProposeRemoveMemberError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingCredentialBundle => ErrorCode::validation(3901),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::UnknownMember => ErrorCode::validation(3902),
+            Self::AmbiguousIdentity => ErrorCode::validation(3903),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for RemoveMembersError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingCredentialBundle => ErrorCode::validation(4001),
+            Self::EmptyInput(e) => e.error_code(),
+            Self::CreateCommitError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::UnknownMember => ErrorCode::validation(4002),
+        }
+    }
+}
+
+impl HasErrorCode for LeaveGroupError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingCredentialBundle => ErrorCode::validation(4101),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for SelfUpdateError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingCredentialBundle => ErrorCode::validation(4201),
+            Self::CreateCommitError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::KeyStoreError => ErrorCode::storage(4202),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for ProposeSelfUpdateError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingCredentialBundle => ErrorCode::validation(4301),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::KeyStoreError(_) => ErrorCode::storage(4302),
+            Self::PublicTreeError(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for UpdateCapabilitiesError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::KeyStoreError(_) => ErrorCode::storage(4401),
+            Self::PublicTreeError(e) => e.error_code(),
+            Self::UnsupportedCiphersuiteOrVersion => ErrorCode::validation(4402),
+            Self::LeafNodeValidation(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for UpdateLeafExtensionsError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::KeyStoreError(_) => ErrorCode::storage(4501),
+            Self::PublicTreeError(e) => e.error_code(),
+            Self::LeafNodeValidation(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for CommitToPendingProposalsError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingCredentialBundle => ErrorCode::validation(4601),
+            Self::CreateCommitError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ExportGroupInfoError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NoMatchingCredentialBundle => ErrorCode::validation(4701),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::UnsupportedExtensionType(_) => ErrorCode::validation(4702),
+            Self::InvalidExtension(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ExportSecretError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::KeyLengthTooLong => ErrorCode::validation(4801),
+            Self::UnknownEpoch => ErrorCode::validation(4802),
+            Self::GroupStateError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ProposePskError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Psk(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::LibraryError(e) => e.error_code(),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for ProposalError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::ProposeAddMemberError(e) => e.error_code(),
+            Self::CreateAddProposalError(e) => e.error_code(),
+            Self::ProposeSelfUpdateError(e) => e.error_code(),
+            Self::ProposeRemoveMemberError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::ValidationError(e) => e.error_code(),
+            Self::CreateGroupContextExtProposalError(e) => e.error_code(),
+            Self::ProposeExternalSenderError(e) => e.error_code(),
+            Self::SafeExtensionError(e) => e.error_code(),
+            Self::CreateRemoveProposalError(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ProposeExternalSenderError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::DuplicateExternalSender => ErrorCode::validation(5101),
+            Self::UnknownExternalSender => ErrorCode::validation(5102),
+        }
+    }
+}
+
+impl HasErrorCode for SafeExtensionError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::ExportSecretError(e) => e.error_code(),
+            Self::UnregisteredExtension => ErrorCode::validation(5201),
+        }
+    }
+}
+
+impl HasErrorCode for InvalidConfigError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::ExternalSendersWithCiphertextOnlyPolicy => ErrorCode::validation(5301),
+            Self::MaxPastEpochAgeWithoutPastEpochs => ErrorCode::validation(5302),
+            Self::RequiredMediaTypesNotAccepted => ErrorCode::validation(5303),
+            Self::InvalidPaddingBuckets => ErrorCode::validation(5304),
+        }
+    }
+}
+
+impl HasErrorCode for CreationFromExternalError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::TreeSyncError(e) => e.error_code(),
+            Self::UnknownSender => ErrorCode::validation(5401),
+            Self::InvalidGroupInfoSignature => ErrorCode::crypto(5402),
+            Self::TreeHashMismatch => ErrorCode::validation(5403),
+            Self::UnsupportedMlsVersion => ErrorCode::validation(5404),
+        }
+    }
+}
+
+impl HasErrorCode for PublicGroupBuildError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::UnsupportedProposalType => ErrorCode::validation(5501),
+            Self::UnsupportedExtensionType => ErrorCode::validation(5502),
+            Self::UnsupportedMediaTypes => ErrorCode::validation(5503),
+            Self::InvalidExtensions(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for SnapshotImportError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::TreeSyncError(e) => e.error_code(),
+            Self::DigestMismatch => ErrorCode::validation(5601),
+            Self::TreeHashMismatch => ErrorCode::validation(5602),
+        }
+    }
+}
+
+impl HasErrorCode for KeyPackageVerifyError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::InvalidLifetime => ErrorCode::validation(5701),
+            Self::MissingLifetime => ErrorCode::validation(5702),
+            Self::UnsupportedExtension => ErrorCode::validation(5703),
+            Self::InvalidSignature => ErrorCode::crypto(5704),
+            Self::InvalidLeafNodeSignature => ErrorCode::crypto(5705),
+            Self::InvalidLeafNodeSourceType => ErrorCode::validation(5706),
+        }
+    }
+}
+
+impl HasErrorCode for KeyPackageExtensionSupportError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::UnsupportedExtension => ErrorCode::validation(5801),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for KeyPackageNewError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::CiphersuiteSignatureSchemeMismatch => ErrorCode::validation(5901),
+            Self::KeyStoreError(_) => ErrorCode::storage(5902),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for GroupInfoError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::DecryptionFailed => ErrorCode::crypto(6001),
+            Self::Malformed => ErrorCode::validation(6002),
+        }
+    }
+}
+
+impl HasErrorCode for GroupSecretsError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::DecryptionFailed => ErrorCode::crypto(6101),
+            Self::Malformed => ErrorCode::validation(6102),
+        }
+    }
+}
+
+impl HasErrorCode for PskError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::TooManyKeys => ErrorCode::validation(6201),
+            Self::KeyNotFound => ErrorCode::validation(6202),
+            Self::KeyStore => ErrorCode::storage(6203),
+            Self::TypeMismatch { .. } => ErrorCode::validation(6204),
+            Self::UsageMismatch { .. } => ErrorCode::validation(6205),
+            Self::NonceLengthMismatch { .. } => ErrorCode::validation(6206),
+            Self::Duplicate { .. } => ErrorCode::validation(6207),
+        }
+    }
+}
+
+impl HasErrorCode for SecretTreeError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::TooDistantInThePast => ErrorCode::validation(6301),
+            Self::TooDistantInTheFuture => ErrorCode::validation(6302),
+            Self::IndexOutOfBounds => ErrorCode::validation(6303),
+            Self::SecretReuseError => ErrorCode::validation(6304),
+            Self::RatchetTypeError => ErrorCode::validation(6305),
+            Self::RatchetTooLong => ErrorCode::validation(6306),
+            Self::LibraryError => ErrorCode::library(6307),
+            Self::CodecError(_) => ErrorCode::library(6308),
+            Self::CryptoError(_) => ErrorCode::crypto(6309),
+        }
+    }
+}
+
+impl HasErrorCode for PublicTreeError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::PublicKeyMismatch => ErrorCode::validation(6401),
+            Self::DuplicateKeyPackage => ErrorCode::validation(6402),
+            Self::MissingKeyPackage => ErrorCode::validation(6403),
+            Self::MalformedTree => ErrorCode::validation(6404),
+            Self::InvalidParentHash => ErrorCode::validation(6405),
+            Self::IdentityMismatch => ErrorCode::validation(6406),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ApplyUpdatePathError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::PathLengthMismatch => ErrorCode::validation(6501),
+            Self::PathMismatch => ErrorCode::validation(6502),
+            Self::ParentHashMismatch => ErrorCode::validation(6503),
+            Self::MissingParentHash => ErrorCode::validation(6504),
+            Self::UnableToDecrypt => ErrorCode::crypto(6505),
+            Self::MissingSender => ErrorCode::validation(6506),
+            Self::TreeFull => ErrorCode::validation(6507),
+            Self::InconsistentSenderIndex => ErrorCode::validation(6508),
+        }
+    }
+}
+
+impl HasErrorCode for TreeSyncFromNodesError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::PublicTreeError(e) => e.error_code(),
+            Self::RatchetTreeError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for RatchetTreeVerificationError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::RatchetTreeError(e) => e.error_code(),
+            Self::TreeSyncFromNodesError(e) => e.error_code(),
+            Self::DuplicateSignatureKey => ErrorCode::validation(6701),
+            Self::DuplicateEncryptionKey => ErrorCode::validation(6702),
+            Self::UnknownSigner => ErrorCode::validation(6703),
+            Self::InvalidGroupInfoSignature => ErrorCode::crypto(6704),
+            Self::TreeHashMismatch => ErrorCode::validation(6705),
+        }
+    }
+}
+
+impl HasErrorCode for LeafNodeValidationError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Lifetime(e) => e.error_code(),
+            Self::UnsupportedExtensions => ErrorCode::validation(6801),
+            Self::UnsupportedProposals => ErrorCode::validation(6802),
+            Self::UnsupportedCredentials => ErrorCode::validation(6803),
+            Self::CredentialNotInCapabilities => ErrorCode::validation(6804),
+            Self::ExtensionsNotInCapabilities => ErrorCode::validation(6805),
+            Self::SignatureKeyAlreadyInUse => ErrorCode::validation(6806),
+            Self::EncryptionKeyAlreadyInUse => ErrorCode::validation(6807),
+            Self::InvalidLeafNodeSource => ErrorCode::validation(6808),
+            Self::LeafNodeCredentialNotSupportedByMember => ErrorCode::validation(6809),
+            Self::MemberCredentialNotSupportedByLeafNode => ErrorCode::validation(6810),
+        }
+    }
+}
+
+impl HasErrorCode for LifetimeError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::RangeTooBig => ErrorCode::validation(6901),
+            Self::NotCurrent => ErrorCode::validation(6902),
+        }
+    }
+}
+
+impl HasErrorCode for UpdatePathError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidType => ErrorCode::validation(7001),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for RatchetTreeDeltaError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::TreeTooLarge => ErrorCode::validation(7101),
+            Self::InvalidNodeIndex => ErrorCode::validation(7102),
+        }
+    }
+}
+
+impl HasErrorCode for RatchetTreeError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::MissingNodes => ErrorCode::validation(7201),
+            Self::TrailingBlankNodes => ErrorCode::validation(7202),
+            Self::InvalidNodeSignature => ErrorCode::crypto(7203),
+            Self::WrongNodeType => ErrorCode::validation(7204),
+        }
+    }
+}
+
+impl HasErrorCode for VersionError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::UnsupportedMlsVersion => ErrorCode::validation(7301),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for KeyPackageDeleteError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::KeyStoreError(_) => ErrorCode::storage(7401),
+        }
+    }
+}
+
+impl HasErrorCode for ExportCommitAuditLogError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::AuditLogDisabled => ErrorCode::validation(7501),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl<KeyStoreError> HasErrorCode for GroupStateTransferError<KeyStoreError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::CiphersuiteMismatch => ErrorCode::validation(7601),
+            Self::KeyPackageMismatch => ErrorCode::validation(7602),
+            Self::NoMatchingKeyPackage => ErrorCode::validation(7603),
+            Self::DecryptionFailed => ErrorCode::crypto(7604),
+            Self::MalformedContent => ErrorCode::validation(7605),
+            Self::KeyStoreError(_) => ErrorCode::storage(7606),
+            Self::InvalidSignature => ErrorCode::crypto(7607),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for ComputeProposalRefError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::NotAProposal => ErrorCode::validation(7701),
+        }
+    }
+}
+
+impl HasErrorCode for VerifyMembershipTokenError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::UnknownEpoch => ErrorCode::validation(7801),
+            Self::UnknownMember => ErrorCode::validation(7802),
+            Self::InvalidToken => ErrorCode::crypto(7803),
+            Self::InvalidSignature => ErrorCode::crypto(7804),
+        }
+    }
+}
+
+impl HasErrorCode for CreateMembershipTokenError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::LibraryError(e) => e.error_code(),
+            Self::GroupStateError(e) => e.error_code(),
+            Self::SignatureError(e) => e.error_code(),
+        }
+    }
+}
+
+impl HasErrorCode for AddProposalError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::ProposalStoreFull => ErrorCode::validation(8101),
+        }
+    }
+}
+
+impl HasErrorCode for CryptoProviderError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::UnknownCryptoProvider(_) => ErrorCode::validation(8201),
+            Self::ProviderTypeMismatch => ErrorCode::validation(8202),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::error::{ErrorCategory, LibraryError};
+
+    #[test]
+    fn error_codes() {
+        assert_eq!(
+            LibraryError::custom("test").error_code(),
+            ErrorCode::library(1)
+        );
+
+        // Wrapped errors return the code of the wrapped error.
+        let error = ProcessMessageError::InvalidProposal(ProposalValidationError::InsufficientRole);
+        assert_eq!(error.code(), 1818);
+        assert_eq!(error.category(), ErrorCategory::Validation);
+        let error = ProcessMessageError::ValidationError(ValidationError::InvalidSignature);
+        assert_eq!(error.code(), ValidationError::InvalidSignature.code());
+        assert_eq!(error.category(), ErrorCategory::Crypto);
+
+        let error = WelcomeError::KeyStoreError(());
+        assert_eq!(error.code(), 1316);
+        assert_eq!(error.category(), ErrorCategory::Storage);
+    }
+
+    // Returns the codes of all `HasErrorCode` implementations in the given
+    // source, grouped by the type they are implemented for.
+    fn codes_by_type(source: &str) -> Vec<(String, Vec<u32>)> {
+        let mut codes_by_type: Vec<(String, Vec<u32>)> = Vec::new();
+        for line in source.lines() {
+            if let Some((_, ty)) = line.split_once("HasErrorCode for ") {
+                let ty = ty
+                    .split(|c: char| !c.is_alphanumeric() && c != '_')
+                    .next()
+                    .unwrap();
+                codes_by_type.push((ty.to_string(), Vec::new()));
+            }
+            let codes = line.split("ErrorCode::").skip(1).filter_map(|call| {
+                let (_, code) = call.split_once('(')?;
+                let (code, _) = code.split_once(')')?;
+                code.parse::<u32>().ok()
+            });
+            for code in codes {
+                codes_by_type
+                    .last_mut()
+                    .expect("Error code outside of an implementation.")
+                    .1
+                    .push(code);
+            }
+        }
+        codes_by_type
+    }
+
+    #[test]
+    fn unique_error_codes() {
+        let source = include_str!("codes.rs");
+        let source = &source[..source.find("#[cfg(test)]").unwrap()];
+        let mut codes_by_type = codes_by_type(source);
+        // The codes of the `LibraryError` are assigned in the error module.
+        let library_source = include_str!("../error.rs");
+        let library_codes = codes_by_type(
+            &library_source[library_source
+                .find("impl HasErrorCode for LibraryError")
+                .unwrap()..],
+        )
+        .remove(0);
+        assert_eq!(library_codes.1, [1, 2, 3]);
+        codes_by_type.push(library_codes);
+
+        let mut codes = HashMap::new();
+        let mut blocks = HashMap::new();
+        for (ty, type_codes) in &codes_by_type {
+            assert!(!type_codes.is_empty(), "{ty} has no error codes.");
+            for code in type_codes {
+                if let Some(other) = codes.insert(*code, ty) {
+                    panic!("The error code {code} is used by {ty} and {other}.");
+                }
+            }
+
+            // All codes of an error enum are in its own block.
+            let block = type_codes[0] / 100;
+            assert!(
+                type_codes.iter().all(|code| code / 100 == block),
+                "The error codes of {ty} are not in a single block."
+            );
+            if let Some(other) = blocks.insert(block, ty) {
+                panic!("The error code block {block} is used by {ty} and {other}.");
+            }
+        }
+        assert!(codes.len() > 300);
+    }
+
+    #[test]
+    fn stable_error_codes() {
+        // Codes must never change. If this test fails, a code was changed
+        // instead of a new one being assigned.
+        let codes = [
+            (LibraryError::custom("test").error_code(), 1),
+            (SignatureError::VerificationError.error_code(), 101),
+            (CredentialError::UnsupportedCredentialType.error_code(), 201),
+            (ExtensionError::UnsupportedProposalType.error_code(), 301),
+            (
+                WelcomeError::<()>::PrivateInitKeyNotFound.error_code(),
+                1301,
+            ),
+            (
+                WelcomeError::<()>::ConfirmationTagMismatch.error_code(),
+                1305,
+            ),
+            (ProcessMessageError::InvalidAad.error_code(), 2907),
+            (
+                ProcessMessageError::MissingCredentialValidator.error_code(),
+                2910,
+            ),
+            (AddMembersError::<()>::InvalidCredential.error_code(), 3702),
+            (BasicIdentityError::NotBasicCredential.error_code(), 8001),
+            (
+                CryptoProviderError::UnknownCryptoProvider("fips".into()).error_code(),
+                8201,
+            ),
+        ];
+        for (error_code, code) in codes {
+            assert_eq!(error_code.code(), code);
+        }
+        assert_eq!(
+            WelcomeError::<()>::ConfirmationTagMismatch.category(),
+            ErrorCategory::Crypto
+        );
+    }
+}
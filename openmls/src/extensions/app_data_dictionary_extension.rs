@@ -0,0 +1,153 @@
+use std::io::Read;
+
+use tls_codec::{Deserialize as _, Serialize as _, TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+
+use super::{CustomExtension, Deserialize, Serialize};
+
+/// # Component Data
+///
+/// The state of a single safe extension component in an
+/// [`AppDataDictionaryExtension`].
+///
+/// ```c
+/// // draft-ietf-mls-extensions-03
+/// struct {
+///     ComponentID component_id;
+///     opaque data<V>;
+/// } ComponentData;
+/// ```
+#[derive(
+    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ComponentData {
+    component_id: u16,
+    data: VLBytes,
+}
+
+impl ComponentData {
+    /// Returns the component ID.
+    pub fn component_id(&self) -> u16 {
+        self.component_id
+    }
+
+    /// Returns the data of the component.
+    pub fn data(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+}
+
+/// # App Data Dictionary Extension
+///
+/// Group context extension that stores the state of safe extension
+/// components. Every component has at most one entry, and the entries are
+/// sorted by component ID.
+///
+/// Components are identified by the extension type of the respective
+/// [`CustomExtension`].
+///
+/// This extension uses the private use extension type `0xff04` and is
+/// implemented as [`CustomExtension`] itself.
+///
+/// ```c
+/// // draft-ietf-mls-extensions-03
+/// struct {
+///     ComponentData component_data<V>;
+/// } AppDataDictionary;
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct AppDataDictionaryExtension {
+    component_data: Vec<ComponentData>,
+}
+
+impl AppDataDictionaryExtension {
+    /// Create an empty dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the entries of the dictionary in ascending order of their
+    /// component ID.
+    pub fn component_data(&self) -> &[ComponentData] {
+        &self.component_data
+    }
+
+    /// Returns `true` if the dictionary has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.component_data.is_empty()
+    }
+
+    /// Returns the data of the component with the given `component_id`, if
+    /// any.
+    pub fn get(&self, component_id: u16) -> Option<&[u8]> {
+        self.position(component_id)
+            .ok()
+            .map(|index| self.component_data[index].data())
+    }
+
+    /// Sets the data of the component with the given `component_id`.
+    ///
+    /// Returns the previous data of the component, if any.
+    pub fn insert(&mut self, component_id: u16, data: Vec<u8>) -> Option<Vec<u8>> {
+        let data = data.into();
+        match self.position(component_id) {
+            Ok(index) => Some(std::mem::replace(&mut self.component_data[index].data, data).into()),
+            Err(index) => {
+                self.component_data
+                    .insert(index, ComponentData { component_id, data });
+                None
+            }
+        }
+    }
+
+    /// Removes the component with the given `component_id` from the
+    /// dictionary.
+    ///
+    /// Returns the data of the removed component, if any.
+    pub fn remove(&mut self, component_id: u16) -> Option<Vec<u8>> {
+        self.position(component_id)
+            .ok()
+            .map(|index| self.component_data.remove(index).data.into())
+    }
+
+    fn position(&self, component_id: u16) -> Result<usize, usize> {
+        self.component_data
+            .binary_search_by_key(&component_id, |entry| entry.component_id)
+    }
+}
+
+impl tls_codec::Deserialize for AppDataDictionaryExtension {
+    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, tls_codec::Error>
+    where
+        Self: Sized,
+    {
+        let component_data: Vec<ComponentData> = Vec::tls_deserialize(bytes)?;
+
+        // The entries must be unique and sorted by component ID.
+        if component_data
+            .windows(2)
+            .any(|entries| entries[0].component_id >= entries[1].component_id)
+        {
+            return Err(tls_codec::Error::DecodingError(
+                "App data dictionary entries are not sorted or not unique".into(),
+            ));
+        }
+
+        Ok(Self { component_data })
+    }
+}
+
+impl CustomExtension for AppDataDictionaryExtension {
+    const EXTENSION_TYPE: u16 = 0xff04;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        // Serializing can only fail if the extension is too large for the
+        // length encoding.
+        self.tls_serialize_detached().unwrap_or_default()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::tls_deserialize_exact(bytes).ok()
+    }
+}
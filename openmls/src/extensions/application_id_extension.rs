@@ -10,6 +10,7 @@ use super::{Deserialize, Serialize};
 #[derive(
     PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ApplicationIdExtension {
     key_id: VLBytes,
 }
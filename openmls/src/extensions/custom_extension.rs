@@ -0,0 +1,157 @@
+//! # Custom extensions
+//!
+//! Applications can define their own extension types by implementing
+//! [`CustomExtension`]. Custom extensions are transported as
+//! [`Extension::Unknown`] and are converted with the codec callbacks of the
+//! respective [`CustomExtension`].
+//!
+//! Registering custom extensions with a [`CustomExtensionRegistry`] that is
+//! set in the [`MlsGroupConfig`](crate::group::MlsGroupConfig) makes OpenMLS
+//! advertise the extension types in the capabilities of the own leaf node and
+//! accept them in the required capabilities of a group.
+
+
+use std::collections::HashMap;
+
+use super::{
+    AppDataDictionaryExtension, Extension, ExtensionError, ExtensionType, Extensions,
+    UnknownExtension,
+};
+
+/// An application-defined extension.
+///
+/// The extension type must not be one of the extension types that are
+/// defined by MLS, i.e., it must be parsed as [`ExtensionType::Unknown`].
+pub trait CustomExtension: Sized {
+    /// The extension type.
+    const EXTENSION_TYPE: u16;
+
+    /// Serializes the extension data.
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Deserializes the extension data. Returns `None` if the `bytes` are not
+    /// a valid encoding of the extension.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+impl Extension {
+    /// Creates an [`Extension`] from the given [`CustomExtension`].
+    pub fn from_custom<C: CustomExtension>(extension: &C) -> Self {
+        Extension::Unknown(C::EXTENSION_TYPE, UnknownExtension(extension.to_bytes()))
+    }
+
+    /// Converts this extension into the [`CustomExtension`] `C`.
+    ///
+    /// Returns an [`ExtensionError::InvalidExtensionType`] if this extension
+    /// is not of the extension type of `C` and an
+    /// [`ExtensionError::InvalidCustomExtension`] if the extension data can't
+    /// be deserialized.
+    pub fn as_custom<C: CustomExtension>(&self) -> Result<C, ExtensionError> {
+        match self {
+            Extension::Unknown(extension_type, data) if *extension_type == C::EXTENSION_TYPE => {
+                C::from_bytes(&data.0).ok_or(ExtensionError::InvalidCustomExtension)
+            }
+            _ => Err(ExtensionError::InvalidExtensionType(
+                "This is not the requested custom extension".into(),
+            )),
+        }
+    }
+}
+
+impl Extensions {
+    /// Returns the [`CustomExtension`] `C` if there is any.
+    ///
+    /// Returns `None` if there is no extension of the extension type of `C` or
+    /// if the extension data can't be deserialized.
+    pub fn custom<C: CustomExtension>(&self) -> Option<C> {
+        self.iter()
+            .find(|extension| extension.extension_type() == ExtensionType::from(C::EXTENSION_TYPE))
+            .and_then(|extension| extension.as_custom().ok())
+    }
+}
+
+/// A registry of [`CustomExtension`] types.
+#[derive(Debug, Default, Clone)]
+pub struct CustomExtensionRegistry {
+    decoders: HashMap<u16, fn(&[u8]) -> bool>,
+}
+
+impl CustomExtensionRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the custom extension type `C`.
+    ///
+    /// Returns an error if the extension type of `C` is defined by MLS.
+    pub fn register<C: CustomExtension>(mut self) -> Result<Self, ExtensionError> {
+        if !matches!(
+            ExtensionType::from(C::EXTENSION_TYPE),
+            ExtensionType::Unknown(_)
+        ) {
+            return Err(ExtensionError::InvalidExtensionType(
+                "Custom extensions can't use extension types defined by MLS".into(),
+            ));
+        }
+        self.decoders
+            .insert(C::EXTENSION_TYPE, |bytes: &[u8]| -> bool {
+                C::from_bytes(bytes).is_some()
+            });
+        Ok(self)
+    }
+
+    /// Returns `true` if the extension type is registered.
+    pub fn is_registered(&self, extension_type: ExtensionType) -> bool {
+        self.decoders.contains_key(&u16::from(extension_type))
+    }
+
+    /// Returns the registered extension types in ascending order.
+    pub fn extension_types(&self) -> Vec<ExtensionType> {
+        let mut extension_types: Vec<ExtensionType> = self
+            .decoders
+            .keys()
+            .map(|extension_type| ExtensionType::from(*extension_type))
+            .collect();
+        extension_types.sort();
+        extension_types
+    }
+
+    /// Checks that all extensions of a registered extension type in
+    /// `extensions` can be deserialized. This includes the state of registered
+    /// extensions in an [`AppDataDictionaryExtension`], which must be
+    /// deserializable itself.
+    ///
+    /// Returns an [`ExtensionError::InvalidCustomExtension`] otherwise.
+    pub fn validate(&self, extensions: &Extensions) -> Result<(), ExtensionError> {
+        for extension in extensions.iter() {
+            let Extension::Unknown(extension_type, data) = extension else {
+                continue;
+            };
+            self.validate_data(*extension_type, &data.0)?;
+            if *extension_type == AppDataDictionaryExtension::EXTENSION_TYPE {
+                let dictionary = AppDataDictionaryExtension::from_bytes(&data.0)
+                    .ok_or(ExtensionError::InvalidCustomExtension)?;
+                for entry in dictionary.component_data() {
+                    self.validate_data(entry.component_id(), entry.data())?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_data(&self, extension_type: u16, data: &[u8]) -> Result<(), ExtensionError> {
+        match self.decoders.get(&extension_type) {
+            Some(decode) if !decode(data) => Err(ExtensionError::InvalidCustomExtension),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl PartialEq for CustomExtensionRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.extension_types() == other.extension_types()
+    }
+}
+
+impl Eq for CustomExtensionRegistry {}
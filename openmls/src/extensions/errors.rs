@@ -43,6 +43,9 @@ pub enum ExtensionError {
     /// See [`InvalidExtensionError`] for more details.
     #[error(transparent)]
     InvalidExtension(#[from] InvalidExtensionError),
+    /// The application-defined extension could not be deserialized.
+    #[error("Invalid custom extension.")]
+    InvalidCustomExtension,
 }
 
 /// Capabilities extension error
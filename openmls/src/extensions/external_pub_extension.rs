@@ -4,7 +4,7 @@ use super::{Deserialize, Serialize};
 use crate::ciphersuite::HpkePublicKey;
 
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// struct {
 ///     HPKEPublicKey external_pub;
 /// } ExternalPub;
@@ -12,6 +12,7 @@ use crate::ciphersuite::HpkePublicKey;
 #[derive(
     PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ExternalPubExtension {
     external_pub: HpkePublicKey,
 }
@@ -6,7 +6,7 @@ use crate::{ciphersuite::SignaturePublicKey, credentials::Credential};
 /// ExternalSender
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// struct {
 ///   SignaturePublicKey signature_key;
 ///   Credential credential;
@@ -15,6 +15,7 @@ use crate::{ciphersuite::SignaturePublicKey, credentials::Credential};
 #[derive(
     Clone, PartialEq, Eq, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ExternalSender {
     signature_key: SignaturePublicKey,
     credential: Credential,
@@ -29,11 +30,13 @@ impl ExternalSender {
         }
     }
 
-    pub(crate) fn credential(&self) -> &Credential {
+    /// Returns the credential of this external sender.
+    pub fn credential(&self) -> &Credential {
         &self.credential
     }
 
-    pub(crate) fn signature_key(&self) -> &SignaturePublicKey {
+    /// Returns the signature key of this external sender.
+    pub fn signature_key(&self) -> &SignaturePublicKey {
         &self.signature_key
     }
 }
@@ -41,7 +44,7 @@ impl ExternalSender {
 /// ExternalSender (extension data)
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// ExternalSender external_senders<V>;
 /// ```
 pub type ExternalSendersExtension = Vec<ExternalSender>;
@@ -49,6 +52,7 @@ pub type ExternalSendersExtension = Vec<ExternalSender>;
 #[derive(
     Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SenderExtensionIndex(u32);
 
 impl SenderExtensionIndex {
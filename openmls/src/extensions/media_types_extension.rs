@@ -0,0 +1,166 @@
+use std::ops::Deref;
+
+use tls_codec::{Deserialize as _, Serialize as _, TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+
+use super::{CustomExtension, Deserialize, Serialize};
+
+/// # Media Type
+///
+/// A media type (e.g. `text/markdown;charset=utf-8`) as defined in the content
+/// advertisement section of the MLS extensions draft. Media types are
+/// compared byte-wise.
+///
+/// ```c
+/// // draft-ietf-mls-extensions-01
+/// struct {
+///     opaque media_type<V>;
+/// } MediaType;
+/// ```
+#[derive(
+    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MediaType {
+    media_type: VLBytes,
+}
+
+impl MediaType {
+    /// Create a new media type from a byte slice.
+    pub fn new(media_type: &[u8]) -> Self {
+        Self {
+            media_type: media_type.into(),
+        }
+    }
+
+    /// Get the value of the media type as byte slice.
+    pub fn as_slice(&self) -> &[u8] {
+        self.media_type.as_slice()
+    }
+}
+
+impl From<&str> for MediaType {
+    fn from(media_type: &str) -> Self {
+        Self::new(media_type.as_bytes())
+    }
+}
+
+/// # Media Type List
+///
+/// A list of [`MediaType`]s. It is used as
+/// [`AcceptedMediaTypesExtension`] in leaf nodes to advertise the media types
+/// a client can process and as [`RequiredMediaTypesExtension`] in the group
+/// context to require media types from all members of a group.
+///
+/// ```c
+/// // draft-ietf-mls-extensions-01
+/// struct {
+///     MediaType media_types<V>;
+/// } MediaTypeList;
+///
+/// MediaTypeList accepted_media_types;
+/// MediaTypeList required_media_types;
+/// ```
+#[derive(
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    TlsSerialize,
+    TlsDeserialize,
+    TlsSize,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MediaTypeList {
+    media_types: Vec<MediaType>,
+}
+
+impl MediaTypeList {
+    /// Create a new media type list.
+    pub fn new(media_types: Vec<MediaType>) -> Self {
+        Self { media_types }
+    }
+
+    /// Get a reference to the list of media types.
+    pub fn media_types(&self) -> &[MediaType] {
+        &self.media_types
+    }
+
+    /// Returns `true` if the `media_type` is in this list.
+    pub fn contains(&self, media_type: &MediaType) -> bool {
+        self.media_types.contains(media_type)
+    }
+
+    /// Returns `true` if all media types of `other` are in this list.
+    pub fn contains_all(&self, other: &MediaTypeList) -> bool {
+        other
+            .media_types()
+            .iter()
+            .all(|media_type| self.contains(media_type))
+    }
+}
+
+macro_rules! media_types_extension {
+    ($(#[$doc:meta])* $name:ident, $extension_type:literal) => {
+        $(#[$doc])*
+        #[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize)]
+        #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+        pub struct $name(MediaTypeList);
+
+        impl $name {
+            /// Create a new extension from a media type list.
+            pub fn new(media_types: MediaTypeList) -> Self {
+                Self(media_types)
+            }
+        }
+
+        impl From<MediaTypeList> for $name {
+            fn from(media_types: MediaTypeList) -> Self {
+                Self::new(media_types)
+            }
+        }
+
+        impl Deref for $name {
+            type Target = MediaTypeList;
+
+            fn deref(&self) -> &MediaTypeList {
+                &self.0
+            }
+        }
+
+        impl CustomExtension for $name {
+            const EXTENSION_TYPE: u16 = $extension_type;
+
+            fn to_bytes(&self) -> Vec<u8> {
+                // Serializing a list of byte vectors can only fail if it is too
+                // large for the length encoding.
+                self.0.tls_serialize_detached().unwrap_or_default()
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                MediaTypeList::tls_deserialize_exact(bytes).ok().map(Self)
+            }
+        }
+    };
+}
+
+media_types_extension!(
+    /// Leaf node extension that lists the media types a client can process.
+    ///
+    /// This extension uses the private use extension type `0xff02` and is
+    /// implemented as [`CustomExtension`].
+    AcceptedMediaTypesExtension,
+    0xff02
+);
+
+media_types_extension!(
+    /// Group context extension that lists the media types all members of the
+    /// group must be able to process.
+    ///
+    /// This extension uses the private use extension type `0xff03` and is
+    /// implemented as [`CustomExtension`].
+    RequiredMediaTypesExtension,
+    0xff03
+);
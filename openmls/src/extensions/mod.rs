@@ -18,6 +18,19 @@
 //! - [`RatchetTreeExtension`] (GroupInfo extension)
 //! - [`RequiredCapabilitiesExtension`] (GroupContext extension)
 //! - [`ExternalPubExtension`] (GroupInfo extension)
+//! - [`ExternalSendersExtension`] (GroupContext extension)
+//!
+//! Additionally, OpenMLS implements the following extensions as
+//! [`CustomExtension`]s with extension types from the private use range:
+//!
+//! - [`AcceptedMediaTypesExtension`] (LeafNode extension)
+//! - [`RequiredMediaTypesExtension`] (GroupContext extension)
+//! - [`AppDataDictionaryExtension`] (GroupContext extension)
+//! - [`RolesExtension`] (GroupContext extension)
+//!
+//! Applications can define their own extensions with [`CustomExtension`].
+//! The state of custom extensions can be kept in the group context using the
+//! [`AppDataDictionaryExtension`].
 
 use std::{
     fmt::Debug,
@@ -27,45 +40,65 @@ use std::{
 use serde::{Deserialize, Serialize};
 
 // Private
+mod app_data_dictionary_extension;
 mod application_id_extension;
 mod codec;
+mod custom_extension;
 mod external_pub_extension;
 mod external_sender_extension;
+mod media_types_extension;
 mod ratchet_tree_extension;
 mod required_capabilities;
+mod roles_extension;
 use errors::*;
 
 // Public
 pub mod errors;
 
 // Public re-exports
+pub use app_data_dictionary_extension::{AppDataDictionaryExtension, ComponentData};
 pub use application_id_extension::ApplicationIdExtension;
+pub use custom_extension::{CustomExtension, CustomExtensionRegistry};
 pub use external_pub_extension::ExternalPubExtension;
 pub use external_sender_extension::{
     ExternalSender, ExternalSendersExtension, SenderExtensionIndex,
 };
+pub use media_types_extension::{
+    AcceptedMediaTypesExtension, MediaType, MediaTypeList, RequiredMediaTypesExtension,
+};
 pub use ratchet_tree_extension::RatchetTreeExtension;
-pub use required_capabilities::RequiredCapabilitiesExtension;
+pub use required_capabilities::{
+    RequiredCapabilitiesExtension, RequiredCapabilitiesExtensionBuilder,
+};
+pub use roles_extension::{Role, RoleAssignment, RolesExtension};
 
 #[cfg(test)]
 mod test_extensions;
 
 /// MLS Extension Types
 ///
-/// Copied from draft-ietf-mls-protocol-16:
+/// Copied from RFC 9420:
 ///
 /// | Value            | Name                     | Message(s) | Recommended | Reference |
 /// |:-----------------|:-------------------------|:-----------|:------------|:----------|
-/// | 0x0000           | RESERVED                 | N/A        | N/A         | RFC XXXX  |
-/// | 0x0001           | application_id           | LN         | Y           | RFC XXXX  |
-/// | 0x0002           | ratchet_tree             | GI         | Y           | RFC XXXX  |
-/// | 0x0003           | required_capabilities    | GC         | Y           | RFC XXXX  |
-/// | 0x0004           | external_pub             | GI         | Y           | RFC XXXX  |
-/// | 0x0005           | external_senders         | GC         | Y           | RFC XXXX  |
-/// | 0xff00  - 0xffff | Reserved for Private Use | N/A        | N/A         | RFC XXXX  |
+/// | 0x0000           | RESERVED                 | N/A        | N/A         | RFC 9420  |
+/// | 0x0001           | application_id           | LN         | Y           | RFC 9420  |
+/// | 0x0002           | ratchet_tree             | GI         | Y           | RFC 9420  |
+/// | 0x0003           | required_capabilities    | GC         | Y           | RFC 9420  |
+/// | 0x0004           | external_pub             | GI         | Y           | RFC 9420  |
+/// | 0x0005           | external_senders         | GC         | Y           | RFC 9420  |
+/// | 0xF000  - 0xFFFF | Reserved for Private Use | N/A        | N/A         | RFC 9420  |
+///
+/// Extension types from the private use range are never parsed as one of the
+/// extension types defined by MLS. The extensions of draft-ietf-mls-extensions
+/// and the [`RolesExtension`] of OpenMLS are [`CustomExtension`]s with
+/// extension types from the private use range and are parsed as
+/// [`ExtensionType::Unknown`]. See [`CustomExtension::EXTENSION_TYPE`] for
+/// their extension types.
 ///
 /// Note: OpenMLS does not provide a `Reserved` variant in [ExtensionType].
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ExtensionType {
     /// The application id extension allows applications to add an explicit,
     /// application-defined identifier to a KeyPackage.
@@ -155,6 +188,22 @@ impl ExtensionType {
                 | ExtensionType::ExternalSenders
         )
     }
+
+    /// Check whether an [`ExtensionType`] is a default extension type as
+    /// defined in Section 7.2 of RFC 9420. Default extension types are
+    /// supported by all clients and don't need to be listed in the
+    /// [`Capabilities`](crate::treesync::node::leaf_node::Capabilities) of a
+    /// leaf node.
+    pub fn is_default(&self) -> bool {
+        matches!(
+            self,
+            ExtensionType::ApplicationId
+                | ExtensionType::RatchetTree
+                | ExtensionType::RequiredCapabilities
+                | ExtensionType::ExternalPub
+                | ExtensionType::ExternalSenders
+        )
+    }
 }
 
 /// # Extension
@@ -165,13 +214,14 @@ impl ExtensionType {
 /// See the individual extensions for more details on each extension.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// struct {
 ///     ExtensionType extension_type;
 ///     opaque extension_data<V>;
 /// } Extension;
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Extension {
     /// An [`ApplicationIdExtension`]
     ApplicationId(ApplicationIdExtension),
@@ -194,8 +244,16 @@ pub enum Extension {
 
 /// A unknown/unparsed extension represented by raw bytes.
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct UnknownExtension(pub Vec<u8>);
 
+impl UnknownExtension {
+    /// Returns the raw extension data.
+    pub fn data(&self) -> &[u8] {
+        &self.0
+    }
+}
+
 /// A list of extensions with unique extension types.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, tls_codec::TlsSize)]
 pub struct Extensions {
@@ -219,6 +277,18 @@ impl tls_codec::Deserialize for Extensions {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Extensions {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut extensions = Extensions::empty();
+        for extension in u.arbitrary_iter::<Extension>()? {
+            // Extensions with a duplicate extension type are dropped.
+            let _ = extensions.add(extension?);
+        }
+        Ok(extensions)
+    }
+}
+
 impl Extensions {
     /// Create an empty extension list.
     pub fn empty() -> Self {
@@ -361,6 +431,47 @@ impl Extensions {
                 _ => None,
             })
     }
+
+    /// Get the [`AcceptedMediaTypesExtension`] if there is any.
+    ///
+    /// Returns `None` if the extension data can't be deserialized.
+    pub fn accepted_media_types(&self) -> Option<AcceptedMediaTypesExtension> {
+        self.custom()
+    }
+
+    /// Get the [`RequiredMediaTypesExtension`] if there is any.
+    ///
+    /// Returns `None` if the extension data can't be deserialized.
+    pub fn required_media_types(&self) -> Option<RequiredMediaTypesExtension> {
+        self.custom()
+    }
+
+    /// Get the [`AppDataDictionaryExtension`] if there is any.
+    ///
+    /// Returns `None` if the extension data can't be deserialized.
+    pub fn app_data_dictionary(&self) -> Option<AppDataDictionaryExtension> {
+        self.custom()
+    }
+
+    /// Get the [`RolesExtension`] if there is any.
+    ///
+    /// Returns `None` if the extension data can't be deserialized.
+    pub fn roles(&self) -> Option<RolesExtension> {
+        self.custom()
+    }
+
+    /// Get a reference to the [`UnknownExtension`] with the given extension
+    /// type if there is any.
+    ///
+    /// Extensions that are not known to OpenMLS are kept as raw bytes and can
+    /// be read with this function.
+    pub fn unknown(&self, extension_type: u16) -> Option<&UnknownExtension> {
+        self.find_by_type(ExtensionType::Unknown(extension_type))
+            .and_then(|e| match e {
+                Extension::Unknown(_, e) => Some(e),
+                _ => None,
+            })
+    }
 }
 
 impl Extension {
@@ -428,6 +539,18 @@ impl Extension {
         }
     }
 
+    /// Get a reference to this extension as [`UnknownExtension`].
+    /// Returns an [`ExtensionError::InvalidExtensionType`] error if called on an
+    /// [`Extension`] that's not an [`UnknownExtension`].
+    pub fn as_unknown_extension(&self) -> Result<&UnknownExtension, ExtensionError> {
+        match self {
+            Self::Unknown(_, e) => Ok(e),
+            _ => Err(ExtensionError::InvalidExtensionType(
+                "This is not an UnknownExtension".into(),
+            )),
+        }
+    }
+
     /// Returns the [`ExtensionType`]
     #[inline]
     pub const fn extension_type(&self) -> ExtensionType {
@@ -541,7 +664,11 @@ mod test {
 
     #[test]
     fn that_unknown_extensions_are_de_serialized_correctly() {
-        let extension_types = [0x0000u16, 0x0A0A, 0x7A7A, 0xF000, 0xFFFF];
+        // Extension types from the private use range are unknown to MLS, even if
+        // OpenMLS implements a custom extension with the extension type.
+        let extension_types = [
+            0x0000u16, 0x0A0A, 0x7A7A, 0xF000, 0xFF02, 0xFF03, 0xFF04, 0xFF10, 0xFFFF,
+        ];
         let extension_datas = [vec![], vec![0], vec![1, 2, 3]];
 
         for extension_type in extension_types.into_iter() {
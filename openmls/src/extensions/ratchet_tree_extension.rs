@@ -1,4 +1,9 @@
-use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
+use std::{
+    io::{Read, Write},
+    sync::OnceLock,
+};
+
+use tls_codec::{Deserialize as _, Serialize as _, Size, VLBytes};
 
 use super::{Deserialize, Serialize};
 use crate::treesync::{RatchetTree, RatchetTreeIn};
@@ -9,26 +14,116 @@ use crate::treesync::{RatchetTree, RatchetTreeIn};
 /// represent the public state of the tree in an MLS group.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// optional<Node> ratchet_tree<V>;
 /// ```
-#[derive(
-    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
-)]
+///
+/// When the extension is deserialized, e.g., as part of a
+/// [`GroupInfo`](crate::messages::group_info::GroupInfo), the nodes are not
+/// parsed right away. Instead, they are only parsed when the tree is accessed
+/// with [`RatchetTreeExtension::ratchet_tree()`] for the first time. This keeps
+/// the decoding and the signature verification of the group info of large
+/// groups cheap.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RatchetTreeExtension {
-    ratchet_tree: RatchetTreeIn,
+    ratchet_tree: LazyRatchetTree,
+}
+
+/// A ratchet tree that is either parsed or still in its serialized form.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+enum LazyRatchetTree {
+    Parsed(RatchetTreeIn),
+    Serialized(SerializedRatchetTree),
+}
+
+/// A serialized ratchet tree and the cached result of parsing it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+struct SerializedRatchetTree {
+    /// The content of the serialized vector of nodes, i.e., without the
+    /// length prefix.
+    nodes: VLBytes,
+    #[serde(skip)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    parsed: OnceLock<Result<RatchetTreeIn, tls_codec::Error>>,
 }
 
 impl RatchetTreeExtension {
     /// Build a new extension from a vector of [`Node`](crate::treesync::node::Node)s.
     pub fn new(ratchet_tree: RatchetTree) -> Self {
         RatchetTreeExtension {
-            ratchet_tree: ratchet_tree.into(),
+            ratchet_tree: LazyRatchetTree::Parsed(ratchet_tree.into()),
         }
     }
 
     /// Return the [`RatchetTreeIn`] from this extension.
-    pub fn ratchet_tree(&self) -> &RatchetTreeIn {
-        &self.ratchet_tree
+    ///
+    /// If the extension was deserialized, this parses the nodes of the tree
+    /// when called for the first time. The result is cached.
+    /// Returns an error if the nodes are malformed.
+    pub fn ratchet_tree(&self) -> Result<&RatchetTreeIn, tls_codec::Error> {
+        match &self.ratchet_tree {
+            LazyRatchetTree::Parsed(ratchet_tree) => Ok(ratchet_tree),
+            LazyRatchetTree::Serialized(serialized) => serialized
+                .parsed
+                .get_or_init(|| {
+                    // The encoding of the tree is the encoding of the vector
+                    // of nodes, including the length prefix.
+                    let encoded = serialized.nodes.tls_serialize_detached()?;
+                    RatchetTreeIn::tls_deserialize_exact(encoded)
+                })
+                .as_ref()
+                .map_err(Clone::clone),
+        }
+    }
+}
+
+impl PartialEq for RatchetTreeExtension {
+    fn eq(&self, other: &Self) -> bool {
+        // Parsed and serialized trees are compared by their encoding.
+        match (
+            self.tls_serialize_detached(),
+            other.tls_serialize_detached(),
+        ) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for RatchetTreeExtension {}
+
+impl Size for RatchetTreeExtension {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        match &self.ratchet_tree {
+            LazyRatchetTree::Parsed(ratchet_tree) => ratchet_tree.tls_serialized_len(),
+            LazyRatchetTree::Serialized(serialized) => serialized.nodes.tls_serialized_len(),
+        }
+    }
+}
+
+impl tls_codec::Serialize for RatchetTreeExtension {
+    fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
+        match &self.ratchet_tree {
+            LazyRatchetTree::Parsed(ratchet_tree) => ratchet_tree.tls_serialize(writer),
+            LazyRatchetTree::Serialized(serialized) => serialized.nodes.tls_serialize(writer),
+        }
+    }
+}
+
+impl tls_codec::Deserialize for RatchetTreeExtension {
+    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, tls_codec::Error> {
+        // A vector of nodes has the same encoding as a byte vector with the
+        // concatenated encodings of the nodes.
+        Ok(RatchetTreeExtension {
+            ratchet_tree: LazyRatchetTree::Serialized(SerializedRatchetTree {
+                nodes: VLBytes::tls_deserialize(bytes)?,
+                parsed: OnceLock::new(),
+            }),
+        })
     }
 }
@@ -20,7 +20,7 @@ use super::{Deserialize, ExtensionError, ExtensionType, Serialize};
 /// supported by all current members.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     ExtensionType extension_types<V>;
 ///     ProposalType proposal_types<V>;
@@ -39,6 +39,7 @@ use super::{Deserialize, ExtensionError, ExtensionType, Serialize};
     TlsDeserialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RequiredCapabilitiesExtension {
     extension_types: Vec<ExtensionType>,
     proposal_types: Vec<ProposalType>,
@@ -59,26 +60,50 @@ impl RequiredCapabilitiesExtension {
         }
     }
 
+    /// Returns a builder for [`RequiredCapabilitiesExtension`].
+    pub fn builder() -> RequiredCapabilitiesExtensionBuilder {
+        RequiredCapabilitiesExtensionBuilder::default()
+    }
+
     /// Get a slice with the required extension types.
-    pub(crate) fn extension_types(&self) -> &[ExtensionType] {
+    pub fn extension_types(&self) -> &[ExtensionType] {
         self.extension_types.as_slice()
     }
 
     /// Get a slice with the required proposal types.
-    pub(crate) fn proposal_types(&self) -> &[ProposalType] {
+    pub fn proposal_types(&self) -> &[ProposalType] {
         self.proposal_types.as_slice()
     }
 
     /// Get a slice with the required credential types.
-    #[allow(unused)]
-    pub(crate) fn credential_types(&self) -> &[CredentialType] {
+    pub fn credential_types(&self) -> &[CredentialType] {
         self.credential_types.as_slice()
     }
 
-    /// Check if all extension and proposal types are supported.
-    pub(crate) fn check_support(&self) -> Result<(), ExtensionError> {
+    /// Returns `true` if the extension type is required.
+    pub fn requires_extension_type(&self, extension_type: ExtensionType) -> bool {
+        self.extension_types.contains(&extension_type)
+    }
+
+    /// Returns `true` if the proposal type is required.
+    pub fn requires_proposal_type(&self, proposal_type: ProposalType) -> bool {
+        self.proposal_types.contains(&proposal_type)
+    }
+
+    /// Returns `true` if the credential type is required.
+    pub fn requires_credential_type(&self, credential_type: CredentialType) -> bool {
+        self.credential_types.contains(&credential_type)
+    }
+
+    /// Check if all extension and proposal types are supported. The
+    /// `custom_extension_types` are considered supported in addition to the
+    /// extension types supported by OpenMLS.
+    pub(crate) fn check_support(
+        &self,
+        custom_extension_types: &[ExtensionType],
+    ) -> Result<(), ExtensionError> {
         for extension in self.extension_types() {
-            if !extension.is_supported() {
+            if !extension.is_supported() && !custom_extension_types.contains(extension) {
                 return Err(ExtensionError::UnsupportedExtensionType);
             }
         }
@@ -90,3 +115,102 @@ impl RequiredCapabilitiesExtension {
         Ok(())
     }
 }
+
+/// Builder for a [`RequiredCapabilitiesExtension`].
+///
+/// Types that are added more than once are only listed once.
+///
+/// ```
+/// use openmls::prelude::*;
+///
+/// let required_capabilities = RequiredCapabilitiesExtension::builder()
+///     .extension_type(ExtensionType::ApplicationId)
+///     .proposal_type(ProposalType::Reinit)
+///     .credential_type(CredentialType::Basic)
+///     .build()
+///     .expect("Unsupported required capabilities.");
+/// ```
+#[derive(Debug, Default)]
+pub struct RequiredCapabilitiesExtensionBuilder {
+    extension: RequiredCapabilitiesExtension,
+    custom_extension_types: Vec<ExtensionType>,
+}
+
+impl RequiredCapabilitiesExtensionBuilder {
+    /// Adds a required extension type.
+    pub fn extension_type(mut self, extension_type: ExtensionType) -> Self {
+        if !self.extension.extension_types.contains(&extension_type) {
+            self.extension.extension_types.push(extension_type);
+        }
+        self
+    }
+
+    /// Adds multiple required extension types.
+    pub fn extension_types(self, extension_types: impl IntoIterator<Item = ExtensionType>) -> Self {
+        extension_types
+            .into_iter()
+            .fold(self, |builder, extension_type| {
+                builder.extension_type(extension_type)
+            })
+    }
+
+    /// Adds a required, application-defined extension type. In contrast to
+    /// [`Self::extension_type()`], the extension type is not required to be
+    /// supported by OpenMLS.
+    ///
+    /// See [`CustomExtension`](crate::extensions::CustomExtension) for more
+    /// information.
+    pub fn custom_extension_type(mut self, extension_type: ExtensionType) -> Self {
+        if !self.custom_extension_types.contains(&extension_type) {
+            self.custom_extension_types.push(extension_type);
+        }
+        self.extension_type(extension_type)
+    }
+
+    /// Adds a required proposal type.
+    pub fn proposal_type(mut self, proposal_type: ProposalType) -> Self {
+        if !self.extension.proposal_types.contains(&proposal_type) {
+            self.extension.proposal_types.push(proposal_type);
+        }
+        self
+    }
+
+    /// Adds multiple required proposal types.
+    pub fn proposal_types(self, proposal_types: impl IntoIterator<Item = ProposalType>) -> Self {
+        proposal_types
+            .into_iter()
+            .fold(self, |builder, proposal_type| {
+                builder.proposal_type(proposal_type)
+            })
+    }
+
+    /// Adds a required credential type.
+    pub fn credential_type(mut self, credential_type: CredentialType) -> Self {
+        if !self.extension.credential_types.contains(&credential_type) {
+            self.extension.credential_types.push(credential_type);
+        }
+        self
+    }
+
+    /// Adds multiple required credential types.
+    pub fn credential_types(
+        self,
+        credential_types: impl IntoIterator<Item = CredentialType>,
+    ) -> Self {
+        credential_types
+            .into_iter()
+            .fold(self, |builder, credential_type| {
+                builder.credential_type(credential_type)
+            })
+    }
+
+    /// Finalizes the builder and returns a [`RequiredCapabilitiesExtension`].
+    ///
+    /// Returns an [`ExtensionError::UnsupportedExtensionType`] or
+    /// [`ExtensionError::UnsupportedProposalType`] if a required extension or
+    /// proposal type is not supported by OpenMLS.
+    pub fn build(self) -> Result<RequiredCapabilitiesExtension, ExtensionError> {
+        self.extension.check_support(&self.custom_extension_types)?;
+        Ok(self.extension)
+    }
+}
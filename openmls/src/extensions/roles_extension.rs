@@ -0,0 +1,191 @@
+use std::io::Read;
+
+use tls_codec::{Deserialize as _, Serialize as _, TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+
+use super::{CustomExtension, Deserialize, Serialize};
+
+/// # Role
+///
+/// The role of a member in a group with a [`RolesExtension`]. Roles are
+/// ordered, i.e., an owner has all permissions of an admin and an admin has
+/// all permissions of a member.
+///
+/// ```c
+/// enum {
+///     member(0),
+///     admin(1),
+///     owner(2),
+///     (255)
+/// } Role;
+/// ```
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    TlsSerialize,
+    TlsDeserialize,
+    TlsSize,
+)]
+#[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Role {
+    /// A regular member. Members can update their own leaf and leave the
+    /// group.
+    #[default]
+    Member = 0,
+    /// Admins can additionally add members, remove regular members and
+    /// change the extensions of the group context, except for the roles.
+    Admin = 1,
+    /// Owners can additionally remove admins and owners, change the roles
+    /// and re-initialize the group.
+    Owner = 2,
+}
+
+/// # Role Assignment
+///
+/// Assigns a [`Role`] to the members with the given credential identity.
+///
+/// ```c
+/// struct {
+///     opaque identity<V>;
+///     Role role;
+/// } RoleAssignment;
+/// ```
+#[derive(
+    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RoleAssignment {
+    identity: VLBytes,
+    role: Role,
+}
+
+impl RoleAssignment {
+    /// Returns the credential identity.
+    pub fn identity(&self) -> &[u8] {
+        self.identity.as_slice()
+    }
+
+    /// Returns the role.
+    pub fn role(&self) -> Role {
+        self.role
+    }
+}
+
+/// # Roles Extension
+///
+/// Group context extension that assigns [`Role`]s to the members of the
+/// group. Members are identified by the identity of their credential, and
+/// members without an assignment have the role [`Role::Member`]. The
+/// assignments are unique and sorted by identity.
+///
+/// If the extension is present, all members reject proposals whose sender
+/// doesn't have the role required for the proposal. Proposals of non-members
+/// require the role of the committer. Members can't change the identity of
+/// their credential, and only members with a role can add members with that
+/// role. Since roles are assigned to identities, applications must
+/// authenticate the identities of credentials, e.g., with a
+/// [`CredentialValidator`](crate::credentials::CredentialValidator).
+///
+/// This extension uses the private use extension type `0xff10` and is
+/// implemented as [`CustomExtension`].
+///
+/// ```c
+/// struct {
+///     RoleAssignment assignments<V>;
+/// } Roles;
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RolesExtension {
+    assignments: Vec<RoleAssignment>,
+}
+
+impl RolesExtension {
+    /// Create an extension without assignments.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the assignments in ascending order of the identities.
+    pub fn assignments(&self) -> &[RoleAssignment] {
+        &self.assignments
+    }
+
+    /// Returns the role of the members with the given `identity`.
+    pub fn role(&self, identity: &[u8]) -> Role {
+        self.position(identity)
+            .map(|index| self.assignments[index].role)
+            .unwrap_or_default()
+    }
+
+    /// Sets the role of the members with the given `identity`. Setting the
+    /// role [`Role::Member`] removes the assignment.
+    ///
+    /// Returns the previous role.
+    pub fn set_role(&mut self, identity: &[u8], role: Role) -> Role {
+        match (self.position(identity), role) {
+            (Ok(index), Role::Member) => self.assignments.remove(index).role,
+            (Ok(index), role) => std::mem::replace(&mut self.assignments[index].role, role),
+            (Err(_), Role::Member) => Role::Member,
+            (Err(index), role) => {
+                self.assignments.insert(
+                    index,
+                    RoleAssignment {
+                        identity: identity.to_vec().into(),
+                        role,
+                    },
+                );
+                Role::Member
+            }
+        }
+    }
+
+    fn position(&self, identity: &[u8]) -> Result<usize, usize> {
+        self.assignments
+            .binary_search_by(|assignment| assignment.identity().cmp(identity))
+    }
+}
+
+impl tls_codec::Deserialize for RolesExtension {
+    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, tls_codec::Error>
+    where
+        Self: Sized,
+    {
+        let assignments: Vec<RoleAssignment> = Vec::tls_deserialize(bytes)?;
+
+        // The assignments must be unique and sorted by identity.
+        if assignments
+            .windows(2)
+            .any(|assignments| assignments[0].identity() >= assignments[1].identity())
+        {
+            return Err(tls_codec::Error::DecodingError(
+                "Role assignments are not sorted or not unique".into(),
+            ));
+        }
+
+        Ok(Self { assignments })
+    }
+}
+
+impl CustomExtension for RolesExtension {
+    const EXTENSION_TYPE: u16 = 0xff10;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        // Serializing can only fail if the extension is too large for the
+        // length encoding.
+        self.tls_serialize_detached().unwrap_or_default()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::tls_deserialize_exact(bytes).ok()
+    }
+}
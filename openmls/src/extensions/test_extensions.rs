@@ -14,6 +14,7 @@ use crate::{
     messages::proposals::ProposalType,
     schedule::psk::store::ResumptionPskStore,
     test_utils::*,
+    treesync::{node::leaf_node::Capabilities, RatchetTreeIn},
 };
 
 #[test]
@@ -196,6 +197,57 @@ fn ratchet_tree_extension(ciphersuite: Ciphersuite, backend: &impl OpenMlsCrypto
     );
 }
 
+// The ratchet tree in a deserialized ratchet tree extension is only parsed
+// when it is accessed.
+#[apply(ciphersuites_and_backends)]
+fn lazy_ratchet_tree_extension(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, alice_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Alice",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let alice_group = CoreGroup::builder(
+        GroupId::random(backend),
+        config::CryptoConfig::with_default_version(ciphersuite),
+        alice_credential_with_key,
+    )
+    .build(backend, &alice_signature_keys)
+    .expect("Error creating group.");
+    let ratchet_tree = alice_group.public_group().export_ratchet_tree();
+
+    let extension = RatchetTreeExtension::new(ratchet_tree.clone());
+    let serialized = extension
+        .tls_serialize_detached()
+        .expect("An unexpected error occurred.");
+    let deserialized = RatchetTreeExtension::tls_deserialize_exact(&serialized)
+        .expect("An unexpected error occurred.");
+
+    assert_eq!(extension, deserialized);
+    assert_eq!(
+        deserialized
+            .tls_serialize_detached()
+            .expect("An unexpected error occurred."),
+        serialized
+    );
+    assert_eq!(
+        deserialized
+            .ratchet_tree()
+            .expect("An unexpected error occurred."),
+        &RatchetTreeIn::from(ratchet_tree)
+    );
+    // The parsed tree is cached.
+    assert!(std::ptr::eq(
+        deserialized.ratchet_tree().unwrap(),
+        deserialized.ratchet_tree().unwrap()
+    ));
+
+    // Malformed nodes are only detected when the tree is parsed.
+    let malformed = RatchetTreeExtension::tls_deserialize_exact([0x02, 0xFF, 0xFF])
+        .expect("An unexpected error occurred.");
+    assert!(malformed.ratchet_tree().is_err());
+}
+
 #[test]
 fn required_capabilities() {
     // A raw required capabilities extension with the default values for openmls (none).
@@ -235,3 +287,867 @@ fn required_capabilities() {
     assert_eq!(ext, ext_decoded);
     assert_eq!(extension_bytes, encoded);
 }
+
+#[derive(Debug, PartialEq)]
+struct ColorExtension(u8);
+
+impl CustomExtension for ColorExtension {
+    const EXTENSION_TYPE: u16 = 0xFF01;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        vec![self.0]
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [color] => Some(Self(*color)),
+            _ => None,
+        }
+    }
+}
+
+struct FakeApplicationIdExtension;
+
+impl CustomExtension for FakeApplicationIdExtension {
+    const EXTENSION_TYPE: u16 = 0x0001;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        vec![]
+    }
+
+    fn from_bytes(_bytes: &[u8]) -> Option<Self> {
+        Some(Self)
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn custom_extension(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    // Custom extensions survive a round-trip through `Extensions`.
+    let extensions = Extensions::single(Extension::from_custom(&ColorExtension(7)));
+    let encoded = extensions
+        .tls_serialize_detached()
+        .expect("error encoding extensions");
+    let decoded =
+        Extensions::tls_deserialize(&mut encoded.as_slice()).expect("error decoding extensions");
+    assert_eq!(extensions, decoded);
+    assert_eq!(decoded.custom::<ColorExtension>(), Some(ColorExtension(7)));
+
+    // Extension types defined by MLS can't be registered.
+    assert!(CustomExtensionRegistry::new()
+        .register::<FakeApplicationIdExtension>()
+        .is_err());
+
+    let registry = CustomExtensionRegistry::new()
+        .register::<ColorExtension>()
+        .expect("error registering custom extension");
+    assert!(registry.is_registered(ExtensionType::Unknown(0xFF01)));
+    assert!(registry.validate(&decoded).is_ok());
+    let invalid = Extensions::single(Extension::Unknown(0xFF01, UnknownExtension(vec![1, 2])));
+    assert_eq!(
+        registry.validate(&invalid),
+        Err(ExtensionError::InvalidCustomExtension)
+    );
+
+    // Groups can only require custom extensions that are registered.
+    let (alice_credential_with_key, alice_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Alice",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let required_capabilities =
+        RequiredCapabilitiesExtension::new(&[ExtensionType::Unknown(0xFF01)], &[], &[]);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(config::CryptoConfig::with_default_version(ciphersuite))
+        .required_capabilities(required_capabilities.clone())
+        .build();
+    let result = MlsGroup::new(
+        backend,
+        &alice_signature_keys,
+        &mls_group_config,
+        alice_credential_with_key.clone(),
+    );
+    assert!(matches!(
+        result,
+        Err(NewGroupError::UnsupportedExtensionType)
+    ));
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(config::CryptoConfig::with_default_version(ciphersuite))
+        .required_capabilities(required_capabilities)
+        .custom_extensions(registry)
+        .build();
+    let alice_group = MlsGroup::new(
+        backend,
+        &alice_signature_keys,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("error creating group with custom extension");
+    assert!(alice_group
+        .own_leaf_node()
+        .expect("missing own leaf node")
+        .capabilities()
+        .extensions()
+        .contains(&ExtensionType::Unknown(0xFF01)));
+}
+
+#[test]
+fn required_capabilities_builder() {
+    let required_capabilities = RequiredCapabilitiesExtension::builder()
+        .extension_types([ExtensionType::ApplicationId, ExtensionType::RatchetTree])
+        .extension_type(ExtensionType::ApplicationId)
+        .proposal_type(ProposalType::Reinit)
+        .proposal_type(ProposalType::Reinit)
+        .credential_type(CredentialType::Basic)
+        .build()
+        .expect("error building required capabilities");
+
+    // Duplicates are only listed once.
+    assert_eq!(
+        required_capabilities,
+        RequiredCapabilitiesExtension::new(
+            &[ExtensionType::ApplicationId, ExtensionType::RatchetTree],
+            &[ProposalType::Reinit],
+            &[CredentialType::Basic],
+        )
+    );
+    assert!(required_capabilities.requires_extension_type(ExtensionType::RatchetTree));
+    assert!(!required_capabilities.requires_proposal_type(ProposalType::Add));
+
+    // Unsupported types are rejected ...
+    assert_eq!(
+        RequiredCapabilitiesExtension::builder()
+            .extension_type(ExtensionType::Unknown(0xFF01))
+            .build(),
+        Err(ExtensionError::UnsupportedExtensionType)
+    );
+    assert_eq!(
+        RequiredCapabilitiesExtension::builder()
+            .proposal_type(ProposalType::Unknown(0xFF01))
+            .build(),
+        Err(ExtensionError::UnsupportedProposalType)
+    );
+
+    // ... unless they are application-defined extension types.
+    let required_capabilities = RequiredCapabilitiesExtension::builder()
+        .custom_extension_type(ExtensionType::Unknown(0xFF01))
+        .build()
+        .expect("error building required capabilities");
+    assert_eq!(
+        required_capabilities.extension_types(),
+        &[ExtensionType::Unknown(0xFF01)]
+    );
+}
+
+#[test]
+fn media_types() {
+    let media_types = MediaTypeList::new(vec![
+        MediaType::from("text/plain;charset=utf-8"),
+        MediaType::from("application/mimi-content"),
+    ]);
+    assert!(media_types.contains(&MediaType::from("text/plain;charset=utf-8")));
+    assert!(!media_types.contains(&MediaType::from("text/markdown")));
+    assert!(
+        media_types.contains_all(&MediaTypeList::new(vec![MediaType::from(
+            "application/mimi-content"
+        )]))
+    );
+    assert!(media_types.contains_all(&MediaTypeList::default()));
+
+    let accepted_media_types = AcceptedMediaTypesExtension::new(media_types.clone());
+    let required_media_types = RequiredMediaTypesExtension::new(media_types);
+    let extensions = Extensions::from_vec(vec![
+        Extension::from_custom(&accepted_media_types),
+        Extension::from_custom(&required_media_types),
+    ])
+    .expect("error creating extensions");
+    let serialized = extensions
+        .tls_serialize_detached()
+        .expect("error encoding extensions");
+    let decoded =
+        Extensions::tls_deserialize(&mut serialized.as_slice()).expect("error decoding extensions");
+    assert_eq!(extensions, decoded);
+    assert_eq!(decoded.accepted_media_types(), Some(accepted_media_types));
+    assert_eq!(decoded.required_media_types(), Some(required_media_types));
+
+    // The extensions use private use extension types that are unknown to MLS.
+    assert_eq!(
+        decoded
+            .iter()
+            .map(Extension::extension_type)
+            .collect::<Vec<_>>(),
+        vec![
+            ExtensionType::Unknown(0xff02),
+            ExtensionType::Unknown(0xff03)
+        ]
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn media_types_negotiation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let plain_text = MediaType::from("text/plain");
+    let markdown = MediaType::from("text/markdown");
+    let required_media_types =
+        RequiredMediaTypesExtension::new(MediaTypeList::new(vec![plain_text.clone()]));
+
+    let (alice_credential_with_key, alice_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Alice",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+
+    // The creator must accept the required media types.
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(config::CryptoConfig::with_default_version(ciphersuite))
+        .required_media_types(required_media_types.clone())
+        .build();
+    let result = MlsGroup::new(
+        backend,
+        &alice_signature_keys,
+        &mls_group_config,
+        alice_credential_with_key.clone(),
+    );
+    assert!(matches!(result, Err(NewGroupError::UnsupportedMediaTypes)));
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(config::CryptoConfig::with_default_version(ciphersuite))
+        .accepted_media_types(MediaTypeList::new(vec![plain_text.clone(), markdown.clone()]).into())
+        .required_media_types(required_media_types.clone())
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signature_keys,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("error creating group with required media types");
+    assert_eq!(
+        alice_group
+            .export_group_context()
+            .extensions()
+            .required_media_types(),
+        Some(required_media_types)
+    );
+
+    // Bob accepts the required media types and can be added.
+    let (bob_credential_with_key, bob_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Bob",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let bob_key_package = KeyPackage::builder()
+        .leaf_node_capabilities(Capabilities::new(
+            None,
+            None,
+            Some(&[ExtensionType::Unknown(
+                AcceptedMediaTypesExtension::EXTENSION_TYPE,
+            )]),
+            None,
+            None,
+        ))
+        .leaf_node_extensions(Extensions::single(Extension::from_custom(
+            &AcceptedMediaTypesExtension::new(MediaTypeList::new(vec![plain_text])),
+        )))
+        .build(
+            config::CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signature_keys,
+            bob_credential_with_key,
+        )
+        .expect("error creating key package");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signature_keys, &[bob_key_package])
+        .expect("error adding Bob");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging commit");
+    let _bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("unexpected message type"),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error joining group");
+
+    // Charlie only accepts markdown and can't be added.
+    let (charlie_credential_with_key, charlie_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Charlie",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let charlie_key_package = KeyPackage::builder()
+        .leaf_node_capabilities(Capabilities::new(
+            None,
+            None,
+            Some(&[ExtensionType::Unknown(
+                AcceptedMediaTypesExtension::EXTENSION_TYPE,
+            )]),
+            None,
+            None,
+        ))
+        .leaf_node_extensions(Extensions::single(Extension::from_custom(
+            &AcceptedMediaTypesExtension::new(MediaTypeList::new(vec![markdown])),
+        )))
+        .build(
+            config::CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &charlie_signature_keys,
+            charlie_credential_with_key,
+        )
+        .expect("error creating key package");
+    let err = alice_group
+        .add_members(backend, &alice_signature_keys, &[charlie_key_package])
+        .expect_err("added a member that doesn't accept the required media types");
+    assert!(matches!(
+        err,
+        AddMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
+            ProposalValidationError::UnsupportedMediaTypes
+        ))
+    ));
+}
+
+#[test]
+fn app_data_dictionary() {
+    let mut dictionary = AppDataDictionaryExtension::new();
+    assert!(dictionary.is_empty());
+    assert_eq!(dictionary.insert(0xFF02, vec![2]), None);
+    assert_eq!(dictionary.insert(0xFF01, vec![1]), None);
+    assert_eq!(dictionary.insert(0xFF02, vec![3]), Some(vec![2]));
+
+    // Entries are kept sorted by component ID.
+    let component_ids: Vec<u16> = dictionary
+        .component_data()
+        .iter()
+        .map(|entry| entry.component_id())
+        .collect();
+    assert_eq!(component_ids, vec![0xFF01, 0xFF02]);
+    assert_eq!(dictionary.get(0xFF02), Some([3].as_slice()));
+
+    let extension = Extension::from_custom(&dictionary);
+    let encoded = extension
+        .tls_serialize_detached()
+        .expect("error encoding app data dictionary");
+    let decoded = Extension::tls_deserialize(&mut encoded.as_slice())
+        .expect("error decoding app data dictionary");
+    assert_eq!(extension, decoded);
+    assert_eq!(decoded.as_custom(), Ok(dictionary.clone()));
+
+    assert_eq!(dictionary.remove(0xFF01), Some(vec![1]));
+    assert_eq!(dictionary.get(0xFF01), None);
+
+    // Unsorted entries are rejected.
+    let unsorted = [0x06, 0x00, 0x02, 0x00, 0x00, 0x01, 0x00];
+    assert!(AppDataDictionaryExtension::tls_deserialize(&mut unsorted.as_slice()).is_err());
+}
+
+#[test]
+fn roles_extension() {
+    let mut roles = RolesExtension::new();
+    assert_eq!(roles.set_role(b"Bob", Role::Admin), Role::Member);
+    assert_eq!(roles.set_role(b"Alice", Role::Owner), Role::Member);
+    assert_eq!(roles.set_role(b"Charlie", Role::Member), Role::Member);
+
+    // Assignments are kept sorted by identity and members aren't stored.
+    let identities: Vec<&[u8]> = roles
+        .assignments()
+        .iter()
+        .map(|assignment| assignment.identity())
+        .collect();
+    assert_eq!(identities, vec![b"Alice".as_slice(), b"Bob".as_slice()]);
+    assert_eq!(roles.role(b"Bob"), Role::Admin);
+    assert_eq!(roles.role(b"Charlie"), Role::Member);
+
+    let extension = Extension::from_custom(&roles);
+    let encoded = extension
+        .tls_serialize_detached()
+        .expect("error encoding roles extension");
+    let decoded = Extension::tls_deserialize(&mut encoded.as_slice())
+        .expect("error decoding roles extension");
+    assert_eq!(extension, decoded);
+    assert_eq!(decoded.as_custom(), Ok(roles.clone()));
+
+    assert_eq!(roles.set_role(b"Bob", Role::Member), Role::Admin);
+    assert_eq!(roles.assignments().len(), 1);
+
+    // Unsorted assignments are rejected.
+    let unsorted = [0x06, 0x01, b'b', 0x01, 0x01, b'a', 0x02];
+    assert!(RolesExtension::tls_deserialize(&mut unsorted.as_slice()).is_err());
+}
+
+struct ShapeExtension;
+
+impl CustomExtension for ShapeExtension {
+    const EXTENSION_TYPE: u16 = 0xFF05;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        vec![]
+    }
+
+    fn from_bytes(_bytes: &[u8]) -> Option<Self> {
+        Some(Self)
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn safe_extensions(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let registry = CustomExtensionRegistry::new()
+        .register::<ColorExtension>()
+        .expect("error registering custom extension");
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(config::CryptoConfig::with_default_version(ciphersuite))
+        .custom_extensions(registry.clone())
+        .build();
+
+    let (alice_credential_with_key, alice_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Alice",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let (bob_credential_with_key, bob_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Bob",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signature_keys,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("error creating group");
+    let bob_key_package = KeyPackage::builder()
+        .build(
+            config::CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signature_keys,
+            bob_credential_with_key,
+        )
+        .expect("error creating key package");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signature_keys, &[bob_key_package])
+        .expect("error adding Bob");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("unexpected message type"),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error joining group");
+
+    // Extension secrets are shared by all members and separated by label.
+    let alice_secret = alice_group
+        .export_extension_secret::<ColorExtension>(backend, "palette", 32)
+        .expect("error exporting extension secret");
+    let bob_secret = bob_group
+        .export_extension_secret::<ColorExtension>(backend, "palette", 32)
+        .expect("error exporting extension secret");
+    assert_eq!(alice_secret, bob_secret);
+    assert_ne!(
+        alice_secret,
+        alice_group
+            .export_extension_secret::<ColorExtension>(backend, "brush", 32)
+            .expect("error exporting extension secret")
+    );
+
+    // Extension secrets can't be obtained with the public exporter.
+    let mut context = ColorExtension::EXTENSION_TYPE.to_be_bytes().to_vec();
+    context.push(b"palette".len() as u8);
+    context.extend_from_slice(b"palette");
+    for label in ["ExtensionExport", "extension"] {
+        assert_ne!(
+            alice_secret,
+            alice_group
+                .export_secret(backend, label, &context, 32)
+                .expect("error exporting secret")
+        );
+    }
+
+    // Unregistered extensions can't use the safe extension API.
+    assert_eq!(
+        alice_group.export_extension_secret::<ShapeExtension>(backend, "palette", 32),
+        Err(SafeExtensionError::UnregisteredExtension)
+    );
+    assert!(matches!(
+        alice_group.propose_extension_state(backend, &alice_signature_keys, Some(&ShapeExtension)),
+        Err(ProposalError::SafeExtensionError(
+            SafeExtensionError::UnregisteredExtension
+        ))
+    ));
+
+    // The state of the extension is agreed on via the group context.
+    assert_eq!(alice_group.extension_state::<ColorExtension>(), None);
+    alice_group
+        .propose_extension_state(backend, &alice_signature_keys, Some(&ColorExtension(3)))
+        .expect("error proposing extension state");
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signature_keys)
+        .expect("error committing extension state");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging commit");
+    let processed_message = bob_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("unexpected message type"),
+        )
+        .expect("error processing commit");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    {
+        bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("error merging commit");
+    } else {
+        unreachable!("Expected a StagedCommit.");
+    }
+
+    assert_eq!(
+        alice_group.extension_state::<ColorExtension>(),
+        Some(ColorExtension(3))
+    );
+    assert_eq!(
+        bob_group.extension_state::<ColorExtension>(),
+        Some(ColorExtension(3))
+    );
+    assert!(registry
+        .validate(alice_group.export_group_context().extensions())
+        .is_ok());
+
+    // Registered extensions with invalid state don't validate.
+    let mut dictionary = AppDataDictionaryExtension::new();
+    dictionary.insert(ColorExtension::EXTENSION_TYPE, vec![1, 2]);
+    assert_eq!(
+        registry.validate(&Extensions::single(Extension::from_custom(&dictionary))),
+        Err(ExtensionError::InvalidCustomExtension)
+    );
+
+    // Malformed dictionaries don't validate.
+    assert_eq!(
+        registry.validate(&Extensions::single(Extension::Unknown(
+            AppDataDictionaryExtension::EXTENSION_TYPE,
+            UnknownExtension(vec![0x01]),
+        ))),
+        Err(ExtensionError::InvalidCustomExtension)
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn invalid_custom_extensions(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let registry = CustomExtensionRegistry::new()
+        .register::<ColorExtension>()
+        .expect("error registering custom extension");
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let registry_config = MlsGroupConfig::builder()
+        .crypto_config(config::CryptoConfig::with_default_version(ciphersuite))
+        .custom_extensions(registry)
+        .build();
+
+    let (alice_credential_with_key, alice_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Alice",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let (bob_credential_with_key, bob_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Bob",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let (charlie_credential_with_key, charlie_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Charlie",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+
+    // Alice doesn't know the custom extension, Bob does.
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signature_keys,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("error creating group");
+    let bob_key_package = KeyPackage::builder()
+        .build(
+            config::CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signature_keys,
+            bob_credential_with_key,
+        )
+        .expect("error creating key package");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signature_keys, &[bob_key_package])
+        .expect("error adding Bob");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &registry_config,
+        welcome.into_welcome().expect("unexpected message type"),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error joining group");
+
+    // Charlie's leaf node carries a custom extension that can't be decoded.
+    let charlie_key_package = KeyPackage::builder()
+        .leaf_node_capabilities(Capabilities::new(
+            None,
+            None,
+            Some(&[ExtensionType::Unknown(ColorExtension::EXTENSION_TYPE)]),
+            None,
+            None,
+        ))
+        .leaf_node_extensions(Extensions::single(Extension::Unknown(
+            ColorExtension::EXTENSION_TYPE,
+            UnknownExtension(vec![1, 2]),
+        )))
+        .build(
+            config::CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &charlie_signature_keys,
+            charlie_credential_with_key,
+        )
+        .expect("error creating key package");
+    let (commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signature_keys, &[charlie_key_package])
+        .expect("error adding Charlie");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging commit");
+
+    // Bob rejects the commit ...
+    let error = bob_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("unexpected message type"),
+        )
+        .expect_err("processed commit with invalid custom extension");
+    assert_eq!(
+        error,
+        ProcessMessageError::InvalidExtension(ExtensionError::InvalidCustomExtension)
+    );
+
+    // ... and Charlie rejects the Welcome.
+    let error = MlsGroup::new_from_welcome(
+        backend,
+        &registry_config,
+        welcome.into_welcome().expect("unexpected message type"),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect_err("joined group with invalid custom extension");
+    assert!(matches!(
+        error,
+        WelcomeError::InvalidExtension(ExtensionError::InvalidCustomExtension)
+    ));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn unknown_leaf_node_extension(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let unknown_extension_type = 0xff00;
+    let unknown_extension_data = vec![1u8, 2, 3];
+
+    // Unknown extensions survive a round-trip through `Extensions`.
+    let extensions = Extensions::single(Extension::Unknown(
+        unknown_extension_type,
+        UnknownExtension(unknown_extension_data.clone()),
+    ));
+    let decoded = Extensions::tls_deserialize(
+        &mut extensions
+            .tls_serialize_detached()
+            .expect("error encoding extensions")
+            .as_slice(),
+    )
+    .expect("error decoding extensions");
+    assert_eq!(
+        decoded
+            .unknown(unknown_extension_type)
+            .map(UnknownExtension::data),
+        Some(unknown_extension_data.as_slice())
+    );
+    assert!(decoded.unknown(0xff01).is_none());
+
+    let (alice_credential_with_key, alice_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Alice",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signature_keys,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("error creating group");
+
+    // Bob's leaf node carries an extension that OpenMLS doesn't know.
+    let (bob_credential_with_key, bob_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Bob",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let bob_key_package = KeyPackage::builder()
+        .leaf_node_capabilities(Capabilities::new(
+            None,
+            None,
+            Some(&[ExtensionType::Unknown(unknown_extension_type)]),
+            None,
+            None,
+        ))
+        .leaf_node_extensions(extensions)
+        .build(
+            config::CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signature_keys,
+            bob_credential_with_key,
+        )
+        .expect("error creating key package");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signature_keys, &[bob_key_package])
+        .expect("error adding Bob");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("unexpected message type"),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error joining group");
+
+    // Bob updates his leaf, which re-signs it.
+    let (commit, _welcome, _group_info) = bob_group
+        .self_update(backend, &bob_signature_keys)
+        .expect("error creating self update");
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("error merging commit");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) = alice_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("error processing commit")
+        .into_content()
+    {
+        alice_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("error merging commit");
+    } else {
+        unreachable!("Expected a StagedCommit.");
+    }
+
+    // The extension is still there for both members.
+    for group in [&alice_group, &bob_group] {
+        let bob_leaf = group
+            .ratchet_tree_view()
+            .leaf(bob_group.own_leaf_index())
+            .expect("Bob's leaf is blank.");
+        assert_eq!(
+            bob_leaf
+                .extensions()
+                .unknown(unknown_extension_type)
+                .map(UnknownExtension::data),
+            Some(unknown_extension_data.as_slice())
+        );
+    }
+    assert_eq!(alice_group.tree_hash(), bob_group.tree_hash());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn group_info_custom_extensions(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let registry = CustomExtensionRegistry::new()
+        .register::<ColorExtension>()
+        .expect("error registering custom extension");
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(config::CryptoConfig::with_default_version(ciphersuite))
+        .custom_extensions(registry)
+        .build();
+
+    let (alice_credential_with_key, alice_signature_keys) = test_utils::new_credential(
+        backend,
+        b"Alice",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let alice_group = MlsGroup::new(
+        backend,
+        &alice_signature_keys,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("error creating group");
+
+    // A registered custom extension is signed into the GroupInfo.
+    let options = GroupInfoExportOptions::new()
+        .with_ratchet_tree(true)
+        .with_extension(Extension::from_custom(&ColorExtension(3)));
+    let group_info = alice_group
+        .export_group_info_with_options(backend, &alice_signature_keys, &options)
+        .expect("error exporting group info")
+        .into_verifiable_group_info()
+        .expect("unexpected message type");
+    assert_eq!(
+        group_info.extensions().custom::<ColorExtension>(),
+        Some(ColorExtension(3))
+    );
+    assert!(group_info.has_ratchet_tree_extension());
+    assert!(group_info.supports_external_commit());
+
+    // The standard extensions can be left out.
+    let options = GroupInfoExportOptions::new().with_external_pub(false);
+    let group_info = alice_group
+        .export_group_info_with_options(backend, &alice_signature_keys, &options)
+        .expect("error exporting group info")
+        .into_verifiable_group_info()
+        .expect("unexpected message type");
+    assert_eq!(group_info.extensions(), &Extensions::empty());
+    assert!(!group_info.supports_external_commit());
+
+    // Unregistered and standard extension types are rejected ...
+    let options =
+        GroupInfoExportOptions::new().with_extension(Extension::from_custom(&ShapeExtension));
+    assert_eq!(
+        alice_group
+            .export_group_info_with_options(backend, &alice_signature_keys, &options)
+            .expect_err("exported unregistered extension"),
+        ExportGroupInfoError::UnsupportedExtensionType(ExtensionType::Unknown(0xFF05))
+    );
+    let options = GroupInfoExportOptions::new().with_extension(Extension::ApplicationId(
+        ApplicationIdExtension::new(b"Alice"),
+    ));
+    assert_eq!(
+        alice_group
+            .export_group_info_with_options(backend, &alice_signature_keys, &options)
+            .expect_err("exported standard extension"),
+        ExportGroupInfoError::UnsupportedExtensionType(ExtensionType::ApplicationId)
+    );
+
+    // ... as well as invalid data for registered extension types.
+    let options = GroupInfoExportOptions::new()
+        .with_extension(Extension::Unknown(0xFF01, UnknownExtension(vec![1, 2])));
+    assert_eq!(
+        alice_group
+            .export_group_info_with_options(backend, &alice_signature_keys, &options)
+            .expect_err("exported invalid extension"),
+        ExportGroupInfoError::InvalidExtension(ExtensionError::InvalidCustomExtension)
+    );
+}
@@ -2,7 +2,7 @@
 //!
 //! This module contains errors related to message framing operations.
 
-use crate::error::LibraryError;
+use crate::{ciphersuite::signable::SignatureError, error::LibraryError};
 use thiserror::Error;
 
 // === Public ===
@@ -51,6 +51,9 @@ pub(crate) enum MessageEncryptionError {
     /// See [`SenderError`] for more details.
     #[error(transparent)]
     SenderError(#[from] SenderError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
 }
 
 /// Sender error
@@ -76,4 +79,62 @@ pub enum MlsMessageError {
     /// The message (or one of its parts) is too large to be encoded.
     #[error("The message (or one of its parts) is too large to be encoded.")]
     UnableToEncode,
+    /// The length prefix of the message exceeds the maximum message length.
+    #[error("The length prefix of the message exceeds the maximum message length.")]
+    MessageTooLarge,
+}
+
+/// Syntactic validation error
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum SyntacticValidationError {
+    /// The message could not be decoded.
+    #[error("The message could not be decoded.")]
+    UnableToDecode,
+    /// The message is not of a supported MLS version.
+    #[error("The message is not of a supported MLS version.")]
+    UnsupportedMlsVersion,
+    /// The message is neither a PublicMessage nor a PrivateMessage.
+    #[error("The message is neither a PublicMessage nor a PrivateMessage.")]
+    NotAProtocolMessage,
+    /// Message group ID differs from the group's group ID.
+    #[error("Message group ID differs from the group's group ID.")]
+    WrongGroupId,
+    /// Message epoch is outside of the accepted epoch window.
+    #[error("Message epoch is outside of the accepted epoch window.")]
+    WrongEpoch,
+    /// The message's wire format is incompatible with the group's wire format policy.
+    #[error("The message's wire format is incompatible with the group's wire format policy.")]
+    IncompatibleWireFormat,
+    /// The PublicMessage contains an application message but was not encrypted.
+    #[error("The PublicMessage contains an application message but was not encrypted.")]
+    UnencryptedApplicationMessage,
+    /// Membership tag is missing.
+    #[error("Membership tag is missing.")]
+    MissingMembershipTag,
+    /// The confirmation tag is missing.
+    #[error("The confirmation tag is missing.")]
+    MissingConfirmationTag,
+    /// The PublicMessage is not a Commit despite the sender being of type NewMemberCommit.
+    #[error("The PublicMessage is not a Commit despite the sender being of type NewMemberCommit.")]
+    NotACommit,
+    /// The Commit doesn't have a path despite the sender being of type NewMemberCommit.
+    #[error("The Commit doesn't have a path despite the sender being of type NewMemberCommit.")]
+    NoPath,
+    /// The PublicMessage is not an external Add proposal despite the sender being of type NewMemberProposal.
+    #[error("The PublicMessage is not an external Add proposal despite the sender being of type NewMemberProposal.")]
+    NotAnExternalAddProposal,
+    /// The sender type is not allowed to send this content type.
+    #[error("The sender type is not allowed to send this content type.")]
+    InvalidSenderType,
+}
+
+/// Compute proposal reference error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ComputeProposalRefError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The message doesn't contain a proposal.
+    #[error("The message doesn't contain a proposal.")]
+    NotAProposal,
 }
@@ -0,0 +1,166 @@
+//! MLS Message Bundle
+//!
+//! Some operations produce several messages that the DS has to handle
+//! atomically, e.g., a commit that adds members, the corresponding
+//! [`Welcome`] and the updated [`GroupInfo`](crate::messages::group_info::GroupInfo).
+//! An [`MlsMessageBundle`] concatenates such [`MlsMessageOut`]s into a single
+//! upload, which the receiver splits into [`MlsMessageIn`]s again with
+//! [`MlsMessageBundleIn::split()`].
+//!
+//! ```c
+//! struct {
+//!     uint32 message_count;
+//!     // Each message is prefixed with its length as a big-endian uint32.
+//!     LengthPrefixedMLSMessage messages[message_count];
+//! } MlsMessageBundle;
+//! ```
+
+use std::io::{Read, Write};
+
+use super::*;
+
+/// A bundle of outgoing messages that are sent to the DS as a single upload.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MlsMessageBundle {
+    messages: Vec<MlsMessageOut>,
+}
+
+impl MlsMessageBundle {
+    /// Creates an empty bundle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `message` to the bundle.
+    pub fn push(&mut self, message: impl Into<MlsMessageOut>) {
+        self.messages.push(message.into());
+    }
+
+    /// Returns the messages of the bundle in the order they were added.
+    pub fn messages(&self) -> &[MlsMessageOut] {
+        &self.messages
+    }
+
+    /// Returns the number of messages in the bundle.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if the bundle contains no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Serializes the bundle to a byte vector. Returns
+    /// [`MlsMessageError::UnableToEncode`] on failure.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MlsMessageError> {
+        let mut bytes = vec![];
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Serializes the bundle directly into `writer`. Returns the number of
+    /// bytes written or [`MlsMessageError::UnableToEncode`] on failure.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<usize, MlsMessageError> {
+        let message_count =
+            u32::try_from(self.messages.len()).map_err(|_| MlsMessageError::UnableToEncode)?;
+        writer
+            .write_all(&message_count.to_be_bytes())
+            .map_err(|_| MlsMessageError::UnableToEncode)?;
+        let mut written = LENGTH_PREFIX_LENGTH;
+        for message in &self.messages {
+            written += message.write_length_prefixed_to(writer)?;
+        }
+        Ok(written)
+    }
+}
+
+impl From<Vec<MlsMessageOut>> for MlsMessageBundle {
+    fn from(messages: Vec<MlsMessageOut>) -> Self {
+        Self { messages }
+    }
+}
+
+impl FromIterator<MlsMessageOut> for MlsMessageBundle {
+    fn from_iter<I: IntoIterator<Item = MlsMessageOut>>(iter: I) -> Self {
+        Self {
+            messages: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// A bundle of incoming messages that was received as a single upload.
+///
+/// The `-In` suffix of this struct is to separate it from the
+/// [`MlsMessageBundle`] that is sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MlsMessageBundleIn {
+    messages: Vec<MlsMessageIn>,
+}
+
+impl MlsMessageBundleIn {
+    /// Splits a bundle that was serialized with
+    /// [`MlsMessageBundle::to_bytes()`] into its messages.
+    ///
+    /// The bundle is rejected as a whole if any of its messages can't be
+    /// decoded, such that the messages of an atomic operation are either all
+    /// processed or none of them.
+    ///
+    /// Returns [`MlsMessageError::MessageTooLarge`] if the length prefix of a
+    /// message exceeds `max_message_length` and
+    /// [`MlsMessageError::UnableToDecode`] if a message could not be decoded,
+    /// the bundle is truncated or there are bytes left after the last
+    /// message.
+    pub fn split(bytes: &[u8], max_message_length: usize) -> Result<Self, MlsMessageError> {
+        let mut reader = bytes;
+        let mut message_count = [0u8; LENGTH_PREFIX_LENGTH];
+        reader
+            .read_exact(&mut message_count)
+            .map_err(|_| MlsMessageError::UnableToDecode)?;
+        let message_count = u32::from_be_bytes(message_count);
+
+        // Every message takes at least the bytes of its length prefix, so the
+        // message count can't be used to allocate more than the input.
+        let mut messages =
+            Vec::with_capacity((message_count as usize).min(reader.len() / LENGTH_PREFIX_LENGTH));
+        for _ in 0..message_count {
+            messages.push(MlsMessageIn::read_length_prefixed_from(
+                &mut reader,
+                max_message_length,
+            )?);
+        }
+        if !reader.is_empty() {
+            return Err(MlsMessageError::UnableToDecode);
+        }
+        Ok(Self { messages })
+    }
+
+    /// Returns the messages of the bundle in the order they were sent.
+    pub fn messages(&self) -> &[MlsMessageIn] {
+        &self.messages
+    }
+
+    /// Returns the number of messages in the bundle.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if the bundle contains no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Returns the messages of the bundle in the order they were sent.
+    pub fn into_messages(self) -> Vec<MlsMessageIn> {
+        self.messages
+    }
+}
+
+impl IntoIterator for MlsMessageBundleIn {
+    type Item = MlsMessageIn;
+    type IntoIter = std::vec::IntoIter<MlsMessageIn>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.messages.into_iter()
+    }
+}
@@ -12,7 +12,12 @@
 //! [`MlsMessageIn::extract()`] for use with the [`MlsGroup`] API.
 //!
 //! If an [`MlsMessageIn`] contains a [`PublicMessage`] or [`PrivateMessage`],
-//! can be used to determine which group can be used to process the message.
+//! its group ID, epoch and content type can be inspected without consuming the
+//! message, e.g., to determine which group can be used to process the message.
+
+use std::io::{self, Read};
+
+use tls_codec::Deserialize as TlsDeserializeTrait;
 
 use super::*;
 use crate::{
@@ -24,7 +29,7 @@ use crate::{
 /// `extract` to yield its [`MlsMessageInBody`].
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     ProtocolVersion version = mls10;
 ///
@@ -36,6 +41,8 @@ use crate::{
 /// which is commonly returned by functions of the [`MlsGroup`] API.
 #[derive(PartialEq, Debug, Clone, TlsSize, TlsDeserialize)]
 #[cfg_attr(feature = "test-utils", derive(TlsSerialize))]
+#[cfg_attr(feature = "serde-types", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MlsMessageIn {
     pub(crate) version: ProtocolVersion,
     pub(crate) body: MlsMessageInBody,
@@ -49,16 +56,16 @@ pub struct MlsMessageIn {
 /// contradicts the variant given in `body`.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     // ... continued from [MlsMessage] ...
 ///
 ///     WireFormat wire_format;
 ///     select (MLSMessage.wire_format) {
-///         case mls_plaintext:
-///             PublicMessage plaintext;
-///         case mls_ciphertext:
-///             PrivateMessage ciphertext;
+///         case mls_public_message:
+///             PublicMessage public_message;
+///         case mls_private_message:
+///             PrivateMessage private_message;
 ///         case mls_welcome:
 ///             Welcome welcome;
 ///         case mls_group_info:
@@ -71,6 +78,8 @@ pub struct MlsMessageIn {
 #[derive(Debug, PartialEq, Clone, TlsDeserialize, TlsSize)]
 #[cfg_attr(feature = "test-utils", derive(TlsSerialize))]
 #[repr(u16)]
+#[cfg_attr(feature = "serde-types", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum MlsMessageInBody {
     /// Plaintext message
     #[tls_codec(discriminant = 1)]
@@ -93,7 +102,57 @@ pub enum MlsMessageInBody {
     KeyPackage(KeyPackageIn),
 }
 
+/// The length of the length prefix used by
+/// [`MlsMessageOut::write_length_prefixed_to()`] and
+/// [`MlsMessageIn::read_length_prefixed_from()`].
+pub(crate) const LENGTH_PREFIX_LENGTH: usize = 4;
+
 impl MlsMessageIn {
+    /// Deserializes a message directly from `reader`, without an intermediate
+    /// buffer. Only the bytes of the message are consumed from `reader`.
+    ///
+    /// Returns [`MlsMessageError::UnableToDecode`] if the message could not be
+    /// decoded.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, MlsMessageError> {
+        Self::tls_deserialize(reader).map_err(|_| MlsMessageError::UnableToDecode)
+    }
+
+    /// Deserializes a message that was written with
+    /// [`MlsMessageOut::write_length_prefixed_to()`] from `reader`.
+    ///
+    /// Returns [`MlsMessageError::MessageTooLarge`] if the length prefix
+    /// exceeds `max_length`. In this case, only the length prefix is consumed
+    /// from `reader`.
+    ///
+    /// Returns [`MlsMessageError::UnableToDecode`] if the message could not be
+    /// decoded or does not match its length prefix. In this case, the rest of
+    /// the message is skipped such that the next message can be read from
+    /// `reader`.
+    pub fn read_length_prefixed_from<R: Read>(
+        reader: &mut R,
+        max_length: usize,
+    ) -> Result<Self, MlsMessageError> {
+        let mut length = [0u8; LENGTH_PREFIX_LENGTH];
+        reader
+            .read_exact(&mut length)
+            .map_err(|_| MlsMessageError::UnableToDecode)?;
+        let length = u32::from_be_bytes(length);
+        if length as usize > max_length {
+            return Err(MlsMessageError::MessageTooLarge);
+        }
+
+        let mut frame = reader.by_ref().take(length.into());
+        let message = Self::read_from(&mut frame);
+        // Skip whatever is left of the frame, such that the reader is
+        // positioned at the next message.
+        let remaining =
+            io::copy(&mut frame, &mut io::sink()).map_err(|_| MlsMessageError::UnableToDecode)?;
+        if remaining != 0 {
+            return Err(MlsMessageError::UnableToDecode);
+        }
+        message
+    }
+
     /// Returns the wire format.
     pub fn wire_format(&self) -> WireFormat {
         match self.body {
@@ -105,6 +164,55 @@ impl MlsMessageIn {
         }
     }
 
+    /// Returns the protocol version of the message.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    /// Returns the group ID of a [`PublicMessage`] or [`PrivateMessage`].
+    ///
+    /// Returns `None` for all other messages.
+    pub fn group_id(&self) -> Option<&GroupId> {
+        match &self.body {
+            MlsMessageInBody::PublicMessage(m) => Some(m.group_id()),
+            MlsMessageInBody::PrivateMessage(m) => Some(m.group_id()),
+            _ => None,
+        }
+    }
+
+    /// Returns the epoch of a [`PublicMessage`] or [`PrivateMessage`].
+    ///
+    /// Returns `None` for all other messages.
+    pub fn epoch(&self) -> Option<GroupEpoch> {
+        match &self.body {
+            MlsMessageInBody::PublicMessage(m) => Some(m.epoch()),
+            MlsMessageInBody::PrivateMessage(m) => Some(m.epoch()),
+            _ => None,
+        }
+    }
+
+    /// Returns the content type of a [`PublicMessage`] or [`PrivateMessage`].
+    ///
+    /// Returns `None` for all other messages.
+    pub fn content_type(&self) -> Option<ContentType> {
+        match &self.body {
+            MlsMessageInBody::PublicMessage(m) => Some(m.content_type()),
+            MlsMessageInBody::PrivateMessage(m) => Some(m.content_type()),
+            _ => None,
+        }
+    }
+
+    /// Returns the (unverified) sender of a [`PublicMessage`].
+    ///
+    /// Returns `None` for all other messages. The sender of a
+    /// [`PrivateMessage`] is encrypted and only known after processing.
+    pub fn sender(&self) -> Option<&Sender> {
+        match &self.body {
+            MlsMessageInBody::PublicMessage(m) => Some(m.sender()),
+            _ => None,
+        }
+    }
+
     /// Extract the content of an [`MlsMessageIn`] after deserialization for use
     /// with the [`MlsGroup`] API.
     pub fn extract(self) -> MlsMessageInBody {
@@ -145,7 +253,11 @@ impl MlsMessageIn {
         }
     }
 
-    #[cfg(any(feature = "test-utils", test))]
+    /// Convert this message into a [`ProtocolMessage`] for use with
+    /// [`MlsGroup::process_message()`].
+    ///
+    /// Returns `None` if this message is neither a [`PublicMessage`] nor a
+    /// [`PrivateMessage`].
     pub fn into_protocol_message(self) -> Option<ProtocolMessage> {
         match self.body {
             MlsMessageInBody::PublicMessage(m) => Some(m.into()),
@@ -154,7 +266,10 @@ impl MlsMessageIn {
         }
     }
 
-    #[cfg(any(feature = "test-utils", test))]
+    /// Convert this message into a [`VerifiableGroupInfo`], e.g., for use
+    /// with [`MlsGroup::join_by_external_commit()`].
+    ///
+    /// Returns `None` if this message is not a group info message.
     pub fn into_verifiable_group_info(self) -> Option<VerifiableGroupInfo> {
         match self.body {
             MlsMessageInBody::GroupInfo(group_info) => Some(group_info),
@@ -207,6 +322,16 @@ impl ProtocolMessage {
         }
     }
 
+    /// Returns the (unverified) sender of a [`PublicMessage`].
+    ///
+    /// Returns `None` for a [`PrivateMessage`], whose sender is encrypted.
+    pub fn sender(&self) -> Option<&Sender> {
+        match self {
+            ProtocolMessage::PrivateMessage(_) => None,
+            ProtocolMessage::PublicMessage(ref m) => Some(m.sender()),
+        }
+    }
+
     /// Returns `true` if this is either an external proposal or external commit
     pub fn is_external(&self) -> bool {
         match &self {
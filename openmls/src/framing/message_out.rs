@@ -7,7 +7,9 @@
 //!
 //! The [`MlsMessageOut`] struct is meant to be serialized upon its return from
 //! a function of the `MlsGroup` API so that it can be sent to the DS.
-use tls_codec::Serialize;
+use std::io::Write;
+
+use tls_codec::{Serialize, Size};
 
 use super::*;
 
@@ -19,6 +21,7 @@ use crate::messages::group_info::VerifiableGroupInfo;
 /// An [`MlsMessageOut`] is typically returned from an [`MlsGroup`] function and
 /// meant to be serialized and sent to the DS.
 #[derive(Debug, Clone, PartialEq, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "serde-types", derive(serde::Serialize, serde::Deserialize))]
 pub struct MlsMessageOut {
     pub(crate) version: ProtocolVersion,
     pub(crate) body: MlsMessageOutBody,
@@ -32,16 +35,16 @@ pub struct MlsMessageOut {
 /// where `wire_format` contradicts the variant given in `body`.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     // ... continued from [MlsMessage] ...
 ///
 ///     WireFormat wire_format;
 ///     select (MLSMessage.wire_format) {
-///         case mls_plaintext:
-///             PublicMessage plaintext;
-///         case mls_ciphertext:
-///             PrivateMessage ciphertext;
+///         case mls_public_message:
+///             PublicMessage public_message;
+///         case mls_private_message:
+///             PrivateMessage private_message;
 ///         case mls_welcome:
 ///             Welcome welcome;
 ///         case mls_group_info:
@@ -53,6 +56,7 @@ pub struct MlsMessageOut {
 /// ```
 #[derive(Debug, PartialEq, Clone, TlsSerialize, TlsSize)]
 #[repr(u16)]
+#[cfg_attr(feature = "serde-types", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum MlsMessageOutBody {
     /// Plaintext message
     #[tls_codec(discriminant = 1)]
@@ -132,6 +136,42 @@ impl MlsMessageOut {
         self.tls_serialize_detached()
             .map_err(|_| MlsMessageError::UnableToEncode)
     }
+
+    /// Appends the serialized message to `buffer`. Unlike
+    /// [`MlsMessageOut::to_bytes()`], this allows reusing the same buffer for
+    /// many messages. Returns the number of bytes written or
+    /// [`MlsMessageError::UnableToEncode`] on failure.
+    pub fn serialize_into(&self, buffer: &mut Vec<u8>) -> Result<usize, MlsMessageError> {
+        buffer.reserve(self.tls_serialized_len());
+        self.write_to(buffer)
+    }
+
+    /// Serializes the message directly into `writer`, without an intermediate
+    /// buffer. Returns the number of bytes written or
+    /// [`MlsMessageError::UnableToEncode`] on failure.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<usize, MlsMessageError> {
+        self.tls_serialize(writer)
+            .map_err(|_| MlsMessageError::UnableToEncode)
+    }
+
+    /// Serializes the message into `writer`, prefixed with its length as a
+    /// big-endian `u32`. This framing allows sending messages over stream
+    /// transports, where they can be read with
+    /// [`MlsMessageIn::read_length_prefixed_from()`].
+    ///
+    /// Returns the number of bytes written, including the length prefix, or
+    /// [`MlsMessageError::UnableToEncode`] on failure.
+    pub fn write_length_prefixed_to<W: Write>(
+        &self,
+        writer: &mut W,
+    ) -> Result<usize, MlsMessageError> {
+        let length = u32::try_from(self.tls_serialized_len())
+            .map_err(|_| MlsMessageError::UnableToEncode)?;
+        writer
+            .write_all(&length.to_be_bytes())
+            .map_err(|_| MlsMessageError::UnableToEncode)?;
+        Ok(LENGTH_PREFIX_LENGTH + self.write_to(writer)?)
+    }
 }
 
 // Convenience functions for tests and test-utils
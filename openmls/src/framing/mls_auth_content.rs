@@ -18,15 +18,14 @@ use super::{
 };
 use crate::{
     binary_tree::LeafNodeIndex,
-    ciphersuite::signable::{Signable, SignedStruct},
-    error::LibraryError,
+    ciphersuite::signable::{Signable, SignatureError, SignedStruct},
     extensions::SenderExtensionIndex,
 };
 
 /// 7.1 Content Authentication
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 ///
 /// struct {
 ///    /* SignWithLabel(., "FramedContentTBS", FramedContentTBS) */
@@ -45,6 +44,7 @@ use crate::{
 ///} FramedContentAuthData;
 /// ```
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct FramedContentAuthData {
     pub(super) signature: Signature,
     pub(super) confirmation_tag: Option<ConfirmationTag>,
@@ -71,7 +71,7 @@ impl FramedContentAuthData {
 /// 6 Message Framing
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 ///
 /// struct {
 ///     WireFormat wire_format;
@@ -95,7 +95,7 @@ impl AuthenticatedContent {
         body: FramedContentBody,
         context: &GroupContext,
         signer: &impl Signer,
-    ) -> Result<Self, LibraryError> {
+    ) -> Result<Self, SignatureError> {
         let mut content_tbs = FramedContentTbs::new(
             framing_parameters.wire_format(),
             context.group_id().clone(),
@@ -108,13 +108,11 @@ impl AuthenticatedContent {
         if matches!(sender, Sender::NewMemberCommit | Sender::Member(_)) {
             let serialized_context = context
                 .tls_serialize_detached()
-                .map_err(LibraryError::missing_bound_check)?;
+                .map_err(|_| SignatureError::SigningError)?;
             content_tbs = content_tbs.with_context(serialized_context);
         }
 
-        content_tbs
-            .sign(signer)
-            .map_err(|_| LibraryError::custom("Signing failed"))
+        content_tbs.sign(signer)
     }
 
     /// This constructor builds an `AuthenticatedContent` containing an application
@@ -125,7 +123,7 @@ impl AuthenticatedContent {
         application_message: &[u8],
         context: &GroupContext,
         signer: &impl Signer,
-    ) -> Result<Self, LibraryError> {
+    ) -> Result<Self, SignatureError> {
         let framing_parameters =
             FramingParameters::new(authenticated_data, WireFormat::PrivateMessage);
         Self::new_and_sign(
@@ -145,7 +143,7 @@ impl AuthenticatedContent {
         proposal: Proposal,
         context: &GroupContext,
         signer: &impl Signer,
-    ) -> Result<Self, LibraryError> {
+    ) -> Result<Self, SignatureError> {
         Self::new_and_sign(
             framing_parameters,
             Sender::Member(sender_leaf_index),
@@ -161,7 +159,7 @@ impl AuthenticatedContent {
         group_id: GroupId,
         epoch: GroupEpoch,
         signer: &impl Signer,
-    ) -> Result<Self, LibraryError> {
+    ) -> Result<Self, SignatureError> {
         let body = FramedContentBody::Proposal(proposal);
 
         let content_tbs = FramedContentTbs::new(
@@ -173,9 +171,7 @@ impl AuthenticatedContent {
             body,
         );
 
-        content_tbs
-            .sign(signer)
-            .map_err(|_| LibraryError::custom("Signing failed"))
+        content_tbs.sign(signer)
     }
 
     /// This constructor builds an `PublicMessage` containing an External Proposal.
@@ -185,7 +181,7 @@ impl AuthenticatedContent {
         epoch: GroupEpoch,
         signer: &impl Signer,
         sender_index: SenderExtensionIndex,
-    ) -> Result<Self, LibraryError> {
+    ) -> Result<Self, SignatureError> {
         let body = FramedContentBody::Proposal(proposal);
 
         let content_tbs = FramedContentTbs::new(
@@ -197,9 +193,7 @@ impl AuthenticatedContent {
             body,
         );
 
-        content_tbs
-            .sign(signer)
-            .map_err(|_| LibraryError::custom("Signing failed"))
+        content_tbs.sign(signer)
     }
 
     /// This constructor builds an `PublicMessage` containing a Commit. If the
@@ -213,7 +207,7 @@ impl AuthenticatedContent {
         commit: Commit,
         context: &GroupContext,
         signer: &impl Signer,
-    ) -> Result<Self, LibraryError> {
+    ) -> Result<Self, SignatureError> {
         Self::new_and_sign(
             framing_parameters,
             sender,
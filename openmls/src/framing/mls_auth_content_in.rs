@@ -14,7 +14,7 @@ use tls_codec::Serialize as TlsSerializeTrait;
 
 use super::{mls_auth_content::*, mls_content_in::*, *};
 use crate::{
-    ciphersuite::signable::{SignedStruct, Verifiable, VerifiedStruct},
+    ciphersuite::signable::{signature_labels, SignedStruct, Verifiable, VerifiedStruct},
     credentials::CredentialWithKey,
     group::errors::ValidationError,
     messages::proposals_in::ProposalIn,
@@ -33,7 +33,7 @@ mod private_mod {
 /// 6 Message Framing
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 ///
 /// struct {
 ///     WireFormat wire_format;
@@ -53,7 +53,7 @@ impl AuthenticatedContentIn {
     pub(crate) fn validate(
         self,
         ciphersuite: Ciphersuite,
-        crypto: &impl OpenMlsCrypto,
+        crypto: &(impl OpenMlsCrypto + Sync),
         sender_context: Option<SenderContext>,
     ) -> Result<AuthenticatedContent, ValidationError> {
         Ok(AuthenticatedContent {
@@ -197,7 +197,7 @@ impl Verifiable for VerifiableAuthenticatedContentIn {
     }
 
     fn label(&self) -> &str {
-        "FramedContentTBS"
+        signature_labels::FRAMED_CONTENT
     }
 }
 
@@ -2,7 +2,7 @@
 //! such as [`FramedContentTbs`], as well as their implementations.
 
 use crate::{
-    ciphersuite::signable::Signable,
+    ciphersuite::signable::{signature_labels, Signable},
     error::LibraryError,
     group::{GroupEpoch, GroupId},
     messages::{proposals::Proposal, Commit},
@@ -20,7 +20,7 @@ use serde::{Deserialize, Serialize};
 use tls_codec::{Serialize as TlsSerializeTrait, Size, TlsSerialize, TlsSize, VLBytes};
 
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     opaque group_id<V>;
 ///     uint64 epoch;
@@ -47,7 +47,7 @@ impl From<AuthenticatedContent> for FramedContent {
 }
 
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     // ... continued from [FramedContent] ...
 ///
@@ -108,7 +108,7 @@ impl FramedContentBody {
 /// 7.2 Encoding and Decoding a Plaintext
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 ///
 /// struct {
 ///   FramedContentTBS tbs;
@@ -152,7 +152,7 @@ impl Signable for FramedContentTbs {
     }
 
     fn label(&self) -> &str {
-        "FramedContentTBS"
+        signature_labels::FRAMED_CONTENT
     }
 }
 
@@ -2,7 +2,7 @@
 //! such as [`FramedContentTbsIn`], as well as their implementations.
 
 use crate::{
-    ciphersuite::signable::Signable,
+    ciphersuite::signable::{signature_labels, Signable},
     error::LibraryError,
     framing::SenderContext,
     group::{errors::ValidationError, GroupEpoch, GroupId},
@@ -26,7 +26,7 @@ use tls_codec::{
 };
 
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     opaque group_id<V>;
 ///     uint64 epoch;
@@ -39,6 +39,7 @@ use tls_codec::{
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct FramedContentIn {
     pub(super) group_id: GroupId,
     pub(super) epoch: GroupEpoch,
@@ -52,7 +53,7 @@ impl FramedContentIn {
     pub(crate) fn validate(
         self,
         ciphersuite: Ciphersuite,
-        crypto: &impl OpenMlsCrypto,
+        crypto: &(impl OpenMlsCrypto + Sync),
         sender_context: Option<SenderContext>,
     ) -> Result<FramedContent, ValidationError> {
         Ok(FramedContent {
@@ -72,7 +73,7 @@ impl From<AuthenticatedContentIn> for FramedContentIn {
 }
 
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     // ... continued from [FramedContent] ...
 ///
@@ -91,6 +92,7 @@ impl From<AuthenticatedContentIn> for FramedContentIn {
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) enum FramedContentBodyIn {
     #[tls_codec(discriminant = 1)]
     Application(VLBytes),
@@ -129,7 +131,7 @@ impl FramedContentBodyIn {
     pub(crate) fn validate(
         self,
         ciphersuite: Ciphersuite,
-        crypto: &impl OpenMlsCrypto,
+        crypto: &(impl OpenMlsCrypto + Sync),
         sender_context: Option<SenderContext>,
     ) -> Result<FramedContentBody, ValidationError> {
         Ok(match self {
@@ -166,7 +168,7 @@ impl Signable for FramedContentTbsIn {
     }
 
     fn label(&self) -> &str {
-        "FramedContentTBS"
+        signature_labels::FRAMED_CONTENT
     }
 }
 
@@ -38,6 +38,7 @@
 //! ```
 //!
 //!  - [`MlsMessageIn`]/[`MlsMessageOut`]: Unified message type for incoming & outgoing MLS messages
+//!  - [`MlsMessageBundleIn`]/[`MlsMessageBundle`]: Several MLS messages that are sent to the DS as a single upload
 //!  - [`ApplicationMessage`]: Application message received through a [`ProcessedMessage`]
 
 use serde::{Deserialize, Serialize};
@@ -53,17 +54,20 @@ use crate::{
 
 pub(crate) mod codec;
 
+pub(crate) mod message_bundle;
 pub(crate) mod message_in;
 pub(crate) mod message_out;
 pub(crate) mod mls_auth_content;
 pub(crate) mod mls_auth_content_in;
 pub(crate) mod mls_content;
 pub(crate) mod mls_content_in;
+pub(crate) mod padding;
 pub(crate) mod private_message;
 pub(crate) mod private_message_in;
 pub(crate) mod public_message;
 pub(crate) mod public_message_in;
 pub(crate) mod sender;
+pub(crate) mod stateless_validation;
 pub(crate) mod validation;
 pub(crate) use errors::*;
 
@@ -83,13 +87,16 @@ pub(crate) use sender::*;
 // Public
 pub mod errors;
 
+pub use message_bundle::*;
 pub use message_in::*;
 pub use message_out::*;
+pub use padding::*;
 pub use private_message::*;
 pub use private_message_in::*;
 pub use public_message::*;
 pub use public_message_in::*;
 pub use sender::*;
+pub use stateless_validation::*;
 pub use validation::*;
 
 // Tests
@@ -98,16 +105,16 @@ pub(crate) mod test_framing;
 
 /// Wire format of MLS messages.
 ///
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// | Value           | Name                     | Recommended | Reference |
 /// |-----------------|--------------------------|-------------|-----------|
-/// | 0x0000          | RESERVED                 | N/A         | RFC XXXX  |
-/// | 0x0001          | mls_plaintext            | Y           | RFC XXXX  |
-/// | 0x0002          | mls_ciphertext           | Y           | RFC XXXX  |
-/// | 0x0003          | mls_welcome              | Y           | RFC XXXX  |
-/// | 0x0004          | mls_group_info           | Y           | RFC XXXX  |
-/// | 0x0005          | mls_key_package          | Y           | RFC XXXX  |
-/// | 0xf000 - 0xffff | Reserved for Private Use | N/A         | RFC XXXX  |
+/// | 0x0000          | RESERVED                 | N/A         | RFC 9420  |
+/// | 0x0001          | mls_public_message       | Y           | RFC 9420  |
+/// | 0x0002          | mls_private_message      | Y           | RFC 9420  |
+/// | 0x0003          | mls_welcome              | Y           | RFC 9420  |
+/// | 0x0004          | mls_group_info           | Y           | RFC 9420  |
+/// | 0x0005          | mls_key_package          | Y           | RFC 9420  |
+/// | 0xf000 - 0xffff | Reserved for Private Use | N/A         | RFC 9420  |
 #[derive(
     PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
@@ -150,7 +157,7 @@ impl<'a> FramingParameters<'a> {
 }
 
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// enum {
 ///     reserved(0),
 ///     application(1),
@@ -163,6 +170,7 @@ impl<'a> FramingParameters<'a> {
     PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ContentType {
     /// Application message
     Application = 1,
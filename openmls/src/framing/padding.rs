@@ -0,0 +1,102 @@
+//! # Padding
+//!
+//! The content of a [`PrivateMessage`](super::PrivateMessage) is padded with
+//! zero bytes before encryption to hide its exact length. How much padding is
+//! added is determined by the [`PaddingPolicy`].
+
+use serde::{Deserialize, Serialize};
+
+/// Padding policy for [`PrivateMessage`](super::PrivateMessage)s.
+///
+/// The padding is chosen such that the length of the ciphertext, i.e., the
+/// encrypted content including the AEAD tag, matches the policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PaddingPolicy {
+    /// No padding is added.
+    #[default]
+    None,
+    /// The ciphertext is padded to a multiple of the given number of bytes. A
+    /// size of `0` disables padding.
+    Multiple(usize),
+    /// The ciphertext is padded to the smallest of the given bucket sizes
+    /// (in bytes) it fits into. Ciphertexts that are larger than all buckets
+    /// are padded to a multiple of the largest bucket.
+    Buckets(Vec<usize>),
+    /// The ciphertext is padded according to the Padmé scheme, which leaks
+    /// at most `O(log log L)` bits of the length `L` and adds at most 12%
+    /// overhead.
+    Padme,
+}
+
+impl PaddingPolicy {
+    /// Returns the number of padding bytes for a ciphertext of `length`
+    /// bytes.
+    pub(crate) fn padding_length(&self, length: usize) -> usize {
+        match self {
+            PaddingPolicy::None => 0,
+            PaddingPolicy::Multiple(size) => multiple_padding_length(length, *size),
+            PaddingPolicy::Buckets(buckets) => {
+                match buckets.iter().filter(|bucket| **bucket >= length).min() {
+                    Some(bucket) => bucket - length,
+                    None => multiple_padding_length(
+                        length,
+                        buckets.iter().max().copied().unwrap_or_default(),
+                    ),
+                }
+            }
+            PaddingPolicy::Padme => padme_length(length) - length,
+        }
+    }
+}
+
+/// Returns the number of bytes needed to pad `length` to a multiple of
+/// `size`.
+fn multiple_padding_length(length: usize, size: usize) -> usize {
+    if size > 0 {
+        (size - (length % size)) % size
+    } else {
+        0
+    }
+}
+
+/// Returns the padded length of `length` according to Padmé.
+///
+/// See "Reducing Metadata Leakage from Encrypted Files and Communication with
+/// PURBs" (Nikitin et al., PETS 2019).
+fn padme_length(length: usize) -> usize {
+    if length < 2 {
+        return length;
+    }
+    // E = floor(log2(L))
+    let exponent = usize::BITS - 1 - length.leading_zeros();
+    // S = floor(log2(E)) + 1
+    let significant_bits = u32::BITS - exponent.leading_zeros();
+    let last_bits = exponent - significant_bits;
+    let bit_mask = (1usize << last_bits) - 1;
+    (length + bit_mask) & !bit_mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padding_lengths() {
+        assert_eq!(PaddingPolicy::None.padding_length(17), 0);
+        assert_eq!(PaddingPolicy::Multiple(0).padding_length(17), 0);
+        assert_eq!(PaddingPolicy::Multiple(16).padding_length(17), 15);
+        assert_eq!(PaddingPolicy::Multiple(16).padding_length(32), 0);
+
+        let buckets = PaddingPolicy::Buckets(vec![1024, 64, 256]);
+        assert_eq!(buckets.padding_length(10), 54);
+        assert_eq!(buckets.padding_length(64), 0);
+        assert_eq!(buckets.padding_length(65), 191);
+        assert_eq!(buckets.padding_length(1500), 548);
+        assert_eq!(PaddingPolicy::Buckets(vec![]).padding_length(17), 0);
+
+        // Padmé rounds to the most significant bits of the length.
+        for (length, padded) in [(0, 0), (1, 1), (9, 10), (100, 104), (1000, 1024)] {
+            assert_eq!(PaddingPolicy::Padme.padding_length(length) + length, padded);
+        }
+    }
+}
@@ -1,5 +1,4 @@
 use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
-use std::io::Write;
 use tls_codec::{Serialize, Size, TlsSerialize, TlsSize};
 
 use super::{
@@ -19,7 +18,7 @@ use super::*;
 /// Service.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     opaque group_id<V>;
 ///     uint64 epoch;
@@ -30,6 +29,7 @@ use super::*;
 /// } PrivateMessage;
 /// ```
 #[derive(Debug, PartialEq, Eq, Clone, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "serde-types", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrivateMessage {
     pub(crate) group_id: GroupId,
     pub(crate) epoch: GroupEpoch,
@@ -74,7 +74,7 @@ impl PrivateMessage {
         ciphersuite: Ciphersuite,
         backend: &impl OpenMlsCryptoProvider,
         message_secrets: &mut MessageSecrets,
-        padding_size: usize,
+        padding_policy: &PaddingPolicy,
     ) -> Result<PrivateMessage, MessageEncryptionError> {
         log::debug!("PrivateMessage::try_from_authenticated_content");
         log::trace!("  ciphersuite: {}", ciphersuite);
@@ -88,7 +88,7 @@ impl PrivateMessage {
             ciphersuite,
             backend,
             message_secrets,
-            padding_size,
+            padding_policy,
         )
     }
 
@@ -106,7 +106,7 @@ impl PrivateMessage {
             ciphersuite,
             backend,
             message_secrets,
-            padding_size,
+            &PaddingPolicy::Multiple(padding_size),
         )
     }
 
@@ -125,7 +125,7 @@ impl PrivateMessage {
             ciphersuite,
             backend,
             message_secrets,
-            padding_size,
+            &PaddingPolicy::Multiple(padding_size),
         )
     }
 
@@ -137,7 +137,7 @@ impl PrivateMessage {
         ciphersuite: Ciphersuite,
         backend: &impl OpenMlsCryptoProvider,
         message_secrets: &mut MessageSecrets,
-        padding_size: usize,
+        padding_policy: &PaddingPolicy,
     ) -> Result<PrivateMessage, MessageEncryptionError> {
         let sender_index = if let Some(index) = public_message.sender().as_member() {
             index
@@ -185,7 +185,7 @@ impl PrivateMessage {
                 backend,
                 &Self::encode_padded_ciphertext_content_detached(
                     public_message,
-                    padding_size,
+                    padding_policy,
                     ciphersuite.mac_length(),
                 )
                 .map_err(LibraryError::missing_bound_check)?,
@@ -256,40 +256,64 @@ impl PrivateMessage {
     /// Encodes the `PrivateMessageContent` struct with padding.
     fn encode_padded_ciphertext_content_detached(
         authenticated_content: &AuthenticatedContent,
-        padding_size: usize,
+        padding_policy: &PaddingPolicy,
         mac_len: usize,
     ) -> Result<Vec<u8>, tls_codec::Error> {
-        let plaintext_length = authenticated_content
-            .content()
-            .serialized_len_without_type()
-            + authenticated_content.auth.tls_serialized_len();
+        let plaintext_length = Self::content_length(authenticated_content);
 
-        let padding_length = if padding_size > 0 {
-            // Calculate padding block size.
-            // Only the AEAD tag is added.
-            let padding_offset = plaintext_length + mac_len;
-            // Return padding block size
-            (padding_size - (padding_offset % padding_size)) % padding_size
-        } else {
-            0
-        };
+        // Only the AEAD tag is added to the padded content.
+        let padding_length = padding_policy.padding_length(plaintext_length + mac_len);
 
         // Persist all initial fields manually (avoids cloning them)
-        let buffer = &mut Vec::with_capacity(plaintext_length + padding_length);
+        let mut buffer = Vec::with_capacity(plaintext_length + padding_length);
 
         // The `content` field is serialized without the `content_type`, which
         // is not part of the struct as per MLS spec.
         authenticated_content
             .content()
-            .serialize_without_type(buffer)?;
-        authenticated_content.auth.tls_serialize(buffer)?;
-        // Note: The `tls_codec::Serialize` implementation for `&[u8]` prepends the length.
-        // We do not want this here and thus use the "raw" `write_all` method.
-        buffer
-            .write_all(&vec![0u8; padding_length])
-            .map_err(|_| Error::EncodingError("Failed to write padding.".into()))?;
+            .serialize_without_type(&mut buffer)?;
+        authenticated_content.auth.tls_serialize(&mut buffer)?;
+        // The padding consists of zero bytes without a length prefix.
+        buffer.resize(buffer.len() + padding_length, 0);
+
+        Ok(buffer)
+    }
+
+    /// Returns the length of the serialized `PrivateMessageContent` of the
+    /// given `AuthenticatedContent` without padding.
+    pub(crate) fn content_length(authenticated_content: &AuthenticatedContent) -> usize {
+        authenticated_content
+            .content()
+            .serialized_len_without_type()
+            + authenticated_content.auth.tls_serialized_len()
+    }
 
-        Ok(buffer.to_vec())
+    /// Returns the length of a serialized `PrivateMessage` in the group with
+    /// the given `group_id` that carries `authenticated_data_length` bytes of
+    /// authenticated data and a `PrivateMessageContent` of `content_length`
+    /// bytes (without padding).
+    pub(crate) fn serialized_len(
+        group_id: &GroupId,
+        authenticated_data_length: usize,
+        content_length: usize,
+        ciphersuite: Ciphersuite,
+        padding_policy: &PaddingPolicy,
+    ) -> usize {
+        let mac_len = ciphersuite.mac_length();
+        let ciphertext_length =
+            content_length + padding_policy.padding_length(content_length + mac_len) + mac_len;
+        // The sender data consists of the leaf index (4 bytes), the generation
+        // (4 bytes) and the reuse guard.
+        let encrypted_sender_data_length = 4 + 4 + REUSE_GUARD_BYTES + mac_len;
+
+        group_id.tls_serialized_len()
+            // epoch
+            + 8
+            // content_type
+            + 1
+            + vlbytes_len(authenticated_data_length)
+            + vlbytes_len(encrypted_sender_data_length)
+            + vlbytes_len(ciphertext_length)
     }
 
     /// Get the cipher text bytes as slice.
@@ -299,6 +323,21 @@ impl PrivateMessage {
     }
 }
 
+/// Returns the serialized length of a variable-length vector with `length`
+/// bytes, i.e., including its length prefix.
+pub(crate) fn vlbytes_len(length: usize) -> usize {
+    let length_prefix = if length <= 0x3f {
+        1
+    } else if length <= 0x3fff {
+        2
+    } else if length <= 0x3fff_ffff {
+        4
+    } else {
+        8
+    };
+    length_prefix + length
+}
+
 // === Helper structs ===
 
 /// PrivateMessageContent
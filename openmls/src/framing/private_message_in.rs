@@ -20,7 +20,7 @@ use super::*;
 /// Service.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     opaque group_id<V>;
 ///     uint64 epoch;
@@ -31,6 +31,8 @@ use super::*;
 /// } PrivateMessage;
 /// ```
 #[derive(Debug, PartialEq, Eq, Clone, TlsSerialize, TlsSize, TlsDeserialize)]
+#[cfg_attr(feature = "serde-types", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PrivateMessageIn {
     group_id: GroupId,
     epoch: GroupEpoch,
@@ -120,7 +122,6 @@ impl PrivateMessageIn {
             )
             .map_err(|_| {
                 log::error!("  Ciphertext decryption error");
-                debug_assert!(false, "Ciphertext decryption failed");
                 MessageDecryptionError::AeadError
             })?;
         log_content!(
@@ -158,12 +159,17 @@ impl PrivateMessageIn {
                 sender_data.generation,
                 sender_ratchet_configuration,
             )
-            .map_err(|_| {
+            .map_err(|e| {
                 log::error!(
                     "  Ciphertext generation out of bounds {}",
                     sender_data.generation
                 );
-                MessageDecryptionError::GenerationOutOfBound
+                // Keep track of senders that are too far ahead, since the
+                // group can't catch up with them anymore.
+                match e {
+                    SecretTreeError::TooDistantInTheFuture => e.into(),
+                    _ => MessageDecryptionError::GenerationOutOfBound,
+                }
             })?;
         // Prepare the nonce by xoring with the reuse guard.
         let prepared_nonce = ratchet_nonce.xor_with_reuse_guard(&sender_data.reuse_guard);
@@ -219,7 +225,7 @@ impl PrivateMessageIn {
 /// PrivateMessageContent
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     select (PrivateMessage.content_type) {
 ///         case application:
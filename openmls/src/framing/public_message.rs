@@ -19,6 +19,7 @@ use crate::{error::LibraryError, versions::ProtocolVersion};
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct MembershipTag(pub(crate) Mac);
 
 /// [`PublicMessage`] is a framing structure for MLS messages. It can contain
@@ -27,7 +28,7 @@ pub(crate) struct MembershipTag(pub(crate) Mac);
 /// 9. Message framing
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 ///
 /// struct {
 ///     FramedContent content;
@@ -175,7 +176,7 @@ impl From<PublicMessage> for FramedContentTbs {
 // -------------------------------------------------------------------------------------------------
 
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     WireFormat wire_format;
 ///     FramedContent content; /* with content_type == commit */
@@ -228,7 +229,7 @@ impl<'a> TryFrom<&'a AuthenticatedContent> for ConfirmedTranscriptHashInput<'a>
 // -------------------------------------------------------------------------------------------------
 
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     MAC confirmation_tag;
 /// } InterimTranscriptHashInput;
@@ -3,7 +3,12 @@
 //! A PublicMessageIn is a framing structure for MLS messages. It can contain
 //! Proposals, Commits and application messages.
 
-use crate::{error::LibraryError, group::errors::ValidationError, versions::ProtocolVersion};
+use crate::{
+    ciphersuite::hash_ref::{make_proposal_ref, ProposalRef},
+    error::LibraryError,
+    group::errors::ValidationError,
+    versions::ProtocolVersion,
+};
 
 use super::{
     mls_auth_content::FramedContentAuthData,
@@ -13,7 +18,7 @@ use super::{
     *,
 };
 
-use openmls_traits::OpenMlsCryptoProvider;
+use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite, OpenMlsCryptoProvider};
 use std::{
     convert::TryFrom,
     io::{Read, Write},
@@ -28,7 +33,7 @@ use tls_codec::{
 /// 9. Message framing
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 ///
 /// struct {
 ///     FramedContent content;
@@ -37,6 +42,7 @@ use tls_codec::{
 /// } PublicMessage;
 /// ```
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PublicMessageIn {
     pub(crate) content: FramedContentIn,
     pub(crate) auth: FramedContentAuthData,
@@ -103,15 +109,54 @@ impl PublicMessageIn {
     }
 
     /// Returns the [`ContentType`] of the message.
-    pub(crate) fn content_type(&self) -> ContentType {
+    pub fn content_type(&self) -> ContentType {
         self.content.body.content_type()
     }
 
     /// Get the sender of this message.
-    pub(crate) fn sender(&self) -> &Sender {
+    ///
+    /// Note that the sender is not authenticated until the message has been
+    /// processed by the group.
+    pub fn sender(&self) -> &Sender {
         &self.content.sender
     }
 
+    /// Computes the [`ProposalRef`] of the proposal in this message, i.e.,
+    /// the reference a group computes when it processes the message and that
+    /// a later commit uses to cover the proposal by reference.
+    ///
+    /// This allows applications and delivery services to correlate stored
+    /// proposals with commits without processing them through a group. The
+    /// `ciphersuite` must be the ciphersuite of the group. Note that the
+    /// message is not authenticated.
+    ///
+    /// Returns [`ComputeProposalRefError::NotAProposal`] if the message
+    /// doesn't contain a proposal.
+    pub fn proposal_ref(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+        ciphersuite: Ciphersuite,
+    ) -> Result<ProposalRef, ComputeProposalRefError> {
+        if self.content_type() != ContentType::Proposal {
+            return Err(ComputeProposalRefError::NotAProposal);
+        }
+
+        // The reference is computed over the `AuthenticatedContent`, i.e.,
+        // the message without the membership tag.
+        let mut authenticated_content = WireFormat::PublicMessage
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        self.content
+            .tls_serialize(&mut authenticated_content)
+            .map_err(LibraryError::missing_bound_check)?;
+        self.auth
+            .tls_serialize(&mut authenticated_content)
+            .map_err(LibraryError::missing_bound_check)?;
+
+        make_proposal_ref(&authenticated_content, ciphersuite, crypto)
+            .map_err(|e| LibraryError::unexpected_crypto_error(e).into())
+    }
+
     #[cfg(test)]
     pub(crate) fn set_membership_tag(
         &mut self,
@@ -171,12 +216,12 @@ impl PublicMessageIn {
     }
 
     /// Get the group epoch.
-    pub(crate) fn epoch(&self) -> GroupEpoch {
+    pub fn epoch(&self) -> GroupEpoch {
         self.content.epoch
     }
 
     /// Get the [`GroupId`].
-    pub(crate) fn group_id(&self) -> &GroupId {
+    pub fn group_id(&self) -> &GroupId {
         &self.content.group_id
     }
 
@@ -216,7 +261,7 @@ impl From<PublicMessageIn> for FramedContentTbsIn {
 /// 9.2 Transcript Hashes
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 ///
 /// struct {
 ///    WireFormat wire_format;
@@ -8,7 +8,7 @@ use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
 /// All possible sender types according to the MLS protocol spec.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// enum {
 ///     reserved(0),
 ///     member(1),
@@ -18,7 +18,7 @@ use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
 ///     (255)
 /// } SenderType;
 ///
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// struct {
 ///     SenderType sender_type;
 ///     select (Sender.sender_type) {
@@ -36,6 +36,7 @@ use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Sender {
     /// The sender is a member of the group
     #[tls_codec(discriminant = 1)]
@@ -0,0 +1,214 @@
+//! # Stateless message validation
+//!
+//! This module contains [`validate_message_syntax()`], which performs all
+//! checks on an incoming message that neither require the secrets nor the
+//! public state of a group. It is meant for edge nodes of a delivery service
+//! that only know the group ID, the current epoch and the wire format policy
+//! of a group.
+
+use tls_codec::Deserialize;
+
+use super::{
+    errors::SyntacticValidationError, mls_content_in::FramedContentBodyIn, ContentType,
+    MlsMessageIn, ProtocolMessage, Sender, WireFormat,
+};
+use crate::{
+    group::{GroupEpoch, GroupId, WireFormatPolicy},
+    messages::proposals_in::ProposalIn,
+    versions::ProtocolVersion,
+};
+
+/// The context of a group that is required by [`validate_message_syntax()`].
+#[derive(Debug, Clone)]
+pub struct SyntacticValidationContext {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    max_past_epochs: u64,
+    wire_format_policy: WireFormatPolicy,
+}
+
+impl SyntacticValidationContext {
+    /// Creates a new [`SyntacticValidationContext`] for the group with the
+    /// given ID in the given epoch.
+    ///
+    /// By default, application messages of past epochs are rejected. See
+    /// [`Self::with_max_past_epochs()`].
+    pub fn new(group_id: GroupId, epoch: GroupEpoch, wire_format_policy: WireFormatPolicy) -> Self {
+        Self {
+            group_id,
+            epoch,
+            max_past_epochs: 0,
+            wire_format_policy,
+        }
+    }
+
+    /// Accept application messages of up to `max_past_epochs` past epochs.
+    ///
+    /// This should match the
+    /// [`max_past_epochs`](crate::group::MlsGroupConfig::max_past_epochs) of
+    /// the members of the group.
+    pub fn with_max_past_epochs(mut self, max_past_epochs: usize) -> Self {
+        self.max_past_epochs = max_past_epochs as u64;
+        self
+    }
+
+    /// Returns `true` if a message of the given content type and epoch is
+    /// within the epoch window.
+    fn is_in_epoch_window(&self, content_type: ContentType, epoch: GroupEpoch) -> bool {
+        match content_type {
+            ContentType::Application => {
+                epoch <= self.epoch
+                    && epoch.as_u64().saturating_add(self.max_past_epochs) >= self.epoch.as_u64()
+            }
+            _ => epoch == self.epoch,
+        }
+    }
+}
+
+/// The result of a successful [`validate_message_syntax()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntacticValidationReport {
+    wire_format: WireFormat,
+    epoch: GroupEpoch,
+    content_type: ContentType,
+    sender: Option<Sender>,
+}
+
+impl SyntacticValidationReport {
+    /// Returns the wire format of the message.
+    pub fn wire_format(&self) -> WireFormat {
+        self.wire_format
+    }
+
+    /// Returns the epoch of the message.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the content type of the message.
+    pub fn content_type(&self) -> ContentType {
+        self.content_type
+    }
+
+    /// Returns the (unverified) sender of the message.
+    ///
+    /// Returns `None` for a [`PrivateMessage`](super::PrivateMessage), whose
+    /// sender is encrypted.
+    pub fn sender(&self) -> Option<&Sender> {
+        self.sender.as_ref()
+    }
+}
+
+/// Validates the serialized [`MlsMessageIn`] in `bytes` against the given
+/// [`SyntacticValidationContext`], without requiring any group state.
+///
+/// Checks the following semantic validation:
+///  - ValSem002
+///  - ValSem003
+///  - ValSem005
+///  - ValSem007
+///  - ValSem009
+///
+/// Additionally, the message must be a [`ProtocolMessage`] of MLS 1.0 that
+/// complies with the incoming wire format policy, a commit of a
+/// [`Sender::NewMemberCommit`] must contain a path and a proposal of a
+/// [`Sender::NewMemberProposal`] must be an add proposal.
+///
+/// Signatures and membership tags can't be verified without the group state.
+/// Messages that pass this validation can still be rejected by the group.
+pub fn validate_message_syntax(
+    mut bytes: &[u8],
+    context: &SyntacticValidationContext,
+) -> Result<SyntacticValidationReport, SyntacticValidationError> {
+    let message = MlsMessageIn::tls_deserialize(&mut bytes)
+        .map_err(|_| SyntacticValidationError::UnableToDecode)?;
+    if !bytes.is_empty() {
+        return Err(SyntacticValidationError::UnableToDecode);
+    }
+    if message.version() != ProtocolVersion::Mls10 {
+        return Err(SyntacticValidationError::UnsupportedMlsVersion);
+    }
+    let message = message
+        .into_protocol_message()
+        .ok_or(SyntacticValidationError::NotAProtocolMessage)?;
+
+    // ValSem002
+    if message.group_id() != &context.group_id {
+        return Err(SyntacticValidationError::WrongGroupId);
+    }
+
+    // ValSem003
+    let content_type = message.content_type();
+    if !context.is_in_epoch_window(content_type, message.epoch()) {
+        return Err(SyntacticValidationError::WrongEpoch);
+    }
+
+    if !message.is_external()
+        && message.is_handshake_message()
+        && !context
+            .wire_format_policy
+            .incoming_for(content_type)
+            .is_compatible_with(message.wire_format())
+    {
+        return Err(SyntacticValidationError::IncompatibleWireFormat);
+    }
+
+    if let ProtocolMessage::PublicMessage(public_message) = &message {
+        validate_public_message_syntax(
+            public_message.sender(),
+            public_message.content(),
+            public_message.membership_tag().is_some(),
+            public_message.confirmation_tag().is_some(),
+        )?;
+    }
+
+    Ok(SyntacticValidationReport {
+        wire_format: message.wire_format(),
+        epoch: message.epoch(),
+        content_type,
+        sender: message.sender().cloned(),
+    })
+}
+
+fn validate_public_message_syntax(
+    sender: &Sender,
+    content: &FramedContentBodyIn,
+    has_membership_tag: bool,
+    has_confirmation_tag: bool,
+) -> Result<(), SyntacticValidationError> {
+    // ValSem005: Application messages must always be encrypted
+    if let FramedContentBodyIn::Application(_) = content {
+        return Err(SyntacticValidationError::UnencryptedApplicationMessage);
+    }
+
+    // ValSem007
+    if sender.is_member() && !has_membership_tag {
+        return Err(SyntacticValidationError::MissingMembershipTag);
+    }
+
+    // ValSem009
+    if let FramedContentBodyIn::Commit(_) = content {
+        if !has_confirmation_tag {
+            return Err(SyntacticValidationError::MissingConfirmationTag);
+        }
+    }
+
+    match (sender, content) {
+        (Sender::NewMemberCommit, FramedContentBodyIn::Commit(commit)) => {
+            if commit.unverified_credential().is_none() {
+                return Err(SyntacticValidationError::NoPath);
+            }
+        }
+        (Sender::NewMemberCommit, _) => return Err(SyntacticValidationError::NotACommit),
+        (Sender::NewMemberProposal, FramedContentBodyIn::Proposal(ProposalIn::Add(_))) => {}
+        (Sender::NewMemberProposal, _) => {
+            return Err(SyntacticValidationError::NotAnExternalAddProposal)
+        }
+        (Sender::External(_), FramedContentBodyIn::Commit(_)) => {
+            return Err(SyntacticValidationError::InvalidSenderType)
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -42,15 +42,16 @@ fn codec_plaintext(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
         vec![],
         Extensions::empty(),
     );
+    let group_id = GroupId::random(backend);
 
     let serialized_context = group_context
         .tls_serialize_detached()
         .expect("An unexpected error occurred.");
     let signature_input = FramedContentTbs::new(
         WireFormat::PublicMessage,
-        GroupId::random(backend),
+        group_id.clone(),
         1,
-        sender,
+        sender.clone(),
         vec![1, 2, 3].into(),
         FramedContentBody::Application(vec![4, 5, 6].into()),
     )
@@ -72,6 +73,13 @@ fn codec_plaintext(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
         .expect("An unexpected error occurred.");
     let copy = PublicMessageIn::tls_deserialize(&mut enc.as_slice())
         .expect("An unexpected error occurred.");
+
+    // The routing information is available before processing.
+    assert_eq!(copy.sender(), &sender);
+    assert_eq!(copy.group_id(), &group_id);
+    assert_eq!(copy.epoch(), GroupEpoch::from(1));
+    assert_eq!(copy.content_type(), ContentType::Application);
+
     assert_eq!(orig, copy.into());
     assert!(!orig.is_handshake_message());
 }
@@ -283,7 +291,7 @@ fn wire_format_checks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProv
             ciphersuite,
             backend,
             &mut message_secrets,
-            0,
+            &PaddingPolicy::None,
         )
         .expect_err("Could encrypt despite wrong wire format."),
         MessageEncryptionError::WrongWireFormat
@@ -27,15 +27,17 @@ use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite, OpenMlsCryptoPro
 
 use crate::{
     binary_tree::LeafNodeIndex,
-    ciphersuite::signable::Verifiable,
+    ciphersuite::{signable::Verifiable, SignaturePublicKey},
     error::LibraryError,
     extensions::ExternalSendersExtension,
     group::{
         core_group::{proposals::QueuedProposal, staged_commit::StagedCommit},
         errors::ValidationError,
     },
+    key_packages::errors::KeyPackageVerifyError,
+    messages::proposals::Proposal,
     tree::sender_ratchet::SenderRatchetConfiguration,
-    treesync::TreeSync,
+    treesync::{node::leaf_node::TimeProvider, TreeSync},
 };
 
 use self::mls_group::errors::ProcessMessageError;
@@ -269,7 +271,7 @@ impl UnverifiedMessage {
     pub(crate) fn verify(
         self,
         ciphersuite: Ciphersuite,
-        crypto: &impl OpenMlsCrypto,
+        crypto: &(impl OpenMlsCrypto + Sync),
     ) -> Result<(AuthenticatedContent, Credential), ProcessMessageError> {
         let content: AuthenticatedContentIn = self
             .verifiable_content
@@ -294,6 +296,7 @@ pub struct ProcessedMessage {
     authenticated_data: Vec<u8>,
     content: ProcessedMessageContent,
     credential: Credential,
+    warnings: Vec<ProcessingWarning>,
 }
 
 impl ProcessedMessage {
@@ -313,6 +316,7 @@ impl ProcessedMessage {
             authenticated_data,
             content,
             credential,
+            warnings: Vec::new(),
         }
     }
 
@@ -341,6 +345,16 @@ impl ProcessedMessage {
         &self.content
     }
 
+    /// Returns the [`ContentType`] of the message.
+    pub fn content_type(&self) -> ContentType {
+        match self.content {
+            ProcessedMessageContent::ApplicationMessage(_) => ContentType::Application,
+            ProcessedMessageContent::ProposalMessage(_)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(_) => ContentType::Proposal,
+            ProcessedMessageContent::StagedCommitMessage(_) => ContentType::Commit,
+        }
+    }
+
     /// Returns the content of the message and consumes the message.
     pub fn into_content(self) -> ProcessedMessageContent {
         self.content
@@ -350,6 +364,70 @@ impl ProcessedMessage {
     pub fn credential(&self) -> &Credential {
         &self.credential
     }
+
+    /// Returns the warnings that were raised while processing the message.
+    ///
+    /// Warnings don't prevent a message from being processed, but should be
+    /// inspected by the application before merging a commit or storing a
+    /// proposal.
+    pub fn warnings(&self) -> &[ProcessingWarning] {
+        &self.warnings
+    }
+
+    /// Adds a warning to the message.
+    pub(crate) fn add_warning(&mut self, warning: ProcessingWarning) {
+        self.warnings.push(warning)
+    }
+
+    /// Checks that the lifetimes of the key packages added by the message
+    /// are valid at the current time of the `time_provider`, tolerating a
+    /// clock skew of `skew_tolerance` seconds.
+    pub(crate) fn validate_key_package_lifetimes(
+        &self,
+        time_provider: &(impl TimeProvider + ?Sized),
+        skew_tolerance: u64,
+    ) -> Result<(), ValidationError> {
+        let mut key_packages = Vec::new();
+        match &self.content {
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                if let Proposal::Add(add_proposal) = queued_proposal.proposal() {
+                    key_packages.push(add_proposal.key_package());
+                }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => key_packages.extend(
+                staged_commit
+                    .add_proposals()
+                    .map(|add_proposal| add_proposal.add_proposal().key_package()),
+            ),
+            ProcessedMessageContent::ApplicationMessage(_) => {}
+        }
+        for key_package in key_packages {
+            if let Some(lifetime) = key_package.leaf_node().life_time() {
+                if !lifetime.is_valid_at(time_provider, skew_tolerance) {
+                    return Err(KeyPackageVerifyError::InvalidLifetime.into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A warning that was raised while processing a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessingWarning {
+    /// A leaf node in the message has the same identity as a previously seen
+    /// member, but a different signature key than the one pinned for that
+    /// identity. See
+    /// [`MlsGroupConfigBuilder::use_signature_key_pinning()`](crate::group::MlsGroupConfigBuilder::use_signature_key_pinning()).
+    KeyChanged {
+        /// The identity of the member.
+        identity: Vec<u8>,
+        /// The signature key pinned for the identity.
+        pinned_signature_key: SignaturePublicKey,
+        /// The signature key in the message.
+        signature_key: SignaturePublicKey,
+    },
 }
 
 /// Content of a processed message.
@@ -389,12 +467,18 @@ pub enum ProcessedMessageContent {
 #[derive(Debug, PartialEq, Eq)]
 pub struct ApplicationMessage {
     bytes: Vec<u8>,
+    aad: Vec<u8>,
 }
 
 impl ApplicationMessage {
     /// Create a new [ApplicationMessage].
-    pub(crate) fn new(bytes: Vec<u8>) -> Self {
-        Self { bytes }
+    pub(crate) fn new(bytes: Vec<u8>, aad: Vec<u8>) -> Self {
+        Self { bytes, aad }
+    }
+
+    /// Returns the authenticated data the sender attached to the message.
+    pub fn aad(&self) -> &[u8] {
+        &self.aad
     }
 
     /// Returns the inner bytes and consumes the [`ApplicationMessage`].
@@ -402,3 +486,18 @@ impl ApplicationMessage {
         self.bytes
     }
 }
+
+/// A validator for the authenticated data of incoming messages.
+///
+/// OpenMLS consults the validator configured in the
+/// [`MlsGroupConfig`](crate::group::MlsGroupConfig) for every message that is
+/// processed with
+/// [`MlsGroup::process_message()`](crate::group::MlsGroup::process_message()).
+/// This allows applications to enforce a format for authenticated
+/// application metadata, e.g. a message type or a thread ID.
+pub trait AadValidator: Send + Sync {
+    /// Returns `true` if the authenticated data `aad` of a message of the
+    /// given `content_type` from `sender` is acceptable and `false`
+    /// otherwise.
+    fn validate(&self, sender: &Sender, content_type: ContentType, aad: &[u8]) -> bool;
+}
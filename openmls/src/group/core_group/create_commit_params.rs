@@ -24,6 +24,7 @@ pub(crate) struct CreateCommitParams<'a> {
     force_self_update: bool,                        // Optional
     commit_type: CommitType,                        // Optional (default is `Member`)
     credential_with_key: Option<CredentialWithKey>, // Mandatory for external commits
+    always_create_group_info: bool,                 // Optional (default is `false`)
 }
 
 pub(crate) struct TempBuilderCCPM0 {}
@@ -58,6 +59,7 @@ impl<'a> TempBuilderCCPM1<'a> {
                 force_self_update: true,
                 commit_type: CommitType::Member,
                 credential_with_key: None,
+                always_create_group_info: false,
             },
         }
     }
@@ -81,6 +83,10 @@ impl<'a> CreateCommitParamsBuilder<'a> {
         self.ccp.credential_with_key = Some(credential_with_key);
         self
     }
+    pub(crate) fn always_create_group_info(mut self, always_create_group_info: bool) -> Self {
+        self.ccp.always_create_group_info = always_create_group_info;
+        self
+    }
     pub(crate) fn build(self) -> CreateCommitParams<'a> {
         self.ccp
     }
@@ -105,6 +111,9 @@ impl<'a> CreateCommitParams<'a> {
     pub(crate) fn commit_type(&self) -> CommitType {
         self.commit_type
     }
+    pub(crate) fn always_create_group_info(&self) -> bool {
+        self.always_create_group_info
+    }
     pub(crate) fn take_credential_with_key(&mut self) -> Option<CredentialWithKey> {
         self.credential_with_key.take()
     }
@@ -69,6 +69,10 @@ pub struct WelcomeTestVector {
 
 #[test]
 fn test_read_vectors() {
+    let _ = pretty_env_logger::formatted_builder()
+        .is_test(true)
+        .try_init();
+
     let test_vectors: Vec<WelcomeTestVector> = read(TEST_VECTOR_PATH_READ);
 
     for (i, test_vector) in test_vectors.into_iter().enumerate() {
@@ -105,10 +109,6 @@ fn test_read_vectors() {
 // }
 
 pub fn run_test_vector(test_vector: WelcomeTestVector) -> Result<(), &'static str> {
-    let _ = pretty_env_logger::formatted_builder()
-        .is_test(true)
-        .try_init();
-
     let backend = OpenMlsRustCrypto::default();
 
     // ---------------------------------------------------------------------------------------------
@@ -134,7 +134,7 @@ pub fn run_test_vector(test_vector: WelcomeTestVector) -> Result<(), &'static st
         }
     };
 
-    println!("{key_package:?}");
+    log::trace!("{key_package:?}");
 
     let welcome: Welcome = {
         let mls_message_welcome = MlsMessageIn::tls_deserialize_exact(test_vector.welcome).unwrap();
@@ -145,7 +145,7 @@ pub fn run_test_vector(test_vector: WelcomeTestVector) -> Result<(), &'static st
         }
     };
 
-    println!("{welcome:?}");
+    log::trace!("{welcome:?}");
 
     // ---------------------------------------------------------------------------------------------
 
@@ -155,7 +155,7 @@ pub fn run_test_vector(test_vector: WelcomeTestVector) -> Result<(), &'static st
         .supported_ciphersuites()
         .contains(&cipher_suite)
     {
-        println!("Unsupported ciphersuite.");
+        log::debug!("Unsupported ciphersuite.");
         return Ok(());
     }
 
@@ -193,7 +193,7 @@ pub fn run_test_vector(test_vector: WelcomeTestVector) -> Result<(), &'static st
         welcome.secrets(),
     )
     .unwrap();
-    println!("{encrypted_group_secrets:?}");
+    log::trace!("{encrypted_group_secrets:?}");
 
     // // //  * Decrypt the encrypted group secrets using `init_priv`
     let group_secrets = GroupSecrets::try_from_ciphertext(
@@ -204,7 +204,7 @@ pub fn run_test_vector(test_vector: WelcomeTestVector) -> Result<(), &'static st
         backend.crypto(),
     )
     .unwrap();
-    println!("{group_secrets:?}");
+    log::trace!("{group_secrets:?}");
 
     // // //  * Decrypt the encrypted group info
     let psk_secret = {
@@ -240,13 +240,13 @@ pub fn run_test_vector(test_vector: WelcomeTestVector) -> Result<(), &'static st
             )
             .unwrap()
         };
-        println!("{verifiable_group_info:?}");
+        log::trace!("{verifiable_group_info:?}");
 
         verifiable_group_info
             .verify(backend.crypto(), &signer_pub)
             .unwrap()
     };
-    println!("{group_info:?}");
+    log::trace!("{group_info:?}");
 
     // * Verify the confirmation_tag in the decrypted group info:
     //
@@ -19,7 +19,7 @@ pub(crate) mod staged_commit;
 // Tests
 #[cfg(test)]
 pub(crate) mod kat_passive_client;
-#[cfg(test)]
+#[cfg(any(feature = "test-vectors", test))]
 pub(crate) mod kat_welcome;
 #[cfg(test)]
 pub(crate) mod test_core_group;
@@ -46,8 +46,9 @@ use self::{
 use super::{
     builder::TempBuilderPG1,
     errors::{
-        CoreGroupBuildError, CreateAddProposalError, CreateCommitError, ExporterError,
-        ValidationError,
+        CoreGroupBuildError, CreateAddProposalError, CreateCommitError,
+        CreateGroupContextExtProposalError, CreateRemoveProposalError, ExportGroupInfoError,
+        ExporterError,
     },
     group_context::*,
     public_group::{diff::compute_path::PathComputationResult, PublicGroup},
@@ -55,7 +56,10 @@ use super::{
 
 use crate::{
     binary_tree::array_representation::{LeafNodeIndex, TreeSize},
-    ciphersuite::{signable::Signable, HpkePublicKey, SignaturePublicKey},
+    ciphersuite::{
+        signable::{Signable, SignatureError},
+        HpkePublicKey,
+    },
     credentials::*,
     error::LibraryError,
     framing::{mls_auth_content::AuthenticatedContent, *},
@@ -73,17 +77,13 @@ use crate::{
     },
     tree::{secret_tree::SecretTreeError, sender_ratchet::SenderRatchetConfiguration},
     treesync::{
-        node::{
-            encryption_keys::{EncryptionKey, EncryptionKeyPair},
-            leaf_node::Lifetime,
-        },
+        node::{encryption_keys::EncryptionKeyPair, leaf_node::Lifetime},
         *,
     },
     versions::ProtocolVersion,
 };
+use std::time::Duration;
 
-#[cfg(test)]
-use super::errors::CreateGroupContextExtProposalError;
 #[cfg(test)]
 use crate::treesync::node::leaf_node::TreePosition;
 #[cfg(test)]
@@ -125,6 +125,12 @@ impl Member {
             credential,
         }
     }
+
+    /// Returns the certificate chain of the member's credential if it is an
+    /// X.509 credential, or `None` otherwise.
+    pub fn certificate_chain(&self) -> Option<&CertificateChain> {
+        self.credential.certificate_chain()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -205,6 +211,35 @@ impl CoreGroupBuilder {
         }
         self
     }
+    /// Set the media types accepted by the own leaf and the media types
+    /// required in the [`CoreGroup`].
+    pub(crate) fn with_media_types(
+        mut self,
+        accepted_media_types: Option<AcceptedMediaTypesExtension>,
+        required_media_types: Option<RequiredMediaTypesExtension>,
+    ) -> Self {
+        self.public_group_builder = self
+            .public_group_builder
+            .with_media_types(accepted_media_types, required_media_types);
+        self
+    }
+    /// Set the roles of the members in the [`CoreGroup`].
+    pub(crate) fn with_roles(mut self, roles: Option<RolesExtension>) -> Self {
+        if let Some(roles) = roles {
+            self.public_group_builder = self.public_group_builder.with_roles(roles);
+        }
+        self
+    }
+    /// Set the custom extension types that are supported in the [`CoreGroup`].
+    pub(crate) fn with_custom_extension_types(
+        mut self,
+        custom_extension_types: Vec<ExtensionType>,
+    ) -> Self {
+        self.public_group_builder = self
+            .public_group_builder
+            .with_custom_extension_types(custom_extension_types);
+        self
+    }
     /// Set the number of past epochs the group should keep secrets.
     pub fn with_max_past_epoch_secrets(mut self, max_past_epochs: usize) -> Self {
         self.max_past_epochs = max_past_epochs;
@@ -360,7 +395,7 @@ impl CoreGroup {
         //      operate on a reference to make this more efficient.
         leaf_node: LeafNode,
         signer: &impl Signer,
-    ) -> Result<AuthenticatedContent, LibraryError> {
+    ) -> Result<AuthenticatedContent, SignatureError> {
         let update_proposal = UpdateProposal { leaf_node };
         let proposal = Proposal::Update(update_proposal);
         AuthenticatedContent::member_proposal(
@@ -381,9 +416,9 @@ impl CoreGroup {
         framing_parameters: FramingParameters,
         removed: LeafNodeIndex,
         signer: &impl Signer,
-    ) -> Result<AuthenticatedContent, ValidationError> {
+    ) -> Result<AuthenticatedContent, CreateRemoveProposalError> {
         if self.public_group().leaf(removed).is_none() {
-            return Err(ValidationError::UnknownMember);
+            return Err(CreateRemoveProposalError::UnknownMember);
         }
         let remove_proposal = RemoveProposal { removed };
         let proposal = Proposal::Remove(remove_proposal);
@@ -394,7 +429,7 @@ impl CoreGroup {
             self.context(),
             signer,
         )
-        .map_err(ValidationError::LibraryError)
+        .map_err(|e| e.into())
     }
 
     // 11.1.4. PreSharedKey
@@ -407,7 +442,7 @@ impl CoreGroup {
         framing_parameters: FramingParameters,
         psk: PreSharedKeyId,
         signer: &impl Signer,
-    ) -> Result<AuthenticatedContent, LibraryError> {
+    ) -> Result<AuthenticatedContent, SignatureError> {
         let presharedkey_proposal = PreSharedKeyProposal::new(psk);
         let proposal = Proposal::PreSharedKey(presharedkey_proposal);
         AuthenticatedContent::member_proposal(
@@ -420,7 +455,6 @@ impl CoreGroup {
     }
 
     /// Create a `GroupContextExtensions` proposal.
-    #[cfg(test)]
     pub(crate) fn create_group_context_ext_proposal(
         &self,
         framing_parameters: FramingParameters,
@@ -435,9 +469,9 @@ impl CoreGroup {
         if let Some(required_extension) = required_extension {
             let required_capabilities = required_extension.as_required_capabilities_extension()?;
             // Ensure we support all the capabilities.
-            required_capabilities.check_support()?;
-            self.own_leaf_node()?
-                .validate_required_capabilities(required_capabilities)?;
+            let own_leaf_node = self.own_leaf_node()?;
+            required_capabilities.check_support(own_leaf_node.capabilities().extensions())?;
+            own_leaf_node.validate_required_capabilities(required_capabilities)?;
             // Ensure that all other leaf nodes support all the required
             // extensions as well.
             self.public_group()
@@ -460,7 +494,7 @@ impl CoreGroup {
         &mut self,
         aad: &[u8],
         msg: &[u8],
-        padding_size: usize,
+        padding_policy: &PaddingPolicy,
         backend: &impl OpenMlsCryptoProvider,
         signer: &impl Signer,
     ) -> Result<PrivateMessage, MessageEncryptionError> {
@@ -471,14 +505,14 @@ impl CoreGroup {
             self.context(),
             signer,
         )?;
-        self.encrypt(public_message, padding_size, backend)
+        self.encrypt(public_message, padding_policy, backend)
     }
 
     // Encrypt an PublicMessage into an PrivateMessage
     pub(crate) fn encrypt(
         &mut self,
         public_message: AuthenticatedContent,
-        padding_size: usize,
+        padding_policy: &PaddingPolicy,
         backend: &impl OpenMlsCryptoProvider,
     ) -> Result<PrivateMessage, MessageEncryptionError> {
         log::trace!("{:?}", public_message.confirmation_tag());
@@ -487,7 +521,7 @@ impl CoreGroup {
             self.ciphersuite(),
             backend,
             self.message_secrets_store.message_secrets_mut(),
-            padding_size,
+            padding_policy,
         )
     }
 
@@ -541,40 +575,118 @@ impl CoreGroup {
             .map_err(LibraryError::unexpected_crypto_error)?)
     }
 
+    /// Exporter for safe extensions. The secrets are independent of the
+    /// secrets exported with [`Self::export_secret()`].
+    pub(crate) fn export_extension_secret(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<Vec<u8>, ExporterError> {
+        if key_length > u16::MAX.into() {
+            log::error!("Got a key that is larger than u16::MAX");
+            return Err(ExporterError::KeyLengthTooLong);
+        }
+        Ok(self
+            .group_epoch_secrets
+            .exporter_secret()
+            .derive_extension_secret(self.ciphersuite(), backend, context, key_length)
+            .map_err(LibraryError::unexpected_crypto_error)?)
+    }
+
+    /// Exporter for message streams. The secrets are independent of the
+    /// secrets exported with [`Self::export_secret()`].
+    pub(crate) fn export_stream_secret(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        stream_id: &[u8],
+    ) -> Result<Vec<u8>, LibraryError> {
+        let ciphersuite = self.ciphersuite();
+        self.group_epoch_secrets
+            .exporter_secret()
+            .derive_stream_secret(ciphersuite, backend, stream_id, ciphersuite.hash_length())
+            .map_err(LibraryError::unexpected_crypto_error)
+    }
+
+    /// Exporter for the current or a past epoch. The exporter secrets of past
+    /// epochs are kept as long as their message secrets.
+    pub(crate) fn export_secret_for_epoch(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        epoch: GroupEpoch,
+        label: &str,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<Vec<u8>, ExporterError> {
+        if epoch == self.context().epoch() {
+            return self.export_secret(backend, label, context, key_length);
+        }
+        if key_length > u16::MAX.into() {
+            log::error!("Got a key that is larger than u16::MAX");
+            return Err(ExporterError::KeyLengthTooLong);
+        }
+        Ok(self
+            .message_secrets_store
+            .exporter_secret_for_epoch(epoch)
+            .ok_or(ExporterError::UnknownEpoch)?
+            .derive_exported_secret(self.ciphersuite(), backend, label, context, key_length)
+            .map_err(LibraryError::unexpected_crypto_error)?)
+    }
+
     pub(crate) fn export_group_info(
         &self,
         backend: &impl OpenMlsCryptoProvider,
         signer: &impl Signer,
         with_ratchet_tree: bool,
-    ) -> Result<GroupInfo, LibraryError> {
-        let extensions = {
-            let ratchet_tree_extension = || {
-                Extension::RatchetTree(RatchetTreeExtension::new(
-                    self.public_group().export_ratchet_tree(),
-                ))
-            };
+    ) -> Result<GroupInfo, ExportGroupInfoError> {
+        self.export_group_info_with_extensions(
+            backend,
+            signer,
+            with_ratchet_tree,
+            true,
+            Extensions::empty(),
+        )
+    }
 
-            let external_pub_extension = || {
-                let external_pub = self
-                    .group_epoch_secrets()
-                    .external_secret()
-                    .derive_external_keypair(backend.crypto(), self.ciphersuite())
-                    .public;
-                Extension::ExternalPub(ExternalPubExtension::new(HpkePublicKey::from(external_pub)))
-            };
+    /// Export a group info object that contains the ratchet tree and the
+    /// external public key if requested, as well as the given additional
+    /// `extensions`, which must not contain either of the two.
+    pub(crate) fn export_group_info_with_extensions(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        signer: &impl Signer,
+        with_ratchet_tree: bool,
+        with_external_pub: bool,
+        mut extensions: Extensions,
+    ) -> Result<GroupInfo, ExportGroupInfoError> {
+        let ratchet_tree_extension = || {
+            Extension::RatchetTree(RatchetTreeExtension::new(
+                self.public_group().export_ratchet_tree(),
+            ))
+        };
 
-            if with_ratchet_tree {
-                Extensions::from_vec(vec![ratchet_tree_extension(), external_pub_extension()])
-                    .map_err(|_| {
-                        LibraryError::custom(
-                            "There should not have been duplicate extensions here.",
-                        )
-                    })?
-            } else {
-                Extensions::single(external_pub_extension())
-            }
+        let external_pub_extension = || {
+            let external_pub = self
+                .group_epoch_secrets()
+                .external_secret()
+                .derive_external_keypair(backend.crypto(), self.ciphersuite())
+                .public;
+            Extension::ExternalPub(ExternalPubExtension::new(HpkePublicKey::from(external_pub)))
         };
 
+        let duplicate_extension =
+            |_| LibraryError::custom("There should not have been duplicate extensions here.");
+        if with_ratchet_tree {
+            extensions
+                .add(ratchet_tree_extension())
+                .map_err(duplicate_extension)?;
+        }
+        if with_external_pub {
+            extensions
+                .add(external_pub_extension())
+                .map_err(duplicate_extension)?;
+        }
+
         // Create to-be-signed group info.
         let group_info_tbs = GroupInfoTBS::new(
             self.context().clone(),
@@ -587,9 +699,7 @@ impl CoreGroup {
         );
 
         // Sign to-be-signed group info.
-        group_info_tbs
-            .sign(signer)
-            .map_err(|_| LibraryError::custom("Signing failed"))
+        Ok(group_info_tbs.sign(signer)?)
     }
 
     /// Returns the epoch authenticator
@@ -683,6 +793,24 @@ impl CoreGroup {
         self.message_secrets_store.message_secrets()
     }
 
+    /// Returns the past epochs for which message secrets are stored.
+    pub(crate) fn past_epochs(&self) -> impl Iterator<Item = GroupEpoch> + '_ {
+        self.message_secrets_store.past_epochs()
+    }
+
+    /// Returns the members of the group in the given epoch, which is either
+    /// the current epoch or one of the past epochs for which message secrets
+    /// are stored. Returns `None` otherwise.
+    pub(crate) fn members_at_epoch(&self, epoch: GroupEpoch) -> Option<Vec<Member>> {
+        if epoch == self.context().epoch() {
+            Some(self.public_group().members().collect())
+        } else {
+            self.message_secrets_store
+                .members_for_past_epoch(epoch)
+                .map(<[Member]>::to_vec)
+        }
+    }
+
     /// Sets the size of the [`MessageSecretsStore`], i.e. the number of past
     /// epochs to keep.
     /// This allows application messages from previous epochs to be decrypted.
@@ -690,6 +818,43 @@ impl CoreGroup {
         self.message_secrets_store.resize(max_past_epochs);
     }
 
+    /// Deletes the message secrets of all past epochs that ended more than
+    /// `max_age` before `now`, given in seconds since the UNIX epoch.
+    pub(crate) fn delete_expired_message_secrets(&mut self, max_age: Duration, now: u64) {
+        self.message_secrets_store.delete_expired(max_age, now);
+    }
+
+    /// Deletes the message secrets of the oldest past epochs until the
+    /// estimated size of all message secrets is at most `budget` bytes.
+    pub(crate) fn enforce_message_secrets_budget(&mut self, budget: usize) {
+        let ciphersuite = self.ciphersuite();
+        self.message_secrets_store
+            .enforce_budget(budget, ciphersuite);
+    }
+
+    /// Deletes the key material of the own sender ratchets of all past epochs.
+    pub(crate) fn erase_past_encryption_ratchets(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<(), LibraryError> {
+        let ciphersuite = self.ciphersuite();
+        self.message_secrets_store
+            .erase_past_encryption_ratchets(ciphersuite, backend)
+            .map_err(|_| LibraryError::custom("Own sender ratchets could not be erased"))
+    }
+
+    /// Returns the estimated sizes in bytes of the message secrets of the
+    /// current epoch and of all past epochs, as well as the number of
+    /// retained keys of skipped generations.
+    pub(crate) fn message_secrets_usage(&self) -> (usize, usize, usize) {
+        let ciphersuite = self.ciphersuite();
+        (
+            self.message_secrets_store.current_epoch_size(ciphersuite),
+            self.message_secrets_store.past_epochs_size(ciphersuite),
+            self.message_secrets_store.retained_key_count(),
+        )
+    }
+
     /// Get the message secrets. Either from the secrets store or from the group.
     pub(crate) fn message_secrets_mut(
         &mut self,
@@ -784,6 +949,22 @@ impl CoreGroup {
             .unwrap_or_default()
     }
 
+    /// Delete the [`EncryptionKeyPair`]s of the current [`GroupEpoch`] from
+    /// the `backend`'s key store.
+    ///
+    /// Returns an error if access to the key store fails.
+    pub(super) fn delete_epoch_keypairs<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), KeyStore::Error> {
+        let k = EpochKeypairId::new(
+            self.group_id(),
+            self.context().epoch().as_u64(),
+            self.own_leaf_index(),
+        );
+        backend.key_store().delete::<Vec<EncryptionKeyPair>>(&k.0)
+    }
+
     /// Delete the [`EncryptionKeyPair`]s from the previous [`GroupEpoch`] from
     /// the `backend`'s key store.
     ///
@@ -800,6 +981,23 @@ impl CoreGroup {
         backend.key_store().delete::<Vec<EncryptionKeyPair>>(&k.0)
     }
 
+    /// Delete the [`EncryptionKeyPair`]s of the next [`GroupEpoch`] from the
+    /// `backend`'s key store. Such keypairs only exist if merging a commit
+    /// failed after the keypairs of the new epoch were stored.
+    ///
+    /// Returns an error if access to the key store fails.
+    pub(super) fn delete_next_epoch_keypairs<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), KeyStore::Error> {
+        let k = EpochKeypairId::new(
+            self.group_id(),
+            self.context().epoch().as_u64() + 1,
+            self.own_leaf_index(),
+        );
+        backend.key_store().delete::<Vec<EncryptionKeyPair>>(&k.0)
+    }
+
     pub(crate) fn create_commit<KeyStore: OpenMlsKeyStore>(
         &self,
         mut params: CreateCommitParams,
@@ -807,6 +1005,7 @@ impl CoreGroup {
         signer: &impl Signer,
     ) -> Result<CreateCommitResult, CreateCommitError<KeyStore::Error>> {
         let ciphersuite = self.ciphersuite();
+        let always_create_group_info = params.always_create_group_info();
 
         let sender = match params.commit_type() {
             CommitType::External => Sender::NewMemberCommit,
@@ -854,6 +1053,10 @@ impl CoreGroup {
             .validate_remove_proposals(&proposal_queue)?;
         self.public_group
             .validate_pre_shared_key_proposals(&proposal_queue)?;
+        self.public_group
+            .validate_group_context_extensions_proposals(&proposal_queue)?;
+        self.public_group
+            .validate_proposal_permissions(&proposal_queue, &sender, None)?;
         // Validate update proposals for member commits
         if let Sender::Member(sender_index) = &sender {
             // ValSem110
@@ -897,6 +1100,11 @@ impl CoreGroup {
                 PathComputationResult::default()
             };
 
+        let update_path_leaf_node = path_computation_result
+            .encrypted_path
+            .as_ref()
+            .map(|path| path.leaf_node().clone());
+
         // Create commit message
         let commit = Commit {
             proposals: proposal_reference_list,
@@ -969,8 +1177,8 @@ impl CoreGroup {
         diff.update_interim_transcript_hash(ciphersuite, backend, confirmation_tag.clone())?;
 
         // only computes the group info if necessary
-        let group_info = if !apply_proposals_values.invitation_list.is_empty()
-            || self.use_ratchet_tree_extension
+        let return_group_info = self.use_ratchet_tree_extension || always_create_group_info;
+        let group_info = if !apply_proposals_values.invitation_list.is_empty() || return_group_info
         {
             // Create the ratchet tree extension if necessary
             let external_pub = provisional_epoch_secrets
@@ -1061,13 +1269,15 @@ impl CoreGroup {
         let staged_commit = StagedCommit::new(
             proposal_queue,
             StagedCommitState::GroupMember(Box::new(staged_commit_state)),
+            update_path_leaf_node,
+            authenticated_content.sender().clone(),
         );
 
         Ok(CreateCommitResult {
             commit: authenticated_content,
             welcome_option,
             staged_commit,
-            group_info: group_info.filter(|_| self.use_ratchet_tree_extension),
+            group_info: group_info.filter(|_| return_group_info),
         })
     }
 
@@ -1075,58 +1285,6 @@ impl CoreGroup {
     pub(crate) fn own_tree_position(&self) -> TreePosition {
         TreePosition::new(self.group_id().clone(), self.own_leaf_index())
     }
-
-    /// Return supported credentials of all members.
-    // TODO(#1186)
-    #[allow(unused)]
-    pub(crate) fn members_supported_credentials(
-        &self,
-    ) -> impl Iterator<Item = &[CredentialType]> + '_ {
-        self.public_group().members().filter_map(|member| {
-            self.public_group()
-                .leaf(member.index)
-                .map(|leaf| leaf.capabilities().credentials())
-        })
-    }
-
-    /// Return currently used credentials of all members.
-    // TODO(#1186)
-    #[allow(unused)]
-    pub(crate) fn members_used_credentials(&self) -> impl Iterator<Item = CredentialType> + '_ {
-        self.public_group()
-            .members()
-            .map(|Member { credential, .. }| credential.credential_type())
-    }
-
-    /// Return currently used signature keys of all members.
-    // TODO(#1186)
-    #[allow(unused)]
-    pub(crate) fn signature_keys(
-        &self,
-        exclude_own: bool,
-    ) -> impl Iterator<Item = SignaturePublicKey> + '_ {
-        self.public_group()
-            .members()
-            .filter(move |member| {
-                if exclude_own {
-                    member.index != self.own_leaf_index
-                } else {
-                    true
-                }
-            })
-            .map(|Member { signature_key, .. }| SignaturePublicKey::from(signature_key))
-    }
-
-    /// Return currently used encryption keys of all members.
-    // TODO(#1186)
-    #[allow(unused)]
-    pub(crate) fn encryption_keys(&self) -> impl Iterator<Item = EncryptionKey> + '_ {
-        self.public_group()
-            .members()
-            .map(|Member { encryption_key, .. }| {
-                EncryptionKey::from(HpkePublicKey::new(encryption_key))
-            })
-    }
 }
 
 /// Composite key for key material of a client within an epoch
@@ -1159,12 +1317,12 @@ impl CoreGroup {
         println!("{}: {}", message, self.public_group().export_ratchet_tree());
     }
 
-    #[cfg(test)]
+    #[cfg(any(feature = "test-vectors", test))]
     pub(crate) fn message_secrets_store(&self) -> &MessageSecretsStore {
         &self.message_secrets_store
     }
 
-    #[cfg(test)]
+    #[cfg(any(feature = "test-vectors", test))]
     pub(crate) fn set_group_context(&mut self, group_context: GroupContext) {
         self.public_group.set_group_context(group_context)
     }
@@ -3,8 +3,10 @@ use crate::{
     group::{
         core_group::create_commit_params::{CommitType, CreateCommitParams},
         errors::ExternalCommitError,
+        public_group::errors::CreationFromExternalError,
     },
     messages::proposals::{ExternalInitProposal, Proposal},
+    treesync::errors::PublicTreeError,
 };
 
 use super::CoreGroup;
@@ -37,14 +39,21 @@ impl CoreGroup {
         // If we got a ratchet tree extension in the welcome, we enable it for
         // this group. Note that this is not strictly necessary. But there's
         // currently no other mechanism to enable the extension.
-        let (ratchet_tree, enable_ratchet_tree_extension) =
-            match verifiable_group_info.extensions().ratchet_tree() {
-                Some(extension) => (extension.ratchet_tree().clone(), true),
-                None => match ratchet_tree {
-                    Some(ratchet_tree) => (ratchet_tree, false),
-                    None => return Err(ExternalCommitError::MissingRatchetTree),
-                },
-            };
+        let (ratchet_tree, enable_ratchet_tree_extension) = match verifiable_group_info
+            .extensions()
+            .ratchet_tree()
+        {
+            Some(extension) => (
+                extension.ratchet_tree().cloned().map_err(|_| {
+                    CreationFromExternalError::TreeSyncError(PublicTreeError::MalformedTree.into())
+                })?,
+                true,
+            ),
+            None => match ratchet_tree {
+                Some(ratchet_tree) => (ratchet_tree, false),
+                None => return Err(ExternalCommitError::MissingRatchetTree),
+            },
+        };
 
         let (public_group, group_info) = PublicGroup::from_external(
             backend,
@@ -8,6 +8,7 @@ use crate::{
     treesync::{
         errors::{DerivePathError, PublicTreeError},
         node::encryption_keys::EncryptionKeyPair,
+        RatchetTreeProvider,
     },
 };
 
@@ -18,6 +19,27 @@ impl CoreGroup {
         ratchet_tree: Option<RatchetTreeIn>,
         key_package_bundle: KeyPackageBundle,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        resumption_psk_store: ResumptionPskStore,
+    ) -> Result<Self, WelcomeError<KeyStore::Error>> {
+        Self::new_from_welcome_with_ratchet_tree_provider(
+            welcome,
+            ratchet_tree,
+            None,
+            key_package_bundle,
+            backend,
+            resumption_psk_store,
+        )
+    }
+
+    // Join a group from a welcome message. If neither the welcome nor the
+    // caller provide a ratchet tree, it is requested from the
+    // `ratchet_tree_provider`.
+    pub(crate) fn new_from_welcome_with_ratchet_tree_provider<KeyStore: OpenMlsKeyStore>(
+        welcome: Welcome,
+        ratchet_tree: Option<RatchetTreeIn>,
+        ratchet_tree_provider: Option<&dyn RatchetTreeProvider>,
+        key_package_bundle: KeyPackageBundle,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         mut resumption_psk_store: ResumptionPskStore,
     ) -> Result<Self, WelcomeError<KeyStore::Error>> {
         log::debug!("CoreGroup::new_from_welcome_internal");
@@ -35,72 +57,32 @@ impl CoreGroup {
             .map_err(|_| WelcomeError::NoMatchingEncryptionKey)?;
 
         let ciphersuite = welcome.ciphersuite();
-
-        // Find key_package in welcome secrets
-        let egs = if let Some(egs) = Self::find_key_package_from_welcome_secrets(
-            key_package_bundle
-                .key_package()
-                .hash_ref(backend.crypto())?,
-            welcome.secrets(),
-        ) {
-            egs
-        } else {
-            return Err(WelcomeError::JoinerSecretNotFound);
-        };
-        if ciphersuite != key_package_bundle.key_package().ciphersuite() {
-            let e = WelcomeError::CiphersuiteMismatch;
-            debug!("new_from_welcome {:?}", e);
-            return Err(e);
-        }
-
-        let group_secrets = GroupSecrets::try_from_ciphertext(
-            key_package_bundle.private_key(),
-            egs.encrypted_group_secrets(),
-            welcome.encrypted_group_info(),
-            ciphersuite,
-            backend.crypto(),
-        )?;
-
-        // Prepare the PskSecret
-        let psk_secret = {
-            let psks = load_psks(
-                backend.key_store(),
-                &resumption_psk_store,
-                &group_secrets.psks,
-            )?;
-
-            PskSecret::new(backend, ciphersuite, psks)?
-        };
-
-        // Create key schedule
-        let mut key_schedule = KeySchedule::init(
-            ciphersuite,
+        let (group_secrets, mut key_schedule, verifiable_group_info) = Self::decrypt_welcome(
+            &welcome,
+            &key_package_bundle,
             backend,
-            &group_secrets.joiner_secret,
-            psk_secret,
+            &resumption_psk_store,
         )?;
 
-        // Derive welcome key & nonce from the key schedule
-        let (welcome_key, welcome_nonce) = key_schedule
-            .welcome(backend)
-            .map_err(|_| LibraryError::custom("Using the key schedule in the wrong state"))?
-            .derive_welcome_key_nonce(backend)
-            .map_err(LibraryError::unexpected_crypto_error)?;
-
-        let verifiable_group_info = VerifiableGroupInfo::try_from_ciphertext(
-            &welcome_key,
-            &welcome_nonce,
-            welcome.encrypted_group_info(),
-            &[],
-            backend,
-        )?;
+        // Make sure that we support the protocol version of the group.
+        if verifiable_group_info.group_context().protocol_version() != ProtocolVersion::Mls10 {
+            return Err(WelcomeError::UnsupportedMlsVersion);
+        }
 
         // Make sure that we can support the required capabilities in the group info.
         if let Some(required_capabilities) =
             verifiable_group_info.extensions().required_capabilities()
         {
+            // Custom extension types are supported if they are advertised in
+            // the capabilities of our own leaf node.
             required_capabilities
-                .check_support()
+                .check_support(
+                    key_package_bundle
+                        .key_package()
+                        .leaf_node()
+                        .capabilities()
+                        .extensions(),
+                )
                 .map_err(|_| WelcomeError::UnsupportedCapability)?;
             // Also check that our key package actually supports the extensions.
             // Per spec the sender must have checked this. But you never know.
@@ -110,18 +92,46 @@ impl CoreGroup {
                 .validate_required_capabilities(required_capabilities)?;
         }
 
+        // Make sure that we accept the media types required by the group.
+        if !key_package_bundle
+            .key_package()
+            .leaf_node()
+            .supports_media_types(
+                verifiable_group_info
+                    .group_context_extensions()
+                    .required_media_types()
+                    .as_ref(),
+            )
+        {
+            return Err(WelcomeError::UnsupportedMediaTypes);
+        }
+
         let path_secret_option = group_secrets.path_secret;
 
         // Build the ratchet tree
 
-        // Set nodes either from the extension or from the `nodes_option`.
+        // Set nodes either from the extension, from the `ratchet_tree` or
+        // from the `ratchet_tree_provider`. The tree is verified against the
+        // tree hash in the group info when building the public group below.
         // If we got a ratchet tree extension in the welcome, we enable it for
         // this group. Note that this is not strictly necessary. But there's
         // currently no other mechanism to enable the extension.
         let (ratchet_tree, enable_ratchet_tree_extension) =
             match verifiable_group_info.extensions().ratchet_tree() {
-                Some(extension) => (extension.ratchet_tree().clone(), true),
-                None => match ratchet_tree {
+                Some(extension) => (
+                    extension.ratchet_tree().cloned().map_err(|_| {
+                        WelcomeError::PublicTreeError(PublicTreeError::MalformedTree)
+                    })?,
+                    true,
+                ),
+                None => match ratchet_tree.or_else(|| {
+                    ratchet_tree_provider.and_then(|provider| {
+                        provider.ratchet_tree(
+                            verifiable_group_info.group_id(),
+                            verifiable_group_info.epoch(),
+                        )
+                    })
+                }) {
                     Some(ratchet_tree) => (ratchet_tree, false),
                     None => return Err(WelcomeError::MissingRatchetTree),
                 },
@@ -218,7 +228,6 @@ impl CoreGroup {
             log::error!("Confirmation tag mismatch");
             log_crypto!(trace, "  Got:      {:x?}", confirmation_tag);
             log_crypto!(trace, "  Expected: {:x?}", public_group.confirmation_tag());
-            debug_assert!(false, "Confirmation tag mismatch");
             return Err(WelcomeError::ConfirmationTagMismatch);
         }
 
@@ -245,6 +254,80 @@ impl CoreGroup {
 
     // Helper functions
 
+    // Decrypt the group secrets and the group info of a welcome message with
+    // the private key of the given key package bundle. The key store is only
+    // read, such that the welcome can be inspected before it is consumed.
+    pub(crate) fn decrypt_welcome<KeyStore: OpenMlsKeyStore>(
+        welcome: &Welcome,
+        key_package_bundle: &KeyPackageBundle,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        resumption_psk_store: &ResumptionPskStore,
+    ) -> Result<(GroupSecrets, KeySchedule, VerifiableGroupInfo), WelcomeError<KeyStore::Error>>
+    {
+        let ciphersuite = welcome.ciphersuite();
+
+        // Find key_package in welcome secrets
+        let egs = if let Some(egs) = Self::find_key_package_from_welcome_secrets(
+            key_package_bundle
+                .key_package()
+                .hash_ref(backend.crypto())?,
+            welcome.secrets(),
+        ) {
+            egs
+        } else {
+            return Err(WelcomeError::JoinerSecretNotFound);
+        };
+        if ciphersuite != key_package_bundle.key_package().ciphersuite() {
+            let e = WelcomeError::CiphersuiteMismatch;
+            debug!("new_from_welcome {:?}", e);
+            return Err(e);
+        }
+
+        let group_secrets = GroupSecrets::try_from_ciphertext(
+            key_package_bundle.private_key(),
+            egs.encrypted_group_secrets(),
+            welcome.encrypted_group_info(),
+            ciphersuite,
+            backend.crypto(),
+        )?;
+
+        // Prepare the PskSecret
+        let psk_secret = {
+            let psks = load_psks(
+                backend.key_store(),
+                resumption_psk_store,
+                &group_secrets.psks,
+            )?;
+
+            PskSecret::new(backend, ciphersuite, psks)?
+        };
+
+        // Create key schedule
+        let mut key_schedule = KeySchedule::init(
+            ciphersuite,
+            backend,
+            &group_secrets.joiner_secret,
+            psk_secret,
+        )?;
+
+        // Derive welcome key & nonce from the key schedule
+        let (welcome_key, welcome_nonce) = key_schedule
+            .welcome(backend)
+            .map_err(|_| LibraryError::custom("Using the key schedule in the wrong state"))?
+            .derive_welcome_key_nonce(backend)
+            .map_err(LibraryError::unexpected_crypto_error)?;
+
+        let verifiable_group_info = VerifiableGroupInfo::try_from_ciphertext(
+            &welcome_key,
+            &welcome_nonce,
+            welcome.encrypted_group_info(),
+            &[],
+            backend,
+        )?;
+
+        Ok((group_secrets, key_schedule, verifiable_group_info))
+    }
+
     pub(crate) fn find_key_package_from_welcome_secrets(
         hash_ref: HashReference,
         welcome_secrets: &[EncryptedGroupSecrets],
@@ -1,6 +1,6 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, time::Duration};
 
-use crate::schedule::message_secrets::MessageSecrets;
+use crate::schedule::{message_secrets::MessageSecrets, ExporterSecret};
 
 use super::*;
 
@@ -12,6 +12,27 @@ struct EpochTree {
     epoch: u64,
     message_secrets: MessageSecrets,
     leaves: Vec<Member>,
+    // Time in seconds since the UNIX epoch at which the epoch ended, or `None`
+    // if it is not known yet, e.g., because the epoch was stored by a version
+    // that didn't record it. The time is then set on the next purge.
+    #[serde(default)]
+    retired_at: Option<u64>,
+    // The exporter secret of the epoch.
+    #[serde(default)]
+    exporter_secret: Option<ExporterSecret>,
+}
+
+impl EpochTree {
+    /// Returns an estimate of the memory in bytes that the secrets of the
+    /// epoch occupy.
+    fn size(&self, ciphersuite: Ciphersuite) -> usize {
+        let exporter_secret_size = if self.exporter_secret.is_some() {
+            ciphersuite.hash_length()
+        } else {
+            0
+        };
+        self.message_secrets.key_material_size(ciphersuite) + exporter_secret_size
+    }
 }
 
 /// Can store message secrets for up to `max_epochs`. The trees are added with [`self::add()`] and can be queried
@@ -52,16 +73,20 @@ impl MessageSecretsStore {
 
     /// Resize the store.
     pub(crate) fn resize(&mut self, max_past_epochs: usize) {
-        let old_size = self.max_epochs;
         self.max_epochs = max_past_epochs;
-        if old_size > max_past_epochs {
-            let num_epochs_out = old_size - max_past_epochs;
-            self.past_epoch_trees.rotate_left(num_epochs_out);
-            self.past_epoch_trees.truncate(max_past_epochs);
-        }
+        // Drop the oldest epochs that no longer fit into the store
+        let num_epochs_out = self.past_epoch_trees.len().saturating_sub(max_past_epochs);
+        self.past_epoch_trees.drain(..num_epochs_out);
     }
 
-    /// Add a secret tree for a given epoch `group_epoch`.
+    /// Returns `true` if the store keeps the secrets of past epochs.
+    pub(crate) fn stores_past_epochs(&self) -> bool {
+        self.max_epochs > 0
+    }
+
+    /// Add a secret tree and optionally the exporter secret for a given
+    /// epoch `group_epoch` that ended at `retired_at`, given in seconds since
+    /// the UNIX epoch.
     /// Note that this does not take the epoch into account and pops out the
     /// oldest element.
     pub(crate) fn add(
@@ -69,6 +94,8 @@ impl MessageSecretsStore {
         group_epoch: impl Into<GroupEpoch>,
         message_secrets: MessageSecrets,
         leaves: Vec<Member>,
+        exporter_secret: Option<ExporterSecret>,
+        retired_at: Option<u64>,
     ) {
         // Don't store the tree if it's not intended
         if self.max_epochs == 0 {
@@ -82,6 +109,8 @@ impl MessageSecretsStore {
             epoch: group_epoch.into().as_u64(),
             message_secrets,
             leaves,
+            retired_at,
+            exporter_secret,
         });
         debug_assert!(
             self.max_epochs >= self.past_epoch_trees.len(),
@@ -91,6 +120,77 @@ impl MessageSecretsStore {
         );
     }
 
+    /// Delete the message secrets of all past epochs that ended more than
+    /// `max_age` before `now`, given in seconds since the UNIX epoch.
+    /// Epochs for which the end is not known are considered to have ended
+    /// at `now`, so that they expire `max_age` after the first purge.
+    pub(crate) fn delete_expired(&mut self, max_age: Duration, now: u64) {
+        let cutoff = now.saturating_sub(max_age.as_secs());
+        self.past_epoch_trees
+            .retain_mut(|epoch_tree| *epoch_tree.retired_at.get_or_insert(now) >= cutoff);
+    }
+
+    /// Returns an estimate of the memory in bytes that the message secrets of
+    /// the current epoch occupy.
+    pub(crate) fn current_epoch_size(&self, ciphersuite: Ciphersuite) -> usize {
+        self.message_secrets.key_material_size(ciphersuite)
+    }
+
+    /// Returns an estimate of the memory in bytes that the message secrets
+    /// and exporter secrets of all past epochs occupy.
+    pub(crate) fn past_epochs_size(&self, ciphersuite: Ciphersuite) -> usize {
+        self.past_epoch_trees
+            .iter()
+            .map(|epoch_tree| epoch_tree.size(ciphersuite))
+            .sum()
+    }
+
+    /// Returns the number of past generations for which key material is
+    /// retained across all epochs and senders.
+    pub(crate) fn retained_key_count(&self) -> usize {
+        self.message_secrets.secret_tree().retained_key_count()
+            + self
+                .past_epoch_trees
+                .iter()
+                .map(|epoch_tree| {
+                    epoch_tree
+                        .message_secrets
+                        .secret_tree()
+                        .retained_key_count()
+                })
+                .sum::<usize>()
+    }
+
+    /// Delete the message secrets of the oldest past epochs until the
+    /// estimated size of the store is at most `budget` bytes. The message
+    /// secrets of the current epoch are never deleted, so the store can
+    /// still exceed the budget afterwards.
+    pub(crate) fn enforce_budget(&mut self, budget: usize, ciphersuite: Ciphersuite) {
+        let mut size = self.current_epoch_size(ciphersuite) + self.past_epochs_size(ciphersuite);
+        while size > budget {
+            match self.past_epoch_trees.pop_front() {
+                Some(epoch_tree) => size -= epoch_tree.size(ciphersuite),
+                None => break,
+            }
+        }
+    }
+
+    /// Delete the key material of the own sender ratchets of all past epochs,
+    /// since no messages are encrypted in past epochs.
+    pub(crate) fn erase_past_encryption_ratchets(
+        &mut self,
+        ciphersuite: Ciphersuite,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<(), SecretTreeError> {
+        for epoch_tree in self.past_epoch_trees.iter_mut() {
+            epoch_tree
+                .message_secrets
+                .secret_tree_mut()
+                .erase_own_sender_ratchets(ciphersuite, backend)?;
+        }
+        Ok(())
+    }
+
     /// Get a mutable reference to a secret tree for a given epoch `group_epoch`.
     /// If no message secrets are found for that epoch, `None` is returned.
     pub(crate) fn secrets_for_epoch_mut(
@@ -137,6 +237,19 @@ impl MessageSecretsStore {
         None
     }
 
+    /// Get a reference to the exporter secret for a given epoch `group_epoch`.
+    /// If no exporter secret is found for that epoch, `None` is returned.
+    pub(crate) fn exporter_secret_for_epoch(
+        &self,
+        group_epoch: impl Into<GroupEpoch>,
+    ) -> Option<&ExporterSecret> {
+        let epoch = group_epoch.into().as_u64();
+        self.past_epoch_trees
+            .iter()
+            .find(|epoch_tree| epoch_tree.epoch == epoch)
+            .and_then(|epoch_tree| epoch_tree.exporter_secret.as_ref())
+    }
+
     /// Return a slice with the [`Member`]s of the `group_epoch`.
     pub(crate) fn leaves_for_epoch(&self, group_epoch: impl Into<GroupEpoch>) -> &[Member] {
         let epoch = group_epoch.into().as_u64();
@@ -148,20 +261,41 @@ impl MessageSecretsStore {
         &[]
     }
 
+    /// Return a slice with the [`Member`]s of the past epoch `group_epoch`
+    /// or `None` if the epoch is not stored.
+    pub(crate) fn members_for_past_epoch(
+        &self,
+        group_epoch: impl Into<GroupEpoch>,
+    ) -> Option<&[Member]> {
+        let epoch = group_epoch.into().as_u64();
+        self.past_epoch_trees
+            .iter()
+            .find(|epoch_tree| epoch_tree.epoch == epoch)
+            .map(|epoch_tree| epoch_tree.leaves.as_slice())
+    }
+
     /// Check if the provided epoch contains a leaf index.
     pub(crate) fn epoch_has_leaf(
         &self,
         group_epoch: GroupEpoch,
         leaf_index: LeafNodeIndex,
     ) -> bool {
+        // The leaves are sorted by their index.
         self.past_epoch_trees.iter().any(|t| {
             t.epoch == group_epoch.0
                 && t.leaves
-                    .iter()
-                    .any(|Member { index, .. }| *index == leaf_index)
+                    .binary_search_by_key(&leaf_index, |Member { index, .. }| *index)
+                    .is_ok()
         })
     }
 
+    /// Returns the epochs for which past message secrets are stored.
+    pub(crate) fn past_epochs(&self) -> impl Iterator<Item = GroupEpoch> + '_ {
+        self.past_epoch_trees
+            .iter()
+            .map(|epoch_tree| GroupEpoch::from(epoch_tree.epoch))
+    }
+
     /// Get a mutable reference to the message secrets of the current epoch.
     pub(crate) fn message_secrets_mut(&mut self) -> &mut MessageSecrets {
         &mut self.message_secrets
@@ -38,6 +38,7 @@ impl CoreGroup {
     ///  - ValSem243
     ///  - ValSem244
     ///  - ValSem246 (as part of ValSem010)
+    ///  - ValSem248
     pub(crate) fn process_unverified_message(
         &self,
         backend: &impl OpenMlsCryptoProvider,
@@ -61,6 +62,7 @@ impl CoreGroup {
                     FramedContentBody::Application(application_message) => {
                         ProcessedMessageContent::ApplicationMessage(ApplicationMessage::new(
                             application_message.as_slice().to_owned(),
+                            authenticated_data.clone(),
                         ))
                     }
                     FramedContentBody::Proposal(_) => {
@@ -69,6 +71,8 @@ impl CoreGroup {
                             backend,
                             content,
                         )?);
+                        self.public_group
+                            .validate_proposal_permission(&sender, proposal.proposal())?;
                         if matches!(sender, Sender::NewMemberProposal) {
                             ProcessedMessageContent::ExternalJoinProposalMessage(proposal)
                         } else {
@@ -166,6 +170,7 @@ impl CoreGroup {
     ///  - ValSem244
     ///  - ValSem245
     ///  - ValSem246 (as part of ValSem010)
+    ///  - ValSem248
     pub(crate) fn process_message(
         &mut self,
         backend: &impl OpenMlsCryptoProvider,
@@ -291,16 +296,32 @@ impl CoreGroup {
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         staged_commit: StagedCommit,
         proposal_store: &mut ProposalStore,
+        retain_exporter_secret: bool,
+        now: Option<u64>,
     ) -> Result<(), MergeCommitError<KeyStore::Error>> {
         // Save the past epoch
         let past_epoch = self.context().epoch();
-        // Get all the full leaves
-        let leaves = self.public_group().members().collect();
-        // Merge the staged commit into the group state and store the secret tree from the
-        // previous epoch in the message secrets store.
-        if let Some(message_secrets) = self.merge_commit(backend, staged_commit)? {
-            self.message_secrets_store
-                .add(past_epoch, message_secrets, leaves);
+        // Get all the full leaves, if they are kept for the past epoch
+        let leaves = if self.message_secrets_store.stores_past_epochs() {
+            self.public_group().members().collect()
+        } else {
+            Vec::new()
+        };
+        // Merge the staged commit into the group state and store the secret tree and, if
+        // requested, the exporter secret from the previous epoch in the message secrets store.
+        // The previous epoch ended `now`.
+        if let Some((message_secrets, group_epoch_secrets)) =
+            self.merge_commit(backend, staged_commit)?
+        {
+            let exporter_secret =
+                retain_exporter_secret.then(|| group_epoch_secrets.into_exporter_secret());
+            self.message_secrets_store.add(
+                past_epoch,
+                message_secrets,
+                leaves,
+                exporter_secret,
+                now,
+            );
         }
         // Empty the proposal store
         proposal_store.empty();
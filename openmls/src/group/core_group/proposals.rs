@@ -31,7 +31,7 @@ impl ProposalStore {
             queued_proposals: Vec::new(),
         }
     }
-    #[cfg(test)]
+    #[cfg(any(feature = "test-vectors", test))]
     pub(crate) fn from_queued_proposal(queued_proposal: QueuedProposal) -> Self {
         Self {
             queued_proposals: vec![queued_proposal],
@@ -46,22 +46,83 @@ impl ProposalStore {
     pub(crate) fn is_empty(&self) -> bool {
         self.queued_proposals.is_empty()
     }
+    pub(crate) fn len(&self) -> usize {
+        self.queued_proposals.len()
+    }
     pub(crate) fn empty(&mut self) {
         self.queued_proposals.clear();
     }
 
+    /// Adds a proposal to the store while keeping at most `max_len`
+    /// proposals. If the store is full, the `policy` decides whether the
+    /// oldest proposal is evicted or the new one is rejected.
+    ///
+    /// Returns the proposal that was evicted or rejected, if any.
+    pub(crate) fn add_bounded(
+        &mut self,
+        queued_proposal: QueuedProposal,
+        max_len: Option<usize>,
+        policy: ProposalStoreFullPolicy,
+    ) -> Option<QueuedProposal> {
+        match max_len {
+            Some(max_len) if self.len() >= max_len => match policy {
+                // A maximum of zero disables the store.
+                ProposalStoreFullPolicy::EvictOldest if max_len > 0 => {
+                    let evicted = self.remove_oldest();
+                    self.add(queued_proposal);
+                    evicted
+                }
+                ProposalStoreFullPolicy::EvictOldest | ProposalStoreFullPolicy::Reject => {
+                    Some(queued_proposal)
+                }
+            },
+            _ => {
+                self.add(queued_proposal);
+                None
+            }
+        }
+    }
+
     /// Removes a proposal from the store using its reference. It will return None if it wasn't
     /// found in the store.
-    pub(crate) fn remove(&mut self, proposal_ref: ProposalRef) -> Option<()> {
+    pub(crate) fn remove(&mut self, proposal_ref: ProposalRef) -> Option<QueuedProposal> {
         let index = self
             .queued_proposals
             .iter()
             .position(|p| p.proposal_reference() == proposal_ref)?;
-        self.queued_proposals.remove(index);
-        Some(())
+        Some(self.queued_proposals.remove(index))
+    }
+
+    /// Removes the proposal that was added to the store first. It will return None if the store
+    /// is empty.
+    pub(crate) fn remove_oldest(&mut self) -> Option<QueuedProposal> {
+        if self.queued_proposals.is_empty() {
+            return None;
+        }
+        Some(self.queued_proposals.remove(0))
     }
 }
 
+/// Defines what happens to a new proposal if the proposal store of a group
+/// already holds the maximum number of pending proposals.
+///
+/// Either way, a proposal that isn't stored can't be resolved if a commit
+/// references it, so the commit can't be processed and the member falls out
+/// of sync with the group. The maximum should therefore be well above the
+/// number of proposals honest members send per epoch.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProposalStoreFullPolicy {
+    /// Evict the oldest pending proposal to make room for the new one.
+    ///
+    /// This allows a member spamming proposals to evict the proposals of
+    /// honest members, such that the commits covering them can't be
+    /// processed.
+    EvictOldest,
+    /// Reject the new proposal. This is the default.
+    #[default]
+    Reject,
+}
+
 /// Alternative representation of a Proposal, where the sender is extracted from
 /// the encapsulating PublicMessage and the ProposalRef is attached.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -151,7 +212,7 @@ impl QueuedProposal {
         &self.proposal
     }
     /// Returns the `ProposalRef`.
-    pub(crate) fn proposal_reference(&self) -> ProposalRef {
+    pub fn proposal_reference(&self) -> ProposalRef {
         self.proposal_reference.clone()
     }
     /// Returns the `ProposalOrRefType`.
@@ -402,6 +463,7 @@ impl ProposalQueue {
         let mut proposal_pool: HashMap<ProposalRef, QueuedProposal> = HashMap::new();
         let mut contains_own_updates = false;
         let mut contains_external_init = false;
+        let mut group_context_extensions = None;
 
         // Aggregate both proposal types to a common iterator
         // We checked earlier that only proposals can end up here
@@ -476,7 +538,8 @@ impl ProposalQueue {
                     }
                 }
                 Proposal::GroupContextExtensions(_) => {
-                    // TODO: Validate proposal?
+                    // Only keep the last GroupContextExtensions proposal.
+                    group_context_extensions = Some(queued_proposal.proposal_reference());
                     proposal_pool.insert(queued_proposal.proposal_reference(), queued_proposal);
                 }
                 Proposal::AppAck(_) => unimplemented!("See #291"),
@@ -496,6 +559,9 @@ impl ProposalQueue {
                 valid_proposals.insert(last_update.proposal_reference());
             }
         }
+        if let Some(group_context_extensions) = group_context_extensions {
+            valid_proposals.insert(group_context_extensions);
+        }
         // Only retain `adds` and `valid_proposals`
         let mut proposal_queue = ProposalQueue::default();
         for proposal_reference in adds.iter().chain(valid_proposals.iter()) {
@@ -1,7 +1,7 @@
 use core::fmt::Debug;
 use std::mem;
 
-use openmls_traits::key_store::OpenMlsKeyStore;
+use openmls_traits::key_store::{MlsEntity, MlsEntityId, OpenMlsKeyStore};
 use public_group::diff::{apply_proposals::ApplyProposalsValues, StagedPublicGroupDiff};
 
 use super::{super::errors::*, proposals::ProposalStore, *};
@@ -110,6 +110,7 @@ impl CoreGroup {
     ///  - ValSem242
     ///  - ValSem243
     ///  - ValSem244
+    ///  - ValSem248
     /// Returns an error if the given commit was sent by the owner of this
     /// group.
     pub(crate) fn stage_commit(
@@ -146,6 +147,8 @@ impl CoreGroup {
             return Ok(StagedCommit::new(
                 proposal_queue,
                 StagedCommitState::PublicState(Box::new(staged_diff)),
+                commit.path.as_ref().map(|path| path.leaf_node().clone()),
+                mls_content.sender().clone(),
             ));
         }
 
@@ -264,7 +267,12 @@ impl CoreGroup {
                 new_leaf_keypair_option,
             )));
 
-        Ok(StagedCommit::new(proposal_queue, staged_commit_state))
+        Ok(StagedCommit::new(
+            proposal_queue,
+            staged_commit_state,
+            commit.path.as_ref().map(|path| path.leaf_node().clone()),
+            mls_content.sender().clone(),
+        ))
     }
 
     /// Merges a [StagedCommit] into the group state and optionally return a [`SecretTree`]
@@ -276,7 +284,8 @@ impl CoreGroup {
         &mut self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         staged_commit: StagedCommit,
-    ) -> Result<Option<MessageSecrets>, MergeCommitError<KeyStore::Error>> {
+    ) -> Result<Option<(MessageSecrets, GroupEpochSecrets)>, MergeCommitError<KeyStore::Error>>
+    {
         // Get all keypairs from the old epoch, so we can later store the ones
         // that are still relevant in the new epoch.
         let old_epoch_keypairs = self.read_epoch_keypairs(backend);
@@ -286,7 +295,9 @@ impl CoreGroup {
                 Ok(None)
             }
             StagedCommitState::GroupMember(state) => {
-                self.group_epoch_secrets = state.group_epoch_secrets;
+                // Replace the previous group epoch secrets with the new ones and return the previous ones
+                let mut group_epoch_secrets = state.group_epoch_secrets;
+                mem::swap(&mut group_epoch_secrets, &mut self.group_epoch_secrets);
 
                 // Replace the previous message secrets with the new ones and return the previous message secrets
                 let mut message_secrets = state.message_secrets;
@@ -339,7 +350,7 @@ impl CoreGroup {
                         .map_err(MergeCommitError::KeyStoreError)?;
                 }
 
-                Ok(Some(message_secrets))
+                Ok(Some((message_secrets, group_epoch_secrets)))
             }
         }
     }
@@ -378,18 +389,37 @@ pub(crate) enum StagedCommitState {
 pub struct StagedCommit {
     staged_proposal_queue: ProposalQueue,
     state: StagedCommitState,
+    #[serde(default)]
+    update_path_leaf_node: Option<LeafNode>,
+    // The sender of the commit. This is `None` if the commit was staged by a
+    // version of OpenMLS that didn't record it.
+    #[serde(default)]
+    sender: Option<Sender>,
 }
 
 impl StagedCommit {
     /// Create a new [`StagedCommit`] from the provisional group state created
     /// during the commit process.
-    pub(crate) fn new(staged_proposal_queue: ProposalQueue, state: StagedCommitState) -> Self {
+    pub(crate) fn new(
+        staged_proposal_queue: ProposalQueue,
+        state: StagedCommitState,
+        update_path_leaf_node: Option<LeafNode>,
+        sender: Sender,
+    ) -> Self {
         StagedCommit {
             staged_proposal_queue,
             state,
+            update_path_leaf_node,
+            sender: Some(sender),
         }
     }
 
+    /// Returns the leaf node of the committer's update path, or `None` if the
+    /// Commit message doesn't contain a path.
+    pub fn update_path_leaf_node(&self) -> Option<&LeafNode> {
+        self.update_path_leaf_node.as_ref()
+    }
+
     /// Returns the Add proposals that are covered by the Commit message as in iterator over [QueuedAddProposal].
     pub fn add_proposals(&self) -> impl Iterator<Item = QueuedAddProposal> {
         self.staged_proposal_queue.add_proposals()
@@ -416,12 +446,45 @@ impl StagedCommit {
         matches!(self.state, StagedCommitState::PublicState(_))
     }
 
+    /// Returns the ID of the group the Commit message belongs to.
+    pub fn group_id(&self) -> &GroupId {
+        self.group_context().group_id()
+    }
+
+    /// Returns the epoch in which the Commit message was staged. The commit
+    /// can only be merged into a group in this epoch.
+    pub fn epoch(&self) -> GroupEpoch {
+        GroupEpoch::from(self.group_context().epoch().as_u64().saturating_sub(1))
+    }
+
+    /// Returns the group context of the group after the commit is merged.
+    pub(crate) fn group_context(&self) -> &GroupContext {
+        match &self.state {
+            StagedCommitState::PublicState(staged_diff) => staged_diff.group_context(),
+            StagedCommitState::GroupMember(state) => state.staged_diff.group_context(),
+        }
+    }
+
+    /// Returns the sender of the Commit message, or `None` if it is unknown.
+    pub(crate) fn sender(&self) -> Option<&Sender> {
+        self.sender.as_ref()
+    }
+
+    /// Returns all proposals that are covered by the Commit message.
+    pub(crate) fn queued_proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
+        self.staged_proposal_queue.queued_proposals()
+    }
+
     /// Consume this [`StagedCommit`] and return the internal [`StagedCommitState`].
     pub(crate) fn into_state(self) -> StagedCommitState {
         self.state
     }
 }
 
+impl MlsEntity for StagedCommit {
+    const ID: MlsEntityId = MlsEntityId::GroupState;
+}
+
 /// This struct is used internally by [StagedCommit] to encapsulate all the modified group state.
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct MemberStagedCommitState {
@@ -1,5 +1,7 @@
 //! This module contains tests regarding the use of [`MessageSecretsStore`]
 
+use std::time::Duration;
+
 use crate::{
     binary_tree::LeafNodeIndex, group::past_secrets::MessageSecretsStore,
     schedule::message_secrets::MessageSecrets, test_utils::*,
@@ -18,6 +20,8 @@ fn test_secret_tree_store(ciphersuite: Ciphersuite, backend: &impl OpenMlsCrypto
         0,
         MessageSecrets::random(ciphersuite, backend, LeafNodeIndex::new(0)),
         Vec::new(),
+        None,
+        None,
     );
 
     // Make sure we can access the message secrets we just stored
@@ -29,6 +33,8 @@ fn test_secret_tree_store(ciphersuite: Ciphersuite, backend: &impl OpenMlsCrypto
             i,
             MessageSecrets::random(ciphersuite, backend, LeafNodeIndex::new(0)),
             Vec::new(),
+            None,
+            None,
         );
     }
 
@@ -57,8 +63,97 @@ fn test_empty_secret_tree_store(ciphersuite: Ciphersuite, backend: &impl OpenMls
         0,
         MessageSecrets::random(ciphersuite, backend, LeafNodeIndex::new(0)),
         Vec::new(),
+        None,
+        None,
     );
 
     // Make sure we cannot access the message secrets we just stored
     assert!(message_secrets_store.secrets_for_epoch_mut(0).is_none());
 }
+
+#[apply(ciphersuites_and_backends)]
+fn test_expired_secret_tree_store(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let now = 1_000_000;
+
+    // Create a store that keeps up to 3 epochs
+    let mut message_secrets_store = MessageSecretsStore::new_with_secret(
+        3,
+        MessageSecrets::random(ciphersuite, backend, LeafNodeIndex::new(0)),
+    );
+
+    // Add message secrets for two epochs to the store
+    for i in 0..2u64 {
+        message_secrets_store.add(
+            i,
+            MessageSecrets::random(ciphersuite, backend, LeafNodeIndex::new(0)),
+            Vec::new(),
+            None,
+            Some(now),
+        );
+    }
+
+    // Nothing has expired yet
+    message_secrets_store.delete_expired(Duration::from_secs(60), now + 60);
+    assert!(message_secrets_store.secrets_for_epoch(0).is_some());
+    assert!(message_secrets_store.secrets_for_epoch(1).is_some());
+
+    // Pretend that an hour has passed, all past epochs expired
+    message_secrets_store.delete_expired(Duration::from_secs(60), now + 3600);
+    assert!(message_secrets_store.secrets_for_epoch(0).is_none());
+    assert!(message_secrets_store.secrets_for_epoch(1).is_none());
+    assert_eq!(message_secrets_store.past_epochs().count(), 0);
+
+    // Epochs added afterwards are stored again
+    message_secrets_store.add(
+        2,
+        MessageSecrets::random(ciphersuite, backend, LeafNodeIndex::new(0)),
+        Vec::new(),
+        None,
+        Some(now + 3600),
+    );
+    assert!(message_secrets_store.secrets_for_epoch(2).is_some());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn test_legacy_secret_tree_store_expiry(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) {
+    let now = 1_000_000;
+
+    let mut message_secrets_store = MessageSecretsStore::new_with_secret(
+        3,
+        MessageSecrets::random(ciphersuite, backend, LeafNodeIndex::new(0)),
+    );
+    message_secrets_store.add(
+        0,
+        MessageSecrets::random(ciphersuite, backend, LeafNodeIndex::new(0)),
+        Vec::new(),
+        None,
+        Some(now),
+    );
+
+    // Strip the end of the epoch, as in state persisted before it was recorded
+    let mut serialized =
+        serde_json::to_value(&message_secrets_store).expect("error serializing store");
+    for epoch_tree in serialized["past_epoch_trees"]
+        .as_array_mut()
+        .expect("past epoch trees are not a list")
+    {
+        epoch_tree
+            .as_object_mut()
+            .expect("epoch tree is not an object")
+            .remove("retired_at");
+    }
+    let mut message_secrets_store: MessageSecretsStore =
+        serde_json::from_value(serialized).expect("error deserializing store");
+
+    // The epoch isn't purged on load, but expires `max_age` after the first
+    // purge
+    message_secrets_store.delete_expired(Duration::from_secs(60), now + 3600);
+    assert!(message_secrets_store.secrets_for_epoch(0).is_some());
+    message_secrets_store.delete_expired(Duration::from_secs(60), now + 3600 + 60);
+    assert!(message_secrets_store.secrets_for_epoch(0).is_some());
+    message_secrets_store.delete_expired(Duration::from_secs(60), now + 3600 + 61);
+    assert!(message_secrets_store.secrets_for_epoch(0).is_none());
+}
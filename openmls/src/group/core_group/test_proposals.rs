@@ -659,3 +659,59 @@ fn test_group_context_extension_proposal(
             .expect("Error exporting secret.")
     )
 }
+
+#[apply(ciphersuites_and_backends)]
+fn test_multiple_group_context_extension_proposals(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) {
+    // Basic group setup.
+    let group_aad = b"Alice's test group";
+    let framing_parameters = FramingParameters::new(group_aad, WireFormat::PublicMessage);
+
+    let (alice_credential, _, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    let alice_group = CoreGroup::builder(
+        GroupId::random(backend),
+        CryptoConfig::with_default_version(ciphersuite),
+        alice_credential,
+    )
+    .build(backend, &alice_signer)
+    .expect("Error creating CoreGroup.");
+
+    // Alice proposes two different sets of group context extensions.
+    let mut proposal_store = ProposalStore::default();
+    for extension_types in [&[][..], &[ExtensionType::ApplicationId][..]] {
+        let required_capabilities = Extension::RequiredCapabilities(
+            RequiredCapabilitiesExtension::new(extension_types, &[], &[CredentialType::Basic]),
+        );
+        let gce_proposal = alice_group
+            .create_group_context_ext_proposal(
+                framing_parameters,
+                Extensions::single(required_capabilities),
+                &alice_signer,
+            )
+            .expect("Error creating gce proposal.");
+        proposal_store.add(
+            QueuedProposal::from_authenticated_content_by_ref(ciphersuite, backend, gce_proposal)
+                .expect("Could not create QueuedProposal."),
+        );
+    }
+
+    // A commit can't contain more than one GroupContextExtensions proposal.
+    let params = CreateCommitParams::builder()
+        .framing_parameters(framing_parameters)
+        .proposal_store(&proposal_store)
+        .force_self_update(false)
+        .build();
+    let error = alice_group
+        .create_commit(params, backend, &alice_signer)
+        .expect_err("Created a commit with two gce proposals.");
+    assert_eq!(
+        error,
+        CreateCommitError::ProposalValidationError(
+            ProposalValidationError::MultipleGroupContextExtensionsProposals
+        )
+    );
+}
@@ -4,6 +4,7 @@
 
 use thiserror::Error;
 
+pub use super::mls_client::errors::*;
 pub use super::mls_group::errors::*;
 use super::public_group::errors::{CreationFromExternalError, PublicGroupBuildError};
 use crate::{
@@ -54,6 +55,9 @@ pub enum WelcomeError<KeyStoreError> {
     /// We don't support all capabilities of the group.
     #[error("We don't support all capabilities of the group.")]
     UnsupportedCapability,
+    /// We don't accept all media types required by the group.
+    #[error("We don't accept all media types required by the group.")]
+    UnsupportedMediaTypes,
     /// Sender not found in tree.
     #[error("Sender not found in tree.")]
     UnknownSender,
@@ -88,6 +92,15 @@ pub enum WelcomeError<KeyStoreError> {
     /// This error indicates the leaf node is invalid. See [`LeafNodeValidationError`] for more details.
     #[error(transparent)]
     LeafNodeValidation(#[from] LeafNodeValidationError),
+    /// The credential of a group member was rejected by the credential validator.
+    #[error("The credential of a group member was rejected by the credential validator.")]
+    InvalidCredential,
+    /// The key package or its init key was already consumed by another Welcome message.
+    #[error("The key package or its init key was already consumed by another Welcome message.")]
+    KeyPackageAlreadyConsumed,
+    /// See [`ExtensionError`] for more details.
+    #[error(transparent)]
+    InvalidExtension(#[from] ExtensionError),
 }
 
 /// External Commit error
@@ -366,6 +379,19 @@ pub enum ProposalValidationError {
     /// The capabilities of the add proposal are insufficient for this group.
     #[error("The capabilities of the add proposal are insufficient for this group.")]
     InsufficientCapabilities,
+    /// A member or new member doesn't accept the media types required by the group.
+    #[error("A member or new member doesn't accept the media types required by the group.")]
+    UnsupportedMediaTypes,
+    /// The sender of a proposal doesn't have the role required by the group.
+    #[error("The sender of a proposal doesn't have the role required by the group.")]
+    InsufficientRole,
+    /// The identity of the leaf node in the update path of a commit did not
+    /// match the existing identity of the committer.
+    #[error("The identity of the update path did not match the existing identity.")]
+    UpdatePathIdentityMismatch,
+    /// The Commit includes more than one GroupContextExtensions proposal.
+    #[error("The Commit includes more than one GroupContextExtensions proposal.")]
+    MultipleGroupContextExtensionsProposals,
     /// See [`PskError`] for more details.
     #[error(transparent)]
     Psk(#[from] PskError),
@@ -386,7 +412,6 @@ pub enum ExternalCommitValidationError {
     /// Found inline Add or Update proposals.
     #[error("Found inline Add or Update proposals.")]
     InvalidInlineProposals,
-    // TODO #803: this seems unused
     /// Found multiple inline Remove proposals.
     #[error("Found multiple inline Remove proposals.")]
     MultipleRemoveProposals,
@@ -403,6 +428,15 @@ pub enum ExternalCommitValidationError {
     /// External commit contains referenced proposal
     #[error("Found a referenced proposal in an External Commit.")]
     ReferencedProposal,
+    /// The message is not an External Commit.
+    #[error("The message is not an External Commit.")]
+    NotAnExternalCommit,
+    /// The GroupInfo doesn't match the current state of the group.
+    #[error("The GroupInfo doesn't match the current state of the group.")]
+    GroupInfoMismatch,
+    /// The GroupInfo doesn't contain an external_pub extension.
+    #[error("The GroupInfo doesn't contain an external_pub extension.")]
+    MissingExternalPub,
 }
 
 /// Create add proposal error
@@ -414,6 +448,20 @@ pub enum CreateAddProposalError {
     /// See [`LeafNodeValidationError`] for more details.
     #[error(transparent)]
     LeafNodeValidation(#[from] LeafNodeValidationError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
+/// Create remove proposal error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum CreateRemoveProposalError {
+    /// The member that should be removed can not be found.
+    #[error("The member that should be removed can not be found.")]
+    UnknownMember,
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
 }
 
 // === Crate errors ===
@@ -426,6 +474,8 @@ pub(crate) enum ExporterError {
     LibraryError(#[from] LibraryError),
     #[error("The requested key length is not supported (too large).")]
     KeyLengthTooLong,
+    #[error("The exporter secret of the requested epoch is not available.")]
+    UnknownEpoch,
 }
 
 /// Proposal queue error
@@ -508,22 +558,22 @@ pub(crate) enum CoreGroupParseMessageError {
 
 /// Create group context ext proposal error
 #[derive(Error, Debug, PartialEq, Clone)]
-pub(crate) enum CreateGroupContextExtProposalError {
+pub enum CreateGroupContextExtProposalError {
     /// See [`LibraryError`] for more details.
     #[error(transparent)]
     LibraryError(#[from] LibraryError),
     /// See [`KeyPackageExtensionSupportError`] for more details.
     #[error(transparent)]
     KeyPackageExtensionSupport(#[from] KeyPackageExtensionSupportError),
-    /// See [`TreeSyncError`] for more details.
-    #[error(transparent)]
-    TreeSyncError(#[from] TreeSyncError),
     /// See [`ExtensionError`] for more details.
     #[error(transparent)]
     Extension(#[from] ExtensionError),
     /// See [`LeafNodeValidationError`] for more details.
     #[error(transparent)]
     LeafNodeValidation(#[from] LeafNodeValidationError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
 }
 
 /// Error merging a commit.
@@ -535,4 +585,7 @@ pub enum MergeCommitError<KeyStoreError> {
     /// Error accessing the key store.
     #[error("Error accessing the key store.")]
     KeyStoreError(KeyStoreError),
+    /// The commit was staged for a different group or epoch.
+    #[error("The commit was staged for a different group or epoch.")]
+    WrongEpoch,
 }
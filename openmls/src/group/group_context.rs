@@ -14,6 +14,7 @@ use crate::{
 #[derive(
     Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GroupContext {
     protocol_version: ProtocolVersion,
     ciphersuite: Ciphersuite,
@@ -79,6 +80,12 @@ impl GroupContext {
         self.tree_hash = new_tree_hash.into()
     }
 
+    /// Replace the extensions, e.g., when applying a GroupContextExtensions
+    /// proposal.
+    pub(crate) fn set_extensions(&mut self, extensions: Extensions) {
+        self.extensions = extensions
+    }
+
     /// Update the confirmed transcript hash using the given
     /// `interim_transcript_hash`, as well as the `commit_content`.
     pub(crate) fn update_confirmed_transcript_hash(
@@ -0,0 +1,52 @@
+//! # MlsClient errors
+//!
+//! This module defines the public errors that can be returned from all calls
+//! to methods of [`MlsClient`](super::MlsClient).
+
+// These errors are exposed through `crate::group::errors`.
+
+use thiserror::Error;
+
+use crate::{
+    error::LibraryError,
+    group::errors::{NewGroupError, ProcessMessageError, WelcomeError},
+    key_packages::errors::KeyPackageNewError,
+};
+
+/// MlsClient error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum MlsClientError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The message belongs to a group that is not managed by this client.
+    #[error("The message belongs to a group that is not managed by this client.")]
+    NoMatchingGroup,
+    /// A group with the same group ID is already managed by this client.
+    #[error("A group with the same group ID is already managed by this client.")]
+    DuplicateGroup,
+    /// The message is from a future epoch of the group.
+    #[error("The message is from a future epoch of the group.")]
+    FutureEpoch,
+    /// No client state was found in the key store.
+    #[error("No client state was found in the key store.")]
+    ClientStateNotFound,
+    /// The state of a group managed by this client was not found in the key store.
+    #[error("The state of a group managed by this client was not found in the key store.")]
+    GroupStateNotFound,
+    /// See [`ProcessMessageError`] for more details.
+    #[error(transparent)]
+    ProcessMessageError(#[from] ProcessMessageError),
+    /// See [`WelcomeError`] for more details.
+    #[error(transparent)]
+    WelcomeError(#[from] WelcomeError<KeyStoreError>),
+    /// See [`NewGroupError`] for more details.
+    #[error(transparent)]
+    NewGroupError(#[from] NewGroupError<KeyStoreError>),
+    /// See [`KeyPackageNewError`] for more details.
+    #[error(transparent)]
+    KeyPackageNewError(#[from] KeyPackageNewError<KeyStoreError>),
+    /// Error accessing the key store.
+    #[error("Error accessing the key store.")]
+    KeyStoreError(KeyStoreError),
+}
@@ -0,0 +1,380 @@
+//! MLS Client
+//!
+//! This module contains [`MlsClient`], a manager for all [`MlsGroup`]s of a
+//! single client. It routes incoming messages to the right group, keeps track
+//! of the client's key packages and persists its state through the key store
+//! of the backend.
+//!
+//! The client holds its credential and public signature key, but not the
+//! private signature key. The private key is only accessed through a
+//! [`Signer`] that is passed to every operation that signs.
+
+use std::collections::HashMap;
+
+use openmls_traits::{
+    key_store::{MlsEntity, MlsEntityId, OpenMlsKeyStore},
+    signatures::Signer,
+    types::Ciphersuite,
+    OpenMlsCryptoProvider,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ciphersuite::hash_ref::KeyPackageRef,
+    credentials::CredentialWithKey,
+    framing::{MlsMessageIn, MlsMessageInBody, ProcessedMessage, ProtocolMessage},
+    group::{config::CryptoConfig, GroupId, MlsGroup, MlsGroupConfig},
+    key_packages::{KeyPackage, KeyPackageIn},
+    messages::{group_info::VerifiableGroupInfo, Welcome},
+    treesync::RatchetTreeIn,
+    versions::ProtocolVersion,
+};
+
+pub mod errors;
+
+use errors::MlsClientError;
+
+// Tests
+#[cfg(test)]
+mod test_mls_client;
+
+/// The result of dispatching an [`MlsMessageIn`] with
+/// [`MlsClient::process_message()`].
+#[derive(Debug)]
+pub enum DispatchedMessage {
+    /// A public or private message that was processed by the group with the
+    /// corresponding [`GroupId`]. Staged commits still have to be merged
+    /// through [`MlsClient::group_mut()`].
+    Processed(ProcessedMessage),
+    /// A [`Welcome`] message that was used to join the group with the given
+    /// [`GroupId`].
+    Joined(GroupId),
+    /// A group info that is not bound to any group managed by the client.
+    GroupInfo(VerifiableGroupInfo),
+    /// A key package that is not bound to any group managed by the client.
+    KeyPackage(KeyPackageIn),
+}
+
+/// The persisted state of an [`MlsClient`]. The state of the individual
+/// groups is stored separately, indexed by their [`GroupId`].
+#[derive(Serialize, Deserialize)]
+struct MlsClientState {
+    credential_with_key: CredentialWithKey,
+    mls_group_config: MlsGroupConfig,
+    group_ids: Vec<GroupId>,
+    key_package_refs: Vec<KeyPackageRef>,
+}
+
+impl MlsEntity for MlsClientState {
+    const ID: MlsEntityId = MlsEntityId::ClientState;
+}
+
+/// A client that manages any number of [`MlsGroup`]s.
+///
+/// The [`MlsClient`] owns all groups of a client, routes incoming messages to
+/// the group they belong to and keeps track of the key packages the client
+/// has published.
+///
+/// The [`MlsClient`] doesn't store or persist the private signature key of
+/// the client, which may, e.g., never leave dedicated hardware. Operations
+/// that sign, like [`MlsClient::create_group()`], take a [`Signer`] for the
+/// signature key in the [`CredentialWithKey`] of the client. The same
+/// [`Signer`] has to be used for the groups returned by
+/// [`MlsClient::group_mut()`].
+///
+/// All state is persisted through the key store of the backend with
+/// [`MlsClient::save()`] and can be restored with [`MlsClient::load()`].
+#[derive(Debug)]
+pub struct MlsClient {
+    client_id: Vec<u8>,
+    credential_with_key: CredentialWithKey,
+    mls_group_config: MlsGroupConfig,
+    groups: HashMap<GroupId, MlsGroup>,
+    key_package_refs: Vec<KeyPackageRef>,
+}
+
+impl MlsClient {
+    /// Creates a new [`MlsClient`] with the given `client_id`. The
+    /// `client_id` is used to index the client state in the key store.
+    ///
+    /// The `mls_group_config` is used for all groups that are created or
+    /// joined through this client.
+    pub fn new(
+        client_id: impl Into<Vec<u8>>,
+        credential_with_key: CredentialWithKey,
+        mls_group_config: MlsGroupConfig,
+    ) -> Self {
+        Self {
+            client_id: client_id.into(),
+            credential_with_key,
+            mls_group_config,
+            groups: HashMap::new(),
+            key_package_refs: Vec::new(),
+        }
+    }
+
+    // === Load & save ===
+
+    /// Loads the client with the given `client_id` and all its groups from
+    /// the key store of the backend.
+    ///
+    /// Returns an error if no state was persisted for the client or if the
+    /// state of one of its groups is missing.
+    pub fn load<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        client_id: impl Into<Vec<u8>>,
+    ) -> Result<Self, MlsClientError<KeyStore::Error>> {
+        let client_id = client_id.into();
+        let state: MlsClientState = backend
+            .key_store()
+            .read(&Self::key_store_id(&client_id))
+            .ok_or(MlsClientError::ClientStateNotFound)?;
+
+        let groups = state
+            .group_ids
+            .into_iter()
+            .map(|group_id| {
+                MlsGroup::read(backend.key_store(), &group_id)
+                    .map(|group| (group_id, group))
+                    .ok_or(MlsClientError::GroupStateNotFound)
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        Ok(Self {
+            client_id,
+            credential_with_key: state.credential_with_key,
+            mls_group_config: state.mls_group_config,
+            groups,
+            key_package_refs: state.key_package_refs,
+        })
+    }
+
+    /// Persists the state of the client and of all its groups in the key
+    /// store of the backend.
+    ///
+    /// Returns an error if access to the key store fails.
+    pub fn save<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), MlsClientError<KeyStore::Error>> {
+        for group in self.groups.values_mut() {
+            group
+                .store(backend.key_store())
+                .map_err(MlsClientError::KeyStoreError)?;
+        }
+
+        let state = MlsClientState {
+            credential_with_key: self.credential_with_key.clone(),
+            mls_group_config: self.mls_group_config.clone(),
+            group_ids: self.groups.keys().cloned().collect(),
+            key_package_refs: self.key_package_refs.clone(),
+        };
+        backend
+            .key_store()
+            .store(&Self::key_store_id(&self.client_id), &state)
+            .map_err(MlsClientError::KeyStoreError)
+    }
+
+    // === Accessors ===
+
+    /// Returns the ID of the client.
+    pub fn client_id(&self) -> &[u8] {
+        &self.client_id
+    }
+
+    /// Returns the credential and signature public key of the client.
+    pub fn credential_with_key(&self) -> &CredentialWithKey {
+        &self.credential_with_key
+    }
+
+    /// Returns the group configuration used for new groups.
+    pub fn mls_group_config(&self) -> &MlsGroupConfig {
+        &self.mls_group_config
+    }
+
+    /// Returns the group with the given [`GroupId`] if it is managed by the
+    /// client.
+    pub fn group(&self, group_id: &GroupId) -> Option<&MlsGroup> {
+        self.groups.get(group_id)
+    }
+
+    /// Returns a mutable reference to the group with the given [`GroupId`]
+    /// if it is managed by the client.
+    pub fn group_mut(&mut self, group_id: &GroupId) -> Option<&mut MlsGroup> {
+        self.groups.get_mut(group_id)
+    }
+
+    /// Returns an iterator over all groups managed by the client.
+    pub fn groups(&self) -> impl Iterator<Item = &MlsGroup> {
+        self.groups.values()
+    }
+
+    /// Returns the references of all key packages generated by the client
+    /// that have not been used to join a group yet.
+    pub fn key_package_refs(&self) -> &[KeyPackageRef] {
+        &self.key_package_refs
+    }
+
+    // === Key packages ===
+
+    /// Generates a new [`KeyPackage`] for the given `ciphersuite` and stores
+    /// its private key material in the key store of the backend.
+    pub fn generate_key_package<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        ciphersuite: Ciphersuite,
+    ) -> Result<KeyPackage, MlsClientError<KeyStore::Error>> {
+        let key_package = KeyPackage::builder().build(
+            CryptoConfig {
+                ciphersuite,
+                version: ProtocolVersion::default(),
+            },
+            backend,
+            signer,
+            self.credential_with_key.clone(),
+        )?;
+        self.key_package_refs
+            .push(key_package.hash_ref(backend.crypto())?);
+        Ok(key_package)
+    }
+
+    // === Group management ===
+
+    /// Creates a new group with the client as the only member and returns
+    /// its [`GroupId`].
+    pub fn create_group<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+    ) -> Result<GroupId, MlsClientError<KeyStore::Error>> {
+        let group = MlsGroup::new(
+            backend,
+            signer,
+            &self.mls_group_config,
+            self.credential_with_key.clone(),
+        )?;
+        self.insert_group(group)
+    }
+
+    /// Joins a group from a [`Welcome`] message and returns its [`GroupId`].
+    /// The key package used to join the group is removed from the client.
+    ///
+    /// Returns [`MlsClientError::DuplicateGroup`] if a group with the same
+    /// [`GroupId`] is already managed by the client. The key package is not
+    /// consumed in this case.
+    pub fn join_group<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        welcome: Welcome,
+        ratchet_tree: Option<RatchetTreeIn>,
+    ) -> Result<GroupId, MlsClientError<KeyStore::Error>> {
+        // Check for a duplicate group before the key package is consumed
+        let group_id = MlsGroup::welcome_group_id(backend, &self.mls_group_config, &welcome)?;
+        if self.groups.contains_key(&group_id) {
+            return Err(MlsClientError::DuplicateGroup);
+        }
+        let new_members: Vec<KeyPackageRef> = welcome
+            .secrets()
+            .iter()
+            .map(|egs| egs.new_member())
+            .collect();
+        let group =
+            MlsGroup::new_from_welcome(backend, &self.mls_group_config, welcome, ratchet_tree)?;
+        self.key_package_refs
+            .retain(|kp_ref| !new_members.contains(kp_ref));
+        self.insert_group(group)
+    }
+
+    /// Removes the group with the given [`GroupId`] from the client and
+    /// deletes its state from the key store of the backend.
+    ///
+    /// Returns the removed group, or `None` if the group is not managed by
+    /// the client.
+    pub fn remove_group<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        group_id: &GroupId,
+    ) -> Result<Option<MlsGroup>, MlsClientError<KeyStore::Error>> {
+        // Only remove the group once its state is deleted, such that it is
+        // still managed by the client if the key store fails
+        if !self.groups.contains_key(group_id) {
+            return Ok(None);
+        }
+        MlsGroup::delete(backend.key_store(), group_id).map_err(MlsClientError::KeyStoreError)?;
+        Ok(self.groups.remove(group_id))
+    }
+
+    // === Message dispatch ===
+
+    /// Dispatches an incoming [`MlsMessageIn`].
+    ///
+    /// Public and private messages are routed to the group with the matching
+    /// [`GroupId`] and processed there. A [`Welcome`] is used to join a new
+    /// group. Group infos and key packages are returned to the caller as they
+    /// are not bound to a group.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MlsClientError::NoMatchingGroup`] if the message belongs to
+    /// a group that is not managed by the client and
+    /// [`MlsClientError::FutureEpoch`] if the message is from an epoch the
+    /// group has not reached yet. Such messages can be retried after the
+    /// pending commits of the group have been processed.
+    pub fn process_message<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        message: MlsMessageIn,
+        ratchet_tree: Option<RatchetTreeIn>,
+    ) -> Result<DispatchedMessage, MlsClientError<KeyStore::Error>> {
+        let protocol_message: ProtocolMessage = match message.extract() {
+            MlsMessageInBody::PublicMessage(message) => message.into(),
+            MlsMessageInBody::PrivateMessage(message) => message.into(),
+            MlsMessageInBody::Welcome(welcome) => {
+                return self
+                    .join_group(backend, welcome, ratchet_tree)
+                    .map(DispatchedMessage::Joined)
+            }
+            MlsMessageInBody::GroupInfo(group_info) => {
+                return Ok(DispatchedMessage::GroupInfo(group_info))
+            }
+            MlsMessageInBody::KeyPackage(key_package) => {
+                return Ok(DispatchedMessage::KeyPackage(key_package))
+            }
+        };
+
+        let group = self
+            .groups
+            .get_mut(protocol_message.group_id())
+            .ok_or(MlsClientError::NoMatchingGroup)?;
+        if protocol_message.epoch() > group.epoch() {
+            return Err(MlsClientError::FutureEpoch);
+        }
+
+        group
+            .process_message(backend, protocol_message)
+            .map(DispatchedMessage::Processed)
+            .map_err(MlsClientError::from)
+    }
+
+    // === Helpers ===
+
+    /// Adds a group to the client. Returns an error if a group with the same
+    /// [`GroupId`] is already managed by the client.
+    fn insert_group<KeyStoreError>(
+        &mut self,
+        group: MlsGroup,
+    ) -> Result<GroupId, MlsClientError<KeyStoreError>> {
+        let group_id = group.group_id().clone();
+        if self.groups.contains_key(&group_id) {
+            return Err(MlsClientError::DuplicateGroup);
+        }
+        self.groups.insert(group_id.clone(), group);
+        Ok(group_id)
+    }
+
+    /// Composite key for the client state in the key store.
+    fn key_store_id(client_id: &[u8]) -> Vec<u8> {
+        [b"MlsClient".as_slice(), client_id].concat()
+    }
+}
@@ -0,0 +1,173 @@
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::{
+    credentials::{test_utils::new_credential, CredentialType},
+    framing::*,
+    group::{errors::*, *},
+    messages::Welcome,
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn test_mls_client_dispatch(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+
+    let (alice_credential_with_key, alice_signer) = new_credential(
+        backend,
+        b"Alice",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let (bob_credential_with_key, bob_signer) = new_credential(
+        backend,
+        b"Bob",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+
+    let mut alice = MlsClient::new("alice", alice_credential_with_key, mls_group_config.clone());
+    let mut bob = MlsClient::new("bob", bob_credential_with_key, mls_group_config);
+
+    // === Bob publishes a key package ===
+    let bob_key_package = bob
+        .generate_key_package(backend, &bob_signer, ciphersuite)
+        .expect("Could not generate key package");
+    assert_eq!(bob.key_package_refs().len(), 1);
+
+    // === Alice creates a group and adds Bob ===
+    let group_id = alice
+        .create_group(backend, &alice_signer)
+        .expect("Could not create group");
+    let alice_group = alice.group_mut(&group_id).expect("Group not found");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_key_package])
+        .expect("Could not add Bob");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit");
+
+    // === Bob joins through the dispatcher ===
+    let dispatched = bob
+        .process_message(backend, welcome.into(), None)
+        .expect("Could not process Welcome");
+    assert!(matches!(dispatched, DispatchedMessage::Joined(ref id) if id == &group_id));
+    assert!(bob.key_package_refs().is_empty());
+
+    // === Alice sends an application message ===
+    let message = alice
+        .group_mut(&group_id)
+        .expect("Group not found")
+        .create_message(backend, &alice_signer, b"Hello Bob")
+        .expect("Could not create message");
+    match bob
+        .process_message(backend, message.into(), None)
+        .expect("Could not process message")
+    {
+        DispatchedMessage::Processed(processed_message) => {
+            assert_eq!(processed_message.group_id(), &group_id);
+            match processed_message.into_content() {
+                ProcessedMessageContent::ApplicationMessage(application_message) => {
+                    assert_eq!(application_message.into_bytes(), b"Hello Bob")
+                }
+                _ => panic!("Expected an application message"),
+            }
+        }
+        _ => panic!("Expected a processed message"),
+    }
+
+    // === Messages for unknown groups are rejected ===
+    let mut charlie = MlsClient::new(
+        "charlie",
+        bob.credential_with_key().clone(),
+        bob.mls_group_config().clone(),
+    );
+    let message = alice
+        .group_mut(&group_id)
+        .expect("Group not found")
+        .create_message(backend, &alice_signer, b"Hello Charlie")
+        .expect("Could not create message");
+    assert!(matches!(
+        charlie.process_message(backend, message.into(), None),
+        Err(MlsClientError::NoMatchingGroup)
+    ));
+
+    // === Bob persists and restores his state ===
+    let bob_epoch = bob.group(&group_id).expect("Group not found").epoch();
+    bob.save(backend).expect("Could not save client");
+    let bob = MlsClient::load(backend, "bob").expect("Could not load client");
+    assert_eq!(bob.groups().count(), 1);
+    assert_eq!(
+        bob.group(&group_id).expect("Group not found").epoch(),
+        bob_epoch
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn test_mls_client_duplicate_group(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+
+    let (alice_credential_with_key, alice_signer) = new_credential(
+        backend,
+        b"Alice",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let (bob_credential_with_key, bob_signer) = new_credential(
+        backend,
+        b"Bob",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+
+    let mut bob = MlsClient::new("bob", bob_credential_with_key, mls_group_config.clone());
+    let bob_key_packages = [
+        bob.generate_key_package(backend, &bob_signer, ciphersuite)
+            .expect("Could not generate key package"),
+        bob.generate_key_package(backend, &bob_signer, ciphersuite)
+            .expect("Could not generate key package"),
+    ];
+
+    // === Alice invites Bob to two groups with the same group ID ===
+    let group_id = GroupId::from_slice(b"Test Group");
+    let welcomes: Vec<Welcome> = bob_key_packages
+        .iter()
+        .map(|key_package| {
+            let mut group = MlsGroup::new_with_group_id(
+                backend,
+                &alice_signer,
+                &mls_group_config,
+                group_id.clone(),
+                alice_credential_with_key.clone(),
+            )
+            .expect("Could not create group");
+            let (_commit, welcome, _group_info) = group
+                .add_members(backend, &alice_signer, &[key_package.clone()])
+                .expect("Could not add Bob");
+            welcome.into_welcome().expect("Unexpected message type")
+        })
+        .collect();
+
+    // === Bob joins the first group and rejects the second one ===
+    bob.join_group(backend, welcomes[0].clone(), None)
+        .expect("Could not join group");
+    let error = bob
+        .join_group(backend, welcomes[1].clone(), None)
+        .expect_err("Joined a group twice");
+    assert_eq!(error, MlsClientError::DuplicateGroup);
+    assert_eq!(bob.key_package_refs().len(), 1);
+
+    // === The second key package is still usable ===
+    bob.remove_group(backend, &group_id)
+        .expect("Could not remove group")
+        .expect("Group not found");
+    assert!(bob.group(&group_id).is_none());
+    bob.join_group(backend, welcomes[1].clone(), None)
+        .expect("Could not join group");
+    assert!(bob.key_package_refs().is_empty());
+}
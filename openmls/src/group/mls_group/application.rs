@@ -1,6 +1,11 @@
 use openmls_traits::signatures::Signer;
 
-use super::{errors::CreateMessageError, *};
+use crate::{framing::errors::MessageEncryptionError, messages::targeted_message::TargetedMessage};
+
+use super::{
+    errors::{CreateMessageError, CreateTargetedMessageError},
+    *,
+};
 
 impl MlsGroup {
     // === Application messages ===
@@ -11,11 +16,44 @@ impl MlsGroup {
     /// Returns `CreateMessageError::MlsGroupStateError::PendingProposal` if pending proposals
     /// exist. In that case `.process_pending_proposals()` must be called first
     /// and incoming messages from the DS must be processed afterwards.
+    ///
+    /// The AAD set via [`MlsGroup::set_aad()`] is included as authenticated
+    /// data. Use [`MlsGroup::create_message_with_aad()`] to set the
+    /// authenticated data for a single message.
     pub fn create_message(
         &mut self,
         backend: &impl OpenMlsCryptoProvider,
         signer: &impl Signer,
         message: &[u8],
+    ) -> Result<MlsMessageOut, CreateMessageError> {
+        self.create_application_message(backend, signer, message, None)
+    }
+
+    /// Creates an application message like [`MlsGroup::create_message()`],
+    /// but with the given `aad` as authenticated data instead of the AAD set
+    /// via [`MlsGroup::set_aad()`]. The AAD of the group is not changed.
+    ///
+    /// The authenticated data is not encrypted, but it is signed and bound to
+    /// the message. Recipients can access it through
+    /// [`ApplicationMessage::aad()`].
+    pub fn create_message_with_aad(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        signer: &impl Signer,
+        message: &[u8],
+        aad: &[u8],
+    ) -> Result<MlsMessageOut, CreateMessageError> {
+        self.create_application_message(backend, signer, message, Some(aad))
+    }
+
+    /// Creates an application message with the given `aad`, or the AAD of the
+    /// group if it is `None`.
+    fn create_application_message(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        signer: &impl Signer,
+        message: &[u8],
+        aad: Option<&[u8]>,
     ) -> Result<MlsMessageOut, CreateMessageError> {
         if !self.is_active() {
             return Err(CreateMessageError::GroupStateError(
@@ -28,24 +66,88 @@ impl MlsGroup {
             ));
         }
 
+        let aad = aad.unwrap_or(&self.aad);
         let ciphertext = self
             .group
             .create_application_message(
-                &self.aad,
+                aad,
                 message,
-                self.configuration().padding_size(),
+                &self.configuration().padding_policy(),
                 backend,
                 signer,
             )
-            // We know the application message is wellformed and we have the key material of the current epoch
-            .map_err(|_| LibraryError::custom("Malformed plaintext"))?;
+            .map_err(|e| match e {
+                MessageEncryptionError::SignatureError(e) => CreateMessageError::SignatureError(e),
+                // We know the application message is wellformed and we have the key material of the current epoch
+                _ => LibraryError::custom("Malformed plaintext").into(),
+            })?;
 
         // Since the state of the group might be changed, arm the state flag
         self.flag_state_change();
+        self.record_sender_ratchet_update(self.epoch(), self.own_leaf_index());
 
         Ok(MlsMessageOut::from_private_message(
             ciphertext,
             self.group.version(),
         ))
     }
+
+    /// Creates an application message like [`MlsGroup::create_message()`] and
+    /// appends the serialized [`MlsMessageOut`] to `buffer`. This allows
+    /// reusing the same buffer for many messages.
+    ///
+    /// Returns the number of bytes written to `buffer`.
+    pub fn create_message_into(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        signer: &impl Signer,
+        message: &[u8],
+        buffer: &mut Vec<u8>,
+    ) -> Result<usize, CreateMessageError> {
+        let message = self.create_message(backend, signer, message)?;
+        let written = message
+            .serialize_into(buffer)
+            .map_err(|_| LibraryError::custom("Serializing the message failed"))?;
+        Ok(written)
+    }
+
+    /// Creates a [`TargetedMessage`] that can only be decrypted by the member
+    /// at `recipient_index`. The message is signed with the own leaf's
+    /// signature key and bound to the current group context. The AAD set via
+    /// [`MlsGroup::set_aad()`] is included as authenticated data.
+    ///
+    /// Returns `CreateTargetedMessageError::MlsGroupStateError::UseAfterEviction`
+    /// if the member is no longer part of the group.
+    /// Returns [`CreateTargetedMessageError::UnknownRecipient`] if there is no
+    /// member at `recipient_index`.
+    pub fn create_targeted_message(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        signer: &impl Signer,
+        recipient_index: LeafNodeIndex,
+        payload: &[u8],
+    ) -> Result<TargetedMessage, CreateTargetedMessageError> {
+        if !self.is_active() {
+            return Err(CreateTargetedMessageError::GroupStateError(
+                MlsGroupStateError::UseAfterEviction,
+            ));
+        }
+
+        let recipient = self
+            .group
+            .public_group()
+            .leaf(recipient_index)
+            .ok_or(CreateTargetedMessageError::UnknownRecipient)?;
+
+        Ok(TargetedMessage::new(
+            backend,
+            signer,
+            self.group.context(),
+            self.own_leaf_index(),
+            recipient_index,
+            recipient.encryption_key(),
+            &self.aad,
+            payload,
+        )?)
+    }
 }
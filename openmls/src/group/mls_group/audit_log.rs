@@ -0,0 +1,286 @@
+//! # Commit audit log
+//!
+//! If [`MlsGroupConfig::use_commit_audit_log()`] is set, an [`MlsGroup`]
+//! records a [`CommitAuditEntry`] for every commit it merges. An entry
+//! contains the sender of the commit, a [`ProposalSummary`] of the membership
+//! changes it made, as well as the confirmed transcript hash and the
+//! confirmation tag of the epoch the commit started. Since the transcript
+//! hash chains all commits, two members can compare their logs to find the
+//! commit at which their view of the group diverged, and auditors can check
+//! after the fact that all membership changes were authorized.
+//!
+//! The log is append-only and is persisted with the group. It can be exported
+//! as a [`CommitAuditLog`] that is signed by the exporting member with
+//! [`MlsGroup::export_commit_audit_log()`].
+
+use openmls_traits::{signatures::Signer, OpenMlsCryptoProvider};
+use serde::{Deserialize, Serialize};
+use tls_codec::{Serialize as TlsSerializeTrait, TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+
+use super::{errors::ExportCommitAuditLogError, *};
+use crate::{
+    ciphersuite::{
+        signable::{signature_labels, Signable, SignatureError, SignedStruct, Verifiable},
+        OpenMlsSignaturePublicKey, Signature,
+    },
+    messages::ConfirmationTag,
+};
+
+const SIGNATURE_COMMIT_AUDIT_LOG_LABEL: &str = signature_labels::COMMIT_AUDIT_LOG;
+
+/// A summary of the proposals covered by a commit.
+///
+/// ```c
+/// struct {
+///     ProposalType proposal_types<V>;
+///     Credential added_members<V>;
+///     Credential removed_members<V>;
+/// } ProposalSummary;
+/// ```
+#[derive(
+    Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct ProposalSummary {
+    proposal_types: Vec<ProposalType>,
+    added_members: Vec<Credential>,
+    removed_members: Vec<Credential>,
+}
+
+impl ProposalSummary {
+    /// Returns the types of the proposals in the order they were applied.
+    pub fn proposal_types(&self) -> &[ProposalType] {
+        &self.proposal_types
+    }
+
+    /// Returns the credentials of the members that were added.
+    pub fn added_members(&self) -> &[Credential] {
+        &self.added_members
+    }
+
+    /// Returns the credentials of the members that were removed.
+    pub fn removed_members(&self) -> &[Credential] {
+        &self.removed_members
+    }
+}
+
+/// An entry of the commit audit log, recorded when a commit is merged.
+///
+/// ```c
+/// struct {
+///     uint64 epoch;
+///     Sender sender;
+///     ProposalSummary proposals;
+///     opaque confirmed_transcript_hash<V>;
+///     MAC confirmation_tag;
+/// } CommitAuditEntry;
+/// ```
+#[derive(
+    Debug, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct CommitAuditEntry {
+    epoch: GroupEpoch,
+    sender: Sender,
+    proposals: ProposalSummary,
+    confirmed_transcript_hash: VLBytes,
+    confirmation_tag: ConfirmationTag,
+}
+
+impl CommitAuditEntry {
+    /// Returns the epoch the commit started.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the sender of the commit.
+    pub fn sender(&self) -> &Sender {
+        &self.sender
+    }
+
+    /// Returns the summary of the proposals covered by the commit.
+    pub fn proposals(&self) -> &ProposalSummary {
+        &self.proposals
+    }
+
+    /// Returns the confirmed transcript hash of the epoch the commit started.
+    pub fn confirmed_transcript_hash(&self) -> &[u8] {
+        self.confirmed_transcript_hash.as_slice()
+    }
+
+    /// Returns the confirmation tag of the commit.
+    pub fn confirmation_tag(&self) -> &ConfirmationTag {
+        &self.confirmation_tag
+    }
+}
+
+/// Commit audit log (To Be Signed)
+///
+/// ```c
+/// struct {
+///     opaque group_id<V>;
+///     uint64 epoch;
+///     uint32 signer;
+///     CommitAuditEntry entries<V>;
+/// } CommitAuditLogTBS;
+/// ```
+#[derive(Debug, PartialEq, Clone, TlsSerialize, TlsDeserialize, TlsSize)]
+struct CommitAuditLogTbs {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    signer: LeafNodeIndex,
+    entries: Vec<CommitAuditEntry>,
+}
+
+/// The commit audit log of a group, signed by the member that exported it.
+///
+/// ```c
+/// struct {
+///     CommitAuditLogTBS payload;
+///     /* SignWithLabel(., "CommitAuditLogTBS", CommitAuditLogTBS) */
+///     opaque signature<V>;
+/// } CommitAuditLog;
+/// ```
+#[derive(Debug, PartialEq, Clone, TlsSerialize, TlsDeserialize, TlsSize)]
+pub struct CommitAuditLog {
+    payload: CommitAuditLogTbs,
+    signature: Signature,
+}
+
+impl CommitAuditLog {
+    /// Returns the group ID of the group the log was exported from.
+    pub fn group_id(&self) -> &GroupId {
+        &self.payload.group_id
+    }
+
+    /// Returns the epoch in which the log was exported.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.payload.epoch
+    }
+
+    /// Returns the leaf index of the member that exported and signed the log.
+    pub fn signer(&self) -> LeafNodeIndex {
+        self.payload.signer
+    }
+
+    /// Returns the entries of the log, oldest first.
+    pub fn entries(&self) -> &[CommitAuditEntry] {
+        &self.payload.entries
+    }
+
+    /// Verifies the signature of the log with the `signature_key` of the
+    /// member at [`CommitAuditLog::signer()`].
+    pub fn verify(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        signature_key: &OpenMlsSignaturePublicKey,
+    ) -> Result<(), SignatureError> {
+        self.verify_no_out(backend.crypto(), signature_key)
+    }
+}
+
+impl Signable for CommitAuditLogTbs {
+    type SignedOutput = CommitAuditLog;
+
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.tls_serialize_detached()
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_COMMIT_AUDIT_LOG_LABEL
+    }
+}
+
+impl SignedStruct<CommitAuditLogTbs> for CommitAuditLog {
+    fn from_payload(payload: CommitAuditLogTbs, signature: Signature) -> Self {
+        Self { payload, signature }
+    }
+}
+
+impl Verifiable for CommitAuditLog {
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.payload.tls_serialize_detached()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_COMMIT_AUDIT_LOG_LABEL
+    }
+}
+
+impl MlsGroup {
+    // === Commit audit log ===
+
+    /// Returns the entries of the commit audit log, oldest first.
+    ///
+    /// The log is empty unless
+    /// [`MlsGroupConfig::use_commit_audit_log()`] is set.
+    pub fn commit_audit_log(&self) -> &[CommitAuditEntry] {
+        &self.commit_audit_log
+    }
+
+    /// Exports the commit audit log, signed with the own signature key.
+    ///
+    /// Returns [`ExportCommitAuditLogError::AuditLogDisabled`] if
+    /// [`MlsGroupConfig::use_commit_audit_log()`] is not set.
+    pub fn export_commit_audit_log(
+        &self,
+        signer: &impl Signer,
+    ) -> Result<CommitAuditLog, ExportCommitAuditLogError> {
+        if !self.is_active() {
+            return Err(MlsGroupStateError::UseAfterEviction.into());
+        }
+        if !self.configuration().use_commit_audit_log() {
+            return Err(ExportCommitAuditLogError::AuditLogDisabled);
+        }
+
+        let tbs = CommitAuditLogTbs {
+            group_id: self.group_id().clone(),
+            epoch: self.epoch(),
+            signer: self.own_leaf_index(),
+            entries: self.commit_audit_log.clone(),
+        };
+        tbs.sign(signer)
+            .map_err(ExportCommitAuditLogError::SignatureError)
+    }
+
+    /// Summarizes the proposals of the `staged_commit`. This has to be called
+    /// before the commit is merged, such that the credentials of removed
+    /// members can still be looked up.
+    pub(crate) fn summarize_proposals(&self, staged_commit: &StagedCommit) -> ProposalSummary {
+        let mut summary = ProposalSummary {
+            proposal_types: vec![],
+            added_members: vec![],
+            removed_members: vec![],
+        };
+        for queued_proposal in staged_commit.queued_proposals() {
+            let proposal = queued_proposal.proposal();
+            summary.proposal_types.push(proposal.proposal_type());
+            match proposal {
+                Proposal::Add(add_proposal) => summary
+                    .added_members
+                    .push(add_proposal.key_package().leaf_node().credential().clone()),
+                Proposal::Remove(remove_proposal) => {
+                    if let Some(leaf) = self.group.public_group().leaf(remove_proposal.removed()) {
+                        summary.removed_members.push(leaf.credential().clone());
+                    }
+                }
+                _ => (),
+            }
+        }
+        summary
+    }
+
+    /// Appends an entry for the commit that was just merged to the commit
+    /// audit log.
+    pub(crate) fn record_commit(&mut self, sender: Sender, proposals: ProposalSummary) {
+        self.commit_audit_log.push(CommitAuditEntry {
+            epoch: self.epoch(),
+            sender,
+            proposals,
+            confirmed_transcript_hash: self.confirmed_transcript_hash().into(),
+            confirmation_tag: self.group.public_group().confirmation_tag().clone(),
+        });
+    }
+}
@@ -13,7 +13,22 @@
 //! ```
 //!
 //! See [`MlsGroupConfigBuilder`](MlsGroupConfigBuilder#implementations) for
-//! all options that can be configured.
+//! all options that can be configured. [`MlsGroupConfig::validate()`] checks a
+//! configuration for options that contradict each other.
+//!
+//! ### Presets
+//! [`MlsGroupConfig::rfc_default()`], [`MlsGroupConfig::high_security()`] and
+//! [`MlsGroupConfig::large_group()`] return configurations for common use
+//! cases.
+//!
+//! ```
+//! use openmls::prelude::*;
+//!
+//! let group_config = MlsGroupConfig::high_security(
+//!     Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+//! );
+//! assert!(group_config.validate().is_ok());
+//! ```
 //!
 //! ### Wire format policies
 //! Only some combination of possible wire formats are valid within OpenMLS.
@@ -29,10 +44,17 @@
 
 use super::*;
 use crate::{
-    group::config::CryptoConfig, tree::sender_ratchet::SenderRatchetConfiguration,
-    treesync::node::leaf_node::Lifetime,
+    credentials::{basic_identity::BasicIdentitySchema, CredentialValidator},
+    extensions::{
+        AcceptedMediaTypesExtension, CustomExtensionRegistry, RequiredMediaTypesExtension,
+        RolesExtension,
+    },
+    group::config::CryptoConfig,
+    tree::sender_ratchet::SenderRatchetConfiguration,
+    treesync::node::leaf_node::{Lifetime, SystemTimeProvider, TimeProvider},
 };
 use serde::{Deserialize, Serialize};
+use std::{fmt, sync::Arc, time::Duration};
 
 /// Specifies the configuration parameters for a [`MlsGroup`]. Refer to
 /// the [User Manual](https://openmls.tech/book/user_manual/group_config.html) for more information about the different configuration values.
@@ -41,11 +63,33 @@ pub struct MlsGroupConfig {
     /// Defines the wire format policy for outgoing and incoming handshake messages.
     /// Application are always encrypted regardless.
     pub(crate) wire_format_policy: WireFormatPolicy,
-    /// Size of padding in bytes
+    /// Size of padding in bytes. Only used if no padding policy is set.
     pub(crate) padding_size: usize,
+    /// Padding policy for encrypted messages
+    #[serde(default)]
+    pub(crate) padding_policy: Option<PaddingPolicy>,
+    /// Fragmentation configuration for application messages
+    #[serde(default)]
+    pub(crate) fragmentation_config: FragmentationConfig,
     /// Maximum number of past epochs for which application messages
     /// can be decrypted. The default is 0.
     pub(crate) max_past_epochs: usize,
+    /// Maximum time for which application messages from past epochs can be
+    /// decrypted after the epoch ended. The default is `None`, i.e., no limit.
+    #[serde(default)]
+    pub(crate) max_past_epoch_age: Option<Duration>,
+    /// Maximum estimated size in bytes of the message secrets of the current
+    /// and past epochs. The default is `None`, i.e., no limit.
+    #[serde(default)]
+    pub(crate) message_secrets_budget: Option<usize>,
+    /// Flag to indicate that key material is deleted as soon as it was used
+    /// once, without any tolerance for out-of-order messages
+    #[serde(default)]
+    pub(crate) immediate_key_deletion: bool,
+    /// Flag to indicate that the exporter secrets of past epochs are kept
+    /// along with their message secrets
+    #[serde(default)]
+    pub(crate) retain_past_exporter_secrets: bool,
     /// Number of resumtion secrets to keep
     pub(crate) number_of_resumption_psks: usize,
     /// Flag to indicate the Ratchet Tree Extension should be used
@@ -60,6 +104,76 @@ pub struct MlsGroupConfig {
     pub(crate) lifetime: Lifetime,
     /// Ciphersuite and protocol version
     pub(crate) crypto_config: CryptoConfig,
+    /// Validator for the credentials encountered in the group. The validator
+    /// is not persisted and has to be set again after loading a group.
+    #[serde(skip)]
+    pub(crate) credential_validator: Option<SharedCredentialValidator>,
+    /// Flag to indicate that a credential validator was configured. Unlike
+    /// the validator itself, the flag is persisted, such that a loaded group
+    /// refuses to process messages until the validator is set again.
+    #[serde(default)]
+    pub(crate) requires_credential_validator: bool,
+    /// Flag to indicate that signature keys should be pinned to identities
+    #[serde(default)]
+    pub(crate) use_signature_key_pinning: bool,
+    /// Flag to indicate that every commit should return a freshly signed
+    /// GroupInfo
+    #[serde(default)]
+    pub(crate) always_return_group_info: bool,
+    /// Flag to indicate that merged commits should be recorded in the commit
+    /// audit log
+    #[serde(default)]
+    pub(crate) use_commit_audit_log: bool,
+    /// Flag to indicate that every identity may only occupy one leaf
+    #[serde(default)]
+    pub(crate) unique_identities: bool,
+    /// Schema the identities of basic credentials must match
+    #[serde(default)]
+    pub(crate) basic_identity_schema: Option<BasicIdentitySchema>,
+    /// Identifier of the crypto provider backing the group
+    #[serde(default)]
+    pub(crate) crypto_provider: Option<String>,
+    /// Application-defined extension types supported by the group. The
+    /// registry is not persisted and has to be set again after loading a
+    /// group.
+    #[serde(skip)]
+    pub(crate) custom_extensions: CustomExtensionRegistry,
+    /// Media types the own leaf accepts
+    #[serde(default)]
+    pub(crate) accepted_media_types: Option<AcceptedMediaTypesExtension>,
+    /// Media types all members of a new group must accept
+    #[serde(default)]
+    pub(crate) required_media_types: Option<RequiredMediaTypesExtension>,
+    /// Roles of the members of a new group
+    #[serde(default)]
+    pub(crate) roles: Option<RolesExtension>,
+    /// Policy for the validation of leaf nodes
+    #[serde(default)]
+    pub(crate) leaf_node_validation_policy: LeafNodeValidationPolicy,
+    /// Validator for the authenticated data of incoming messages. The
+    /// validator is not persisted and has to be set again after loading a
+    /// group.
+    #[serde(skip)]
+    pub(crate) aad_validator: Option<SharedAadValidator>,
+    /// Maximum number of pending proposals per epoch. The default is `None`,
+    /// i.e., no limit.
+    #[serde(default)]
+    pub(crate) max_pending_proposals: Option<usize>,
+    /// What happens to new proposals if the proposal store is full
+    #[serde(default)]
+    pub(crate) proposal_store_full_policy: ProposalStoreFullPolicy,
+    /// Policy for scheduled updates of the own leaf
+    #[serde(default)]
+    pub(crate) update_policy: Option<UpdatePolicy>,
+    /// Source of the current time for the validation of lifetimes and
+    /// credential expiry. The time provider is not persisted and has to be
+    /// set again after loading a group.
+    #[serde(skip)]
+    pub(crate) time_provider: Option<SharedTimeProvider>,
+    /// Clock skew that is tolerated when validating lifetimes and credential
+    /// expiry. The default is 0.
+    #[serde(default)]
+    pub(crate) clock_skew_tolerance: Duration,
 }
 
 impl MlsGroupConfig {
@@ -78,11 +192,69 @@ impl MlsGroupConfig {
         self.padding_size
     }
 
+    /// Returns the [`MlsGroupConfig`] padding policy. If no padding policy
+    /// was set, messages are padded to a multiple of the padding size.
+    pub fn padding_policy(&self) -> PaddingPolicy {
+        self.padding_policy
+            .clone()
+            .unwrap_or(PaddingPolicy::Multiple(self.padding_size))
+    }
+
+    /// Returns the [`MlsGroupConfig`] fragmentation configuration.
+    pub fn fragmentation_config(&self) -> &FragmentationConfig {
+        &self.fragmentation_config
+    }
+
     /// Returns the [`MlsGroupConfig`] max past epochs.
     pub fn max_past_epochs(&self) -> usize {
         self.max_past_epochs
     }
 
+    /// Returns the [`MlsGroupConfig`] max past epoch age.
+    pub fn max_past_epoch_age(&self) -> Option<Duration> {
+        self.max_past_epoch_age
+    }
+
+    /// Returns the [`MlsGroupConfig`] message secrets budget.
+    pub fn message_secrets_budget(&self) -> Option<usize> {
+        self.message_secrets_budget
+    }
+
+    /// Returns the [`MlsGroupConfig`] immediate key deletion flag.
+    pub fn immediate_key_deletion(&self) -> bool {
+        self.immediate_key_deletion
+    }
+
+    /// Returns the [`MlsGroupConfig`] retain past exporter secrets flag.
+    pub fn retain_past_exporter_secrets(&self) -> bool {
+        self.retain_past_exporter_secrets
+    }
+
+    /// Returns the [`MlsGroupConfig`] clock skew tolerance.
+    pub fn clock_skew_tolerance(&self) -> Duration {
+        self.clock_skew_tolerance
+    }
+
+    /// Returns the [`MlsGroupConfig`] max pending proposals.
+    pub fn max_pending_proposals(&self) -> Option<usize> {
+        self.max_pending_proposals
+    }
+
+    /// Returns the [`MlsGroupConfig`] proposal store full policy.
+    pub fn proposal_store_full_policy(&self) -> ProposalStoreFullPolicy {
+        self.proposal_store_full_policy
+    }
+
+    /// Returns the [`MlsGroupConfig`] update policy.
+    pub fn update_policy(&self) -> Option<&UpdatePolicy> {
+        self.update_policy.as_ref()
+    }
+
+    /// Returns the [`MlsGroupConfig`] leaf node validation policy.
+    pub fn leaf_node_validation_policy(&self) -> LeafNodeValidationPolicy {
+        self.leaf_node_validation_policy
+    }
+
     /// Returns the [`MlsGroupConfig`] number of resumption psks.
     pub fn number_of_resumption_psks(&self) -> usize {
         self.number_of_resumption_psks
@@ -93,6 +265,31 @@ impl MlsGroupConfig {
         self.use_ratchet_tree_extension
     }
 
+    /// Returns the [`MlsGroupConfig`] boolean flag that indicates whether signature keys should be pinned.
+    pub fn use_signature_key_pinning(&self) -> bool {
+        self.use_signature_key_pinning
+    }
+
+    /// Returns the [`MlsGroupConfig`] boolean flag that indicates whether every commit returns a GroupInfo.
+    pub fn always_return_group_info(&self) -> bool {
+        self.always_return_group_info
+    }
+
+    /// Returns the [`MlsGroupConfig`] boolean flag that indicates whether merged commits are recorded in the commit audit log.
+    pub fn use_commit_audit_log(&self) -> bool {
+        self.use_commit_audit_log
+    }
+
+    /// Returns the [`MlsGroupConfig`] boolean flag that indicates whether every identity may only occupy one leaf.
+    pub fn unique_identities(&self) -> bool {
+        self.unique_identities
+    }
+
+    /// Returns the [`MlsGroupConfig`] schema for the identities of basic credentials.
+    pub fn basic_identity_schema(&self) -> Option<&BasicIdentitySchema> {
+        self.basic_identity_schema.as_ref()
+    }
+
     /// Returns the [`MlsGroupConfig`] sender ratchet configuration.
     pub fn sender_ratchet_configuration(&self) -> &SenderRatchetConfiguration {
         &self.sender_ratchet_configuration
@@ -113,6 +310,199 @@ impl MlsGroupConfig {
         &self.crypto_config
     }
 
+    /// Returns the identifier of the crypto provider backing the group, if any.
+    /// See [`CryptoProviders`](super::providers::CryptoProviders) for more information.
+    pub fn crypto_provider(&self) -> Option<&str> {
+        self.crypto_provider.as_deref()
+    }
+
+    /// Returns the [`MlsGroupConfig`] custom extension registry.
+    pub fn custom_extensions(&self) -> &CustomExtensionRegistry {
+        &self.custom_extensions
+    }
+
+    /// Returns the [`MlsGroupConfig`] accepted media types.
+    pub fn accepted_media_types(&self) -> Option<&AcceptedMediaTypesExtension> {
+        self.accepted_media_types.as_ref()
+    }
+
+    /// Returns the [`MlsGroupConfig`] required media types.
+    pub fn required_media_types(&self) -> Option<&RequiredMediaTypesExtension> {
+        self.required_media_types.as_ref()
+    }
+
+    /// Returns the [`MlsGroupConfig`] roles.
+    pub fn roles(&self) -> Option<&RolesExtension> {
+        self.roles.as_ref()
+    }
+
+    /// Returns the [`MlsGroupConfig`] credential validator.
+    pub fn credential_validator(&self) -> Option<&dyn CredentialValidator> {
+        self.credential_validator
+            .as_ref()
+            .map(|validator| validator.0.as_ref())
+    }
+
+    /// Returns the [`MlsGroupConfig`] AAD validator.
+    pub fn aad_validator(&self) -> Option<&dyn AadValidator> {
+        self.aad_validator
+            .as_ref()
+            .map(|validator| validator.0.as_ref())
+    }
+
+    /// Returns the [`MlsGroupConfig`] time provider. If none is configured,
+    /// the system clock is used.
+    pub fn time_provider(&self) -> &dyn TimeProvider {
+        self.time_provider
+            .as_ref()
+            .map(|time_provider| time_provider.0.as_ref())
+            .unwrap_or(&SystemTimeProvider)
+    }
+
+    /// Validates the authenticated data `aad` of a message with the
+    /// configured [`AadValidator`]. Returns `true` if no validator is
+    /// configured.
+    pub(crate) fn validate_aad(
+        &self,
+        sender: &Sender,
+        content_type: ContentType,
+        aad: &[u8],
+    ) -> bool {
+        self.aad_validator()
+            .map(|validator| validator.validate(sender, content_type, aad))
+            .unwrap_or(true)
+    }
+
+    /// Returns `true` if a [`CredentialValidator`] was configured, but is
+    /// missing, e.g., because the group was loaded and the validator wasn't
+    /// set again.
+    pub(crate) fn credential_validator_missing(&self) -> bool {
+        self.requires_credential_validator && self.credential_validator.is_none()
+    }
+
+    /// Validates the given `credential` with the configured
+    /// [`BasicIdentitySchema`] and [`CredentialValidator`]. Returns `true` if
+    /// neither is configured.
+    pub(crate) fn validate_credential(&self, credential: &Credential) -> bool {
+        self.basic_identity_schema()
+            .map(|schema| schema.validate(credential))
+            .unwrap_or(true)
+            && self
+                .credential_validator()
+                .map(|validator| validator.validate(credential))
+                .unwrap_or(true)
+    }
+
+    /// Checks the given `credential` for revocation and expiry with the
+    /// configured [`CredentialValidator`]. Returns `false` if no validator is
+    /// configured.
+    pub(crate) fn is_credential_revoked(&self, credential: &Credential) -> bool {
+        self.credential_validator()
+            .map(|validator| {
+                validator.is_revoked(credential)
+                    || validator
+                        .not_after(credential)
+                        .map(|not_after| {
+                            self.time_provider()
+                                .unix_now()
+                                .map(|now| {
+                                    now >= not_after
+                                        .saturating_add(self.clock_skew_tolerance.as_secs())
+                                })
+                                .unwrap_or(true)
+                        })
+                        .unwrap_or(false)
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns a configuration for the given `ciphersuite` that follows the
+    /// defaults of the MLS specification: handshake messages are sent as
+    /// PrivateMessages, while PublicMessages, e.g. from new members, are
+    /// accepted as well, and the ratchet tree is included in Welcome
+    /// messages.
+    pub fn rfc_default(ciphersuite: Ciphersuite) -> Self {
+        Self::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .wire_format_policy(MIXED_CIPHERTEXT_WIRE_FORMAT_POLICY)
+            .use_ratchet_tree_extension(true)
+            .build()
+    }
+
+    /// Returns a configuration for the given `ciphersuite` that favors
+    /// confidentiality and forward secrecy over robustness: all handshake
+    /// messages must be PrivateMessages, ciphertexts are padded with
+    /// [`PaddingPolicy::Padme`], no secrets of past epochs or skipped
+    /// generations are kept, signature keys are pinned and leaf nodes are
+    /// validated strictly.
+    ///
+    /// Since no secrets of skipped generations are kept, messages that are
+    /// delivered out of order can't be decrypted.
+    pub fn high_security(ciphersuite: Ciphersuite) -> Self {
+        Self::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .wire_format_policy(PURE_CIPHERTEXT_WIRE_FORMAT_POLICY)
+            .padding_policy(PaddingPolicy::Padme)
+            .max_past_epochs(0)
+            .number_of_resumption_psks(0)
+            .sender_ratchet_configuration(SenderRatchetConfiguration::new(0, 100))
+            .use_signature_key_pinning(true)
+            .leaf_node_validation_policy(LeafNodeValidationPolicy::Strict)
+            .build()
+    }
+
+    /// Returns a configuration for the given `ciphersuite` that is suited for
+    /// groups with many members: the ratchet tree is not included in Welcome
+    /// messages and has to be distributed out of band, and more secrets of
+    /// past epochs and skipped generations are kept, since messages are more
+    /// likely to arrive late or out of order.
+    pub fn large_group(ciphersuite: Ciphersuite) -> Self {
+        Self::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .wire_format_policy(MIXED_CIPHERTEXT_WIRE_FORMAT_POLICY)
+            .use_ratchet_tree_extension(false)
+            .max_past_epochs(3)
+            .sender_ratchet_configuration(SenderRatchetConfiguration::new(20, 5000))
+            .build()
+    }
+
+    /// Checks the configuration for combinations of values that contradict
+    /// each other or have no effect.
+    ///
+    /// Returns an [`InvalidConfigError`] for the first inconsistency that is
+    /// found.
+    pub fn validate(&self) -> Result<(), InvalidConfigError> {
+        if !self.external_senders.is_empty()
+            && self.wire_format_policy.incoming_for(ContentType::Proposal)
+                == IncomingWireFormatPolicy::AlwaysCiphertext
+        {
+            return Err(InvalidConfigError::ExternalSendersWithCiphertextOnlyPolicy);
+        }
+
+        if self.max_past_epoch_age.is_some() && self.max_past_epochs == 0 {
+            return Err(InvalidConfigError::MaxPastEpochAgeWithoutPastEpochs);
+        }
+
+        if let Some(required_media_types) = &self.required_media_types {
+            let accepted = self
+                .accepted_media_types
+                .as_ref()
+                .map(|accepted_media_types| accepted_media_types.contains_all(required_media_types))
+                .unwrap_or(required_media_types.media_types().is_empty());
+            if !accepted {
+                return Err(InvalidConfigError::RequiredMediaTypesNotAccepted);
+            }
+        }
+
+        if let Some(PaddingPolicy::Buckets(buckets)) = &self.padding_policy {
+            if buckets.is_empty() || buckets.contains(&0) {
+                return Err(InvalidConfigError::InvalidPaddingBuckets);
+            }
+        }
+
+        Ok(())
+    }
+
     #[cfg(any(feature = "test-utils", test))]
     pub fn test_default(ciphersuite: Ciphersuite) -> Self {
         Self::builder()
@@ -146,8 +536,25 @@ impl MlsGroupConfigBuilder {
     }
 
     /// Sets the `padding_size` property of the MlsGroupConfig.
+    /// This replaces a previously set padding policy with
+    /// [`PaddingPolicy::Multiple`].
     pub fn padding_size(mut self, padding_size: usize) -> Self {
         self.config.padding_size = padding_size;
+        self.config.padding_policy = None;
+        self
+    }
+
+    /// Sets the `padding_policy` property of the MlsGroupConfig.
+    /// See [`PaddingPolicy`] for the available policies.
+    pub fn padding_policy(mut self, padding_policy: PaddingPolicy) -> Self {
+        self.config.padding_policy = Some(padding_policy);
+        self
+    }
+
+    /// Sets the `fragmentation_config` property of the MlsGroupConfig.
+    /// See [`FragmentationConfig`] for more information.
+    pub fn fragmentation_config(mut self, fragmentation_config: FragmentationConfig) -> Self {
+        self.config.fragmentation_config = fragmentation_config;
         self
     }
 
@@ -166,6 +573,107 @@ impl MlsGroupConfigBuilder {
         self
     }
 
+    /// Sets the `max_past_epoch_age` property of the MlsGroupConfig.
+    /// The message secrets of past epochs are deleted once the epoch ended
+    /// more than `max_past_epoch_age` ago, even if fewer than
+    /// `max_past_epochs` epochs have passed since. This bounds the exposure
+    /// of past message secrets in groups where epochs rarely change.
+    ///
+    /// Expired secrets are deleted when a message is processed, when a commit
+    /// is merged, and when [`MlsGroup::delete_expired_message_secrets()`] is
+    /// called.
+    pub fn max_past_epoch_age(mut self, max_past_epoch_age: Duration) -> Self {
+        self.config.max_past_epoch_age = Some(max_past_epoch_age);
+        self
+    }
+
+    /// Sets the `message_secrets_budget` property of the MlsGroupConfig.
+    /// If the estimated size of the message secrets of the current and past
+    /// epochs exceeds `message_secrets_budget` bytes, the secrets of the
+    /// oldest past epochs are deleted first, even if fewer than
+    /// `max_past_epochs` epochs have passed since. The secrets of the current
+    /// epoch are never deleted. Their size can be bounded with the
+    /// `maximum_skipped_keys` of the [`SenderRatchetConfiguration`].
+    ///
+    /// The budget is enforced when a message is processed and when a commit
+    /// is merged. See [`MlsGroup::message_secrets_usage()`] for the current
+    /// usage.
+    pub fn message_secrets_budget(mut self, message_secrets_budget: usize) -> Self {
+        self.config.message_secrets_budget = Some(message_secrets_budget);
+        self
+    }
+
+    /// Sets the `immediate_key_deletion` property of the MlsGroupConfig.
+    /// If set, the key material of a generation is deleted as soon as a
+    /// message of that generation was decrypted, and the key material of
+    /// skipped generations is never kept, regardless of the
+    /// `out_of_order_tolerance` and `maximum_skipped_keys` of the
+    /// [`SenderRatchetConfiguration`]. Messages that arrive out of order can
+    /// therefore not be decrypted. In addition, the own encryption ratchets
+    /// of an epoch are deleted once the epoch ended, such that the messages
+    /// of past epochs that are kept according to `max_past_epochs` can still
+    /// be decrypted, but no further messages can be encrypted in them.
+    ///
+    /// This provides the strongest forward secrecy guarantees within an
+    /// epoch for deployments whose DS delivers messages in order.
+    pub fn immediate_key_deletion(mut self, immediate_key_deletion: bool) -> Self {
+        self.config.immediate_key_deletion = immediate_key_deletion;
+        self
+    }
+
+    /// Sets the `retain_past_exporter_secrets` property of the
+    /// MlsGroupConfig. If set, the exporter secrets of the past epochs that
+    /// are kept according to `max_past_epochs` are kept as well, so that
+    /// secrets can be exported from these epochs with
+    /// [`MlsGroup::export_secret_for_epoch()`]. The default is `false`.
+    ///
+    /// Keeping exporter secrets weakens the forward secrecy of all secrets
+    /// exported from past epochs. Exporter secrets are therefore never kept
+    /// if `immediate_key_deletion` is set.
+    pub fn retain_past_exporter_secrets(mut self, retain_past_exporter_secrets: bool) -> Self {
+        self.config.retain_past_exporter_secrets = retain_past_exporter_secrets;
+        self
+    }
+
+    /// Sets the `max_pending_proposals` property of the MlsGroupConfig.
+    /// This bounds the number of proposals a group keeps in its proposal
+    /// store, so that members spamming proposals can't exhaust the memory of
+    /// other members. What happens to proposals received while the store is
+    /// full is determined by the `proposal_store_full_policy`.
+    pub fn max_pending_proposals(mut self, max_pending_proposals: usize) -> Self {
+        self.config.max_pending_proposals = Some(max_pending_proposals);
+        self
+    }
+
+    /// Sets the `proposal_store_full_policy` property of the MlsGroupConfig.
+    /// The default is [`ProposalStoreFullPolicy::Reject`]. See
+    /// [`ProposalStoreFullPolicy`] for the available policies and their
+    /// consequences.
+    pub fn proposal_store_full_policy(
+        mut self,
+        proposal_store_full_policy: ProposalStoreFullPolicy,
+    ) -> Self {
+        self.config.proposal_store_full_policy = proposal_store_full_policy;
+        self
+    }
+
+    /// Sets the `update_policy` property of the MlsGroupConfig. See
+    /// [`MlsGroup::maybe_self_update()`].
+    pub fn update_policy(mut self, update_policy: UpdatePolicy) -> Self {
+        self.config.update_policy = Some(update_policy);
+        self
+    }
+
+    /// Sets the `leaf_node_validation_policy` property of the MlsGroupConfig.
+    /// See [`LeafNodeValidationPolicy`] for the available policies.
+    pub fn leaf_node_validation_policy(
+        mut self,
+        leaf_node_validation_policy: LeafNodeValidationPolicy,
+    ) -> Self {
+        self.config.leaf_node_validation_policy = leaf_node_validation_policy;
+        self
+    }
+
     /// Sets the `number_of_resumption_psks` property of the MlsGroupConfig.
     pub fn number_of_resumption_psks(mut self, number_of_resumption_psks: usize) -> Self {
         self.config.number_of_resumption_psks = number_of_resumption_psks;
@@ -178,6 +686,64 @@ impl MlsGroupConfigBuilder {
         self
     }
 
+    /// Sets the `use_signature_key_pinning` property of the MlsGroupConfig.
+    ///
+    /// If enabled, the group pins the signature key first seen for each
+    /// identity and raises a
+    /// [`ProcessingWarning::KeyChanged`](crate::framing::ProcessingWarning::KeyChanged)
+    /// for every leaf node that has the identity of a known member, but a
+    /// different signature key.
+    pub fn use_signature_key_pinning(mut self, use_signature_key_pinning: bool) -> Self {
+        self.config.use_signature_key_pinning = use_signature_key_pinning;
+        self
+    }
+
+    /// Sets the `always_return_group_info` property of the MlsGroupConfig.
+    ///
+    /// If set, every operation that creates a commit also returns the
+    /// GroupInfo of the new epoch, signed by the committer and including the
+    /// external public key, so that it can be handed to the delivery service
+    /// for external joins right away. Otherwise, the GroupInfo is only
+    /// returned if the ratchet tree extension is used.
+    pub fn always_return_group_info(mut self, always_return_group_info: bool) -> Self {
+        self.config.always_return_group_info = always_return_group_info;
+        self
+    }
+
+    /// Sets the `use_commit_audit_log` property of the MlsGroupConfig.
+    ///
+    /// If set, the group records the confirmation tag, sender, proposals and
+    /// confirmed transcript hash of every merged commit in an append-only log
+    /// that can be exported with
+    /// [`MlsGroup::export_commit_audit_log()`](crate::group::MlsGroup::export_commit_audit_log()).
+    pub fn use_commit_audit_log(mut self, use_commit_audit_log: bool) -> Self {
+        self.config.use_commit_audit_log = use_commit_audit_log;
+        self
+    }
+
+    /// Sets the `unique_identities` property of the MlsGroupConfig.
+    ///
+    /// If set, Add proposals and External Commits are rejected if the
+    /// identity of their credential is already a member of the group, unless
+    /// the commit also removes the existing leaf, e.g., when a client rejoins
+    /// with an External Commit to resync.
+    pub fn unique_identities(mut self, unique_identities: bool) -> Self {
+        self.config.unique_identities = unique_identities;
+        self
+    }
+
+    /// Sets the `basic_identity_schema` property of the MlsGroupConfig.
+    /// See [`BasicIdentitySchema`] for more information.
+    ///
+    /// If set, basic credentials whose identity doesn't match the schema are
+    /// treated like credentials rejected by the [`CredentialValidator`], i.e.,
+    /// they can't be added, groups with such members can't be joined and
+    /// messages introducing such credentials are rejected.
+    pub fn basic_identity_schema(mut self, basic_identity_schema: BasicIdentitySchema) -> Self {
+        self.config.basic_identity_schema = Some(basic_identity_schema);
+        self
+    }
+
     /// Sets the `sender_ratchet_configuration` property of the MlsGroupConfig.
     /// See [`SenderRatchetConfiguration`] for more information.
     pub fn sender_ratchet_configuration(
@@ -206,12 +772,238 @@ impl MlsGroupConfigBuilder {
         self
     }
 
+    /// Sets the `crypto_provider` property of the MlsGroupConfig.
+    /// See [`CryptoProviders`](super::providers::CryptoProviders) for more information.
+    pub fn crypto_provider(mut self, crypto_provider: impl Into<String>) -> Self {
+        self.config.crypto_provider = Some(crypto_provider.into());
+        self
+    }
+
+    /// Sets the `required_capabilities` property of the MlsGroupConfig.
+    pub fn required_capabilities(
+        mut self,
+        required_capabilities: RequiredCapabilitiesExtension,
+    ) -> Self {
+        self.config.required_capabilities = required_capabilities;
+        self
+    }
+
+    /// Sets the `custom_extensions` property of the MlsGroupConfig.
+    /// See [`CustomExtensionRegistry`] for more information.
+    pub fn custom_extensions(mut self, custom_extensions: CustomExtensionRegistry) -> Self {
+        self.config.custom_extensions = custom_extensions;
+        self
+    }
+
+    /// Sets the `accepted_media_types` property of the MlsGroupConfig.
+    ///
+    /// The media types are advertised in the own leaf node when creating a
+    /// group.
+    pub fn accepted_media_types(
+        mut self,
+        accepted_media_types: AcceptedMediaTypesExtension,
+    ) -> Self {
+        self.config.accepted_media_types = Some(accepted_media_types);
+        self
+    }
+
+    /// Sets the `required_media_types` property of the MlsGroupConfig.
+    ///
+    /// The media types are required in the group context when creating a
+    /// group. New members must accept all of them.
+    pub fn required_media_types(
+        mut self,
+        required_media_types: RequiredMediaTypesExtension,
+    ) -> Self {
+        self.config.required_media_types = Some(required_media_types);
+        self
+    }
+
+    /// Sets the `roles` property of the MlsGroupConfig.
+    ///
+    /// The roles are stored in the group context when creating a group and
+    /// the creator of the group is made an owner. See [`RolesExtension`] for
+    /// the permissions of the roles.
+    pub fn roles(mut self, roles: RolesExtension) -> Self {
+        self.config.roles = Some(roles);
+        self
+    }
+
+    /// Sets the `credential_validator` property of the MlsGroupConfig.
+    /// See [`CredentialValidator`] for more information.
+    ///
+    /// The validator is not persisted with the group and has to be set again
+    /// with [`MlsGroup::set_configuration()`] after loading a group. Until
+    /// then, the group refuses to process messages and to add members.
+    pub fn credential_validator(
+        mut self,
+        credential_validator: impl CredentialValidator + 'static,
+    ) -> Self {
+        self.config.credential_validator =
+            Some(SharedCredentialValidator(Arc::new(credential_validator)));
+        self.config.requires_credential_validator = true;
+        self
+    }
+
+    /// Sets the `aad_validator` property of the MlsGroupConfig.
+    /// See [`AadValidator`] for more information.
+    ///
+    /// The validator is not persisted with the group and has to be set again
+    /// with [`MlsGroup::set_configuration()`] after loading a group.
+    pub fn aad_validator(mut self, aad_validator: impl AadValidator + 'static) -> Self {
+        self.config.aad_validator = Some(SharedAadValidator(Arc::new(aad_validator)));
+        self
+    }
+
+    /// Sets the `time_provider` property of the MlsGroupConfig. The time
+    /// provider replaces the system clock when validating the lifetimes of
+    /// key packages and leaf nodes and the expiry of credentials. See
+    /// [`TimeProvider`] for more information.
+    ///
+    /// The time provider is not persisted with the group and has to be set
+    /// again with [`MlsGroup::set_configuration()`] after loading a group.
+    pub fn time_provider(mut self, time_provider: impl TimeProvider + 'static) -> Self {
+        self.config.time_provider = Some(SharedTimeProvider(Arc::new(time_provider)));
+        self
+    }
+
+    /// Sets the `clock_skew_tolerance` property of the MlsGroupConfig.
+    /// Lifetimes of key packages are extended by `clock_skew_tolerance` into
+    /// the past and the future, and credentials expire `clock_skew_tolerance`
+    /// later, to accept members whose clocks are slightly off. The tolerance
+    /// is applied with a precision of seconds.
+    pub fn clock_skew_tolerance(mut self, clock_skew_tolerance: Duration) -> Self {
+        self.config.clock_skew_tolerance = clock_skew_tolerance;
+        self
+    }
+
     /// Finalizes the builder and retursn an `[MlsGroupConfig`].
     pub fn build(self) -> MlsGroupConfig {
         self.config
     }
 }
 
+/// A [`CredentialValidator`] that can be shared between configurations.
+#[derive(Clone)]
+pub(crate) struct SharedCredentialValidator(Arc<dyn CredentialValidator>);
+
+impl fmt::Debug for SharedCredentialValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedCredentialValidator").finish()
+    }
+}
+
+impl PartialEq for SharedCredentialValidator {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::as_ptr(&self.0) as *const () == Arc::as_ptr(&other.0) as *const ()
+    }
+}
+
+impl Eq for SharedCredentialValidator {}
+
+/// An [`AadValidator`] that can be shared between configurations.
+#[derive(Clone)]
+pub(crate) struct SharedAadValidator(Arc<dyn AadValidator>);
+
+impl fmt::Debug for SharedAadValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedAadValidator").finish()
+    }
+}
+
+impl PartialEq for SharedAadValidator {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::as_ptr(&self.0) as *const () == Arc::as_ptr(&other.0) as *const ()
+    }
+}
+
+impl Eq for SharedAadValidator {}
+
+/// A [`TimeProvider`] that can be shared between configurations.
+#[derive(Clone)]
+pub(crate) struct SharedTimeProvider(pub(crate) Arc<dyn TimeProvider>);
+
+impl fmt::Debug for SharedTimeProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedTimeProvider").finish()
+    }
+}
+
+impl PartialEq for SharedTimeProvider {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::as_ptr(&self.0) as *const () == Arc::as_ptr(&other.0) as *const ()
+    }
+}
+
+impl Eq for SharedTimeProvider {}
+
+/// Defines how thoroughly the leaf nodes of other members are validated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LeafNodeValidationPolicy {
+    /// Leaf nodes are only checked for the properties that are necessary for
+    /// the group to function, e.g. their signature, their ciphersuite and the
+    /// required capabilities of the group.
+    #[default]
+    Lenient,
+    /// Leaf nodes are fully validated as specified in Section 7.3 of the MLS
+    /// specification: the capabilities must list the extensions and the
+    /// credential type of the leaf node, the signature and encryption keys
+    /// must be unique in the group, the credential types of all members must
+    /// be supported by all members, and the lifetime of leaf nodes in key
+    /// packages must be current and not too long.
+    ///
+    /// Leaf nodes are validated in Add and Update proposals, in the path of
+    /// commits, and in the ratchet tree when joining a group from a Welcome
+    /// message.
+    Strict,
+}
+
+impl LeafNodeValidationPolicy {
+    /// Returns `true` if the policy is [`LeafNodeValidationPolicy::Strict`].
+    pub fn is_strict(&self) -> bool {
+        matches!(self, LeafNodeValidationPolicy::Strict)
+    }
+}
+
+/// Defines when [`MlsGroup::maybe_self_update()`] updates the own leaf.
+///
+/// Regularly updating the own leaf heals the group from a compromise of the
+/// own key material (post-compromise security). An update is due if any of
+/// the configured thresholds is exceeded:
+///
+///  - max_epoch_age:
+/// The maximum time for which the group may remain in the same epoch. See
+/// [`MlsGroup::epoch_age()`].
+///  - max_messages_sent:
+/// The maximum number of application messages the own member may send in the
+/// same epoch.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdatePolicy {
+    max_epoch_age: Option<Duration>,
+    max_messages_sent: Option<u32>,
+}
+
+impl UpdatePolicy {
+    /// Create a new policy. `None` disables the respective threshold.
+    pub fn new(max_epoch_age: Option<Duration>, max_messages_sent: Option<u32>) -> Self {
+        Self {
+            max_epoch_age,
+            max_messages_sent,
+        }
+    }
+
+    /// Get the maximum time for which the group may remain in the same epoch.
+    pub fn max_epoch_age(&self) -> Option<Duration> {
+        self.max_epoch_age
+    }
+
+    /// Get the maximum number of application messages the own member may
+    /// send in the same epoch.
+    pub fn max_messages_sent(&self) -> Option<u32> {
+        self.max_messages_sent
+    }
+}
+
 /// Defines what wire format is acceptable for incoming handshake messages.
 /// Note that application messages must always be encrypted.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -249,10 +1041,36 @@ pub enum OutgoingWireFormatPolicy {
 
 /// Defines what wire format is desired for outgoing handshake messages.
 /// Note that application messages must always be encrypted.
+///
+/// The policy applies to proposals and commits alike, unless it is overridden
+/// for one of them with [`WireFormatPolicy::with_proposal_policy()`] or
+/// [`WireFormatPolicy::with_commit_policy()`].
+///
+/// ```
+/// use openmls::prelude::*;
+///
+/// // Proposals are sent and accepted as PublicMessages, e.g. such that the
+/// // delivery service can inspect them, while commits are encrypted.
+/// let wire_format_policy = PURE_CIPHERTEXT_WIRE_FORMAT_POLICY.with_proposal_policy(
+///     OutgoingWireFormatPolicy::AlwaysPlaintext,
+///     IncomingWireFormatPolicy::AlwaysPlaintext,
+/// );
+/// ```
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WireFormatPolicy {
     outgoing: OutgoingWireFormatPolicy,
     incoming: IncomingWireFormatPolicy,
+    #[serde(default)]
+    proposal: Option<ContentTypeWireFormatPolicy>,
+    #[serde(default)]
+    commit: Option<ContentTypeWireFormatPolicy>,
+}
+
+/// The wire format policy for a single content type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct ContentTypeWireFormatPolicy {
+    outgoing: OutgoingWireFormatPolicy,
+    incoming: IncomingWireFormatPolicy,
 }
 
 impl WireFormatPolicy {
@@ -263,18 +1081,71 @@ impl WireFormatPolicy {
         outgoing: OutgoingWireFormatPolicy,
         incoming: IncomingWireFormatPolicy,
     ) -> Self {
-        Self { outgoing, incoming }
+        Self {
+            outgoing,
+            incoming,
+            proposal: None,
+            commit: None,
+        }
+    }
+
+    /// Returns a copy of the policy in which the wire formats of proposals
+    /// are determined by the given `outgoing` and `incoming` policies.
+    pub fn with_proposal_policy(
+        mut self,
+        outgoing: OutgoingWireFormatPolicy,
+        incoming: IncomingWireFormatPolicy,
+    ) -> Self {
+        self.proposal = Some(ContentTypeWireFormatPolicy { outgoing, incoming });
+        self
+    }
+
+    /// Returns a copy of the policy in which the wire formats of commits are
+    /// determined by the given `outgoing` and `incoming` policies.
+    pub fn with_commit_policy(
+        mut self,
+        outgoing: OutgoingWireFormatPolicy,
+        incoming: IncomingWireFormatPolicy,
+    ) -> Self {
+        self.commit = Some(ContentTypeWireFormatPolicy { outgoing, incoming });
+        self
     }
 
     /// Returns a reference to the wire format policy's outgoing wire format policy.
+    ///
+    /// This is the policy for handshake messages whose content type has no
+    /// policy of its own. See [`WireFormatPolicy::outgoing_for()`].
     pub fn outgoing(&self) -> OutgoingWireFormatPolicy {
         self.outgoing
     }
 
     /// Returns a reference to the wire format policy's incoming wire format policy.
+    ///
+    /// This is the policy for handshake messages whose content type has no
+    /// policy of its own. See [`WireFormatPolicy::incoming_for()`].
     pub fn incoming(&self) -> IncomingWireFormatPolicy {
         self.incoming
     }
+
+    /// Returns the outgoing wire format policy for messages of the given
+    /// `content_type`. Application messages are always encrypted.
+    pub fn outgoing_for(&self, content_type: ContentType) -> OutgoingWireFormatPolicy {
+        match content_type {
+            ContentType::Application => OutgoingWireFormatPolicy::AlwaysCiphertext,
+            ContentType::Proposal => self.proposal.map_or(self.outgoing, |p| p.outgoing),
+            ContentType::Commit => self.commit.map_or(self.outgoing, |p| p.outgoing),
+        }
+    }
+
+    /// Returns the incoming wire format policy for messages of the given
+    /// `content_type`. Application messages must always be encrypted.
+    pub fn incoming_for(&self, content_type: ContentType) -> IncomingWireFormatPolicy {
+        match content_type {
+            ContentType::Application => IncomingWireFormatPolicy::AlwaysCiphertext,
+            ContentType::Proposal => self.proposal.map_or(self.incoming, |p| p.incoming),
+            ContentType::Commit => self.commit.map_or(self.incoming, |p| p.incoming),
+        }
+    }
 }
 
 impl Default for WireFormatPolicy {
@@ -308,12 +1179,16 @@ pub const WIRE_FORMAT_POLICIES: [WireFormatPolicy; 4] = [
 pub const PURE_PLAINTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolicy {
     outgoing: OutgoingWireFormatPolicy::AlwaysPlaintext,
     incoming: IncomingWireFormatPolicy::AlwaysPlaintext,
+    proposal: None,
+    commit: None,
 };
 
 /// Incoming and outgoing wire formats are always ciphertext.
 pub const PURE_CIPHERTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolicy {
     outgoing: OutgoingWireFormatPolicy::AlwaysCiphertext,
     incoming: IncomingWireFormatPolicy::AlwaysCiphertext,
+    proposal: None,
+    commit: None,
 };
 
 /// Incoming wire formats can be mixed while outgoing wire formats are always
@@ -321,6 +1196,8 @@ pub const PURE_CIPHERTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolic
 pub const MIXED_PLAINTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolicy {
     outgoing: OutgoingWireFormatPolicy::AlwaysPlaintext,
     incoming: IncomingWireFormatPolicy::Mixed,
+    proposal: None,
+    commit: None,
 };
 
 /// Incoming wire formats can be mixed while outgoing wire formats are always
@@ -328,4 +1205,6 @@ pub const MIXED_PLAINTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolic
 pub const MIXED_CIPHERTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolicy {
     outgoing: OutgoingWireFormatPolicy::AlwaysCiphertext,
     incoming: IncomingWireFormatPolicy::Mixed,
+    proposal: None,
+    commit: None,
 };
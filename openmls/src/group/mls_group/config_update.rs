@@ -0,0 +1,100 @@
+//! # Configuration updates
+//!
+//! The [`SenderRatchetConfiguration`] and the `max_past_epochs` of an
+//! [`MlsGroup`] determine for how long secrets that allow decrypting messages
+//! are retained. Both can be changed on a live group, e.g. to tighten the
+//! forward secrecy of existing groups.
+//!
+//! Changing the parameters in the middle of an epoch could make messages
+//! undecryptable that other members already sent under the assumption of the
+//! previous parameters. Changes are therefore staged as a
+//! [`PendingConfigUpdate`] and only take effect when the next commit is
+//! merged.
+
+use serde::{Deserialize, Serialize};
+
+use crate::tree::sender_ratchet::SenderRatchetConfiguration;
+
+use super::*;
+
+/// Changes to the [`MlsGroupConfig`] of an [`MlsGroup`] that take effect when
+/// the next commit is merged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingConfigUpdate {
+    sender_ratchet_configuration: Option<SenderRatchetConfiguration>,
+    max_past_epochs: Option<usize>,
+}
+
+impl PendingConfigUpdate {
+    /// Returns the new [`SenderRatchetConfiguration`], if it is changed.
+    pub fn sender_ratchet_configuration(&self) -> Option<&SenderRatchetConfiguration> {
+        self.sender_ratchet_configuration.as_ref()
+    }
+
+    /// Returns the new number of past epochs to keep, if it is changed.
+    pub fn max_past_epochs(&self) -> Option<usize> {
+        self.max_past_epochs
+    }
+}
+
+impl MlsGroup {
+    // === Configuration updates ===
+
+    /// Sets the [`SenderRatchetConfiguration`] of the group. The new
+    /// configuration takes effect when the next commit is merged.
+    pub fn update_sender_ratchet_configuration(
+        &mut self,
+        sender_ratchet_configuration: SenderRatchetConfiguration,
+    ) {
+        self.pending_config_update
+            .get_or_insert_with(PendingConfigUpdate::default)
+            .sender_ratchet_configuration = Some(sender_ratchet_configuration);
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change();
+    }
+
+    /// Sets the maximum number of past epochs for which application messages
+    /// can be decrypted. The new value takes effect when the next commit is
+    /// merged. If it is lower than the previous one, the message secrets of
+    /// the oldest epochs are deleted at that point.
+    ///
+    /// See [`MlsGroupConfigBuilder::max_past_epochs()`] for more information.
+    pub fn update_max_past_epochs(&mut self, max_past_epochs: usize) {
+        self.pending_config_update
+            .get_or_insert_with(PendingConfigUpdate::default)
+            .max_past_epochs = Some(max_past_epochs);
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change();
+    }
+
+    /// Returns the configuration changes that take effect when the next
+    /// commit is merged, if there are any.
+    pub fn pending_config_update(&self) -> Option<&PendingConfigUpdate> {
+        self.pending_config_update.as_ref()
+    }
+
+    /// Discards the configuration changes that were not applied yet.
+    pub fn clear_pending_config_update(&mut self) {
+        if self.pending_config_update.take().is_some() {
+            // Since the state of the group might be changed, arm the state flag
+            self.flag_state_change();
+        }
+    }
+
+    /// Applies the pending configuration changes. This is called when a
+    /// commit was merged.
+    pub(super) fn apply_pending_config_update(&mut self) {
+        let Some(update) = self.pending_config_update.take() else {
+            return;
+        };
+        if let Some(sender_ratchet_configuration) = update.sender_ratchet_configuration {
+            self.mls_group_config.sender_ratchet_configuration = sender_ratchet_configuration;
+        }
+        if let Some(max_past_epochs) = update.max_past_epochs {
+            self.mls_group_config.max_past_epochs = max_past_epochs;
+            self.group.set_max_past_epochs(max_past_epochs);
+        }
+    }
+}
@@ -2,16 +2,17 @@ use openmls_traits::signatures::Signer;
 
 use super::*;
 use crate::{
-    ciphersuite::HpkePrivateKey,
+    ciphersuite::{hash_ref::KeyPackageRef, HpkePrivateKey},
     credentials::CredentialWithKey,
     group::{
         core_group::create_commit_params::CreateCommitParams,
         errors::{CoreGroupBuildError, ExternalCommitError, WelcomeError},
         public_group::errors::PublicGroupBuildError,
     },
+    key_packages::errors::KeyPackageDeleteError,
     messages::group_info::{GroupInfo, VerifiableGroupInfo},
     schedule::psk::store::ResumptionPskStore,
-    treesync::RatchetTreeIn,
+    treesync::{RatchetTreeIn, RatchetTreeProvider},
 };
 
 impl MlsGroup {
@@ -49,6 +50,12 @@ impl MlsGroup {
             add_ratchet_tree_extension: mls_group_config.use_ratchet_tree_extension,
         };
 
+        // The creator of the group is its first owner.
+        let roles = mls_group_config.roles.clone().map(|mut roles| {
+            roles.set_role(credential_with_key.credential.identity(), Role::Owner);
+            roles
+        });
+
         let mut group = CoreGroup::builder(
             group_id,
             mls_group_config.crypto_config,
@@ -56,7 +63,13 @@ impl MlsGroup {
         )
         .with_config(group_config)
         .with_required_capabilities(mls_group_config.required_capabilities.clone())
+        .with_custom_extension_types(mls_group_config.custom_extensions.extension_types())
         .with_external_senders(mls_group_config.external_senders.clone())
+        .with_media_types(
+            mls_group_config.accepted_media_types.clone(),
+            mls_group_config.required_media_types.clone(),
+        )
+        .with_roles(roles)
         .with_max_past_epoch_secrets(mls_group_config.max_past_epochs)
         .with_lifetime(*mls_group_config.lifetime())
         .build(backend, signer)
@@ -76,7 +89,11 @@ impl MlsGroup {
                 PublicGroupBuildError::UnsupportedExtensionType => {
                     NewGroupError::UnsupportedExtensionType
                 }
+                PublicGroupBuildError::UnsupportedMediaTypes => {
+                    NewGroupError::UnsupportedMediaTypes
+                }
                 PublicGroupBuildError::InvalidExtensions(e) => NewGroupError::InvalidExtensions(e),
+                PublicGroupBuildError::SignatureError(e) => e.into(),
             },
         })?;
 
@@ -86,15 +103,25 @@ impl MlsGroup {
             .resumption_psk_store
             .add(group.context().epoch(), resumption_psk.clone());
 
-        let mls_group = MlsGroup {
+        let mut mls_group = MlsGroup {
             mls_group_config: mls_group_config.clone(),
             group,
             proposal_store: ProposalStore::new(),
             own_leaf_nodes: vec![],
             aad: vec![],
+            signature_key_pins: SignatureKeyPins::default(),
             group_state: MlsGroupState::Operational,
             state_changed: InnerState::Changed,
+            pending_config_update: None,
+            updated_sender_ratchets: BTreeSet::new(),
+            epoch_history: EpochHistory::default(),
+            commit_audit_log: Vec::new(),
         };
+        mls_group.pin_members();
+        mls_group.epoch_history.record(
+            mls_group.epoch(),
+            mls_group.mls_group_config.time_provider(),
+        );
 
         Ok(mls_group)
     }
@@ -102,66 +129,207 @@ impl MlsGroup {
     /// Creates a new group from a [`Welcome`] message. Returns an error
     /// ([`WelcomeError::NoMatchingKeyPackage`]) if no [`KeyPackage`]
     /// can be found.
+    ///
+    /// Consumed key packages are recorded in the key store. Returns
+    /// [`WelcomeError::KeyPackageAlreadyConsumed`] if the [`Welcome`] targets
+    /// a key package or an init key that was already used to join a group.
     // TODO: #1326 This should take an MlsMessage rather than a Welcome message.
     pub fn new_from_welcome<KeyStore: OpenMlsKeyStore>(
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         mls_group_config: &MlsGroupConfig,
         welcome: Welcome,
         ratchet_tree: Option<RatchetTreeIn>,
+    ) -> Result<Self, WelcomeError<KeyStore::Error>> {
+        Self::new_from_welcome_internal(backend, mls_group_config, welcome, ratchet_tree, None)
+    }
+
+    /// Creates a new group from a [`Welcome`] message like
+    /// [`MlsGroup::new_from_welcome()`]. If the [`Welcome`] doesn't contain
+    /// a ratchet tree extension, the ratchet tree is requested from the
+    /// `ratchet_tree_provider` with the group ID and epoch of the group. The
+    /// tree is verified against the tree hash of the group before it is used.
+    ///
+    /// Returns [`WelcomeError::MissingRatchetTree`] if the provider doesn't
+    /// return a ratchet tree.
+    pub fn new_from_welcome_with_ratchet_tree_provider<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        mls_group_config: &MlsGroupConfig,
+        welcome: Welcome,
+        ratchet_tree_provider: &impl RatchetTreeProvider,
+    ) -> Result<Self, WelcomeError<KeyStore::Error>> {
+        Self::new_from_welcome_internal(
+            backend,
+            mls_group_config,
+            welcome,
+            None,
+            Some(ratchet_tree_provider),
+        )
+    }
+
+    fn new_from_welcome_internal<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        mls_group_config: &MlsGroupConfig,
+        welcome: Welcome,
+        ratchet_tree: Option<RatchetTreeIn>,
+        ratchet_tree_provider: Option<&dyn RatchetTreeProvider>,
     ) -> Result<Self, WelcomeError<KeyStore::Error>> {
         let resumption_psk_store =
             ResumptionPskStore::new(mls_group_config.number_of_resumption_psks);
-        let (key_package, _) = welcome
-            .secrets()
-            .iter()
-            .find_map(|egs| {
-                let hash_ref = egs.new_member().as_slice().to_vec();
-                backend
-                    .key_store()
-                    .read(&hash_ref)
-                    .map(|kp: KeyPackage| (kp, hash_ref))
-            })
-            .ok_or(WelcomeError::NoMatchingKeyPackage)?;
-
-        // TODO #751
-        let private_key = backend
-            .key_store()
-            .read::<HpkePrivateKey>(key_package.hpke_init_key().as_slice())
-            .ok_or(WelcomeError::NoMatchingKeyPackage)?;
-        let key_package_bundle = KeyPackageBundle {
-            key_package,
-            private_key,
-        };
+        let (key_package_bundle, hash_ref) =
+            Self::key_package_bundle_from_welcome(backend, &welcome)?;
 
         // Delete the [`KeyPackage`] and the corresponding private key from the
         // key store
         key_package_bundle
             .key_package
             .delete(backend)
+            .map_err(|e| match e {
+                KeyPackageDeleteError::LibraryError(e) => WelcomeError::LibraryError(e),
+                KeyPackageDeleteError::KeyStoreError(e) => WelcomeError::KeyStoreError(e),
+            })?;
+        key_package_bundle
+            .key_package
+            .mark_consumed(backend, &hash_ref)
             .map_err(WelcomeError::KeyStoreError)?;
 
-        let mut group = CoreGroup::new_from_welcome(
+        let mut group = CoreGroup::new_from_welcome_with_ratchet_tree_provider(
             welcome,
             ratchet_tree,
+            ratchet_tree_provider,
             key_package_bundle,
             backend,
             resumption_psk_store,
         )?;
         group.set_max_past_epochs(mls_group_config.max_past_epochs);
 
-        let mls_group = MlsGroup {
+        // Let the application validate the credentials of all members
+        if !group
+            .public_group()
+            .members()
+            .all(|member| mls_group_config.validate_credential(&member.credential))
+        {
+            return Err(WelcomeError::InvalidCredential);
+        }
+
+        // Check that all registered custom extensions in the group context
+        // and in the leaf nodes of the members can be decoded
+        let registry = mls_group_config.custom_extensions();
+        registry.validate(group.group_context_extensions())?;
+        let public_group = group.public_group();
+        for member in public_group.members() {
+            if let Some(leaf) = public_group.leaf(member.index) {
+                registry.validate(leaf.extensions())?;
+            }
+        }
+
+        // Fully validate the leaf nodes in the ratchet tree if configured
+        if mls_group_config.leaf_node_validation_policy().is_strict() {
+            let public_group = group.public_group();
+            for member in public_group.members() {
+                if let Some(leaf) = public_group.leaf(member.index) {
+                    leaf.validate(
+                        &public_group
+                            .leaf_node_validation_context(&[member.index])
+                            .with_time(
+                                mls_group_config.time_provider(),
+                                mls_group_config.clock_skew_tolerance().as_secs(),
+                            ),
+                    )?;
+                }
+            }
+        }
+
+        let mut mls_group = MlsGroup {
             mls_group_config: mls_group_config.clone(),
             group,
             proposal_store: ProposalStore::new(),
             own_leaf_nodes: vec![],
             aad: vec![],
+            signature_key_pins: SignatureKeyPins::default(),
             group_state: MlsGroupState::Operational,
             state_changed: InnerState::Changed,
+            pending_config_update: None,
+            updated_sender_ratchets: BTreeSet::new(),
+            epoch_history: EpochHistory::default(),
+            commit_audit_log: Vec::new(),
         };
+        mls_group.pin_members();
+        mls_group.epoch_history.record(
+            mls_group.epoch(),
+            mls_group.mls_group_config.time_provider(),
+        );
 
         Ok(mls_group)
     }
 
+    /// Returns the [`GroupId`] of the group the [`Welcome`] message is for.
+    ///
+    /// Unlike [`MlsGroup::new_from_welcome()`], this function doesn't consume
+    /// the key package the [`Welcome`] message was created for.
+    pub(crate) fn welcome_group_id<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        mls_group_config: &MlsGroupConfig,
+        welcome: &Welcome,
+    ) -> Result<GroupId, WelcomeError<KeyStore::Error>> {
+        let (key_package_bundle, _hash_ref) =
+            Self::key_package_bundle_from_welcome(backend, welcome)?;
+        let (_group_secrets, _key_schedule, verifiable_group_info) = CoreGroup::decrypt_welcome(
+            welcome,
+            &key_package_bundle,
+            backend,
+            &ResumptionPskStore::new(mls_group_config.number_of_resumption_psks),
+        )?;
+        Ok(verifiable_group_info.group_id().clone())
+    }
+
+    // Reads the key package the welcome message was created for, together
+    // with its private key, from the key store.
+    fn key_package_bundle_from_welcome<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        welcome: &Welcome,
+    ) -> Result<(KeyPackageBundle, KeyPackageRef), WelcomeError<KeyStore::Error>> {
+        let (key_package, hash_ref) = welcome
+            .secrets()
+            .iter()
+            .find_map(|egs| {
+                let hash_ref = egs.new_member();
+                backend
+                    .key_store()
+                    .read(hash_ref.as_slice())
+                    .map(|kp: KeyPackage| (kp, hash_ref))
+            })
+            .ok_or_else(|| {
+                // Distinguish a reused key package from an unknown one
+                if welcome
+                    .secrets()
+                    .iter()
+                    .any(|egs| KeyPackage::is_consumed(backend, &egs.new_member()))
+                {
+                    WelcomeError::KeyPackageAlreadyConsumed
+                } else {
+                    WelcomeError::NoMatchingKeyPackage
+                }
+            })?;
+
+        // Key packages must only be used once, even if another key package
+        // with the same init key was created.
+        if key_package.init_key_consumed(backend) {
+            return Err(WelcomeError::KeyPackageAlreadyConsumed);
+        }
+
+        // TODO #751
+        let private_key = backend
+            .key_store()
+            .read::<HpkePrivateKey>(key_package.hpke_init_key().as_slice())
+            .ok_or(WelcomeError::NoMatchingKeyPackage)?;
+        let key_package_bundle = KeyPackageBundle {
+            key_package,
+            private_key,
+        };
+
+        Ok((key_package_bundle, hash_ref))
+    }
+
     /// Join an existing group through an External Commit.
     /// The resulting [`MlsGroup`] instance starts off with a pending
     /// commit (the external commit, which adds this client to the group).
@@ -193,6 +361,7 @@ impl MlsGroup {
             .framing_parameters(framing_parameters)
             .proposal_store(&proposal_store)
             .credential_with_key(credential_with_key)
+            .always_create_group_info(mls_group_config.always_return_group_info())
             .build();
         let (mut group, create_commit_result) = CoreGroup::join_by_external_commit(
             backend,
@@ -209,10 +378,15 @@ impl MlsGroup {
             proposal_store: ProposalStore::new(),
             own_leaf_nodes: vec![],
             aad: vec![],
+            signature_key_pins: SignatureKeyPins::default(),
             group_state: MlsGroupState::PendingCommit(Box::new(PendingCommitState::External(
                 create_commit_result.staged_commit,
             ))),
             state_changed: InnerState::Changed,
+            pending_config_update: None,
+            updated_sender_ratchets: BTreeSet::new(),
+            epoch_history: EpochHistory::default(),
+            commit_audit_log: Vec::new(),
         };
 
         let public_message: PublicMessage = create_commit_result.commit.into();
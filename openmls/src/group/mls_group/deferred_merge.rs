@@ -0,0 +1,64 @@
+//! # Deferred merge
+//!
+//! A [`StagedCommit`] contains all changes a commit makes to the group,
+//! including the key material of the new epoch. Applications that need an
+//! approval before merging a commit, e.g., from the user or a policy
+//! service, can persist it in the key store with
+//! [`MlsGroup::store_staged_commit()`] and merge it later, possibly after a
+//! restart, with [`MlsGroup::read_staged_commit()`] and
+//! [`MlsGroup::merge_staged_commit()`].
+//!
+//! Only one staged commit is stored per group. It is deleted from the key
+//! store when a commit is merged into the group, since it can only be merged
+//! in the epoch in which it was staged.
+
+use openmls_traits::key_store::OpenMlsKeyStore;
+
+use super::*;
+
+impl MlsGroup {
+    // === Deferred merge ===
+
+    /// Persists the `staged_commit` in the key store, indexed by the group's
+    /// [`GroupId`], replacing a previously stored staged commit.
+    ///
+    /// Returns an error if access to the key store fails.
+    pub fn store_staged_commit<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        staged_commit: &StagedCommit,
+    ) -> Result<(), KeyStore::Error> {
+        backend
+            .key_store()
+            .store(&Self::staged_commit_id(self.group_id()), staged_commit)
+    }
+
+    /// Loads the staged commit that was stored for this group with
+    /// [`MlsGroup::store_staged_commit()`].
+    ///
+    /// Returns `None` if no staged commit was stored or if it was deleted
+    /// because a commit was merged since.
+    pub fn read_staged_commit(&self, backend: &impl OpenMlsCryptoProvider) -> Option<StagedCommit> {
+        backend
+            .key_store()
+            .read(&Self::staged_commit_id(self.group_id()))
+    }
+
+    /// Deletes the staged commit that was stored for this group, e.g., if
+    /// the commit was rejected.
+    ///
+    /// Returns an error if access to the key store fails.
+    pub fn delete_staged_commit<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), KeyStore::Error> {
+        backend
+            .key_store()
+            .delete::<StagedCommit>(&Self::staged_commit_id(self.group_id()))
+    }
+
+    /// Composite key for the staged commit in the key store.
+    fn staged_commit_id(group_id: &GroupId) -> Vec<u8> {
+        [b"StagedCommit".as_slice(), group_id.as_slice()].concat()
+    }
+}
@@ -0,0 +1,163 @@
+//! # Desync detection
+//!
+//! A member whose group state diverged from the rest of the group, e.g.,
+//! because it missed a commit or lost messages, can't process messages from
+//! the other members anymore. Instead of returning the error of the failed
+//! check, [`MlsGroup::process_message()`] returns
+//! [`ProcessMessageError::Desynced`] with a [`DesyncReport`] that describes
+//! how far the group and the message are apart.
+//!
+//! The epoch and the generation of a message are read before the message
+//! can be authenticated, since the group doesn't have the secrets of the
+//! epoch of the message. Anyone can therefore provoke a [`DesyncReport`] with
+//! arbitrary epochs, and a report is only a hint. Applications must not
+//! rejoin the group automatically based on a report alone, but should first
+//! confirm that the group fell behind, e.g., by fetching the latest
+//! [`VerifiableGroupInfo`] from the delivery service and checking it with
+//! [`DesyncReport::can_resync_with()`]. The report is therefore not stored
+//! in the group.
+//!
+//! This replaces the [`ValidationError::WrongEpoch`] error, as well as the
+//! errors of messages from deleted epochs or with too distant generations,
+//! that [`MlsGroup::process_message()`] returned before.
+
+use serde::{Deserialize, Serialize};
+
+use super::{errors::ProcessMessageError, *};
+use crate::{
+    framing::errors::MessageDecryptionError, group::errors::ValidationError,
+    messages::group_info::VerifiableGroupInfo, tree::secret_tree::SecretTreeError,
+};
+
+/// The way in which a group and a message it couldn't process are out of
+/// sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DesyncKind {
+    /// The message is from a later epoch, i.e., the group missed at least one
+    /// commit.
+    EpochBehind,
+    /// The message is from an earlier epoch, i.e., the sender missed at least
+    /// one commit or the secrets of the epoch were already deleted.
+    EpochAhead,
+    /// The message is from the current epoch, but the sender's ratchet is too
+    /// far ahead of the group's, i.e., the group missed too many messages
+    /// from the sender.
+    SecretTreeExhausted,
+}
+
+/// Describes why a message couldn't be processed because the group is out
+/// of sync with the sender of the message.
+///
+/// The epoch of the message is not authenticated. See the
+/// [module documentation](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DesyncReport {
+    group_id: GroupId,
+    kind: DesyncKind,
+    group_epoch: GroupEpoch,
+    message_epoch: GroupEpoch,
+}
+
+impl DesyncReport {
+    /// Returns the way in which the group and the message are out of sync.
+    pub fn kind(&self) -> DesyncKind {
+        self.kind
+    }
+
+    /// Returns the epoch of the group when the message was processed.
+    pub fn group_epoch(&self) -> GroupEpoch {
+        self.group_epoch
+    }
+
+    /// Returns the (unauthenticated) epoch of the message.
+    pub fn message_epoch(&self) -> GroupEpoch {
+        self.message_epoch
+    }
+
+    /// Returns the number of epochs the group is behind the message.
+    pub fn epochs_behind(&self) -> u64 {
+        self.message_epoch
+            .as_u64()
+            .saturating_sub(self.group_epoch.as_u64())
+    }
+
+    /// Returns the number of epochs the group is ahead of the message.
+    pub fn epochs_ahead(&self) -> u64 {
+        self.group_epoch
+            .as_u64()
+            .saturating_sub(self.message_epoch.as_u64())
+    }
+
+    /// Returns `true` if the group can't recover without rejoining, i.e., if
+    /// it is behind the message or missed too many messages of the sender.
+    pub fn requires_resync(&self) -> bool {
+        matches!(
+            self.kind,
+            DesyncKind::EpochBehind | DesyncKind::SecretTreeExhausted
+        )
+    }
+
+    /// Returns `true` if the group can resync by joining through an External
+    /// Commit based on `group_info`, i.e., if a resync is required and the
+    /// (unverified) group info belongs to the group, is not older than the
+    /// message and supports External Commits.
+    pub fn can_resync_with(&self, group_info: &VerifiableGroupInfo) -> bool {
+        self.requires_resync()
+            && group_info.group_id() == &self.group_id
+            && group_info.epoch() >= self.message_epoch
+            && group_info.supports_external_commit()
+    }
+
+    /// Returns a report if `error` was caused by a message from
+    /// `message_epoch` that is out of sync with the group, which is in
+    /// `group_epoch`.
+    pub(crate) fn from_error(
+        group_id: &GroupId,
+        group_epoch: GroupEpoch,
+        message_epoch: GroupEpoch,
+        error: &ProcessMessageError,
+    ) -> Option<Self> {
+        let kind = match error {
+            ProcessMessageError::ValidationError(ValidationError::WrongEpoch)
+                if message_epoch > group_epoch =>
+            {
+                DesyncKind::EpochBehind
+            }
+            ProcessMessageError::ValidationError(
+                ValidationError::WrongEpoch
+                | ValidationError::NoPastEpochData
+                | ValidationError::UnableToDecrypt(MessageDecryptionError::SecretTreeError(
+                    SecretTreeError::TooDistantInThePast,
+                )),
+            ) => DesyncKind::EpochAhead,
+            ProcessMessageError::ValidationError(ValidationError::UnableToDecrypt(
+                MessageDecryptionError::SecretTreeError(SecretTreeError::TooDistantInTheFuture),
+            )) => DesyncKind::SecretTreeExhausted,
+            _ => return None,
+        };
+        Some(Self {
+            group_id: group_id.clone(),
+            kind,
+            group_epoch,
+            message_epoch,
+        })
+    }
+}
+
+impl MlsGroup {
+    // === Desync detection ===
+
+    /// Replaces `error` with [`ProcessMessageError::Desynced`] if it was
+    /// caused by a message from `message_epoch` that is out of sync with the
+    /// group.
+    pub(crate) fn check_desync(
+        &self,
+        message_epoch: GroupEpoch,
+        error: ProcessMessageError,
+    ) -> ProcessMessageError {
+        match DesyncReport::from_error(self.group_id(), self.epoch(), message_epoch, &error) {
+            Some(report) => ProcessMessageError::Desynced(report),
+            None => error,
+        }
+    }
+}
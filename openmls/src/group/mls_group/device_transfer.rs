@@ -0,0 +1,384 @@
+//! # Group state transfer
+//!
+//! A member that adds a new device can hand over its participation in a
+//! group to the new device instead of removing and re-adding itself. The old
+//! device exports a [`GroupStateTransfer`] with
+//! [`MlsGroup::transfer_to_device()`]. The transfer contains the full group
+//! state, including the message secrets and resumption PSKs, as well as the
+//! private keys of the own leaf. It is encrypted to the init key of a fresh
+//! [`KeyPackage`] of the new device, which imports it with
+//! [`MlsGroup::new_from_state_transfer()`].
+//!
+//! The new device takes over the own leaf, including its signature key. The
+//! key package of the new device must therefore contain the same credential
+//! and signature key as the own leaf. Transferring the private signature key
+//! is up to the application.
+//!
+//! Since anyone can encrypt to the init key of a key package, the transfer is
+//! signed with the signature key of the own leaf. The new device verifies the
+//! signature with the signature key of its key package before decrypting the
+//! transfer, such that only the own client can hand over a group state.
+//!
+//! Since two devices must never use the same leaf, the old device must stop
+//! using the group once the new device imported the transfer. Exporting the
+//! transfer doesn't change the group, so that it can be retried if the
+//! transfer gets lost. Once the new device confirmed the import, the old
+//! device calls [`MlsGroup::complete_device_transfer()`], which deletes the
+//! private keys of the own leaf and makes the group inactive. The new device
+//! should update its leaf with [`MlsGroup::self_update()`] right after the
+//! import, such that the old device can't decrypt any future messages.
+
+use openmls_traits::{
+    key_store::OpenMlsKeyStore, signatures::Signer, types::HpkeCiphertext, OpenMlsCryptoProvider,
+};
+use serde::{Deserialize, Serialize};
+use tls_codec::{Serialize as TlsSerializeTrait, TlsDeserialize, TlsSerialize, TlsSize};
+
+use super::{errors::GroupStateTransferError, *};
+use crate::{
+    ciphersuite::{
+        hash_ref::KeyPackageRef,
+        hpke,
+        signable::{signature_labels, Signable, SignedStruct, Verifiable},
+        HpkePrivateKey, OpenMlsSignaturePublicKey, Signature,
+    },
+    key_packages::errors::KeyPackageDeleteError,
+    treesync::node::encryption_keys::EncryptionKeyPair,
+};
+
+const GROUP_STATE_TRANSFER_LABEL: &str = "GroupStateTransfer";
+const SIGNATURE_GROUP_STATE_TRANSFER_LABEL: &str = signature_labels::GROUP_STATE_TRANSFER;
+
+/// The encrypted state of a group that is transferred to a new device of the
+/// own client.
+///
+/// ```c
+/// struct {
+///     opaque group_id<V>;
+///     uint64 epoch;
+///     KeyPackageRef key_package_ref;
+///     HPKECiphertext hpke_ciphertext;
+///     /* SignWithLabel(., "GroupStateTransferTBS", GroupStateTransferTBS) */
+///     opaque signature<V>;
+/// } GroupStateTransfer;
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, TlsSerialize, TlsDeserialize, TlsSize)]
+pub struct GroupStateTransfer {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    key_package_ref: KeyPackageRef,
+    hpke_ciphertext: HpkeCiphertext,
+    signature: Signature,
+}
+
+/// Group state transfer (To Be Signed)
+///
+/// ```c
+/// struct {
+///     opaque group_id<V>;
+///     uint64 epoch;
+///     KeyPackageRef key_package_ref;
+///     HPKECiphertext hpke_ciphertext;
+/// } GroupStateTransferTBS;
+/// ```
+#[derive(Debug, TlsSerialize, TlsSize)]
+struct GroupStateTransferTbs {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    key_package_ref: KeyPackageRef,
+    hpke_ciphertext: HpkeCiphertext,
+}
+
+/// The encrypted content of a [`GroupStateTransfer`]. The group is borrowed
+/// when the content is serialized and owned when it is deserialized.
+#[derive(Serialize, Deserialize)]
+struct GroupStateTransferContent<G> {
+    key_package_ref: KeyPackageRef,
+    group: G,
+    epoch_keypairs: Vec<EncryptionKeyPair>,
+    leaf_node_keypairs: Vec<EncryptionKeyPair>,
+}
+
+impl GroupStateTransfer {
+    /// Returns the group ID of the transferred group.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Returns the epoch of the transferred group.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the reference of the key package the transfer is encrypted to.
+    pub fn key_package_ref(&self) -> &KeyPackageRef {
+        &self.key_package_ref
+    }
+
+    /// Returns the HPKE context that binds the ciphertext to the group, the
+    /// epoch and the key package of the new device.
+    fn context(
+        group_id: &GroupId,
+        epoch: GroupEpoch,
+        key_package_ref: &KeyPackageRef,
+    ) -> Result<Vec<u8>, LibraryError> {
+        let mut context = group_id
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        epoch
+            .tls_serialize(&mut context)
+            .map_err(LibraryError::missing_bound_check)?;
+        key_package_ref
+            .tls_serialize(&mut context)
+            .map_err(LibraryError::missing_bound_check)?;
+        Ok(context)
+    }
+}
+
+impl MlsGroup {
+    // === Group state transfer ===
+
+    /// Exports the state of the group for a new device of the own client,
+    /// encrypted to the init key of the new device's `key_package` and signed
+    /// with the `signer` of the own leaf.
+    ///
+    /// The `key_package` must have the ciphersuite of the group as well as
+    /// the credential and signature key of the own leaf. The group is not
+    /// changed. Once the new device confirmed the import, the application
+    /// must call [`MlsGroup::complete_device_transfer()`].
+    ///
+    /// Returns an error if the group is inactive or has a pending commit.
+    pub fn transfer_to_device<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        key_package: &KeyPackage,
+    ) -> Result<GroupStateTransfer, GroupStateTransferError<KeyStore::Error>> {
+        self.is_operational()?;
+
+        if key_package.ciphersuite() != self.ciphersuite() {
+            return Err(GroupStateTransferError::CiphersuiteMismatch);
+        }
+        let own_leaf = self
+            .own_leaf_node()
+            .ok_or_else(|| LibraryError::custom("Own leaf node missing"))?;
+        let new_leaf = key_package.leaf_node();
+        if new_leaf.credential() != own_leaf.credential()
+            || new_leaf.signature_key() != own_leaf.signature_key()
+        {
+            return Err(GroupStateTransferError::KeyPackageMismatch);
+        }
+
+        let key_package_ref = key_package.hash_ref(backend.crypto())?;
+        let leaf_node_keypairs: Vec<EncryptionKeyPair> = self
+            .own_leaf_nodes
+            .iter()
+            .filter_map(|leaf_node| {
+                EncryptionKeyPair::read_from_key_store(backend, leaf_node.encryption_key())
+            })
+            .collect();
+        let plaintext = serde_json::to_vec(&GroupStateTransferContent {
+            key_package_ref: key_package_ref.clone(),
+            group: &*self,
+            epoch_keypairs: self.group.read_epoch_keypairs(backend),
+            leaf_node_keypairs,
+        })
+        .map_err(|_| LibraryError::custom("Serializing the group state failed"))?;
+        let context = GroupStateTransfer::context(self.group_id(), self.epoch(), &key_package_ref)?;
+        let hpke_ciphertext = hpke::encrypt_with_label(
+            key_package.hpke_init_key().as_slice(),
+            GROUP_STATE_TRANSFER_LABEL,
+            &context,
+            &plaintext,
+            self.ciphersuite(),
+            backend.crypto(),
+        )
+        .map_err(|_| LibraryError::custom("Encryption failed. A serialization issue really"))?;
+
+        let tbs = GroupStateTransferTbs {
+            group_id: self.group_id().clone(),
+            epoch: self.epoch(),
+            key_package_ref,
+            hpke_ciphertext,
+        };
+        Ok(tbs.sign(signer)?)
+    }
+
+    /// Completes a transfer of the group state that was exported with
+    /// [`MlsGroup::transfer_to_device()`].
+    ///
+    /// Must only be called once the new device confirmed the import of the
+    /// transfer, since the own leaf then belongs to the new device. Deletes
+    /// the private keys of the own leaf from the key store and makes the
+    /// group inactive.
+    ///
+    /// Returns an error if the group is inactive or has a pending commit.
+    pub fn complete_device_transfer<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), GroupStateTransferError<KeyStore::Error>> {
+        self.is_operational()?;
+
+        self.group
+            .delete_epoch_keypairs(backend)
+            .map_err(GroupStateTransferError::KeyStoreError)?;
+        for leaf_node in &self.own_leaf_nodes {
+            if let Some(keypair) =
+                EncryptionKeyPair::read_from_key_store(backend, leaf_node.encryption_key())
+            {
+                keypair
+                    .delete_from_key_store(backend)
+                    .map_err(GroupStateTransferError::KeyStoreError)?;
+            }
+        }
+        self.own_leaf_nodes.clear();
+        self.group_state = MlsGroupState::Inactive;
+
+        // Since the state of the group was changed, arm the state flag
+        self.flag_state_change();
+
+        Ok(())
+    }
+
+    /// Imports a [`GroupStateTransfer`] that was exported by another device
+    /// of the own client with [`MlsGroup::transfer_to_device()`].
+    ///
+    /// The key package the transfer is encrypted to is looked up in the key
+    /// store and deleted after the import, like when joining from a
+    /// [`Welcome`]. The signature of the transfer is verified with the
+    /// signature key of the key package before the transfer is decrypted.
+    /// The private keys of the own leaf are stored in the key store. The
+    /// `mls_group_config` replaces the configuration of the old device.
+    pub fn new_from_state_transfer<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        mls_group_config: &MlsGroupConfig,
+        transfer: GroupStateTransfer,
+    ) -> Result<Self, GroupStateTransferError<KeyStore::Error>> {
+        let key_package: KeyPackage = backend
+            .key_store()
+            .read(transfer.key_package_ref.as_slice())
+            .ok_or(GroupStateTransferError::NoMatchingKeyPackage)?;
+        let private_key = backend
+            .key_store()
+            .read::<HpkePrivateKey>(key_package.hpke_init_key().as_slice())
+            .ok_or(GroupStateTransferError::NoMatchingKeyPackage)?;
+
+        // Only the own client can sign with the signature key of the key
+        // package.
+        let signature_key = OpenMlsSignaturePublicKey::from_signature_key(
+            key_package.leaf_node().signature_key().clone(),
+            key_package.ciphersuite().signature_algorithm(),
+        );
+        transfer
+            .verify_no_out(backend.crypto(), &signature_key)
+            .map_err(|_| GroupStateTransferError::InvalidSignature)?;
+
+        let context = GroupStateTransfer::context(
+            &transfer.group_id,
+            transfer.epoch,
+            &transfer.key_package_ref,
+        )?;
+        let plaintext = hpke::decrypt_with_label(
+            private_key.as_slice(),
+            GROUP_STATE_TRANSFER_LABEL,
+            &context,
+            &transfer.hpke_ciphertext,
+            key_package.ciphersuite(),
+            backend.crypto(),
+        )
+        .map_err(|_| GroupStateTransferError::DecryptionFailed)?;
+        let content: GroupStateTransferContent<MlsGroup> = serde_json::from_slice(&plaintext)
+            .map_err(|_| GroupStateTransferError::MalformedContent)?;
+
+        let mut mls_group = content.group;
+        if content.key_package_ref != transfer.key_package_ref
+            || mls_group.group_id() != &transfer.group_id
+            || mls_group.epoch() != transfer.epoch
+        {
+            return Err(GroupStateTransferError::MalformedContent);
+        }
+        let own_leaf = mls_group
+            .own_leaf_node()
+            .ok_or(GroupStateTransferError::MalformedContent)?;
+        let new_leaf = key_package.leaf_node();
+        if new_leaf.credential() != own_leaf.credential()
+            || new_leaf.signature_key() != own_leaf.signature_key()
+        {
+            return Err(GroupStateTransferError::KeyPackageMismatch);
+        }
+
+        mls_group
+            .group
+            .store_epoch_keypairs(backend, &content.epoch_keypairs)
+            .map_err(GroupStateTransferError::KeyStoreError)?;
+        for keypair in content.leaf_node_keypairs {
+            keypair
+                .write_to_key_store(backend)
+                .map_err(GroupStateTransferError::KeyStoreError)?;
+        }
+
+        // The leaf of the key package is not used, since the own leaf is
+        // taken over.
+        if let Some(keypair) =
+            EncryptionKeyPair::read_from_key_store(backend, new_leaf.encryption_key())
+        {
+            keypair
+                .delete_from_key_store(backend)
+                .map_err(GroupStateTransferError::KeyStoreError)?;
+        }
+
+        // Delete the [`KeyPackage`] and the corresponding private key from the
+        // key store
+        key_package.delete(backend).map_err(|e| match e {
+            KeyPackageDeleteError::LibraryError(e) => GroupStateTransferError::LibraryError(e),
+            KeyPackageDeleteError::KeyStoreError(e) => GroupStateTransferError::KeyStoreError(e),
+        })?;
+
+        mls_group.set_configuration(mls_group_config);
+
+        Ok(mls_group)
+    }
+}
+
+impl Signable for GroupStateTransferTbs {
+    type SignedOutput = GroupStateTransfer;
+
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.tls_serialize_detached()
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_GROUP_STATE_TRANSFER_LABEL
+    }
+}
+
+impl SignedStruct<GroupStateTransferTbs> for GroupStateTransfer {
+    fn from_payload(payload: GroupStateTransferTbs, signature: Signature) -> Self {
+        Self {
+            group_id: payload.group_id,
+            epoch: payload.epoch,
+            key_package_ref: payload.key_package_ref,
+            hpke_ciphertext: payload.hpke_ciphertext,
+            signature,
+        }
+    }
+}
+
+impl Verifiable for GroupStateTransfer {
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        // The serialization of the `GroupStateTransferTBS`.
+        let mut payload = self.group_id.tls_serialize_detached()?;
+        self.epoch.tls_serialize(&mut payload)?;
+        self.key_package_ref.tls_serialize(&mut payload)?;
+        self.hpke_ciphertext.tls_serialize(&mut payload)?;
+        Ok(payload)
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_GROUP_STATE_TRANSFER_LABEL
+    }
+}
@@ -0,0 +1,106 @@
+//! # Epoch history
+//!
+//! An [`MlsGroup`] records the local time at which it entered each epoch,
+//! i.e., when the group was created or joined and when commits were merged.
+//! This allows applications to implement policies such as "update the own
+//! leaf if the epoch is older than N days" and helps debugging groups that
+//! don't make progress.
+//!
+//! The times are taken from the time provider of the [`MlsGroupConfig`] and
+//! are not authenticated by other members. Only the most recent
+//! [`MAX_EPOCH_HISTORY`] transitions are kept.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+use crate::treesync::node::leaf_node::TimeProvider;
+
+/// The maximum number of epoch transitions that are kept in the history.
+pub const MAX_EPOCH_HISTORY: usize = 64;
+
+/// The local time at which a group entered an epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochTransition {
+    epoch: GroupEpoch,
+    started_at: SystemTime,
+}
+
+impl EpochTransition {
+    /// Returns the epoch.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the local time at which the group entered the epoch.
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+}
+
+/// The most recent [`EpochTransition`]s of a group, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct EpochHistory {
+    transitions: VecDeque<EpochTransition>,
+}
+
+impl EpochHistory {
+    /// Records that the group entered `epoch` now, according to the
+    /// `time_provider`. Nothing is recorded if the time provider doesn't
+    /// return the current time.
+    pub(crate) fn record(&mut self, epoch: GroupEpoch, time_provider: &dyn TimeProvider) {
+        let Some(started_at) = now(time_provider) else {
+            return;
+        };
+        if self.transitions.len() >= MAX_EPOCH_HISTORY {
+            self.transitions.pop_front();
+        }
+        self.transitions
+            .push_back(EpochTransition { epoch, started_at });
+    }
+}
+
+/// Returns the current time according to the `time_provider`.
+fn now(time_provider: &dyn TimeProvider) -> Option<SystemTime> {
+    time_provider
+        .unix_now()
+        .map(|now| UNIX_EPOCH + Duration::from_secs(now))
+}
+
+impl MlsGroup {
+    // === Epoch history ===
+
+    /// Returns the local time at which the group entered the current epoch.
+    ///
+    /// Returns `None` if the time is unknown, e.g., because the group was
+    /// persisted by a version of OpenMLS that didn't record it.
+    pub fn epoch_started_at(&self) -> Option<SystemTime> {
+        self.epoch_history
+            .transitions
+            .back()
+            .filter(|transition| transition.epoch == self.epoch())
+            .map(|transition| transition.started_at)
+    }
+
+    /// Returns the time that passed since the group entered the current
+    /// epoch, or `None` if it is unknown. See
+    /// [`MlsGroup::epoch_started_at()`].
+    ///
+    /// The current time is taken from the time provider of the
+    /// [`MlsGroupConfig`]. If the clock went backwards since, the age is zero.
+    pub fn epoch_age(&self) -> Option<Duration> {
+        let now = now(self.configuration().time_provider())?;
+        self.epoch_started_at()
+            .map(|started_at| now.duration_since(started_at).unwrap_or_default())
+    }
+
+    /// Returns the recorded [`EpochTransition`]s of the group, oldest first.
+    /// At most [`MAX_EPOCH_HISTORY`] transitions are kept.
+    pub fn epoch_history(&self) -> impl Iterator<Item = &EpochTransition> {
+        self.epoch_history.transitions.iter()
+    }
+}
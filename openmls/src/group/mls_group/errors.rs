@@ -8,13 +8,23 @@
 use thiserror::Error;
 
 use crate::{
+    binary_tree::LeafNodeIndex,
+    ciphersuite::signable::SignatureError,
     error::LibraryError,
-    extensions::errors::InvalidExtensionError,
-    group::errors::{
-        CreateAddProposalError, CreateCommitError, MergeCommitError, StageCommitError,
-        ValidationError,
+    extensions::{
+        errors::{ExtensionError, InvalidExtensionError},
+        ExtensionType,
+    },
+    group::{
+        errors::{
+            CreateAddProposalError, CreateCommitError, CreateGroupContextExtProposalError,
+            CreateRemoveProposalError, MergeCommitError, ProposalValidationError, StageCommitError,
+            ValidationError,
+        },
+        DesyncReport,
     },
     schedule::errors::PskError,
+    tree::secret_tree::SecretTreeError,
     treesync::errors::{LeafNodeValidationError, PublicTreeError},
 };
 
@@ -39,9 +49,15 @@ pub enum NewGroupError<KeyStoreError> {
     /// Unsupported extension type in required capabilities.
     #[error("Unsupported extension type in required capabilities.")]
     UnsupportedExtensionType,
+    /// The accepted media types don't contain the required media types.
+    #[error("The accepted media types don't contain the required media types.")]
+    UnsupportedMediaTypes,
     /// Invalid extensions set in configuration
     #[error("Invalid extensions set in configuration")]
     InvalidExtensions(InvalidExtensionError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
 }
 
 /// EmptyInput error
@@ -89,6 +105,17 @@ pub enum MergePendingCommitError<KeyStoreError> {
     MergeCommitError(#[from] MergeCommitError<KeyStoreError>),
 }
 
+/// Rollback pending commit error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum RollbackPendingCommitError<KeyStoreError> {
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// Error accessing the key store.
+    #[error("Error accessing the key store.")]
+    KeyStoreError(KeyStoreError),
+}
+
 /// Process message error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ProcessMessageError {
@@ -116,6 +143,37 @@ pub enum ProcessMessageError {
     /// The proposal is invalid for the Sender of type [External](crate::prelude::Sender::External)
     #[error("The proposal is invalid for the Sender of type External")]
     UnsupportedProposalType,
+    /// A credential in the message was rejected by the credential validator.
+    #[error("A credential in the message was rejected by the credential validator.")]
+    InvalidCredential,
+    /// The credential of the member at the given leaf index is revoked or expired.
+    #[error("The credential of the member at leaf index {0:?} is revoked or expired.")]
+    RevokedCredential(LeafNodeIndex),
+    /// See [`LeafNodeValidationError`] for more details.
+    #[error(transparent)]
+    LeafNodeValidation(#[from] LeafNodeValidationError),
+    /// The authenticated data of the message was rejected by the AAD validator.
+    #[error("The authenticated data of the message was rejected by the AAD validator.")]
+    InvalidAad,
+    /// The proposal was rejected because the proposal store is full.
+    #[error("The proposal was rejected because the proposal store is full.")]
+    ProposalStoreFull,
+    /// See [`ProposalValidationError`] for more details.
+    #[error(transparent)]
+    InvalidProposal(#[from] ProposalValidationError),
+    /// The group is out of sync with the sender of the message. See
+    /// [`DesyncReport`] for more details.
+    #[error("The group is out of sync with the sender of the message.")]
+    Desynced(DesyncReport),
+    /// See [`ExtensionError`] for more details.
+    #[error(transparent)]
+    InvalidExtension(#[from] ExtensionError),
+    /// A credential validator was configured for the group, but is missing.
+    /// It has to be set again with
+    /// [`MlsGroup::set_configuration()`](crate::group::MlsGroup::set_configuration())
+    /// after loading the group.
+    #[error("A credential validator was configured for the group, but is missing.")]
+    MissingCredentialValidator,
 }
 
 /// Create message error
@@ -130,6 +188,144 @@ pub enum CreateMessageError {
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
+/// Create fragmented message error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum CreateFragmentedMessageError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The maximum message size is too small to fit a fragment.
+    #[error("The maximum message size is too small to fit a fragment.")]
+    MaxMessageSizeTooSmall,
+    /// The message requires more fragments than supported.
+    #[error("The message requires more fragments than supported.")]
+    TooManyFragments,
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
+/// Fragment reassembly error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ReassemblyError {
+    /// The message is not an application message.
+    #[error("The message is not an application message.")]
+    NotAnApplicationMessage,
+    /// The authenticated data of the message does not contain a valid fragment header.
+    #[error("The authenticated data of the message does not contain a valid fragment header.")]
+    MalformedFragmentHeader,
+    /// The fragment does not match the previously received fragments of the same message.
+    #[error("The fragment does not match the previously received fragments of the same message.")]
+    InconsistentFragment,
+    /// The fragment was already received.
+    #[error("The fragment was already received.")]
+    DuplicateFragment,
+    /// Buffering the fragment would exceed the configured memory limit.
+    #[error("Buffering the fragment would exceed the configured memory limit.")]
+    MemoryLimitExceeded,
+    /// The maximum number of incomplete messages is reached.
+    #[error("The maximum number of incomplete messages is reached.")]
+    TooManyPendingMessages,
+}
+
+/// Message stream error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum MessageStreamError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The stream header could not be written.
+    #[error("The stream header could not be written.")]
+    WriteFailed,
+    /// The stream header could not be read or is malformed.
+    #[error("The stream header could not be read or is malformed.")]
+    MalformedHeader,
+    /// The stream was created in a different group.
+    #[error("The stream was created in a different group.")]
+    WrongGroupId,
+    /// The stream was created in a different epoch.
+    #[error("The stream was created in a different epoch.")]
+    WrongEpoch,
+}
+
+/// Sender ratchet state error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum SenderRatchetStateError {
+    /// The state belongs to a different group.
+    #[error("The state belongs to a different group.")]
+    WrongGroupId,
+    /// The secrets of the epoch of the state are not available.
+    #[error("The secrets of the epoch of the state are not available.")]
+    UnknownEpoch,
+    /// The sender ratchets in the state could not be decoded.
+    #[error("The sender ratchets in the state could not be decoded.")]
+    MalformedState,
+    /// See [`SecretTreeError`] for more details.
+    #[error(transparent)]
+    SecretTreeError(#[from] SecretTreeError),
+}
+
+/// Create targeted message error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum CreateTargetedMessageError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The recipient is not a member of the group.
+    #[error("The recipient is not a member of the group.")]
+    UnknownRecipient,
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
+/// Process targeted message error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ProcessTargetedMessageError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The message was sent in a different group.
+    #[error("The message was sent in a different group.")]
+    WrongGroupId,
+    /// The message was sent in a different epoch.
+    #[error("The message was sent in a different epoch.")]
+    WrongEpoch,
+    /// The message is targeted at a different member.
+    #[error("The message is targeted at a different member.")]
+    WrongRecipient,
+    /// The decryption key of the own leaf could not be found in the key store.
+    #[error("The decryption key of the own leaf could not be found in the key store.")]
+    MissingDecryptionKey,
+    /// The message could not be decrypted.
+    #[error("The message could not be decrypted.")]
+    DecryptionFailed,
+    /// The decrypted content of the message is malformed.
+    #[error("The decrypted content of the message is malformed.")]
+    MalformedContent,
+    /// The sender is not a member of the group.
+    #[error("The sender is not a member of the group.")]
+    UnknownSender,
+    /// The message's signature is invalid.
+    #[error("The message's signature is invalid.")]
+    InvalidSignature,
 }
 
 /// Add members error
@@ -150,6 +346,15 @@ pub enum AddMembersError<KeyStoreError> {
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// The credential of a new member was rejected by the credential validator.
+    #[error("The credential of a new member was rejected by the credential validator.")]
+    InvalidCredential,
+    /// A credential validator was configured for the group, but is missing.
+    /// It has to be set again with
+    /// [`MlsGroup::set_configuration()`](crate::group::MlsGroup::set_configuration())
+    /// after loading the group.
+    #[error("A credential validator was configured for the group, but is missing.")]
+    MissingCredentialValidator,
 }
 
 /// Propose add members error
@@ -170,6 +375,12 @@ pub enum ProposeAddMemberError {
     /// See [`LeafNodeValidationError`] for more details.
     #[error(transparent)]
     LeafNodeValidation(#[from] LeafNodeValidationError),
+    /// See [`ProposalValidationError`] for more details.
+    #[error(transparent)]
+    ProposalValidationError(#[from] ProposalValidationError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
 }
 
 /// Propose remove members error
@@ -187,6 +398,13 @@ pub enum ProposeRemoveMemberError {
     /// The member that should be removed can not be found.
     #[error("The member that should be removed can not be found.")]
     UnknownMember,
+    /// More than one member has the identity of the member that should be
+    /// removed.
+    #[error("More than one member has the identity of the member that should be removed.")]
+    AmbiguousIdentity,
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
 }
 
 /// Remove members error
@@ -224,6 +442,9 @@ pub enum LeaveGroupError {
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
 }
 
 /// Self update error
@@ -264,6 +485,61 @@ pub enum ProposeSelfUpdateError<KeyStoreError> {
     /// See [`PublicTreeError`] for more details.
     #[error(transparent)]
     PublicTreeError(#[from] PublicTreeError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
+/// Update capabilities error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum UpdateCapabilitiesError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// Error accessing the key store.
+    #[error("Error accessing the key store.")]
+    KeyStoreError(KeyStoreError),
+    /// See [`PublicTreeError`] for more details.
+    #[error(transparent)]
+    PublicTreeError(#[from] PublicTreeError),
+    /// The new capabilities don't include the ciphersuite or the protocol
+    /// version of the group.
+    #[error(
+        "The new capabilities don't include the ciphersuite or the protocol version of the group."
+    )]
+    UnsupportedCiphersuiteOrVersion,
+    /// See [`LeafNodeValidationError`] for more details.
+    #[error(transparent)]
+    LeafNodeValidation(#[from] LeafNodeValidationError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
+/// Update leaf extensions error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum UpdateLeafExtensionsError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// Error accessing the key store.
+    #[error("Error accessing the key store.")]
+    KeyStoreError(KeyStoreError),
+    /// See [`PublicTreeError`] for more details.
+    #[error(transparent)]
+    PublicTreeError(#[from] PublicTreeError),
+    /// See [`LeafNodeValidationError`] for more details.
+    #[error(transparent)]
+    LeafNodeValidation(#[from] LeafNodeValidationError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
 }
 
 /// Commit to pending proposals error
@@ -295,6 +571,68 @@ pub enum ExportGroupInfoError {
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// The extension type is not a custom extension type registered in the
+    /// group's configuration.
+    #[error("The extension type {0:?} can't be added to a GroupInfo.")]
+    UnsupportedExtensionType(ExtensionType),
+    /// See [`ExtensionError`] for more details.
+    #[error(transparent)]
+    InvalidExtension(#[from] ExtensionError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
+/// Errors that can happen when exporting the commit audit log.
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ExportCommitAuditLogError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The commit audit log is not enabled in the group's configuration.
+    #[error("The commit audit log is not enabled in the group's configuration.")]
+    AuditLogDisabled,
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
+/// Errors that can happen when transferring the group state to a new device.
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum GroupStateTransferError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The ciphersuite of the key package doesn't match the group's ciphersuite.
+    #[error("The ciphersuite of the key package doesn't match the group's ciphersuite.")]
+    CiphersuiteMismatch,
+    /// The credential or signature key of the key package doesn't match the own leaf.
+    #[error("The credential or signature key of the key package doesn't match the own leaf.")]
+    KeyPackageMismatch,
+    /// No matching KeyPackage was found in the key store.
+    #[error("No matching KeyPackage was found in the key store.")]
+    NoMatchingKeyPackage,
+    /// The transfer could not be decrypted.
+    #[error("The transfer could not be decrypted.")]
+    DecryptionFailed,
+    /// The signature of the transfer is invalid.
+    #[error("The signature of the transfer is invalid.")]
+    InvalidSignature,
+    /// The decrypted content of the transfer is malformed.
+    #[error("The decrypted content of the transfer is malformed.")]
+    MalformedContent,
+    /// Error writing to or deleting from the key store.
+    #[error("Error writing to or deleting from the key store.")]
+    KeyStoreError(KeyStoreError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
 }
 
 /// Export secret error
@@ -306,9 +644,49 @@ pub enum ExportSecretError {
     /// The requested key length is too long.
     #[error("The requested key length is too long.")]
     KeyLengthTooLong,
+    /// The exporter secret of the requested epoch is not available.
+    #[error("The exporter secret of the requested epoch is not available.")]
+    UnknownEpoch,
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+}
+
+/// Errors that can happen when verifying a membership token.
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum VerifyMembershipTokenError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The exporter secret of the epoch of the token is not available.
+    #[error("The exporter secret of the epoch of the token is not available.")]
+    UnknownEpoch,
+    /// The leaf of the token was blank in the epoch of the token.
+    #[error("The leaf of the token was blank in the epoch of the token.")]
+    UnknownMember,
+    /// The HMAC of the token is invalid.
+    #[error("The HMAC of the token is invalid.")]
+    InvalidToken,
+    /// The signature of the token is invalid.
+    #[error("The signature of the token is invalid.")]
+    InvalidSignature,
+}
+
+/// Create membership token error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum CreateMembershipTokenError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
 }
 
 /// Propose PSK error
@@ -349,4 +727,69 @@ pub enum ProposalError<KeyStoreError> {
     /// See [`ValidationError`] for more details.
     #[error(transparent)]
     ValidationError(#[from] ValidationError),
+    /// See [`CreateGroupContextExtProposalError`] for more details.
+    #[error(transparent)]
+    CreateGroupContextExtProposalError(#[from] CreateGroupContextExtProposalError),
+    /// See [`ProposeExternalSenderError`] for more details.
+    #[error(transparent)]
+    ProposeExternalSenderError(#[from] ProposeExternalSenderError),
+    /// See [`SafeExtensionError`] for more details.
+    #[error(transparent)]
+    SafeExtensionError(#[from] SafeExtensionError),
+    /// See [`CreateRemoveProposalError`] for more details.
+    #[error(transparent)]
+    CreateRemoveProposalError(#[from] CreateRemoveProposalError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
+/// Propose external sender error
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum ProposeExternalSenderError {
+    /// An external sender with the same credential is already in the group context.
+    #[error("An external sender with the same credential is already in the group context.")]
+    DuplicateExternalSender,
+    /// The external sender that should be removed can not be found.
+    #[error("The external sender that should be removed can not be found.")]
+    UnknownExternalSender,
+}
+
+/// Safe extension error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum SafeExtensionError {
+    /// See [`ExportSecretError`] for more details.
+    #[error(transparent)]
+    ExportSecretError(#[from] ExportSecretError),
+    /// The extension is not registered in the group's custom extension registry.
+    #[error("The extension is not registered in the group's custom extension registry.")]
+    UnregisteredExtension,
+}
+
+/// Invalid [`MlsGroupConfig`](super::MlsGroupConfig) error
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum InvalidConfigError {
+    /// External senders can only send PublicMessages, which contradicts an incoming wire format policy that only accepts PrivateMessages for proposals.
+    #[error("External senders can only send PublicMessages, which contradicts an incoming wire format policy that only accepts PrivateMessages for proposals.")]
+    ExternalSendersWithCiphertextOnlyPolicy,
+    /// A maximum age for past epochs is set, but no past epochs are kept.
+    #[error("A maximum age for past epochs is set, but no past epochs are kept.")]
+    MaxPastEpochAgeWithoutPastEpochs,
+    /// The own leaf doesn't accept all required media types.
+    #[error("The own leaf doesn't accept all required media types.")]
+    RequiredMediaTypesNotAccepted,
+    /// The padding policy contains a bucket size of zero or no buckets at all.
+    #[error("The padding policy contains a bucket size of zero or no buckets at all.")]
+    InvalidPaddingBuckets,
+}
+
+/// Crypto provider selection error
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum CryptoProviderError {
+    /// No crypto provider is registered under the configured identifier.
+    #[error("No crypto provider is registered under the configured identifier.")]
+    UnknownCryptoProvider(String),
+    /// The crypto provider is not of the requested type.
+    #[error("The crypto provider is not of the requested type.")]
+    ProviderTypeMismatch,
 }
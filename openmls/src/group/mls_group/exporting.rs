@@ -23,10 +23,7 @@ impl MlsGroup {
             Ok(self
                 .group
                 .export_secret(backend, label, context, key_length)
-                .map_err(|e| match e {
-                    ExporterError::LibraryError(e) => e.into(),
-                    ExporterError::KeyLengthTooLong => ExportSecretError::KeyLengthTooLong,
-                })?)
+                .map_err(export_secret_error)?)
         } else {
             Err(ExportSecretError::GroupStateError(
                 MlsGroupStateError::UseAfterEviction,
@@ -34,6 +31,54 @@ impl MlsGroup {
         }
     }
 
+    /// Exports a secret from the given epoch, which is either the current
+    /// epoch or one of the past epochs whose secrets are kept according to
+    /// [`MlsGroupConfig::max_past_epochs`](crate::group::MlsGroupConfig::max_past_epochs).
+    /// The exporter secrets of past epochs are only kept if
+    /// [`MlsGroupConfig::retain_past_exporter_secrets`](crate::group::MlsGroupConfig::retain_past_exporter_secrets)
+    /// is set and `immediate_key_deletion` is not.
+    /// Returns [`ExportSecretError::UnknownEpoch`] if the secrets of the epoch
+    /// are not available.
+    /// Returns [`ExportSecretError::KeyLengthTooLong`] if the requested
+    /// key length is too long.
+    /// Returns [`ExportSecretError::GroupStateError(MlsGroupStateError::UseAfterEviction)`](MlsGroupStateError::UseAfterEviction)
+    /// if the group is not active.
+    pub fn export_secret_for_epoch(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        epoch: GroupEpoch,
+        label: &str,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<Vec<u8>, ExportSecretError> {
+        if self.is_active() {
+            Ok(self
+                .group
+                .export_secret_for_epoch(backend, epoch, label, context, key_length)
+                .map_err(export_secret_error)?)
+        } else {
+            Err(ExportSecretError::GroupStateError(
+                MlsGroupStateError::UseAfterEviction,
+            ))
+        }
+    }
+
+    /// ☣️ Exports all secrets of the current epoch.
+    ///
+    /// This is only meant for test harnesses, known-answer test generation
+    /// and formal verification tooling. See
+    /// [`ExportedEpochSecrets`](crate::schedule::ExportedEpochSecrets) for
+    /// more details. The secrets of past epochs are deleted and can't be
+    /// exported.
+    #[cfg(feature = "epoch-secrets-export")]
+    pub fn export_epoch_secrets(&self) -> crate::schedule::ExportedEpochSecrets {
+        crate::schedule::ExportedEpochSecrets::new(
+            self.epoch(),
+            self.group.group_epoch_secrets(),
+            self.group.message_secrets(),
+        )
+    }
+
     /// Returns the epoch authenticator of the current epoch.
     pub fn epoch_authenticator(&self) -> &EpochAuthenticator {
         self.group.epoch_authenticator()
@@ -51,6 +96,9 @@ impl MlsGroup {
     }
 
     /// Export a group info object for this group.
+    ///
+    /// Use [`MlsGroup::export_group_info_with_options()`] to choose the
+    /// standard extensions and add custom extensions.
     pub fn export_group_info(
         &self,
         backend: &impl OpenMlsCryptoProvider,
@@ -63,3 +111,11 @@ impl MlsGroup {
             .into())
     }
 }
+
+pub(super) fn export_secret_error(error: ExporterError) -> ExportSecretError {
+    match error {
+        ExporterError::LibraryError(e) => e.into(),
+        ExporterError::KeyLengthTooLong => ExportSecretError::KeyLengthTooLong,
+        ExporterError::UnknownEpoch => ExportSecretError::UnknownEpoch,
+    }
+}
@@ -0,0 +1,228 @@
+//! # Forward secrecy report
+//!
+//! An [`MlsGroup`] retains secrets that allow decrypting messages beyond the
+//! ones that were already processed: the message secrets of past epochs (see
+//! `max_past_epochs` in the [`MlsGroupConfig`]), key material for skipped
+//! generations of the sender ratchets (see the
+//! [`SenderRatchetConfiguration`](crate::prelude::SenderRatchetConfiguration))
+//! and resumption PSKs.
+//!
+//! [`MlsGroup::forward_secrecy_report()`] returns a [`ForwardSecrecyReport`]
+//! that lists these secrets, i.e., what an attacker could decrypt if they
+//! seized the state of the group now. Ages are given in epochs and
+//! generations, respectively.
+
+use crate::tree::{secret_tree::SecretTree, sender_ratchet::SenderRatchet};
+
+use super::*;
+
+/// Lists the secrets that an [`MlsGroup`] retains and that allow decrypting
+/// messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForwardSecrecyReport {
+    current_epoch: GroupEpoch,
+    epochs: Vec<EpochSecretsReport>,
+    resumption_psk_epochs: Vec<GroupEpoch>,
+}
+
+impl ForwardSecrecyReport {
+    /// Returns the current epoch of the group.
+    pub fn current_epoch(&self) -> GroupEpoch {
+        self.current_epoch
+    }
+
+    /// Returns a report for every epoch for which message secrets are
+    /// retained, in ascending order of the epochs. The last entry is the
+    /// current epoch.
+    pub fn epochs(&self) -> &[EpochSecretsReport] {
+        &self.epochs
+    }
+
+    /// Returns the epochs for which a resumption PSK is retained, in
+    /// ascending order.
+    pub fn resumption_psk_epochs(&self) -> &[GroupEpoch] {
+        &self.resumption_psk_epochs
+    }
+
+    /// Returns the number of key material entries for skipped generations
+    /// that are retained across all epochs and senders.
+    pub fn retained_generation_count(&self) -> usize {
+        self.epochs
+            .iter()
+            .map(EpochSecretsReport::retained_generation_count)
+            .sum()
+    }
+}
+
+/// Lists the message secrets of a single epoch that an [`MlsGroup`] retains.
+///
+/// As long as message secrets of an epoch are retained, messages from all
+/// future generations of the senders in that epoch can be decrypted. Messages
+/// from past generations can only be decrypted if their key material is
+/// retained.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpochSecretsReport {
+    epoch: GroupEpoch,
+    age: u64,
+    tree_secrets: usize,
+    senders: Vec<SenderSecretsReport>,
+}
+
+impl EpochSecretsReport {
+    fn new(epoch: GroupEpoch, current_epoch: GroupEpoch, secret_tree: &SecretTree) -> Self {
+        let senders = secret_tree
+            .initialized_senders()
+            .into_iter()
+            .map(|sender| {
+                let (handshake, application) = secret_tree.sender_ratchets(sender);
+                SenderSecretsReport {
+                    sender,
+                    handshake: handshake.map(RatchetReport::new),
+                    application: application.map(RatchetReport::new),
+                }
+            })
+            .collect();
+
+        Self {
+            epoch,
+            age: current_epoch.as_u64() - epoch.as_u64(),
+            tree_secrets: secret_tree.node_secret_count(),
+            senders,
+        }
+    }
+
+    /// Returns the epoch.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the age of the epoch, i.e., the number of epochs that passed
+    /// since. The age of the current epoch is `0`.
+    pub fn age(&self) -> u64 {
+        self.age
+    }
+
+    /// Returns the number of secret tree nodes that are retained. The
+    /// message secrets of all senders whose ratchets were not yet used can
+    /// be derived from these nodes.
+    pub fn tree_secrets(&self) -> usize {
+        self.tree_secrets
+    }
+
+    /// Returns a report for every sender whose ratchets were used in the
+    /// epoch, in ascending order of their leaf index.
+    pub fn senders(&self) -> &[SenderSecretsReport] {
+        &self.senders
+    }
+
+    /// Returns the number of key material entries for skipped generations
+    /// that are retained across all senders of the epoch.
+    pub fn retained_generation_count(&self) -> usize {
+        self.senders
+            .iter()
+            .flat_map(|sender| [&sender.handshake, &sender.application])
+            .flatten()
+            .map(|ratchet| ratchet.retained_generations.len())
+            .sum()
+    }
+}
+
+/// Lists the ratchets of a sender in a single epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SenderSecretsReport {
+    sender: LeafNodeIndex,
+    handshake: Option<RatchetReport>,
+    application: Option<RatchetReport>,
+}
+
+impl SenderSecretsReport {
+    /// Returns the leaf index of the sender.
+    pub fn sender(&self) -> LeafNodeIndex {
+        self.sender
+    }
+
+    /// Returns the report of the handshake ratchet, if it was used.
+    pub fn handshake(&self) -> Option<&RatchetReport> {
+        self.handshake.as_ref()
+    }
+
+    /// Returns the report of the application ratchet, if it was used.
+    pub fn application(&self) -> Option<&RatchetReport> {
+        self.application.as_ref()
+    }
+}
+
+/// Describes the state of a single sender ratchet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RatchetReport {
+    generation: u32,
+    retained_generations: Vec<u32>,
+}
+
+impl RatchetReport {
+    fn new(sender_ratchet: &SenderRatchet) -> Self {
+        Self {
+            generation: sender_ratchet.generation(),
+            retained_generations: sender_ratchet.retained_generations(),
+        }
+    }
+
+    /// Returns the generation of the ratchet. Key material for this and all
+    /// later generations can be derived.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Returns the past generations for which key material is retained,
+    /// e.g. because their messages were not received yet, in descending
+    /// order.
+    pub fn retained_generations(&self) -> &[u32] {
+        &self.retained_generations
+    }
+
+    /// Returns the age of the oldest retained key material in generations,
+    /// if any.
+    pub fn max_age(&self) -> Option<u32> {
+        self.retained_generations
+            .last()
+            .map(|generation| self.generation - generation)
+    }
+}
+
+impl MlsGroup {
+    // === Forward secrecy ===
+
+    /// Returns a [`ForwardSecrecyReport`] that lists the secrets the group
+    /// retains and that allow decrypting messages.
+    pub fn forward_secrecy_report(&self) -> ForwardSecrecyReport {
+        let current_epoch = self.epoch();
+        let mut epochs: Vec<EpochSecretsReport> = self
+            .group
+            .past_epochs()
+            .filter_map(|epoch| {
+                let message_secrets = self.group.message_secrets_for_epoch(epoch).ok()?;
+                Some(EpochSecretsReport::new(
+                    epoch,
+                    current_epoch,
+                    message_secrets.secret_tree(),
+                ))
+            })
+            .collect();
+        epochs.sort_by_key(|report| report.epoch);
+        epochs.push(EpochSecretsReport::new(
+            current_epoch,
+            current_epoch,
+            self.group.message_secrets().secret_tree(),
+        ));
+
+        let mut resumption_psk_epochs: Vec<GroupEpoch> =
+            self.group.resumption_psk_store.epochs().collect();
+        resumption_psk_epochs.sort();
+
+        ForwardSecrecyReport {
+            current_epoch,
+            epochs,
+            resumption_psk_epochs,
+        }
+    }
+}
@@ -0,0 +1,487 @@
+//! # Fragmentation
+//!
+//! Many transports limit the size of messages well below the size of the
+//! application payloads. This module provides an opt-in fragmentation layer
+//! for application messages.
+//!
+//! [`MlsGroup::create_fragmented_message()`] splits a payload into fragments
+//! such that every resulting [`MlsMessageOut`] fits into the maximum message
+//! size configured in the [`FragmentationConfig`]. Every fragment is sent as a
+//! separate `PrivateMessage` that carries a [`FragmentHeader`] at the
+//! beginning of its authenticated data.
+//!
+//! On the receiving side, a [`FragmentReassembler`] collects the fragments of
+//! processed application messages and returns the payload once all fragments
+//! were received. Incomplete messages are dropped after a timeout, and both
+//! the number of incomplete messages and the memory used for buffered
+//! fragments are bounded.
+//!
+//! Note that the reassembler expects every application message of the group
+//! to be created with [`MlsGroup::create_fragmented_message()`].
+
+use std::{collections::HashMap, mem, sync::Arc, time::Duration};
+
+use openmls_traits::{random::OpenMlsRand, signatures::Signer};
+use serde::{Deserialize, Serialize};
+use tls_codec::{
+    Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, TlsDeserialize,
+    TlsSerialize, TlsSize,
+};
+
+use super::{
+    errors::{CreateFragmentedMessageError, ReassemblyError},
+    *,
+};
+use crate::{
+    framing::errors::MessageEncryptionError,
+    treesync::node::leaf_node::{SystemTimeProvider, TimeProvider},
+};
+
+const MESSAGE_ID_LENGTH: usize = 16;
+const FRAGMENT_HEADER_LENGTH: usize = MESSAGE_ID_LENGTH + 2 + 2;
+const DEFAULT_MAX_PENDING_MESSAGES: usize = 64;
+
+/// Fragmentation configuration
+///
+/// Defines how application messages are fragmented and reassembled:
+///  - max_message_size:
+/// The maximum size in bytes of a serialized fragment, i.e., the MTU of the
+/// transport. The default value is 4096.
+///  - reassembly_timeout:
+/// The time after which incomplete messages are dropped. The default value is
+/// 60 seconds.
+///  - max_pending_bytes:
+/// The maximum number of bytes that are buffered for incomplete messages,
+/// including the bookkeeping of every announced fragment. The default value is
+/// 1 MiB.
+///  - max_pending_messages:
+/// The maximum number of incomplete messages. The default value is 64.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FragmentationConfig {
+    max_message_size: usize,
+    reassembly_timeout: Duration,
+    max_pending_bytes: usize,
+    #[serde(default = "default_max_pending_messages")]
+    max_pending_messages: usize,
+}
+
+fn default_max_pending_messages() -> usize {
+    DEFAULT_MAX_PENDING_MESSAGES
+}
+
+impl FragmentationConfig {
+    /// Create a new configuration
+    pub fn new(
+        max_message_size: usize,
+        reassembly_timeout: Duration,
+        max_pending_bytes: usize,
+        max_pending_messages: usize,
+    ) -> Self {
+        Self {
+            max_message_size,
+            reassembly_timeout,
+            max_pending_bytes,
+            max_pending_messages,
+        }
+    }
+
+    /// Get the maximum size of a serialized fragment.
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// Get the time after which incomplete messages are dropped.
+    pub fn reassembly_timeout(&self) -> Duration {
+        self.reassembly_timeout
+    }
+
+    /// Get the maximum number of bytes that are buffered for incomplete
+    /// messages.
+    pub fn max_pending_bytes(&self) -> usize {
+        self.max_pending_bytes
+    }
+
+    /// Get the maximum number of incomplete messages.
+    pub fn max_pending_messages(&self) -> usize {
+        self.max_pending_messages
+    }
+}
+
+impl Default for FragmentationConfig {
+    fn default() -> Self {
+        Self::new(
+            4096,
+            Duration::from_secs(60),
+            1 << 20,
+            DEFAULT_MAX_PENDING_MESSAGES,
+        )
+    }
+}
+
+/// Fragment header
+///
+/// The header is prepended to the authenticated data of every fragment.
+///
+/// ```c
+/// struct {
+///     opaque message_id[16];
+///     uint16 fragment_index;
+///     uint16 fragment_count;
+/// } FragmentHeader;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsSize)]
+pub struct FragmentHeader {
+    message_id: [u8; MESSAGE_ID_LENGTH],
+    fragment_index: u16,
+    fragment_count: u16,
+}
+
+impl FragmentHeader {
+    /// Returns the random identifier of the fragmented message.
+    pub fn message_id(&self) -> &[u8] {
+        &self.message_id
+    }
+
+    /// Returns the index of the fragment.
+    pub fn fragment_index(&self) -> u16 {
+        self.fragment_index
+    }
+
+    /// Returns the total number of fragments of the message.
+    pub fn fragment_count(&self) -> u16 {
+        self.fragment_count
+    }
+}
+
+impl MlsGroup {
+    // === Fragmentation ===
+
+    /// Creates one or more application messages that carry the `message`.
+    /// Every returned [`MlsMessageOut`] is at most as large as the maximum
+    /// message size of the [`FragmentationConfig`] of the group. The messages
+    /// have to be reassembled with a [`FragmentReassembler`] after processing.
+    ///
+    /// The AAD set via [`MlsGroup::set_aad()`] is included in every fragment.
+    ///
+    /// Returns `CreateFragmentedMessageError::MlsGroupStateError::UseAfterEviction`
+    /// if the member is no longer part of the group.
+    /// Returns `CreateFragmentedMessageError::MlsGroupStateError::PendingProposal`
+    /// if pending proposals exist.
+    /// Returns [`CreateFragmentedMessageError::MaxMessageSizeTooSmall`] if not
+    /// even a single byte of payload fits into a fragment.
+    pub fn create_fragmented_message(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        signer: &impl Signer,
+        message: &[u8],
+    ) -> Result<Vec<MlsMessageOut>, CreateFragmentedMessageError> {
+        if !self.is_active() {
+            return Err(CreateFragmentedMessageError::GroupStateError(
+                MlsGroupStateError::UseAfterEviction,
+            ));
+        }
+        if !self.proposal_store.is_empty() {
+            return Err(CreateFragmentedMessageError::GroupStateError(
+                MlsGroupStateError::PendingProposal,
+            ));
+        }
+
+        let fragment_size = self
+            .max_fragment_size(FRAGMENT_HEADER_LENGTH + self.aad.len())
+            .ok_or(CreateFragmentedMessageError::MaxMessageSizeTooSmall)?;
+        let fragments: Vec<&[u8]> = if message.is_empty() {
+            vec![message]
+        } else {
+            message.chunks(fragment_size).collect()
+        };
+        let fragment_count = u16::try_from(fragments.len())
+            .map_err(|_| CreateFragmentedMessageError::TooManyFragments)?;
+        let message_id = backend
+            .rand()
+            .random_array()
+            .map_err(|_| LibraryError::custom("Not enough randomness."))?;
+
+        let padding_policy = self.configuration().padding_policy();
+        let mut messages = Vec::with_capacity(fragments.len());
+        for (fragment_index, fragment) in fragments.into_iter().enumerate() {
+            let header = FragmentHeader {
+                message_id,
+                // The number of fragments was checked above.
+                fragment_index: fragment_index as u16,
+                fragment_count,
+            };
+            let mut aad = header
+                .tls_serialize_detached()
+                .map_err(LibraryError::missing_bound_check)?;
+            aad.extend_from_slice(&self.aad);
+
+            let ciphertext = self
+                .group
+                .create_application_message(&aad, fragment, &padding_policy, backend, signer)
+                .map_err(|e| match e {
+                    MessageEncryptionError::SignatureError(e) => {
+                        CreateFragmentedMessageError::SignatureError(e)
+                    }
+                    // We know the application message is wellformed and we have the key material of the current epoch
+                    _ => LibraryError::custom("Malformed plaintext").into(),
+                })?;
+            messages.push(MlsMessageOut::from_private_message(
+                ciphertext,
+                self.group.version(),
+            ));
+        }
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change();
+
+        Ok(messages)
+    }
+
+    /// Returns the largest payload length for which an application message
+    /// with `authenticated_data_length` bytes of authenticated data fits into
+    /// the maximum message size, or `None` if no payload fits.
+    fn max_fragment_size(&self, authenticated_data_length: usize) -> Option<usize> {
+        let max_message_size = self
+            .configuration()
+            .fragmentation_config()
+            .max_message_size();
+        let fits = |payload_length| {
+            self.application_message_size(payload_length, authenticated_data_length)
+                <= max_message_size
+        };
+        if !fits(1) {
+            return None;
+        }
+
+        // The message size grows monotonically with the payload length.
+        let (mut low, mut high) = (1, max_message_size);
+        while low < high {
+            let middle = low + (high - low + 1) / 2;
+            if fits(middle) {
+                low = middle;
+            } else {
+                high = middle - 1;
+            }
+        }
+        Some(low)
+    }
+}
+
+/// A message that was reassembled by a [`FragmentReassembler`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ReassembledMessage {
+    sender: LeafNodeIndex,
+    credential: Credential,
+    authenticated_data: Vec<u8>,
+    payload: Vec<u8>,
+}
+
+impl ReassembledMessage {
+    /// Returns the leaf index of the sender of the message.
+    pub fn sender(&self) -> LeafNodeIndex {
+        self.sender
+    }
+
+    /// Returns the credential of the sender of the message.
+    pub fn credential(&self) -> &Credential {
+        &self.credential
+    }
+
+    /// Returns the authenticated data of the message without the
+    /// [`FragmentHeader`].
+    pub fn authenticated_data(&self) -> &[u8] {
+        &self.authenticated_data
+    }
+
+    /// Returns the payload of the message.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Consumes the message and returns the payload.
+    pub fn into_payload(self) -> Vec<u8> {
+        self.payload
+    }
+}
+
+/// The fragments of a message that were received so far.
+struct PendingMessage {
+    // The time in seconds since the UNIX epoch at which the first fragment
+    // was received.
+    first_received: u64,
+    credential: Credential,
+    authenticated_data: Vec<u8>,
+    fragments: Vec<Option<Vec<u8>>>,
+    received: usize,
+    size: usize,
+}
+
+/// Reassembles messages created with
+/// [`MlsGroup::create_fragmented_message()`].
+///
+/// Fragments are buffered until all fragments of a message were received.
+/// Messages that are incomplete after the reassembly timeout of the
+/// [`FragmentationConfig`] are dropped. A fragment that would exceed the
+/// maximum number of buffered bytes is rejected, and the respective message is
+/// dropped. Since the number of fragments is announced by the sender, every
+/// fragment of an incomplete message counts towards the buffered bytes, even
+/// if it wasn't received yet. The first fragment of a message is rejected if
+/// the maximum number of incomplete messages is reached.
+pub struct FragmentReassembler {
+    config: FragmentationConfig,
+    time_provider: Arc<dyn TimeProvider>,
+    pending: HashMap<(LeafNodeIndex, [u8; MESSAGE_ID_LENGTH]), PendingMessage>,
+    pending_bytes: usize,
+}
+
+impl FragmentReassembler {
+    /// Create a new reassembler with the given `config` that reads the
+    /// system clock.
+    pub fn new(config: FragmentationConfig) -> Self {
+        Self {
+            config,
+            time_provider: Arc::new(SystemTimeProvider),
+            pending: HashMap::new(),
+            pending_bytes: 0,
+        }
+    }
+
+    /// Replaces the system clock with the given `time_provider`, e.g., the
+    /// one of the [`MlsGroupConfig`]. See [`TimeProvider`] for more
+    /// information.
+    pub fn with_time_provider(mut self, time_provider: impl TimeProvider + 'static) -> Self {
+        self.time_provider = Arc::new(time_provider);
+        self
+    }
+
+    /// Returns the number of incomplete messages.
+    pub fn pending_messages(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns the number of bytes buffered for incomplete messages.
+    pub fn pending_bytes(&self) -> usize {
+        self.pending_bytes
+    }
+
+    /// Processes a fragment that was received as part of the given
+    /// `processed_message`. Returns the reassembled message if this was the
+    /// last missing fragment and `None` otherwise.
+    ///
+    /// Returns an error if the message is not an application message or does
+    /// not carry a valid [`FragmentHeader`], or if the fragment can't be
+    /// buffered.
+    pub fn process(
+        &mut self,
+        processed_message: ProcessedMessage,
+    ) -> Result<Option<ReassembledMessage>, ReassemblyError> {
+        self.remove_expired();
+
+        let sender = match processed_message.sender() {
+            Sender::Member(leaf_index) => *leaf_index,
+            _ => return Err(ReassemblyError::NotAnApplicationMessage),
+        };
+        let credential = processed_message.credential().clone();
+        let aad = processed_message.authenticated_data().to_vec();
+        let fragment = match processed_message.into_content() {
+            ProcessedMessageContent::ApplicationMessage(message) => message.into_bytes(),
+            _ => return Err(ReassemblyError::NotAnApplicationMessage),
+        };
+
+        let mut aad_slice = aad.as_slice();
+        let header = FragmentHeader::tls_deserialize(&mut aad_slice)
+            .map_err(|_| ReassemblyError::MalformedFragmentHeader)?;
+        let authenticated_data = aad_slice.to_vec();
+        if header.fragment_index >= header.fragment_count {
+            return Err(ReassemblyError::MalformedFragmentHeader);
+        }
+
+        // Messages that consist of a single fragment don't need to be buffered.
+        if header.fragment_count == 1 {
+            return Ok(Some(ReassembledMessage {
+                sender,
+                credential,
+                authenticated_data,
+                payload: fragment,
+            }));
+        }
+
+        let key = (sender, header.message_id);
+        // The slots of all announced fragments of a new message count
+        // towards the buffered bytes before they are allocated.
+        let slots_size = if self.pending.contains_key(&key) {
+            0
+        } else {
+            if self.pending.len() >= self.config.max_pending_messages {
+                return Err(ReassemblyError::TooManyPendingMessages);
+            }
+            header.fragment_count as usize * mem::size_of::<Option<Vec<u8>>>()
+        };
+        if self.pending_bytes + slots_size + fragment.len() > self.config.max_pending_bytes {
+            self.remove(&key);
+            return Err(ReassemblyError::MemoryLimitExceeded);
+        }
+
+        let time_provider = &self.time_provider;
+        let pending = self.pending.entry(key).or_insert_with(|| PendingMessage {
+            first_received: time_provider.unix_now().unwrap_or_default(),
+            credential: credential.clone(),
+            authenticated_data: authenticated_data.clone(),
+            fragments: vec![None; header.fragment_count as usize],
+            received: 0,
+            size: slots_size,
+        });
+        self.pending_bytes += slots_size;
+        if pending.fragments.len() != header.fragment_count as usize
+            || pending.credential != credential
+            || pending.authenticated_data != authenticated_data
+        {
+            self.remove(&key);
+            return Err(ReassemblyError::InconsistentFragment);
+        }
+        let slot = &mut pending.fragments[header.fragment_index as usize];
+        if slot.is_some() {
+            return Err(ReassemblyError::DuplicateFragment);
+        }
+        pending.size += fragment.len();
+        pending.received += 1;
+        self.pending_bytes += fragment.len();
+        *slot = Some(fragment);
+
+        if pending.received < pending.fragments.len() {
+            return Ok(None);
+        }
+
+        Ok(self.remove(&key).map(|pending| ReassembledMessage {
+            sender,
+            credential: pending.credential,
+            authenticated_data: pending.authenticated_data,
+            payload: pending.fragments.into_iter().flatten().flatten().collect(),
+        }))
+    }
+
+    /// Removes the incomplete message with the given `key` and releases its
+    /// buffered bytes.
+    fn remove(&mut self, key: &(LeafNodeIndex, [u8; MESSAGE_ID_LENGTH])) -> Option<PendingMessage> {
+        let pending = self.pending.remove(key)?;
+        self.pending_bytes -= pending.size;
+        Some(pending)
+    }
+
+    /// Removes all incomplete messages whose reassembly timeout elapsed.
+    /// Nothing is removed if the time provider doesn't know the time.
+    fn remove_expired(&mut self) {
+        let Some(now) = self.time_provider.unix_now() else {
+            return;
+        };
+        let timeout = self.config.reassembly_timeout.as_secs();
+        let pending_bytes = &mut self.pending_bytes;
+        self.pending.retain(|_, pending| {
+            let expired = now.saturating_sub(pending.first_received) >= timeout;
+            if expired {
+                *pending_bytes -= pending.size;
+            }
+            !expired
+        });
+    }
+}
@@ -0,0 +1,124 @@
+//! # GroupInfo export
+//!
+//! [`MlsGroup::export_group_info()`] only allows to choose whether the ratchet
+//! tree is included in the exported GroupInfo. Deployments that bootstrap
+//! external joins often need to distribute additional metadata that is signed
+//! by a group member. [`MlsGroup::export_group_info_with_options()`] takes a
+//! [`GroupInfoExportOptions`] that selects the standard extensions and adds
+//! custom extensions to the GroupInfo.
+//!
+//! Custom extensions must be registered in the
+//! [`CustomExtensionRegistry`](crate::extensions::CustomExtensionRegistry) of
+//! the group's [`MlsGroupConfig`].
+
+use openmls_traits::signatures::Signer;
+
+use crate::extensions::{Extension, ExtensionType, Extensions};
+
+use super::*;
+
+/// Options for exporting a GroupInfo with
+/// [`MlsGroup::export_group_info_with_options()`].
+///
+/// By default, the GroupInfo contains the external public key, but neither the
+/// ratchet tree nor any custom extensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupInfoExportOptions {
+    with_ratchet_tree: bool,
+    with_external_pub: bool,
+    extensions: Extensions,
+}
+
+impl Default for GroupInfoExportOptions {
+    fn default() -> Self {
+        Self {
+            with_ratchet_tree: false,
+            with_external_pub: true,
+            extensions: Extensions::empty(),
+        }
+    }
+}
+
+impl GroupInfoExportOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the ratchet tree extension is included.
+    pub fn with_ratchet_tree(mut self, with_ratchet_tree: bool) -> Self {
+        self.with_ratchet_tree = with_ratchet_tree;
+        self
+    }
+
+    /// Sets whether the external public key extension is included. Without
+    /// it, the GroupInfo can't be used to join the group via an external
+    /// commit.
+    pub fn with_external_pub(mut self, with_external_pub: bool) -> Self {
+        self.with_external_pub = with_external_pub;
+        self
+    }
+
+    /// Adds the custom `extension` to the GroupInfo. An extension of the same
+    /// type that was added before is replaced.
+    pub fn with_extension(mut self, extension: Extension) -> Self {
+        self.extensions.add_or_replace(extension);
+        self
+    }
+
+    /// Returns `true` if the ratchet tree extension is included.
+    pub fn ratchet_tree(&self) -> bool {
+        self.with_ratchet_tree
+    }
+
+    /// Returns `true` if the external public key extension is included.
+    pub fn external_pub(&self) -> bool {
+        self.with_external_pub
+    }
+
+    /// Returns the custom extensions.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+}
+
+impl MlsGroup {
+    /// Export a group info object for this group with the given `options`.
+    ///
+    /// Returns [`ExportGroupInfoError::UnsupportedExtensionType`] if one of the
+    /// custom extensions is not of a type registered in the
+    /// [`CustomExtensionRegistry`](crate::extensions::CustomExtensionRegistry)
+    /// of the group's [`MlsGroupConfig`], and
+    /// [`ExportGroupInfoError::InvalidExtension`] if it can't be decoded as the
+    /// registered type.
+    pub fn export_group_info_with_options(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        signer: &impl Signer,
+        options: &GroupInfoExportOptions,
+    ) -> Result<MlsMessageOut, ExportGroupInfoError> {
+        let registry = self.configuration().custom_extensions();
+        for extension in options.extensions.iter() {
+            let extension_type = extension.extension_type();
+            if !matches!(extension_type, ExtensionType::Unknown(_))
+                || !registry.is_registered(extension_type)
+            {
+                return Err(ExportGroupInfoError::UnsupportedExtensionType(
+                    extension_type,
+                ));
+            }
+        }
+        registry.validate(&options.extensions)?;
+
+        Ok(self
+            .group
+            .export_group_info_with_extensions(
+                backend,
+                signer,
+                options.with_ratchet_tree,
+                options.with_external_pub,
+                options.extensions.clone(),
+            )?
+            .into())
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! This module contains membership-related operations and exposes [`RemoveOperation`].
 
+use std::collections::HashSet;
+
 use core_group::create_commit_params::CreateCommitParams;
 use openmls_traits::signatures::Signer;
 
@@ -10,7 +12,9 @@ use super::{
     *,
 };
 use crate::{
-    binary_tree::array_representation::LeafNodeIndex, messages::group_info::GroupInfo,
+    binary_tree::array_representation::LeafNodeIndex,
+    group::errors::{CreateRemoveProposalError, ProposalValidationError},
+    messages::group_info::GroupInfo,
     treesync::LeafNode,
 };
 
@@ -24,7 +28,8 @@ impl MlsGroup {
     ///
     /// If successful, it returns a triple of [`MlsMessageOut`]s, where the first
     /// contains the commit, the second one the [Welcome] and the third an optional [GroupInfo] that
-    /// will be [Some] if the group has the `use_ratchet_tree_extension` flag set.
+    /// will be [Some] if the group has the `use_ratchet_tree_extension` or the
+    /// `always_return_group_info` flag set.
     ///
     /// Returns an error if there is a pending commit.
     // FIXME: #1217
@@ -42,6 +47,44 @@ impl MlsGroup {
             return Err(AddMembersError::EmptyInput(EmptyInputError::AddMembers));
         }
 
+        // Let the application validate the credentials of the new members
+        if self.configuration().credential_validator_missing() {
+            return Err(AddMembersError::MissingCredentialValidator);
+        }
+        if !key_packages.iter().all(|key_package| {
+            self.configuration()
+                .validate_credential(key_package.leaf_node().credential())
+        }) {
+            return Err(AddMembersError::InvalidCredential);
+        }
+
+        // Reject new members whose identity is already in the group if
+        // configured, taking the pending proposals into account
+        if self.configuration().unique_identities() {
+            let pending_proposals = || {
+                self.proposal_store
+                    .proposals()
+                    .map(|queued_proposal| queued_proposal.proposal())
+            };
+            let removed: Vec<LeafNodeIndex> = pending_proposals()
+                .filter_map(|proposal| match proposal {
+                    Proposal::Remove(remove_proposal) => Some(remove_proposal.removed()),
+                    _ => None,
+                })
+                .collect();
+            let pending_credentials = pending_proposals().filter_map(|proposal| match proposal {
+                Proposal::Add(add_proposal) => {
+                    Some(add_proposal.key_package().leaf_node().credential())
+                }
+                _ => None,
+            });
+            let new_credentials = key_packages
+                .iter()
+                .map(|key_package| key_package.leaf_node().credential());
+            self.check_unique_identities(pending_credentials.chain(new_credentials), &removed)
+                .map_err(|e| AddMembersError::CreateCommitError(e.into()))?;
+        }
+
         // Create inline add proposals from key packages
         let inline_proposals = key_packages
             .iter()
@@ -55,9 +98,10 @@ impl MlsGroup {
         // Create Commit over all proposals
         // TODO #751
         let params = CreateCommitParams::builder()
-            .framing_parameters(self.framing_parameters())
+            .framing_parameters(self.framing_parameters(ContentType::Commit))
             .proposal_store(&self.proposal_store)
             .inline_proposals(inline_proposals)
+            .always_create_group_info(self.configuration().always_return_group_info())
             .build();
         let create_commit_result = self.group.create_commit(params, backend, signer)?;
 
@@ -102,7 +146,8 @@ impl MlsGroup {
     /// [GroupInfo].
     /// The [Welcome] is [Some] when the queue of pending proposals contained
     /// add proposals
-    /// The [GroupInfo] is [Some] if the group has the `use_ratchet_tree_extension` flag set.
+    /// The [GroupInfo] is [Some] if the group has the `use_ratchet_tree_extension` or the
+    /// `always_return_group_info` flag set.
 
     ///
     /// Returns an error if there is a pending commit.
@@ -134,9 +179,10 @@ impl MlsGroup {
         // Create Commit over all proposals
         // TODO #751
         let params = CreateCommitParams::builder()
-            .framing_parameters(self.framing_parameters())
+            .framing_parameters(self.framing_parameters(ContentType::Commit))
             .proposal_store(&self.proposal_store)
             .inline_proposals(inline_proposals)
+            .always_create_group_info(self.configuration().always_return_group_info())
             .build();
         let create_commit_result = self.group.create_commit(params, backend, signer)?;
 
@@ -178,8 +224,17 @@ impl MlsGroup {
         let removed = self.group.own_leaf_index();
         let remove_proposal = self
             .group
-            .create_remove_proposal(self.framing_parameters(), removed, signer)
-            .map_err(|_| LibraryError::custom("Creating a self removal should not fail"))?;
+            .create_remove_proposal(
+                self.framing_parameters(ContentType::Proposal),
+                removed,
+                signer,
+            )
+            .map_err(|e| match e {
+                CreateRemoveProposalError::UnknownMember => {
+                    LibraryError::custom("Creating a self removal should not fail").into()
+                }
+                CreateRemoveProposalError::SignatureError(e) => LeaveGroupError::SignatureError(e),
+            })?;
 
         self.proposal_store
             .add(QueuedProposal::from_authenticated_content_by_ref(
@@ -196,6 +251,18 @@ impl MlsGroup {
         self.group.public_group().members()
     }
 
+    /// Returns a list of [`Member`]s whose credential is revoked or expired
+    /// according to the configured
+    /// [`CredentialValidator`](crate::credentials::CredentialValidator).
+    ///
+    /// This can be used to remove compromised members from the group.
+    pub fn revoked_members(&self) -> impl Iterator<Item = Member> + '_ {
+        self.members().filter(|member| {
+            self.configuration()
+                .is_credential_revoked(&member.credential)
+        })
+    }
+
     /// Returns the [`Credential`] of a member corresponding to the given
     /// leaf index. Returns `None` if the member can not be found in this group.
     pub fn member(&self, leaf_index: LeafNodeIndex) -> Option<&Credential> {
@@ -205,6 +272,75 @@ impl MlsGroup {
             .leaf(leaf_index)
             .map(|leaf| leaf.credential())
     }
+
+    /// Returns the [`Member`]s of the group in the given `epoch`.
+    ///
+    /// This allows attributing messages from past epochs, e.g., late-arriving
+    /// application messages, to the members at the time they were sent.
+    /// Returns `None` if `epoch` is neither the current epoch nor one of the
+    /// past epochs whose secrets are kept according to
+    /// [`MlsGroupConfig::max_past_epochs`](crate::group::MlsGroupConfig::max_past_epochs).
+    pub fn members_at_epoch(&self, epoch: GroupEpoch) -> Option<Vec<Member>> {
+        self.group.members_at_epoch(epoch)
+    }
+
+    /// Returns the [`Member`] at the given leaf index in the given `epoch`.
+    ///
+    /// Returns `None` if the leaf was blank in that epoch or if the members of
+    /// the epoch are not known, see [`MlsGroup::members_at_epoch()`].
+    pub fn member_at_epoch(&self, epoch: GroupEpoch, leaf_index: LeafNodeIndex) -> Option<Member> {
+        self.members_at_epoch(epoch)?
+            .into_iter()
+            .find(|member| member.index == leaf_index)
+    }
+
+    /// Returns all members whose credential has the given identity in
+    /// ascending order of their leaf indices, e.g., the devices of a client.
+    pub fn members_by_identity<'a>(
+        &'a self,
+        identity: &'a [u8],
+    ) -> impl Iterator<Item = Member> + 'a {
+        self.group.public_group().members_by_identity(identity)
+    }
+
+    /// Returns the member with the given identity. If the client is a member
+    /// multiple times, the member with the lowest leaf index is returned.
+    ///
+    /// Returns `None` if there is no member with the given identity.
+    pub fn member_by_identity(&self, identity: &[u8]) -> Option<Member> {
+        self.group.public_group().member_by_identity(identity)
+    }
+
+    /// Returns the leaf index of the member with the given signature key or
+    /// `None` if there is no such member.
+    pub fn leaf_index_of(&self, signature_key: &[u8]) -> Option<LeafNodeIndex> {
+        self.group.public_group().leaf_index_of(signature_key)
+    }
+
+    /// Checks that the `credentials` of new members have pairwise distinct
+    /// identities that are not yet in the group, ignoring the members in
+    /// `removed`, as required by
+    /// [`MlsGroupConfig::unique_identities()`].
+    pub(crate) fn check_unique_identities<'a>(
+        &self,
+        credentials: impl IntoIterator<Item = &'a Credential>,
+        removed: &[LeafNodeIndex],
+    ) -> Result<(), ProposalValidationError> {
+        let mut identities = HashSet::new();
+        for credential in credentials {
+            let identity = credential.identity();
+            if !identities.insert(identity) {
+                return Err(ProposalValidationError::DuplicateIdentityAddProposal);
+            }
+            if self
+                .members_by_identity(identity)
+                .any(|member| !removed.contains(&member.index))
+            {
+                return Err(ProposalValidationError::ExistingIdentityAddProposal);
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Helper `enum` that classifies the kind of remove operation. This can be used to
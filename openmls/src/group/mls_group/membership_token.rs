@@ -0,0 +1,244 @@
+//! # Membership tokens
+//!
+//! Applications often need to prove membership in a group to an adjacent
+//! service, e.g., to join the media session of a call through an SFU. A
+//! [`MembershipToken`] contains an HMAC over a context chosen by the
+//! application, e.g., the ID of the session, keyed with a secret that is
+//! exported for the member's leaf from the exporter secret of the epoch. The
+//! token is signed with the signature key of the member's leaf. It is created
+//! with [`MlsGroup::membership_token()`] and checked by any other member of
+//! the epoch with [`MlsGroup::verify_membership_token()`].
+//!
+//! Every member of an epoch can compute the HMACs of all other members of the
+//! epoch, so the HMAC only proves that the creator of a token was a member of
+//! the group in that epoch. The signature proves which member created it.
+
+use openmls_traits::{signatures::Signer, OpenMlsCryptoProvider};
+use serde::{Deserialize, Serialize};
+use tls_codec::{Serialize as TlsSerializeTrait, TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+
+use super::{
+    errors::{CreateMembershipTokenError, VerifyMembershipTokenError},
+    *,
+};
+use crate::ciphersuite::{
+    signable::{signature_labels, Signable, SignedStruct, Verifiable},
+    Mac, OpenMlsSignaturePublicKey, Secret, Signature, SignaturePublicKey,
+};
+
+const MEMBERSHIP_TOKEN_LABEL: &str = "MLS membership token";
+const SIGNATURE_MEMBERSHIP_TOKEN_LABEL: &str = signature_labels::MEMBERSHIP_TOKEN;
+
+/// A token that proves membership in a group in a specific epoch.
+///
+/// ```c
+/// struct {
+///     uint64 epoch;
+///     uint32 leaf_index;
+///     opaque mac<V>;
+///     /* SignWithLabel(., "MembershipTokenTBS", MembershipTokenTBS) */
+///     opaque signature<V>;
+/// } MembershipToken;
+/// ```
+#[derive(
+    Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct MembershipToken {
+    epoch: GroupEpoch,
+    leaf_index: LeafNodeIndex,
+    mac: VLBytes,
+    signature: Signature,
+}
+
+/// Membership token (To Be Signed)
+///
+/// ```c
+/// struct {
+///     opaque group_id<V>;
+///     uint64 epoch;
+///     uint32 leaf_index;
+///     opaque mac<V>;
+/// } MembershipTokenTBS;
+/// ```
+#[derive(Debug, TlsSerialize, TlsSize)]
+struct MembershipTokenTbs {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    leaf_index: LeafNodeIndex,
+    mac: VLBytes,
+}
+
+/// A [`MembershipTokenTbs`] and its signature that has not been verified yet.
+struct VerifiableMembershipToken {
+    tbs: MembershipTokenTbs,
+    signature: Signature,
+}
+
+impl MembershipToken {
+    /// Returns the epoch in which the token was created.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the leaf index of the member that created the token.
+    pub fn leaf_index(&self) -> LeafNodeIndex {
+        self.leaf_index
+    }
+
+    /// Returns the HMAC over the context of the token.
+    pub fn mac(&self) -> &[u8] {
+        self.mac.as_slice()
+    }
+}
+
+impl MlsGroup {
+    // === Membership tokens ===
+
+    /// Creates a [`MembershipToken`] for the own leaf in the current epoch
+    /// over the application-defined `context`, signed with the `signer` of
+    /// the own leaf.
+    ///
+    /// Returns [`CreateMembershipTokenError::GroupStateError(MlsGroupStateError::UseAfterEviction)`](MlsGroupStateError::UseAfterEviction)
+    /// if the group is not active.
+    pub fn membership_token(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        signer: &impl Signer,
+        context: &[u8],
+    ) -> Result<MembershipToken, CreateMembershipTokenError> {
+        let epoch = self.epoch();
+        let leaf_index = self.own_leaf_index();
+        let mac = self
+            .membership_token_mac(backend, epoch, leaf_index, context)
+            .map_err(|e| match e {
+                ExportSecretError::LibraryError(e) => e.into(),
+                ExportSecretError::KeyLengthTooLong | ExportSecretError::UnknownEpoch => {
+                    LibraryError::custom("The current epoch has a valid exporter").into()
+                }
+                ExportSecretError::GroupStateError(e) => e.into(),
+            })?;
+        let tbs = MembershipTokenTbs {
+            group_id: self.group_id().clone(),
+            epoch,
+            leaf_index,
+            mac: mac.mac_value,
+        };
+        Ok(tbs.sign(signer)?)
+    }
+
+    /// Verifies that `token` was created over `context` by a member of the
+    /// epoch of the token and signed by the member at the leaf index of the
+    /// token, and returns that member.
+    ///
+    /// The epoch of the token must be the current epoch or one of the past
+    /// epochs whose secrets are kept according to
+    /// [`MlsGroupConfig::max_past_epochs`](crate::group::MlsGroupConfig::max_past_epochs).
+    /// Otherwise, [`VerifyMembershipTokenError::UnknownEpoch`] is returned.
+    pub fn verify_membership_token(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        token: &MembershipToken,
+        context: &[u8],
+    ) -> Result<Member, VerifyMembershipTokenError> {
+        let mac = self
+            .membership_token_mac(backend, token.epoch, token.leaf_index, context)
+            .map_err(|e| match e {
+                ExportSecretError::LibraryError(e) => e.into(),
+                ExportSecretError::KeyLengthTooLong => {
+                    LibraryError::custom("The hash length is a valid key length").into()
+                }
+                ExportSecretError::UnknownEpoch => VerifyMembershipTokenError::UnknownEpoch,
+                ExportSecretError::GroupStateError(e) => e.into(),
+            })?;
+        let member = self
+            .member_at_epoch(token.epoch, token.leaf_index)
+            .ok_or(VerifyMembershipTokenError::UnknownMember)?;
+
+        // `Mac` is compared in constant time.
+        let token_mac = Mac {
+            mac_value: token.mac.clone(),
+        };
+        if mac != token_mac {
+            return Err(VerifyMembershipTokenError::InvalidToken);
+        }
+
+        // Every member of the epoch can compute the HMAC, but only the
+        // member at the leaf index can sign the token.
+        let signature_key = OpenMlsSignaturePublicKey::from_signature_key(
+            SignaturePublicKey::from(member.signature_key.as_slice()),
+            self.ciphersuite().signature_algorithm(),
+        );
+        VerifiableMembershipToken {
+            tbs: MembershipTokenTbs {
+                group_id: self.group_id().clone(),
+                epoch: token.epoch,
+                leaf_index: token.leaf_index,
+                mac: token.mac.clone(),
+            },
+            signature: token.signature.clone(),
+        }
+        .verify_no_out(backend.crypto(), &signature_key)
+        .map_err(|_| VerifyMembershipTokenError::InvalidSignature)?;
+
+        Ok(member)
+    }
+
+    /// Computes the HMAC of the membership token of the member at
+    /// `leaf_index` in `epoch` over `context`.
+    fn membership_token_mac(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        epoch: GroupEpoch,
+        leaf_index: LeafNodeIndex,
+        context: &[u8],
+    ) -> Result<Mac, ExportSecretError> {
+        let ciphersuite = self.ciphersuite();
+        let key = self.export_secret_for_epoch(
+            backend,
+            epoch,
+            MEMBERSHIP_TOKEN_LABEL,
+            &leaf_index.u32().to_be_bytes(),
+            ciphersuite.hash_length(),
+        )?;
+        let key = Secret::from_slice(&key, self.group.version(), ciphersuite);
+        Mac::new(backend, &key, context)
+            .map_err(|_| LibraryError::custom("Computing the HMAC failed").into())
+    }
+}
+
+impl Signable for MembershipTokenTbs {
+    type SignedOutput = MembershipToken;
+
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.tls_serialize_detached()
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_MEMBERSHIP_TOKEN_LABEL
+    }
+}
+
+impl SignedStruct<MembershipTokenTbs> for MembershipToken {
+    fn from_payload(payload: MembershipTokenTbs, signature: Signature) -> Self {
+        Self {
+            epoch: payload.epoch,
+            leaf_index: payload.leaf_index,
+            mac: payload.mac,
+            signature,
+        }
+    }
+}
+
+impl Verifiable for VerifiableMembershipToken {
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.tbs.tls_serialize_detached()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_MEMBERSHIP_TOKEN_LABEL
+    }
+}
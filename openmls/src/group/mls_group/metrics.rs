@@ -0,0 +1,223 @@
+//! # Group metrics
+//!
+//! [`MlsGroup::metrics()`] returns [`GroupMetrics`], a snapshot of figures
+//! that allow operators to monitor the health of a group, e.g., to detect
+//! trees that degrade because of blank nodes, proposals that are never
+//! committed or members whose ratchets never advance.
+//!
+//! [`MlsGroup::message_secrets_usage()`] returns [`MessageSecretsUsage`],
+//! the estimated memory used by the message secrets of the group, e.g., to
+//! tune the `message_secrets_budget` of the [`MlsGroupConfig`] on
+//! constrained devices.
+
+use super::*;
+
+/// A snapshot of metrics of an [`MlsGroup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupMetrics {
+    epoch: GroupEpoch,
+    member_count: usize,
+    leaf_count: u32,
+    node_count: u32,
+    blank_node_count: usize,
+    pending_proposal_count: usize,
+    retained_past_epochs: usize,
+    sender_ratchets: Vec<SenderSecretsReport>,
+    serialized_state_size: usize,
+    message_secrets_usage: MessageSecretsUsage,
+}
+
+impl GroupMetrics {
+    /// Returns the current epoch of the group.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the number of members of the group.
+    pub fn member_count(&self) -> usize {
+        self.member_count
+    }
+
+    /// Returns the number of leaves of the ratchet tree, including blank
+    /// leaves.
+    pub fn leaf_count(&self) -> u32 {
+        self.leaf_count
+    }
+
+    /// Returns the depth of the ratchet tree, i.e., the number of parent
+    /// nodes on the direct path of a leaf.
+    pub fn tree_depth(&self) -> u32 {
+        self.leaf_count.next_power_of_two().trailing_zeros()
+    }
+
+    /// Returns the number of nodes of the ratchet tree, including blank
+    /// nodes.
+    pub fn node_count(&self) -> u32 {
+        self.node_count
+    }
+
+    /// Returns the number of blank nodes of the ratchet tree.
+    pub fn blank_node_count(&self) -> usize {
+        self.blank_node_count
+    }
+
+    /// Returns the ratio of blank nodes to all nodes of the ratchet tree,
+    /// between `0.0` and `1.0`. A high ratio increases the size of commits
+    /// and the cost of encrypting path secrets.
+    pub fn blank_ratio(&self) -> f64 {
+        if self.node_count == 0 {
+            return 0.0;
+        }
+        self.blank_node_count as f64 / self.node_count as f64
+    }
+
+    /// Returns the number of pending proposals of the group.
+    pub fn pending_proposal_count(&self) -> usize {
+        self.pending_proposal_count
+    }
+
+    /// Returns the number of past epochs for which message secrets are
+    /// retained.
+    pub fn retained_past_epochs(&self) -> usize {
+        self.retained_past_epochs
+    }
+
+    /// Returns the ratchets of all senders that sent a message in the current
+    /// epoch, in ascending order of their leaf index. Members without an entry
+    /// did not send a message in the current epoch.
+    pub fn sender_ratchets(&self) -> &[SenderSecretsReport] {
+        &self.sender_ratchets
+    }
+
+    /// Returns the size in bytes of the serialized state of the group, as
+    /// written by [`MlsGroup::save()`].
+    pub fn serialized_state_size(&self) -> usize {
+        self.serialized_state_size
+    }
+
+    /// Returns the estimated memory used by the message secrets of the group.
+    pub fn message_secrets_usage(&self) -> &MessageSecretsUsage {
+        &self.message_secrets_usage
+    }
+}
+
+/// The estimated memory used by the message secrets of an [`MlsGroup`].
+///
+/// Sizes are estimated from the length of the key material and don't include
+/// the overhead of the data structures that hold it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageSecretsUsage {
+    budget: Option<usize>,
+    current_epoch_size: usize,
+    past_epochs_size: usize,
+    retained_past_epochs: usize,
+    retained_keys: usize,
+}
+
+impl MessageSecretsUsage {
+    /// Returns the configured budget in bytes, if any.
+    pub fn budget(&self) -> Option<usize> {
+        self.budget
+    }
+
+    /// Returns the estimated size in bytes of the message secrets of the
+    /// current and all past epochs.
+    pub fn size(&self) -> usize {
+        self.current_epoch_size + self.past_epochs_size
+    }
+
+    /// Returns the estimated size in bytes of the message secrets of the
+    /// current epoch.
+    pub fn current_epoch_size(&self) -> usize {
+        self.current_epoch_size
+    }
+
+    /// Returns the estimated size in bytes of the message secrets of all
+    /// past epochs.
+    pub fn past_epochs_size(&self) -> usize {
+        self.past_epochs_size
+    }
+
+    /// Returns the number of past epochs for which message secrets are
+    /// retained.
+    pub fn retained_past_epochs(&self) -> usize {
+        self.retained_past_epochs
+    }
+
+    /// Returns the number of keys of skipped generations that are retained
+    /// across all epochs and senders.
+    pub fn retained_keys(&self) -> usize {
+        self.retained_keys
+    }
+
+    /// Returns `true` if the message secrets exceed the configured budget.
+    /// This is only possible if the message secrets of the current epoch
+    /// alone exceed the budget.
+    pub fn exceeds_budget(&self) -> bool {
+        self.budget.map_or(false, |budget| self.size() > budget)
+    }
+}
+
+impl MlsGroup {
+    // === Metrics ===
+
+    /// Returns the [`GroupMetrics`] of the group.
+    ///
+    /// Note that this function serializes the state of the group to determine
+    /// its size. The serialized state is only counted and never buffered.
+    ///
+    /// Returns a [`LibraryError`] if the group state can't be serialized.
+    pub fn metrics(&self) -> Result<GroupMetrics, LibraryError> {
+        let view = self.ratchet_tree_view();
+        let forward_secrecy_report = self.forward_secrecy_report();
+        let (current_epoch, past_epochs) = forward_secrecy_report
+            .epochs()
+            .split_last()
+            .ok_or_else(|| LibraryError::custom("No secrets for the current epoch"))?;
+        let mut byte_counter = ByteCounter::default();
+        serde_json::to_writer_pretty(&mut byte_counter, self)
+            .map_err(|_| LibraryError::custom("Could not serialize the group state"))?;
+        let serialized_state_size = byte_counter.0;
+
+        Ok(GroupMetrics {
+            epoch: self.epoch(),
+            member_count: self.members().count(),
+            leaf_count: view.leaf_count(),
+            node_count: view.node_count(),
+            blank_node_count: view.blank_node_count(),
+            pending_proposal_count: self.proposal_store.proposals().count(),
+            retained_past_epochs: past_epochs.len(),
+            sender_ratchets: current_epoch.senders().to_vec(),
+            serialized_state_size,
+            message_secrets_usage: self.message_secrets_usage(),
+        })
+    }
+
+    /// Returns the [`MessageSecretsUsage`] of the group.
+    pub fn message_secrets_usage(&self) -> MessageSecretsUsage {
+        let (current_epoch_size, past_epochs_size, retained_keys) =
+            self.group.message_secrets_usage();
+        MessageSecretsUsage {
+            budget: self.configuration().message_secrets_budget(),
+            current_epoch_size,
+            past_epochs_size,
+            retained_past_epochs: self.group.past_epochs().count(),
+            retained_keys,
+        }
+    }
+}
+
+/// A writer that discards the written bytes and only counts them.
+#[derive(Default)]
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -13,28 +13,60 @@ use crate::{
     key_packages::{KeyPackage, KeyPackageBundle},
     messages::{proposals::*, Welcome},
     schedule::ResumptionPskSecret,
-    treesync::{node::leaf_node::LeafNode, RatchetTree},
+    tree::sender_ratchet::SenderRatchetConfiguration,
+    treesync::{
+        node::leaf_node::{LeafNode, LeafNodeValidationContext},
+        RatchetTree, RatchetTreeView,
+    },
 };
 use openmls_traits::{key_store::OpenMlsKeyStore, types::Ciphersuite, OpenMlsCryptoProvider};
-use std::io::{Error, Read, Write};
+use std::{
+    collections::BTreeSet,
+    io::{Error, Read, Write},
+};
 
 // Private
 mod application;
 mod creation;
+mod deferred_merge;
 mod exporting;
+mod roles;
+mod safe_extensions;
+mod size_estimation;
 mod updates;
 
+use audit_log::*;
 use config::*;
+use epoch_history::*;
 use errors::*;
 use ser::*;
 
+// Public
+pub mod pinning;
+
+use pinning::*;
+
 // Crate
+pub(crate) mod audit_log;
 pub(crate) mod config;
+pub(crate) mod config_update;
+pub(crate) mod desync;
+pub(crate) mod device_transfer;
+pub(crate) mod epoch_history;
 pub(crate) mod errors;
+pub(crate) mod forward_secrecy;
+pub(crate) mod fragmentation;
+pub(crate) mod group_info_export;
 pub(crate) mod membership;
+pub(crate) mod membership_token;
+pub(crate) mod metrics;
 pub(crate) mod processing;
 pub(crate) mod proposal;
+pub(crate) mod providers;
+pub(crate) mod ratchet_persistence;
 pub(crate) mod ser;
+pub(crate) mod sframe;
+pub(crate) mod streaming;
 
 // Tests
 #[cfg(test)]
@@ -148,6 +180,17 @@ pub enum MlsGroupState {
 /// An `MlsGroup` has an internal state variable determining if it is active or
 /// inactive, as well as if it has a pending commit. See [`MlsGroupState`] for
 /// more information.
+///
+/// ## Thread safety
+///
+/// An `MlsGroup` is `Send + Sync` and does not use any interior mutability.
+/// All functions that change the group state (e.g. processing messages,
+/// creating commits or application messages) take `&mut self`, while read-only
+/// accessors take `&self`. An `MlsGroup` can therefore be shared between threads
+/// behind a lock (e.g. a `Mutex` or `RwLock`, or their async counterparts).
+/// Since every operation on the group advances or reads a single consistent
+/// state, callers should hold the lock for the whole duration of an
+/// operation, including the subsequent merge of a pending or staged commit.
 #[derive(Debug)]
 pub struct MlsGroup {
     // The group configuration. See `MlsGroupCongig` for more information.
@@ -168,10 +211,25 @@ pub struct MlsGroup {
     // A variable that indicates the state of the group. See [`MlsGroupState`]
     // for more information.
     group_state: MlsGroupState,
+    // The signature keys pinned to the identities of the members. See
+    // [`SignatureKeyPins`] for more information.
+    signature_key_pins: SignatureKeyPins,
     // A flag that indicates if the group state has changed and needs to be persisted again. The value
     // is set to `InnerState::Changed` whenever an the internal group state is change and is set to
     // `InnerState::Persisted` once the state has been persisted.
     state_changed: InnerState,
+    // The epochs and senders whose sender ratchets advanced since the state
+    // was persisted. See [`SenderRatchetState`] for more information.
+    updated_sender_ratchets: BTreeSet<(GroupEpoch, LeafNodeIndex)>,
+    // Configuration changes that take effect when the next commit is merged.
+    // See [`PendingConfigUpdate`] for more information.
+    pending_config_update: Option<PendingConfigUpdate>,
+    // The local times at which the group entered its most recent epochs. See
+    // [`EpochHistory`] for more information.
+    epoch_history: EpochHistory,
+    // The entries of the commit audit log. See [`CommitAuditEntry`] for more
+    // information.
+    commit_audit_log: Vec<CommitAuditEntry>,
 }
 
 impl MlsGroup {
@@ -185,6 +243,7 @@ impl MlsGroup {
     /// Sets the configuration.
     pub fn set_configuration(&mut self, mls_group_config: &MlsGroupConfig) {
         self.mls_group_config = mls_group_config.clone();
+        self.enforce_message_secrets_budget();
 
         // Since the state of the group might be changed, arm the state flag
         self.flag_state_change();
@@ -196,6 +255,10 @@ impl MlsGroup {
     }
 
     /// Sets the AAD used in the framing.
+    ///
+    /// The AAD is used for all subsequent messages of the group until it is
+    /// changed again. Use [`MlsGroup::create_message_with_aad()`] to set the
+    /// authenticated data of a single application message.
     pub fn set_aad(&mut self, aad: &[u8]) {
         self.aad = aad.to_vec();
 
@@ -254,6 +317,58 @@ impl MlsGroup {
         self.group.context().epoch()
     }
 
+    /// Returns the [`GroupContext`] of the current epoch.
+    ///
+    /// Members of the group agree on the group context. Comparing it, e.g.,
+    /// out-of-band, allows detecting members whose state diverged.
+    pub fn export_group_context(&self) -> &GroupContext {
+        self.group.context()
+    }
+
+    /// Returns the tree hash of the current epoch.
+    pub fn tree_hash(&self) -> &[u8] {
+        self.group.context().tree_hash()
+    }
+
+    /// Returns the confirmed transcript hash of the current epoch, which
+    /// covers all commits up to and including the last merged one.
+    pub fn confirmed_transcript_hash(&self) -> &[u8] {
+        self.group.context().confirmed_transcript_hash()
+    }
+
+    /// Returns the interim transcript hash of the current epoch, i.e., the
+    /// confirmed transcript hash combined with the confirmation tag of the
+    /// last merged commit.
+    pub fn interim_transcript_hash(&self) -> &[u8] {
+        self.group.public_group().interim_transcript_hash()
+    }
+
+    /// Returns the [`RequiredCapabilitiesExtension`] of the group context, if
+    /// any.
+    pub fn required_capabilities(&self) -> Option<&RequiredCapabilitiesExtension> {
+        self.group.public_group().required_capabilities()
+    }
+
+    /// Returns the external senders of the group context's
+    /// `external_senders` extension. The slice is empty if the group context
+    /// doesn't contain the extension.
+    pub fn external_senders(&self) -> &[ExternalSender] {
+        self.group
+            .context()
+            .extensions()
+            .external_senders()
+            .map_or(&[], |external_senders| external_senders.as_slice())
+    }
+
+    /// Returns the [`SenderExtensionIndex`] of the external sender with the
+    /// given `credential` or `None` if there is no such external sender.
+    pub fn external_sender_index(&self, credential: &Credential) -> Option<SenderExtensionIndex> {
+        self.external_senders()
+            .iter()
+            .position(|external_sender| external_sender.credential() == credential)
+            .map(|index| SenderExtensionIndex::new(index as u32))
+    }
+
     /// Returns an `Iterator` over pending proposals.
     pub fn pending_proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
         self.proposal_store.proposals()
@@ -294,6 +409,132 @@ impl MlsGroup {
         }
     }
 
+    /// Discards the pending commit of the group, e.g., because the DS rejected
+    /// it in favour of a concurrent commit, and returns the group to the state
+    /// it was in before the commit was created.
+    ///
+    /// In contrast to [`MlsGroup::clear_pending_commit()`], this function
+    ///  * puts all proposals that the commit covered by reference back into
+    ///    the proposal store, so that they can be committed again, and
+    ///  * deletes any key material of the abandoned epoch from the key store.
+    ///
+    /// Proposals that were included in the commit by value, e.g., the Add
+    /// proposals created by [`MlsGroup::add_members()`], are discarded along
+    /// with the commit.
+    ///
+    /// Returns an error if there is no pending commit or if the group was
+    /// created through an external commit that has not been merged yet. For
+    /// more information, see [`MlsGroup::join_by_external_commit()`].
+    pub fn rollback_pending_commit<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), RollbackPendingCommitError<KeyStore::Error>> {
+        let staged_commit = match self.group_state {
+            MlsGroupState::PendingCommit(ref pending_commit_state) => {
+                match **pending_commit_state {
+                    PendingCommitState::Member(ref staged_commit) => staged_commit,
+                    PendingCommitState::External(_) => {
+                        return Err(MlsGroupStateError::PendingCommit.into())
+                    }
+                }
+            }
+            MlsGroupState::Operational | MlsGroupState::Inactive => {
+                return Err(MlsGroupStateError::NoPendingCommit.into())
+            }
+        };
+
+        // Restore the proposals the commit covered by reference.
+        let missing_proposals: Vec<QueuedProposal> = staged_commit
+            .queued_proposals()
+            .filter(|queued_proposal| {
+                queued_proposal.proposal_or_ref_type() == ProposalOrRefType::Reference
+                    && !self.proposal_store.proposals().any(|stored_proposal| {
+                        stored_proposal.proposal_reference() == queued_proposal.proposal_reference()
+                    })
+            })
+            .cloned()
+            .collect();
+
+        // Delete key material of the abandoned epoch.
+        self.group
+            .delete_next_epoch_keypairs(backend)
+            .map_err(RollbackPendingCommitError::KeyStoreError)?;
+
+        for queued_proposal in missing_proposals {
+            self.proposal_store.add(queued_proposal);
+        }
+        self.group_state = MlsGroupState::Operational;
+
+        // Since the state of the group was changed, arm the state flag
+        self.flag_state_change();
+
+        Ok(())
+    }
+
+    /// Deletes the message secrets of all past epochs that ended longer ago
+    /// than the `max_past_epoch_age` of the [`MlsGroupConfig`], according to
+    /// the configured time provider. This has no effect if no
+    /// `max_past_epoch_age` is configured or if the time provider doesn't
+    /// know the current time.
+    ///
+    /// Expired secrets are also deleted when a message is processed or a
+    /// commit is merged. Applications with groups that are quiet for a long
+    /// time should call this function periodically.
+    pub fn delete_expired_message_secrets(&mut self) {
+        let max_past_epoch_age = self.configuration().max_past_epoch_age();
+        let now = self.configuration().time_provider().unix_now();
+        if let (Some(max_past_epoch_age), Some(now)) = (max_past_epoch_age, now) {
+            self.group
+                .delete_expired_message_secrets(max_past_epoch_age, now);
+
+            // Since the state of the group might be changed, arm the state flag
+            self.flag_state_change();
+        }
+    }
+
+    /// Deletes the message secrets of the oldest past epochs until the
+    /// estimated size of all message secrets is within the
+    /// `message_secrets_budget` of the [`MlsGroupConfig`]. This has no effect
+    /// if no budget is configured.
+    pub(crate) fn enforce_message_secrets_budget(&mut self) {
+        if let Some(budget) = self.configuration().message_secrets_budget() {
+            self.group.enforce_message_secrets_budget(budget);
+        }
+    }
+
+    /// Returns the [`SenderRatchetConfiguration`] that is used to decrypt
+    /// messages. If `immediate_key_deletion` is set in the [`MlsGroupConfig`],
+    /// no key material of past generations is kept.
+    pub(crate) fn sender_ratchet_configuration(&self) -> SenderRatchetConfiguration {
+        let configuration = self.configuration().sender_ratchet_configuration();
+        if self.configuration().immediate_key_deletion() {
+            SenderRatchetConfiguration::new(0, configuration.maximum_forward_distance())
+                .with_maximum_skipped_keys(0)
+        } else {
+            configuration.clone()
+        }
+    }
+
+    /// Returns `true` if the exporter secrets of past epochs are kept, i.e.,
+    /// if `retain_past_exporter_secrets` is set and `immediate_key_deletion`
+    /// is not set in the [`MlsGroupConfig`].
+    pub(crate) fn retains_past_exporter_secrets(&self) -> bool {
+        self.configuration().retain_past_exporter_secrets()
+            && !self.configuration().immediate_key_deletion()
+    }
+
+    /// Deletes the own encryption ratchets of past epochs if
+    /// `immediate_key_deletion` is set in the [`MlsGroupConfig`].
+    pub(crate) fn erase_past_encryption_ratchets(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<(), LibraryError> {
+        if self.configuration().immediate_key_deletion() {
+            self.group.erase_past_encryption_ratchets(backend)?;
+        }
+        Ok(())
+    }
+
     // === Load & save ===
 
     /// Loads the state from persisted state.
@@ -309,6 +550,7 @@ impl MlsGroup {
         let serialized_mls_group = serde_json::to_string_pretty(self)?;
         writer.write_all(&serialized_mls_group.into_bytes())?;
         self.state_changed = InnerState::Persisted;
+        self.updated_sender_ratchets.clear();
         Ok(())
     }
 
@@ -318,12 +560,59 @@ impl MlsGroup {
         self.state_changed
     }
 
+    /// Persists the state in the given key store, indexed by the group's
+    /// [`GroupId`].
+    ///
+    /// Returns an error if access to the key store fails.
+    pub(crate) fn store<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        key_store: &KeyStore,
+    ) -> Result<(), KeyStore::Error> {
+        key_store.store(&Self::key_store_id(self.group_id()), self)?;
+        self.state_changed = InnerState::Persisted;
+        self.updated_sender_ratchets.clear();
+        Ok(())
+    }
+
+    /// Loads the state of the group with the given [`GroupId`] from the given
+    /// key store.
+    ///
+    /// Returns `None` if no state was persisted for this group.
+    pub(crate) fn read<KeyStore: OpenMlsKeyStore>(
+        key_store: &KeyStore,
+        group_id: &GroupId,
+    ) -> Option<Self> {
+        key_store.read(&Self::key_store_id(group_id))
+    }
+
+    /// Deletes the state of the group with the given [`GroupId`] from the
+    /// given key store.
+    ///
+    /// Returns an error if access to the key store fails.
+    pub(crate) fn delete<KeyStore: OpenMlsKeyStore>(
+        key_store: &KeyStore,
+        group_id: &GroupId,
+    ) -> Result<(), KeyStore::Error> {
+        key_store.delete::<Self>(&Self::key_store_id(group_id))
+    }
+
+    /// Composite key for the group state in the key store.
+    fn key_store_id(group_id: &GroupId) -> Vec<u8> {
+        [b"MlsGroup".as_slice(), group_id.as_slice()].concat()
+    }
+
     // === Extensions ===
 
     /// Exports the Ratchet Tree.
     pub fn export_ratchet_tree(&self) -> RatchetTree {
         self.group.public_group().export_ratchet_tree()
     }
+
+    /// Returns a read-only [`RatchetTreeView`] of the ratchet tree, e.g. to
+    /// inspect the structure of the tree.
+    pub fn ratchet_tree_view(&self) -> RatchetTreeView<'_> {
+        self.group.public_group().ratchet_tree_view()
+    }
 }
 
 // Private methods of MlsGroup
@@ -336,7 +625,11 @@ impl MlsGroup {
         mls_auth_content: AuthenticatedContent,
         backend: &impl OpenMlsCryptoProvider,
     ) -> Result<MlsMessageOut, LibraryError> {
-        let msg = match self.configuration().wire_format_policy().outgoing() {
+        let outgoing_wire_format_policy = self
+            .configuration()
+            .wire_format_policy()
+            .outgoing_for(mls_auth_content.content().content_type());
+        let msg = match outgoing_wire_format_policy {
             OutgoingWireFormatPolicy::AlwaysPlaintext => {
                 let mut plaintext: PublicMessage = mls_auth_content.into();
                 // Set the membership tag only if the sender type is `Member`.
@@ -354,11 +647,12 @@ impl MlsGroup {
                     .group
                     .encrypt(
                         mls_auth_content,
-                        self.configuration().padding_size(),
+                        &self.configuration().padding_policy(),
                         backend,
                     )
                     // We can be sure the encryption will work because the plaintext was created by us
                     .map_err(|_| LibraryError::custom("Malformed plaintext"))?;
+                self.record_sender_ratchet_update(self.epoch(), self.own_leaf_index());
                 MlsMessageOut::from_private_message(ciphertext, self.group.version())
             }
         };
@@ -370,14 +664,33 @@ impl MlsGroup {
         self.state_changed = InnerState::Changed;
     }
 
-    /// Group framing parameters
-    pub(crate) fn framing_parameters(&self) -> FramingParameters {
+    /// Group framing parameters for messages of the given `content_type`
+    pub(crate) fn framing_parameters(&self, content_type: ContentType) -> FramingParameters {
         FramingParameters::new(
             &self.aad,
-            self.mls_group_config.wire_format_policy().outgoing(),
+            self.mls_group_config
+                .wire_format_policy()
+                .outgoing_for(content_type),
         )
     }
 
+    /// Returns the context to validate a leaf node against the members of the
+    /// group, except for the members at the `excluded` leaf indices. Lifetimes
+    /// are validated with the configured time provider and clock skew
+    /// tolerance.
+    pub(crate) fn leaf_node_validation_context(
+        &self,
+        excluded: &[LeafNodeIndex],
+    ) -> LeafNodeValidationContext<'_> {
+        self.group
+            .public_group()
+            .leaf_node_validation_context(excluded)
+            .with_time(
+                self.configuration().time_provider(),
+                self.configuration().clock_skew_tolerance().as_secs(),
+            )
+    }
+
     /// Check if the group is operational. Throws an error if the group is
     /// inactive or if there is a pending commit.
     fn is_operational(&self) -> Result<(), MlsGroupStateError> {
@@ -391,16 +704,6 @@ impl MlsGroup {
 
 // Methods used in tests
 impl MlsGroup {
-    #[cfg(any(feature = "test-utils", test))]
-    pub fn export_group_context(&self) -> &GroupContext {
-        self.group.context()
-    }
-
-    #[cfg(any(feature = "test-utils", test))]
-    pub fn tree_hash(&self) -> &[u8] {
-        self.group.public_group().group_context().tree_hash()
-    }
-
     #[cfg(any(feature = "test-utils", test))]
     pub fn print_ratchet_tree(&self, message: &str) {
         self.group.print_ratchet_tree(message)
@@ -425,6 +728,7 @@ impl MlsGroup {
     ) -> Result<(), MlsGroupStateError> {
         self.proposal_store
             .remove(proposal_ref)
+            .map(|_| ())
             .ok_or(MlsGroupStateError::PendingProposalNotFound)
     }
 }
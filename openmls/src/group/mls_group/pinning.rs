@@ -0,0 +1,154 @@
+//! MLS group signature key pinning
+//!
+//! This module contains the [`SignatureKeyPins`] of an [`MlsGroup`], which
+//! record the signature key first seen for each identity (trust on first use).
+//! Keys are only pinned when a member is part of the group, i.e., when the
+//! group is created or joined and when a commit is merged.
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+use crate::ciphersuite::SignaturePublicKey;
+
+/// The signature keys pinned to the identities of the members of a group.
+///
+/// The signature key of the first member with an identity is pinned. If a
+/// member later shows up with the same identity, but a different signature
+/// key, a [`ProcessingWarning::KeyChanged`] is raised.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureKeyPins {
+    pins: Vec<(Vec<u8>, SignaturePublicKey)>,
+}
+
+impl SignatureKeyPins {
+    /// Returns the signature key pinned for the given `identity`, if any.
+    pub fn get(&self, identity: &[u8]) -> Option<&SignaturePublicKey> {
+        self.pins
+            .iter()
+            .find(|(pinned_identity, _)| pinned_identity == identity)
+            .map(|(_, signature_key)| signature_key)
+    }
+
+    /// Pins the `signature_key` to the given `identity`, replacing any
+    /// previously pinned key.
+    pub fn pin(&mut self, identity: &[u8], signature_key: SignaturePublicKey) {
+        match self
+            .pins
+            .iter_mut()
+            .find(|(pinned_identity, _)| pinned_identity == identity)
+        {
+            Some((_, pinned_signature_key)) => *pinned_signature_key = signature_key,
+            None => self.pins.push((identity.to_vec(), signature_key)),
+        }
+    }
+
+    /// Pins the `signature_key` to the given `identity` if no key is pinned
+    /// for the identity yet.
+    fn pin_if_unpinned(&mut self, identity: &[u8], signature_key: &SignaturePublicKey) {
+        if self.get(identity).is_none() {
+            self.pin(identity, signature_key.clone());
+        }
+    }
+
+    /// Checks the `signature_key` against the key pinned for the given
+    /// `identity`.
+    ///
+    /// Returns a [`ProcessingWarning::KeyChanged`] if a different key is
+    /// pinned for the identity.
+    pub(crate) fn check(
+        &self,
+        identity: &[u8],
+        signature_key: &SignaturePublicKey,
+    ) -> Option<ProcessingWarning> {
+        self.get(identity)
+            .filter(|pinned_signature_key| *pinned_signature_key != signature_key)
+            .map(|pinned_signature_key| ProcessingWarning::KeyChanged {
+                identity: identity.to_vec(),
+                pinned_signature_key: pinned_signature_key.clone(),
+                signature_key: signature_key.clone(),
+            })
+    }
+}
+
+impl MlsGroup {
+    /// Returns the signature keys pinned to the identities of the members of
+    /// the group.
+    pub fn signature_key_pins(&self) -> &SignatureKeyPins {
+        &self.signature_key_pins
+    }
+
+    /// Pins the `signature_key` to the given `identity`, e.g. to accept the
+    /// new signature key of a member after a
+    /// [`ProcessingWarning::KeyChanged`].
+    pub fn pin_signature_key(&mut self, identity: &[u8], signature_key: SignaturePublicKey) {
+        self.signature_key_pins.pin(identity, signature_key);
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change();
+    }
+
+    /// Pins the signature keys of all current members of the group whose
+    /// identity is not pinned yet.
+    pub(crate) fn pin_members(&mut self) {
+        if !self.configuration().use_signature_key_pinning() {
+            return;
+        }
+        for member in self.group.public_group().members() {
+            self.signature_key_pins
+                .pin_if_unpinned(member.credential.identity(), &member.signature_key.into());
+        }
+    }
+
+    /// Checks the signature keys of all leaf nodes in the given
+    /// [`ProcessedMessage`] against the pinned keys and adds a
+    /// [`ProcessingWarning::KeyChanged`] to the message for every mismatch.
+    ///
+    /// No keys are pinned here. The keys of new members are pinned when the
+    /// commit is merged, see [`MlsGroup::merge_staged_commit()`].
+    pub(crate) fn check_signature_key_pins(&self, processed_message: &mut ProcessedMessage) {
+        if !self.configuration().use_signature_key_pinning() {
+            return;
+        }
+
+        let mut leaf_nodes: Vec<LeafNode> = Vec::new();
+        match processed_message.content() {
+            ProcessedMessageContent::ApplicationMessage(_) => {}
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                match queued_proposal.proposal() {
+                    Proposal::Add(add_proposal) => {
+                        leaf_nodes.push(add_proposal.key_package().leaf_node().clone())
+                    }
+                    Proposal::Update(update_proposal) => {
+                        leaf_nodes.push(update_proposal.leaf_node().clone())
+                    }
+                    _ => {}
+                }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                leaf_nodes.extend(staged_commit.add_proposals().map(|add_proposal| {
+                    add_proposal
+                        .add_proposal()
+                        .key_package()
+                        .leaf_node()
+                        .clone()
+                }));
+                leaf_nodes.extend(
+                    staged_commit.update_proposals().map(|update_proposal| {
+                        update_proposal.update_proposal().leaf_node().clone()
+                    }),
+                );
+                leaf_nodes.extend(staged_commit.update_path_leaf_node().cloned());
+            }
+        }
+
+        for leaf_node in leaf_nodes {
+            if let Some(warning) = self
+                .signature_key_pins
+                .check(leaf_node.credential().identity(), leaf_node.signature_key())
+            {
+                processed_message.add_warning(warning);
+            }
+        }
+    }
+}
@@ -6,12 +6,20 @@ use core_group::staged_commit::StagedCommit;
 use openmls_traits::signatures::Signer;
 
 use crate::{
-    group::core_group::create_commit_params::CreateCommitParams, messages::group_info::GroupInfo,
+    ciphersuite::OpenMlsSignaturePublicKey,
+    group::core_group::create_commit_params::CreateCommitParams,
+    messages::{
+        group_info::GroupInfo,
+        targeted_message::{ProcessedTargetedMessage, TargetedMessage},
+    },
 };
 
 use crate::group::errors::MergeCommitError;
 
-use super::{errors::ProcessMessageError, *};
+use super::{
+    errors::{ProcessMessageError, ProcessTargetedMessageError},
+    *,
+};
 
 impl MlsGroup {
     /// Parses incoming messages from the DS. Checks for syntactic errors and
@@ -34,7 +42,14 @@ impl MlsGroup {
                 MlsGroupStateError::UseAfterEviction,
             ));
         }
+        // Don't skip the credential validation of a loaded group whose
+        // validator wasn't set again
+        if self.configuration().credential_validator_missing() {
+            return Err(ProcessMessageError::MissingCredentialValidator);
+        }
         let message = message.into();
+        let is_private_message = message.wire_format() == WireFormat::PrivateMessage;
+        let message_epoch = message.epoch();
 
         // Check that handshake messages are compatible with the incoming wire format policy
         if !message.is_external()
@@ -42,7 +57,7 @@ impl MlsGroup {
             && !self
                 .configuration()
                 .wire_format_policy()
-                .incoming()
+                .incoming_for(message.content_type())
                 .is_compatible_with(message.wire_format())
         {
             return Err(ProcessMessageError::IncompatibleWireFormat);
@@ -51,22 +66,162 @@ impl MlsGroup {
         // Since the state of the group might be changed, arm the state flag
         self.flag_state_change();
 
+        // Messages from expired epochs can't be decrypted anymore
+        self.delete_expired_message_secrets();
+
         // Parse the message
-        let sender_ratchet_configuration =
-            self.configuration().sender_ratchet_configuration().clone();
-        self.group.process_message(
+        let sender_ratchet_configuration = self.sender_ratchet_configuration();
+        let result = self.group.process_message(
             backend,
             message,
             &sender_ratchet_configuration,
             &self.proposal_store,
             &self.own_leaf_nodes,
+        );
+
+        // Decrypting the message might have retained keys of skipped
+        // generations
+        self.enforce_message_secrets_budget();
+
+        let mut processed_message =
+            result.map_err(|error| self.check_desync(message_epoch, error))?;
+
+        // Decrypting the message advanced the ratchets of the sender
+        if is_private_message {
+            if let Sender::Member(sender) = processed_message.sender() {
+                self.record_sender_ratchet_update(processed_message.epoch(), *sender);
+            }
+        }
+
+        // Check the lifetimes of the added key packages against the
+        // configured time source
+        processed_message.validate_key_package_lifetimes(
+            self.configuration().time_provider(),
+            self.configuration().clock_skew_tolerance().as_secs(),
+        )?;
+
+        // Let the application validate all credentials in the message
+        self.validate_credentials(&processed_message)?;
+
+        // Check that all registered custom extensions in the message can be
+        // decoded
+        self.validate_custom_extensions(&processed_message)?;
+
+        // Let the application validate the authenticated data of the message
+        if !self.configuration().validate_aad(
+            processed_message.sender(),
+            processed_message.content_type(),
+            processed_message.authenticated_data(),
+        ) {
+            return Err(ProcessMessageError::InvalidAad);
+        }
+
+        // Reject proposals if there is no room for them. A store without
+        // room can't evict proposals to make room.
+        if processed_message.content_type() == ContentType::Proposal
+            && self
+                .configuration()
+                .max_pending_proposals()
+                .map_or(false, |max| {
+                    max == 0
+                        || (self.configuration().proposal_store_full_policy()
+                            == ProposalStoreFullPolicy::Reject
+                            && self.proposal_store.len() >= max)
+                })
+        {
+            return Err(ProcessMessageError::ProposalStoreFull);
+        }
+
+        // Fully validate the leaf nodes in the message if configured
+        if self
+            .configuration()
+            .leaf_node_validation_policy()
+            .is_strict()
+        {
+            self.validate_leaf_nodes(&processed_message)?;
+        }
+
+        // Reject new members whose identity is already in the group if
+        // configured
+        if self.configuration().unique_identities() {
+            self.check_new_member_identities(&processed_message)?;
+        }
+
+        // Check the signature keys in the message against the pinned keys
+        self.check_signature_key_pins(&mut processed_message);
+
+        Ok(processed_message)
+    }
+
+    /// Decrypts a [`TargetedMessage`] sent to the own leaf by another member
+    /// of the group and verifies the sender's signature. Targeted messages can
+    /// only be processed in the epoch in which they were created.
+    ///
+    /// # Errors:
+    /// Returns a [`ProcessTargetedMessageError`] when the message is not
+    /// addressed to the own leaf in the current epoch, can't be decrypted or
+    /// when the sender can't be authenticated.
+    pub fn process_targeted_message(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: TargetedMessage,
+    ) -> Result<ProcessedTargetedMessage, ProcessTargetedMessageError> {
+        // Make sure we are still a member of the group
+        if !self.is_active() {
+            return Err(ProcessTargetedMessageError::GroupStateError(
+                MlsGroupStateError::UseAfterEviction,
+            ));
+        }
+        if message.group_id() != self.group_id() {
+            return Err(ProcessTargetedMessageError::WrongGroupId);
+        }
+        if message.epoch() != self.epoch() {
+            return Err(ProcessTargetedMessageError::WrongEpoch);
+        }
+        if message.recipient_leaf_index() != self.own_leaf_index() {
+            return Err(ProcessTargetedMessageError::WrongRecipient);
+        }
+
+        let own_encryption_key = self.group.own_leaf_node()?.encryption_key();
+        let own_keypair = self
+            .group
+            .read_epoch_keypairs(backend)
+            .into_iter()
+            .find(|keypair| keypair.public_key() == own_encryption_key)
+            .ok_or(ProcessTargetedMessageError::MissingDecryptionKey)?;
+
+        let public_group = self.group.public_group();
+        message.decrypt_and_verify(
+            backend,
+            self.group.context(),
+            own_keypair.private_key(),
+            |sender_leaf_index| {
+                public_group.leaf(sender_leaf_index).map(|leaf_node| {
+                    (
+                        leaf_node.credential().clone(),
+                        OpenMlsSignaturePublicKey::from_signature_key(
+                            leaf_node.signature_key().clone(),
+                            public_group.ciphersuite().signature_algorithm(),
+                        ),
+                    )
+                })
+            },
         )
     }
 
     /// Stores a standalone proposal in the internal [ProposalStore]
+    ///
+    /// If the store already holds the `max_pending_proposals` of the
+    /// [`MlsGroupConfig`], the proposal is not stored or, with
+    /// [`ProposalStoreFullPolicy::EvictOldest`], the oldest proposal is
+    /// evicted.
     pub fn store_pending_proposal(&mut self, proposal: QueuedProposal) {
         // Store the proposal in in the internal ProposalStore
-        self.proposal_store.add(proposal);
+        self.proposal_store.add_bounded(
+            proposal,
+            self.configuration().max_pending_proposals(),
+            self.configuration().proposal_store_full_policy(),
+        );
 
         // Since the state of the group might be changed, arm the state flag
         self.flag_state_change();
@@ -93,8 +248,9 @@ impl MlsGroup {
         // Create Commit over all pending proposals
         // TODO #751
         let params = CreateCommitParams::builder()
-            .framing_parameters(self.framing_parameters())
+            .framing_parameters(self.framing_parameters(ContentType::Commit))
             .proposal_store(&self.proposal_store)
+            .always_create_group_info(self.configuration().always_return_group_info())
             .build();
         let create_commit_result = self.group.create_commit(params, backend, signer)?;
 
@@ -121,12 +277,26 @@ impl MlsGroup {
     }
 
     /// Merge a [StagedCommit] into the group after inspection. As this advances
-    /// the epoch of the group, it also clears any pending commits.
+    /// the epoch of the group, it also clears any pending commits and deletes
+    /// a staged commit that was stored with [`MlsGroup::store_staged_commit()`].
+    ///
+    /// Returns [`MergeCommitError::WrongEpoch`] if the commit was not staged
+    /// for this group in its current epoch.
     pub fn merge_staged_commit<KeyStore: OpenMlsKeyStore>(
         &mut self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         staged_commit: StagedCommit,
     ) -> Result<(), MergeCommitError<KeyStore::Error>> {
+        // A commit staged in an earlier session might be outdated
+        if staged_commit.group_id() != self.group_id() || staged_commit.epoch() != self.epoch() {
+            return Err(MergeCommitError::WrongEpoch);
+        }
+
+        // A stored staged commit can't be merged in the new epoch. It is
+        // deleted first, such that the group is not changed if this fails.
+        self.delete_staged_commit(backend)
+            .map_err(MergeCommitError::KeyStoreError)?;
+
         // Check if we were removed from the group
         if staged_commit.self_removed() {
             self.group_state = MlsGroupState::Inactive;
@@ -135,9 +305,28 @@ impl MlsGroup {
         // Since the state of the group might be changed, arm the state flag
         self.flag_state_change();
 
+        // Summarize the commit for the audit log while the removed members
+        // are still in the tree
+        let audit_log_entry = staged_commit
+            .sender()
+            .filter(|_| self.configuration().use_commit_audit_log())
+            .map(|sender| (sender.clone(), self.summarize_proposals(&staged_commit)));
+
         // Merge staged commit
-        self.group
-            .merge_staged_commit(backend, staged_commit, &mut self.proposal_store)?;
+        let retain_exporter_secret = self.retains_past_exporter_secrets();
+        let now = self.configuration().time_provider().unix_now();
+        self.group.merge_staged_commit(
+            backend,
+            staged_commit,
+            &mut self.proposal_store,
+            retain_exporter_secret,
+            now,
+        )?;
+
+        // Record the commit in the audit log
+        if let Some((sender, proposals)) = audit_log_entry {
+            self.record_commit(sender, proposals);
+        }
 
         // Extract and store the resumption psk for the current epoch
         let resumption_psk = self.group.group_epoch_secrets().resumption_psk();
@@ -145,12 +334,33 @@ impl MlsGroup {
             .resumption_psk_store
             .add(self.group.context().epoch(), resumption_psk.clone());
 
+        // Record the start of the new epoch
+        self.epoch_history
+            .record(self.epoch(), self.mls_group_config.time_provider());
+
+        // Pin the signature keys of new members
+        self.pin_members();
+
         // Delete own KeyPackageBundles
         self.own_leaf_nodes.clear();
 
         // Delete a potential pending commit
         self.clear_pending_commit();
 
+        // Configuration changes take effect in the new epoch
+        self.apply_pending_config_update();
+
+        // The previous epoch just ended, but older ones might have expired
+        self.delete_expired_message_secrets();
+
+        // The secrets of the previous epoch might exceed the budget
+        self.enforce_message_secrets_budget();
+
+        // No more messages are encrypted in the previous epoch. This is done
+        // last, such that the bookkeeping of the new epoch is complete even
+        // if it fails.
+        self.erase_past_encryption_ratchets(backend)?;
+
         Ok(())
     }
 
@@ -173,3 +383,245 @@ impl MlsGroup {
         }
     }
 }
+
+impl MlsGroup {
+    /// Validates the credentials of the sender, of new joiners and of members
+    /// updating their leaf node, including the leaf node in the update path of
+    /// a commit, in the given [`ProcessedMessage`] with the
+    /// configured [`CredentialValidator`](crate::credentials::CredentialValidator).
+    ///
+    /// For proposals and commits, this also checks that the credentials of
+    /// the sender and of members updating their leaf node are neither revoked
+    /// nor expired.
+    fn validate_credentials(
+        &self,
+        processed_message: &ProcessedMessage,
+    ) -> Result<(), ProcessMessageError> {
+        let config = self.configuration();
+        let validate = |credential: &Credential| {
+            if config.validate_credential(credential) && !config.is_credential_revoked(credential) {
+                Ok(())
+            } else {
+                Err(ProcessMessageError::InvalidCredential)
+            }
+        };
+        let validate_member = |sender: &Sender, credential: &Credential| match sender {
+            Sender::Member(leaf_index) if config.is_credential_revoked(credential) => {
+                Err(ProcessMessageError::RevokedCredential(*leaf_index))
+            }
+            _ if !config.validate_credential(credential) => {
+                Err(ProcessMessageError::InvalidCredential)
+            }
+            _ => Ok(()),
+        };
+
+        match processed_message.content() {
+            ProcessedMessageContent::ApplicationMessage(_) => {
+                if !config.validate_credential(processed_message.credential()) {
+                    return Err(ProcessMessageError::InvalidCredential);
+                }
+            }
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                validate_member(processed_message.sender(), processed_message.credential())?;
+                match queued_proposal.proposal() {
+                    Proposal::Add(add_proposal) => {
+                        validate(add_proposal.key_package().leaf_node().credential())?
+                    }
+                    Proposal::Update(update_proposal) => validate_member(
+                        processed_message.sender(),
+                        update_proposal.leaf_node().credential(),
+                    )?,
+                    _ => {}
+                }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                validate_member(processed_message.sender(), processed_message.credential())?;
+                for add_proposal in staged_commit.add_proposals() {
+                    validate(
+                        add_proposal
+                            .add_proposal()
+                            .key_package()
+                            .leaf_node()
+                            .credential(),
+                    )?;
+                }
+                for update_proposal in staged_commit.update_proposals() {
+                    validate_member(
+                        update_proposal.sender(),
+                        update_proposal.update_proposal().leaf_node().credential(),
+                    )?;
+                }
+                // The committer may replace its credential in the leaf node
+                // of the update path
+                if let Some(leaf_node) = staged_commit.update_path_leaf_node() {
+                    match processed_message.sender() {
+                        Sender::Member(_) => {
+                            validate_member(processed_message.sender(), leaf_node.credential())?
+                        }
+                        _ => validate(leaf_node.credential())?,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that all extensions of a type that is registered in the
+    /// configured [`CustomExtensionRegistry`](crate::extensions::CustomExtensionRegistry)
+    /// in the given [`ProcessedMessage`] can be decoded. This covers the key
+    /// packages and leaf nodes in the message and the group context
+    /// extensions that are proposed or result from a commit.
+    fn validate_custom_extensions(
+        &self,
+        processed_message: &ProcessedMessage,
+    ) -> Result<(), ProcessMessageError> {
+        let registry = self.configuration().custom_extensions();
+        let validate_proposal = |proposal: &Proposal| match proposal {
+            Proposal::Add(add_proposal) => {
+                let key_package = add_proposal.key_package();
+                registry.validate(key_package.extensions())?;
+                registry.validate(key_package.leaf_node().extensions())
+            }
+            Proposal::Update(update_proposal) => {
+                registry.validate(update_proposal.leaf_node().extensions())
+            }
+            Proposal::GroupContextExtensions(group_context_extensions) => {
+                registry.validate(group_context_extensions.extensions())
+            }
+            _ => Ok(()),
+        };
+
+        match processed_message.content() {
+            ProcessedMessageContent::ApplicationMessage(_) => {}
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                validate_proposal(queued_proposal.proposal())?;
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                for queued_proposal in staged_commit.queued_proposals() {
+                    validate_proposal(queued_proposal.proposal())?;
+                }
+                if let Some(leaf_node) = staged_commit.update_path_leaf_node() {
+                    registry.validate(leaf_node.extensions())?;
+                }
+                registry.validate(staged_commit.group_context().extensions())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the members added by the given [`ProcessedMessage`] don't
+    /// have the identity of a member that remains in the group, see
+    /// [`MlsGroupConfig::unique_identities()`]. A member that joins with an
+    /// External Commit to resync may remove its old leaf in the same commit.
+    fn check_new_member_identities(
+        &self,
+        processed_message: &ProcessedMessage,
+    ) -> Result<(), ProcessMessageError> {
+        match processed_message.content() {
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                if let Proposal::Add(add_proposal) = queued_proposal.proposal() {
+                    self.check_unique_identities(
+                        [add_proposal.key_package().leaf_node().credential()],
+                        &[],
+                    )?;
+                }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                let removed: Vec<LeafNodeIndex> = staged_commit
+                    .remove_proposals()
+                    .map(|remove_proposal| remove_proposal.remove_proposal().removed())
+                    .collect();
+                let added: Vec<Credential> = staged_commit
+                    .add_proposals()
+                    .map(|add_proposal| {
+                        add_proposal
+                            .add_proposal()
+                            .key_package()
+                            .leaf_node()
+                            .credential()
+                            .clone()
+                    })
+                    .collect();
+                // The joiner of an External Commit is a new member as well
+                let joiner = match processed_message.sender() {
+                    Sender::NewMemberCommit => staged_commit
+                        .update_path_leaf_node()
+                        .map(|leaf_node| leaf_node.credential()),
+                    _ => None,
+                };
+                self.check_unique_identities(added.iter().chain(joiner), &removed)?;
+            }
+            ProcessedMessageContent::ApplicationMessage(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Validates the leaf nodes in the given [`ProcessedMessage`] against the
+    /// current members of the group as specified in Section 7.3 of the MLS
+    /// specification. Members that are removed or whose leaf node is replaced
+    /// by the message are not taken into account.
+    fn validate_leaf_nodes(
+        &self,
+        processed_message: &ProcessedMessage,
+    ) -> Result<(), ProcessMessageError> {
+        let context = |excluded: &[LeafNodeIndex]| self.leaf_node_validation_context(excluded);
+        let sender_index = match processed_message.sender() {
+            Sender::Member(leaf_index) => Some(*leaf_index),
+            _ => None,
+        };
+
+        match processed_message.content() {
+            ProcessedMessageContent::ApplicationMessage(_) => {}
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                match queued_proposal.proposal() {
+                    Proposal::Add(add_proposal) => {
+                        add_proposal
+                            .key_package()
+                            .leaf_node()
+                            .validate_in_key_package(&context(&[]))?;
+                    }
+                    Proposal::Update(update_proposal) => {
+                        let excluded: Vec<LeafNodeIndex> = sender_index.into_iter().collect();
+                        update_proposal
+                            .leaf_node()
+                            .validate_in_update(&context(&excluded))?;
+                    }
+                    _ => {}
+                }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                let removed: Vec<LeafNodeIndex> = staged_commit
+                    .remove_proposals()
+                    .map(|remove_proposal| remove_proposal.remove_proposal().removed())
+                    .collect();
+                for add_proposal in staged_commit.add_proposals() {
+                    add_proposal
+                        .add_proposal()
+                        .key_package()
+                        .leaf_node()
+                        .validate_in_key_package(&context(&removed))?;
+                }
+                for update_proposal in staged_commit.update_proposals() {
+                    let mut excluded = removed.clone();
+                    if let Sender::Member(leaf_index) = update_proposal.sender() {
+                        excluded.push(*leaf_index);
+                    }
+                    update_proposal
+                        .update_proposal()
+                        .leaf_node()
+                        .validate_in_update(&context(&excluded))?;
+                }
+                if let Some(leaf_node) = staged_commit.update_path_leaf_node() {
+                    let mut excluded = removed;
+                    excluded.extend(sender_index);
+                    leaf_node.validate_in_commit(&context(&excluded))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -3,16 +3,21 @@ use openmls_traits::{
 };
 
 use super::{
-    errors::{ProposalError, ProposeAddMemberError, ProposeRemoveMemberError},
+    errors::{
+        ProposalError, ProposeAddMemberError, ProposeExternalSenderError, ProposeRemoveMemberError,
+    },
     MlsGroup,
 };
 use crate::{
     binary_tree::LeafNodeIndex,
     ciphersuite::hash_ref::ProposalRef,
     credentials::Credential,
-    extensions::Extensions,
-    framing::MlsMessageOut,
-    group::{errors::CreateAddProposalError, GroupId, QueuedProposal},
+    extensions::{Extension, ExtensionType, Extensions, ExternalSender, ExternalSendersExtension},
+    framing::{ContentType, MlsMessageOut},
+    group::{
+        errors::{CreateAddProposalError, CreateRemoveProposalError},
+        GroupId, QueuedProposal,
+    },
     key_packages::KeyPackage,
     messages::proposals::ProposalOrRefType,
     prelude::LibraryError,
@@ -68,9 +73,11 @@ macro_rules! impl_propose_fun {
         ) -> Result<(MlsMessageOut, ProposalRef), ProposalError<KeyStore::Error>> {
             self.is_operational()?;
 
-            let proposal = self
-                .group
-                .$group_fun(self.framing_parameters(), value, signer)?;
+            let proposal = self.group.$group_fun(
+                self.framing_parameters(ContentType::Proposal),
+                value,
+                signer,
+            )?;
 
             let queued_proposal = QueuedProposal::from_authenticated_content(
                 self.ciphersuite(),
@@ -121,6 +128,20 @@ impl MlsGroup {
         ProposalOrRefType::Proposal
     );
 
+    impl_propose_fun!(
+        propose_group_context_extensions,
+        Extensions,
+        create_group_context_ext_proposal,
+        ProposalOrRefType::Reference
+    );
+
+    impl_propose_fun!(
+        propose_group_context_extensions_by_value,
+        Extensions,
+        create_group_context_ext_proposal,
+        ProposalOrRefType::Proposal
+    );
+
     /// Generate a proposal
     pub fn propose<KeyStore: OpenMlsKeyStore>(
         &mut self,
@@ -191,9 +212,14 @@ impl MlsGroup {
             Propose::ExternalInit(_) => Err(ProposalError::LibraryError(LibraryError::custom(
                 "Unsupported proposal type ExternalInit",
             ))),
-            Propose::GroupContextExtensions(_) => Err(ProposalError::LibraryError(
-                LibraryError::custom("Unsupported proposal type GroupContextExtensions"),
-            )),
+            Propose::GroupContextExtensions(extensions) => match ref_or_value {
+                ProposalOrRefType::Proposal => {
+                    self.propose_group_context_extensions_by_value(backend, signer, extensions)
+                }
+                ProposalOrRefType::Reference => {
+                    self.propose_group_context_extensions(backend, signer, extensions)
+                }
+            },
         }
     }
 
@@ -208,14 +234,25 @@ impl MlsGroup {
     ) -> Result<(MlsMessageOut, ProposalRef), ProposeAddMemberError> {
         self.is_operational()?;
 
+        // Reject new members whose identity is already in the group if
+        // configured
+        if self.configuration().unique_identities() {
+            self.check_unique_identities([key_package.leaf_node().credential()], &[])?;
+        }
+
         let add_proposal = self
             .group
-            .create_add_proposal(self.framing_parameters(), key_package.clone(), signer)
+            .create_add_proposal(
+                self.framing_parameters(ContentType::Proposal),
+                key_package.clone(),
+                signer,
+            )
             .map_err(|e| match e {
                 CreateAddProposalError::LibraryError(e) => e.into(),
                 CreateAddProposalError::LeafNodeValidation(error) => {
                     ProposeAddMemberError::LeafNodeValidation(error)
                 }
+                CreateAddProposalError::SignatureError(e) => e.into(),
             })?;
 
         let proposal = QueuedProposal::from_authenticated_content_by_ref(
@@ -248,8 +285,15 @@ impl MlsGroup {
 
         let remove_proposal = self
             .group
-            .create_remove_proposal(self.framing_parameters(), member, signer)
-            .map_err(|_| ProposeRemoveMemberError::UnknownMember)?;
+            .create_remove_proposal(
+                self.framing_parameters(ContentType::Proposal),
+                member,
+                signer,
+            )
+            .map_err(|e| match e {
+                CreateRemoveProposalError::UnknownMember => ProposeRemoveMemberError::UnknownMember,
+                CreateRemoveProposalError::SignatureError(e) => e.into(),
+            })?;
 
         let proposal = QueuedProposal::from_authenticated_content_by_ref(
             self.ciphersuite(),
@@ -319,34 +363,72 @@ impl MlsGroup {
         }
     }
 
-    #[cfg(test)]
-    pub fn propose_group_context_extensions(
+    /// Creates a GroupContextExtensions proposal that adds `external_sender`
+    /// to the `external_senders` extension of the group context. All other
+    /// extensions of the group context are kept.
+    ///
+    /// Returns an error if an external sender with the same credential is
+    /// already present.
+    pub fn propose_add_external_sender<KeyStore: OpenMlsKeyStore>(
         &mut self,
-        backend: &impl OpenMlsCryptoProvider,
-        extensions: Extensions,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         signer: &impl Signer,
-    ) -> Result<(MlsMessageOut, ProposalRef), ProposalError<()>> {
-        self.is_operational()?;
-
-        let proposal = self
-            .group
-            .create_group_context_ext_proposal(self.framing_parameters(), extensions, signer)
-            .unwrap();
-
-        let queued_proposal = QueuedProposal::from_authenticated_content_by_ref(
-            self.ciphersuite(),
-            backend,
-            proposal.clone(),
-        )?;
+        external_sender: ExternalSender,
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposalError<KeyStore::Error>> {
+        if self
+            .external_sender_index(external_sender.credential())
+            .is_some()
+        {
+            return Err(ProposeExternalSenderError::DuplicateExternalSender.into());
+        }
 
-        let proposal_ref = queued_proposal.proposal_reference();
-        self.proposal_store.add(queued_proposal);
+        let mut external_senders = self.external_senders().to_vec();
+        external_senders.push(external_sender);
 
-        let mls_message = self.content_to_mls_message(proposal, backend)?;
+        let extensions = self.extensions_with_external_senders(external_senders);
+        self.propose_group_context_extensions(backend, signer, extensions)
+    }
 
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+    /// Creates a GroupContextExtensions proposal that removes the external
+    /// sender with the given `credential` from the `external_senders`
+    /// extension of the group context. All other extensions of the group
+    /// context are kept. The extension is removed altogether if it becomes
+    /// empty.
+    ///
+    /// Note that the [`SenderExtensionIndex`](crate::extensions::SenderExtensionIndex)
+    /// of all external senders after the removed one changes once the proposal
+    /// is committed.
+    ///
+    /// Returns an error if there is no external sender with the credential.
+    pub fn propose_remove_external_sender<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        credential: &Credential,
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposalError<KeyStore::Error>> {
+        let mut external_senders = self.external_senders().to_vec();
+        let index = external_senders
+            .iter()
+            .position(|external_sender| external_sender.credential() == credential)
+            .ok_or(ProposeExternalSenderError::UnknownExternalSender)?;
+        external_senders.remove(index);
+
+        let extensions = self.extensions_with_external_senders(external_senders);
+        self.propose_group_context_extensions(backend, signer, extensions)
+    }
 
-        Ok((mls_message, proposal_ref))
+    /// Returns the extensions of the group context with the
+    /// `external_senders` extension replaced by `external_senders`.
+    fn extensions_with_external_senders(
+        &self,
+        external_senders: ExternalSendersExtension,
+    ) -> Extensions {
+        let mut extensions = self.group.context().extensions().clone();
+        if external_senders.is_empty() {
+            extensions.remove(ExtensionType::ExternalSenders);
+        } else {
+            extensions.add_or_replace(Extension::ExternalSenders(external_senders));
+        }
+        extensions
     }
 }
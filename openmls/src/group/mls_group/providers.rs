@@ -0,0 +1,135 @@
+//! Per-group crypto provider selection
+//!
+//! This module contains [`CryptoProviders`], which allows one process to serve
+//! groups that are backed by different [`OpenMlsCryptoProvider`]s, e.g., a FIPS
+//! validated backend next to the default backend. The providers don't have to
+//! be of the same type. Groups are associated with a provider through the
+//! `crypto_provider` property of their [`MlsGroupConfig`].
+//!
+//! ```
+//! use openmls::prelude::*;
+//! use openmls_rust_crypto::OpenMlsRustCrypto;
+//! # type FipsProvider = OpenMlsRustCrypto;
+//!
+//! let providers = CryptoProviders::new(OpenMlsRustCrypto::default())
+//!     .with_provider("fips", FipsProvider::default());
+//!
+//! let group_config = MlsGroupConfig::builder()
+//!     .crypto_provider("fips")
+//!     .build();
+//!
+//! let backend = providers
+//!     .for_config::<FipsProvider>(&group_config)
+//!     .expect("The FIPS provider is not registered.");
+//! # let _ = backend;
+//! ```
+
+use std::{any::Any, collections::HashMap, fmt};
+
+use super::*;
+
+type AnyProvider = Box<dyn Any + Send + Sync>;
+
+/// A set of named [`OpenMlsCryptoProvider`] instances with a default provider.
+///
+/// The providers are stored as trait objects, such that providers of different
+/// types can be registered. They are retrieved with their concrete type.
+///
+/// Groups that don't have a `crypto_provider` configured use the default
+/// provider. Groups that reference a provider that is not registered don't
+/// fall back to the default provider, see [`CryptoProviders::for_config()`].
+pub struct CryptoProviders {
+    default: AnyProvider,
+    providers: HashMap<String, AnyProvider>,
+}
+
+impl CryptoProviders {
+    /// Creates a new set of providers with the given `default` provider.
+    pub fn new(default: impl OpenMlsCryptoProvider + 'static) -> Self {
+        Self {
+            default: Box::new(default),
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Registers the `provider` under the given `id` and returns the updated
+    /// set of providers.
+    pub fn with_provider(
+        mut self,
+        id: impl Into<String>,
+        provider: impl OpenMlsCryptoProvider + 'static,
+    ) -> Self {
+        self.register(id, provider);
+        self
+    }
+
+    /// Registers the `provider` under the given `id`. Returns `true` if a
+    /// provider was previously registered under this `id`. The previous
+    /// provider is replaced.
+    pub fn register(
+        &mut self,
+        id: impl Into<String>,
+        provider: impl OpenMlsCryptoProvider + 'static,
+    ) -> bool {
+        self.providers
+            .insert(id.into(), Box::new(provider))
+            .is_some()
+    }
+
+    /// Returns `true` if a provider is registered under the given `id`.
+    pub fn contains(&self, id: &str) -> bool {
+        self.providers.contains_key(id)
+    }
+
+    /// Returns the default provider, or `None` if it is not of type
+    /// `Provider`.
+    pub fn default_provider<Provider: OpenMlsCryptoProvider + 'static>(&self) -> Option<&Provider> {
+        self.default.downcast_ref()
+    }
+
+    /// Returns the provider registered under the given `id`, or `None` if no
+    /// provider is registered under this `id` or if it is not of type
+    /// `Provider`.
+    pub fn get<Provider: OpenMlsCryptoProvider + 'static>(&self, id: &str) -> Option<&Provider> {
+        self.providers.get(id)?.downcast_ref()
+    }
+
+    /// Returns the provider for a group with the given configuration.
+    ///
+    /// Returns a [`CryptoProviderError::UnknownCryptoProvider`] error if no
+    /// provider is registered under the configured identifier and a
+    /// [`CryptoProviderError::ProviderTypeMismatch`] error if the provider is
+    /// not of type `Provider`.
+    pub fn for_config<Provider: OpenMlsCryptoProvider + 'static>(
+        &self,
+        mls_group_config: &MlsGroupConfig,
+    ) -> Result<&Provider, CryptoProviderError> {
+        let provider = match mls_group_config.crypto_provider() {
+            Some(id) => self
+                .providers
+                .get(id)
+                .ok_or_else(|| CryptoProviderError::UnknownCryptoProvider(id.to_string()))?,
+            None => &self.default,
+        };
+        provider
+            .downcast_ref()
+            .ok_or(CryptoProviderError::ProviderTypeMismatch)
+    }
+
+    /// Returns the provider for the given `mls_group`. See
+    /// [`CryptoProviders::for_config()`] for the errors.
+    pub fn for_group<Provider: OpenMlsCryptoProvider + 'static>(
+        &self,
+        mls_group: &MlsGroup,
+    ) -> Result<&Provider, CryptoProviderError> {
+        self.for_config(mls_group.configuration())
+    }
+}
+
+impl fmt::Debug for CryptoProviders {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CryptoProviders")
+            .field("providers", &self.providers.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
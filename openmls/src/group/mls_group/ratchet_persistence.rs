@@ -0,0 +1,172 @@
+//! # Sender ratchet persistence
+//!
+//! Every message that is encrypted or decrypted advances a sender ratchet of
+//! the group. When the state of the group is only persisted via
+//! [`MlsGroup::save()`] from time to time, a crash rewinds these ratchets.
+//! After loading the group again, already processed messages can be
+//! decrypted a second time, i.e., replays go unnoticed, and the own ratchets
+//! reuse key material for encryption.
+//!
+//! To prevent this, the state of individual sender ratchets can be persisted
+//! right after a message was encrypted or decrypted.
+//! [`MlsGroup::take_sender_ratchet_updates()`] returns the
+//! [`SenderRatchetState`] of every sender whose ratchets advanced since the
+//! last call. These are small compared to the state of the whole group. After
+//! loading the group, the persisted states are restored with
+//! [`MlsGroup::restore_sender_ratchet_state()`].
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tree::sender_ratchet::SenderRatchet;
+
+use super::{errors::SenderRatchetStateError, *};
+
+/// The state of the sender ratchets of a single member of a group in a
+/// single epoch.
+///
+/// The state contains secret key material and has to be stored as securely
+/// as the state of the group itself.
+#[derive(Serialize, Deserialize)]
+pub struct SenderRatchetState {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    sender: LeafNodeIndex,
+    // The serialized handshake and application sender ratchets. The ratchets
+    // are serialized right away, such that their key material doesn't have
+    // to be cloned.
+    sender_ratchets: Vec<u8>,
+}
+
+/// The handshake and application sender ratchets of a sender.
+type SenderRatchets = (Option<SenderRatchet>, Option<SenderRatchet>);
+
+impl SenderRatchetState {
+    /// Returns the group ID of the group the state belongs to.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Returns the epoch the state belongs to.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the leaf index of the sender whose ratchets the state
+    /// contains.
+    pub fn sender(&self) -> LeafNodeIndex {
+        self.sender
+    }
+}
+
+impl fmt::Debug for SenderRatchetState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SenderRatchetState")
+            .field("group_id", &self.group_id)
+            .field("epoch", &self.epoch)
+            .field("sender", &self.sender)
+            .field("sender_ratchets", &"***")
+            .finish()
+    }
+}
+
+impl MlsGroup {
+    // === Sender ratchet persistence ===
+
+    /// Returns the [`SenderRatchetState`] of every sender whose ratchets
+    /// advanced since the last call of this function or since the group was
+    /// last saved, whichever happened later.
+    ///
+    /// The ratchets of a sender advance when a message of the sender is
+    /// processed with [`MlsGroup::process_message()`], or, for the own
+    /// ratchets, when a message is encrypted. Ratchets of epochs that are no
+    /// longer available are skipped.
+    pub fn take_sender_ratchet_updates(&mut self) -> Vec<SenderRatchetState> {
+        std::mem::take(&mut self.updated_sender_ratchets)
+            .into_iter()
+            .filter_map(|(epoch, sender)| self.sender_ratchet_state(epoch, sender))
+            .collect()
+    }
+
+    /// Returns the [`SenderRatchetState`] of the `sender` in the given
+    /// `epoch`.
+    ///
+    /// Returns `None` if the secrets of the epoch are not available or the
+    /// ratchets of the sender were not used in the epoch.
+    pub fn sender_ratchet_state(
+        &self,
+        epoch: GroupEpoch,
+        sender: LeafNodeIndex,
+    ) -> Option<SenderRatchetState> {
+        if epoch > self.epoch() {
+            return None;
+        }
+        let message_secrets = self.group.message_secrets_for_epoch(epoch).ok()?;
+        let sender_ratchets = message_secrets.secret_tree().sender_ratchets(sender);
+        if matches!(sender_ratchets, (None, None)) {
+            return None;
+        }
+
+        Some(SenderRatchetState {
+            group_id: self.group_id().clone(),
+            epoch,
+            sender,
+            sender_ratchets: serde_json::to_vec(&sender_ratchets).ok()?,
+        })
+    }
+
+    /// Restores a [`SenderRatchetState`] that was persisted after the state
+    /// of the group. Ratchets are never set back to an earlier state: if a
+    /// ratchet in the group is further advanced than the one in the `state`,
+    /// it is kept, and key material of past generations is only kept if it
+    /// was not used according to either of them.
+    ///
+    /// Returns [`SenderRatchetStateError::WrongGroupId`] if the state belongs
+    /// to another group and [`SenderRatchetStateError::UnknownEpoch`] if the
+    /// secrets of the epoch of the state are not available.
+    /// Returns [`SenderRatchetStateError::MalformedState`] if the ratchets in
+    /// the state could not be decoded.
+    pub fn restore_sender_ratchet_state(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        state: SenderRatchetState,
+    ) -> Result<(), SenderRatchetStateError> {
+        if &state.group_id != self.group_id() {
+            return Err(SenderRatchetStateError::WrongGroupId);
+        }
+        if state.epoch > self.epoch() {
+            return Err(SenderRatchetStateError::UnknownEpoch);
+        }
+        let (handshake_sender_ratchet, application_sender_ratchet): SenderRatchets =
+            serde_json::from_slice(&state.sender_ratchets)
+                .map_err(|_| SenderRatchetStateError::MalformedState)?;
+        let ciphersuite = self.ciphersuite();
+        let message_secrets = self
+            .group
+            .message_secrets_mut(state.epoch)
+            .map_err(|_| SenderRatchetStateError::UnknownEpoch)?;
+        message_secrets.secret_tree_mut().merge_sender_ratchets(
+            ciphersuite,
+            backend,
+            state.sender,
+            handshake_sender_ratchet,
+            application_sender_ratchet,
+        )?;
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change();
+
+        Ok(())
+    }
+
+    /// Records that the ratchets of the `sender` in the given `epoch`
+    /// advanced.
+    pub(super) fn record_sender_ratchet_update(
+        &mut self,
+        epoch: GroupEpoch,
+        sender: LeafNodeIndex,
+    ) {
+        self.updated_sender_ratchets.insert((epoch, sender));
+    }
+}
@@ -0,0 +1,53 @@
+//! Roles of the members of an [`MlsGroup`].
+//!
+//! If the group context contains a [`RolesExtension`], all members reject
+//! proposals whose sender doesn't have the required [`Role`]. See
+//! [`RolesExtension`] for the permissions of the roles.
+
+use openmls_traits::{key_store::OpenMlsKeyStore, signatures::Signer};
+
+use crate::{
+    ciphersuite::hash_ref::ProposalRef,
+    extensions::{Extension, Role, RolesExtension},
+};
+
+use super::{errors::ProposalError, *};
+
+impl MlsGroup {
+    // === Roles ===
+
+    /// Returns the [`RolesExtension`] of the group context, if any.
+    pub fn roles(&self) -> Option<RolesExtension> {
+        self.group.context().extensions().roles()
+    }
+
+    /// Returns the [`Role`] of the member at the given leaf index.
+    ///
+    /// Returns `None` if the group doesn't use roles or if there is no member
+    /// at the leaf index.
+    pub fn member_role(&self, leaf_index: LeafNodeIndex) -> Option<Role> {
+        let roles = self.roles()?;
+        self.group
+            .public_group()
+            .leaf(leaf_index)
+            .map(|leaf| roles.role(leaf.credential().identity()))
+    }
+
+    /// Creates a proposal to replace the [`RolesExtension`] of the group
+    /// context with `roles`. The other extensions of the group context are
+    /// kept.
+    ///
+    /// Only owners can change the roles. Proposals of other members are
+    /// rejected by all members of the group.
+    pub fn propose_roles<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        roles: RolesExtension,
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposalError<KeyStore::Error>> {
+        let mut extensions = self.group.context().extensions().clone();
+        extensions.add_or_replace(Extension::from_custom(&roles));
+
+        self.propose_group_context_extensions(backend, signer, extensions)
+    }
+}
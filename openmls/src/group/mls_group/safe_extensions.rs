@@ -0,0 +1,134 @@
+//! Safe extension functions of an [`MlsGroup`].
+//!
+//! Safe extensions are [`CustomExtension`]s that are registered in the
+//! [`CustomExtensionRegistry`](crate::extensions::CustomExtensionRegistry) of
+//! the group's configuration. They can derive secrets from the key schedule
+//! that are separated from the secrets of all other extensions and keep their
+//! state in the [`AppDataDictionaryExtension`] of the group context.
+
+use openmls_traits::{key_store::OpenMlsKeyStore, signatures::Signer};
+use tls_codec::{Serialize, TlsSerialize, TlsSize, VLBytes};
+
+use crate::{
+    ciphersuite::hash_ref::ProposalRef,
+    extensions::{AppDataDictionaryExtension, CustomExtension, Extension, ExtensionType},
+};
+
+use super::{
+    errors::{ExportSecretError, ProposalError, SafeExtensionError},
+    exporting::export_secret_error,
+    *,
+};
+
+/// The context used to derive extension secrets.
+///
+/// ```c
+/// struct {
+///     ComponentID component_id;
+///     opaque label<V>;
+/// } ExtensionExportContext;
+/// ```
+#[derive(TlsSerialize, TlsSize)]
+struct ExtensionExportContext {
+    component_id: u16,
+    label: VLBytes,
+}
+
+impl MlsGroup {
+    // === Safe extensions ===
+
+    /// Exports a secret for the [`CustomExtension`] `C` from the current
+    /// epoch. Secrets of different extensions are independent of each other.
+    /// They are derived from the exporter secret with a separate label and
+    /// can't be obtained with [`MlsGroup::export_secret()`].
+    ///
+    /// Returns [`SafeExtensionError::UnregisteredExtension`] if `C` is not
+    /// registered in the group's configuration.
+    pub fn export_extension_secret<C: CustomExtension>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        label: &str,
+        key_length: usize,
+    ) -> Result<Vec<u8>, SafeExtensionError> {
+        self.check_extension_registration::<C>()?;
+        if !self.is_active() {
+            return Err(
+                ExportSecretError::GroupStateError(MlsGroupStateError::UseAfterEviction).into(),
+            );
+        }
+
+        let context = ExtensionExportContext {
+            component_id: C::EXTENSION_TYPE,
+            label: label.as_bytes().into(),
+        }
+        .tls_serialize_detached()
+        .map_err(|e| ExportSecretError::from(LibraryError::missing_bound_check(e)))?;
+
+        Ok(self
+            .group
+            .export_extension_secret(backend, &context, key_length)
+            .map_err(export_secret_error)?)
+    }
+
+    /// Returns the state of the [`CustomExtension`] `C` that is stored in the
+    /// [`AppDataDictionaryExtension`] of the group context.
+    ///
+    /// Returns `None` if there is no state for `C` or if it can't be
+    /// deserialized.
+    pub fn extension_state<C: CustomExtension>(&self) -> Option<C> {
+        self.group
+            .context()
+            .extensions()
+            .app_data_dictionary()
+            .and_then(|dictionary| dictionary.get(C::EXTENSION_TYPE).and_then(C::from_bytes))
+    }
+
+    /// Creates a GroupContextExtensions proposal that sets the state of the
+    /// [`CustomExtension`] `C` in the [`AppDataDictionaryExtension`] of the
+    /// group context. If `state` is `None`, the state of `C` is removed. All
+    /// other extensions of the group context and the state of all other
+    /// extensions are kept.
+    ///
+    /// Returns [`SafeExtensionError::UnregisteredExtension`] if `C` is not
+    /// registered in the group's configuration.
+    pub fn propose_extension_state<C: CustomExtension, KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        state: Option<&C>,
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposalError<KeyStore::Error>> {
+        self.check_extension_registration::<C>()?;
+
+        let mut extensions = self.group.context().extensions().clone();
+        let mut dictionary = extensions
+            .app_data_dictionary()
+            .unwrap_or_else(AppDataDictionaryExtension::new);
+        match state {
+            Some(state) => dictionary.insert(C::EXTENSION_TYPE, state.to_bytes()),
+            None => dictionary.remove(C::EXTENSION_TYPE),
+        };
+        if dictionary.is_empty() {
+            extensions.remove(ExtensionType::from(
+                AppDataDictionaryExtension::EXTENSION_TYPE,
+            ));
+        } else {
+            extensions.add_or_replace(Extension::from_custom(&dictionary));
+        }
+
+        self.propose_group_context_extensions(backend, signer, extensions)
+    }
+
+    /// Checks that the [`CustomExtension`] `C` is registered in the group's
+    /// configuration.
+    fn check_extension_registration<C: CustomExtension>(&self) -> Result<(), SafeExtensionError> {
+        if self
+            .configuration()
+            .custom_extensions()
+            .is_registered(ExtensionType::from(C::EXTENSION_TYPE))
+        {
+            Ok(())
+        } else {
+            Err(SafeExtensionError::UnregisteredExtension)
+        }
+    }
+}
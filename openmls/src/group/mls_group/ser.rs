@@ -4,9 +4,10 @@
 use super::*;
 use crate::schedule::psk::store::ResumptionPskStore;
 
+use openmls_traits::key_store::{MlsEntity, MlsEntityId};
 use serde::{
     ser::{SerializeStruct, Serializer},
-    Deserialize, Serialize,
+    Deserialize, Deserializer, Serialize,
 };
 
 /// Helper struct that contains the serializable values of an `MlsGroup.
@@ -23,6 +24,14 @@ pub struct SerializedMlsGroup {
     aad: Vec<u8>,
     resumption_psk_store: ResumptionPskStore,
     group_state: MlsGroupState,
+    #[serde(default)]
+    signature_key_pins: SignatureKeyPins,
+    #[serde(default)]
+    pending_config_update: Option<PendingConfigUpdate>,
+    #[serde(default)]
+    epoch_history: EpochHistory,
+    #[serde(default)]
+    commit_audit_log: Vec<CommitAuditEntry>,
 }
 
 impl SerializedMlsGroup {
@@ -35,7 +44,12 @@ impl SerializedMlsGroup {
             own_leaf_nodes: self.own_leaf_nodes,
             aad: self.aad,
             group_state: self.group_state,
+            signature_key_pins: self.signature_key_pins,
             state_changed: InnerState::Persisted,
+            updated_sender_ratchets: BTreeSet::new(),
+            pending_config_update: self.pending_config_update,
+            epoch_history: self.epoch_history,
+            commit_audit_log: self.commit_audit_log,
         }
     }
 }
@@ -45,7 +59,7 @@ impl Serialize for MlsGroup {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("SerializedMlsGroup", 6)?;
+        let mut state = serializer.serialize_struct("SerializedMlsGroup", 11)?;
         state.serialize_field("mls_group_config", &self.mls_group_config)?;
         state.serialize_field("group", &self.group)?;
         state.serialize_field("proposal_store", &self.proposal_store)?;
@@ -53,6 +67,24 @@ impl Serialize for MlsGroup {
         state.serialize_field("aad", &self.aad)?;
         state.serialize_field("resumption_psk_store", &self.group.resumption_psk_store)?;
         state.serialize_field("group_state", &self.group_state)?;
+        state.serialize_field("signature_key_pins", &self.signature_key_pins)?;
+        state.serialize_field("pending_config_update", &self.pending_config_update)?;
+        state.serialize_field("epoch_history", &self.epoch_history)?;
+        state.serialize_field("commit_audit_log", &self.commit_audit_log)?;
         state.end()
     }
 }
+
+impl<'de> Deserialize<'de> for MlsGroup {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        SerializedMlsGroup::deserialize(deserializer)
+            .map(|serialized_mls_group| serialized_mls_group.into_mls_group())
+    }
+}
+
+impl MlsEntity for MlsGroup {
+    const ID: MlsEntityId = MlsEntityId::GroupState;
+}
@@ -0,0 +1,204 @@
+//! # SFrame key derivation
+//!
+//! Conferencing applications commonly encrypt media with SFrame (RFC 9605)
+//! and use MLS to manage the keys. Section 5.2 of RFC 9605 defines how the
+//! SFrame base keys of the members are derived from an MLS epoch:
+//!
+//! ```text
+//! sframe_epoch_secret = MLS-Exporter("SFrame 1.0 Base Key", "", AEAD.Nh)
+//! base_key = HKDF-Expand(sframe_epoch_secret,
+//!                        encode_big_endian(sender_index, 4), AEAD.Nh)
+//! ```
+//!
+//! [`MlsGroup::derive_sframe_keys()`] returns the [`SframeEpochKeys`] of an
+//! epoch, i.e., the base keys of all members of the epoch, such that media
+//! keys rotate with the MLS epoch. The key IDs of the base keys are computed
+//! with [`SframeEpochKeys::key_id()`].
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::HashType, OpenMlsCryptoProvider};
+
+use super::*;
+
+const SFRAME_BASE_KEY_LABEL: &str = "SFrame 1.0 Base Key";
+
+/// The SFrame cipher suites as defined in Section 4.5 of RFC 9605.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum SframeCipher {
+    /// AES_128_CTR_HMAC_SHA256_80
+    Aes128CtrHmacSha256_80 = 0x0001,
+    /// AES_128_CTR_HMAC_SHA256_64
+    Aes128CtrHmacSha256_64 = 0x0002,
+    /// AES_128_CTR_HMAC_SHA256_32
+    Aes128CtrHmacSha256_32 = 0x0003,
+    /// AES_128_GCM_SHA256_128
+    Aes128GcmSha256_128 = 0x0004,
+    /// AES_256_GCM_SHA512_128
+    Aes256GcmSha512_128 = 0x0005,
+}
+
+impl SframeCipher {
+    /// Returns the hash function of the cipher suite.
+    pub fn hash_type(&self) -> HashType {
+        match self {
+            SframeCipher::Aes128CtrHmacSha256_80
+            | SframeCipher::Aes128CtrHmacSha256_64
+            | SframeCipher::Aes128CtrHmacSha256_32
+            | SframeCipher::Aes128GcmSha256_128 => HashType::Sha2_256,
+            SframeCipher::Aes256GcmSha512_128 => HashType::Sha2_512,
+        }
+    }
+
+    /// Returns the output size of the hash function of the cipher suite,
+    /// i.e., `AEAD.Nh`, which is also the length of the base keys.
+    pub fn hash_length(&self) -> usize {
+        self.hash_type().size()
+    }
+}
+
+impl From<SframeCipher> for u16 {
+    fn from(cipher: SframeCipher) -> Self {
+        cipher as u16
+    }
+}
+
+/// The SFrame base keys of the members of a group in a specific epoch.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SframeEpochKeys {
+    epoch: GroupEpoch,
+    cipher: SframeCipher,
+    base_keys: Vec<(LeafNodeIndex, Vec<u8>)>,
+}
+
+impl std::fmt::Debug for SframeEpochKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Don't leak the base keys into logs.
+        f.debug_struct("SframeEpochKeys")
+            .field("epoch", &self.epoch)
+            .field("cipher", &self.cipher)
+            .field(
+                "senders",
+                &self
+                    .base_keys
+                    .iter()
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl SframeEpochKeys {
+    /// Returns the epoch the keys were derived from.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the cipher suite the keys were derived for.
+    pub fn cipher(&self) -> SframeCipher {
+        self.cipher
+    }
+
+    /// Returns the base key of the member at `sender_index`, or `None` if the
+    /// leaf was blank in the epoch.
+    pub fn base_key(&self, sender_index: LeafNodeIndex) -> Option<&[u8]> {
+        self.base_keys
+            .iter()
+            .find(|(index, _)| *index == sender_index)
+            .map(|(_, base_key)| base_key.as_slice())
+    }
+
+    /// Returns the base keys of all members of the epoch in ascending order
+    /// of their leaf indices.
+    pub fn base_keys(&self) -> impl Iterator<Item = (LeafNodeIndex, &[u8])> {
+        self.base_keys
+            .iter()
+            .map(|(index, base_key)| (*index, base_key.as_slice()))
+    }
+
+    /// Returns the key ID of the base key of the member at `sender_index` as
+    /// defined in Section 5.2 of RFC 9605:
+    ///
+    /// ```text
+    /// KID = (context_id << S) | (sender_index << E) | (epoch & ((1 << E) - 1))
+    /// ```
+    ///
+    /// The `epoch_bits` (`E`) and `sender_bits` (`S - E`) are chosen by the
+    /// application, such that all senders and the epochs that are in use at
+    /// the same time can be distinguished. The `context_id` distinguishes
+    /// several media streams of the same sender.
+    ///
+    /// Returns `None` if the sender index doesn't fit into `sender_bits` or
+    /// if the key ID doesn't fit into 64 bits.
+    pub fn key_id(
+        &self,
+        sender_index: LeafNodeIndex,
+        context_id: u64,
+        epoch_bits: u32,
+        sender_bits: u32,
+    ) -> Option<u64> {
+        let context_shift = epoch_bits.checked_add(sender_bits)?;
+        if context_shift > u64::BITS {
+            return None;
+        }
+        let sender_index = u128::from(sender_index.u32());
+        if sender_index >> sender_bits != 0 {
+            return None;
+        }
+        let epoch = u128::from(self.epoch.as_u64()) & ((1u128 << epoch_bits) - 1);
+        let key_id =
+            (u128::from(context_id) << context_shift) | (sender_index << epoch_bits) | epoch;
+        u64::try_from(key_id).ok()
+    }
+}
+
+impl MlsGroup {
+    // === SFrame ===
+
+    /// Derives the SFrame base keys of all members of the group in `epoch`
+    /// for the SFrame `cipher` as defined in Section 5.2 of RFC 9605.
+    ///
+    /// The `epoch` is either the current epoch or one of the past epochs
+    /// whose secrets are kept according to
+    /// [`MlsGroupConfig::max_past_epochs`](crate::group::MlsGroupConfig::max_past_epochs),
+    /// which allows decrypting media that was sent before a commit.
+    /// Returns [`ExportSecretError::UnknownEpoch`] otherwise.
+    pub fn derive_sframe_keys(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        epoch: GroupEpoch,
+        cipher: SframeCipher,
+    ) -> Result<SframeEpochKeys, ExportSecretError> {
+        let sframe_epoch_secret = self.export_secret_for_epoch(
+            backend,
+            epoch,
+            SFRAME_BASE_KEY_LABEL,
+            &[],
+            cipher.hash_length(),
+        )?;
+        let members = self
+            .members_at_epoch(epoch)
+            .ok_or(ExportSecretError::UnknownEpoch)?;
+        let base_keys = members
+            .into_iter()
+            .map(|member| {
+                backend
+                    .crypto()
+                    .hkdf_expand(
+                        cipher.hash_type(),
+                        &sframe_epoch_secret,
+                        &member.index.u32().to_be_bytes(),
+                        cipher.hash_length(),
+                    )
+                    .map(|base_key| (member.index, base_key))
+                    .map_err(LibraryError::unexpected_crypto_error)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(SframeEpochKeys {
+            epoch,
+            cipher,
+            base_keys,
+        })
+    }
+}
@@ -0,0 +1,108 @@
+//! Message size estimation of an [`MlsGroup`].
+//!
+//! Delivery services commonly limit the size of the messages they accept. The
+//! functions in this module allow an application to learn the size of a
+//! message before creating it, taking the ciphersuite, the
+//! [`PaddingPolicy`] and the outgoing wire format of the group into account.
+
+use core_group::create_commit_params::CreateCommitParams;
+use openmls_traits::signatures::Signer;
+use tls_codec::Size;
+
+use crate::{ciphersuite::Signature, framing::private_message::vlbytes_len};
+
+use super::{errors::CommitToPendingProposalsError, *};
+
+/// The length of the `version` and `wire_format` fields of an `MLSMessage`.
+const MLS_MESSAGE_HEADER_LENGTH: usize = 2 + 2;
+
+impl MlsGroup {
+    // === Size estimation ===
+
+    /// Returns an upper bound for the length in bytes of the serialized
+    /// [`MlsMessageOut`] that [`MlsGroup::create_message()`] returns for a
+    /// message with a payload of `payload_length` bytes. The AAD set via
+    /// [`MlsGroup::set_aad()`] is taken into account.
+    ///
+    /// The estimate is exact unless the ciphersuite uses ECDSA signatures,
+    /// whose length varies by a few bytes.
+    pub fn estimate_message_size(&self, payload_length: usize) -> usize {
+        self.application_message_size(payload_length, self.aad.len())
+    }
+
+    /// Returns an upper bound for the length in bytes of a serialized
+    /// application message with a payload of `payload_length` bytes and
+    /// `authenticated_data_length` bytes of authenticated data.
+    pub(super) fn application_message_size(
+        &self,
+        payload_length: usize,
+        authenticated_data_length: usize,
+    ) -> usize {
+        let ciphersuite = self.ciphersuite();
+        // The content of an application message is the payload followed by
+        // the signature.
+        let content_length = vlbytes_len(payload_length)
+            + vlbytes_len(Signature::max_length(ciphersuite.signature_algorithm()));
+
+        MLS_MESSAGE_HEADER_LENGTH
+            + PrivateMessage::serialized_len(
+                self.group_id(),
+                authenticated_data_length,
+                content_length,
+                ciphersuite,
+                &self.configuration().padding_policy(),
+            )
+    }
+
+    /// Returns the length in bytes of the serialized [`MlsMessageOut`] of a
+    /// commit that covers all pending proposals of the group as well as the
+    /// given `inline_proposals`. The AAD set via [`MlsGroup::set_aad()`] and
+    /// the outgoing wire format policy of the group are taken into account.
+    ///
+    /// The commit is created to determine its size, but the state of the group
+    /// is not changed. A Welcome message that might be created along with the
+    /// commit is not included in the estimate.
+    pub fn estimate_commit_size<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        inline_proposals: &[Proposal],
+    ) -> Result<usize, CommitToPendingProposalsError<KeyStore::Error>> {
+        self.is_operational()?;
+
+        let params = CreateCommitParams::builder()
+            .framing_parameters(self.framing_parameters(ContentType::Commit))
+            .proposal_store(&self.proposal_store)
+            .inline_proposals(inline_proposals.to_vec())
+            .build();
+        let commit = self.group.create_commit(params, backend, signer)?.commit;
+
+        let size = match self
+            .configuration()
+            .wire_format_policy()
+            .outgoing_for(ContentType::Commit)
+        {
+            OutgoingWireFormatPolicy::AlwaysPlaintext => {
+                let mut plaintext: PublicMessage = commit.into();
+                plaintext.set_membership_tag(
+                    backend,
+                    self.group.message_secrets().membership_key(),
+                    self.group.message_secrets().serialized_context(),
+                )?;
+                MlsMessageOut::from(plaintext).tls_serialized_len()
+            }
+            OutgoingWireFormatPolicy::AlwaysCiphertext => {
+                MLS_MESSAGE_HEADER_LENGTH
+                    + PrivateMessage::serialized_len(
+                        self.group_id(),
+                        commit.authenticated_data().len(),
+                        PrivateMessage::content_length(&commit),
+                        self.ciphersuite(),
+                        &self.configuration().padding_policy(),
+                    )
+            }
+        };
+
+        Ok(size)
+    }
+}
@@ -0,0 +1,338 @@
+//! # Streaming encryption
+//!
+//! Large payloads such as attachments can be encrypted and decrypted as a
+//! stream, without holding the whole payload in memory.
+//!
+//! [`MlsGroup::create_message_stream()`] returns a [`MessageStreamWriter`]
+//! that encrypts everything written to it chunk by chunk. Every chunk is
+//! encrypted under its own key and nonce, which are derived from a stream
+//! secret. The stream secret is derived from the exporter secret of the
+//! current epoch, but can't be obtained with [`MlsGroup::export_secret()`].
+//! The last chunk is marked, such that a truncated stream is detected.
+//! [`MlsGroup::open_message_stream()`] returns a [`MessageStreamReader`] that
+//! decrypts such a stream.
+//!
+//! Note that the stream is only authenticated as coming from a member of the
+//! group in the epoch in which it was created, but not from a specific member.
+//!
+//! A stream consists of a header followed by a sequence of chunks:
+//!
+//! ```c
+//! struct {
+//!     opaque group_id<V>;
+//!     uint64 epoch;
+//!     opaque stream_id[16];
+//! } MessageStreamHeader;
+//!
+//! struct {
+//!     uint8 is_last;
+//!     uint32 length;
+//!     opaque ciphertext[length];
+//! } MessageStreamChunk;
+//! ```
+
+use std::io::{self, Read, Write};
+
+use openmls_traits::random::OpenMlsRand;
+use tls_codec::{
+    Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, TlsDeserialize,
+    TlsSerialize, TlsSize,
+};
+
+use crate::ciphersuite::{AeadKey, AeadNonce, Secret};
+
+use super::{errors::MessageStreamError, *};
+
+const STREAM_ID_LENGTH: usize = 16;
+
+/// The size of the plaintext of all chunks but the last one.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsSize)]
+struct MessageStreamHeader {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    stream_id: [u8; STREAM_ID_LENGTH],
+}
+
+/// The context for the derivation of the key and nonce of a chunk.
+#[derive(TlsSerialize, TlsSize)]
+struct ChunkContext {
+    chunk_index: u64,
+    is_last: u8,
+}
+
+/// The stream secret and the serialized header of a stream, which are needed
+/// to encrypt and decrypt its chunks.
+struct StreamKeys {
+    stream_secret: Secret,
+    serialized_header: Vec<u8>,
+}
+
+impl StreamKeys {
+    /// Derive the key and nonce for the chunk with the given `chunk_index`.
+    fn chunk_key_and_nonce(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        chunk_index: u64,
+        is_last: bool,
+    ) -> io::Result<(AeadKey, AeadNonce)> {
+        let ciphersuite = self.stream_secret.ciphersuite();
+        let context = ChunkContext {
+            chunk_index,
+            is_last: is_last.into(),
+        }
+        .tls_serialize_detached()
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let key = self
+            .stream_secret
+            .kdf_expand_label(backend, "key", &context, ciphersuite.aead_key_length())
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let nonce = self
+            .stream_secret
+            .kdf_expand_label(backend, "nonce", &context, ciphersuite.aead_nonce_length())
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        Ok((AeadKey::from_secret(key), AeadNonce::from_secret(nonce)))
+    }
+}
+
+impl MlsGroup {
+    // === Streaming encryption ===
+
+    /// Creates a [`MessageStreamWriter`] that encrypts everything written to
+    /// it and writes the resulting stream to `writer`. The stream header is
+    /// written to `writer` right away. [`MessageStreamWriter::finish()`] has to
+    /// be called to complete the stream.
+    ///
+    /// The stream can be decrypted by the members of the group in the current
+    /// epoch with [`MlsGroup::open_message_stream()`].
+    ///
+    /// Returns `MessageStreamError::MlsGroupStateError::UseAfterEviction`
+    /// if the member is no longer part of the group.
+    pub fn create_message_stream<'a, W: Write, Provider: OpenMlsCryptoProvider>(
+        &self,
+        backend: &'a Provider,
+        mut writer: W,
+    ) -> Result<MessageStreamWriter<'a, W, Provider>, MessageStreamError> {
+        if !self.is_active() {
+            return Err(MessageStreamError::GroupStateError(
+                MlsGroupStateError::UseAfterEviction,
+            ));
+        }
+
+        let header = MessageStreamHeader {
+            group_id: self.group_id().clone(),
+            epoch: self.epoch(),
+            stream_id: backend
+                .rand()
+                .random_array()
+                .map_err(|_| LibraryError::custom("Not enough randomness."))?,
+        };
+        let keys = self.stream_keys(backend, &header)?;
+        writer
+            .write_all(&keys.serialized_header)
+            .map_err(|_| MessageStreamError::WriteFailed)?;
+
+        Ok(MessageStreamWriter {
+            backend,
+            writer,
+            keys,
+            chunk_index: 0,
+            buffer: Vec::with_capacity(CHUNK_SIZE),
+        })
+    }
+
+    /// Reads the header of a stream created with
+    /// [`MlsGroup::create_message_stream()`] from `reader` and returns a
+    /// [`MessageStreamReader`] that decrypts the stream.
+    ///
+    /// Returns [`MessageStreamError::WrongGroupId`] or
+    /// [`MessageStreamError::WrongEpoch`] if the stream was not created in
+    /// the current epoch of this group.
+    pub fn open_message_stream<'a, R: Read, Provider: OpenMlsCryptoProvider>(
+        &self,
+        backend: &'a Provider,
+        mut reader: R,
+    ) -> Result<MessageStreamReader<'a, R, Provider>, MessageStreamError> {
+        let header = MessageStreamHeader::tls_deserialize(&mut reader)
+            .map_err(|_| MessageStreamError::MalformedHeader)?;
+        if &header.group_id != self.group_id() {
+            return Err(MessageStreamError::WrongGroupId);
+        }
+        if header.epoch != self.epoch() {
+            return Err(MessageStreamError::WrongEpoch);
+        }
+        let keys = self.stream_keys(backend, &header)?;
+
+        Ok(MessageStreamReader {
+            backend,
+            reader,
+            keys,
+            chunk_index: 0,
+            plaintext: vec![],
+            position: 0,
+            finished: false,
+        })
+    }
+
+    /// Derive the stream secret of the stream with the given `header`.
+    fn stream_keys(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        header: &MessageStreamHeader,
+    ) -> Result<StreamKeys, LibraryError> {
+        let ciphersuite = self.ciphersuite();
+        let stream_secret = self
+            .group
+            .export_stream_secret(backend, &header.stream_id)?;
+        let serialized_header = header
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+
+        Ok(StreamKeys {
+            stream_secret: Secret::from_slice(&stream_secret, self.group.version(), ciphersuite),
+            serialized_header,
+        })
+    }
+}
+
+/// Encrypts a stream created with [`MlsGroup::create_message_stream()`].
+///
+/// Everything written to the [`MessageStreamWriter`] is encrypted and written
+/// to the inner writer in chunks. The stream has to be completed with
+/// [`MessageStreamWriter::finish()`], otherwise the receiver considers it
+/// truncated.
+pub struct MessageStreamWriter<'a, W: Write, Provider: OpenMlsCryptoProvider> {
+    backend: &'a Provider,
+    writer: W,
+    keys: StreamKeys,
+    chunk_index: u64,
+    buffer: Vec<u8>,
+}
+
+impl<'a, W: Write, Provider: OpenMlsCryptoProvider> MessageStreamWriter<'a, W, Provider> {
+    /// Encrypts and writes the remaining data as the last chunk of the stream
+    /// and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let chunk = std::mem::take(&mut self.buffer);
+        self.write_chunk(&chunk, true)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_chunk(&mut self, chunk: &[u8], is_last: bool) -> io::Result<()> {
+        let (key, nonce) =
+            self.keys
+                .chunk_key_and_nonce(self.backend, self.chunk_index, is_last)?;
+        let ciphertext = key
+            .aead_seal(self.backend, chunk, &self.keys.serialized_header, &nonce)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let length = u32::try_from(ciphertext.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Chunk too large"))?;
+
+        self.writer.write_all(&[u8::from(is_last)])?;
+        self.writer.write_all(&length.to_be_bytes())?;
+        self.writer.write_all(&ciphertext)?;
+        self.chunk_index += 1;
+        Ok(())
+    }
+}
+
+impl<'a, W: Write, Provider: OpenMlsCryptoProvider> Write for MessageStreamWriter<'a, W, Provider> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut input = buf;
+        while !input.is_empty() {
+            // A full chunk is only written once more data follows, because the
+            // last chunk has to be marked.
+            if self.buffer.len() == CHUNK_SIZE {
+                let chunk = std::mem::take(&mut self.buffer);
+                self.write_chunk(&chunk, false)?;
+            }
+            let length = (CHUNK_SIZE - self.buffer.len()).min(input.len());
+            self.buffer.extend_from_slice(&input[..length]);
+            input = &input[length..];
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Decrypts a stream created with [`MlsGroup::create_message_stream()`].
+///
+/// Reading from the [`MessageStreamReader`] returns the decrypted payload.
+/// Chunks that fail to decrypt and truncated streams result in an error.
+pub struct MessageStreamReader<'a, R: Read, Provider: OpenMlsCryptoProvider> {
+    backend: &'a Provider,
+    reader: R,
+    keys: StreamKeys,
+    chunk_index: u64,
+    plaintext: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl<'a, R: Read, Provider: OpenMlsCryptoProvider> MessageStreamReader<'a, R, Provider> {
+    fn read_chunk(&mut self) -> io::Result<()> {
+        let mut chunk_header = [0u8; 5];
+        self.reader.read_exact(&mut chunk_header)?;
+        let is_last = match chunk_header[0] {
+            0 => false,
+            1 => true,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Malformed chunk header",
+                ))
+            }
+        };
+        let length = u32::from_be_bytes([
+            chunk_header[1],
+            chunk_header[2],
+            chunk_header[3],
+            chunk_header[4],
+        ]) as usize;
+        let ciphersuite = self.keys.stream_secret.ciphersuite();
+        if length > CHUNK_SIZE + ciphersuite.mac_length() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Chunk too large",
+            ));
+        }
+        let mut ciphertext = vec![0u8; length];
+        self.reader.read_exact(&mut ciphertext)?;
+
+        let (key, nonce) =
+            self.keys
+                .chunk_key_and_nonce(self.backend, self.chunk_index, is_last)?;
+        self.plaintext = key
+            .aead_open(
+                self.backend,
+                &ciphertext,
+                &self.keys.serialized_header,
+                &nonce,
+            )
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.position = 0;
+        self.chunk_index += 1;
+        self.finished = is_last;
+        Ok(())
+    }
+}
+
+impl<'a, R: Read, Provider: OpenMlsCryptoProvider> Read for MessageStreamReader<'a, R, Provider> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.plaintext.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            self.read_chunk()?;
+        }
+        let length = (self.plaintext.len() - self.position).min(buf.len());
+        buf[..length].copy_from_slice(&self.plaintext[self.position..self.position + length]);
+        self.position += length;
+        Ok(length)
+    }
+}
@@ -1,9 +1,18 @@
 use core_group::test_core_group::setup_client;
 use openmls_rust_crypto::OpenMlsRustCrypto;
-use openmls_traits::{key_store::OpenMlsKeyStore, OpenMlsCryptoProvider};
+use openmls_traits::{key_store::OpenMlsKeyStore, signatures::Signer, OpenMlsCryptoProvider};
 
 use crate::{
     binary_tree::LeafNodeIndex,
+    ciphersuite::hash_ref::ProposalRef,
+    credentials::{
+        basic_identity::{BasicIdentitySchema, IdentityFormat, DEFAULT_MAX_IDENTITY_LENGTH},
+        Credential, CredentialValidator,
+    },
+    extensions::{
+        ApplicationIdExtension, Extension, ExtensionType, Extensions, ExternalSender,
+        MediaTypeList, Role, RolesExtension, UnknownExtension,
+    },
     framing::*,
     group::{config::CryptoConfig, errors::*, *},
     key_packages::*,
@@ -12,6 +21,12 @@ use crate::{
         errors::ClientError, ActionType::Commit, CodecUse, MlsGroupTestSetup,
     },
     test_utils::*,
+    tree::sender_ratchet::SenderRatchetConfiguration,
+    treesync::{
+        errors::{LeafNodeValidationError, RatchetTreeVerificationError},
+        node::leaf_node::{Capabilities, SystemTimeProvider, TimeProvider},
+        RatchetTreeDelta, RatchetTreeIn, TreeNodeView,
+    },
 };
 
 #[apply(ciphersuites_and_backends)]
@@ -239,6 +254,131 @@ fn export_secret(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider)
     )
 }
 
+#[apply(ciphersuites_and_backends)]
+fn export_secret_for_epoch(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    // Keep the secrets of two past epochs, including the exporter secrets
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_past_epochs(2)
+        .retain_past_exporter_secrets(true)
+        .build();
+
+    // === Alice creates a group ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let mut exported_secrets = Vec::new();
+    for _ in 0..3 {
+        let epoch = alice_group.epoch();
+        let secret = alice_group
+            .export_secret(backend, "test", &[], ciphersuite.hash_length())
+            .expect("An unexpected error occurred.");
+        assert_eq!(
+            alice_group
+                .export_secret_for_epoch(backend, epoch, "test", &[], ciphersuite.hash_length())
+                .expect("An unexpected error occurred."),
+            secret
+        );
+        exported_secrets.push((epoch, secret));
+
+        alice_group
+            .self_update(backend, &alice_signer)
+            .expect("An unexpected error occurred.");
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("error merging pending commit");
+    }
+
+    // The two most recent past epochs are still available
+    for (epoch, secret) in &exported_secrets[1..] {
+        assert_eq!(
+            &alice_group
+                .export_secret_for_epoch(backend, *epoch, "test", &[], ciphersuite.hash_length())
+                .expect("An unexpected error occurred."),
+            secret
+        );
+    }
+
+    // The oldest epoch has been dropped and future epochs are unknown
+    let (oldest_epoch, _) = exported_secrets[0];
+    assert_eq!(
+        alice_group.export_secret_for_epoch(
+            backend,
+            oldest_epoch,
+            "test",
+            &[],
+            ciphersuite.hash_length()
+        ),
+        Err(ExportSecretError::UnknownEpoch)
+    );
+    assert_eq!(
+        alice_group.export_secret_for_epoch(
+            backend,
+            GroupEpoch::from(alice_group.epoch().as_u64() + 1),
+            "test",
+            &[],
+            ciphersuite.hash_length()
+        ),
+        Err(ExportSecretError::UnknownEpoch)
+    );
+
+    // Without the flag, or with immediate key deletion, no exporter secrets
+    // of past epochs are kept
+    let configs = [
+        MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .max_past_epochs(2)
+            .build(),
+        MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .max_past_epochs(2)
+            .retain_past_exporter_secrets(true)
+            .immediate_key_deletion(true)
+            .build(),
+    ];
+    for mls_group_config in configs {
+        let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+            setup_client("Alice", ciphersuite, backend);
+        let mut alice_group = MlsGroup::new(
+            backend,
+            &alice_signer,
+            &mls_group_config,
+            alice_credential_with_key,
+        )
+        .expect("An unexpected error occurred.");
+        let epoch = alice_group.epoch();
+
+        alice_group
+            .self_update(backend, &alice_signer)
+            .expect("An unexpected error occurred.");
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("error merging pending commit");
+
+        assert_eq!(
+            alice_group.export_secret_for_epoch(
+                backend,
+                epoch,
+                "test",
+                &[],
+                ciphersuite.hash_length()
+            ),
+            Err(ExportSecretError::UnknownEpoch)
+        );
+    }
+}
+
 #[apply(ciphersuites_and_backends)]
 fn test_invalid_plaintext(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
     // Some basic setup functions for the MlsGroup.
@@ -646,3 +786,4892 @@ fn remove_prosposal_by_ref(ciphersuite: Ciphersuite, backend: &impl OpenMlsCrypt
         _ => unreachable!("Expected a StagedCommit."),
     }
 }
+
+// Test that the group types can be shared across threads, e.g. behind an
+// (async) mutex in a multi-threaded server.
+#[test]
+fn group_types_are_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<MlsGroup>();
+    assert_send_sync::<MlsGroupConfig>();
+    assert_send_sync::<PublicGroup>();
+    assert_send_sync::<StagedCommit>();
+    assert_send_sync::<ProcessedMessage>();
+    assert_send_sync::<MlsMessageIn>();
+    assert_send_sync::<MlsMessageOut>();
+}
+
+// Requires the AAD of application messages to start with the given prefix.
+struct RequireAadPrefix(&'static [u8]);
+
+impl AadValidator for RequireAadPrefix {
+    fn validate(&self, _sender: &Sender, content_type: ContentType, aad: &[u8]) -> bool {
+        content_type != ContentType::Application || aad.starts_with(self.0)
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn per_message_aad(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .aad_validator(RequireAadPrefix(b"thread-"))
+            .build(),
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // The AAD of a single message doesn't change the AAD of the group.
+    alice_group.set_aad(b"sticky");
+    let message = alice_group
+        .create_message_with_aad(backend, &alice_signer, b"Hello", b"thread-1")
+        .expect("Could not create message.");
+    assert_eq!(alice_group.aad(), b"sticky");
+
+    let processed_message = bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    assert_eq!(processed_message.content_type(), ContentType::Application);
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(application_message) => {
+            assert_eq!(application_message.aad(), b"thread-1");
+            assert_eq!(application_message.into_bytes(), b"Hello");
+        }
+        _ => panic!("Expected an application message."),
+    }
+
+    // Bob rejects messages with an AAD that doesn't pass the validator.
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello")
+        .expect("Could not create message.");
+    let error = bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect_err("Processed a message with a rejected AAD.");
+    assert_eq!(error, ProcessMessageError::InvalidAad);
+}
+
+// Rejects all credentials with the given identity.
+struct DenyIdentity(&'static [u8]);
+
+impl CredentialValidator for DenyIdentity {
+    fn validate(&self, credential: &Credential) -> bool {
+        credential.identity() != self.0
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn credential_validator(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    // === Alice creates a group and refuses to add Bob ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .credential_validator(DenyIdentity(b"Bob"))
+            .build(),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let error = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect_err("Added a member with a rejected credential.");
+    assert!(matches!(error, AddMembersError::InvalidCredential));
+
+    // === Alice adds Bob, who does not accept Alice ===
+    alice_group.set_configuration(&mls_group_config);
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+
+    let error = MlsGroup::new_from_welcome(
+        backend,
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .credential_validator(DenyIdentity(b"Alice"))
+            .build(),
+        welcome.clone(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect_err("Joined a group with a rejected member.");
+    assert!(matches!(error, WelcomeError::InvalidCredential));
+
+    // === Bob joins and does not accept Charlie ===
+    let bob_kpb = {
+        let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+            setup_client("Bob", ciphersuite, backend);
+        bob_kpb
+    };
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .credential_validator(DenyIdentity(b"Charlie"))
+            .build(),
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // === Alice adds Charlie ===
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[charlie_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    let error = bob_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type"),
+        )
+        .expect_err("Processed a commit adding a rejected member.");
+    assert_eq!(error, ProcessMessageError::InvalidCredential);
+}
+
+// Treats all credentials with the given identity as revoked.
+struct RevokeIdentity(&'static [u8]);
+
+impl CredentialValidator for RevokeIdentity {
+    fn validate(&self, _credential: &Credential) -> bool {
+        true
+    }
+
+    fn is_revoked(&self, credential: &Credential) -> bool {
+        credential.identity() == self.0
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn revoked_credential(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, bob_signer, _bob_pk) = setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    // === Alice creates a group with Bob ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // === Bob's credential gets revoked ===
+    alice_group.set_configuration(
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .credential_validator(RevokeIdentity(b"Bob"))
+            .build(),
+    );
+    let revoked_members: Vec<Member> = alice_group.revoked_members().collect();
+    assert_eq!(revoked_members.len(), 1);
+    assert_eq!(revoked_members[0].index, LeafNodeIndex::new(1));
+
+    // === Alice rejects Bob's commit ===
+    let (commit, _welcome, _group_info) = bob_group
+        .self_update(backend, &bob_signer)
+        .expect("Could not create self update.");
+    let error = alice_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type"),
+        )
+        .expect_err("Processed a commit from a revoked member.");
+    assert_eq!(
+        error,
+        ProcessMessageError::RevokedCredential(LeafNodeIndex::new(1))
+    );
+    // === Alice rejects an External Commit from a revoked identity ===
+    let (charlie_credential_with_key, _charlie_kpb, charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    alice_group.set_configuration(
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .credential_validator(RevokeIdentity(b"Charlie"))
+            .build(),
+    );
+    let group_info: MlsMessageIn = alice_group
+        .export_group_info(backend, &alice_signer, true)
+        .expect("Could not export group info.")
+        .into();
+    let (_charlie_group, commit, _group_info) = MlsGroup::join_by_external_commit(
+        backend,
+        &charlie_signer,
+        None,
+        group_info
+            .into_verifiable_group_info()
+            .expect("Unexpected message type."),
+        &mls_group_config,
+        b"",
+        charlie_credential_with_key,
+    )
+    .expect("Could not join by external commit.");
+    let error = alice_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type"),
+        )
+        .expect_err("Processed an External Commit from a revoked identity.");
+    assert_eq!(error, ProcessMessageError::InvalidCredential);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn loaded_group_requires_credential_validator(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, bob_signer, _bob_pk) = setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let validating_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .credential_validator(DenyIdentity(b"Mallory"))
+        .build();
+
+    // === Alice creates a group with Bob, who validates credentials ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &validating_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // === Bob saves and loads the group without the validator ===
+    let mut saved_group = vec![];
+    bob_group
+        .save(&mut saved_group)
+        .expect("Could not save group.");
+    let mut bob_group = MlsGroup::load(saved_group.as_slice()).expect("Could not load group.");
+
+    // Bob refuses to process messages and to add members.
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello")
+        .expect("Could not create message.");
+    let error = bob_group
+        .process_message(backend, message.clone().into_protocol_message().unwrap())
+        .expect_err("Processed a message without the credential validator.");
+    assert_eq!(error, ProcessMessageError::MissingCredentialValidator);
+    let error = bob_group
+        .add_members(backend, &bob_signer, &[charlie_kpb.key_package().clone()])
+        .expect_err("Added a member without the credential validator.");
+    assert!(matches!(error, AddMembersError::MissingCredentialValidator));
+
+    // === Bob sets the validator again ===
+    bob_group.set_configuration(&validating_config);
+    bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    bob_group
+        .add_members(backend, &bob_signer, &[charlie_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+}
+
+#[apply(ciphersuites_and_backends)]
+fn signature_key_pinning(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    // A second client that claims Bob's identity with a new signature key.
+    let (_mallory_credential, mallory_kpb, _mallory_signer, _mallory_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_signature_key_pinning(true)
+        .build();
+
+    // === Alice creates a group with Bob ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // Both members pinned both identities
+    for group in [&alice_group, &bob_group] {
+        assert_eq!(
+            group.signature_key_pins().get(b"Alice"),
+            Some(&alice_credential_with_key.signature_key)
+        );
+        assert_eq!(
+            group.signature_key_pins().get(b"Bob"),
+            Some(&bob_credential_with_key.signature_key)
+        );
+    }
+
+    // === Alice adds a client with Bob's identity but another key ===
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[mallory_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    let processed_message = bob_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type"),
+        )
+        .expect("Could not process message.");
+    assert_eq!(
+        processed_message.warnings(),
+        &[ProcessingWarning::KeyChanged {
+            identity: b"Bob".to_vec(),
+            pinned_signature_key: bob_credential_with_key.signature_key.clone(),
+            signature_key: mallory_kpb
+                .key_package()
+                .leaf_node()
+                .signature_key()
+                .clone(),
+        }]
+    );
+
+    // The pinned key is not replaced
+    assert_eq!(
+        bob_group.signature_key_pins().get(b"Bob"),
+        Some(&bob_credential_with_key.signature_key)
+    );
+    // === Keys of new members are only pinned when the commit is merged ===
+    let (charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    alice_group.clear_pending_commit();
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[charlie_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    let processed_message = bob_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type"),
+        )
+        .expect("Could not process message.");
+    assert!(processed_message.warnings().is_empty());
+    assert_eq!(bob_group.signature_key_pins().get(b"Charlie"), None);
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    {
+        bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit.");
+    } else {
+        unreachable!("Expected a StagedCommit.");
+    }
+    assert_eq!(
+        bob_group.signature_key_pins().get(b"Charlie"),
+        Some(&charlie_credential_with_key.signature_key)
+    );
+}
+
+/// A provider of another type than the default provider, e.g., a FIPS
+/// validated backend.
+#[derive(Default)]
+struct FipsBackend(OpenMlsRustCrypto);
+
+impl OpenMlsCryptoProvider for FipsBackend {
+    type CryptoProvider = <OpenMlsRustCrypto as OpenMlsCryptoProvider>::CryptoProvider;
+    type RandProvider = <OpenMlsRustCrypto as OpenMlsCryptoProvider>::RandProvider;
+    type KeyStoreProvider = <OpenMlsRustCrypto as OpenMlsCryptoProvider>::KeyStoreProvider;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        self.0.crypto()
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        self.0.rand()
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        self.0.key_store()
+    }
+}
+
+#[apply(ciphersuites)]
+fn crypto_provider_selection(ciphersuite: Ciphersuite) {
+    let providers = CryptoProviders::new(OpenMlsRustCrypto::default())
+        .with_provider("fips", FipsBackend::default());
+    let fips_backend: &FipsBackend = providers
+        .get("fips")
+        .expect("No provider registered as fips.");
+    let default_backend: &OpenMlsRustCrypto = providers
+        .default_provider()
+        .expect("Unexpected type of the default provider.");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, fips_backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .crypto_provider("fips")
+        .build();
+
+    let alice_group = MlsGroup::new_with_group_id(
+        fips_backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    assert_eq!(alice_group.configuration().crypto_provider(), Some("fips"));
+    assert!(std::ptr::eq(
+        providers
+            .for_group::<FipsBackend>(&alice_group)
+            .expect("Error selecting the provider."),
+        fips_backend
+    ));
+    assert_eq!(
+        providers.for_group::<OpenMlsRustCrypto>(&alice_group).err(),
+        Some(CryptoProviderError::ProviderTypeMismatch)
+    );
+
+    // Groups without a configured provider use the default provider.
+    let default_config = MlsGroupConfig::test_default(ciphersuite);
+    assert!(std::ptr::eq(
+        providers
+            .for_config::<OpenMlsRustCrypto>(&default_config)
+            .expect("Error selecting the provider."),
+        default_backend
+    ));
+
+    // Unknown providers don't fall back to the default provider.
+    let unknown_config = MlsGroupConfig::builder().crypto_provider("unknown").build();
+    assert!(!providers.contains("unknown"));
+    assert_eq!(
+        providers
+            .for_config::<OpenMlsRustCrypto>(&unknown_config)
+            .err(),
+        Some(CryptoProviderError::UnknownCryptoProvider(
+            "unknown".to_string()
+        ))
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn ratchet_tree_provider(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    // The ratchet tree is not included in the Welcome.
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(false)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id.clone(),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_, welcome, _) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+
+    // The tree is fetched lazily for the group and epoch of the Welcome.
+    let ratchet_tree: RatchetTreeIn = alice_group.export_ratchet_tree().into();
+    let provider = |requested_group_id: &GroupId, epoch: GroupEpoch| {
+        assert_eq!(requested_group_id, &group_id);
+        assert_eq!(epoch, GroupEpoch::from(1));
+        Some(ratchet_tree.clone())
+    };
+    let bob_group = MlsGroup::new_from_welcome_with_ratchet_tree_provider(
+        backend,
+        &mls_group_config,
+        welcome.clone(),
+        &provider,
+    )
+    .expect("Could not join the group.");
+    assert_eq!(
+        bob_group.export_ratchet_tree(),
+        alice_group.export_ratchet_tree()
+    );
+
+    // Joining fails if the provider doesn't have the tree.
+    let provider = |_: &GroupId, _: GroupEpoch| -> Option<RatchetTreeIn> { None };
+    let err = MlsGroup::new_from_welcome_with_ratchet_tree_provider(
+        backend,
+        &mls_group_config,
+        welcome,
+        &provider,
+    )
+    .expect_err("Could join the group without a ratchet tree.");
+    assert!(matches!(err, WelcomeError::MissingRatchetTree));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn targeted_messages(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+    use crate::messages::targeted_message::TargetedMessage;
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_, welcome, _) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+
+    let mut bob_group =
+        MlsGroup::new_from_welcome(backend, &mls_group_config, welcome.clone(), None)
+            .expect("Could not join the group.");
+    let charlie_group = MlsGroup::new_from_welcome(backend, &mls_group_config, welcome, None)
+        .expect("Could not join the group.");
+
+    // Alice sends a message that only Bob can read.
+    alice_group.set_aad(b"targeted aad");
+    let targeted_message = alice_group
+        .create_targeted_message(
+            backend,
+            &alice_signer,
+            bob_group.own_leaf_index(),
+            b"Hello Bob",
+        )
+        .expect("Could not create targeted message.");
+    assert_eq!(
+        targeted_message.recipient_leaf_index(),
+        LeafNodeIndex::new(1)
+    );
+
+    // The message survives a round trip through the wire encoding.
+    let serialized = targeted_message
+        .tls_serialize_detached()
+        .expect("Could not serialize targeted message.");
+    let targeted_message = TargetedMessage::tls_deserialize(&mut serialized.as_slice())
+        .expect("Could not deserialize targeted message.");
+
+    let processed = bob_group
+        .process_targeted_message(backend, targeted_message.clone())
+        .expect("Could not process targeted message.");
+    assert_eq!(processed.payload(), b"Hello Bob");
+    assert_eq!(processed.authenticated_data(), b"targeted aad");
+    assert_eq!(processed.sender_leaf_index(), alice_group.own_leaf_index());
+    assert_eq!(
+        processed.credential(),
+        &alice_credential_with_key.credential
+    );
+
+    // Charlie can't process a message that is targeted at Bob.
+    let err = charlie_group
+        .process_targeted_message(backend, targeted_message.clone())
+        .expect_err("Charlie could process a message targeted at Bob.");
+    assert_eq!(err, ProcessTargetedMessageError::WrongRecipient);
+
+    // There is no member at an unknown leaf index.
+    let err = alice_group
+        .create_targeted_message(backend, &alice_signer, LeafNodeIndex::new(7), b"Hello")
+        .expect_err("Could create a targeted message for an unknown recipient.");
+    assert_eq!(err, CreateTargetedMessageError::UnknownRecipient);
+
+    // Targeted messages are only valid in the epoch they were created in.
+    let (commit, _, _) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    {
+        bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Could not merge commit.");
+    } else {
+        unreachable!("Expected a StagedCommit.");
+    }
+
+    let err = bob_group
+        .process_targeted_message(backend, targeted_message)
+        .expect_err("Could process a targeted message from a past epoch.");
+    assert_eq!(err, ProcessTargetedMessageError::WrongEpoch);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn message_size_estimation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use openmls_traits::types::SignatureScheme;
+    use tls_codec::Serialize as TlsSerializeTrait;
+
+    // ECDSA signatures vary in length, so the estimate may differ slightly.
+    let exact = matches!(
+        ciphersuite.signature_algorithm(),
+        SignatureScheme::ED25519 | SignatureScheme::ED448
+    );
+
+    for (i, (wire_format_policy, padding_policy)) in [
+        (PURE_PLAINTEXT_WIRE_FORMAT_POLICY, PaddingPolicy::None),
+        (PURE_CIPHERTEXT_WIRE_FORMAT_POLICY, PaddingPolicy::None),
+        (
+            PURE_CIPHERTEXT_WIRE_FORMAT_POLICY,
+            PaddingPolicy::Multiple(32),
+        ),
+        (PURE_CIPHERTEXT_WIRE_FORMAT_POLICY, PaddingPolicy::Padme),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let group_id = GroupId::from_slice(&[i as u8]);
+
+        let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+            setup_client("Alice", ciphersuite, backend);
+        let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+            setup_client("Bob", ciphersuite, backend);
+
+        let mls_group_config = MlsGroupConfigBuilder::new()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .wire_format_policy(wire_format_policy)
+            .padding_policy(padding_policy)
+            .build();
+
+        let mut alice_group = MlsGroup::new_with_group_id(
+            backend,
+            &alice_signer,
+            &mls_group_config,
+            group_id,
+            alice_credential_with_key,
+        )
+        .expect("An unexpected error occurred.");
+        alice_group.set_aad(b"size estimation aad");
+
+        // Application message
+        let payload = vec![0x42; 1000];
+        let estimate = alice_group.estimate_message_size(payload.len());
+        let message = alice_group
+            .create_message(backend, &alice_signer, &payload)
+            .expect("Could not create message.");
+        let size = message
+            .tls_serialize_detached()
+            .expect("Could not serialize message.")
+            .len();
+        if exact {
+            assert_eq!(estimate, size);
+        } else {
+            assert!(estimate >= size);
+        }
+
+        // Commit
+        let add_proposal = Proposal::Add(AddProposal {
+            key_package: bob_kpb.key_package().clone(),
+        });
+        let estimate = alice_group
+            .estimate_commit_size(backend, &alice_signer, &[add_proposal])
+            .expect("Could not estimate commit size.");
+        let (commit, _, _) = alice_group
+            .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+            .expect("Could not add member.");
+        let size = commit
+            .tls_serialize_detached()
+            .expect("Could not serialize message.")
+            .len();
+        if exact {
+            assert_eq!(estimate, size);
+        } else {
+            assert!(estimate.abs_diff(size) <= 8);
+        }
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn fragmentation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use std::time::Duration;
+
+    use tls_codec::Serialize as TlsSerializeTrait;
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let fragmentation_config = FragmentationConfig::new(300, Duration::from_secs(60), 1 << 20, 64);
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .fragmentation_config(fragmentation_config.clone())
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+    let (_, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Could not join the group.");
+
+    // Alice sends a message that doesn't fit into a single fragment.
+    let payload: Vec<u8> = (0..2000).map(|i| i as u8).collect();
+    alice_group.set_aad(b"fragmented aad");
+    let messages = alice_group
+        .create_fragmented_message(backend, &alice_signer, &payload)
+        .expect("Could not create fragmented message.");
+    assert!(messages.len() > 1);
+    for message in &messages {
+        let size = message
+            .tls_serialize_detached()
+            .expect("Could not serialize message.")
+            .len();
+        assert!(size <= fragmentation_config.max_message_size());
+    }
+
+    // Bob reassembles the message.
+    let mut reassembler = FragmentReassembler::new(fragmentation_config.clone());
+    let mut reassembled = None;
+    for (i, message) in messages.iter().enumerate() {
+        let processed_message = bob_group
+            .process_message(backend, message.clone().into_protocol_message().unwrap())
+            .expect("Could not process message.");
+        let result = reassembler
+            .process(processed_message)
+            .expect("Could not reassemble message.");
+        if i + 1 < messages.len() {
+            assert!(result.is_none());
+            assert_eq!(reassembler.pending_messages(), 1);
+        } else {
+            reassembled = result;
+        }
+    }
+    let reassembled = reassembled.expect("The message was not reassembled.");
+    assert_eq!(reassembled.sender(), alice_group.own_leaf_index());
+    assert_eq!(
+        reassembled.credential(),
+        &alice_credential_with_key.credential
+    );
+    assert_eq!(reassembled.authenticated_data(), b"fragmented aad");
+    assert_eq!(reassembled.payload(), payload.as_slice());
+    assert_eq!(reassembler.pending_messages(), 0);
+    assert_eq!(reassembler.pending_bytes(), 0);
+
+    // A reassembler with a small memory limit rejects the message.
+    let messages = alice_group
+        .create_fragmented_message(backend, &alice_signer, &payload)
+        .expect("Could not create fragmented message.");
+    let mut reassembler = FragmentReassembler::new(FragmentationConfig::new(
+        300,
+        Duration::from_secs(60),
+        500,
+        64,
+    ));
+    let results: Vec<_> = messages
+        .iter()
+        .map(|message| {
+            let processed_message = bob_group
+                .process_message(backend, message.clone().into_protocol_message().unwrap())
+                .expect("Could not process message.");
+            reassembler.process(processed_message)
+        })
+        .collect();
+    assert!(results.contains(&Err(ReassemblyError::MemoryLimitExceeded)));
+    assert!(!results.iter().any(|result| matches!(result, Ok(Some(_)))));
+    assert!(reassembler.pending_bytes() <= 500);
+
+    // Incomplete messages are dropped after the timeout.
+    let messages = alice_group
+        .create_fragmented_message(backend, &alice_signer, &payload)
+        .expect("Could not create fragmented message.");
+    let mut reassembler =
+        FragmentReassembler::new(FragmentationConfig::new(300, Duration::ZERO, 1 << 20, 64));
+    for message in messages {
+        let processed_message = bob_group
+            .process_message(backend, message.into_protocol_message().unwrap())
+            .expect("Could not process message.");
+        let result = reassembler
+            .process(processed_message)
+            .expect("Could not process fragment.");
+        assert!(result.is_none());
+        assert_eq!(reassembler.pending_messages(), 1);
+    }
+
+    // Messages that fit into a single fragment are returned right away.
+    let messages = alice_group
+        .create_fragmented_message(backend, &alice_signer, b"short")
+        .expect("Could not create fragmented message.");
+    assert_eq!(messages.len(), 1);
+    let processed_message = bob_group
+        .process_message(
+            backend,
+            messages[0].clone().into_protocol_message().unwrap(),
+        )
+        .expect("Could not process message.");
+    let reassembled = reassembler
+        .process(processed_message)
+        .expect("Could not process fragment.")
+        .expect("The message was not reassembled.");
+    assert_eq!(reassembled.into_payload(), b"short");
+
+    // The number of announced fragments counts towards the memory limit, and
+    // the number of incomplete messages is bounded.
+    let mut reassembler = FragmentReassembler::new(FragmentationConfig::new(
+        300,
+        Duration::from_secs(60),
+        1 << 20,
+        1,
+    ));
+    let mut fragment = |message_id: u8, fragment_count: u16| {
+        let mut header = vec![message_id; 16];
+        header.extend_from_slice(&0u16.to_be_bytes());
+        header.extend_from_slice(&fragment_count.to_be_bytes());
+        alice_group.set_aad(&header);
+        let message = alice_group
+            .create_message(backend, &alice_signer, b"fragment")
+            .expect("Could not create message.");
+        bob_group
+            .process_message(backend, message.into_protocol_message().unwrap())
+            .expect("Could not process message.")
+    };
+    assert_eq!(
+        reassembler.process(fragment(1, u16::MAX)),
+        Err(ReassemblyError::MemoryLimitExceeded)
+    );
+    assert_eq!(reassembler.pending_messages(), 0);
+    assert_eq!(reassembler.process(fragment(2, 2)), Ok(None));
+    assert_eq!(
+        reassembler.process(fragment(3, 2)),
+        Err(ReassemblyError::TooManyPendingMessages)
+    );
+    assert_eq!(reassembler.pending_messages(), 1);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn message_streams(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use std::io::{Read, Write};
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Could not join the group.");
+
+    // Alice encrypts a payload that spans multiple chunks.
+    let payload: Vec<u8> = (0..200_000).map(|i| (i % 251) as u8).collect();
+    let mut writer = alice_group
+        .create_message_stream(backend, Vec::new())
+        .expect("Could not create message stream.");
+    for piece in payload.chunks(1000) {
+        writer.write_all(piece).expect("Could not write to stream.");
+    }
+    let stream = writer.finish().expect("Could not finish stream.");
+
+    // Bob decrypts the stream.
+    let mut reader = bob_group
+        .open_message_stream(backend, stream.as_slice())
+        .expect("Could not open message stream.");
+    let mut decrypted = Vec::new();
+    reader
+        .read_to_end(&mut decrypted)
+        .expect("Could not read from stream.");
+    assert_eq!(decrypted, payload);
+
+    // An empty stream can be decrypted as well.
+    let empty_stream = alice_group
+        .create_message_stream(backend, Vec::new())
+        .expect("Could not create message stream.")
+        .finish()
+        .expect("Could not finish stream.");
+    let mut decrypted = Vec::new();
+    bob_group
+        .open_message_stream(backend, empty_stream.as_slice())
+        .expect("Could not open message stream.")
+        .read_to_end(&mut decrypted)
+        .expect("Could not read from stream.");
+    assert!(decrypted.is_empty());
+
+    // A stream without its last chunk is detected.
+    let last_chunk_length = 5 + payload.len() % (64 * 1024) + ciphersuite.mac_length();
+    let truncated_stream = &stream[..stream.len() - last_chunk_length];
+    let mut reader = bob_group
+        .open_message_stream(backend, truncated_stream)
+        .expect("Could not open message stream.");
+    let error = reader
+        .read_to_end(&mut Vec::new())
+        .expect_err("Truncated stream was accepted.");
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    // A modified chunk is detected.
+    let mut modified_stream = stream.clone();
+    let middle = modified_stream.len() / 2;
+    modified_stream[middle] ^= 0xff;
+    let mut reader = bob_group
+        .open_message_stream(backend, modified_stream.as_slice())
+        .expect("Could not open message stream.");
+    let error = reader
+        .read_to_end(&mut Vec::new())
+        .expect_err("Modified stream was accepted.");
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // The stream can't be opened in a later epoch.
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update own leaf.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    assert!(matches!(
+        alice_group.open_message_stream(backend, stream.as_slice()),
+        Err(MessageStreamError::WrongEpoch)
+    ));
+
+    // Stream secrets can't be obtained with the public exporter.
+    let stream_id = [0x42; 16];
+    let stream_secret = alice_group
+        .group
+        .export_stream_secret(backend, &stream_id)
+        .expect("Could not export stream secret.");
+    for label in ["MessageStream", "stream"] {
+        assert_ne!(
+            stream_secret,
+            alice_group
+                .export_secret(backend, label, &stream_id, ciphersuite.hash_length())
+                .expect("Could not export secret.")
+        );
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn peek_mls_message_in(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    // Handshake messages are sent as public messages.
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        group_id.clone(),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let serialize_and_parse = |message: MlsMessageOut| {
+        let bytes = message
+            .tls_serialize_detached()
+            .expect("Could not serialize message.");
+        MlsMessageIn::tls_deserialize(&mut bytes.as_slice()).expect("Could not parse message.")
+    };
+
+    // Application message
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello")
+        .expect("Could not create message.");
+    let message = serialize_and_parse(message);
+    assert_eq!(message.wire_format(), WireFormat::PrivateMessage);
+    assert_eq!(message.group_id(), Some(&group_id));
+    assert_eq!(message.epoch(), Some(alice_group.epoch()));
+    assert_eq!(message.content_type(), Some(ContentType::Application));
+    assert_eq!(message.sender(), None);
+    let protocol_message = message
+        .into_protocol_message()
+        .expect("Unexpected message type.");
+    assert_eq!(protocol_message.sender(), None);
+
+    // Commit
+    let (commit, _, _) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update own leaf.");
+    let message = serialize_and_parse(commit);
+    assert_eq!(message.wire_format(), WireFormat::PublicMessage);
+    assert_eq!(message.group_id(), Some(&group_id));
+    assert_eq!(message.epoch(), Some(alice_group.epoch()));
+    assert_eq!(message.content_type(), Some(ContentType::Commit));
+    assert_eq!(
+        message.sender(),
+        Some(&Sender::build_member(alice_group.own_leaf_index()))
+    );
+    assert!(message.clone().into_verifiable_group_info().is_none());
+    let protocol_message = message
+        .into_protocol_message()
+        .expect("Unexpected message type.");
+    assert_eq!(
+        protocol_message.sender(),
+        Some(&Sender::build_member(alice_group.own_leaf_index()))
+    );
+
+    // Group info
+    let group_info = alice_group
+        .export_group_info(backend, &alice_signer, true)
+        .expect("Could not export group info.");
+    let message = serialize_and_parse(group_info);
+    assert_eq!(message.wire_format(), WireFormat::GroupInfo);
+    assert_eq!(message.group_id(), None);
+    assert_eq!(message.epoch(), None);
+    assert_eq!(message.content_type(), None);
+    assert!(message.into_verifiable_group_info().is_some());
+}
+
+#[cfg(feature = "serde-types")]
+#[apply(ciphersuites_and_backends)]
+fn serde_protocol_types(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let application_message = alice_group
+        .create_message(backend, &alice_signer, b"Hello")
+        .expect("Could not create message.");
+    let group_info = alice_group
+        .export_group_info(backend, &alice_signer, true)
+        .expect("Could not export group info.");
+    let key_package = MlsMessageOut::from(bob_kpb.key_package().clone());
+
+    for message in [
+        commit,
+        welcome,
+        application_message,
+        group_info,
+        key_package,
+    ] {
+        // Outgoing messages
+        let json = serde_json::to_string(&message).expect("Could not serialize message.");
+        let decoded: MlsMessageOut =
+            serde_json::from_str(&json).expect("Could not deserialize message.");
+        assert_eq!(decoded, message);
+
+        // Incoming messages
+        let bytes = message
+            .tls_serialize_detached()
+            .expect("Could not serialize message.");
+        let message_in =
+            MlsMessageIn::tls_deserialize(&mut bytes.as_slice()).expect("Could not parse message.");
+        let json = serde_json::to_string(&message_in).expect("Could not serialize message.");
+        let decoded: MlsMessageIn =
+            serde_json::from_str(&json).expect("Could not deserialize message.");
+        assert_eq!(decoded, message_in);
+    }
+
+    // Ratchet trees
+    let ratchet_tree = alice_group.export_ratchet_tree();
+    let json = serde_json::to_string(&ratchet_tree).expect("Could not serialize ratchet tree.");
+    let decoded: crate::treesync::RatchetTree =
+        serde_json::from_str(&json).expect("Could not deserialize ratchet tree.");
+    assert_eq!(decoded, ratchet_tree);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn stream_mls_messages(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+
+    // Serializing into a writer yields the same bytes as `to_bytes()`.
+    let mut bytes = vec![];
+    let written = welcome
+        .write_to(&mut bytes)
+        .expect("Could not write message.");
+    assert_eq!(written, bytes.len());
+    assert_eq!(
+        bytes,
+        welcome.to_bytes().expect("Could not serialize message.")
+    );
+
+    // Serializing into a buffer appends to it.
+    let mut buffer = vec![];
+    commit
+        .serialize_into(&mut buffer)
+        .expect("Could not serialize message.");
+    let written = welcome
+        .serialize_into(&mut buffer)
+        .expect("Could not serialize message.");
+    assert_eq!(written, bytes.len());
+    assert_eq!(
+        buffer,
+        [
+            commit.to_bytes().expect("Could not serialize message."),
+            bytes.clone()
+        ]
+        .concat()
+    );
+    let message_in =
+        MlsMessageIn::read_from(&mut bytes.as_slice()).expect("Could not read message.");
+    assert_eq!(MlsMessageOut::from(message_in), welcome);
+
+    // Write a sequence of length-prefixed messages, with a malformed message
+    // in between.
+    let mut stream = vec![];
+    commit
+        .write_length_prefixed_to(&mut stream)
+        .expect("Could not write message.");
+    stream.extend_from_slice(&[0, 0, 0, 3, 0xff, 0xff, 0xff]);
+    let written = welcome
+        .write_length_prefixed_to(&mut stream)
+        .expect("Could not write message.");
+    assert_eq!(written, 4 + bytes.len());
+
+    let mut reader = stream.as_slice();
+    let message_in = MlsMessageIn::read_length_prefixed_from(&mut reader, usize::MAX)
+        .expect("Could not read message.");
+    assert_eq!(MlsMessageOut::from(message_in), commit);
+    assert!(matches!(
+        MlsMessageIn::read_length_prefixed_from(&mut reader, usize::MAX),
+        Err(MlsMessageError::UnableToDecode)
+    ));
+    let message_in = MlsMessageIn::read_length_prefixed_from(&mut reader, usize::MAX)
+        .expect("Could not read message.");
+    assert_eq!(MlsMessageOut::from(message_in), welcome);
+    assert!(reader.is_empty());
+
+    // Messages that exceed the maximum length are rejected.
+    let mut stream = vec![];
+    welcome
+        .write_length_prefixed_to(&mut stream)
+        .expect("Could not write message.");
+    assert!(matches!(
+        MlsMessageIn::read_length_prefixed_from(&mut stream.as_slice(), bytes.len() - 1),
+        Err(MlsMessageError::MessageTooLarge)
+    ));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn bundle_mls_messages(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (commit, welcome, group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    let group_info = group_info.expect("No group info was returned.");
+
+    // Bundle the commit, the welcome and the group info into one upload.
+    let mut bundle = MlsMessageBundle::new();
+    bundle.push(commit.clone());
+    bundle.push(welcome.clone());
+    bundle.push(group_info.clone());
+    assert_eq!(bundle.len(), 3);
+    let bytes = bundle.to_bytes().expect("Could not serialize bundle.");
+    let mut written = vec![];
+    assert_eq!(
+        bundle
+            .write_to(&mut written)
+            .expect("Could not write bundle."),
+        bytes.len()
+    );
+    assert_eq!(written, bytes);
+
+    // The bundle is split into the original messages.
+    let bundle_in = MlsMessageBundleIn::split(&bytes, usize::MAX).expect("Could not split bundle.");
+    let messages = bundle_in
+        .into_iter()
+        .map(MlsMessageOut::from)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![commit, welcome, MlsMessageOut::from(group_info)]
+    );
+
+    // Empty bundles are allowed.
+    let empty = MlsMessageBundle::new()
+        .to_bytes()
+        .expect("Could not serialize bundle.");
+    assert!(MlsMessageBundleIn::split(&empty, usize::MAX)
+        .expect("Could not split bundle.")
+        .is_empty());
+
+    // Truncated bundles and trailing bytes are rejected as a whole.
+    assert!(matches!(
+        MlsMessageBundleIn::split(&bytes[..bytes.len() - 1], usize::MAX),
+        Err(MlsMessageError::UnableToDecode)
+    ));
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(
+        MlsMessageBundleIn::split(&trailing, usize::MAX),
+        Err(MlsMessageError::UnableToDecode)
+    ));
+
+    // Messages that exceed the maximum length are rejected.
+    assert!(matches!(
+        MlsMessageBundleIn::split(&bytes, 10),
+        Err(MlsMessageError::MessageTooLarge)
+    ));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn sender_ratchet_persistence(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // Bob saves the whole group state.
+    let mut saved_group = vec![];
+    bob_group
+        .save(&mut saved_group)
+        .expect("Could not save group.");
+
+    // Encrypting a message advances Alice's own ratchets.
+    let _ = alice_group.take_sender_ratchet_updates();
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello")
+        .expect("Could not create message.");
+    let alice_updates = alice_group.take_sender_ratchet_updates();
+    assert_eq!(alice_updates.len(), 1);
+    assert_eq!(alice_updates[0].sender(), alice_group.own_leaf_index());
+    assert!(alice_group.take_sender_ratchet_updates().is_empty());
+
+    // Decrypting the message advances Alice's ratchets in Bob's group.
+    bob_group
+        .process_message(backend, message.clone().into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    let bob_updates = bob_group.take_sender_ratchet_updates();
+    assert_eq!(bob_updates.len(), 1);
+    assert_eq!(bob_updates[0].group_id(), bob_group.group_id());
+    assert_eq!(bob_updates[0].epoch(), bob_group.epoch());
+    assert_eq!(bob_updates[0].sender(), alice_group.own_leaf_index());
+    let persisted_update =
+        serde_json::to_string(&bob_updates[0]).expect("Could not serialize ratchet state.");
+
+    // Without the persisted ratchet state, the reloaded group accepts the
+    // message a second time.
+    let mut reloaded_group = MlsGroup::load(saved_group.as_slice()).expect("Could not load group.");
+    reloaded_group
+        .process_message(backend, message.clone().into_protocol_message().unwrap())
+        .expect("Could not process message.");
+
+    // With the persisted ratchet state, the replay is detected.
+    let mut reloaded_group = MlsGroup::load(saved_group.as_slice()).expect("Could not load group.");
+    let update: SenderRatchetState =
+        serde_json::from_str(&persisted_update).expect("Could not deserialize ratchet state.");
+    reloaded_group
+        .restore_sender_ratchet_state(backend, update)
+        .expect("Could not restore ratchet state.");
+    assert!(reloaded_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .is_err());
+
+    // Later messages are still processed.
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello again")
+        .expect("Could not create message.");
+    reloaded_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+
+    // Alice's own encryption ratchet can't be merged into Bob's decryption
+    // ratchet for Alice.
+    let alice_update = alice_updates.into_iter().next().unwrap();
+    assert_eq!(
+        bob_group.restore_sender_ratchet_state(backend, alice_update),
+        Err(SenderRatchetStateError::SecretTreeError(
+            SecretTreeError::RatchetTypeError
+        ))
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn forward_secrecy_report(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_past_epochs(2)
+        .number_of_resumption_psks(2)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // Nothing was decrypted yet.
+    let report = bob_group.forward_secrecy_report();
+    assert_eq!(report.current_epoch(), bob_group.epoch());
+    assert_eq!(report.epochs().len(), 1);
+    assert!(report.epochs()[0].senders().is_empty());
+    assert_eq!(report.epochs()[0].tree_secrets(), 1);
+    assert_eq!(report.retained_generation_count(), 0);
+
+    // Alice sends three messages, of which Bob only receives the last one.
+    let messages: Vec<MlsMessageOut> = (0..3)
+        .map(|_| {
+            alice_group
+                .create_message(backend, &alice_signer, b"Hello")
+                .expect("Could not create message.")
+        })
+        .collect();
+    bob_group
+        .process_message(
+            backend,
+            messages[2].clone().into_protocol_message().unwrap(),
+        )
+        .expect("Could not process message.");
+
+    let report = bob_group.forward_secrecy_report();
+    let senders = report.epochs()[0].senders();
+    assert_eq!(senders.len(), 1);
+    assert_eq!(senders[0].sender(), alice_group.own_leaf_index());
+    assert!(senders[0].handshake().is_some());
+    let application = senders[0].application().expect("No application ratchet");
+    assert_eq!(application.generation(), 3);
+    assert_eq!(application.retained_generations(), &[1, 0]);
+    assert_eq!(application.max_age(), Some(3));
+    assert_eq!(report.retained_generation_count(), 2);
+
+    // Receiving a skipped message deletes its key material.
+    bob_group
+        .process_message(
+            backend,
+            messages[0].clone().into_protocol_message().unwrap(),
+        )
+        .expect("Could not process message.");
+    let report = bob_group.forward_secrecy_report();
+    let application = report.epochs()[0].senders()[0]
+        .application()
+        .expect("No application ratchet");
+    assert_eq!(application.retained_generations(), &[1]);
+
+    // After an epoch change, the secrets of the previous epoch are retained.
+    let (commit, _, _) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not create commit.");
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    {
+        bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Could not merge commit.");
+    } else {
+        unreachable!("Expected a StagedCommit.");
+    }
+
+    let report = bob_group.forward_secrecy_report();
+    assert_eq!(report.epochs().len(), 2);
+    assert_eq!(report.epochs()[0].age(), 1);
+    assert_eq!(report.epochs()[0].retained_generation_count(), 1);
+    assert_eq!(report.epochs()[1].age(), 0);
+    assert_eq!(report.epochs()[1].epoch(), bob_group.epoch());
+    assert!(report.resumption_psk_epochs().contains(&bob_group.epoch()));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn config_update_takes_effect_next_epoch(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_past_epochs(2)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // Advance the epoch a few times, such that past epochs are retained.
+    for _ in 0..3 {
+        alice_group
+            .self_update(backend, &alice_signer)
+            .expect("Could not update own leaf.");
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("Could not merge commit.");
+    }
+    assert_eq!(alice_group.forward_secrecy_report().epochs().len(), 3);
+
+    // Tighten the configuration. Nothing changes in the current epoch.
+    let sender_ratchet_configuration = SenderRatchetConfiguration::new(0, 10);
+    alice_group.update_sender_ratchet_configuration(sender_ratchet_configuration.clone());
+    alice_group.update_max_past_epochs(0);
+    assert_eq!(alice_group.configuration().max_past_epochs(), 2);
+    assert_eq!(
+        alice_group.configuration().sender_ratchet_configuration(),
+        &SenderRatchetConfiguration::default()
+    );
+    assert_eq!(alice_group.forward_secrecy_report().epochs().len(), 3);
+
+    // The pending update survives saving and loading the group.
+    let mut saved_group = Vec::new();
+    alice_group
+        .save(&mut saved_group)
+        .expect("Could not save group.");
+    let mut alice_group = MlsGroup::load(saved_group.as_slice()).expect("Could not load group.");
+    let pending_config_update = alice_group
+        .pending_config_update()
+        .expect("No pending config update.");
+    assert_eq!(pending_config_update.max_past_epochs(), Some(0));
+    assert_eq!(
+        pending_config_update.sender_ratchet_configuration(),
+        Some(&sender_ratchet_configuration)
+    );
+
+    // The update takes effect with the next commit.
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update own leaf.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    assert!(alice_group.pending_config_update().is_none());
+    assert_eq!(alice_group.configuration().max_past_epochs(), 0);
+    assert_eq!(
+        alice_group.configuration().sender_ratchet_configuration(),
+        &sender_ratchet_configuration
+    );
+    assert_eq!(alice_group.forward_secrecy_report().epochs().len(), 1);
+
+    // A pending update can be discarded.
+    alice_group.update_max_past_epochs(5);
+    alice_group.clear_pending_config_update();
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update own leaf.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    assert_eq!(alice_group.configuration().max_past_epochs(), 0);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn strict_leaf_node_validation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (charlie_credential_with_key, _charlie_kpb, charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let lenient_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let strict_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .leaf_node_validation_policy(LeafNodeValidationPolicy::Strict)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &lenient_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+
+    // The leaf nodes in the ratchet tree are valid.
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &strict_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // Charlie's leaf node doesn't list its own credential type in its
+    // capabilities.
+    let charlie_key_package = KeyPackage::builder()
+        .leaf_node_capabilities(Capabilities::new(
+            None,
+            Some(&[ciphersuite]),
+            None,
+            None,
+            Some(&[]),
+        ))
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &charlie_signer,
+            charlie_credential_with_key,
+        )
+        .expect("Could not create key package.");
+
+    // Alice validates leniently and adds Charlie.
+    let (commit, _welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[charlie_key_package])
+        .expect("Could not add member.");
+
+    // Bob rejects the commit.
+    let err = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect_err("Invalid leaf node was accepted.");
+    assert_eq!(
+        err,
+        ProcessMessageError::LeafNodeValidation(
+            LeafNodeValidationError::CredentialNotInCapabilities
+        )
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn ratchet_tree_view(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // A group with a single member consists of a single leaf.
+    let view = alice_group.ratchet_tree_view();
+    assert_eq!(view.leaf_count(), 1);
+    assert_eq!(view.node_count(), 1);
+    assert_eq!(view.blank_node_count(), 0);
+    assert_eq!(view.tree_hash(), alice_group.tree_hash());
+
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // Bob updates his leaf, which populates the parent node.
+    let (commit, _, _) = bob_group
+        .self_update(backend, &bob_signer)
+        .expect("Could not update own leaf.");
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) = alice_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process message.")
+        .into_content()
+    {
+        alice_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Could not merge commit.");
+    } else {
+        unreachable!("Expected a StagedCommit.");
+    }
+
+    let view = alice_group.ratchet_tree_view();
+    assert_eq!(view.tree_hash(), bob_group.ratchet_tree_view().tree_hash());
+    assert_eq!(view.leaf_count(), 2);
+    let nodes: Vec<TreeNodeView> = view.nodes().collect();
+    assert_eq!(nodes.len(), view.node_count() as usize);
+    for (node_index, node) in nodes.iter().enumerate() {
+        assert_eq!(node.node_index(), node_index as u32);
+    }
+    assert!(nodes.iter().all(|node| !node.is_blank()));
+    let parent = &nodes[1];
+    assert!(matches!(parent, TreeNodeView::Parent { .. }));
+    assert!(parent.unmerged_leaves().is_empty());
+    // Bob's leaf node was created in a commit and is bound to the parent.
+    let bob_leaf = view
+        .leaf(bob_group.own_leaf_index())
+        .expect("Bob's leaf is blank.");
+    assert!(bob_leaf.parent_hash().is_some());
+    assert_eq!(nodes[2].parent_hash(), bob_leaf.parent_hash());
+
+    // Removing Bob blanks his leaf and the parent.
+    alice_group
+        .remove_members(backend, &alice_signer, &[bob_group.own_leaf_index()])
+        .expect("Could not remove member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let view = alice_group.ratchet_tree_view();
+    assert!(view.leaf(bob_group.own_leaf_index()).is_none());
+    assert_eq!(
+        view.leaves().filter(|(_, leaf)| leaf.is_some()).count(),
+        alice_group.members().count()
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn verify_ratchet_tree(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let old_ratchet_tree = alice_group.export_ratchet_tree();
+
+    alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+
+    let group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .expect("Could not export group info.")
+        .into_verifiable_group_info()
+        .expect("Unexpected message type.");
+
+    // The current tree matches the group info.
+    let ratchet_tree = alice_group.export_ratchet_tree();
+    ratchet_tree
+        .verify(backend, &group_info)
+        .expect("Could not verify ratchet tree.");
+    let verified_tree = RatchetTreeIn::from(ratchet_tree.clone())
+        .verify(backend, &group_info)
+        .expect("Could not verify ratchet tree.");
+    assert_eq!(verified_tree, ratchet_tree);
+
+    // The tree of the previous epoch doesn't.
+    assert_eq!(
+        RatchetTreeIn::from(old_ratchet_tree).verify(backend, &group_info),
+        Err(RatchetTreeVerificationError::TreeHashMismatch)
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn epoch_history(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let created_at = alice_group
+        .epoch_started_at()
+        .expect("No start time for the initial epoch.");
+    assert!(alice_group.epoch_age().is_some());
+    assert_eq!(alice_group.epoch_history().count(), 1);
+
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    // The epoch only changes when the commit is merged.
+    assert_eq!(alice_group.epoch_started_at(), Some(created_at));
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let history: Vec<EpochTransition> = alice_group.epoch_history().copied().collect();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].epoch(), GroupEpoch::from(0));
+    assert_eq!(history[0].started_at(), created_at);
+    assert_eq!(history[1].epoch(), GroupEpoch::from(1));
+    assert!(history[1].started_at() >= created_at);
+    assert_eq!(
+        alice_group.epoch_started_at(),
+        Some(history[1].started_at())
+    );
+
+    // Bob's history starts with the epoch in which Bob joined.
+    let bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+    let bob_history: Vec<&EpochTransition> = bob_group.epoch_history().collect();
+    assert_eq!(bob_history.len(), 1);
+    assert_eq!(bob_history[0].epoch(), GroupEpoch::from(1));
+
+    // The history is persisted.
+    let mut serialized = Vec::new();
+    alice_group
+        .save(&mut serialized)
+        .expect("Could not save group.");
+    let loaded_group = MlsGroup::load(serialized.as_slice()).expect("Could not load group.");
+    assert!(loaded_group.epoch_history().eq(alice_group.epoch_history()));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn config_presets_and_validation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    // All presets are valid and can be used to create a group.
+    for mls_group_config in [
+        MlsGroupConfig::rfc_default(ciphersuite),
+        MlsGroupConfig::high_security(ciphersuite),
+        MlsGroupConfig::large_group(ciphersuite),
+    ] {
+        assert_eq!(mls_group_config.validate(), Ok(()));
+        assert_eq!(mls_group_config.crypto_config().ciphersuite, ciphersuite);
+        MlsGroup::new(
+            backend,
+            &alice_signer,
+            &mls_group_config,
+            alice_credential_with_key.clone(),
+        )
+        .expect("Could not create group.");
+    }
+
+    let external_sender = ExternalSender::new(
+        alice_credential_with_key.signature_key.clone(),
+        alice_credential_with_key.credential.clone(),
+    );
+    let mls_group_config = MlsGroupConfig::builder()
+        .wire_format_policy(PURE_CIPHERTEXT_WIRE_FORMAT_POLICY)
+        .external_senders(vec![external_sender.clone()])
+        .build();
+    assert_eq!(
+        mls_group_config.validate(),
+        Err(InvalidConfigError::ExternalSendersWithCiphertextOnlyPolicy)
+    );
+    let mls_group_config = MlsGroupConfig::builder()
+        .wire_format_policy(MIXED_CIPHERTEXT_WIRE_FORMAT_POLICY)
+        .external_senders(vec![external_sender])
+        .build();
+    assert_eq!(mls_group_config.validate(), Ok(()));
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .max_past_epoch_age(std::time::Duration::from_secs(60))
+        .build();
+    assert_eq!(
+        mls_group_config.validate(),
+        Err(InvalidConfigError::MaxPastEpochAgeWithoutPastEpochs)
+    );
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .required_media_types(MediaTypeList::new(vec!["text/plain".into()]).into())
+        .build();
+    assert_eq!(
+        mls_group_config.validate(),
+        Err(InvalidConfigError::RequiredMediaTypesNotAccepted)
+    );
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .padding_policy(PaddingPolicy::Buckets(vec![0, 256]))
+        .build();
+    assert_eq!(
+        mls_group_config.validate(),
+        Err(InvalidConfigError::InvalidPaddingBuckets)
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn group_metrics(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_past_epochs(2)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let metrics = alice_group.metrics().expect("Could not compute metrics.");
+    assert_eq!(metrics.epoch(), GroupEpoch::from(0));
+    assert_eq!(metrics.member_count(), 1);
+    assert_eq!(metrics.leaf_count(), 1);
+    assert_eq!(metrics.tree_depth(), 0);
+    assert_eq!(metrics.blank_node_count(), 0);
+    assert_eq!(metrics.retained_past_epochs(), 0);
+
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // Bob learns about Alice's ratchet when receiving a message from her.
+    assert!(bob_group
+        .metrics()
+        .expect("Could not compute metrics.")
+        .sender_ratchets()
+        .is_empty());
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello")
+        .expect("Could not create message.");
+    bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    let metrics = bob_group.metrics().expect("Could not compute metrics.");
+    assert_eq!(metrics.sender_ratchets().len(), 1);
+    assert_eq!(
+        metrics.sender_ratchets()[0].sender(),
+        alice_group.own_leaf_index()
+    );
+
+    alice_group
+        .propose_add_member(backend, &alice_signer, charlie_kpb.key_package())
+        .expect("Could not create proposal.");
+
+    let metrics = alice_group.metrics().expect("Could not compute metrics.");
+    assert_eq!(metrics.epoch(), GroupEpoch::from(1));
+    assert_eq!(metrics.member_count(), 2);
+    assert_eq!(metrics.leaf_count(), 2);
+    assert_eq!(metrics.tree_depth(), 1);
+    assert_eq!(metrics.node_count(), 3);
+    assert!((0.0..=1.0).contains(&metrics.blank_ratio()));
+    assert_eq!(metrics.pending_proposal_count(), 1);
+    assert_eq!(metrics.retained_past_epochs(), 1);
+
+    let mut serialized = Vec::new();
+    alice_group
+        .save(&mut serialized)
+        .expect("Could not save group.");
+    assert_eq!(metrics.serialized_state_size(), serialized.len());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn group_info_extension_accessors(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    let alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    for with_ratchet_tree in [true, false] {
+        let group_info = alice_group
+            .export_group_info(backend, &alice_signer, with_ratchet_tree)
+            .expect("Could not export group info.")
+            .into_verifiable_group_info()
+            .expect("Unexpected message type.");
+
+        assert!(group_info.supports_external_commit());
+        assert_eq!(
+            group_info.external_pub(),
+            group_info
+                .extensions()
+                .external_pub()
+                .map(|extension| extension.external_pub())
+        );
+        assert_eq!(group_info.has_ratchet_tree_extension(), with_ratchet_tree);
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn update_capabilities(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // Capabilities without the ciphersuite of the group are rejected.
+    let error = bob_group
+        .update_capabilities(
+            backend,
+            &bob_signer,
+            Capabilities::new(None, Some(&[]), None, None, None),
+        )
+        .expect_err("Capabilities without the group's ciphersuite were accepted.");
+    assert_eq!(
+        error,
+        UpdateCapabilitiesError::UnsupportedCiphersuiteOrVersion
+    );
+
+    // Bob advertises support for a new extension.
+    let new_extension = ExtensionType::Unknown(0xff00);
+    let (proposal, _) = bob_group
+        .update_capabilities(
+            backend,
+            &bob_signer,
+            Capabilities::new(
+                None,
+                Some(&[ciphersuite]),
+                Some(&[new_extension]),
+                None,
+                None,
+            ),
+        )
+        .expect("Could not update capabilities.");
+
+    if let ProcessedMessageContent::ProposalMessage(staged_proposal) = alice_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect("Could not process message.")
+        .into_content()
+    {
+        alice_group.store_pending_proposal(*staged_proposal);
+    } else {
+        unreachable!("Expected a proposal.");
+    }
+    let (commit, _, _) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Could not commit to proposals.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process message.")
+        .into_content()
+    {
+        bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Could not merge commit.");
+    } else {
+        unreachable!("Expected a StagedCommit.");
+    }
+
+    let bob_leaf = alice_group
+        .ratchet_tree_view()
+        .leaf(bob_group.own_leaf_index())
+        .expect("Bob's leaf is blank.");
+    assert!(bob_leaf.supports_extension(&new_extension));
+    assert_eq!(alice_group.tree_hash(), bob_group.tree_hash());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn ratchet_tree_delta(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let old_ratchet_tree = alice_group.export_ratchet_tree();
+
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[charlie_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let new_ratchet_tree = alice_group.export_ratchet_tree();
+
+    // The delta only contains the changed nodes and survives a round trip.
+    let delta = old_ratchet_tree.delta(&new_ratchet_tree);
+    assert!(delta.changed_node_count() < delta.node_count() as usize);
+    let delta = RatchetTreeDelta::tls_deserialize(
+        &mut delta
+            .tls_serialize_detached()
+            .expect("Could not serialize delta.")
+            .as_slice(),
+    )
+    .expect("Could not deserialize delta.");
+
+    // Applying the delta to the old tree yields the new tree.
+    let ratchet_tree = RatchetTreeIn::from(old_ratchet_tree)
+        .apply_delta(delta)
+        .expect("Could not apply delta.");
+    assert_eq!(ratchet_tree, RatchetTreeIn::from(new_ratchet_tree));
+
+    // Charlie can join with the resulting tree.
+    let charlie_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(ratchet_tree),
+    )
+    .expect("Error creating group from Welcome");
+    assert_eq!(alice_group.tree_hash(), charlie_group.tree_hash());
+}
+
+#[cfg(feature = "epoch-secrets-export")]
+#[apply(ciphersuites_and_backends)]
+fn export_epoch_secrets(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+
+    let bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error joining group from Welcome");
+
+    // Both members export the same secrets.
+    let alice_secrets = alice_group.export_epoch_secrets();
+    let bob_secrets = bob_group.export_epoch_secrets();
+    assert_eq!(alice_secrets, bob_secrets);
+    assert_eq!(alice_secrets.epoch(), alice_group.epoch());
+    assert!(alice_secrets.encryption_secret().is_some());
+    assert_eq!(
+        alice_secrets.epoch_authenticator(),
+        alice_group.epoch_authenticator().as_slice()
+    );
+
+    // The secrets change with the epoch.
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let new_secrets = alice_group.export_epoch_secrets();
+    assert_ne!(
+        new_secrets.exporter_secret(),
+        alice_secrets.exporter_secret()
+    );
+    assert_ne!(new_secrets.epoch(), alice_secrets.epoch());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn rollback_pending_commit(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // Without a pending commit, there is nothing to roll back.
+    let error = alice_group
+        .rollback_pending_commit(backend)
+        .expect_err("no error rolling back without a pending commit");
+    assert_eq!(
+        error,
+        RollbackPendingCommitError::GroupStateError(MlsGroupStateError::NoPendingCommit)
+    );
+
+    // A commit with inline proposals is discarded entirely.
+    alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .rollback_pending_commit(backend)
+        .expect("error rolling back pending commit");
+    assert!(alice_group.pending_commit().is_none());
+    assert_eq!(alice_group.pending_proposals().count(), 0);
+    assert_eq!(alice_group.epoch(), GroupEpoch::from(0));
+
+    // Proposals committed by reference are restored.
+    let (_proposal, proposal_ref) = alice_group
+        .propose_add_member(backend, &alice_signer, bob_kpb.key_package())
+        .expect("Could not create proposal.");
+    alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Could not commit to pending proposals.");
+    // Simulate the proposal store being emptied while the commit was pending.
+    alice_group.clear_pending_proposals();
+    alice_group
+        .rollback_pending_commit(backend)
+        .expect("error rolling back pending commit");
+    assert!(alice_group.pending_commit().is_none());
+    let pending_proposals: Vec<&QueuedProposal> = alice_group.pending_proposals().collect();
+    assert_eq!(pending_proposals.len(), 1);
+    assert_eq!(pending_proposals[0].proposal_reference(), proposal_ref);
+
+    // The restored proposal can be committed again and the group remains
+    // functional.
+    let (_commit, welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Could not commit to pending proposals.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert_eq!(alice_group.epoch(), GroupEpoch::from(1));
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome
+            .expect("no welcome after commit")
+            .into_welcome()
+            .expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello Bob")
+        .expect("Could not create message.");
+    let processed_message = bob_group
+        .process_message(
+            backend,
+            message
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process message.");
+    assert!(matches!(
+        processed_message.into_content(),
+        ProcessedMessageContent::ApplicationMessage(_)
+    ));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn pending_proposal_limit(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let bob_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_pending_proposals(1)
+        .build();
+    assert_eq!(bob_config.max_pending_proposals(), Some(1));
+    assert_eq!(
+        bob_config.proposal_store_full_policy(),
+        ProposalStoreFullPolicy::Reject
+    );
+    let bob_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_pending_proposals(1)
+        .proposal_store_full_policy(ProposalStoreFullPolicy::EvictOldest)
+        .build();
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &bob_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // With the evict policy, the oldest proposal is evicted if the store is full.
+    let mut proposal_refs = Vec::new();
+    for _ in 0..2 {
+        let (message, proposal_ref) = alice_group
+            .propose_self_update(backend, &alice_signer, None)
+            .expect("Could not create proposal.");
+        let processed_message = bob_group
+            .process_message(backend, message.into_protocol_message().unwrap())
+            .expect("Could not process message.");
+        match processed_message.into_content() {
+            ProcessedMessageContent::ProposalMessage(proposal) => {
+                bob_group.store_pending_proposal(*proposal)
+            }
+            _ => panic!("Unexpected message type."),
+        }
+        proposal_refs.push(proposal_ref);
+    }
+    let pending_proposal_refs: Vec<ProposalRef> = bob_group
+        .pending_proposals()
+        .map(|proposal| proposal.proposal_reference())
+        .collect();
+    assert_eq!(pending_proposal_refs, proposal_refs[1..].to_vec());
+
+    // With the reject policy, proposals are rejected while the store is full.
+    bob_group.set_configuration(
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .max_pending_proposals(1)
+            .proposal_store_full_policy(ProposalStoreFullPolicy::Reject)
+            .build(),
+    );
+    let (message, _proposal_ref) = alice_group
+        .propose_self_update(backend, &alice_signer, None)
+        .expect("Could not create proposal.");
+    let error = bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect_err("Proposal was not rejected.");
+    assert_eq!(error, ProcessMessageError::ProposalStoreFull);
+    assert_eq!(bob_group.pending_proposals().count(), 1);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn scheduled_self_update(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use std::time::Duration;
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .update_policy(UpdatePolicy::new(None, Some(2)))
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // No update is due before the thresholds are exceeded.
+    assert!(!alice_group.self_update_due());
+    assert!(alice_group
+        .maybe_self_update(backend, &alice_signer)
+        .expect("Could not check for a scheduled update.")
+        .is_none());
+    assert!(alice_group.pending_commit().is_none());
+
+    // Sending messages triggers an update.
+    for _ in 0..2 {
+        alice_group
+            .create_message(backend, &alice_signer, b"Hello")
+            .expect("Could not create message.");
+    }
+    assert!(alice_group.self_update_due());
+    let own_encryption_key = alice_group
+        .own_leaf()
+        .expect("No own leaf.")
+        .encryption_key()
+        .clone();
+    alice_group
+        .maybe_self_update(backend, &alice_signer)
+        .expect("Could not create scheduled update.")
+        .expect("No update was created.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert_ne!(
+        alice_group
+            .own_leaf()
+            .expect("No own leaf.")
+            .encryption_key(),
+        &own_encryption_key
+    );
+
+    // The message count starts over in the new epoch.
+    assert!(!alice_group.self_update_due());
+
+    // An epoch that is too old triggers an update as well.
+    alice_group.set_configuration(
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .update_policy(UpdatePolicy::new(Some(Duration::ZERO), None))
+            .build(),
+    );
+    assert!(alice_group.self_update_due());
+
+    // Without a policy, no update is ever due.
+    alice_group.set_configuration(&MlsGroupConfig::test_default(ciphersuite));
+    assert!(!alice_group.self_update_due());
+}
+
+// The age of an epoch is measured with the configured time provider, such
+// that an update is due once the clock passes the maximum epoch age.
+#[apply(ciphersuites_and_backends)]
+fn scheduled_self_update_after_max_epoch_age(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) {
+    use std::{
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        },
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    /// A clock that only advances when the test advances it.
+    #[derive(Clone)]
+    struct MockClock(Arc<AtomicU64>);
+
+    impl MockClock {
+        fn advance(&self, duration: Duration) {
+            self.0.fetch_add(duration.as_secs(), Ordering::SeqCst);
+        }
+    }
+
+    impl TimeProvider for MockClock {
+        fn unix_now(&self) -> Option<u64> {
+            Some(self.0.load(Ordering::SeqCst))
+        }
+    }
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    let start = SystemTimeProvider.unix_now().expect("No system time.");
+    let clock = MockClock(Arc::new(AtomicU64::new(start)));
+    let max_epoch_age = Duration::from_secs(24 * 60 * 60);
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .update_policy(UpdatePolicy::new(Some(max_epoch_age), None))
+        .time_provider(clock.clone())
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    assert_eq!(
+        alice_group.epoch_started_at(),
+        Some(UNIX_EPOCH + Duration::from_secs(start))
+    );
+    assert_eq!(alice_group.epoch_age(), Some(Duration::ZERO));
+
+    // No update is due before the maximum epoch age is reached.
+    clock.advance(max_epoch_age - Duration::from_secs(1));
+    assert_eq!(
+        alice_group.epoch_age(),
+        Some(max_epoch_age - Duration::from_secs(1))
+    );
+    assert!(!alice_group.self_update_due());
+    assert!(alice_group
+        .maybe_self_update(backend, &alice_signer)
+        .expect("Could not check for a scheduled update.")
+        .is_none());
+
+    // Once the clock passes the maximum epoch age, an update is due.
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(alice_group.epoch_age(), Some(max_epoch_age));
+    assert!(alice_group.self_update_due());
+    alice_group
+        .maybe_self_update(backend, &alice_signer)
+        .expect("Could not create scheduled update.")
+        .expect("No update was created.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    // The new epoch started at the time of the clock.
+    assert_eq!(
+        alice_group.epoch_started_at(),
+        Some(UNIX_EPOCH + Duration::from_secs(start) + max_epoch_age)
+    );
+    assert_eq!(alice_group.epoch_age(), Some(Duration::ZERO));
+    assert!(!alice_group.self_update_due());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn transcript_hash_accessors(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // The confirmed transcript hash of a new group is empty.
+    assert!(alice_group.confirmed_transcript_hash().is_empty());
+    assert!(!alice_group.interim_transcript_hash().is_empty());
+    let initial_interim_transcript_hash = alice_group.interim_transcript_hash().to_vec();
+
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // The accessors are consistent with the group context.
+    let group_context = alice_group.export_group_context();
+    assert_eq!(group_context.epoch(), alice_group.epoch());
+    assert_eq!(group_context.group_id(), alice_group.group_id());
+    assert_eq!(group_context.tree_hash(), alice_group.tree_hash());
+    assert_eq!(
+        group_context.confirmed_transcript_hash(),
+        alice_group.confirmed_transcript_hash()
+    );
+    assert_eq!(
+        alice_group.tree_hash(),
+        alice_group.ratchet_tree_view().tree_hash()
+    );
+
+    // The commit changed the transcript hashes.
+    assert!(!alice_group.confirmed_transcript_hash().is_empty());
+    assert_ne!(
+        alice_group.interim_transcript_hash(),
+        initial_interim_transcript_hash.as_slice()
+    );
+    assert_ne!(
+        alice_group.interim_transcript_hash(),
+        alice_group.confirmed_transcript_hash()
+    );
+
+    // All members agree on the state of the group.
+    assert_eq!(
+        alice_group.export_group_context(),
+        bob_group.export_group_context()
+    );
+    assert_eq!(
+        alice_group.interim_transcript_hash(),
+        bob_group.interim_transcript_hash()
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn roles_and_permissions(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, bob_signer, _bob_pk) = setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .roles(RolesExtension::new())
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.clone(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+    let mut charlie_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // The creator of the group is its owner.
+    let alice_index = alice_group.own_leaf_index();
+    let bob_index = bob_group.own_leaf_index();
+    let charlie_index = charlie_group.own_leaf_index();
+    assert_eq!(bob_group.member_role(alice_index), Some(Role::Owner));
+    assert_eq!(bob_group.member_role(bob_index), Some(Role::Member));
+
+    // A regular member can't remove other members.
+    let (message, proposal_ref) = bob_group
+        .propose_remove_member(backend, &bob_signer, charlie_index)
+        .expect("Could not create proposal.");
+    for group in [&mut alice_group, &mut charlie_group] {
+        let error = group
+            .process_message(backend, message.clone().into_protocol_message().unwrap())
+            .expect_err("Proposal was not rejected.");
+        assert_eq!(
+            error,
+            ProcessMessageError::InvalidProposal(ProposalValidationError::InsufficientRole)
+        );
+    }
+    bob_group
+        .remove_pending_proposal(proposal_ref)
+        .expect("Could not remove proposal.");
+    let error = bob_group
+        .remove_members(backend, &bob_signer, &[charlie_index])
+        .expect_err("Commit was not rejected.");
+    assert_eq!(
+        error,
+        RemoveMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
+            ProposalValidationError::InsufficientRole
+        ))
+    );
+
+    // The owner makes Bob an admin.
+    let mut roles = alice_group.roles().expect("No roles in the group.");
+    assert_eq!(roles.set_role(b"Bob", Role::Admin), Role::Member);
+    let (message, _proposal_ref) = alice_group
+        .propose_roles(backend, &alice_signer, roles)
+        .expect("Could not create proposal.");
+    for group in [&mut bob_group, &mut charlie_group] {
+        let processed_message = group
+            .process_message(backend, message.clone().into_protocol_message().unwrap())
+            .expect("Could not process message.");
+        match processed_message.into_content() {
+            ProcessedMessageContent::ProposalMessage(proposal) => {
+                group.store_pending_proposal(*proposal)
+            }
+            _ => panic!("Unexpected message type."),
+        }
+    }
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Could not commit proposals.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    for group in [&mut bob_group, &mut charlie_group] {
+        let processed_message = group
+            .process_message(backend, commit.clone().into_protocol_message().unwrap())
+            .expect("Could not process commit.");
+        match processed_message.into_content() {
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => group
+                .merge_staged_commit(backend, *staged_commit)
+                .expect("Could not merge commit."),
+            _ => panic!("Unexpected message type."),
+        }
+    }
+    assert_eq!(alice_group.member_role(bob_index), Some(Role::Admin));
+
+    // An admin can't add a client with the identity of the owner.
+    let (_alice_credential, alice_kpb, _alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let error = bob_group
+        .add_members(backend, &bob_signer, &[alice_kpb.key_package().clone()])
+        .expect_err("Commit was not rejected.");
+    assert_eq!(
+        error,
+        AddMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
+            ProposalValidationError::InsufficientRole
+        ))
+    );
+
+    // An admin can remove regular members, but not the owner.
+    let error = bob_group
+        .remove_members(backend, &bob_signer, &[alice_index])
+        .expect_err("Commit was not rejected.");
+    assert_eq!(
+        error,
+        RemoveMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
+            ProposalValidationError::InsufficientRole
+        ))
+    );
+    let (commit, _welcome, _group_info) = bob_group
+        .remove_members(backend, &bob_signer, &[charlie_index])
+        .expect("Could not remove member.");
+    let processed_message = alice_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    assert!(matches!(
+        processed_message.into_content(),
+        ProcessedMessageContent::StagedCommitMessage(_)
+    ));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn update_own_leaf_extensions(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // Extensions that are not in the capabilities of the leaf are rejected.
+    let error = bob_group
+        .update_own_leaf_extensions(
+            backend,
+            &bob_signer,
+            Extensions::single(Extension::Unknown(0xff00, UnknownExtension(vec![1]))),
+        )
+        .expect_err("An unsupported extension was accepted.");
+    assert_eq!(
+        error,
+        UpdateLeafExtensionsError::LeafNodeValidation(
+            LeafNodeValidationError::ExtensionsNotInCapabilities
+        )
+    );
+    assert_eq!(bob_group.pending_proposals().count(), 0);
+
+    // Bob sets a new application ID.
+    let application_id = ApplicationIdExtension::new(b"Bob's phone");
+    let (proposal, _) = bob_group
+        .update_own_leaf_extensions(
+            backend,
+            &bob_signer,
+            Extensions::single(Extension::ApplicationId(application_id.clone())),
+        )
+        .expect("Could not update leaf extensions.");
+
+    if let ProcessedMessageContent::ProposalMessage(staged_proposal) = alice_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect("Could not process message.")
+        .into_content()
+    {
+        alice_group.store_pending_proposal(*staged_proposal);
+    } else {
+        unreachable!("Expected a proposal.");
+    }
+    let (commit, _, _) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Could not commit to proposals.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process message.")
+        .into_content()
+    {
+        bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Could not merge commit.");
+    } else {
+        unreachable!("Expected a StagedCommit.");
+    }
+
+    let bob_leaf = alice_group
+        .ratchet_tree_view()
+        .leaf(bob_group.own_leaf_index())
+        .expect("Bob's leaf is blank.");
+    assert_eq!(
+        bob_leaf.extensions().application_id(),
+        Some(&application_id)
+    );
+    assert_eq!(alice_group.tree_hash(), bob_group.tree_hash());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn malformed_messages(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    let bytes = alice_group
+        .create_message(backend, &alice_signer, b"Hello, Bob!")
+        .expect("Could not create message.")
+        .tls_serialize_detached()
+        .expect("Could not serialize message.");
+
+    // Truncated messages are rejected while decoding or processing.
+    for length in 0..bytes.len() {
+        if let Ok(message) = MlsMessageIn::tls_deserialize(&mut &bytes[..length]) {
+            if let Some(protocol_message) = message.into_protocol_message() {
+                assert!(bob_group
+                    .process_message(backend, protocol_message)
+                    .is_err());
+            }
+        }
+    }
+
+    // Every modified byte is detected.
+    for position in 0..bytes.len() {
+        let mut modified = bytes.clone();
+        modified[position] ^= 0xff;
+        if let Ok(message) = MlsMessageIn::tls_deserialize(&mut modified.as_slice()) {
+            if let Some(protocol_message) = message.into_protocol_message() {
+                assert!(bob_group
+                    .process_message(backend, protocol_message)
+                    .is_err());
+            }
+        }
+    }
+
+    // Bob can still process new messages from Alice.
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello again, Bob!")
+        .expect("Could not create message.");
+    let processed_message = bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    if let ProcessedMessageContent::ApplicationMessage(application_message) =
+        processed_message.into_content()
+    {
+        assert_eq!(application_message.into_bytes(), b"Hello again, Bob!");
+    } else {
+        unreachable!("Expected an ApplicationMessage.");
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn delete_key_package(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    let key_package = charlie_kpb.key_package();
+
+    key_package
+        .delete(backend)
+        .expect("Could not delete key package.");
+    assert!(backend
+        .key_store()
+        .read::<KeyPackage>(
+            key_package
+                .hash_ref(backend.crypto())
+                .expect("Could not compute hash reference.")
+                .as_slice()
+        )
+        .is_none());
+
+    // Deleting a key package that was already deleted is not an error.
+    key_package
+        .delete(backend)
+        .expect("Could not delete key package.");
+}
+
+#[apply(ciphersuites_and_backends)]
+fn always_return_group_info(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // Without the flag (and without the ratchet tree extension), commits
+    // don't return a GroupInfo.
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        group_id.clone(),
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, _welcome, group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update own leaf.");
+    assert!(group_info.is_none());
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .always_return_group_info(true)
+        .build();
+    assert!(mls_group_config.always_return_group_info());
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // Every commit-producing operation returns a GroupInfo for the new epoch
+    // that allows external joins.
+    let (_commit, _welcome, group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    let group_info = group_info.expect("No GroupInfo was returned.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    assert_eq!(group_info.group_context().epoch(), alice_group.epoch());
+    assert!(group_info.extensions().external_pub().is_some());
+    assert!(!group_info.has_ratchet_tree_extension());
+
+    let (_commit, _welcome, group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update own leaf.");
+    let group_info = group_info.expect("No GroupInfo was returned.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    assert_eq!(group_info.group_context().epoch(), alice_group.epoch());
+
+    let (_commit, _welcome, group_info) = alice_group
+        .remove_members(backend, &alice_signer, &[LeafNodeIndex::new(1)])
+        .expect("Could not remove member.");
+    let group_info = group_info.expect("No GroupInfo was returned.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    assert_eq!(group_info.group_context().epoch(), alice_group.epoch());
+    assert!(group_info.extensions().external_pub().is_some());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn indexed_member_lookup(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        group_id,
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+    alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+
+    // Members are found by identity and signature key.
+    let bob = alice_group
+        .member_by_identity(b"Bob")
+        .expect("Bob is a member.");
+    assert_eq!(bob.index, LeafNodeIndex::new(1));
+    assert_eq!(bob.credential, bob_credential_with_key.credential);
+    assert_eq!(
+        alice_group.members_by_identity(b"Bob").collect::<Vec<_>>(),
+        vec![bob]
+    );
+    assert_eq!(
+        alice_group.leaf_index_of(alice_credential_with_key.signature_key.as_slice()),
+        Some(LeafNodeIndex::new(0))
+    );
+    assert_eq!(
+        alice_group.leaf_index_of(bob_credential_with_key.signature_key.as_slice()),
+        Some(LeafNodeIndex::new(1))
+    );
+    assert!(alice_group.member_by_identity(b"Charlie").is_none());
+
+    // The index is rebuilt when loading the group ...
+    let mut serialized = Vec::new();
+    alice_group
+        .save(&mut serialized)
+        .expect("Could not save group.");
+    let mut alice_group = MlsGroup::load(serialized.as_slice()).expect("Could not load group.");
+    assert_eq!(
+        alice_group.leaf_index_of(bob_credential_with_key.signature_key.as_slice()),
+        Some(LeafNodeIndex::new(1))
+    );
+
+    // ... and kept up to date when the roster changes.
+    alice_group
+        .remove_members(backend, &alice_signer, &[LeafNodeIndex::new(1)])
+        .expect("Could not remove member.");
+    assert!(alice_group.member_by_identity(b"Bob").is_some());
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    assert!(alice_group.member_by_identity(b"Bob").is_none());
+    assert!(alice_group
+        .leaf_index_of(bob_credential_with_key.signature_key.as_slice())
+        .is_none());
+    assert_eq!(
+        alice_group
+            .member_by_identity(b"Alice")
+            .map(|member| member.index),
+        Some(LeafNodeIndex::new(0))
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn members_at_past_epochs(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_past_epochs(2)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let epoch_0 = alice_group.epoch();
+
+    // Epoch 1: Alice adds Bob.
+    alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let epoch_1 = alice_group.epoch();
+
+    // Epoch 2: Alice removes Bob.
+    alice_group
+        .remove_members(backend, &alice_signer, &[LeafNodeIndex::new(1)])
+        .expect("Could not remove member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let epoch_2 = alice_group.epoch();
+
+    let identities = |members: Vec<Member>| -> Vec<Vec<u8>> {
+        members
+            .into_iter()
+            .map(|member| member.credential.identity().to_vec())
+            .collect()
+    };
+    assert_eq!(
+        alice_group.members_at_epoch(epoch_2).map(identities),
+        Some(vec![b"Alice".to_vec()])
+    );
+    assert_eq!(
+        alice_group.members_at_epoch(epoch_1).map(identities),
+        Some(vec![b"Alice".to_vec(), b"Bob".to_vec()])
+    );
+    assert_eq!(
+        alice_group.members_at_epoch(epoch_0).map(identities),
+        Some(vec![b"Alice".to_vec()])
+    );
+
+    // Bob's messages from epoch 1 can still be attributed to him.
+    assert_eq!(
+        alice_group
+            .member_at_epoch(epoch_1, LeafNodeIndex::new(1))
+            .map(|member| member.credential.identity().to_vec()),
+        Some(b"Bob".to_vec())
+    );
+    assert!(alice_group
+        .member_at_epoch(epoch_2, LeafNodeIndex::new(1))
+        .is_none());
+
+    // Epochs beyond the retained window and future epochs are unknown.
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update own leaf.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    assert!(alice_group.members_at_epoch(epoch_0).is_none());
+    assert!(alice_group.members_at_epoch(epoch_1).is_some());
+    assert!(alice_group
+        .members_at_epoch(GroupEpoch::from(alice_group.epoch().as_u64() + 1))
+        .is_none());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn commit_audit_log(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use crate::ciphersuite::signable::SignatureError;
+    use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, bob_kpb, bob_signer, bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_commit_audit_log(true)
+        .build();
+    assert!(mls_group_config.use_commit_audit_log());
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id.clone(),
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+    assert!(alice_group.commit_audit_log().is_empty());
+
+    // === Alice adds Bob ===
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+
+    let entry = &alice_group.commit_audit_log()[0];
+    assert_eq!(entry.epoch(), alice_group.epoch());
+    assert_eq!(entry.sender(), &Sender::build_member(LeafNodeIndex::new(0)));
+    assert_eq!(entry.proposals().proposal_types(), &[ProposalType::Add]);
+    assert_eq!(
+        entry.proposals().added_members(),
+        &[bob_credential_with_key.credential.clone()]
+    );
+    assert!(entry.proposals().removed_members().is_empty());
+    assert_eq!(
+        entry.confirmed_transcript_hash(),
+        alice_group.confirmed_transcript_hash()
+    );
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+    assert!(bob_group.commit_audit_log().is_empty());
+
+    // === Bob updates and Alice processes the commit ===
+    let (commit, _welcome, _group_info) = bob_group
+        .self_update(backend, &bob_signer)
+        .expect("Could not update own leaf.");
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let processed_message = alice_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process message.");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    {
+        alice_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Could not merge commit.");
+    } else {
+        unreachable!("Expected a StagedCommit.");
+    }
+
+    // Both members recorded the same entry.
+    assert_eq!(alice_group.commit_audit_log().len(), 2);
+    assert_eq!(
+        alice_group.commit_audit_log()[1],
+        bob_group.commit_audit_log()[0]
+    );
+    assert_eq!(
+        bob_group.commit_audit_log()[0].sender(),
+        &Sender::build_member(LeafNodeIndex::new(1))
+    );
+
+    // === Alice removes Bob ===
+    alice_group
+        .remove_members(backend, &alice_signer, &[LeafNodeIndex::new(1)])
+        .expect("Could not remove member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let entry = &alice_group.commit_audit_log()[2];
+    assert_eq!(entry.proposals().proposal_types(), &[ProposalType::Remove]);
+    assert_eq!(
+        entry.proposals().removed_members(),
+        &[bob_credential_with_key.credential]
+    );
+    assert_eq!(
+        entry.confirmation_tag(),
+        alice_group.group.public_group().confirmation_tag()
+    );
+
+    // === Alice exports the log ===
+    let audit_log = alice_group
+        .export_commit_audit_log(&alice_signer)
+        .expect("Could not export audit log.");
+    let serialized = audit_log
+        .tls_serialize_detached()
+        .expect("Could not serialize audit log.");
+    let audit_log = CommitAuditLog::tls_deserialize(&mut serialized.as_slice())
+        .expect("Could not deserialize audit log.");
+    assert_eq!(audit_log.group_id(), &group_id);
+    assert_eq!(audit_log.epoch(), alice_group.epoch());
+    assert_eq!(audit_log.signer(), LeafNodeIndex::new(0));
+    assert_eq!(audit_log.entries(), alice_group.commit_audit_log());
+    audit_log
+        .verify(backend, &alice_pk)
+        .expect("Invalid signature.");
+    assert_eq!(
+        audit_log.verify(backend, &bob_pk),
+        Err(SignatureError::VerificationError)
+    );
+
+    // The log is persisted with the group.
+    let serialized_group = serde_json::to_vec(&alice_group).expect("Could not serialize group.");
+    let loaded_group: MlsGroup =
+        serde_json::from_slice(&serialized_group).expect("Could not deserialize group.");
+    assert_eq!(
+        loaded_group.commit_audit_log(),
+        alice_group.commit_audit_log()
+    );
+
+    // === Without the flag, nothing is recorded ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update own leaf.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    assert!(alice_group.commit_audit_log().is_empty());
+    assert_eq!(
+        alice_group.export_commit_audit_log(&alice_signer),
+        Err(ExportCommitAuditLogError::AuditLogDisabled)
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn group_state_transfer(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Error creating group from Welcome");
+
+    // === Alice's new device publishes a key package ===
+    let new_backend = OpenMlsRustCrypto::default();
+    let key_package = KeyPackage::builder()
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            &new_backend,
+            &alice_signer,
+            alice_credential_with_key,
+        )
+        .expect("Could not create key package.");
+
+    // The key package must belong to the own leaf.
+    assert_eq!(
+        alice_group.transfer_to_device(backend, &alice_signer, bob_kpb.key_package()),
+        Err(GroupStateTransferError::KeyPackageMismatch)
+    );
+    assert!(alice_group.is_active());
+
+    // === Alice's old device transfers the group ===
+    let transfer = alice_group
+        .transfer_to_device(backend, &alice_signer, &key_package)
+        .expect("Could not transfer group.");
+    // The old device stays active until the import was confirmed.
+    assert!(alice_group.is_active());
+    assert_eq!(transfer.group_id(), alice_group.group_id());
+    assert_eq!(transfer.epoch(), alice_group.epoch());
+    assert_eq!(
+        transfer.key_package_ref(),
+        &key_package
+            .hash_ref(backend.crypto())
+            .expect("Could not compute hash ref.")
+    );
+
+    let serialized = transfer
+        .tls_serialize_detached()
+        .expect("Could not serialize transfer.");
+    let transfer = GroupStateTransfer::tls_deserialize(&mut serialized.as_slice())
+        .expect("Could not deserialize transfer.");
+
+    // A transfer with an invalid signature is rejected.
+    let mut tampered = serialized.clone();
+    *tampered.last_mut().expect("Empty transfer.") ^= 0xff;
+    let tampered = GroupStateTransfer::tls_deserialize(&mut tampered.as_slice())
+        .expect("Could not deserialize transfer.");
+    assert_eq!(
+        MlsGroup::new_from_state_transfer(&new_backend, &mls_group_config, tampered)
+            .expect_err("A tampered transfer was imported."),
+        GroupStateTransferError::InvalidSignature
+    );
+
+    // === Alice's new device imports the group ===
+    let mut new_alice_group =
+        MlsGroup::new_from_state_transfer(&new_backend, &mls_group_config, transfer.clone())
+            .expect("Could not import group.");
+
+    // === Alice's old device completes the transfer ===
+    alice_group
+        .complete_device_transfer(backend)
+        .expect("Could not complete transfer.");
+    assert!(!alice_group.is_active());
+    assert_eq!(new_alice_group.epoch(), alice_group.epoch());
+    assert_eq!(
+        new_alice_group.own_leaf_index(),
+        alice_group.own_leaf_index()
+    );
+    assert!(new_alice_group.is_active());
+
+    // The key package can only be used once.
+    assert_eq!(
+        MlsGroup::new_from_state_transfer(&new_backend, &mls_group_config, transfer)
+            .expect_err("The key package was used twice."),
+        GroupStateTransferError::NoMatchingKeyPackage
+    );
+
+    // The new device can read and send messages.
+    let message = bob_group
+        .create_message(backend, &bob_signer, b"Hello Alice")
+        .expect("Could not create message.");
+    let processed_message = new_alice_group
+        .process_message(
+            &new_backend,
+            message
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process message.");
+    assert!(matches!(
+        processed_message.into_content(),
+        ProcessedMessageContent::ApplicationMessage(_)
+    ));
+
+    // The new device updates its leaf and Bob follows.
+    let (commit, _welcome, _group_info) = new_alice_group
+        .self_update(&new_backend, &alice_signer)
+        .expect("Could not update own leaf.");
+    new_alice_group
+        .merge_pending_commit(&new_backend)
+        .expect("Could not merge commit.");
+    let processed_message = bob_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process message.");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    {
+        bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Could not merge commit.");
+    } else {
+        unreachable!("Expected a StagedCommit.");
+    }
+
+    let message = new_alice_group
+        .create_message(&new_backend, &alice_signer, b"Hello Bob")
+        .expect("Could not create message.");
+    bob_group
+        .process_message(
+            backend,
+            message
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process message.");
+}
+
+#[apply(ciphersuites_and_backends)]
+fn reused_key_package(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+
+    let _bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.clone(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // The key package was consumed by the first Welcome.
+    let err = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect_err("Processed the same Welcome twice.");
+    assert!(matches!(err, WelcomeError::KeyPackageAlreadyConsumed));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn desync_detection(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // Bob misses the first of two commits.
+    let (missed_commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not self update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not self update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let err = bob_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect_err("Processed a commit from a future epoch.");
+    let ProcessMessageError::Desynced(report) = err else {
+        panic!("Unexpected error: {err:?}");
+    };
+    assert_eq!(report.kind(), DesyncKind::EpochBehind);
+    assert_eq!(report.group_epoch(), bob_group.epoch());
+    assert_eq!(report.epochs_behind(), 1);
+    assert_eq!(report.epochs_ahead(), 0);
+    assert!(report.requires_resync());
+
+    // Alice's group info allows Bob to rejoin with an External Commit, but
+    // only if it contains the external public key.
+    let group_info: MlsMessageIn = alice_group
+        .export_group_info(backend, &alice_signer, true)
+        .expect("Could not export group info.")
+        .into();
+    let group_info = group_info
+        .into_verifiable_group_info()
+        .expect("Unexpected message type.");
+    assert!(report.can_resync_with(&group_info));
+    let group_info: MlsMessageIn = alice_group
+        .export_group_info_with_options(
+            backend,
+            &alice_signer,
+            &GroupInfoExportOptions::new().with_external_pub(false),
+        )
+        .expect("Could not export group info.")
+        .into();
+    let group_info = group_info
+        .into_verifiable_group_info()
+        .expect("Unexpected message type.");
+    assert!(!report.can_resync_with(&group_info));
+
+    // Bob catches up once the missed commit arrives.
+    let processed_message = bob_group
+        .process_message(
+            backend,
+            missed_commit
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process commit.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a staged commit.");
+    };
+    bob_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("Could not merge commit.");
+}
+
+#[apply(ciphersuites_and_backends)]
+fn deferred_merge(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+    assert!(bob_group.read_staged_commit(backend).is_none());
+
+    let process_commit = |group: &mut MlsGroup, commit: MlsMessageOut| {
+        let processed_message = group
+            .process_message(
+                backend,
+                commit
+                    .into_protocol_message()
+                    .expect("Unexpected message type."),
+            )
+            .expect("Could not process commit.");
+        match processed_message.into_content() {
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => *staged_commit,
+            _ => panic!("Expected a staged commit."),
+        }
+    };
+
+    // Bob stages Alice's commit and persists it before the restart.
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not self update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let staged_commit = process_commit(&mut bob_group, commit);
+    assert_eq!(staged_commit.group_id(), bob_group.group_id());
+    assert_eq!(staged_commit.epoch(), bob_group.epoch());
+    bob_group
+        .store_staged_commit(backend, &staged_commit)
+        .expect("Could not store staged commit.");
+
+    let mut serialized = Vec::new();
+    bob_group
+        .save(&mut serialized)
+        .expect("Could not save group.");
+    let mut bob_group = MlsGroup::load(serialized.as_slice()).expect("Could not load group.");
+
+    // After the restart, Bob merges the stored commit.
+    let staged_commit = bob_group
+        .read_staged_commit(backend)
+        .expect("No staged commit was stored.");
+    bob_group
+        .merge_staged_commit(backend, staged_commit)
+        .expect("Could not merge commit.");
+    assert_eq!(bob_group.epoch(), alice_group.epoch());
+    assert_eq!(
+        bob_group.epoch_authenticator(),
+        alice_group.epoch_authenticator()
+    );
+    assert!(bob_group.read_staged_commit(backend).is_none());
+
+    // The key material of the new epoch was restored as well.
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello Bob")
+        .expect("Could not create message.");
+    let processed_message = bob_group
+        .process_message(
+            backend,
+            message
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process message.");
+    assert!(matches!(
+        processed_message.into_content(),
+        ProcessedMessageContent::ApplicationMessage(_)
+    ));
+
+    // A stored commit can't be merged once the group moved on.
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not self update.");
+    let staged_commit = process_commit(&mut bob_group, commit);
+    bob_group
+        .store_staged_commit(backend, &staged_commit)
+        .expect("Could not store staged commit.");
+    let outdated_commit = bob_group
+        .read_staged_commit(backend)
+        .expect("No staged commit was stored.");
+    bob_group
+        .merge_staged_commit(backend, staged_commit)
+        .expect("Could not merge commit.");
+    let err = bob_group
+        .merge_staged_commit(backend, outdated_commit)
+        .expect_err("Merged an outdated commit.");
+    assert!(matches!(err, MergeCommitError::WrongEpoch));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn compute_proposal_ref(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    let into_public_message = |message: MlsMessageOut| {
+        let message: MlsMessageIn = message.into();
+        match message.extract() {
+            MlsMessageInBody::PublicMessage(public_message) => public_message,
+            _ => panic!("Expected a public message."),
+        }
+    };
+
+    // The reference computed from the message matches the reference of the
+    // sender and of the receiving group.
+    let (proposal, proposal_ref) = alice_group
+        .propose_remove_member(backend, &alice_signer, bob_group.own_leaf_index())
+        .expect("Could not create proposal.");
+    let public_message = into_public_message(proposal);
+    let computed_ref = public_message
+        .proposal_ref(backend.crypto(), ciphersuite)
+        .expect("Could not compute proposal reference.");
+    assert_eq!(computed_ref, proposal_ref);
+
+    let processed_message = bob_group
+        .process_message(backend, public_message)
+        .expect("Could not process proposal.");
+    let ProcessedMessageContent::ProposalMessage(queued_proposal) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a proposal.");
+    };
+    assert_eq!(queued_proposal.proposal_reference(), computed_ref);
+
+    // Commits don't have a proposal reference.
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Could not commit to pending proposals.");
+    let err = into_public_message(commit)
+        .proposal_ref(backend.crypto(), ciphersuite)
+        .expect_err("Computed a proposal reference for a commit.");
+    assert_eq!(err, ComputeProposalRefError::NotAProposal);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn unique_identities(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    // A second device of Bob with the same identity
+    let (bob_device_credential, bob_device_kpb, bob_device_signer, _bob_device_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .unique_identities(true)
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // Bob's second device can't be added while Bob is a member.
+    let err = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[bob_device_kpb.key_package().clone()],
+        )
+        .expect_err("Added an existing identity.");
+    assert!(matches!(
+        err,
+        AddMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
+            ProposalValidationError::ExistingIdentityAddProposal
+        ))
+    ));
+    let err = alice_group
+        .propose_add_member(backend, &alice_signer, bob_device_kpb.key_package())
+        .expect_err("Proposed to add an existing identity.");
+    assert!(matches!(
+        err,
+        ProposeAddMemberError::ProposalValidationError(
+            ProposalValidationError::ExistingIdentityAddProposal
+        )
+    ));
+
+    // A member without the policy can add the device, but Bob rejects the
+    // commit.
+    alice_group.set_configuration(&MlsGroupConfig::test_default(ciphersuite));
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[bob_device_kpb.key_package().clone()],
+        )
+        .expect("Could not add member to group.");
+    let err = bob_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect_err("Processed a commit that adds an existing identity.");
+    assert!(matches!(
+        err,
+        ProcessMessageError::InvalidProposal(ProposalValidationError::ExistingIdentityAddProposal)
+    ));
+    alice_group.clear_pending_commit();
+    alice_group.set_configuration(&mls_group_config);
+
+    // Bob's device can rejoin with an External Commit, since it removes the
+    // old leaf of Bob.
+    let group_info: MlsMessageIn = alice_group
+        .export_group_info(backend, &alice_signer, true)
+        .expect("Could not export group info.")
+        .into();
+    let (_bob_device_group, commit, _group_info) = MlsGroup::join_by_external_commit(
+        backend,
+        &bob_device_signer,
+        None,
+        group_info
+            .into_verifiable_group_info()
+            .expect("Unexpected message type."),
+        &mls_group_config,
+        b"",
+        bob_device_credential,
+    )
+    .expect("Could not join by external commit.");
+    let processed_message = alice_group
+        .process_message(
+            backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process external commit.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a staged commit.");
+    };
+    alice_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("Could not merge commit.");
+    assert_eq!(alice_group.members().count(), 2);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn clock_skew_tolerance(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    // A clock that is two hours behind.
+    struct SkewedClock;
+
+    impl TimeProvider for SkewedClock {
+        fn unix_now(&self) -> Option<u64> {
+            SystemTimeProvider.unix_now().map(|now| now - 2 * 60 * 60)
+        }
+    }
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // Charlie's key package is valid from one hour ago, which is in the
+    // future for Bob's skewed clock.
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[charlie_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    let commit: MlsMessageIn = commit.into();
+    let commit = commit
+        .into_protocol_message()
+        .expect("Unexpected message type.");
+
+    bob_group.set_configuration(
+        &MlsGroupConfig::builder()
+            .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .time_provider(SkewedClock)
+            .build(),
+    );
+    let err = bob_group
+        .process_message(backend, commit.clone())
+        .expect_err("Accepted a key package that is not yet valid.");
+    assert_eq!(
+        err,
+        ProcessMessageError::ValidationError(ValidationError::KeyPackageVerifyError(
+            crate::key_packages::errors::KeyPackageVerifyError::InvalidLifetime
+        ))
+    );
+
+    // With a tolerance for the skew, the key package is accepted.
+    bob_group.set_configuration(
+        &MlsGroupConfig::builder()
+            .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .time_provider(SkewedClock)
+            .clock_skew_tolerance(std::time::Duration::from_secs(2 * 60 * 60))
+            .build(),
+    );
+    let processed_message = bob_group
+        .process_message(backend, commit)
+        .expect("Could not process commit.");
+    assert!(matches!(
+        processed_message.into_content(),
+        ProcessedMessageContent::StagedCommitMessage(_)
+    ));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn message_secrets_budget(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_past_epochs(5)
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    for _ in 0..3 {
+        alice_group
+            .self_update(backend, &alice_signer)
+            .expect("Could not update own leaf.");
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("error merging pending commit");
+    }
+    let usage = alice_group.message_secrets_usage();
+    assert_eq!(usage.budget(), None);
+    assert_eq!(usage.retained_past_epochs(), 3);
+    assert!(usage.past_epochs_size() > 0);
+    assert_eq!(
+        usage.size(),
+        usage.current_epoch_size() + usage.past_epochs_size()
+    );
+    assert!(!usage.exceeds_budget());
+
+    // The oldest epoch is deleted to stay within the budget.
+    let budget = usage.size() - 1;
+    alice_group.set_configuration(
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .max_past_epochs(5)
+            .message_secrets_budget(budget)
+            .build(),
+    );
+    let usage = alice_group.message_secrets_usage();
+    assert_eq!(usage.budget(), Some(budget));
+    assert_eq!(usage.retained_past_epochs(), 2);
+    assert!(usage.size() <= budget);
+    assert!(!usage.exceeds_budget());
+    assert_eq!(
+        alice_group
+            .group
+            .past_epochs()
+            .map(|epoch| epoch.as_u64())
+            .collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    // The budget is enforced when an epoch ends.
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update own leaf.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let usage = alice_group.message_secrets_usage();
+    assert!(usage.size() <= budget);
+    assert!(usage.retained_past_epochs() <= 3);
+
+    // The secrets of the current epoch are never deleted.
+    alice_group.set_configuration(
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .max_past_epochs(5)
+            .message_secrets_budget(0)
+            .build(),
+    );
+    let usage = alice_group.message_secrets_usage();
+    assert_eq!(usage.retained_past_epochs(), 0);
+    assert!(usage.current_epoch_size() > 0);
+    assert!(usage.exceeds_budget());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn membership_tokens(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // Keep the secrets of one past epoch
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_past_epochs(1)
+        .use_ratchet_tree_extension(true)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Error creating group from Welcome");
+
+    // Alice creates a token that Bob verifies.
+    let context = b"SFU session 42";
+    let token = alice_group
+        .membership_token(backend, &alice_signer, context)
+        .expect("Could not create token.");
+    assert_eq!(token.epoch(), alice_group.epoch());
+    assert_eq!(token.leaf_index(), LeafNodeIndex::new(0));
+    assert_eq!(token.mac().len(), ciphersuite.hash_length());
+    let token = MembershipToken::tls_deserialize_exact(
+        token
+            .tls_serialize_detached()
+            .expect("Could not serialize token."),
+    )
+    .expect("Could not deserialize token.");
+    let member = bob_group
+        .verify_membership_token(backend, &token, context)
+        .expect("Could not verify token.");
+    assert_eq!(member.index, LeafNodeIndex::new(0));
+    assert_eq!(
+        alice_group
+            .membership_token(backend, &alice_signer, context)
+            .expect("Could not create token.")
+            .mac(),
+        token.mac()
+    );
+
+    // The token is bound to the context and the leaf index.
+    assert_eq!(
+        bob_group.verify_membership_token(backend, &token, b"SFU session 43"),
+        Err(VerifyMembershipTokenError::InvalidToken)
+    );
+    let bob_token = bob_group
+        .membership_token(backend, &bob_signer, context)
+        .expect("Could not create token.");
+    let mut serialized = token
+        .tls_serialize_detached()
+        .expect("Could not serialize token.");
+    // The leaf index follows the epoch.
+    serialized[8..12].copy_from_slice(&1u32.to_be_bytes());
+    let forged_token =
+        MembershipToken::tls_deserialize_exact(serialized).expect("Could not deserialize token.");
+    assert_eq!(
+        alice_group.verify_membership_token(backend, &forged_token, context),
+        Err(VerifyMembershipTokenError::InvalidToken)
+    );
+
+    // Every member can compute the HMAC of the token of every other member,
+    // but only the member at the leaf index can sign the token.
+    let mut serialized = token
+        .tls_serialize_detached()
+        .expect("Could not serialize token.");
+    *serialized.last_mut().expect("Empty token.") ^= 0xff;
+    let forged_token =
+        MembershipToken::tls_deserialize_exact(serialized).expect("Could not deserialize token.");
+    assert_eq!(
+        bob_group.verify_membership_token(backend, &forged_token, context),
+        Err(VerifyMembershipTokenError::InvalidSignature)
+    );
+    assert_eq!(
+        alice_group
+            .verify_membership_token(backend, &bob_token, context)
+            .expect("Could not verify token.")
+            .index,
+        LeafNodeIndex::new(1)
+    );
+
+    // Tokens of past epochs are verified as long as the secrets of the epoch
+    // are kept.
+    for _ in 0..2 {
+        let (commit, _, _) = alice_group
+            .self_update(backend, &alice_signer)
+            .expect("Could not update.");
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("Could not merge commit.");
+        let processed_message = bob_group
+            .process_message(backend, commit.into_protocol_message().unwrap())
+            .expect("Could not process commit.");
+        if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+            processed_message.into_content()
+        {
+            bob_group
+                .merge_staged_commit(backend, *staged_commit)
+                .expect("Could not merge commit.");
+        } else {
+            unreachable!("Expected a StagedCommit.");
+        }
+
+        if bob_group.epoch().as_u64() == token.epoch().as_u64() + 1 {
+            bob_group
+                .verify_membership_token(backend, &token, context)
+                .expect("Could not verify token of a past epoch.");
+        }
+    }
+    assert_eq!(
+        bob_group.verify_membership_token(backend, &token, context),
+        Err(VerifyMembershipTokenError::UnknownEpoch)
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn sframe_keys(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use openmls_traits::{crypto::OpenMlsCrypto, types::HashType};
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // Keep the secrets of one past epoch
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_past_epochs(1)
+        .use_ratchet_tree_extension(true)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Error creating group from Welcome");
+
+    // Both members derive the same base keys, which follow RFC 9605.
+    let epoch = alice_group.epoch();
+    let cipher = SframeCipher::Aes256GcmSha512_128;
+    let alice_keys = alice_group
+        .derive_sframe_keys(backend, epoch, cipher)
+        .expect("Could not derive SFrame keys.");
+    let bob_keys = bob_group
+        .derive_sframe_keys(backend, epoch, cipher)
+        .expect("Could not derive SFrame keys.");
+    assert_eq!(alice_keys, bob_keys);
+    assert_eq!(alice_keys.base_keys().count(), 2);
+    assert!(alice_keys.base_key(LeafNodeIndex::new(2)).is_none());
+
+    let sframe_epoch_secret = alice_group
+        .export_secret(backend, "SFrame 1.0 Base Key", &[], 64)
+        .expect("Could not export secret.");
+    let expected_base_key = backend
+        .crypto()
+        .hkdf_expand(
+            HashType::Sha2_512,
+            &sframe_epoch_secret,
+            &1u32.to_be_bytes(),
+            64,
+        )
+        .expect("Could not expand secret.");
+    assert_eq!(
+        alice_keys.base_key(LeafNodeIndex::new(1)),
+        Some(expected_base_key.as_slice())
+    );
+    assert_ne!(
+        alice_keys.base_key(LeafNodeIndex::new(0)),
+        alice_keys.base_key(LeafNodeIndex::new(1))
+    );
+
+    // The keys depend on the cipher suite.
+    let sha256_keys = alice_group
+        .derive_sframe_keys(backend, epoch, SframeCipher::Aes128GcmSha256_128)
+        .expect("Could not derive SFrame keys.");
+    assert_eq!(
+        sha256_keys
+            .base_key(LeafNodeIndex::new(0))
+            .expect("Missing base key.")
+            .len(),
+        32
+    );
+
+    // Key IDs pack the context ID, the sender index and the epoch.
+    assert_eq!(
+        alice_keys.key_id(LeafNodeIndex::new(1), 3, 4, 8),
+        Some((3 << 12) | (1 << 4) | (epoch.as_u64() & 0xf))
+    );
+    assert_eq!(alice_keys.key_id(LeafNodeIndex::new(4), 0, 4, 2), None);
+    assert_eq!(alice_keys.key_id(LeafNodeIndex::new(1), 1, 32, 32), None);
+
+    // The keys rotate with the epoch and keys of past epochs can still be
+    // derived as long as the secrets of the epoch are kept.
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let new_keys = alice_group
+        .derive_sframe_keys(backend, alice_group.epoch(), cipher)
+        .expect("Could not derive SFrame keys.");
+    assert_ne!(
+        new_keys.base_key(LeafNodeIndex::new(0)),
+        alice_keys.base_key(LeafNodeIndex::new(0))
+    );
+    assert_eq!(
+        alice_group
+            .derive_sframe_keys(backend, epoch, cipher)
+            .expect("Could not derive SFrame keys of a past epoch."),
+        alice_keys
+    );
+    assert_eq!(
+        alice_group.derive_sframe_keys(
+            backend,
+            GroupEpoch::from(alice_group.epoch().as_u64() + 1),
+            cipher
+        ),
+        Err(ExportSecretError::UnknownEpoch)
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn immediate_key_deletion(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use crate::tree::secret_tree::{SecretTreeError, SecretType};
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // Tolerate out-of-order messages, which is overridden by the immediate
+    // key deletion.
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .sender_ratchet_configuration(SenderRatchetConfiguration::new(10, 1000))
+        .max_past_epochs(1)
+        .use_ratchet_tree_extension(true)
+        .immediate_key_deletion(true)
+        .build();
+    assert!(mls_group_config.immediate_key_deletion());
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Error creating group from Welcome");
+
+    // The keys of skipped generations are not kept, so messages that arrive
+    // out of order can't be decrypted.
+    let first_message = alice_group
+        .create_message(backend, &alice_signer, b"First")
+        .expect("Could not create message.");
+    let second_message = alice_group
+        .create_message(backend, &alice_signer, b"Second")
+        .expect("Could not create message.");
+    bob_group
+        .process_message(backend, second_message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    assert_eq!(bob_group.message_secrets_usage().retained_keys(), 0);
+    assert!(bob_group
+        .process_message(backend, first_message.into_protocol_message().unwrap())
+        .is_err());
+
+    // Once the epoch ended, its own encryption ratchets are deleted.
+    let past_epoch = alice_group.epoch();
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let own_leaf_index = alice_group.own_leaf_index();
+    let past_secret_tree = alice_group
+        .group
+        .message_secrets_mut(past_epoch)
+        .expect("Missing secrets of the past epoch.")
+        .secret_tree_mut();
+    for secret_type in [SecretType::HandshakeSecret, SecretType::ApplicationSecret] {
+        assert_eq!(
+            past_secret_tree
+                .secret_for_encryption(ciphersuite, backend, own_leaf_index, secret_type)
+                .map(|(generation, _)| generation),
+            Err(SecretTreeError::RatchetTooLong)
+        );
+    }
+}
+
+/// A signer that records the labels of the payloads it signs and that can be
+/// cancelled, like a hardware token that asks the user for confirmation.
+struct RecordingSigner<'a> {
+    signer: &'a openmls_basic_credential::SignatureKeyPair,
+    labels: std::cell::RefCell<Vec<String>>,
+    cancel: bool,
+}
+
+impl Signer for RecordingSigner<'_> {
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>, openmls_traits::types::Error> {
+        // The label is the first field of the payload, with a one-byte
+        // length prefix for short labels.
+        let label_length = payload[0] as usize;
+        self.labels
+            .borrow_mut()
+            .push(String::from_utf8(payload[1..1 + label_length].to_vec()).unwrap());
+        if self.cancel {
+            return Err(openmls_traits::types::Error::SigningCancelled);
+        }
+        self.signer.sign(payload)
+    }
+
+    fn signature_scheme(&self) -> openmls_traits::types::SignatureScheme {
+        self.signer.signature_scheme()
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn signer_errors(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use crate::ciphersuite::signable::{signature_labels, SignatureError};
+    use openmls_traits::types::Error;
+
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // A self-update signs the new leaf node and the commit exactly once.
+    let recording_signer = RecordingSigner {
+        signer: &alice_signer,
+        labels: Default::default(),
+        cancel: false,
+    };
+    assert_eq!(
+        recording_signer.signature_scheme(),
+        alice_signer.signature_scheme()
+    );
+    alice_group
+        .self_update(backend, &recording_signer)
+        .expect("Could not update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    assert_eq!(
+        recording_signer.labels.into_inner(),
+        vec![
+            format!("MLS 1.0 {}", signature_labels::LEAF_NODE),
+            format!("MLS 1.0 {}", signature_labels::FRAMED_CONTENT),
+        ]
+    );
+
+    // Errors of the signer are returned to the caller.
+    let cancelling_signer = RecordingSigner {
+        signer: &alice_signer,
+        labels: Default::default(),
+        cancel: true,
+    };
+    let err = alice_group
+        .self_update(backend, &cancelling_signer)
+        .expect_err("The update was signed.");
+    assert_eq!(
+        err,
+        SelfUpdateError::CreateCommitError(CreateCommitError::SignatureError(
+            SignatureError::SignerError(Error::SigningCancelled)
+        ))
+    );
+    let err = alice_group
+        .create_message(backend, &cancelling_signer, b"Hello")
+        .expect_err("The message was signed.");
+    assert_eq!(
+        err,
+        CreateMessageError::SignatureError(SignatureError::SignerError(Error::SigningCancelled))
+    );
+
+    // The group is still usable after the signer failed.
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    alice_group
+        .create_message(backend, &alice_signer, b"Hello")
+        .expect("Could not create message.");
+}
+
+#[apply(ciphersuites_and_backends)]
+fn basic_identity_schema(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("alice@example.com", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("bob@example.com", ciphersuite, backend);
+    let (_charlie_credential, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .basic_identity_schema(BasicIdentitySchema::new(
+            IdentityFormat::UserAtDomain,
+            DEFAULT_MAX_IDENTITY_LENGTH,
+        ))
+        .build();
+    assert!(mls_group_config.basic_identity_schema().is_some());
+
+    // === Alice creates a group and refuses to add Charlie ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let error = alice_group
+        .add_members(backend, &alice_signer, &[charlie_kpb.key_package().clone()])
+        .expect_err("Added a member with an invalid identity.");
+    assert!(matches!(error, AddMembersError::InvalidCredential));
+
+    // === Alice adds Bob and Charlie without the schema ===
+    alice_group.set_configuration(
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .build(),
+    );
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    // === Bob refuses to join a group with Charlie ===
+    let error = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect_err("Joined a group with a member with an invalid identity.");
+    assert!(matches!(error, WelcomeError::InvalidCredential));
+}
@@ -1,7 +1,11 @@
 use core_group::create_commit_params::CreateCommitParams;
 use openmls_traits::signatures::Signer;
 
-use crate::{messages::group_info::GroupInfo, treesync::LeafNode, versions::ProtocolVersion};
+use crate::{
+    messages::group_info::GroupInfo,
+    treesync::{node::leaf_node::Capabilities, LeafNode},
+    versions::ProtocolVersion,
+};
 
 use super::*;
 
@@ -12,7 +16,8 @@ impl MlsGroup {
     /// commit), an optional [`MlsMessageOut`] (containing the [`Welcome`]) and the [GroupInfo].
     /// The [Welcome] is [Some] when the queue of pending proposals contained
     /// add proposals
-    /// The [GroupInfo] is [Some] if the group has the `use_ratchet_tree_extension` flag set.
+    /// The [GroupInfo] is [Some] if the group has the `use_ratchet_tree_extension` or the
+    /// `always_return_group_info` flag set.
     ///
     /// Returns an error if there is a pending commit.
     ///
@@ -32,8 +37,9 @@ impl MlsGroup {
         self.is_operational()?;
 
         let params = CreateCommitParams::builder()
-            .framing_parameters(self.framing_parameters())
+            .framing_parameters(self.framing_parameters(ContentType::Commit))
             .proposal_store(&self.proposal_store)
+            .always_create_group_info(self.configuration().always_return_group_info())
             .build();
         // Create Commit over all proposals.
         // TODO #751
@@ -61,6 +67,61 @@ impl MlsGroup {
         ))
     }
 
+    /// Returns `true` if the [`UpdatePolicy`] of the group is configured and
+    /// one of its thresholds is exceeded, i.e., the own leaf should be
+    /// updated.
+    ///
+    /// If the local start time of the current epoch is unknown (see
+    /// [`MlsGroup::epoch_started_at()`]), the `max_epoch_age` is not taken
+    /// into account.
+    pub fn self_update_due(&self) -> bool {
+        let Some(update_policy) = self.configuration().update_policy() else {
+            return false;
+        };
+        let epoch_too_old = match (update_policy.max_epoch_age(), self.epoch_age()) {
+            (Some(max_epoch_age), Some(epoch_age)) => epoch_age >= max_epoch_age,
+            _ => false,
+        };
+        let too_many_messages = update_policy
+            .max_messages_sent()
+            .map_or(false, |max_messages_sent| {
+                self.messages_sent_in_epoch() >= max_messages_sent
+            });
+        epoch_too_old || too_many_messages
+    }
+
+    /// Updates the own leaf node if an update is due according to the
+    /// [`UpdatePolicy`] of the group. See [`MlsGroup::self_update_due()`].
+    ///
+    /// Returns `None` if no update is due. Otherwise, the result of
+    /// [`MlsGroup::self_update()`] is returned and the commit has to be sent
+    /// to the group and merged like any other commit.
+    #[allow(clippy::type_complexity)]
+    pub fn maybe_self_update<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+    ) -> Result<
+        Option<(MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>)>,
+        SelfUpdateError<KeyStore::Error>,
+    > {
+        if !self.self_update_due() {
+            return Ok(None);
+        }
+        self.self_update(backend, signer).map(Some)
+    }
+
+    /// Returns the number of application messages the own member sent in the
+    /// current epoch.
+    fn messages_sent_in_epoch(&self) -> u32 {
+        let (_handshake, application) = self
+            .group
+            .message_secrets()
+            .secret_tree()
+            .sender_ratchets(self.own_leaf_index());
+        application.map_or(0, |sender_ratchet| sender_ratchet.generation())
+    }
+
     /// Creates a proposal to update the own leaf node. Optionally, a
     /// [`LeafNode`] can be provided to update the leaf node. Note that its
     /// private key must be manually added to the key store.
@@ -106,7 +167,7 @@ impl MlsGroup {
         };
 
         let update_proposal = self.group.create_update_proposal(
-            self.framing_parameters(),
+            self.framing_parameters(ContentType::Proposal),
             own_leaf.clone(),
             signer,
         )?;
@@ -163,4 +224,138 @@ impl MlsGroup {
 
         Ok((mls_message, proposal_ref))
     }
+
+    /// Creates a proposal to update the own leaf node with the given
+    /// [`Capabilities`], e.g., to advertise support for extensions that were
+    /// added after joining the group. The leaf node is rekeyed in the
+    /// process.
+    ///
+    /// Returns an error if the new capabilities don't include the ciphersuite
+    /// and the protocol version of the group, or if the updated leaf node
+    /// doesn't satisfy the required capabilities of the group.
+    pub fn update_capabilities<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        capabilities: Capabilities,
+    ) -> Result<(MlsMessageOut, ProposalRef), UpdateCapabilitiesError<KeyStore::Error>> {
+        self.is_operational()?;
+
+        let ciphersuite = self.ciphersuite();
+        let version = self.group.version();
+        if !capabilities.ciphersuites().contains(&ciphersuite.into())
+            || !capabilities.versions().contains(&version)
+        {
+            return Err(UpdateCapabilitiesError::UnsupportedCiphersuiteOrVersion);
+        }
+
+        let mut own_leaf = self
+            .group
+            .public_group()
+            .leaf(self.own_leaf_index())
+            .ok_or_else(|| LibraryError::custom("The tree is broken. Couldn't find own leaf."))?
+            .clone();
+        *own_leaf.capabilities_mut() = capabilities;
+        // Rekeying signs the leaf node including the new capabilities.
+        let keypair = own_leaf.rekey(
+            self.group_id(),
+            self.own_leaf_index(),
+            ciphersuite,
+            version,
+            backend,
+            signer,
+        )?;
+
+        // Make sure that the other members will accept the new leaf node.
+        own_leaf.validate(&self.leaf_node_validation_context(&[self.own_leaf_index()]))?;
+
+        keypair
+            .write_to_key_store(backend)
+            .map_err(UpdateCapabilitiesError::KeyStoreError)?;
+
+        let update_proposal = self.group.create_update_proposal(
+            self.framing_parameters(ContentType::Proposal),
+            own_leaf.clone(),
+            signer,
+        )?;
+        self.own_leaf_nodes.push(own_leaf);
+
+        let proposal = QueuedProposal::from_authenticated_content_by_ref(
+            ciphersuite,
+            backend,
+            update_proposal.clone(),
+        )?;
+        let proposal_ref = proposal.proposal_reference();
+        self.proposal_store.add(proposal);
+
+        let mls_message = self.content_to_mls_message(update_proposal, backend)?;
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change();
+
+        Ok((mls_message, proposal_ref))
+    }
+
+    /// Creates a proposal to update the own leaf node with the given leaf
+    /// node [`Extensions`], e.g., to change the application ID, without
+    /// changing the credential or the signature key. The leaf node is
+    /// rekeyed in the process.
+    ///
+    /// Returns an error if the capabilities of the own leaf node don't
+    /// include the types of the new extensions or if the updated leaf node
+    /// isn't valid in the group otherwise.
+    pub fn update_own_leaf_extensions<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        extensions: Extensions,
+    ) -> Result<(MlsMessageOut, ProposalRef), UpdateLeafExtensionsError<KeyStore::Error>> {
+        self.is_operational()?;
+
+        let mut own_leaf = self
+            .group
+            .public_group()
+            .leaf(self.own_leaf_index())
+            .ok_or_else(|| LibraryError::custom("The tree is broken. Couldn't find own leaf."))?
+            .clone();
+        *own_leaf.extensions_mut() = extensions;
+        // Rekeying signs the leaf node including the new extensions.
+        let keypair = own_leaf.rekey(
+            self.group_id(),
+            self.own_leaf_index(),
+            self.ciphersuite(),
+            self.group.version(),
+            backend,
+            signer,
+        )?;
+
+        // Make sure that the other members will accept the new leaf node.
+        own_leaf.validate(&self.leaf_node_validation_context(&[self.own_leaf_index()]))?;
+
+        keypair
+            .write_to_key_store(backend)
+            .map_err(UpdateLeafExtensionsError::KeyStoreError)?;
+
+        let update_proposal = self.group.create_update_proposal(
+            self.framing_parameters(ContentType::Proposal),
+            own_leaf.clone(),
+            signer,
+        )?;
+        self.own_leaf_nodes.push(own_leaf);
+
+        let proposal = QueuedProposal::from_authenticated_content_by_ref(
+            self.ciphersuite(),
+            backend,
+            update_proposal.clone(),
+        )?;
+        let proposal_ref = proposal.proposal_reference();
+        self.proposal_store.add(proposal);
+
+        let mls_message = self.content_to_mls_message(update_proposal, backend)?;
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change();
+
+        Ok((mls_message, proposal_ref))
+    }
 }
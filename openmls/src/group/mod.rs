@@ -18,9 +18,8 @@ use tls_codec::*;
 pub(crate) mod core_group;
 pub(crate) mod public_group;
 pub(crate) use core_group::*;
+pub(crate) mod mls_client;
 pub(crate) mod mls_group;
-#[cfg(not(any(feature = "test-utils", test)))]
-pub(crate) use group_context::*;
 
 // Public
 pub mod config;
@@ -28,9 +27,25 @@ pub mod errors;
 
 pub use core_group::proposals::*;
 pub use core_group::staged_commit::StagedCommit;
+pub use group_context::GroupContext;
+pub use mls_client::*;
+pub use mls_group::audit_log::*;
 pub use mls_group::config::*;
+pub use mls_group::config_update::*;
+pub use mls_group::desync::*;
+pub use mls_group::device_transfer::*;
+pub use mls_group::epoch_history::*;
+pub use mls_group::forward_secrecy::*;
+pub use mls_group::fragmentation::*;
+pub use mls_group::group_info_export::*;
 pub use mls_group::membership::*;
+pub use mls_group::membership_token::*;
+pub use mls_group::metrics::*;
 pub use mls_group::processing::*;
+pub use mls_group::providers::*;
+pub use mls_group::ratchet_persistence::*;
+pub use mls_group::sframe::*;
+pub use mls_group::streaming::*;
 pub use mls_group::*;
 pub use public_group::*;
 
@@ -39,8 +54,6 @@ pub use public_group::*;
 pub(crate) use core_group::create_commit_params::*;
 #[cfg(any(feature = "test-utils", test))]
 pub(crate) mod tests;
-#[cfg(any(feature = "test-utils", test))]
-pub use group_context::GroupContext;
 use openmls_traits::random::OpenMlsRand;
 #[cfg(any(feature = "test-utils", test))]
 pub use proposals::*;
@@ -60,6 +73,7 @@ pub use proposals::*;
     TlsSerialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GroupId {
     value: VLBytes,
 }
@@ -115,6 +129,7 @@ impl GroupId {
     TlsSerialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GroupEpoch(u64);
 
 impl GroupEpoch {
@@ -2,11 +2,13 @@ use openmls_traits::{crypto::OpenMlsCrypto, signatures::Signer, OpenMlsCryptoPro
 
 use super::{errors::PublicGroupBuildError, PublicGroup};
 use crate::{
+    binary_tree::LeafNodeIndex,
     credentials::CredentialWithKey,
     error::LibraryError,
     extensions::{
-        errors::ExtensionError, Extension, Extensions, ExternalSendersExtension,
-        RequiredCapabilitiesExtension,
+        errors::ExtensionError, AcceptedMediaTypesExtension, Extension, ExtensionType, Extensions,
+        ExternalSendersExtension, RequiredCapabilitiesExtension, RequiredMediaTypesExtension,
+        RolesExtension,
     },
     group::{config::CryptoConfig, GroupContext, GroupId},
     messages::ConfirmationTag,
@@ -28,6 +30,9 @@ pub(crate) struct TempBuilderPG1 {
     required_capabilities: Option<RequiredCapabilitiesExtension>,
     external_senders: Option<ExternalSendersExtension>,
     leaf_extensions: Option<Extensions>,
+    custom_extension_types: Vec<ExtensionType>,
+    required_media_types: Option<RequiredMediaTypesExtension>,
+    roles: Option<RolesExtension>,
 }
 
 impl TempBuilderPG1 {
@@ -44,6 +49,33 @@ impl TempBuilderPG1 {
         self
     }
 
+    pub(crate) fn with_custom_extension_types(
+        mut self,
+        custom_extension_types: Vec<ExtensionType>,
+    ) -> Self {
+        self.custom_extension_types = custom_extension_types;
+        self
+    }
+
+    pub(crate) fn with_media_types(
+        mut self,
+        accepted_media_types: Option<AcceptedMediaTypesExtension>,
+        required_media_types: Option<RequiredMediaTypesExtension>,
+    ) -> Self {
+        if let Some(accepted_media_types) = accepted_media_types {
+            self.leaf_extensions
+                .get_or_insert_with(Extensions::empty)
+                .add_or_replace(Extension::from_custom(&accepted_media_types));
+        }
+        self.required_media_types = required_media_types;
+        self
+    }
+
+    pub(crate) fn with_roles(mut self, roles: RolesExtension) -> Self {
+        self.roles = Some(roles);
+        self
+    }
+
     pub(crate) fn with_external_senders(
         mut self,
         external_senders: ExternalSendersExtension,
@@ -59,10 +91,30 @@ impl TempBuilderPG1 {
         backend: &impl OpenMlsCryptoProvider,
         signer: &impl Signer,
     ) -> Result<(TempBuilderPG2, CommitSecret, EncryptionKeyPair), PublicGroupBuildError> {
-        let capabilities = self
+        // Advertise the required and the custom extension types, as well as
+        // the types of the own leaf extensions.
+        let mut capabilities = self
             .required_capabilities
             .as_ref()
-            .map(|re| re.extension_types());
+            .map(|re| re.extension_types().to_vec())
+            .unwrap_or_default();
+        let leaf_extension_types = self
+            .leaf_extensions
+            .iter()
+            .flat_map(|extensions| extensions.iter())
+            .map(|extension| extension.extension_type())
+            .filter(|extension_type| *extension_type != ExtensionType::ApplicationId)
+            .collect::<Vec<_>>();
+        for extension_type in self
+            .custom_extension_types
+            .iter()
+            .chain(leaf_extension_types.iter())
+        {
+            if !capabilities.contains(extension_type) {
+                capabilities.push(*extension_type);
+            }
+        }
+        let capabilities = (!capabilities.is_empty()).then_some(capabilities.as_slice());
         let (treesync, commit_secret, leaf_keypair) = TreeSync::new(
             backend,
             signer,
@@ -78,23 +130,38 @@ impl TempBuilderPG1 {
             ),
             self.leaf_extensions.unwrap_or(Extensions::empty()),
         )?;
+        if !treesync
+            .leaf(LeafNodeIndex::new(0))
+            .ok_or_else(|| LibraryError::custom("The new tree has no own leaf"))?
+            .supports_media_types(self.required_media_types.as_ref())
+        {
+            return Err(PublicGroupBuildError::UnsupportedMediaTypes);
+        }
         let required_capabilities = self.required_capabilities.unwrap_or_default();
-        required_capabilities.check_support().map_err(|e| match e {
-            ExtensionError::UnsupportedProposalType => {
-                PublicGroupBuildError::UnsupportedProposalType
-            }
-            ExtensionError::UnsupportedExtensionType => {
-                PublicGroupBuildError::UnsupportedExtensionType
-            }
-            _ => LibraryError::custom("Unexpected ExtensionError").into(),
-        })?;
+        required_capabilities
+            .check_support(&self.custom_extension_types)
+            .map_err(|e| match e {
+                ExtensionError::UnsupportedProposalType => {
+                    PublicGroupBuildError::UnsupportedProposalType
+                }
+                ExtensionError::UnsupportedExtensionType => {
+                    PublicGroupBuildError::UnsupportedExtensionType
+                }
+                _ => LibraryError::custom("Unexpected ExtensionError").into(),
+            })?;
         let required_capabilities = Extension::RequiredCapabilities(required_capabilities);
-        let extensions =
+        let mut extensions =
             if let Some(ext_senders) = self.external_senders.map(Extension::ExternalSenders) {
                 vec![required_capabilities, ext_senders]
             } else {
                 vec![required_capabilities]
             };
+        if let Some(required_media_types) = self.required_media_types {
+            extensions.push(Extension::from_custom(&required_media_types));
+        }
+        if let Some(roles) = self.roles {
+            extensions.push(Extension::from_custom(&roles));
+        }
         let group_context = GroupContext::create_initial_group_context(
             self.crypto_config.ciphersuite,
             self.group_id,
@@ -174,6 +241,9 @@ impl PublicGroup {
             required_capabilities: None,
             external_senders: None,
             leaf_extensions: None,
+            custom_extension_types: vec![],
+            required_media_types: None,
+            roles: None,
         }
     }
 }
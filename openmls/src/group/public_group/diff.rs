@@ -2,6 +2,7 @@
 //!
 //! This module contains the [`PublicGroupDiff`] struct, as well as the
 //! [`StagedPublicGroupDiff`] and associated functions and types.
+
 use std::collections::HashSet;
 
 use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
@@ -239,3 +240,10 @@ pub(crate) struct StagedPublicGroupDiff {
     pub(super) interim_transcript_hash: Vec<u8>,
     pub(super) confirmation_tag: ConfirmationTag,
 }
+
+impl StagedPublicGroupDiff {
+    /// Returns the group context of the group after the diff is merged.
+    pub(crate) fn group_context(&self) -> &GroupContext {
+        &self.group_context
+    }
+}
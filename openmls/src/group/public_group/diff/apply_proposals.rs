@@ -140,6 +140,18 @@ impl<'a> PublicGroupDiff<'a> {
             })
             .collect();
 
+        // Process the GroupContextExtensions proposal. Only the last one is
+        // kept by the proposal filter.
+        for queued_proposal in proposal_queue.filtered_by_type(ProposalType::GroupContextExtensions)
+        {
+            if let Proposal::GroupContextExtensions(group_context_extensions_proposal) =
+                queued_proposal.proposal()
+            {
+                self.group_context
+                    .set_extensions(group_context_extensions_proposal.extensions().clone());
+            }
+        }
+
         let proposals_require_path = proposal_queue
             .queued_proposals()
             .any(|p| p.proposal().is_path_required());
@@ -11,9 +11,10 @@ use crate::{
         config::CryptoConfig, core_group::create_commit_params::CommitType,
         errors::CreateCommitError,
     },
-    key_packages::{KeyPackage, KeyPackageCreationResult},
+    key_packages::errors::KeyPackageNewError,
     schedule::CommitSecret,
     treesync::{
+        errors::PublicTreeError,
         node::{
             encryption_keys::EncryptionKeyPair, leaf_node::LeafNode,
             parent_node::PlainUpdatePathNode,
@@ -49,44 +50,35 @@ impl<'a> PublicGroupDiff<'a> {
         let group_id = self.group_context().group_id().clone();
 
         let mut new_keypairs = if commit_type == CommitType::External {
+            if ciphersuite.signature_algorithm() != signer.signature_scheme() {
+                return Err(CreateCommitError::KeyPackageGenerationError(
+                    KeyPackageNewError::CiphersuiteSignatureSchemeMismatch,
+                ));
+            }
             // If this is an external commit we add a fresh leaf to the diff.
-            // Generate a KeyPackageBundle to generate a payload from for later
-            // path generation.
-            let KeyPackageCreationResult {
-                key_package,
-                encryption_keypair,
-                // The KeyPackage is immediately put into the group. No need for
-                // the init key.
-                init_private_key: _,
-            } = KeyPackage::builder().build_without_key_storage(
+            // The leaf is signed once the parent hash is set when applying
+            // the update path below.
+            let (leaf_node, encryption_keypair) = LeafNode::new_for_external_commit(
+                backend,
                 CryptoConfig {
                     ciphersuite,
                     version,
                 },
-                backend,
-                signer,
                 credential_with_key.ok_or(CreateCommitError::MissingCredential)?,
             )?;
-
-            let leaf_node: LeafNode = key_package.into();
             self.diff
                 .add_leaf(leaf_node)
                 .map_err(|_| LibraryError::custom("Tree full: cannot add more members"))?;
             vec![encryption_keypair]
         } else {
-            // If we're already in the tree, we rekey our existing leaf.
+            // If we're already in the tree, we rekey our existing leaf. The
+            // leaf is signed once the parent hash is set when applying the
+            // update path below.
             let own_diff_leaf = self
                 .diff
                 .leaf_mut(leaf_index)
                 .ok_or_else(|| LibraryError::custom("Unable to get own leaf from diff"))?;
-            let encryption_keypair = own_diff_leaf.rekey(
-                &group_id,
-                leaf_index,
-                ciphersuite,
-                version,
-                backend,
-                signer,
-            )?;
+            let encryption_keypair = own_diff_leaf.rekey_unsigned(ciphersuite, version, backend)?;
             vec![encryption_keypair]
         };
 
@@ -94,7 +86,11 @@ impl<'a> PublicGroupDiff<'a> {
         // generated new leaf.
         let (plain_path, mut new_parent_keypairs, commit_secret) = self
             .diff
-            .apply_own_update_path(backend, signer, ciphersuite, group_id, leaf_index)?;
+            .apply_own_update_path(backend, signer, ciphersuite, group_id, leaf_index)
+            .map_err(|e| match e {
+                PublicTreeError::SignatureError(e) => CreateCommitError::SignatureError(e),
+                e => e.into(),
+            })?;
 
         new_keypairs.append(&mut new_parent_keypairs);
 
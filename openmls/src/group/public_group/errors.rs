@@ -1,8 +1,10 @@
 use thiserror::Error;
 
 use crate::{
-    error::LibraryError, extensions::errors::InvalidExtensionError,
-    treesync::errors::TreeSyncFromNodesError,
+    ciphersuite::signable::SignatureError,
+    error::LibraryError,
+    extensions::errors::InvalidExtensionError,
+    treesync::errors::{LeafNodeCreationError, TreeSyncFromNodesError},
 };
 
 /// Public group creation from external error.
@@ -40,7 +42,47 @@ pub enum PublicGroupBuildError {
     /// Unsupported extension type in required capabilities.
     #[error("Unsupported extension type in required capabilities.")]
     UnsupportedExtensionType,
+    /// The own leaf doesn't accept the required media types.
+    #[error("The own leaf doesn't accept the required media types.")]
+    UnsupportedMediaTypes,
     /// Invalid extensions set in configuration
     #[error("Invalid extensions set in configuration")]
     InvalidExtensions(#[from] InvalidExtensionError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
+impl From<LeafNodeCreationError> for PublicGroupBuildError {
+    fn from(e: LeafNodeCreationError) -> Self {
+        match e {
+            LeafNodeCreationError::LibraryError(e) => Self::LibraryError(e),
+            LeafNodeCreationError::SignatureError(e) => Self::SignatureError(e),
+        }
+    }
+}
+
+/// Public group snapshot import error.
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum SnapshotImportError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// This error indicates the public tree is invalid. See [`TreeSyncFromNodesError`] for more details.
+    #[error(transparent)]
+    TreeSyncError(#[from] TreeSyncFromNodesError),
+    /// The digest of the snapshot doesn't match its content.
+    #[error("The digest of the snapshot doesn't match its content.")]
+    DigestMismatch,
+    /// The computed tree hash does not match the one in the group context.
+    #[error("The computed tree hash does not match the one in the group context.")]
+    TreeHashMismatch,
+}
+
+/// Add proposal error.
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum AddProposalError {
+    /// The proposal store is full and doesn't accept new proposals.
+    #[error("The proposal store is full and doesn't accept new proposals.")]
+    ProposalStoreFull,
 }
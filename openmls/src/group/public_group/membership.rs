@@ -0,0 +1,111 @@
+//! This module contains the [`MembershipChanges`] of a commit, which allow a
+//! delivery service to route Welcome messages and to notify clients about
+//! membership changes without access to the private state of the group.
+
+use openmls_traits::OpenMlsCryptoProvider;
+
+use super::PublicGroup;
+use crate::{
+    ciphersuite::hash_ref::KeyPackageRef,
+    credentials::Credential,
+    error::LibraryError,
+    group::{Member, StagedCommit},
+    messages::proposals::ProposalType,
+};
+
+/// A client that is added to the group by a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Joiner {
+    key_package_ref: Option<KeyPackageRef>,
+    credential: Credential,
+}
+
+impl Joiner {
+    /// Returns the [`KeyPackageRef`] of the key package that was used to add
+    /// the client. The Welcome message for the client can be found by this
+    /// reference.
+    ///
+    /// Returns `None` if the client joined through an External Commit and
+    /// thus doesn't require a Welcome message.
+    pub fn key_package_ref(&self) -> Option<&KeyPackageRef> {
+        self.key_package_ref.as_ref()
+    }
+
+    /// Returns the [`Credential`] of the client.
+    pub fn credential(&self) -> &Credential {
+        &self.credential
+    }
+}
+
+/// The changes to the membership of a group that are caused by a commit.
+///
+/// See [`PublicGroup::membership_changes()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MembershipChanges {
+    joiners: Vec<Joiner>,
+    leavers: Vec<Member>,
+}
+
+impl MembershipChanges {
+    /// Returns the clients that are added to the group by the commit.
+    pub fn joiners(&self) -> &[Joiner] {
+        &self.joiners
+    }
+
+    /// Returns the members that are removed from the group by the commit.
+    pub fn leavers(&self) -> &[Member] {
+        &self.leavers
+    }
+}
+
+impl PublicGroup {
+    /// Returns the [`MembershipChanges`] caused by the given [`StagedCommit`].
+    ///
+    /// This function must be called before the staged commit is merged, as
+    /// the removed members are looked up in the current state of the group.
+    ///
+    /// Returns a [`LibraryError`] if an internal error occurs.
+    pub fn membership_changes(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        staged_commit: &StagedCommit,
+    ) -> Result<MembershipChanges, LibraryError> {
+        let mut joiners = staged_commit
+            .add_proposals()
+            .map(|queued_add_proposal| {
+                let key_package = queued_add_proposal.add_proposal().key_package();
+                Ok(Joiner {
+                    key_package_ref: Some(key_package.hash_ref(backend.crypto())?),
+                    credential: key_package.leaf_node().credential().clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, LibraryError>>()?;
+
+        // The committer of an External Commit joins the group as well.
+        let is_external_commit = staged_commit.queued_proposals().any(|queued_proposal| {
+            queued_proposal
+                .proposal()
+                .is_type(ProposalType::ExternalInit)
+        });
+        if is_external_commit {
+            let leaf_node = staged_commit
+                .update_path_leaf_node()
+                .ok_or_else(|| LibraryError::custom("External Commit without a path"))?;
+            joiners.push(Joiner {
+                key_package_ref: None,
+                credential: leaf_node.credential().clone(),
+            });
+        }
+
+        let removed = staged_commit
+            .remove_proposals()
+            .map(|queued_remove_proposal| queued_remove_proposal.remove_proposal().removed())
+            .collect::<Vec<_>>();
+        let leavers = self
+            .members()
+            .filter(|member| removed.contains(&member.index))
+            .collect();
+
+        Ok(MembershipChanges { joiners, leavers })
+    }
+}
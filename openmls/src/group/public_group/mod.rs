@@ -14,21 +14,27 @@
 #[cfg(test)]
 use std::collections::HashSet;
 
+use std::{sync::Arc, time::Duration};
+
 use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite, OpenMlsCryptoProvider};
 use serde::{Deserialize, Serialize};
 
 use self::{
     diff::{PublicGroupDiff, StagedPublicGroupDiff},
-    errors::CreationFromExternalError,
+    errors::{AddProposalError, CreationFromExternalError},
+};
+use super::{
+    GroupContext, GroupEpoch, GroupId, Member, ProposalStore, ProposalStoreFullPolicy,
+    QueuedProposal, SharedTimeProvider,
 };
-use super::{GroupContext, GroupId, Member, ProposalStore, QueuedProposal};
 #[cfg(test)]
 use crate::treesync::{node::parent_node::PlainUpdatePathNode, treekem::UpdatePathNode};
 use crate::{
     binary_tree::{array_representation::TreeSize, LeafNodeIndex},
-    ciphersuite::signable::Verifiable,
+    ciphersuite::{hash_ref::ProposalRef, signable::Verifiable},
+    credentials::Credential,
     error::LibraryError,
-    extensions::RequiredCapabilitiesExtension,
+    extensions::{ExternalSender, RequiredCapabilitiesExtension, SenderExtensionIndex},
     framing::InterimTranscriptHashInput,
     messages::{
         group_info::{GroupInfo, VerifiableGroupInfo},
@@ -40,19 +46,24 @@ use crate::{
         errors::{DerivePathError, TreeSyncFromNodesError},
         node::{
             encryption_keys::{EncryptionKey, EncryptionKeyPair},
-            leaf_node::LeafNode,
+            leaf_node::{LeafNode, LeafNodeValidationContext, SystemTimeProvider, TimeProvider},
         },
-        RatchetTree, RatchetTreeIn, TreeSync,
+        RatchetTree, RatchetTreeIn, RatchetTreeView, TreeSync,
     },
     versions::ProtocolVersion,
 };
 #[cfg(doc)]
-use crate::{framing::PublicMessage, group::CoreGroup};
+use crate::{
+    framing::PublicMessage,
+    group::{errors::ProcessMessageError, CoreGroup},
+};
 
 pub(crate) mod builder;
 pub(crate) mod diff;
 pub mod errors;
+pub mod membership;
 pub mod process;
+pub mod snapshot;
 pub(crate) mod staged_commit;
 #[cfg(test)]
 mod tests;
@@ -68,6 +79,22 @@ pub struct PublicGroup {
     interim_transcript_hash: Vec<u8>,
     // Most recent confirmation tag. Kept here for verification purposes.
     confirmation_tag: ConfirmationTag,
+    // The epoch in which the proposals in the proposal store were added.
+    #[serde(default)]
+    proposal_store_epoch: Option<GroupEpoch>,
+    // The maximum number of proposals in the proposal store.
+    #[serde(default)]
+    max_pending_proposals: Option<usize>,
+    // What happens to new proposals if the proposal store is full.
+    #[serde(default)]
+    proposal_store_full_policy: ProposalStoreFullPolicy,
+    // The source of the current time to validate lifetimes. It is not
+    // persisted and has to be set again after loading the group.
+    #[serde(skip)]
+    time_provider: Option<SharedTimeProvider>,
+    // The clock skew that is tolerated when validating lifetimes.
+    #[serde(default)]
+    clock_skew_tolerance: Duration,
 }
 
 impl PublicGroup {
@@ -95,6 +122,11 @@ impl PublicGroup {
             group_context,
             interim_transcript_hash,
             confirmation_tag: initial_confirmation_tag,
+            proposal_store_epoch: None,
+            max_pending_proposals: None,
+            proposal_store_full_policy: ProposalStoreFullPolicy::default(),
+            time_provider: None,
+            clock_skew_tolerance: Duration::ZERO,
         })
     }
 
@@ -164,7 +196,12 @@ impl PublicGroup {
                 group_context,
                 interim_transcript_hash,
                 confirmation_tag: group_info.confirmation_tag().clone(),
+                proposal_store_epoch: Some(group_info.group_context().epoch()),
                 proposal_store,
+                max_pending_proposals: None,
+                proposal_store_full_policy: ProposalStoreFullPolicy::default(),
+                time_provider: None,
+                clock_skew_tolerance: Duration::ZERO,
             },
             group_info,
         ))
@@ -256,14 +293,210 @@ impl PublicGroup {
         self.treesync().full_leave_members()
     }
 
+    /// Returns the [`Credential`] of a member corresponding to the given
+    /// leaf index. Returns `None` if the member can not be found in this group.
+    pub fn member(&self, leaf_index: LeafNodeIndex) -> Option<&Credential> {
+        self.leaf(leaf_index).map(|leaf| leaf.credential())
+    }
+
+    /// Returns all members whose credential has the given identity in
+    /// ascending order of their leaf indices.
+    ///
+    /// A client can be a member multiple times, e.g., with different devices,
+    /// which is why this returns an iterator.
+    pub fn members_by_identity<'a>(
+        &'a self,
+        identity: &'a [u8],
+    ) -> impl Iterator<Item = Member> + 'a {
+        self.treesync()
+            .leaf_indices_by_identity(identity)
+            .iter()
+            .filter_map(|leaf_index| self.treesync().member(*leaf_index))
+    }
+
+    /// Returns the member with the given identity. If the client is a member
+    /// multiple times, the member with the lowest leaf index is returned. See
+    /// [`PublicGroup::members_by_identity()`] to get all of them.
+    ///
+    /// Returns `None` if there is no member with the given identity.
+    pub fn member_by_identity(&self, identity: &[u8]) -> Option<Member> {
+        self.treesync()
+            .leaf_indices_by_identity(identity)
+            .first()
+            .and_then(|leaf_index| self.treesync().member(*leaf_index))
+    }
+
+    /// Returns the leaf index of the member with the given signature key or
+    /// `None` if there is no such member.
+    pub fn leaf_index_of(&self, signature_key: &[u8]) -> Option<LeafNodeIndex> {
+        self.treesync().leaf_index_by_signature_key(signature_key)
+    }
+
+    /// Returns the external senders of the group together with their
+    /// [`SenderExtensionIndex`], which is used to identify them as the sender
+    /// of a message.
+    pub fn external_senders(
+        &self,
+    ) -> impl Iterator<Item = (SenderExtensionIndex, &ExternalSender)> + '_ {
+        self.group_context()
+            .extensions()
+            .external_senders()
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(|(index, external_sender)| {
+                (SenderExtensionIndex::new(index as u32), external_sender)
+            })
+    }
+
     /// Export the nodes of the public tree.
     pub fn export_ratchet_tree(&self) -> RatchetTree {
         self.treesync().export_ratchet_tree()
     }
 
+    /// Returns a read-only [`RatchetTreeView`] of the public tree.
+    pub fn ratchet_tree_view(&self) -> RatchetTreeView<'_> {
+        self.treesync().view()
+    }
+
     /// Add the [`QueuedProposal`] to the [`PublicGroup`]s internal [`ProposalStore`].
-    pub fn add_proposal(&mut self, proposal: QueuedProposal) {
-        self.proposal_store.add(proposal)
+    ///
+    /// Proposals are only valid in the epoch they were sent in. If the store
+    /// contains proposals of a previous epoch, they are dropped first. If the
+    /// store is full (see [`Self::set_max_pending_proposals()`]), the oldest
+    /// proposal is removed from the store and returned with
+    /// [`ProposalStoreFullPolicy::EvictOldest`]. Otherwise, and always if the
+    /// maximum is 0, the proposal is not stored and
+    /// [`AddProposalError::ProposalStoreFull`] is returned.
+    pub fn add_proposal(
+        &mut self,
+        proposal: QueuedProposal,
+    ) -> Result<Option<QueuedProposal>, AddProposalError> {
+        self.expire_proposals();
+        if self.rejects_new_proposals() {
+            return Err(AddProposalError::ProposalStoreFull);
+        }
+        Ok(self.proposal_store.add_bounded(
+            proposal,
+            self.max_pending_proposals,
+            self.proposal_store_full_policy,
+        ))
+    }
+
+    /// Returns an iterator over the proposals in the [`PublicGroup`]s internal
+    /// [`ProposalStore`].
+    pub fn pending_proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
+        self.proposal_store.proposals()
+    }
+
+    /// Removes the proposal with the given [`ProposalRef`] from the
+    /// [`PublicGroup`]s internal [`ProposalStore`].
+    ///
+    /// Returns the removed proposal or `None` if there is no such proposal.
+    pub fn remove_proposal(&mut self, proposal_ref: ProposalRef) -> Option<QueuedProposal> {
+        self.proposal_store.remove(proposal_ref)
+    }
+
+    /// Removes all proposals from the [`PublicGroup`]s internal
+    /// [`ProposalStore`].
+    pub fn clear_proposals(&mut self) {
+        self.proposal_store.empty()
+    }
+
+    /// Removes all proposals that were added in a previous epoch from the
+    /// [`PublicGroup`]s internal [`ProposalStore`].
+    ///
+    /// Proposals are also dropped when a commit is merged.
+    pub fn expire_proposals(&mut self) {
+        let epoch = self.group_context.epoch();
+        if self.proposal_store_epoch != Some(epoch) {
+            self.proposal_store.empty();
+            self.proposal_store_epoch = Some(epoch);
+        }
+    }
+
+    /// Sets the maximum number of proposals kept in the [`PublicGroup`]s
+    /// internal [`ProposalStore`]. What happens to new proposals if the store
+    /// is full is determined by the [`ProposalStoreFullPolicy`]. `None`
+    /// disables the limit.
+    ///
+    /// If the store contains more proposals than the new maximum, the oldest
+    /// ones are removed.
+    pub fn set_max_pending_proposals(&mut self, max_pending_proposals: Option<usize>) {
+        self.max_pending_proposals = max_pending_proposals;
+        if let Some(max) = max_pending_proposals {
+            while self.proposal_store.len() > max {
+                self.proposal_store.remove_oldest();
+            }
+        }
+    }
+
+    /// Returns the maximum number of proposals kept in the [`PublicGroup`]s
+    /// internal [`ProposalStore`], if any.
+    pub fn max_pending_proposals(&self) -> Option<usize> {
+        self.max_pending_proposals
+    }
+
+    /// Sets what happens to new proposals if the [`PublicGroup`]s internal
+    /// [`ProposalStore`] is full. With [`ProposalStoreFullPolicy::Reject`],
+    /// or if the maximum is 0, [`Self::process_message()`] rejects proposals
+    /// with [`ProcessMessageError::ProposalStoreFull`] while the store is
+    /// full.
+    pub fn set_proposal_store_full_policy(&mut self, policy: ProposalStoreFullPolicy) {
+        self.proposal_store_full_policy = policy;
+    }
+
+    /// Returns what happens to new proposals if the [`PublicGroup`]s internal
+    /// [`ProposalStore`] is full.
+    pub fn proposal_store_full_policy(&self) -> ProposalStoreFullPolicy {
+        self.proposal_store_full_policy
+    }
+
+    /// Sets the source of the current time that is used to validate the
+    /// lifetimes of leaf nodes and key packages, e.g., to use a network time
+    /// on devices with unreliable clocks. See [`TimeProvider`] for more
+    /// details.
+    ///
+    /// The time provider is not persisted and has to be set again after
+    /// loading the group. The default is the system clock.
+    pub fn set_time_provider(&mut self, time_provider: impl TimeProvider + 'static) {
+        self.time_provider = Some(SharedTimeProvider(Arc::new(time_provider)));
+    }
+
+    /// Returns the source of the current time that is used to validate
+    /// lifetimes.
+    pub fn time_provider(&self) -> &dyn TimeProvider {
+        self.time_provider
+            .as_ref()
+            .map(|time_provider| time_provider.0.as_ref())
+            .unwrap_or(&SystemTimeProvider)
+    }
+
+    /// Sets the clock skew that is tolerated when validating lifetimes.
+    /// Lifetimes are extended by `clock_skew_tolerance` into the past and
+    /// the future. The default is 0.
+    pub fn set_clock_skew_tolerance(&mut self, clock_skew_tolerance: Duration) {
+        self.clock_skew_tolerance = clock_skew_tolerance;
+    }
+
+    /// Returns the clock skew that is tolerated when validating lifetimes.
+    pub fn clock_skew_tolerance(&self) -> Duration {
+        self.clock_skew_tolerance
+    }
+
+    /// Returns `true` if a new proposal would be rejected because the
+    /// [`PublicGroup`]s internal [`ProposalStore`] is full.
+    pub(crate) fn rejects_new_proposals(&self) -> bool {
+        // A store without room can't evict proposals to make room.
+        if self.max_pending_proposals == Some(0) {
+            return true;
+        }
+        // Proposals of previous epochs are dropped before a new one is added.
+        self.proposal_store_full_policy == ProposalStoreFullPolicy::Reject
+            && self.proposal_store_epoch == Some(self.group_context.epoch())
+            && self
+                .max_pending_proposals
+                .map_or(false, |max| self.proposal_store.len() >= max)
     }
 }
 
@@ -310,12 +543,31 @@ impl PublicGroup {
         self.treesync().leaf(leaf_index)
     }
 
+    /// Returns the context to validate a leaf node against the members of
+    /// the group, except for the members at the `excluded` leaf indices.
+    /// Lifetimes are validated with the time provider and the clock skew
+    /// tolerance of the group.
+    pub(crate) fn leaf_node_validation_context(
+        &self,
+        excluded: &[LeafNodeIndex],
+    ) -> LeafNodeValidationContext<'_> {
+        LeafNodeValidationContext::new(
+            self.required_capabilities(),
+            self.treesync()
+                .full_leaves_indexed()
+                .filter(|(index, _)| !excluded.contains(index))
+                .map(|(_, leaf)| leaf),
+        )
+        .with_time(self.time_provider(), self.clock_skew_tolerance.as_secs())
+    }
+
     /// Returns the tree size
     pub(crate) fn tree_size(&self) -> TreeSize {
         self.treesync().tree_size()
     }
 
-    fn interim_transcript_hash(&self) -> &[u8] {
+    /// Returns the interim transcript hash of the current epoch.
+    pub fn interim_transcript_hash(&self) -> &[u8] {
         &self.interim_transcript_hash
     }
 
@@ -333,7 +585,7 @@ impl PublicGroup {
         &mut self.group_context
     }
 
-    #[cfg(test)]
+    #[cfg(any(feature = "test-vectors", test))]
     pub(crate) fn set_group_context(&mut self, group_context: GroupContext) {
         self.group_context = group_context;
     }
@@ -6,20 +6,184 @@ use crate::{
     credentials::CredentialWithKey,
     error::LibraryError,
     framing::{
-        mls_content::FramedContentBody, ApplicationMessage, DecryptedMessage, ProcessedMessage,
-        ProcessedMessageContent, ProtocolMessage, Sender, SenderContext, UnverifiedMessage,
+        mls_content::FramedContentBody, ApplicationMessage, ContentType, DecryptedMessage,
+        ProcessedMessage, ProcessedMessageContent, ProtocolMessage, Sender, SenderContext,
+        UnverifiedMessage,
     },
     group::{
         core_group::proposals::{ProposalStore, QueuedProposal},
-        errors::ValidationError,
+        errors::{ExternalCommitValidationError, StageCommitError, ValidationError},
         mls_group::errors::ProcessMessageError,
         past_secrets::MessageSecretsStore,
     },
-    messages::proposals::Proposal,
+    messages::{group_info::VerifiableGroupInfo, proposals::Proposal},
 };
 
 use super::PublicGroup;
 
+/// The decision of [`PublicGroup::validate_incoming()`] on whether a message
+/// would be accepted by the members of the group.
+#[derive(Debug, PartialEq, Clone)]
+pub enum IncomingMessageDecision {
+    /// The message would be accepted by the group.
+    Accept,
+    /// The message would be rejected by the group for the given reason.
+    Reject(RejectionReason),
+}
+
+impl IncomingMessageDecision {
+    /// Returns `true` if the message would be accepted by the group.
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, IncomingMessageDecision::Accept)
+    }
+}
+
+/// The reason why a message was rejected by
+/// [`PublicGroup::validate_incoming()`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum RejectionReason {
+    /// The message is for a different group.
+    WrongGroupId,
+    /// The message is not for the current epoch of the group.
+    WrongEpoch,
+    /// The signature or membership tag of the message is invalid.
+    InvalidSignature,
+    /// The sender is not a member of the group or is not allowed to send this
+    /// message.
+    UnauthorizedSender,
+    /// The content of the message is invalid, e.g., a commit or proposal
+    /// violates the proposal validation rules of the group. See the contained
+    /// [`ProcessMessageError`] for more details.
+    InvalidContent(ProcessMessageError),
+}
+
+impl From<ProcessMessageError> for RejectionReason {
+    fn from(error: ProcessMessageError) -> Self {
+        match error {
+            ProcessMessageError::ValidationError(ValidationError::WrongGroupId) => {
+                RejectionReason::WrongGroupId
+            }
+            ProcessMessageError::ValidationError(
+                ValidationError::WrongEpoch | ValidationError::NoPastEpochData,
+            )
+            | ProcessMessageError::Desynced(_) => RejectionReason::WrongEpoch,
+            ProcessMessageError::InvalidSignature
+            | ProcessMessageError::ValidationError(
+                ValidationError::InvalidSignature
+                | ValidationError::MissingMembershipTag
+                | ValidationError::InvalidMembershipTag,
+            ) => RejectionReason::InvalidSignature,
+            ProcessMessageError::UnauthorizedExternalApplicationMessage
+            | ProcessMessageError::ValidationError(
+                ValidationError::UnknownMember
+                | ValidationError::UnauthorizedExternalSender
+                | ValidationError::NoExternalSendersExtension
+                | ValidationError::NonMemberApplicationMessage
+                | ValidationError::InvalidSenderType,
+            ) => RejectionReason::UnauthorizedSender,
+            error => RejectionReason::InvalidContent(error),
+        }
+    }
+}
+
+impl PublicGroup {
+    /// Decides whether the members of the group would accept the given
+    /// message, without changing the state of the group.
+    ///
+    /// This allows a delivery service to reject invalid messages before they
+    /// are fanned out to the members. For a [`PublicMessage`], all checks of
+    /// [`Self::process_message()`] are performed, including the validation of
+    /// the signature, the sender and the proposals of a commit. The proposals
+    /// referenced by a commit must be known to the group, see
+    /// [`Self::add_proposal()`].
+    ///
+    /// A [`PrivateMessage`] can't be decrypted by the delivery service. For
+    /// those, only the group ID and the epoch are checked.
+    ///
+    /// Returns a [`LibraryError`] if an internal error occurs.
+    ///
+    /// [`PublicMessage`]: crate::framing::PublicMessage
+    /// [`PrivateMessage`]: crate::framing::PrivateMessage
+    pub fn validate_incoming(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: impl Into<ProtocolMessage>,
+    ) -> Result<IncomingMessageDecision, LibraryError> {
+        let protocol_message = message.into();
+
+        let result = if let ProtocolMessage::PrivateMessage(_) = protocol_message {
+            self.validate_framing(&protocol_message)
+                .map_err(ProcessMessageError::from)
+        } else {
+            self.process_message(backend, protocol_message).map(|_| ())
+        };
+
+        match result {
+            Ok(()) => Ok(IncomingMessageDecision::Accept),
+            Err(ProcessMessageError::LibraryError(e))
+            | Err(ProcessMessageError::ValidationError(ValidationError::LibraryError(e))) => Err(e),
+            Err(error) => Ok(IncomingMessageDecision::Reject(error.into())),
+        }
+    }
+
+    /// Fully validates an External Commit against the [`VerifiableGroupInfo`]
+    /// that was served to the joiner, before it is broadcast to the members
+    /// of the group.
+    ///
+    /// In addition to the checks of [`Self::process_message()`], this
+    /// function checks that
+    ///  - the message is a commit of a [`Sender::NewMemberCommit`],
+    ///  - the group context and the confirmation tag of the GroupInfo match
+    ///    the current state of the group, i.e., the joiner used an up-to-date
+    ///    GroupInfo, and
+    ///  - the GroupInfo contains an external_pub extension.
+    ///
+    /// The signature of the GroupInfo is not verified, as it was created by
+    /// a member of the group and is only compared with the state of the
+    /// group.
+    ///
+    /// Returns a [`ProcessedMessage`] containing the staged commit, which can
+    /// be merged with [`Self::merge_commit()`].
+    pub fn process_external_commit(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: impl Into<ProtocolMessage>,
+        group_info: &VerifiableGroupInfo,
+    ) -> Result<ProcessedMessage, ProcessMessageError> {
+        let protocol_message = message.into();
+
+        if protocol_message.sender() != Some(&Sender::NewMemberCommit)
+            || protocol_message.content_type() != ContentType::Commit
+        {
+            return Err(ProcessMessageError::InvalidCommit(
+                StageCommitError::ExternalCommitValidation(
+                    ExternalCommitValidationError::NotAnExternalCommit,
+                ),
+            ));
+        }
+
+        if group_info.group_context() != self.group_context()
+            || group_info.confirmation_tag() != self.confirmation_tag()
+        {
+            return Err(ProcessMessageError::InvalidCommit(
+                StageCommitError::ExternalCommitValidation(
+                    ExternalCommitValidationError::GroupInfoMismatch,
+                ),
+            ));
+        }
+
+        if group_info.extensions().external_pub().is_none() {
+            return Err(ProcessMessageError::InvalidCommit(
+                StageCommitError::ExternalCommitValidation(
+                    ExternalCommitValidationError::MissingExternalPub,
+                ),
+            ));
+        }
+
+        self.process_message(backend, protocol_message)
+    }
+}
+
 impl PublicGroup {
     /// This function is used to parse messages from the DS.
     /// It checks for syntactic errors and makes some semantic checks as well.
@@ -130,6 +294,7 @@ impl PublicGroup {
     ///  - ValSem244
     ///  - ValSem245
     ///  - ValSem246 (as part of ValSem010)
+    ///  - ValSem248
     pub fn process_message(
         &self,
         backend: &impl OpenMlsCryptoProvider,
@@ -160,7 +325,23 @@ impl PublicGroup {
         let unverified_message = self
             .parse_message(decrypted_message, None)
             .map_err(ProcessMessageError::from)?;
-        self.process_unverified_message(backend, unverified_message, &self.proposal_store)
+        let processed_message =
+            self.process_unverified_message(backend, unverified_message, &self.proposal_store)?;
+
+        // Check the lifetimes of the added key packages against the
+        // configured time source.
+        processed_message.validate_key_package_lifetimes(
+            self.time_provider(),
+            self.clock_skew_tolerance.as_secs(),
+        )?;
+
+        // Reject proposals if there is no room for them.
+        if processed_message.content_type() == ContentType::Proposal && self.rejects_new_proposals()
+        {
+            return Err(ProcessMessageError::ProposalStoreFull);
+        }
+
+        Ok(processed_message)
     }
 }
 
@@ -192,6 +373,7 @@ impl PublicGroup {
     ///  - ValSem243
     ///  - ValSem244
     ///  - ValSem246 (as part of ValSem010)
+    ///  - ValSem248
     pub(crate) fn process_unverified_message(
         &self,
         backend: &impl OpenMlsCryptoProvider,
@@ -213,6 +395,7 @@ impl PublicGroup {
                     FramedContentBody::Application(application_message) => {
                         ProcessedMessageContent::ApplicationMessage(ApplicationMessage::new(
                             application_message.as_slice().to_owned(),
+                            authenticated_data.clone(),
                         ))
                     }
                     FramedContentBody::Proposal(_) => {
@@ -221,6 +404,7 @@ impl PublicGroup {
                             backend,
                             content,
                         )?);
+                        self.validate_proposal_permission(&sender, proposal.proposal())?;
                         if matches!(sender, Sender::NewMemberProposal) {
                             ProcessedMessageContent::ExternalJoinProposalMessage(proposal)
                         } else {
@@ -0,0 +1,208 @@
+//! This module contains the [`PublicGroupSnapshot`], which can be used to
+//! replicate the state of a [`PublicGroup`] between multiple nodes of a
+//! delivery service.
+//!
+//! A snapshot contains the group context, the ratchet tree, the interim
+//! transcript hash, the confirmation tag and the queued proposals of a
+//! [`PublicGroup`], as well as a digest over all of these. Replicas can compare
+//! the digest of their own state (see [`PublicGroup::state_digest()`]) with the
+//! digest of a snapshot to check that they agree on the state of the group.
+
+use std::time::Duration;
+
+use openmls_traits::{crypto::OpenMlsCrypto, OpenMlsCryptoProvider};
+use serde::{Deserialize, Serialize};
+use tls_codec::{Serialize as TlsSerializeTrait, TlsSerialize, TlsSize, VLByteSlice};
+
+use super::{errors::SnapshotImportError, PublicGroup};
+use crate::{
+    ciphersuite::hash_ref::ProposalRef,
+    error::LibraryError,
+    framing::Sender,
+    group::{GroupContext, ProposalStore, ProposalStoreFullPolicy, QueuedProposal},
+    messages::{proposals::Proposal, ConfirmationTag},
+    treesync::{errors::TreeSyncFromNodesError, RatchetTreeIn, TreeSync},
+};
+
+/// A snapshot of the state of a [`PublicGroup`].
+///
+/// A snapshot is created with [`PublicGroup::export_snapshot()`] and imported
+/// with [`PublicGroup::from_snapshot()`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublicGroupSnapshot {
+    group_context: GroupContext,
+    ratchet_tree: RatchetTreeIn,
+    interim_transcript_hash: Vec<u8>,
+    confirmation_tag: ConfirmationTag,
+    proposals: Vec<QueuedProposal>,
+    digest: Vec<u8>,
+}
+
+impl PublicGroupSnapshot {
+    /// Returns the [`GroupContext`] of the snapshot.
+    pub fn group_context(&self) -> &GroupContext {
+        &self.group_context
+    }
+
+    /// Returns the digest over the state contained in the snapshot.
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+}
+
+/// Helper struct that is serialized to compute the digest of a snapshot.
+#[derive(TlsSerialize, TlsSize)]
+struct SnapshotDigestInput<'a> {
+    group_context: &'a GroupContext,
+    ratchet_tree: &'a RatchetTreeIn,
+    interim_transcript_hash: VLByteSlice<'a>,
+    confirmation_tag: &'a ConfirmationTag,
+    proposals: Vec<QueuedProposalDigestInput<'a>>,
+}
+
+#[derive(TlsSerialize, TlsSize)]
+struct QueuedProposalDigestInput<'a> {
+    proposal_ref: ProposalRef,
+    proposal: &'a Proposal,
+    sender: &'a Sender,
+}
+
+impl<'a> SnapshotDigestInput<'a> {
+    fn new(
+        group_context: &'a GroupContext,
+        ratchet_tree: &'a RatchetTreeIn,
+        interim_transcript_hash: &'a [u8],
+        confirmation_tag: &'a ConfirmationTag,
+        proposals: impl Iterator<Item = &'a QueuedProposal>,
+    ) -> Self {
+        Self {
+            group_context,
+            ratchet_tree,
+            interim_transcript_hash: VLByteSlice(interim_transcript_hash),
+            confirmation_tag,
+            proposals: proposals
+                .map(|queued_proposal| QueuedProposalDigestInput {
+                    proposal_ref: queued_proposal.proposal_reference(),
+                    proposal: queued_proposal.proposal(),
+                    sender: queued_proposal.sender(),
+                })
+                .collect(),
+        }
+    }
+
+    fn digest(&self, backend: &impl OpenMlsCryptoProvider) -> Result<Vec<u8>, LibraryError> {
+        let payload = self
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        backend
+            .crypto()
+            .hash(self.group_context.ciphersuite().hash_algorithm(), &payload)
+            .map_err(LibraryError::unexpected_crypto_error)
+    }
+}
+
+impl PublicGroup {
+    /// Returns a digest over the group context, the ratchet tree, the interim
+    /// transcript hash, the confirmation tag and the queued proposals of this
+    /// group.
+    ///
+    /// Two [`PublicGroup`]s with the same digest agree on the state of the
+    /// group.
+    pub fn state_digest(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<Vec<u8>, LibraryError> {
+        let ratchet_tree = RatchetTreeIn::from(self.export_ratchet_tree());
+        SnapshotDigestInput::new(
+            &self.group_context,
+            &ratchet_tree,
+            &self.interim_transcript_hash,
+            &self.confirmation_tag,
+            self.proposal_store.proposals(),
+        )
+        .digest(backend)
+    }
+
+    /// Exports a [`PublicGroupSnapshot`] of this group that can be imported by
+    /// another node with [`PublicGroup::from_snapshot()`].
+    pub fn export_snapshot(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<PublicGroupSnapshot, LibraryError> {
+        let ratchet_tree = RatchetTreeIn::from(self.export_ratchet_tree());
+        let digest = SnapshotDigestInput::new(
+            &self.group_context,
+            &ratchet_tree,
+            &self.interim_transcript_hash,
+            &self.confirmation_tag,
+            self.proposal_store.proposals(),
+        )
+        .digest(backend)?;
+
+        Ok(PublicGroupSnapshot {
+            group_context: self.group_context.clone(),
+            ratchet_tree,
+            interim_transcript_hash: self.interim_transcript_hash.clone(),
+            confirmation_tag: self.confirmation_tag.clone(),
+            proposals: self.proposal_store.proposals().cloned().collect(),
+            digest,
+        })
+    }
+
+    /// Imports a [`PublicGroupSnapshot`].
+    ///
+    /// The ratchet tree of the snapshot is verified and its tree hash must
+    /// match the one in the group context. Returns an error if the digest of
+    /// the snapshot doesn't match its content. See [`SnapshotImportError`] for
+    /// more details.
+    pub fn from_snapshot(
+        backend: &impl OpenMlsCryptoProvider,
+        snapshot: PublicGroupSnapshot,
+    ) -> Result<Self, SnapshotImportError> {
+        let digest = SnapshotDigestInput::new(
+            &snapshot.group_context,
+            &snapshot.ratchet_tree,
+            &snapshot.interim_transcript_hash,
+            &snapshot.confirmation_tag,
+            snapshot.proposals.iter(),
+        )
+        .digest(backend)?;
+        if digest != snapshot.digest {
+            return Err(SnapshotImportError::DigestMismatch);
+        }
+
+        let ciphersuite = snapshot.group_context.ciphersuite();
+        let ratchet_tree = snapshot
+            .ratchet_tree
+            .into_verified(
+                ciphersuite,
+                backend.crypto(),
+                snapshot.group_context.group_id(),
+            )
+            .map_err(|e| {
+                SnapshotImportError::TreeSyncError(TreeSyncFromNodesError::RatchetTreeError(e))
+            })?;
+        let treesync = TreeSync::from_ratchet_tree(backend, ciphersuite, ratchet_tree)?;
+        if treesync.tree_hash() != snapshot.group_context.tree_hash() {
+            return Err(SnapshotImportError::TreeHashMismatch);
+        }
+
+        let mut proposal_store = ProposalStore::new();
+        for proposal in snapshot.proposals {
+            proposal_store.add(proposal);
+        }
+
+        Ok(Self {
+            treesync,
+            proposal_store,
+            proposal_store_epoch: Some(snapshot.group_context.epoch()),
+            group_context: snapshot.group_context,
+            interim_transcript_hash: snapshot.interim_transcript_hash,
+            confirmation_tag: snapshot.confirmation_tag,
+            max_pending_proposals: None,
+            proposal_store_full_policy: ProposalStoreFullPolicy::default(),
+            time_provider: None,
+            clock_skew_tolerance: Duration::ZERO,
+        })
+    }
+}
@@ -1,5 +1,3 @@
-use std::collections::HashSet;
-
 use super::{super::errors::*, *};
 use crate::{
     framing::{mls_auth_content::AuthenticatedContent, mls_content::FramedContentBody, Sender},
@@ -89,13 +87,22 @@ impl PublicGroup {
         // ValSem402
         // ValSem403
         self.validate_pre_shared_key_proposals(&proposal_queue)?;
+        self.validate_group_context_extensions_proposals(&proposal_queue)?;
+        self.validate_proposal_permissions(
+            &proposal_queue,
+            sender,
+            commit_update_leaf_node.as_ref(),
+        )?;
 
-        let public_key_set = match sender {
+        // The path key package of an external commit is not checked against
+        // the encryption keys of the existing members.
+        let validate_path_key_package = match sender {
             Sender::Member(leaf_index) => {
                 // ValSem110
                 // ValSem111
                 // ValSem112
-                self.validate_update_proposals(&proposal_queue, *leaf_index)?
+                self.validate_update_proposals(&proposal_queue, *leaf_index)?;
+                true
             }
             Sender::External(_) => {
                 // A commit cannot be issued by a pre-configured sender.
@@ -111,9 +118,9 @@ impl PublicGroup {
                 // ValSem242: External Commit must only cover inline proposal in allowlist (ExternalInit, Remove, PreSharedKey)
                 // ValSem243: External Commit, inline Remove Proposal: The identity and the endpoint_id of the removed
                 //            leaf are identical to the ones in the path KeyPackage.
+                // ValSem248: External Commit must cover at most one inline Remove proposal
                 self.validate_external_commit(&proposal_queue, commit_update_leaf_node.as_ref())?;
-                // Since there are no update proposals in an External Commit we have no public keys to return
-                HashSet::new()
+                false
             }
         };
 
@@ -136,9 +143,11 @@ impl PublicGroup {
         };
 
         // Validation in case of path
-        if let Some(path) = commit.path.clone() {
-            // Make sure that the new path key package is valid
-            self.validate_path_key_package(path.leaf_node(), public_key_set)?;
+        if let Some(path) = commit.path.as_ref() {
+            if validate_path_key_package {
+                // Make sure that the new path key package is valid
+                self.validate_path_key_package(path.leaf_node())?;
+            }
         }
 
         Ok((commit, proposal_queue, sender_index))
@@ -181,6 +190,7 @@ impl PublicGroup {
     ///  - ValSem242
     ///  - ValSem243
     ///  - ValSem244
+    ///  - ValSem248
     /// Returns an error if the given commit was sent by the owner of this
     /// group.
     /// TODO #1255: This will be used by the `process_message` function of the
@@ -201,7 +211,12 @@ impl PublicGroup {
 
         let staged_commit_state = StagedCommitState::PublicState(Box::new(staged_diff));
 
-        Ok(StagedCommit::new(proposal_queue, staged_commit_state))
+        Ok(StagedCommit::new(
+            proposal_queue,
+            staged_commit_state,
+            commit.path.as_ref().map(|path| path.leaf_node().clone()),
+            mls_content.sender().clone(),
+        ))
     }
 
     fn stage_diff(
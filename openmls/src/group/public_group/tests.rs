@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use openmls_rust_crypto::OpenMlsRustCrypto;
 use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
 use rstest::*;
@@ -5,18 +7,28 @@ use rstest_reuse::{self, *};
 
 use crate::{
     binary_tree::LeafNodeIndex,
+    extensions::{ExternalSender, SenderExtensionIndex},
     framing::{
         public_message_in::PublicMessageIn, MlsMessageOut, ProcessedMessage,
         ProcessedMessageContent, ProtocolMessage, Sender,
     },
     group::{
-        config::CryptoConfig, test_core_group::setup_client, GroupId, MlsGroup,
-        MlsGroupConfigBuilder, ProposalStore, StagedCommit, PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+        config::CryptoConfig,
+        errors::{ExternalCommitValidationError, StageCommitError},
+        mls_group::errors::ProcessMessageError,
+        test_core_group::setup_client,
+        GroupId, MlsGroup, MlsGroupConfigBuilder, ProposalStore, ProposalStoreFullPolicy,
+        StagedCommit, PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
     },
     messages::proposals::Proposal,
+    treesync::node::leaf_node::TimeProvider,
 };
 
-use super::PublicGroup;
+use super::{
+    errors::{AddProposalError, SnapshotImportError},
+    process::{IncomingMessageDecision, RejectionReason},
+    PublicGroup,
+};
 
 #[apply(ciphersuites_and_backends)]
 fn public_group(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
@@ -174,7 +186,9 @@ fn public_group(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider)
                 Proposal::Remove(r) => assert_eq!(r.removed(), LeafNodeIndex::new(1)),
                 _ => panic!("Unexpected proposal type"),
             }
-            public_group.add_proposal(*p);
+            public_group
+                .add_proposal(*p)
+                .expect("Could not store proposal.");
         }
     }
 
@@ -267,6 +281,692 @@ fn public_group(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider)
     );
 }
 
+#[apply(ciphersuites_and_backends)]
+fn validate_incoming(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, bob_signer, _bob_pk) = setup_client("Bob", ciphersuite, backend);
+    let (charlie_credential_with_key, _charlie_kpb, charlie_signer, _charlie_pk) =
+        setup_client("Charly", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    // === Alice creates a group with Bob ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_message, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let (mut public_group, _extensions) = PublicGroup::from_external(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+    )
+    .unwrap();
+
+    // === Alice and Bob concurrently commit ===
+    let (alice_commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not create commit.");
+    let (bob_commit, _welcome, _group_info) = bob_group
+        .self_update(backend, &bob_signer)
+        .expect("Could not create commit.");
+
+    // Both commits are acceptable and the public group is unchanged.
+    let group_context = public_group.group_context().clone();
+    assert_eq!(
+        public_group.validate_incoming(backend, into_public_message(alice_commit.clone())),
+        Ok(IncomingMessageDecision::Accept)
+    );
+    assert!(public_group
+        .validate_incoming(backend, into_public_message(bob_commit.clone()))
+        .unwrap()
+        .is_accepted());
+    assert_eq!(public_group.group_context(), &group_context);
+
+    // Once Bob's commit is merged, Alice's commit is for an old epoch.
+    let ppm = public_group
+        .process_message(backend, into_public_message(bob_commit))
+        .unwrap();
+    public_group.merge_commit(extract_staged_commit(ppm));
+    assert_eq!(
+        public_group.validate_incoming(backend, into_public_message(alice_commit)),
+        Ok(IncomingMessageDecision::Reject(RejectionReason::WrongEpoch))
+    );
+
+    // Messages of other groups are rejected.
+    let mut charlie_group = MlsGroup::new_with_group_id(
+        backend,
+        &charlie_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Other Group"),
+        charlie_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (charlie_commit, _welcome, _group_info) = charlie_group
+        .self_update(backend, &charlie_signer)
+        .expect("Could not create commit.");
+    assert_eq!(
+        public_group.validate_incoming(backend, into_public_message(charlie_commit)),
+        Ok(IncomingMessageDecision::Reject(
+            RejectionReason::WrongGroupId
+        ))
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn proposal_store_limits(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let (mut public_group, _extensions) = PublicGroup::from_external(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+    )
+    .unwrap();
+    public_group.set_max_pending_proposals(Some(2));
+    assert_eq!(public_group.max_pending_proposals(), Some(2));
+    assert_eq!(
+        public_group.proposal_store_full_policy(),
+        ProposalStoreFullPolicy::Reject
+    );
+    public_group.set_proposal_store_full_policy(ProposalStoreFullPolicy::EvictOldest);
+
+    // Alice sends three proposals, but only the last two are kept.
+    let mut proposal_refs = Vec::new();
+    for i in 0..3 {
+        let (message, proposal_ref) = alice_group
+            .propose_self_update(backend, &alice_signer, None)
+            .expect("Could not create proposal.");
+        let ppm = public_group
+            .process_message(backend, into_public_message(message))
+            .unwrap();
+        let evicted = match ppm.into_content() {
+            ProcessedMessageContent::ProposalMessage(proposal) => public_group
+                .add_proposal(*proposal)
+                .expect("Could not store proposal."),
+            _ => panic!("Unexpected message type."),
+        };
+        // The third proposal evicts the first one.
+        let expected_eviction = (i == 2).then(|| proposal_refs[0].clone());
+        assert_eq!(
+            evicted.map(|proposal| proposal.proposal_reference()),
+            expected_eviction
+        );
+        proposal_refs.push(proposal_ref);
+    }
+    let pending_proposal_refs: Vec<_> = public_group
+        .pending_proposals()
+        .map(|proposal| proposal.proposal_reference())
+        .collect();
+    assert_eq!(pending_proposal_refs, proposal_refs[1..].to_vec());
+
+    // Proposals can be removed explicitly.
+    assert!(public_group
+        .remove_proposal(proposal_refs[1].clone())
+        .is_some());
+    assert!(public_group
+        .remove_proposal(proposal_refs[0].clone())
+        .is_none());
+    assert_eq!(public_group.pending_proposals().count(), 1);
+
+    // Lowering the maximum evicts proposals and clearing removes all.
+    public_group.set_max_pending_proposals(Some(0));
+    assert_eq!(public_group.pending_proposals().count(), 0);
+    public_group.set_max_pending_proposals(None);
+    let (message, _proposal_ref) = alice_group
+        .propose_self_update(backend, &alice_signer, None)
+        .expect("Could not create proposal.");
+    let ppm = public_group
+        .process_message(backend, into_public_message(message))
+        .unwrap();
+    let proposal = match ppm.into_content() {
+        ProcessedMessageContent::ProposalMessage(proposal) => *proposal,
+        _ => panic!("Unexpected message type."),
+    };
+    assert!(public_group
+        .add_proposal(proposal.clone())
+        .expect("Could not store proposal.")
+        .is_none());
+    assert_eq!(public_group.pending_proposals().count(), 1);
+    public_group.clear_proposals();
+    assert_eq!(public_group.pending_proposals().count(), 0);
+
+    // A store without room rejects proposals regardless of the policy.
+    public_group.set_max_pending_proposals(Some(0));
+    assert_eq!(
+        public_group.proposal_store_full_policy(),
+        ProposalStoreFullPolicy::EvictOldest
+    );
+    assert_eq!(
+        public_group.add_proposal(proposal),
+        Err(AddProposalError::ProposalStoreFull)
+    );
+    let (message, _proposal_ref) = alice_group
+        .propose_self_update(backend, &alice_signer, None)
+        .expect("Could not create proposal.");
+    assert_eq!(
+        public_group
+            .process_message(backend, into_public_message(message))
+            .expect_err("Proposal was not rejected."),
+        ProcessMessageError::ProposalStoreFull
+    );
+    assert_eq!(public_group.pending_proposals().count(), 0);
+
+    // With the reject policy, proposals are rejected while the store is full.
+    public_group.set_max_pending_proposals(Some(1));
+    public_group.set_proposal_store_full_policy(ProposalStoreFullPolicy::Reject);
+    assert_eq!(
+        public_group.proposal_store_full_policy(),
+        ProposalStoreFullPolicy::Reject
+    );
+    let (message, proposal_ref) = alice_group
+        .propose_self_update(backend, &alice_signer, None)
+        .expect("Could not create proposal.");
+    let ppm = public_group
+        .process_message(backend, into_public_message(message))
+        .unwrap();
+    if let ProcessedMessageContent::ProposalMessage(proposal) = ppm.into_content() {
+        assert!(public_group
+            .add_proposal(*proposal)
+            .expect("Could not store proposal.")
+            .is_none());
+    } else {
+        panic!("Unexpected message type.");
+    }
+    let (message, _proposal_ref) = alice_group
+        .propose_self_update(backend, &alice_signer, None)
+        .expect("Could not create proposal.");
+    assert_eq!(
+        public_group
+            .process_message(backend, into_public_message(message))
+            .expect_err("Proposal was not rejected."),
+        ProcessMessageError::ProposalStoreFull
+    );
+    let pending_proposal_refs: Vec<_> = public_group
+        .pending_proposals()
+        .map(|proposal| proposal.proposal_reference())
+        .collect();
+    assert_eq!(pending_proposal_refs, vec![proposal_ref]);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn roster_queries(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (ds_credential_with_key, _ds_kpb, _ds_signer, _ds_pk) =
+        setup_client("Delivery Service", ciphersuite, backend);
+
+    let external_sender = ExternalSender::new(
+        ds_credential_with_key.signature_key.clone(),
+        ds_credential_with_key.credential.clone(),
+    );
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .external_senders(vec![external_sender.clone()])
+        .build();
+
+    // === Alice creates a group with Bob ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let (public_group, _extensions) = PublicGroup::from_external(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+    )
+    .unwrap();
+
+    // The roster matches the one of the members.
+    assert_eq!(
+        public_group.members().collect::<Vec<_>>(),
+        alice_group.members().collect::<Vec<_>>()
+    );
+    let bob_members: Vec<_> = public_group.members_by_identity(b"Bob").collect();
+    assert_eq!(bob_members.len(), 1);
+    assert_eq!(bob_members[0].index, LeafNodeIndex::new(1));
+    assert_eq!(
+        public_group.member(LeafNodeIndex::new(1)),
+        Some(&bob_members[0].credential)
+    );
+    assert!(public_group.member(LeafNodeIndex::new(2)).is_none());
+    assert_eq!(public_group.members_by_identity(b"Charlie").count(), 0);
+
+    // The external senders are enumerated with their index.
+    let external_senders: Vec<_> = public_group.external_senders().collect();
+    assert_eq!(
+        external_senders,
+        vec![(SenderExtensionIndex::new(0), &external_sender)]
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn snapshot_replication(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    // === Alice creates a group with Bob ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let (mut public_group, _extensions) = PublicGroup::from_external(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+    )
+    .unwrap();
+
+    // The public group stores a proposal from Alice.
+    let (message, _proposal_ref) = alice_group
+        .propose_self_update(backend, &alice_signer, None)
+        .expect("Could not create proposal.");
+    let ppm = public_group
+        .process_message(backend, into_public_message(message))
+        .unwrap();
+    if let ProcessedMessageContent::ProposalMessage(proposal) = ppm.into_content() {
+        public_group
+            .add_proposal(*proposal)
+            .expect("Could not store proposal.");
+    } else {
+        panic!("Unexpected message type.");
+    }
+
+    // Another node imports a snapshot and agrees on the state.
+    let snapshot = public_group.export_snapshot(backend).unwrap();
+    let digest = public_group.state_digest(backend).unwrap();
+    assert_eq!(snapshot.digest(), digest.as_slice());
+    assert_eq!(snapshot.group_context(), public_group.group_context());
+
+    let serialized = serde_json::to_vec(&snapshot).unwrap();
+    let replica = PublicGroup::from_snapshot(backend, serde_json::from_slice(&serialized).unwrap())
+        .expect("Could not import snapshot.");
+    assert_eq!(replica, public_group);
+    assert_eq!(replica.state_digest(backend).unwrap(), digest);
+
+    // The digests diverge when the state diverges.
+    let mut replica = replica;
+    replica.clear_proposals();
+    assert_ne!(replica.state_digest(backend).unwrap(), digest);
+
+    // Tampered snapshots are rejected.
+    let mut value = serde_json::to_value(&snapshot).unwrap();
+    value["interim_transcript_hash"] = serde_json::Value::Array(Vec::new());
+    assert_eq!(
+        PublicGroup::from_snapshot(backend, serde_json::from_value(value).unwrap()),
+        Err(SnapshotImportError::DigestMismatch)
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn external_commit_gating(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (charlie_credential_with_key, _charlie_kpb, charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    // === Alice creates a group ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // The DS serves this GroupInfo to external joiners.
+    let served_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let (mut public_group, _extensions) = PublicGroup::from_external(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        served_group_info.clone(),
+        ProposalStore::new(),
+    )
+    .unwrap();
+
+    // Commits of members are not External Commits.
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not create commit.");
+    let err = public_group
+        .process_external_commit(backend, into_public_message(commit), &served_group_info)
+        .expect_err("Accepted a commit of a member.");
+    assert_eq!(
+        err,
+        ProcessMessageError::InvalidCommit(StageCommitError::ExternalCommitValidation(
+            ExternalCommitValidationError::NotAnExternalCommit
+        ))
+    );
+    alice_group.clear_pending_commit();
+
+    // === Charlie joins with an External Commit ===
+    let (charlie_group, external_commit, _group_info) = MlsGroup::join_by_external_commit(
+        backend,
+        &charlie_signer,
+        Some(alice_group.export_ratchet_tree().into()),
+        served_group_info.clone(),
+        &mls_group_config,
+        b"",
+        charlie_credential_with_key,
+    )
+    .expect("Could not join by External Commit.");
+    let external_commit = into_public_message(external_commit);
+
+    let ppm = public_group
+        .process_external_commit(backend, external_commit.clone(), &served_group_info)
+        .expect("Rejected a valid External Commit.");
+    public_group.merge_commit(extract_staged_commit(ppm));
+
+    let ppm = alice_group
+        .process_message(backend, external_commit.clone())
+        .expect("Could not process External Commit.");
+    alice_group
+        .merge_staged_commit(backend, extract_staged_commit(ppm))
+        .expect("Could not merge External Commit.");
+    assert_eq!(
+        public_group.group_context(),
+        alice_group.export_group_context()
+    );
+    assert_eq!(
+        public_group.group_context(),
+        charlie_group.export_group_context()
+    );
+
+    // The served GroupInfo is now outdated.
+    let err = public_group
+        .process_external_commit(backend, external_commit, &served_group_info)
+        .expect_err("Accepted an External Commit for an outdated GroupInfo.");
+    assert_eq!(
+        err,
+        ProcessMessageError::InvalidCommit(StageCommitError::ExternalCommitValidation(
+            ExternalCommitValidationError::GroupInfoMismatch
+        ))
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn membership_changes(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    // === Alice creates a group ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let (mut public_group, _extensions) = PublicGroup::from_external(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+    )
+    .unwrap();
+
+    // === Alice adds Bob and Charlie ===
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let ppm = public_group
+        .process_message(backend, into_public_message(commit))
+        .unwrap();
+    let staged_commit = extract_staged_commit(ppm);
+    let changes = public_group
+        .membership_changes(backend, &staged_commit)
+        .unwrap();
+    let joiners: Vec<_> = changes
+        .joiners()
+        .iter()
+        .map(|joiner| {
+            (
+                joiner.key_package_ref().cloned(),
+                joiner.credential().clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        joiners,
+        vec![
+            (
+                Some(bob_kpb.key_package().hash_ref(backend.crypto()).unwrap()),
+                bob_credential_with_key.credential.clone()
+            ),
+            (
+                Some(
+                    charlie_kpb
+                        .key_package()
+                        .hash_ref(backend.crypto())
+                        .unwrap()
+                ),
+                charlie_credential_with_key.credential
+            ),
+        ]
+    );
+    assert!(changes.leavers().is_empty());
+    public_group.merge_commit(staged_commit);
+
+    // === Alice removes Bob ===
+    let (commit, _welcome, _group_info) = alice_group
+        .remove_members(backend, &alice_signer, &[LeafNodeIndex::new(1)])
+        .expect("Could not remove member from group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let ppm = public_group
+        .process_message(backend, into_public_message(commit))
+        .unwrap();
+    let staged_commit = extract_staged_commit(ppm);
+    let changes = public_group
+        .membership_changes(backend, &staged_commit)
+        .unwrap();
+    assert!(changes.joiners().is_empty());
+    assert_eq!(changes.leavers().len(), 1);
+    assert_eq!(changes.leavers()[0].index, LeafNodeIndex::new(1));
+    assert_eq!(
+        changes.leavers()[0].credential,
+        bob_credential_with_key.credential
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn time_provider(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    struct FixedTime(u64);
+
+    impl TimeProvider for FixedTime {
+        fn unix_now(&self) -> Option<u64> {
+            Some(self.0)
+        }
+    }
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let (mut public_group, _extensions) = PublicGroup::from_external(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+    )
+    .unwrap();
+    assert_eq!(public_group.clock_skew_tolerance(), Duration::ZERO);
+
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member to group.");
+
+    // Bob's key package has expired at the configured time.
+    let far_future = bob_kpb
+        .key_package()
+        .leaf_node()
+        .life_time()
+        .expect("Missing lifetime.")
+        .not_after()
+        + 3600;
+    public_group.set_time_provider(FixedTime(far_future));
+    assert_eq!(public_group.time_provider().unix_now(), Some(far_future));
+    assert!(public_group
+        .process_message(backend, into_public_message(commit.clone()))
+        .is_err());
+
+    // The clock skew tolerance is applied to the configured time.
+    public_group.set_clock_skew_tolerance(Duration::from_secs(7200));
+    public_group
+        .process_message(backend, into_public_message(commit))
+        .expect("Could not process message.");
+}
+
 // A helper function
 fn into_public_message(message: MlsMessageOut) -> PublicMessageIn {
     match message.into_protocol_message().unwrap() {
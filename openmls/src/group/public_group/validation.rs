@@ -6,10 +6,9 @@ use std::collections::{BTreeSet, HashSet};
 use openmls_traits::types::VerifiableCiphersuite;
 
 use super::PublicGroup;
-#[cfg(test)]
-use crate::treesync::errors::LeafNodeValidationError;
 use crate::{
     binary_tree::array_representation::LeafNodeIndex,
+    extensions::{Role, RolesExtension},
     framing::{
         mls_auth_content_in::VerifiableAuthenticatedContentIn, ContentType, ProtocolMessage,
         Sender, WireFormat,
@@ -17,11 +16,11 @@ use crate::{
     group::{
         errors::{ExternalCommitValidationError, ProposalValidationError, ValidationError},
         past_secrets::MessageSecretsStore,
-        Member, ProposalQueue,
+        ProposalQueue,
     },
     messages::proposals::{Proposal, ProposalOrRefType, ProposalType},
     schedule::errors::PskError,
-    treesync::node::leaf_node::LeafNode,
+    treesync::{errors::LeafNodeValidationError, node::leaf_node::LeafNode},
 };
 
 impl PublicGroup {
@@ -211,24 +210,41 @@ impl PublicGroup {
                     return Err(ProposalValidationError::InsufficientCapabilities);
                 }
             }
+            // Check if the key package accepts the media types required by the
+            // group.
+            if !add_proposal
+                .add_proposal()
+                .key_package()
+                .leaf_node()
+                .supports_media_types(
+                    self.group_context()
+                        .extensions()
+                        .required_media_types()
+                        .as_ref(),
+                )
+            {
+                return Err(ProposalValidationError::UnsupportedMediaTypes);
+            }
         }
 
-        for Member {
-            index,
-            encryption_key,
-            signature_key,
-            ..
-        } in self.treesync().full_leave_members()
-        {
-            let has_remove_proposal = proposal_queue
-                .remove_proposals()
-                .any(|p| p.remove_proposal().removed == index);
+        // The checks against the existing members are only necessary if
+        // there are new members.
+        if signature_key_set.is_empty() {
+            return Ok(());
+        }
+        let removed: HashSet<LeafNodeIndex> = proposal_queue
+            .remove_proposals()
+            .map(|remove_proposal| remove_proposal.remove_proposal().removed())
+            .collect();
+        for (index, leaf) in self.treesync().full_leaves_indexed() {
             // ValSem104
-            if signature_key_set.contains(&signature_key) && !has_remove_proposal {
+            if signature_key_set.contains(leaf.signature_key().as_slice())
+                && !removed.contains(&index)
+            {
                 return Err(ProposalValidationError::ExistingSignatureKeyAddProposal);
             }
             // ValSem114
-            if encryption_key_set.contains(&encryption_key) {
+            if encryption_key_set.contains(leaf.encryption_key().as_slice()) {
                 return Err(ProposalValidationError::ExistingPublicKeyAddProposal);
             }
         }
@@ -272,13 +288,8 @@ impl PublicGroup {
         &self,
         proposal_queue: &ProposalQueue,
         committer: LeafNodeIndex,
-    ) -> Result<HashSet<Vec<u8>>, ProposalValidationError> {
+    ) -> Result<(), ProposalValidationError> {
         let mut encryption_keys = HashSet::new();
-        for leaf in self.treesync().full_leaves() {
-            // 8.3. Leaf Node Validation
-            // encryption key must be unique
-            encryption_keys.insert(leaf.encryption_key().as_slice().to_vec());
-        }
 
         // Check the update proposals from the proposal queue first
         let update_proposals = proposal_queue.update_proposals();
@@ -296,18 +307,27 @@ impl PublicGroup {
                 return Err(ProposalValidationError::UpdateFromNonMember);
             }
 
-            let encryption_key = update_proposal
-                .update_proposal()
-                .leaf_node()
-                .encryption_key()
-                .as_slice();
-            // ValSem110
-            // HPKE init key must be unique among existing members
-            if encryption_keys.contains(encryption_key) {
-                return Err(ProposalValidationError::ExistingPublicKeyUpdateProposal);
-            }
+            encryption_keys.insert(
+                update_proposal
+                    .update_proposal()
+                    .leaf_node()
+                    .encryption_key()
+                    .as_slice(),
+            );
+        }
+
+        // ValSem110
+        // HPKE init key must be unique among existing members. We only walk
+        // the tree if there are update proposals.
+        if !encryption_keys.is_empty()
+            && self
+                .treesync()
+                .full_leaves()
+                .any(|leaf| encryption_keys.contains(leaf.encryption_key().as_slice()))
+        {
+            return Err(ProposalValidationError::ExistingPublicKeyUpdateProposal);
         }
-        Ok(encryption_keys)
+        Ok(())
     }
 
     /// Validate PreSharedKey proposals.
@@ -350,10 +370,14 @@ impl PublicGroup {
     pub(crate) fn validate_path_key_package(
         &self,
         leaf_node: &LeafNode,
-        public_key_set: HashSet<Vec<u8>>,
     ) -> Result<(), ProposalValidationError> {
         // ValSem110
-        if public_key_set.contains(leaf_node.encryption_key().as_slice()) {
+        let encryption_key = leaf_node.encryption_key().as_slice();
+        if self
+            .treesync()
+            .full_leaves()
+            .any(|leaf| leaf.encryption_key().as_slice() == encryption_key)
+        {
             return Err(ProposalValidationError::ExistingPublicKeyUpdateProposal);
         }
         Ok(())
@@ -365,6 +389,7 @@ impl PublicGroup {
     ///  - ValSem242: External Commit must only cover inline proposal in allowlist (ExternalInit, Remove, PreSharedKey)
     ///  - ValSem243: External Commit, inline Remove Proposal: The identity and the endpoint_id of the removed
     ///               leaf are identical to the ones in the path KeyPackage.
+    ///  - ValSem248: External Commit must cover at most one inline Remove proposal.
     pub(super) fn validate_external_commit(
         &self,
         proposal_queue: &ProposalQueue,
@@ -394,6 +419,15 @@ impl PublicGroup {
             return Err(ExternalCommitValidationError::InvalidInlineProposals);
         }
 
+        // ValSem248: External Commit must cover at most one inline Remove proposal
+        let count_inline_remove_proposals = proposal_queue
+            .filtered_by_type(ProposalType::Remove)
+            .filter(|p| p.proposal_or_ref_type() == ProposalOrRefType::Proposal)
+            .count();
+        if count_inline_remove_proposals > 1 {
+            return Err(ExternalCommitValidationError::MultipleRemoveProposals);
+        }
+
         let remove_proposals = proposal_queue.filtered_by_type(ProposalType::Remove);
         for proposal in remove_proposals {
             if proposal.proposal_or_ref_type() == ProposalOrRefType::Proposal {
@@ -420,9 +454,232 @@ impl PublicGroup {
         Ok(())
     }
 
+    /// Validate GroupContextExtensions proposals.
+    ///
+    /// A commit must not contain more than one GroupContextExtensions
+    /// proposal (RFC 9420, Section 12.2). The required capabilities and the
+    /// required media types of the proposed group context must be supported
+    /// by all members that remain in the group.
+    pub(crate) fn validate_group_context_extensions_proposals(
+        &self,
+        proposal_queue: &ProposalQueue,
+    ) -> Result<(), ProposalValidationError> {
+        if proposal_queue
+            .filtered_by_type(ProposalType::GroupContextExtensions)
+            .count()
+            > 1
+        {
+            return Err(ProposalValidationError::MultipleGroupContextExtensionsProposals);
+        }
+
+        let removed: HashSet<LeafNodeIndex> = proposal_queue
+            .remove_proposals()
+            .map(|remove_proposal| remove_proposal.remove_proposal().removed())
+            .collect();
+
+        for queued_proposal in proposal_queue.filtered_by_type(ProposalType::GroupContextExtensions)
+        {
+            if let Proposal::GroupContextExtensions(group_context_extensions_proposal) =
+                queued_proposal.proposal()
+            {
+                let required_capabilities = group_context_extensions_proposal
+                    .extensions()
+                    .required_capabilities();
+                for (leaf_index, leaf) in self.treesync().full_leaves_indexed() {
+                    if removed.contains(&leaf_index) {
+                        continue;
+                    }
+                    leaf.validate_required_capabilities(required_capabilities)
+                        .map_err(|_| ProposalValidationError::InsufficientCapabilities)?;
+                    if !leaf.supports_media_types(
+                        group_context_extensions_proposal
+                            .extensions()
+                            .required_media_types()
+                            .as_ref(),
+                    ) {
+                        return Err(ProposalValidationError::UnsupportedMediaTypes);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate the roles of the senders of the proposals in the queue and of
+    /// the `committer` if the group context contains a [`RolesExtension`].
+    ///
+    /// Proposals of members are checked with
+    /// [`PublicGroup::validate_proposal_permission()`]. Proposals of
+    /// non-members, i.e., of external senders and new members, require the
+    /// role of the committer. The inline proposals of an external commit are
+    /// restricted by ValSem242 and ValSem243 instead.
+    ///
+    /// Since roles are assigned to the identity of the credential, a member
+    /// can't change its identity in the `path_leaf_node` of its commit, and an
+    /// external committer can only take the role of its identity if it
+    /// removes its old leaf with the same identity, i.e., resyncs.
+    pub(crate) fn validate_proposal_permissions(
+        &self,
+        proposal_queue: &ProposalQueue,
+        committer: &Sender,
+        path_leaf_node: Option<&LeafNode>,
+    ) -> Result<(), ProposalValidationError> {
+        let Some(roles) = self.group_context().extensions().roles() else {
+            return Ok(());
+        };
+
+        let committer_role = match committer {
+            Sender::Member(committer_index) => {
+                let committer_leaf = self
+                    .leaf(*committer_index)
+                    .ok_or(ProposalValidationError::UnknownMember)?;
+                if let Some(path_leaf_node) = path_leaf_node {
+                    if path_leaf_node.credential().identity()
+                        != committer_leaf.credential().identity()
+                    {
+                        return Err(ProposalValidationError::UpdatePathIdentityMismatch);
+                    }
+                }
+                roles.role(committer_leaf.credential().identity())
+            }
+            Sender::NewMemberCommit => {
+                let resyncs = proposal_queue.queued_proposals().any(|queued_proposal| {
+                    matches!(queued_proposal.proposal(), Proposal::Remove(_))
+                });
+                if let Some(path_leaf_node) = path_leaf_node {
+                    if !resyncs && roles.role(path_leaf_node.credential().identity()) > Role::Member
+                    {
+                        return Err(ProposalValidationError::InsufficientRole);
+                    }
+                }
+                // The external committer is not a member yet.
+                Role::Member
+            }
+            // Non-members can't commit, which is checked separately.
+            Sender::External(_) | Sender::NewMemberProposal => Role::Member,
+        };
+
+        for queued_proposal in proposal_queue.queued_proposals() {
+            match queued_proposal.sender() {
+                sender @ Sender::Member(_) => {
+                    self.validate_proposal_permission(sender, queued_proposal.proposal())?
+                }
+                Sender::External(_) | Sender::NewMemberProposal => {
+                    let required_role =
+                        self.required_role(&roles, None, queued_proposal.proposal());
+                    if committer_role < required_role {
+                        return Err(ProposalValidationError::InsufficientRole);
+                    }
+                }
+                Sender::NewMemberCommit => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate that the `sender` of the `proposal` has the role required for
+    /// the proposal if the group context contains a [`RolesExtension`].
+    ///
+    /// Only proposals from members are checked here. Proposals from
+    /// non-members require the role of the committer, which is checked by
+    /// [`PublicGroup::validate_proposal_permissions()`]. The required roles
+    /// are:
+    ///  - Adding members requires [`Role::Admin`], and adding a member whose
+    ///    identity has a higher role requires that role.
+    ///  - Removing a regular member requires [`Role::Admin`] and removing an
+    ///    admin or owner requires [`Role::Owner`]. Every member may remove
+    ///    themselves.
+    ///  - Changing the [`RolesExtension`] requires [`Role::Owner`] and changing
+    ///    other group context extensions requires [`Role::Admin`].
+    ///  - Re-initializing the group requires [`Role::Owner`].
+    ///  - All other proposals can be sent by every member. Update proposals
+    ///    must not change the identity of the member.
+    pub(crate) fn validate_proposal_permission(
+        &self,
+        sender: &Sender,
+        proposal: &Proposal,
+    ) -> Result<(), ProposalValidationError> {
+        let Some(roles) = self.group_context().extensions().roles() else {
+            return Ok(());
+        };
+        let Sender::Member(sender_index) = sender else {
+            return Ok(());
+        };
+        let sender_leaf = self
+            .leaf(*sender_index)
+            .ok_or(ProposalValidationError::UnknownMember)?;
+
+        if let Proposal::Update(update_proposal) = proposal {
+            if update_proposal.leaf_node().credential().identity()
+                != sender_leaf.credential().identity()
+            {
+                return Err(ProposalValidationError::UpdateProposalIdentityMismatch);
+            }
+        }
+
+        let sender_role = roles.role(sender_leaf.credential().identity());
+        if sender_role < self.required_role(&roles, Some(*sender_index), proposal) {
+            return Err(ProposalValidationError::InsufficientRole);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the role required for the `proposal` of the member at
+    /// `sender_index`, or of a non-member if `sender_index` is `None`.
+    fn required_role(
+        &self,
+        roles: &RolesExtension,
+        sender_index: Option<LeafNodeIndex>,
+        proposal: &Proposal,
+    ) -> Role {
+        match proposal {
+            Proposal::Add(add_proposal) => Role::Admin.max(
+                roles.role(
+                    add_proposal
+                        .key_package()
+                        .leaf_node()
+                        .credential()
+                        .identity(),
+                ),
+            ),
+            Proposal::Remove(remove_proposal) => {
+                let removed = remove_proposal.removed();
+                if Some(removed) == sender_index {
+                    Role::Member
+                } else {
+                    match self
+                        .leaf(removed)
+                        .map(|leaf| roles.role(leaf.credential().identity()))
+                    {
+                        Some(Role::Member) | None => Role::Admin,
+                        Some(Role::Admin) | Some(Role::Owner) => Role::Owner,
+                    }
+                }
+            }
+            Proposal::GroupContextExtensions(group_context_extensions_proposal) => {
+                if group_context_extensions_proposal
+                    .extensions()
+                    .roles()
+                    .as_ref()
+                    != Some(roles)
+                {
+                    Role::Owner
+                } else {
+                    Role::Admin
+                }
+            }
+            Proposal::ReInit(_) => Role::Owner,
+            Proposal::Update(_)
+            | Proposal::PreSharedKey(_)
+            | Proposal::ExternalInit(_)
+            | Proposal::AppAck(_) => Role::Member,
+        }
+    }
+
     /// Returns a [`LeafNodeValidationError`] if an [`ExtensionType`]
     /// in `extensions` is not supported by a leaf in this tree.
-    #[cfg(test)]
     pub(crate) fn check_extension_support(
         &self,
         extensions: &[crate::extensions::ExtensionType],
@@ -325,3 +325,253 @@ fn external_remove_proposal_should_fail_when_no_external_senders(
         ProcessMessageError::ValidationError(ValidationError::NoExternalSendersExtension)
     );
 }
+
+#[apply(ciphersuites_and_backends)]
+fn external_senders_rotation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let old_ds_credential_bundle = generate_credential_bundle(
+        "old-delivery-service".into(),
+        ciphersuite.signature_algorithm(),
+        backend,
+    );
+    let new_ds_credential_bundle = generate_credential_bundle(
+        "new-delivery-service".into(),
+        ciphersuite.signature_algorithm(),
+        backend,
+    );
+    let old_ds_credential = old_ds_credential_bundle
+        .credential_with_key
+        .credential
+        .clone();
+    let new_ds_credential = new_ds_credential_bundle
+        .credential_with_key
+        .credential
+        .clone();
+    let new_external_sender = ExternalSender::new(
+        new_ds_credential_bundle
+            .credential_with_key
+            .signature_key
+            .clone(),
+        new_ds_credential.clone(),
+    );
+
+    let (mut alice_group, alice_credential) = validation_test_setup(
+        PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+        ciphersuite,
+        backend,
+        vec![ExternalSender::new(
+            old_ds_credential_bundle
+                .credential_with_key
+                .signature_key
+                .clone(),
+            old_ds_credential.clone(),
+        )],
+    );
+
+    assert_eq!(alice_group.external_senders().len(), 1);
+    assert_eq!(
+        alice_group.external_sender_index(&old_ds_credential),
+        Some(SenderExtensionIndex::new(0))
+    );
+    assert_eq!(alice_group.external_sender_index(&new_ds_credential), None);
+
+    // Alice adds the new DS as an external sender.
+    alice_group
+        .propose_add_external_sender(
+            backend,
+            &alice_credential.signer,
+            new_external_sender.clone(),
+        )
+        .expect("Could not propose to add the external sender.");
+    alice_group
+        .commit_to_pending_proposals(backend, &alice_credential.signer)
+        .expect("Could not commit the proposal.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge the commit.");
+
+    assert_eq!(alice_group.external_senders().len(), 2);
+    assert_eq!(
+        alice_group.external_sender_index(&new_ds_credential),
+        Some(SenderExtensionIndex::new(1))
+    );
+
+    // Adding the same external sender twice fails.
+    assert_eq!(
+        alice_group
+            .propose_add_external_sender(backend, &alice_credential.signer, new_external_sender)
+            .unwrap_err(),
+        ProposalError::ProposeExternalSenderError(
+            ProposeExternalSenderError::DuplicateExternalSender
+        )
+    );
+
+    // Alice removes the old DS.
+    alice_group
+        .propose_remove_external_sender(backend, &alice_credential.signer, &old_ds_credential)
+        .expect("Could not propose to remove the external sender.");
+    alice_group
+        .commit_to_pending_proposals(backend, &alice_credential.signer)
+        .expect("Could not commit the proposal.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge the commit.");
+
+    assert_eq!(alice_group.external_senders().len(), 1);
+    assert_eq!(alice_group.external_sender_index(&old_ds_credential), None);
+    let new_ds_index = alice_group
+        .external_sender_index(&new_ds_credential)
+        .expect("The new DS is not an external sender.");
+    assert_eq!(new_ds_index, SenderExtensionIndex::new(0));
+
+    // Removing an unknown external sender fails.
+    assert_eq!(
+        alice_group
+            .propose_remove_external_sender(backend, &alice_credential.signer, &old_ds_credential)
+            .unwrap_err(),
+        ProposalError::ProposeExternalSenderError(
+            ProposeExternalSenderError::UnknownExternalSender
+        )
+    );
+
+    // The new DS can now send external proposals.
+    let bob_index = alice_group
+        .members()
+        .find(|member| member.credential.identity() == b"Bob")
+        .map(|member| member.index)
+        .unwrap();
+    let bob_external_remove_proposal: MlsMessageIn = ExternalProposal::new_remove(
+        bob_index,
+        alice_group.group_id().clone(),
+        alice_group.epoch(),
+        &new_ds_credential_bundle.signer,
+        new_ds_index,
+    )
+    .unwrap()
+    .into();
+    let processed_message = alice_group
+        .process_message(backend, bob_external_remove_proposal)
+        .expect("Could not process the external proposal.");
+    assert!(matches!(
+        processed_message.into_content(),
+        ProcessedMessageContent::ProposalMessage(_)
+    ));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn external_remove_proposal_by_identity(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) {
+    // delivery service credentials. DS will craft an external remove proposal
+    let ds_credential_bundle = generate_credential_bundle(
+        "delivery-service".into(),
+        ciphersuite.signature_algorithm(),
+        backend,
+    );
+
+    let (mut alice_group, alice_credential) = validation_test_setup(
+        PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+        ciphersuite,
+        backend,
+        vec![ExternalSender::new(
+            ds_credential_bundle
+                .credential_with_key
+                .signature_key
+                .clone(),
+            ds_credential_bundle.credential_with_key.credential.clone(),
+        )],
+    );
+
+    // The DS resolves Bob's identity against its copy of the roster
+    let public_group = alice_group.group().public_group();
+    let bob_external_remove_proposal: MlsMessageIn = ExternalProposal::new_remove_by_identity(
+        b"Bob",
+        public_group,
+        &ds_credential_bundle.signer,
+        SenderExtensionIndex::new(0),
+    )
+    .unwrap()
+    .into();
+
+    // Unknown identities can't be resolved
+    assert_eq!(
+        ExternalProposal::new_remove_by_identity(
+            b"Charlie",
+            public_group,
+            &ds_credential_bundle.signer,
+            SenderExtensionIndex::new(0),
+        )
+        .unwrap_err(),
+        ProposeRemoveMemberError::UnknownMember
+    );
+
+    // Alice validates and commits the proposal
+    let processed_message = alice_group
+        .process_message(backend, bob_external_remove_proposal)
+        .unwrap();
+    let ProcessedMessageContent::ProposalMessage(remove_proposal) = processed_message.into_content() else { panic!("Not a remove proposal");};
+    alice_group.store_pending_proposal(*remove_proposal);
+    alice_group
+        .commit_to_pending_proposals(backend, &alice_credential.signer)
+        .unwrap();
+    alice_group.merge_pending_commit(backend).unwrap();
+    assert_eq!(alice_group.members().count(), 1);
+
+    // Bob is no longer in the group
+    assert_eq!(
+        ExternalProposal::new_remove_by_identity(
+            b"Bob",
+            alice_group.group().public_group(),
+            &ds_credential_bundle.signer,
+            SenderExtensionIndex::new(0),
+        )
+        .unwrap_err(),
+        ProposeRemoveMemberError::UnknownMember
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn external_remove_proposal_by_ambiguous_identity(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) {
+    let ds_credential_bundle = generate_credential_bundle(
+        "delivery-service".into(),
+        ciphersuite.signature_algorithm(),
+        backend,
+    );
+
+    let (mut alice_group, alice_credential) = validation_test_setup(
+        PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+        ciphersuite,
+        backend,
+        vec![ExternalSender::new(
+            ds_credential_bundle
+                .credential_with_key
+                .signature_key
+                .clone(),
+            ds_credential_bundle.credential_with_key.credential.clone(),
+        )],
+    );
+
+    // Bob joins with a second device
+    let bob_second_device =
+        generate_credential_bundle("Bob".into(), ciphersuite.signature_algorithm(), backend);
+    let bob_key_package =
+        generate_key_package(ciphersuite, Extensions::empty(), backend, bob_second_device);
+    alice_group
+        .add_members(backend, &alice_credential.signer, &[bob_key_package])
+        .unwrap();
+    alice_group.merge_pending_commit(backend).unwrap();
+
+    assert_eq!(
+        ExternalProposal::new_remove_by_identity(
+            b"Bob",
+            alice_group.group().public_group(),
+            &ds_credential_bundle.signer,
+            SenderExtensionIndex::new(0),
+        )
+        .unwrap_err(),
+        ProposeRemoveMemberError::AmbiguousIdentity
+    );
+}
@@ -295,7 +295,7 @@ pub fn generate_test_vector(ciphersuite: Ciphersuite) -> MessagesTestVector {
         .create_application_message(
             b"aad",
             b"msg",
-            random_u8() as usize,
+            &PaddingPolicy::Multiple(random_u8() as usize),
             &crypto,
             &alice_credential_with_key_and_signer.signer,
         )
@@ -334,7 +334,11 @@ pub fn generate_test_vector(ciphersuite: Ciphersuite) -> MessagesTestVector {
     commit_pt.set_membership_tag_test(random_membership_tag);
 
     let private_message = alice_group
-        .encrypt(encryption_target, random_u8() as usize, &crypto)
+        .encrypt(
+            encryption_target,
+            &PaddingPolicy::Multiple(random_u8() as usize),
+            &crypto,
+        )
         .unwrap();
 
     MessagesTestVector {
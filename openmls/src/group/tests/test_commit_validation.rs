@@ -334,7 +334,7 @@ fn test_valsem201(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
             .for_each(|p| alice_group.store_pending_proposal(p));
 
         let params = CreateCommitParams::builder()
-            .framing_parameters(alice_group.framing_parameters())
+            .framing_parameters(alice_group.framing_parameters(ContentType::Commit))
             .proposal_store(&alice_group.proposal_store)
             // has to be turned off otherwise commit path is always present
             .force_self_update(false)
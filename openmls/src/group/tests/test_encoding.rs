@@ -74,7 +74,7 @@ fn test_application_message_encoding(backend: &impl OpenMlsCryptoProvider) {
                 .create_application_message(
                     &aad,
                     &message,
-                    0,
+                    &PaddingPolicy::None,
                     backend,
                     &credential_with_key_and_signer.signer,
                 )
@@ -703,6 +703,111 @@ fn test_valsem246(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
 }
 
 // External Commit should work when group use ciphertext WireFormat
+// ValSem248: External Commit must cover at most one inline Remove proposal
+#[apply(ciphersuites_and_backends)]
+fn test_valsem248(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let ECValidationTestSetup {
+        mut alice_group,
+        alice_credential,
+        bob_credential,
+        ..
+    } = validation_test_setup(PURE_PLAINTEXT_WIRE_FORMAT_POLICY, ciphersuite, backend);
+
+    // Alice has to add Bob first, so that Bob actually creates a remove
+    // proposal to remove his former self.
+    let bob_key_package = generate_key_package(
+        ciphersuite,
+        Extensions::empty(),
+        backend,
+        bob_credential.clone(),
+    );
+
+    alice_group
+        .add_members(backend, &alice_credential.signer, &[bob_key_package])
+        .unwrap();
+
+    alice_group.merge_pending_commit(backend).unwrap();
+
+    // Bob wants to commit externally.
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_credential.signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let ratchet_tree = alice_group.export_ratchet_tree();
+
+    // Note: This will create a remove proposal because Bob is already a member of the group.
+    let (_, public_message_commit, _) = MlsGroup::join_by_external_commit(
+        backend,
+        &bob_credential.signer,
+        Some(ratchet_tree.into()),
+        verifiable_group_info,
+        alice_group.configuration(),
+        &[],
+        bob_credential.credential_with_key,
+    )
+    .unwrap();
+
+    // MlsMessageOut -> MlsMessageIn
+    let serialized_message = public_message_commit.tls_serialize_detached().unwrap();
+    let public_message_commit = MlsMessageIn::tls_deserialize(&mut serialized_message.as_slice())
+        .unwrap()
+        .into_plaintext()
+        .unwrap();
+
+    let public_message_commit_bad = {
+        let commit_bad = {
+            let mut commit =
+                if let FramedContentBody::Commit(commit) = public_message_commit.content() {
+                    commit.clone()
+                } else {
+                    panic!("Unexpected content type.");
+                };
+
+            // Add a second remove proposal targeting Alice.
+            commit
+                .proposals
+                .push(ProposalOrRef::Proposal(Proposal::Remove(RemoveProposal {
+                    removed: alice_group.own_leaf_index(),
+                })));
+
+            commit
+        };
+
+        let mut public_message_commit_bad = public_message_commit.clone();
+
+        public_message_commit_bad.set_content(FramedContentBody::Commit(commit_bad));
+
+        // We have to re-sign, since we changed the content.
+        resign_external_commit(
+            &bob_credential.signer,
+            public_message_commit_bad,
+            public_message_commit.confirmation_tag().unwrap().clone(),
+            alice_group
+                .export_group_context()
+                .tls_serialize_detached()
+                .unwrap(),
+        )
+    };
+
+    // Negative case
+    let err = alice_group
+        .process_message(backend, ProtocolMessage::from(public_message_commit_bad))
+        .expect_err("Could process message despite multiple inline remove proposals.");
+
+    assert_eq!(
+        err,
+        ProcessMessageError::InvalidCommit(StageCommitError::ExternalCommitValidation(
+            ExternalCommitValidationError::MultipleRemoveProposals
+        ))
+    );
+
+    // Positive case
+    alice_group
+        .process_message(backend, ProtocolMessage::from(public_message_commit))
+        .expect("Unexpected error.");
+}
+
 #[apply(ciphersuites_and_backends)]
 fn test_pure_ciphertest(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
     // Test with PrivateMessage
@@ -75,7 +75,7 @@ fn padding(backend: &impl OpenMlsCryptoProvider) {
                     .create_application_message(
                         &aad,
                         &message,
-                        padding_size,
+                        &PaddingPolicy::Multiple(padding_size),
                         backend,
                         &credential.signer,
                     )
@@ -95,6 +95,38 @@ fn padding(backend: &impl OpenMlsCryptoProvider) {
             }
         }
     }
+
+    // The length of a padded ciphertext doesn't need any further padding.
+    for padding_policy in [
+        PaddingPolicy::Buckets(vec![64, 256, 1024]),
+        PaddingPolicy::Padme,
+    ] {
+        for group_state in alice.group_states.borrow_mut().values_mut() {
+            let credential = alice
+                .credentials
+                .get(&group_state.ciphersuite())
+                .expect("An unexpected error occurred.");
+            for _ in 0..10 {
+                let message = randombytes(random_usize() % 1000);
+                let aad = randombytes(random_usize() % 1000);
+                let private_message = group_state
+                    .create_application_message(
+                        &aad,
+                        &message,
+                        &padding_policy,
+                        backend,
+                        &credential.signer,
+                    )
+                    .expect("An unexpected error occurred.");
+                let length = private_message.ciphertext().len();
+                assert_eq!(
+                    padding_policy.padding_length(length),
+                    0,
+                    "Error: message length {length} doesn't match padding policy {padding_policy:?}"
+                );
+            }
+        }
+    }
 }
 
 /// Check that PrivateMessageContent's padding field is verified to be all-zero.
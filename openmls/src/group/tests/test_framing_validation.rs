@@ -206,20 +206,22 @@ fn test_valsem003(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
     let err = bob_group
         .process_message(backend, plaintext.clone())
         .expect_err("Could parse message despite wrong epoch.");
-    assert_eq!(
-        err,
-        ProcessMessageError::ValidationError(ValidationError::WrongEpoch)
-    );
+    let ProcessMessageError::Desynced(report) = err else {
+        panic!("Unexpected error: {err:?}");
+    };
+    assert_eq!(report.kind(), DesyncKind::EpochBehind);
+    assert_eq!(report.epochs_behind(), 1);
 
     // Set the epoch too low
     plaintext.set_epoch(current_epoch.as_u64() - 1);
     let err = bob_group
         .process_message(backend, plaintext)
         .expect_err("Could parse message despite wrong epoch.");
-    assert_eq!(
-        err,
-        ProcessMessageError::ValidationError(ValidationError::WrongEpoch)
-    );
+    let ProcessMessageError::Desynced(report) = err else {
+        panic!("Unexpected error: {err:?}");
+    };
+    assert_eq!(report.kind(), DesyncKind::EpochAhead);
+    assert_eq!(report.epochs_ahead(), 1);
 
     // Positive case
     let processed_msg = bob_group
@@ -238,10 +240,10 @@ fn test_valsem003(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
 
     // Processing a commit twice should fail i.e. an epoch can only be used once in a commit message
     let process_twice = bob_group.process_message(backend, original_message);
-    assert_eq!(
+    assert!(matches!(
         process_twice.unwrap_err(),
-        ProcessMessageError::ValidationError(ValidationError::WrongEpoch)
-    );
+        ProcessMessageError::Desynced(report) if report.kind() == DesyncKind::EpochAhead
+    ));
 }
 
 // ValSem004 Sender: Member: check the member exists
@@ -607,3 +609,118 @@ fn test_valsem010(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
         .process_message(backend, ProtocolMessage::from(original_message))
         .expect("Unexpected error.");
 }
+
+// Stateless syntactic validation
+#[apply(ciphersuites_and_backends)]
+fn test_stateless_validation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let ValidationTestSetup {
+        mut alice_group,
+        bob_group: _,
+        _alice_credential,
+        _bob_credential: _,
+        _alice_key_package: _,
+        _bob_key_package: _,
+    } = validation_test_setup(PURE_PLAINTEXT_WIRE_FORMAT_POLICY, ciphersuite, backend);
+
+    let group_id = alice_group.group_id().clone();
+    let epoch = alice_group.epoch();
+    let context =
+        SyntacticValidationContext::new(group_id.clone(), epoch, PURE_PLAINTEXT_WIRE_FORMAT_POLICY);
+
+    // A commit passes the validation.
+    let (message, _welcome, _group_info) = alice_group
+        .self_update(backend, &_alice_credential.signer)
+        .expect("Could not self-update.");
+    let serialized_message = message
+        .tls_serialize_detached()
+        .expect("Could not serialize message.");
+    let report = validate_message_syntax(&serialized_message, &context)
+        .expect("Could not validate message.");
+    assert_eq!(report.wire_format(), WireFormat::PublicMessage);
+    assert_eq!(report.epoch(), epoch);
+    assert_eq!(report.content_type(), ContentType::Commit);
+    assert_eq!(
+        report.sender(),
+        Some(&Sender::Member(LeafNodeIndex::new(0)))
+    );
+
+    // Trailing bytes can't be decoded.
+    let mut trailing_bytes = serialized_message.clone();
+    trailing_bytes.push(0);
+    assert_eq!(
+        validate_message_syntax(&trailing_bytes, &context),
+        Err(SyntacticValidationError::UnableToDecode)
+    );
+
+    // The message doesn't match the group ID, epoch or wire format policy.
+    let wrong_group_context = SyntacticValidationContext::new(
+        GroupId::from_slice(&[9, 9, 9]),
+        epoch,
+        PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+    );
+    assert_eq!(
+        validate_message_syntax(&serialized_message, &wrong_group_context),
+        Err(SyntacticValidationError::WrongGroupId)
+    );
+    let wrong_epoch_context = SyntacticValidationContext::new(
+        group_id.clone(),
+        GroupEpoch::from(epoch.as_u64() + 1),
+        PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+    )
+    .with_max_past_epochs(5);
+    assert_eq!(
+        validate_message_syntax(&serialized_message, &wrong_epoch_context),
+        Err(SyntacticValidationError::WrongEpoch)
+    );
+    let ciphertext_context = SyntacticValidationContext::new(
+        group_id.clone(),
+        epoch,
+        PURE_CIPHERTEXT_WIRE_FORMAT_POLICY,
+    );
+    assert_eq!(
+        validate_message_syntax(&serialized_message, &ciphertext_context),
+        Err(SyntacticValidationError::IncompatibleWireFormat)
+    );
+
+    // ValSem007: The membership tag is missing.
+    let mut plaintext = MlsMessageIn::tls_deserialize(&mut serialized_message.as_slice())
+        .expect("Could not deserialize message.")
+        .into_plaintext()
+        .expect("Message was not a plaintext.");
+    plaintext.unset_membership_tag();
+    let serialized_message = MlsMessageOut::from(plaintext)
+        .tls_serialize_detached()
+        .expect("Could not serialize message.");
+    assert_eq!(
+        validate_message_syntax(&serialized_message, &context),
+        Err(SyntacticValidationError::MissingMembershipTag)
+    );
+
+    // Application messages of past epochs are only accepted within the window.
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    let message = alice_group
+        .create_message(backend, &_alice_credential.signer, b"Hello")
+        .expect("Could not create message.");
+    let serialized_message = message
+        .tls_serialize_detached()
+        .expect("Could not serialize message.");
+    let next_epoch_context = SyntacticValidationContext::new(
+        group_id,
+        GroupEpoch::from(epoch.as_u64() + 2),
+        PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+    );
+    assert_eq!(
+        validate_message_syntax(&serialized_message, &next_epoch_context),
+        Err(SyntacticValidationError::WrongEpoch)
+    );
+    let report = validate_message_syntax(
+        &serialized_message,
+        &next_epoch_context.with_max_past_epochs(1),
+    )
+    .expect("Could not validate message.");
+    assert_eq!(report.wire_format(), WireFormat::PrivateMessage);
+    assert_eq!(report.content_type(), ContentType::Application);
+    assert_eq!(report.sender(), None);
+}
@@ -372,7 +372,7 @@ fn group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvid
         .create_application_message(
             &[],
             &message_alice,
-            0,
+            &PaddingPolicy::None,
             backend,
             &alice_credential_with_keys.signer,
         )
@@ -701,7 +701,7 @@ fn group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvid
         .create_application_message(
             &[],
             &message_charlie,
-            0,
+            &PaddingPolicy::None,
             backend,
             &charlie_credential_with_keys.signer,
         )
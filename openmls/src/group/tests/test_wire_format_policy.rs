@@ -138,3 +138,53 @@ fn test_wire_policy_negative(ciphersuite: Ciphersuite, backend: &impl OpenMlsCry
         assert_eq!(err, ProcessMessageError::IncompatibleWireFormat);
     }
 }
+
+// Test policies that differ between proposals and commits
+#[apply(ciphersuites_and_backends)]
+fn test_content_type_wire_policy(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let wire_format_policy = PURE_PLAINTEXT_WIRE_FORMAT_POLICY.with_commit_policy(
+        OutgoingWireFormatPolicy::AlwaysCiphertext,
+        IncomingWireFormatPolicy::AlwaysCiphertext,
+    );
+    let (mut alice_group, alice_credential_with_key_and_signer) =
+        create_group(ciphersuite, backend, wire_format_policy);
+
+    // Commits are sent as PrivateMessages.
+    let message = receive_message(
+        ciphersuite,
+        backend,
+        &mut alice_group,
+        &alice_credential_with_key_and_signer.signer,
+    );
+    assert_eq!(message.wire_format(), WireFormat::PrivateMessage);
+    alice_group
+        .process_message(backend, message)
+        .expect("An unexpected error occurred.");
+
+    // Proposals are sent as PublicMessages.
+    let (proposal, _proposal_ref) = alice_group
+        .propose_self_update(backend, &alice_credential_with_key_and_signer.signer, None)
+        .expect("Could not create proposal.");
+    assert_eq!(
+        MlsMessageIn::from(proposal).wire_format(),
+        WireFormat::PublicMessage
+    );
+
+    // Commits must be PrivateMessages, but are sent as PublicMessages.
+    let wire_format_policy = PURE_CIPHERTEXT_WIRE_FORMAT_POLICY.with_commit_policy(
+        OutgoingWireFormatPolicy::AlwaysPlaintext,
+        IncomingWireFormatPolicy::AlwaysCiphertext,
+    );
+    let (mut alice_group, alice_credential_with_key_and_signer) =
+        create_group(ciphersuite, backend, wire_format_policy);
+    let message = receive_message(
+        ciphersuite,
+        backend,
+        &mut alice_group,
+        &alice_credential_with_key_and_signer.signer,
+    );
+    let err = alice_group
+        .process_message(backend, message)
+        .expect_err("An unexpected error occurred.");
+    assert_eq!(err, ProcessMessageError::IncompatibleWireFormat);
+}
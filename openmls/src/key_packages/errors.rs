@@ -4,7 +4,10 @@
 
 use thiserror::Error;
 
-use crate::{ciphersuite::signable::SignatureError, error::LibraryError};
+use crate::{
+    ciphersuite::signable::SignatureError, error::LibraryError,
+    treesync::errors::LeafNodeCreationError,
+};
 
 /// KeyPackage verify error
 #[derive(Error, Debug, PartialEq, Clone)]
@@ -56,3 +59,23 @@ pub enum KeyPackageNewError<KeyStoreError> {
     #[error(transparent)]
     SignatureError(#[from] SignatureError),
 }
+
+impl<KeyStoreError> From<LeafNodeCreationError> for KeyPackageNewError<KeyStoreError> {
+    fn from(e: LeafNodeCreationError) -> Self {
+        match e {
+            LeafNodeCreationError::LibraryError(e) => Self::LibraryError(e),
+            LeafNodeCreationError::SignatureError(e) => Self::SignatureError(e),
+        }
+    }
+}
+
+/// KeyPackage delete error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum KeyPackageDeleteError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// Accessing the key store failed.
+    #[error("Accessing the key store failed.")]
+    KeyStoreError(KeyStoreError),
+}
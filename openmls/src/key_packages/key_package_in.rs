@@ -5,9 +5,10 @@ use crate::{
     ciphersuite::{signable::*, *},
     credentials::*,
     extensions::Extensions,
-    treesync::node::leaf_node::{LeafNodeIn, VerifiableLeafNode},
+    treesync::node::leaf_node::{LeafNodeIn, SystemTimeProvider, TimeProvider, VerifiableLeafNode},
     versions::ProtocolVersion,
 };
+use core::time::Duration;
 use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite};
 use serde::{Deserialize, Serialize};
 use tls_codec::{Serialize as TlsSerializeTrait, TlsDeserialize, TlsSerialize, TlsSize};
@@ -72,6 +73,7 @@ mod private_mod {
 #[derive(
     Debug, Clone, PartialEq, TlsSize, TlsSerialize, TlsDeserialize, Serialize, Deserialize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 struct KeyPackageTbsIn {
     protocol_version: ProtocolVersion,
     ciphersuite: Ciphersuite,
@@ -84,6 +86,7 @@ struct KeyPackageTbsIn {
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct KeyPackageIn {
     payload: KeyPackageTbsIn,
     signature: Signature,
@@ -110,6 +113,36 @@ impl KeyPackageIn {
     pub fn validate(
         self,
         crypto: &impl OpenMlsCrypto,
+    ) -> Result<KeyPackage, KeyPackageVerifyError> {
+        self.validate_with_time(crypto, &SystemTimeProvider, Duration::ZERO)
+    }
+
+    /// Like [`KeyPackageIn::validate()`], but checks the lifetime against the
+    /// current time of the `time_provider` instead of the system clock. The
+    /// lifetime is extended by `skew_tolerance` into the past and the future
+    /// to accept key packages of clients with skewed clocks.
+    pub fn validate_with_time(
+        self,
+        crypto: &impl OpenMlsCrypto,
+        time_provider: &impl TimeProvider,
+        skew_tolerance: Duration,
+    ) -> Result<KeyPackage, KeyPackageVerifyError> {
+        let key_package = self.validate_without_time(crypto)?;
+        if let Some(life_time) = key_package.payload.leaf_node.life_time() {
+            if !life_time.is_valid_at(time_provider, skew_tolerance.as_secs()) {
+                return Err(KeyPackageVerifyError::InvalidLifetime);
+            }
+        }
+        Ok(key_package)
+    }
+
+    /// Verifies this key package like [`KeyPackageIn::validate()`], except
+    /// that the lifetime is not checked against the current time. This is
+    /// used when processing messages, since the time source of the group is
+    /// not known at that point. The lifetime is checked by the group instead.
+    pub(crate) fn validate_without_time(
+        self,
+        crypto: &impl OpenMlsCrypto,
     ) -> Result<KeyPackage, KeyPackageVerifyError> {
         // We first need to verify the LeafNode inside the KeyPackage
         let leaf_node = self.payload.leaf_node.clone().into_verifiable_leaf_node();
@@ -151,12 +184,8 @@ impl KeyPackageIn {
             }
         }
 
-        // Ensure validity of the life time extension in the leaf node.
-        if let Some(life_time) = key_package.payload.leaf_node.life_time() {
-            if !life_time.is_valid() {
-                return Err(KeyPackageVerifyError::InvalidLifetime);
-            }
-        } else {
+        // Ensure the life time extension is present in the leaf node.
+        if key_package.payload.leaf_node.life_time().is_none() {
             // This assumes that we only verify key packages with leaf nodes
             // that were created for the key package.
             return Err(KeyPackageVerifyError::MissingLifetime);
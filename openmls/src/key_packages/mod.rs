@@ -201,12 +201,36 @@ impl SignedStruct<KeyPackageTbs> for KeyPackage {
     }
 }
 
-const SIGNATURE_KEY_PACKAGE_LABEL: &str = "KeyPackageTBS";
+const SIGNATURE_KEY_PACKAGE_LABEL: &str = signature_labels::KEY_PACKAGE;
 
 impl MlsEntity for KeyPackage {
     const ID: MlsEntityId = MlsEntityId::KeyPackage;
 }
 
+/// A record that a [`KeyPackage`] was consumed by a
+/// [`Welcome`](crate::messages::Welcome). The record is kept in the key store
+/// after the key package itself was deleted, such that a second Welcome for
+/// the same key package or init key can be detected.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ConsumedKeyPackage;
+
+impl MlsEntity for ConsumedKeyPackage {
+    const ID: MlsEntityId = MlsEntityId::KeyPackage;
+}
+
+impl ConsumedKeyPackage {
+    /// Key store ID of the record for the key package with the given
+    /// reference.
+    fn key_package_id(key_package_ref: &KeyPackageRef) -> Vec<u8> {
+        [b"ConsumedKeyPackage".as_slice(), key_package_ref.as_slice()].concat()
+    }
+
+    /// Key store ID of the record for the given init key.
+    fn init_key_id(init_key: &HpkePublicKey) -> Vec<u8> {
+        [b"ConsumedInitKey".as_slice(), init_key.as_slice()].concat()
+    }
+}
+
 /// Helper struct containing the results of building a new [`KeyPackage`].
 pub(crate) struct KeyPackageCreationResult {
     pub key_package: KeyPackage,
@@ -313,13 +337,57 @@ impl KeyPackage {
     pub fn delete<KeyStore: OpenMlsKeyStore>(
         &self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
-    ) -> Result<(), KeyStore::Error> {
+    ) -> Result<(), KeyPackageDeleteError<KeyStore::Error>> {
+        let hash_ref = self.hash_ref(backend.crypto())?;
         backend
             .key_store()
-            .delete::<Self>(self.hash_ref(backend.crypto()).unwrap().as_slice())?;
+            .delete::<Self>(hash_ref.as_slice())
+            .map_err(KeyPackageDeleteError::KeyStoreError)?;
         backend
             .key_store()
             .delete::<HpkePrivateKey>(self.hpke_init_key().as_slice())
+            .map_err(KeyPackageDeleteError::KeyStoreError)
+    }
+
+    /// Records in the key store that this key package, which has the given
+    /// `key_package_ref`, and its init key were consumed.
+    ///
+    /// Returns an error if access to the key store fails.
+    pub(crate) fn mark_consumed<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        key_package_ref: &KeyPackageRef,
+    ) -> Result<(), KeyStore::Error> {
+        backend.key_store().store(
+            &ConsumedKeyPackage::key_package_id(key_package_ref),
+            &ConsumedKeyPackage,
+        )?;
+        backend.key_store().store(
+            &ConsumedKeyPackage::init_key_id(self.hpke_init_key()),
+            &ConsumedKeyPackage,
+        )
+    }
+
+    /// Returns `true` if the init key of this key package was already
+    /// consumed by a Welcome, possibly through another key package with the
+    /// same init key.
+    pub(crate) fn init_key_consumed(&self, backend: &impl OpenMlsCryptoProvider) -> bool {
+        backend
+            .key_store()
+            .read::<ConsumedKeyPackage>(&ConsumedKeyPackage::init_key_id(self.hpke_init_key()))
+            .is_some()
+    }
+
+    /// Returns `true` if the key package with the given `key_package_ref`
+    /// was already consumed by a Welcome.
+    pub(crate) fn is_consumed(
+        backend: &impl OpenMlsCryptoProvider,
+        key_package_ref: &KeyPackageRef,
+    ) -> bool {
+        backend
+            .key_store()
+            .read::<ConsumedKeyPackage>(&ConsumedKeyPackage::key_package_id(key_package_ref))
+            .is_some()
     }
 
     /// Get a reference to the extensions of this key package.
@@ -497,8 +565,8 @@ impl KeyPackage {
             protocol_version: config.version,
             ciphersuite: config.ciphersuite,
             init_key: init_key.into(),
-            leaf_node: self.leaf_node().clone(),
-            extensions: self.extensions().clone(),
+            leaf_node: self.payload.leaf_node,
+            extensions: self.payload.extensions,
         };
 
         key_package_tbs.sign(signer)
@@ -577,6 +645,7 @@ impl KeyPackageBuilder {
         self
     }
 
+    #[cfg(any(feature = "test-utils", test))]
     pub(crate) fn build_without_key_storage<KeyStore: OpenMlsKeyStore>(
         self,
         config: CryptoConfig,
@@ -590,7 +659,8 @@ impl KeyPackageBuilder {
             signer,
             credential_with_key,
             self.key_package_extensions.unwrap_or_default(),
-            self.leaf_node_capabilities.unwrap_or_default(),
+            self.leaf_node_capabilities
+                .unwrap_or_else(|| Capabilities::default_for(backend.crypto())),
             self.leaf_node_extensions.unwrap_or_default(),
         )
     }
@@ -613,7 +683,8 @@ impl KeyPackageBuilder {
             signer,
             credential_with_key,
             self.key_package_extensions.unwrap_or_default(),
-            self.leaf_node_capabilities.unwrap_or_default(),
+            self.leaf_node_capabilities
+                .unwrap_or_else(|| Capabilities::default_for(backend.crypto())),
             self.leaf_node_extensions.unwrap_or_default(),
         )?;
 
@@ -668,7 +739,7 @@ impl KeyPackageBundle {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(feature = "test-vectors", test))]
 impl KeyPackageBundle {
     pub(crate) fn new(
         backend: &impl OpenMlsCryptoProvider,
@@ -159,6 +159,9 @@ pub use rstest_reuse;
 #[macro_use]
 pub mod test_utils;
 
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+
 // === Modules ===
 
 #[macro_use]
@@ -10,7 +10,7 @@ use crate::{
     framing::{mls_auth_content::AuthenticatedContent, MlsMessageOut, PublicMessage},
     group::{
         errors::ProposeRemoveMemberError, mls_group::errors::ProposeAddMemberError, GroupEpoch,
-        GroupId,
+        GroupId, PublicGroup,
     },
     key_packages::KeyPackage,
     messages::{AddProposal, Proposal},
@@ -87,4 +87,45 @@ impl ExternalProposal {
         .map(MlsMessageOut::from)
         .map_err(ProposeRemoveMemberError::from)
     }
+
+    /// Creates an external Remove proposal for the member with the given
+    /// `identity`. For delivery services that know the identity of a client,
+    /// but not its leaf index.
+    ///
+    /// The identity is resolved against the roster of the given
+    /// [`PublicGroup`], which also determines the group id and epoch of the
+    /// proposal. Group members validate the resulting proposal against their
+    /// own roster when committing it.
+    ///
+    /// Returns [`ProposeRemoveMemberError::UnknownMember`] if no member has the
+    /// given identity and [`ProposeRemoveMemberError::AmbiguousIdentity`] if
+    /// more than one member has it, e.g., with different devices.
+    ///
+    /// # Arguments
+    /// * `identity` - identity of the client to remove, as in its credential
+    /// * `public_group` - public state of the group
+    /// * `signer` - of the sender to sign the message
+    /// * `sender` - index of the sender of the proposal (in the [crate::extensions::ExternalSendersExtension] array
+    /// from the Group Context)
+    pub fn new_remove_by_identity(
+        identity: &[u8],
+        public_group: &PublicGroup,
+        signer: &impl Signer,
+        sender_index: SenderExtensionIndex,
+    ) -> Result<MlsMessageOut, ProposeRemoveMemberError> {
+        let mut members = public_group.members_by_identity(identity);
+        let removed = match (members.next(), members.next()) {
+            (Some(member), None) => member.index,
+            (None, _) => return Err(ProposeRemoveMemberError::UnknownMember),
+            (Some(_), Some(_)) => return Err(ProposeRemoveMemberError::AmbiguousIdentity),
+        };
+
+        Self::new_remove(
+            removed,
+            public_group.group_id().clone(),
+            public_group.group_context().epoch(),
+            signer,
+            sender_index,
+        )
+    }
 }
@@ -7,15 +7,15 @@ use tls_codec::{Deserialize, Serialize, TlsDeserialize, TlsSerialize, TlsSize};
 use crate::{
     binary_tree::LeafNodeIndex,
     ciphersuite::{
-        signable::{Signable, SignedStruct, Verifiable, VerifiedStruct},
-        AeadKey, AeadNonce, Signature,
+        signable::{signature_labels, Signable, SignedStruct, Verifiable, VerifiedStruct},
+        AeadKey, AeadNonce, HpkePublicKey, Signature,
     },
     extensions::Extensions,
-    group::{GroupContext, GroupId},
+    group::{GroupContext, GroupEpoch, GroupId},
     messages::ConfirmationTag,
 };
 
-const SIGNATURE_GROUP_INFO_LABEL: &str = "GroupInfoTBS";
+const SIGNATURE_GROUP_INFO_LABEL: &str = signature_labels::GROUP_INFO;
 
 /// A type that represents a group info of which the signature has not been verified.
 /// It implements the [`Verifiable`] trait and can be turned into a group info by calling
@@ -24,6 +24,8 @@ const SIGNATURE_GROUP_INFO_LABEL: &str = "GroupInfoTBS";
 /// [`VerifiableGroupInfo`], which can then be turned into a group info as described above.
 #[derive(Debug, PartialEq, Clone, TlsDeserialize, TlsSize)]
 #[cfg_attr(any(test, feature = "test-utils"), derive(TlsSerialize))]
+#[cfg_attr(feature = "serde-types", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct VerifiableGroupInfo {
     payload: GroupInfoTBS,
     signature: Signature,
@@ -81,11 +83,50 @@ impl VerifiableGroupInfo {
 
     /// Get (unverified) extensions of the verifiable group info.
     ///
-    /// Note: This method should only be used when necessary to verify the group info signature.
-    pub(crate) fn extensions(&self) -> &Extensions {
+    /// Note: The extensions are not authenticated before the group info
+    /// signature is verified. They should only be used to decide whether to
+    /// attempt an operation, e.g., an External Commit.
+    pub fn extensions(&self) -> &Extensions {
         &self.payload.extensions
     }
 
+    /// Get the (unverified) external public key of the verifiable group info.
+    ///
+    /// Returns `None` if the group info doesn't contain an external_pub
+    /// extension, i.e., if it can't be used for an External Commit.
+    ///
+    /// Note: The key is not authenticated before the group info signature is
+    /// verified.
+    pub fn external_pub(&self) -> Option<&HpkePublicKey> {
+        self.payload
+            .extensions
+            .external_pub()
+            .map(|extension| extension.external_pub())
+    }
+
+    /// Returns `true` if the (unverified) verifiable group info contains a
+    /// ratchet tree extension.
+    pub fn has_ratchet_tree_extension(&self) -> bool {
+        self.payload.extensions.ratchet_tree().is_some()
+    }
+
+    /// Returns `true` if the (unverified) verifiable group info can be used
+    /// for an External Commit, i.e., if it contains an external_pub extension.
+    ///
+    /// Note that joining by External Commit additionally requires the ratchet
+    /// tree, either from a ratchet tree extension or out of band.
+    pub fn supports_external_commit(&self) -> bool {
+        self.external_pub().is_some()
+    }
+
+    /// Get (unverified) group context extensions of the verifiable group info.
+    ///
+    /// Note: This method should only be used when necessary to verify the group
+    /// info signature.
+    pub(crate) fn group_context_extensions(&self) -> &Extensions {
+        self.payload.group_context.extensions()
+    }
+
     /// Get (unverified) group ID of the verifiable group info.
     ///
     /// Note: This method should only be used when necessary to verify the group
@@ -93,6 +134,30 @@ impl VerifiableGroupInfo {
     pub(crate) fn group_id(&self) -> &GroupId {
         self.payload.group_context.group_id()
     }
+
+    /// Get (unverified) epoch of the verifiable group info.
+    ///
+    /// Note: This method should only be used when necessary to verify the group
+    /// info signature.
+    pub(crate) fn epoch(&self) -> GroupEpoch {
+        self.payload.group_context.epoch()
+    }
+
+    /// Get (unverified) group context of the verifiable group info.
+    ///
+    /// Note: This method should only be used when necessary to verify the group
+    /// info signature.
+    pub(crate) fn group_context(&self) -> &GroupContext {
+        &self.payload.group_context
+    }
+
+    /// Get (unverified) confirmation tag of the verifiable group info.
+    ///
+    /// Note: This method should only be used when necessary to verify the group
+    /// info signature.
+    pub(crate) fn confirmation_tag(&self) -> &ConfirmationTag {
+        &self.payload.confirmation_tag
+    }
 }
 
 #[cfg(test)]
@@ -122,7 +187,7 @@ impl From<VerifiableGroupInfo> for GroupInfo {
 /// Note: The struct is split into a `GroupInfoTBS` payload and a signature.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 ///
 /// struct {
 ///     GroupContext group_context;
@@ -135,6 +200,7 @@ impl From<VerifiableGroupInfo> for GroupInfo {
 /// ```
 #[derive(Debug, PartialEq, Clone, TlsSerialize, TlsSize)]
 #[cfg_attr(feature = "test-utils", derive(TlsDeserialize))]
+#[cfg_attr(feature = "serde-types", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupInfo {
     payload: GroupInfoTBS,
     signature: Signature,
@@ -147,10 +213,24 @@ impl GroupInfo {
     }
 
     /// Returns the extensions.
-    pub(crate) fn extensions(&self) -> &Extensions {
+    pub fn extensions(&self) -> &Extensions {
         &self.payload.extensions
     }
 
+    /// Returns the external public key, or `None` if the group info doesn't
+    /// contain an external_pub extension.
+    pub fn external_pub(&self) -> Option<&HpkePublicKey> {
+        self.payload
+            .extensions
+            .external_pub()
+            .map(|extension| extension.external_pub())
+    }
+
+    /// Returns `true` if the group info contains a ratchet tree extension.
+    pub fn has_ratchet_tree_extension(&self) -> bool {
+        self.payload.extensions.ratchet_tree().is_some()
+    }
+
     /// Returns the confirmation tag.
     pub(crate) fn confirmation_tag(&self) -> &ConfirmationTag {
         &self.payload.confirmation_tag
@@ -179,7 +259,7 @@ impl From<GroupInfo> for GroupContext {
 /// GroupInfo (To Be Signed)
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 ///
 /// struct {
 ///     GroupContext group_context;
@@ -189,6 +269,8 @@ impl From<GroupInfo> for GroupContext {
 /// } GroupInfoTBS;
 /// ```
 #[derive(Debug, PartialEq, Clone, TlsDeserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "serde-types", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct GroupInfoTBS {
     group_context: GroupContext,
     extensions: Extensions,
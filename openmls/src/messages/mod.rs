@@ -8,6 +8,7 @@ use openmls_traits::{
     types::{Ciphersuite, HpkeCiphertext},
     OpenMlsCryptoProvider,
 };
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, *};
@@ -35,6 +36,7 @@ pub mod external_proposals;
 pub mod group_info;
 pub mod proposals;
 pub mod proposals_in;
+pub mod targeted_message;
 
 #[cfg(test)]
 mod tests;
@@ -48,7 +50,7 @@ use self::{proposals::*, proposals_in::ProposalOrRefIn};
 /// [`MlsGroup::new_from_welcome()`](crate::group::mls_group::MlsGroup::new_from_welcome()).
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///   CipherSuite cipher_suite;
 ///   EncryptedGroupSecrets secrets<V>;
@@ -56,6 +58,8 @@ use self::{proposals::*, proposals_in::ProposalOrRefIn};
 /// } Welcome;
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq, TlsDeserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "serde-types", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Welcome {
     cipher_suite: Ciphersuite,
     secrets: Vec<EncryptedGroupSecrets>,
@@ -87,6 +91,45 @@ impl Welcome {
         self.secrets.as_slice()
     }
 
+    /// Returns an iterator over the [`KeyPackageRef`]s of the key packages
+    /// this Welcome message is addressed to.
+    pub fn recipients(&self) -> impl Iterator<Item = &KeyPackageRef> {
+        self.secrets.iter().map(|secret| &secret.new_member)
+    }
+
+    /// Returns `true` if this Welcome message contains encrypted group secrets
+    /// for the key package with the given [`KeyPackageRef`].
+    ///
+    /// This is a cheap check that can be used to skip Welcome messages that
+    /// are not addressed to any of the own key packages.
+    pub fn is_addressed_to(&self, key_package_ref: &KeyPackageRef) -> bool {
+        self.recipients()
+            .any(|recipient| recipient == key_package_ref)
+    }
+
+    /// Returns a copy of this Welcome message that only contains the
+    /// encrypted group secrets for the key package with the given
+    /// [`KeyPackageRef`], or `None` if the message is not addressed to it.
+    ///
+    /// This allows a delivery service to only forward the relevant part of a
+    /// Welcome message to each of the new members.
+    pub fn for_recipient(&self, key_package_ref: &KeyPackageRef) -> Option<Welcome> {
+        let secrets: Vec<EncryptedGroupSecrets> = self
+            .secrets
+            .iter()
+            .filter(|secret| &secret.new_member == key_package_ref)
+            .cloned()
+            .collect();
+        if secrets.is_empty() {
+            return None;
+        }
+        Some(Self {
+            cipher_suite: self.cipher_suite,
+            secrets,
+            encrypted_group_info: self.encrypted_group_info.clone(),
+        })
+    }
+
     /// Returns a reference to the encrypted group info.
     pub(crate) fn encrypted_group_info(&self) -> &[u8] {
         self.encrypted_group_info.as_slice()
@@ -103,6 +146,8 @@ impl Welcome {
 ///
 /// This is part of a [`Welcome`] message. It can be used to correlate the correct secrets with each new member.
 #[derive(Clone, Debug, Eq, PartialEq, TlsDeserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "serde-types", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct EncryptedGroupSecrets {
     /// Key package reference of the new member
     new_member: KeyPackageRef,
@@ -141,7 +186,7 @@ impl EncryptedGroupSecrets {
 /// key schedule.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 ///
 /// struct {
 ///     ProposalOrRef proposals<V>;
@@ -170,6 +215,7 @@ impl Commit {
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct CommitIn {
     proposals: Vec<ProposalOrRefIn>,
     path: Option<UpdatePathIn>,
@@ -191,12 +237,14 @@ impl CommitIn {
     pub(crate) fn validate(
         self,
         ciphersuite: Ciphersuite,
-        crypto: &impl OpenMlsCrypto,
+        crypto: &(impl OpenMlsCrypto + Sync),
         sender_context: SenderContext,
     ) -> Result<Commit, ValidationError> {
+        // Inline proposals are validated in parallel, such that the key
+        // package signatures of large adds are verified concurrently.
         let proposals = self
             .proposals
-            .into_iter()
+            .into_par_iter()
             .map(|p| p.validate(crypto, ciphersuite))
             .collect::<Result<Vec<_>, _>>()?;
 
@@ -265,6 +313,7 @@ impl From<Commit> for CommitIn {
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ConfirmationTag(pub(crate) Mac);
 
 /// PathSecret
@@ -378,7 +427,7 @@ pub(crate) enum PathSecretError {
 /// GroupSecrets
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///   opaque joiner_secret<V>;
 ///   optional<PathSecret> path_secret;
@@ -31,45 +31,46 @@ use crate::{
 ///
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-20
+/// // RFC 9420
 /// // See IANA registry for registered values
 /// uint16 ProposalType;
 /// ```
 ///
 /// | Value           | Name                     | R | Ext | Path | Ref      |
 /// |-----------------|--------------------------|---|-----|------|----------|
-/// | 0x0000          | RESERVED                 | - | -   | -    | RFC XXXX |
-/// | 0x0001          | add                      | Y | Y   | N    | RFC XXXX |
-/// | 0x0002          | update                   | Y | N   | Y    | RFC XXXX |
-/// | 0x0003          | remove                   | Y | Y   | Y    | RFC XXXX |
-/// | 0x0004          | psk                      | Y | Y   | N    | RFC XXXX |
-/// | 0x0005          | reinit                   | Y | Y   | N    | RFC XXXX |
-/// | 0x0006          | external_init            | Y | N   | Y    | RFC XXXX |
-/// | 0x0007          | group_context_extensions | Y | Y   | Y    | RFC XXXX |
-/// | 0x0A0A          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0x1A1A          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0x2A2A          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0x3A3A          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0x4A4A          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0x5A5A          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0x6A6A          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0x7A7A          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0x8A8A          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0x9A9A          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0xAAAA          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0xBABA          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0xCACA          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0xDADA          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0xEAEA          | GREASE                   | Y | -   | -    | RFC XXXX |
-/// | 0xF000 - 0xFFFF | Reserved for Private Use | - | -   | -    | RFC XXXX |
+/// | 0x0000          | RESERVED                 | - | -   | -    | RFC 9420 |
+/// | 0x0001          | add                      | Y | Y   | N    | RFC 9420 |
+/// | 0x0002          | update                   | Y | N   | Y    | RFC 9420 |
+/// | 0x0003          | remove                   | Y | Y   | Y    | RFC 9420 |
+/// | 0x0004          | psk                      | Y | Y   | N    | RFC 9420 |
+/// | 0x0005          | reinit                   | Y | Y   | N    | RFC 9420 |
+/// | 0x0006          | external_init            | Y | N   | Y    | RFC 9420 |
+/// | 0x0007          | group_context_extensions | Y | Y   | Y    | RFC 9420 |
+/// | 0x0A0A          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0x1A1A          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0x2A2A          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0x3A3A          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0x4A4A          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0x5A5A          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0x6A6A          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0x7A7A          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0x8A8A          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0x9A9A          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0xAAAA          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0xBABA          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0xCACA          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0xDADA          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0xEAEA          | GREASE                   | Y | -   | -    | RFC 9420 |
+/// | 0xF000 - 0xFFFF | Reserved for Private Use | - | -   | -    | RFC 9420 |
 ///
 /// # Extensions
 ///
-/// | Value  | Name    | Recommended | Path Required | Reference | Notes                        |
-/// |:=======|:========|:============|:==============|:==========|:=============================|
-/// | 0x0008 | app_ack | Y           | Y             | RFC XXXX  | draft-ietf-mls-extensions-00 |
+/// | Value  | Name    | Recommended | Path Required | Reference                    |
+/// |:=======|:========|:============|:==============|:=============================|
+/// | 0x0008 | app_ack | Y           | Y             | draft-ietf-mls-extensions-00 |
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Serialize, Deserialize)]
 #[allow(missing_docs)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ProposalType {
     Add,
     Update,
@@ -124,6 +125,24 @@ impl ProposalType {
         )
     }
 
+    /// Check whether a proposal type is a default proposal type as defined in
+    /// Section 7.2 of RFC 9420. Default proposal types are supported by all
+    /// clients and don't need to be listed in the
+    /// [`Capabilities`](crate::treesync::node::leaf_node::Capabilities) of a
+    /// leaf node.
+    pub fn is_default(&self) -> bool {
+        matches!(
+            self,
+            ProposalType::Add
+                | ProposalType::Update
+                | ProposalType::Remove
+                | ProposalType::PreSharedKey
+                | ProposalType::Reinit
+                | ProposalType::ExternalInit
+                | ProposalType::GroupContextExtensions
+        )
+    }
+
     /// Returns `true` if the proposal type requires a path and `false`
     pub fn is_path_required(&self) -> bool {
         matches!(
@@ -170,7 +189,7 @@ impl From<ProposalType> for u16 {
 /// This `enum` contains the different proposals in its variants.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     ProposalType msg_type;
 ///     select (Proposal.msg_type) {
@@ -204,7 +223,7 @@ pub enum Proposal {
     #[tls_codec(discriminant = 7)]
     GroupContextExtensions(GroupContextExtensionProposal),
     // # Extensions
-    // TODO(#916): `AppAck` is not in draft-ietf-mls-protocol-17 but
+    // TODO(#916): `AppAck` is not in RFC 9420 but
     //             was moved to `draft-ietf-mls-extensions-00`.
     #[tls_codec(discriminant = 8)]
     AppAck(AppAckProposal),
@@ -240,7 +259,7 @@ impl Proposal {
 /// An Add proposal requests that a client with a specified [`KeyPackage`] be added to the group.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     KeyPackage key_package;
 /// } Add;
@@ -263,7 +282,7 @@ impl AddProposal {
 /// replaces the sender's [`LeafNode`] in the tree instead of adding a new leaf to the tree.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     LeafNode leaf_node;
 /// } Update;
@@ -285,7 +304,7 @@ impl UpdateProposal {
 /// A Remove proposal requests that the member with the leaf index removed be removed from the group.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     uint32 removed;
 /// } Remove;
@@ -293,6 +312,7 @@ impl UpdateProposal {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RemoveProposal {
     pub(crate) removed: LeafNodeIndex,
 }
@@ -310,7 +330,7 @@ impl RemoveProposal {
 /// schedule in the process of advancing the epoch.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     PreSharedKeyID psk;
 /// } PreSharedKey;
@@ -318,6 +338,7 @@ impl RemoveProposal {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PreSharedKeyProposal {
     psk: PreSharedKeyId,
 }
@@ -343,7 +364,7 @@ impl PreSharedKeyProposal {
 /// done by creating a completely new group and shutting down the old one.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     opaque group_id<V>;
 ///     ProtocolVersion version;
@@ -354,6 +375,7 @@ impl PreSharedKeyProposal {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ReInitProposal {
     pub(crate) group_id: GroupId,
     pub(crate) version: ProtocolVersion,
@@ -367,7 +389,7 @@ pub struct ReInitProposal {
 /// commit. This proposal can only be used in that context.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///   opaque kem_output<V>;
 /// } ExternalInit;
@@ -375,6 +397,7 @@ pub struct ReInitProposal {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ExternalInitProposal {
     kem_output: VLBytes,
 }
@@ -402,6 +425,7 @@ impl From<Vec<u8>> for ExternalInitProposal {
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct AppAckProposal {
     received_ranges: Vec<MessageRange>,
 }
@@ -412,7 +436,7 @@ pub struct AppAckProposal {
 /// for the group.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///   Extension extensions<V>;
 /// } GroupContextExtensions;
@@ -420,16 +444,21 @@ pub struct AppAckProposal {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GroupContextExtensionProposal {
     extensions: Extensions,
 }
 
 impl GroupContextExtensionProposal {
     /// Create a new [`GroupContextExtensionProposal`].
-    #[cfg(test)]
     pub(crate) fn new(extensions: Extensions) -> Self {
         Self { extensions }
     }
+
+    /// Returns the extensions of the proposed group context.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
 }
 
 // Crate-only types
@@ -540,6 +569,7 @@ impl ProposalRef {
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct MessageRange {
     sender: KeyPackageRef,
     first_generation: u32,
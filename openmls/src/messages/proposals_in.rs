@@ -29,7 +29,7 @@ use super::proposals::{
 /// This `enum` contains the different proposals in its variants.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     ProposalType msg_type;
 ///     select (Proposal.msg_type) {
@@ -49,6 +49,7 @@ use super::proposals::{
 )]
 #[allow(missing_docs)]
 #[repr(u16)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ProposalIn {
     #[tls_codec(discriminant = 1)]
     Add(AddProposalIn),
@@ -65,7 +66,7 @@ pub enum ProposalIn {
     #[tls_codec(discriminant = 7)]
     GroupContextExtensions(GroupContextExtensionProposal),
     // # Extensions
-    // TODO(#916): `AppAck` is not in draft-ietf-mls-protocol-17 but
+    // TODO(#916): `AppAck` is not in RFC 9420 but
     //             was moved to `draft-ietf-mls-extensions-00`.
     #[tls_codec(discriminant = 8)]
     AppAck(AppAckProposal),
@@ -122,7 +123,7 @@ impl ProposalIn {
 /// An Add proposal requests that a client with a specified [`KeyPackage`] be added to the group.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     KeyPackage key_package;
 /// } Add;
@@ -130,6 +131,7 @@ impl ProposalIn {
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct AddProposalIn {
     key_package: KeyPackageIn,
 }
@@ -139,12 +141,13 @@ impl AddProposalIn {
         self.key_package.unverified_credential()
     }
 
-    /// Returns a [`AddProposal`] after successful validation.
+    /// Returns a [`AddProposal`] after successful validation. The lifetime of
+    /// the key package is checked by the group that processes the proposal.
     pub(crate) fn validate(
         self,
         crypto: &impl OpenMlsCrypto,
     ) -> Result<AddProposal, ValidationError> {
-        let key_package = self.key_package.validate(crypto)?;
+        let key_package = self.key_package.validate_without_time(crypto)?;
         Ok(AddProposal { key_package })
     }
 }
@@ -155,7 +158,7 @@ impl AddProposalIn {
 /// replaces the sender's leaf node instead of adding a new leaf to the tree.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     LeafNode leaf_node;
 /// } Update;
@@ -163,6 +166,7 @@ impl AddProposalIn {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct UpdateProposalIn {
     leaf_node: LeafNodeIn,
 }
@@ -209,6 +213,7 @@ impl UpdateProposalIn {
 #[repr(u8)]
 #[allow(missing_docs)]
 #[allow(clippy::large_enum_variant)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) enum ProposalOrRefIn {
     #[tls_codec(discriminant = 1)]
     Proposal(ProposalIn),
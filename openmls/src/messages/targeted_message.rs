@@ -0,0 +1,304 @@
+//! # Targeted messages
+//!
+//! A targeted message allows a member of a group to send a message to exactly
+//! one other member of the group. The message is encrypted to the HPKE
+//! encryption key of the recipient's leaf and signed with the sender's leaf
+//! signature key. The signature also covers the current [`GroupContext`], such
+//! that the recipient knows the message was sent by a member of the group in
+//! the current epoch.
+//!
+//! Targeted messages are created with
+//! [`MlsGroup::create_targeted_message()`](crate::group::MlsGroup::create_targeted_message())
+//! and processed with
+//! [`MlsGroup::process_targeted_message()`](crate::group::MlsGroup::process_targeted_message()).
+
+use openmls_traits::{signatures::Signer, types::HpkeCiphertext, OpenMlsCryptoProvider};
+use tls_codec::{Deserialize, Serialize, TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    ciphersuite::{
+        hpke,
+        signable::{signature_labels, Signable, SignedStruct, Verifiable},
+        OpenMlsSignaturePublicKey, Signature,
+    },
+    credentials::Credential,
+    error::LibraryError,
+    group::{
+        errors::{CreateTargetedMessageError, ProcessTargetedMessageError},
+        GroupContext, GroupEpoch, GroupId,
+    },
+    treesync::node::encryption_keys::{EncryptionKey, EncryptionPrivateKey},
+};
+
+const SIGNATURE_TARGETED_MESSAGE_LABEL: &str = signature_labels::TARGETED_MESSAGE;
+const TARGETED_MESSAGE_ENCRYPTION_LABEL: &str = "TargetedMessageData";
+
+/// Targeted message
+///
+/// A message that is encrypted to a single member of a group.
+///
+/// ```c
+/// // draft-ietf-mls-extensions-01 (simplified)
+/// struct {
+///     opaque group_id<V>;
+///     uint64 epoch;
+///     uint32 recipient_leaf_index;
+///     opaque authenticated_data<V>;
+///     HPKECiphertext hpke_ciphertext;
+/// } TargetedMessage;
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, TlsSerialize, TlsDeserialize, TlsSize)]
+pub struct TargetedMessage {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    recipient_leaf_index: LeafNodeIndex,
+    authenticated_data: VLBytes,
+    hpke_ciphertext: HpkeCiphertext,
+}
+
+/// The encrypted content of a [`TargetedMessage`].
+///
+/// ```c
+/// // draft-ietf-mls-extensions-01 (simplified)
+/// struct {
+///     uint32 sender_leaf_index;
+///     opaque payload<V>;
+///     /* SignWithLabel(., "TargetedMessageTBS", TargetedMessageTBS) */
+///     opaque signature<V>;
+/// } TargetedMessageContent;
+/// ```
+#[derive(Debug, TlsSerialize, TlsDeserialize, TlsSize)]
+struct TargetedMessageContent {
+    sender_leaf_index: LeafNodeIndex,
+    payload: VLBytes,
+    signature: Signature,
+}
+
+/// Targeted message (To Be Signed)
+///
+/// ```c
+/// // draft-ietf-mls-extensions-01 (simplified)
+/// struct {
+///     opaque group_id<V>;
+///     uint64 epoch;
+///     uint32 recipient_leaf_index;
+///     opaque authenticated_data<V>;
+///     uint32 sender_leaf_index;
+///     opaque payload<V>;
+///     GroupContext group_context;
+/// } TargetedMessageTBS;
+/// ```
+#[derive(Debug, TlsSerialize, TlsSize)]
+struct TargetedMessageTbs {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    recipient_leaf_index: LeafNodeIndex,
+    authenticated_data: VLBytes,
+    sender_leaf_index: LeafNodeIndex,
+    payload: VLBytes,
+    group_context: GroupContext,
+}
+
+/// A decrypted [`TargetedMessageTbs`] and its signature that has not been
+/// verified yet.
+struct VerifiableTargetedMessageContent {
+    tbs: TargetedMessageTbs,
+    signature: Signature,
+}
+
+impl TargetedMessage {
+    /// Sign and encrypt the `payload` to the `recipient_encryption_key` of the
+    /// leaf at `recipient_leaf_index`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        backend: &impl OpenMlsCryptoProvider,
+        signer: &impl Signer,
+        group_context: &GroupContext,
+        sender_leaf_index: LeafNodeIndex,
+        recipient_leaf_index: LeafNodeIndex,
+        recipient_encryption_key: &EncryptionKey,
+        authenticated_data: &[u8],
+        payload: &[u8],
+    ) -> Result<Self, CreateTargetedMessageError> {
+        let tbs = TargetedMessageTbs {
+            group_id: group_context.group_id().clone(),
+            epoch: group_context.epoch(),
+            recipient_leaf_index,
+            authenticated_data: authenticated_data.into(),
+            sender_leaf_index,
+            payload: payload.into(),
+            group_context: group_context.clone(),
+        };
+        let content: TargetedMessageContent = tbs.sign(signer)?;
+        let plaintext = content
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        let context = group_context
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        let hpke_ciphertext = hpke::encrypt_with_label(
+            recipient_encryption_key.as_slice(),
+            TARGETED_MESSAGE_ENCRYPTION_LABEL,
+            &context,
+            &plaintext,
+            group_context.ciphersuite(),
+            backend.crypto(),
+        )
+        .map_err(|_| LibraryError::custom("Encryption failed. A serialization issue really"))?;
+
+        Ok(Self {
+            group_id: group_context.group_id().clone(),
+            epoch: group_context.epoch(),
+            recipient_leaf_index,
+            authenticated_data: authenticated_data.into(),
+            hpke_ciphertext,
+        })
+    }
+
+    /// Returns the group ID of the group the message was sent in.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Returns the epoch in which the message was sent.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the leaf index of the recipient of the message.
+    pub fn recipient_leaf_index(&self) -> LeafNodeIndex {
+        self.recipient_leaf_index
+    }
+
+    /// Returns the authenticated data of the message.
+    pub fn authenticated_data(&self) -> &[u8] {
+        self.authenticated_data.as_slice()
+    }
+
+    /// Decrypt the message with the recipient's `private_key` and verify the
+    /// sender's signature. The signature key of the sender is looked up via
+    /// `sender_lookup`, which returns `None` if the sender is not a member of
+    /// the group.
+    pub(crate) fn decrypt_and_verify(
+        self,
+        backend: &impl OpenMlsCryptoProvider,
+        group_context: &GroupContext,
+        private_key: &EncryptionPrivateKey,
+        sender_lookup: impl FnOnce(LeafNodeIndex) -> Option<(Credential, OpenMlsSignaturePublicKey)>,
+    ) -> Result<ProcessedTargetedMessage, ProcessTargetedMessageError> {
+        let context = group_context
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        let plaintext = private_key
+            .decrypt_with_label(
+                backend,
+                group_context.ciphersuite(),
+                TARGETED_MESSAGE_ENCRYPTION_LABEL,
+                &context,
+                &self.hpke_ciphertext,
+            )
+            .map_err(|_| ProcessTargetedMessageError::DecryptionFailed)?;
+        let content = TargetedMessageContent::tls_deserialize(&mut plaintext.as_slice())
+            .map_err(|_| ProcessTargetedMessageError::MalformedContent)?;
+
+        let (credential, signature_key) = sender_lookup(content.sender_leaf_index)
+            .ok_or(ProcessTargetedMessageError::UnknownSender)?;
+
+        let verifiable = VerifiableTargetedMessageContent {
+            tbs: TargetedMessageTbs {
+                group_id: self.group_id,
+                epoch: self.epoch,
+                recipient_leaf_index: self.recipient_leaf_index,
+                authenticated_data: self.authenticated_data,
+                sender_leaf_index: content.sender_leaf_index,
+                payload: content.payload,
+                group_context: group_context.clone(),
+            },
+            signature: content.signature,
+        };
+        verifiable
+            .verify_no_out(backend.crypto(), &signature_key)
+            .map_err(|_| ProcessTargetedMessageError::InvalidSignature)?;
+
+        Ok(ProcessedTargetedMessage {
+            sender_leaf_index: verifiable.tbs.sender_leaf_index,
+            credential,
+            authenticated_data: verifiable.tbs.authenticated_data.into(),
+            payload: verifiable.tbs.payload.into(),
+        })
+    }
+}
+
+/// A [`TargetedMessage`] that was successfully decrypted and whose signature
+/// was verified.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ProcessedTargetedMessage {
+    sender_leaf_index: LeafNodeIndex,
+    credential: Credential,
+    authenticated_data: Vec<u8>,
+    payload: Vec<u8>,
+}
+
+impl ProcessedTargetedMessage {
+    /// Returns the leaf index of the sender of the message.
+    pub fn sender_leaf_index(&self) -> LeafNodeIndex {
+        self.sender_leaf_index
+    }
+
+    /// Returns the credential of the sender of the message.
+    pub fn credential(&self) -> &Credential {
+        &self.credential
+    }
+
+    /// Returns the authenticated data of the message.
+    pub fn authenticated_data(&self) -> &[u8] {
+        &self.authenticated_data
+    }
+
+    /// Returns the payload of the message.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Consumes the message and returns the payload.
+    pub fn into_payload(self) -> Vec<u8> {
+        self.payload
+    }
+}
+
+impl Signable for TargetedMessageTbs {
+    type SignedOutput = TargetedMessageContent;
+
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.tls_serialize_detached()
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_TARGETED_MESSAGE_LABEL
+    }
+}
+
+impl SignedStruct<TargetedMessageTbs> for TargetedMessageContent {
+    fn from_payload(payload: TargetedMessageTbs, signature: Signature) -> Self {
+        Self {
+            sender_leaf_index: payload.sender_leaf_index,
+            payload: payload.payload,
+            signature,
+        }
+    }
+}
+
+impl Verifiable for VerifiableTargetedMessageContent {
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.tbs.tls_serialize_detached()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_TARGETED_MESSAGE_LABEL
+    }
+}
@@ -1,7 +1,10 @@
 use openmls_basic_credential::SignatureKeyPair;
 use openmls_rust_crypto::OpenMlsRustCrypto;
 use openmls_traits::{
-    crypto::OpenMlsCrypto, key_store::OpenMlsKeyStore, types::Ciphersuite, OpenMlsCryptoProvider,
+    crypto::OpenMlsCrypto,
+    key_store::OpenMlsKeyStore,
+    types::{Ciphersuite, HpkeCiphertext},
+    OpenMlsCryptoProvider,
 };
 use rstest::*;
 use rstest_reuse::{self, *};
@@ -295,6 +298,45 @@ fn test_welcome_message(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
     );
 }
 
+#[apply(ciphersuites)]
+fn welcome_recipients(ciphersuite: Ciphersuite) {
+    let alice = KeyPackageRef::from_slice(&[1u8; 16]);
+    let bob = KeyPackageRef::from_slice(&[2u8; 16]);
+    let charlie = KeyPackageRef::from_slice(&[3u8; 16]);
+    let encrypted_group_secrets = |new_member: &KeyPackageRef| EncryptedGroupSecrets {
+        new_member: new_member.clone(),
+        encrypted_group_secrets: HpkeCiphertext {
+            kem_output: vec![1, 2, 3].into(),
+            ciphertext: new_member.as_slice().to_vec().into(),
+        },
+    };
+    let welcome = Welcome::new(
+        ciphersuite,
+        vec![
+            encrypted_group_secrets(&alice),
+            encrypted_group_secrets(&bob),
+        ],
+        vec![4, 5, 6],
+    );
+
+    assert_eq!(welcome.recipients().collect::<Vec<_>>(), vec![&alice, &bob]);
+    assert!(welcome.is_addressed_to(&alice));
+    assert!(welcome.is_addressed_to(&bob));
+    assert!(!welcome.is_addressed_to(&charlie));
+
+    // Split the welcome for Bob.
+    let bob_welcome = welcome
+        .for_recipient(&bob)
+        .expect("Bob is a recipient of the Welcome.");
+    assert_eq!(bob_welcome.secrets(), &[encrypted_group_secrets(&bob)]);
+    assert_eq!(bob_welcome.ciphersuite(), ciphersuite);
+    assert_eq!(
+        bob_welcome.encrypted_group_info(),
+        welcome.encrypted_group_info()
+    );
+    assert!(welcome.for_recipient(&charlie).is_none());
+}
+
 #[test]
 fn invalid_welcomes() {
     // An almost good welcome message.
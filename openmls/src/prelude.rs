@@ -4,16 +4,18 @@
 // MlsGroup
 pub use crate::group::{config::CryptoConfig, core_group::Member, errors::*, ser::*, *};
 
-pub use crate::group::public_group::{errors::*, process::*, *};
+pub use crate::group::public_group::{errors::*, membership::*, process::*, snapshot::*, *};
 
 // Ciphersuite
 pub use crate::ciphersuite::{hash_ref::KeyPackageRef, signable::*, signature::*, *};
 
 // Messages
-pub use crate::messages::{external_proposals::*, proposals::*, proposals_in::*, *};
+pub use crate::messages::{
+    external_proposals::*, proposals::*, proposals_in::*, targeted_message::*, *,
+};
 
 // Credentials
-pub use crate::credentials::{errors::*, *};
+pub use crate::credentials::{basic_identity::*, errors::*, verifiable_credential::*, *};
 
 // MLS Versions
 pub use crate::versions::*;
@@ -36,10 +38,10 @@ pub use crate::binary_tree::LeafNodeIndex;
 // TreeSync
 pub use crate::treesync::{
     errors::{ApplyUpdatePathError, PublicTreeError},
-    node::leaf_node::{Capabilities, LeafNode},
+    node::leaf_node::{Capabilities, LeafNode, Lifetime, SystemTimeProvider, TimeProvider},
     node::parent_node::ParentNode,
     node::Node,
-    RatchetTreeIn,
+    RatchetTreeDelta, RatchetTreeIn, RatchetTreeProvider, RatchetTreeView, TreeNodeView,
 };
 
 // PSKs
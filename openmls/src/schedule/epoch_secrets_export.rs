@@ -0,0 +1,119 @@
+//! # ☣️ Export of epoch secrets
+//!
+//! This module is only available with the `epoch-secrets-export` feature. It
+//! allows exporting all secrets of the current epoch of a group, e.g., to
+//! cross-check the key schedule in test harnesses, to generate known-answer
+//! tests or for formal verification tooling.
+//!
+//! **Never enable this feature in production.** Exporting the secrets of an
+//! epoch allows decrypting all messages of that epoch. In addition, the
+//! encryption secret is kept in the group state for as long as the epoch is
+//! current, which breaks forward secrecy.
+
+use super::{message_secrets::MessageSecrets, GroupEpochSecrets};
+use crate::group::GroupEpoch;
+
+/// All secrets of an epoch.
+///
+/// See the [module documentation](self) for more details.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExportedEpochSecrets {
+    epoch: GroupEpoch,
+    init_secret: Vec<u8>,
+    sender_data_secret: Vec<u8>,
+    encryption_secret: Option<Vec<u8>>,
+    exporter_secret: Vec<u8>,
+    epoch_authenticator: Vec<u8>,
+    external_secret: Vec<u8>,
+    confirmation_key: Vec<u8>,
+    membership_key: Vec<u8>,
+    resumption_psk: Vec<u8>,
+}
+
+impl std::fmt::Debug for ExportedEpochSecrets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExportedEpochSecrets")
+            .field("epoch", &self.epoch)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExportedEpochSecrets {
+    pub(crate) fn new(
+        epoch: GroupEpoch,
+        group_epoch_secrets: &GroupEpochSecrets,
+        message_secrets: &MessageSecrets,
+    ) -> Self {
+        Self {
+            epoch,
+            init_secret: group_epoch_secrets.init_secret().as_slice().to_vec(),
+            sender_data_secret: message_secrets.sender_data_secret().as_slice().to_vec(),
+            encryption_secret: group_epoch_secrets
+                .encryption_secret()
+                .map(|secret| secret.as_slice().to_vec()),
+            exporter_secret: group_epoch_secrets.exporter_secret().as_slice().to_vec(),
+            epoch_authenticator: group_epoch_secrets
+                .epoch_authenticator()
+                .as_slice()
+                .to_vec(),
+            external_secret: group_epoch_secrets.external_secret().as_slice().to_vec(),
+            confirmation_key: message_secrets.confirmation_key().as_slice().to_vec(),
+            membership_key: message_secrets.membership_key().as_slice().to_vec(),
+            resumption_psk: group_epoch_secrets.resumption_psk().as_slice().to_vec(),
+        }
+    }
+
+    /// Returns the epoch of the secrets.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the `init_secret` for the next epoch.
+    pub fn init_secret(&self) -> &[u8] {
+        &self.init_secret
+    }
+
+    /// Returns the `sender_data_secret`.
+    pub fn sender_data_secret(&self) -> &[u8] {
+        &self.sender_data_secret
+    }
+
+    /// Returns the `encryption_secret`.
+    ///
+    /// Returns `None` if the epoch was created without the
+    /// `epoch-secrets-export` feature, e.g., by a group that was loaded from
+    /// storage.
+    pub fn encryption_secret(&self) -> Option<&[u8]> {
+        self.encryption_secret.as_deref()
+    }
+
+    /// Returns the `exporter_secret`.
+    pub fn exporter_secret(&self) -> &[u8] {
+        &self.exporter_secret
+    }
+
+    /// Returns the `epoch_authenticator`.
+    pub fn epoch_authenticator(&self) -> &[u8] {
+        &self.epoch_authenticator
+    }
+
+    /// Returns the `external_secret`.
+    pub fn external_secret(&self) -> &[u8] {
+        &self.external_secret
+    }
+
+    /// Returns the `confirmation_key`.
+    pub fn confirmation_key(&self) -> &[u8] {
+        &self.confirmation_key
+    }
+
+    /// Returns the `membership_key`.
+    pub fn membership_key(&self) -> &[u8] {
+        &self.membership_key
+    }
+
+    /// Returns the `resumption_psk`.
+    pub fn resumption_psk(&self) -> &[u8] {
+        &self.resumption_psk
+    }
+}
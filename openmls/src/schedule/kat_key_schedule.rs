@@ -6,6 +6,7 @@
 //! If values are not present, they are encoded as empty strings.
 
 use log::info;
+#[cfg(test)]
 use openmls_rust_crypto::OpenMlsRustCrypto;
 use openmls_traits::{random::OpenMlsRand, types::HpkeKeyPair, OpenMlsCryptoProvider};
 use serde::{self, Deserialize, Serialize};
@@ -65,6 +66,7 @@ fn generate(
     init_secret: &InitSecret,
     group_id: &[u8],
     epoch: u64,
+    backend: &impl OpenMlsCryptoProvider,
 ) -> (
     Vec<u8>,
     CommitSecret,
@@ -76,22 +78,21 @@ fn generate(
     GroupContext,
     HpkeKeyPair,
 ) {
-    let crypto = OpenMlsRustCrypto::default();
-    let tree_hash = crypto
+    let tree_hash = backend
         .rand()
         .random_vec(ciphersuite.hash_length())
         .expect("An unexpected error occurred.");
-    let commit_secret = CommitSecret::random(ciphersuite, &crypto);
+    let commit_secret = CommitSecret::random(ciphersuite, backend);
 
-    let confirmed_transcript_hash = crypto
+    let confirmed_transcript_hash = backend
         .rand()
         .random_vec(ciphersuite.hash_length())
         .expect("An unexpected error occurred.");
 
     // PSK secret can sometimes be the all zero vector
-    let a: [u8; 1] = crypto.rand().random_array().unwrap();
+    let a: [u8; 1] = backend.rand().random_array().unwrap();
     let psk_secret = if a[0] > 127 {
-        PskSecret::from(Secret::random(ciphersuite, &crypto, ProtocolVersion::Mls10).unwrap())
+        PskSecret::from(Secret::random(ciphersuite, backend, ProtocolVersion::Mls10).unwrap())
     } else {
         PskSecret::from(Secret::zero(ciphersuite, ProtocolVersion::Mls10))
     };
@@ -106,17 +107,17 @@ fn generate(
     );
 
     let joiner_secret = JoinerSecret::new(
-        &crypto,
+        backend,
         commit_secret.clone(),
         init_secret,
         &group_context.tls_serialize_detached().unwrap(),
     )
     .expect("Could not create JoinerSecret.");
     let mut key_schedule =
-        KeySchedule::init(ciphersuite, &crypto, &joiner_secret, psk_secret.clone())
+        KeySchedule::init(ciphersuite, backend, &joiner_secret, psk_secret.clone())
             .expect("Could not create KeySchedule.");
     let welcome_secret = key_schedule
-        .welcome(&crypto)
+        .welcome(backend)
         .expect("An unexpected error occurred.");
 
     let serialized_group_context = group_context
@@ -124,16 +125,16 @@ fn generate(
         .expect("Could not serialize group context.");
 
     key_schedule
-        .add_context(&crypto, &serialized_group_context)
+        .add_context(backend, &serialized_group_context)
         .expect("An unexpected error occurred.");
     let epoch_secrets = key_schedule
-        .epoch_secrets(&crypto)
+        .epoch_secrets(backend)
         .expect("An unexpected error occurred.");
 
     // Calculate external HPKE key pair
     let external_key_pair = epoch_secrets
         .external_secret()
-        .derive_external_keypair(crypto.crypto(), ciphersuite);
+        .derive_external_keypair(backend.crypto(), ciphersuite);
 
     (
         confirmed_transcript_hash,
@@ -148,6 +149,8 @@ fn generate(
     )
 }
 
+/// Generate a key schedule test vector for `ciphersuite` with `n_epochs`
+/// epochs.
 #[cfg(any(feature = "test-utils", test))]
 pub fn generate_test_vector(
     n_epochs: u64,
@@ -169,7 +172,6 @@ pub fn generate_test_vector(
 
     // Generate info for all epochs
     for epoch in 0..n_epochs {
-        println!("Generating epoch: {epoch:?}");
         let (
             confirmed_transcript_hash,
             commit_secret,
@@ -180,7 +182,7 @@ pub fn generate_test_vector(
             tree_hash,
             group_context,
             external_key_pair,
-        ) = generate(ciphersuite, &init_secret, &group_id, epoch);
+        ) = generate(ciphersuite, &init_secret, &group_id, epoch, backend);
 
         // exporter
         let exporter_label = "exporter label";
@@ -263,6 +265,14 @@ fn read_test_vectors_key_schedule(backend: &impl OpenMlsCryptoProvider) {
     }
 }
 
+#[apply(backends)]
+fn generate_and_run_test_vectors_key_schedule(backend: &impl OpenMlsCryptoProvider) {
+    for &ciphersuite in backend.crypto().supported_ciphersuites().iter() {
+        let test_vector = generate_test_vector(3, ciphersuite, backend);
+        run_test_vector(test_vector, backend).expect("Error while checking generated test vector.");
+    }
+}
+
 #[cfg(any(feature = "test-utils", test))]
 pub fn run_test_vector(
     test_vector: KeyScheduleTestVector,
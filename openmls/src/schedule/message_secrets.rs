@@ -65,10 +65,23 @@ impl MessageSecrets {
         self.serialized_context.as_ref()
     }
 
+    /// Get a reference to the message secrets's secret tree.
+    pub(crate) fn secret_tree(&self) -> &SecretTree {
+        &self.secret_tree
+    }
+
     /// Get a mutable reference to the message secrets's secret tree.
     pub(crate) fn secret_tree_mut(&mut self) -> &mut SecretTree {
         &mut self.secret_tree
     }
+
+    /// Returns an estimate of the memory in bytes that the message secrets
+    /// occupy. See [`SecretTree::key_material_size()`].
+    pub(crate) fn key_material_size(&self, ciphersuite: Ciphersuite) -> usize {
+        3 * ciphersuite.hash_length()
+            + self.serialized_context.len()
+            + self.secret_tree.key_material_size(ciphersuite)
+    }
 }
 
 // Test functions
@@ -78,13 +91,13 @@ impl MessageSecrets {
         &mut self.sender_data_secret
     }
 
-    #[cfg(test)]
+    #[cfg(any(feature = "test-vectors", test))]
     /// Update the message secrets's serialized context.
     pub(crate) fn set_serialized_context(&mut self, serialized_context: Vec<u8>) {
         self.serialized_context = serialized_context;
     }
 
-    #[cfg(test)]
+    #[cfg(any(feature = "test-vectors", test))]
     /// Update the membership key.
     pub(crate) fn set_membership_key(&mut self, membership_key: Secret) {
         self.membership_key = MembershipKey::from_secret(membership_key);
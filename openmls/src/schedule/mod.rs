@@ -148,6 +148,9 @@ use errors::*;
 use message_secrets::MessageSecrets;
 use psk::PskSecret;
 
+#[cfg(feature = "epoch-secrets-export")]
+pub mod epoch_secrets_export;
+
 // Tests
 #[cfg(any(feature = "test-utils", test))]
 pub mod kat_key_schedule;
@@ -157,6 +160,8 @@ pub mod kat_psk_secret;
 mod unit_tests;
 
 // Public types
+#[cfg(feature = "epoch-secrets-export")]
+pub use epoch_secrets_export::ExportedEpochSecrets;
 pub use psk::{ExternalPsk, PreSharedKeyId, Psk};
 
 /// A group secret that can be used among members to prove that a member was
@@ -348,7 +353,7 @@ impl InitSecret {
         }
     }
 
-    #[cfg(any(feature = "test-utils", test))]
+    #[cfg(any(feature = "test-utils", feature = "epoch-secrets-export", test))]
     pub(crate) fn as_slice(&self) -> &[u8] {
         self.secret.as_slice()
     }
@@ -715,7 +720,7 @@ impl ExporterSecret {
         Ok(ExporterSecret { secret })
     }
 
-    #[cfg(any(feature = "test-utils", test))]
+    #[cfg(any(feature = "test-utils", feature = "epoch-secrets-export", test))]
     pub(crate) fn as_slice(&self) -> &[u8] {
         self.secret.as_slice()
     }
@@ -741,6 +746,58 @@ impl ExporterSecret {
             .as_slice()
             .to_vec())
     }
+
+    /// Derive a secret for a safe extension from the exporter secret. The
+    /// expansion uses a different label than [`Self::derive_exported_secret()`],
+    /// such that the secret can't be exported with any label and context.
+    pub(crate) fn derive_extension_secret(
+        &self,
+        ciphersuite: Ciphersuite,
+        backend: &impl OpenMlsCryptoProvider,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.derive_internal_secret(ciphersuite, backend, "extension", context, key_length)
+    }
+
+    /// Derive the secret of a message stream from the exporter secret. Like
+    /// extension secrets, stream secrets can't be exported with any label and
+    /// context.
+    pub(crate) fn derive_stream_secret(
+        &self,
+        ciphersuite: Ciphersuite,
+        backend: &impl OpenMlsCryptoProvider,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.derive_internal_secret(ciphersuite, backend, "stream", context, key_length)
+    }
+
+    /// Derive a secret for internal use like [`Self::derive_exported_secret()`],
+    /// but expand it with the label "`label` exported" instead of "exported".
+    fn derive_internal_secret(
+        &self,
+        ciphersuite: Ciphersuite,
+        backend: &impl OpenMlsCryptoProvider,
+        label: &str,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<Vec<u8>, CryptoError> {
+        let context_hash = &backend
+            .crypto()
+            .hash(ciphersuite.hash_algorithm(), context)?;
+        Ok(self
+            .secret
+            .derive_secret(backend, label)?
+            .kdf_expand_label(
+                backend,
+                &format!("{label} exported"),
+                context_hash,
+                key_length,
+            )?
+            .as_slice()
+            .to_vec())
+    }
 }
 
 /// A secret used when joining a group with an external Commit.
@@ -769,7 +826,7 @@ impl ExternalSecret {
         crypto.derive_hpke_keypair(ciphersuite.hpke_config(), self.secret.as_slice())
     }
 
-    #[cfg(any(feature = "test-utils", test))]
+    #[cfg(any(feature = "test-utils", feature = "epoch-secrets-export", test))]
     pub(crate) fn as_slice(&self) -> &[u8] {
         self.secret.as_slice()
     }
@@ -837,7 +894,10 @@ impl ConfirmationKey {
                 .expect("Not enough randomness."),
         }
     }
+}
 
+#[cfg(any(feature = "test-utils", feature = "epoch-secrets-export", test))]
+impl ConfirmationKey {
     pub(crate) fn as_slice(&self) -> &[u8] {
         self.secret.as_slice()
     }
@@ -865,7 +925,7 @@ impl MembershipKey {
     /// 9.1 Content Authentication
     ///
     /// ```text
-    /// membership_tag = MAC(membership_key, MLSPlaintextTBM);
+    /// membership_tag = MAC(membership_key, AuthenticatedContentTBM);
     /// ```
     pub(crate) fn tag_message(
         &self,
@@ -889,7 +949,7 @@ impl MembershipKey {
         Self { secret }
     }
 
-    #[cfg(any(feature = "test-utils", test))]
+    #[cfg(any(feature = "test-utils", feature = "epoch-secrets-export", test))]
     pub(crate) fn as_slice(&self) -> &[u8] {
         self.secret.as_slice()
     }
@@ -981,7 +1041,7 @@ impl SenderDataSecret {
         }
     }
 
-    #[cfg(any(feature = "test-utils", test))]
+    #[cfg(any(feature = "test-utils", feature = "epoch-secrets-export", test))]
     pub(crate) fn as_slice(&self) -> &[u8] {
         self.secret.as_slice()
     }
@@ -1176,6 +1236,8 @@ impl EpochSecrets {
         treesize: TreeSize,
         own_index: LeafNodeIndex,
     ) -> (GroupEpochSecrets, MessageSecrets) {
+        #[cfg(feature = "epoch-secrets-export")]
+        let encryption_secret = Some(self.encryption_secret.secret.clone());
         let secret_tree = self
             .encryption_secret
             .create_secret_tree(treesize, own_index);
@@ -1186,6 +1248,8 @@ impl EpochSecrets {
                 epoch_authenticator: self.epoch_authenticator,
                 external_secret: self.external_secret,
                 resumption_psk: self.resumption_psk,
+                #[cfg(feature = "epoch-secrets-export")]
+                encryption_secret,
             },
             MessageSecrets::new(
                 self.sender_data_secret,
@@ -1205,6 +1269,11 @@ pub(crate) struct GroupEpochSecrets {
     epoch_authenticator: EpochAuthenticator,
     external_secret: ExternalSecret,
     resumption_psk: ResumptionPskSecret,
+    // ☣️ The encryption secret is only kept to be exported. This breaks
+    // forward secrecy.
+    #[cfg(feature = "epoch-secrets-export")]
+    #[serde(default)]
+    encryption_secret: Option<Secret>,
 }
 
 impl std::fmt::Debug for GroupEpochSecrets {
@@ -1256,4 +1325,17 @@ impl GroupEpochSecrets {
     pub(crate) fn resumption_psk(&self) -> &ResumptionPskSecret {
         &self.resumption_psk
     }
+
+    /// Consume the group epoch secrets and return the exporter secret. All
+    /// other secrets are dropped.
+    pub(crate) fn into_exporter_secret(self) -> ExporterSecret {
+        self.exporter_secret
+    }
+
+    /// Encryption secret. Only available if the epoch was created with the
+    /// `epoch-secrets-export` feature enabled.
+    #[cfg(feature = "epoch-secrets-export")]
+    pub(crate) fn encryption_secret(&self) -> Option<&Secret> {
+        self.encryption_secret.as_ref()
+    }
 }
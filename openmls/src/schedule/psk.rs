@@ -17,7 +17,7 @@ use crate::{
 /// Resumption PSK usage.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-19
+/// // RFC 9420
 /// enum {
 ///   reserved(0),
 ///   application(1),
@@ -42,6 +42,7 @@ use crate::{
     TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ResumptionPskUsage {
     /// Application.
     Application = 1,
@@ -70,6 +71,7 @@ pub enum ResumptionPskUsage {
     TlsSerialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ExternalPsk {
     psk_id: VLBytes,
 }
@@ -113,6 +115,7 @@ impl MlsEntity for PskBundle {
     TlsSerialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ResumptionPsk {
     pub(crate) usage: ResumptionPskUsage,
     pub(crate) psk_group_id: GroupId,
@@ -160,6 +163,7 @@ impl ResumptionPsk {
     TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Psk {
     /// An external PSK provided by the application.
     #[tls_codec(discriminant = 1)]
@@ -170,7 +174,7 @@ pub enum Psk {
 }
 
 /// ```c
-/// // draft-ietf-mls-protocol-19
+/// // RFC 9420
 /// enum {
 ///   reserved(0),
 ///   external(1),
@@ -191,7 +195,7 @@ pub enum PskType {
 /// in the key schedule.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-19
+/// // RFC 9420
 /// struct {
 ///   PSKType psktype;
 ///   select (PreSharedKeyID.psktype) {
@@ -219,6 +223,7 @@ pub enum PskType {
     TlsSerialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PreSharedKeyId {
     pub(crate) psk: Psk,
     pub(crate) psk_nonce: VLBytes,
@@ -362,7 +367,7 @@ impl PreSharedKeyId {
 /// injected in the key schedule.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-19
+/// // RFC 9420
 /// struct {
 ///     PreSharedKeyID id;
 ///     uint16 index;
@@ -411,7 +416,7 @@ impl PskSecret {
 
         let mls_version = ProtocolVersion::default();
 
-        // Following comments are from `draft-ietf-mls-protocol-19`.
+        // Following comments are from RFC 9420.
         //
         // psk_secret_[0] = 0
         let mut psk_secret = Secret::zero(ciphersuite, mls_version);
@@ -550,5 +555,10 @@ pub mod store {
                 .find(|&(e, _s)| e == &epoch)
                 .map(|(_e, s)| s)
         }
+
+        /// Returns the epochs for which a resumption psk is stored.
+        pub(crate) fn epochs(&self) -> impl Iterator<Item = GroupEpoch> + '_ {
+            self.resumption_psk.iter().map(|(epoch, _)| *epoch)
+        }
     }
 }
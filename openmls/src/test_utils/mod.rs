@@ -249,7 +249,7 @@ pub fn backends(backend: &impl OpenMlsCryptoProvider) {}
 
 // === Ciphersuites ===
 
-// For now we support all ciphersuites, regardless of the backend
+// All ciphersuites that are supported by the RustCrypto backend
 
 #[template]
 #[export]
@@ -263,6 +263,18 @@ pub fn backends(backend: &impl OpenMlsCryptoProvider) {}
     ),
     case::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(
         Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519
+    ),
+    case::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448(
+        Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448
+    ),
+    case::MLS_256_DHKEMP521_AES256GCM_SHA512_P521(
+        Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
+    ),
+    case::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448(
+        Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448
+    ),
+    case::MLS_256_DHKEMP384_AES256GCM_SHA384_P384(
+        Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384
     )
 )]
 #[allow(non_snake_case)]
@@ -282,6 +294,10 @@ pub fn ciphersuites(ciphersuite: Ciphersuite) {}
     case::rust_crypto_MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
     case::rust_crypto_MLS_128_DHKEMP256_AES128GCM_SHA256_P256(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256, &OpenMlsRustCrypto::default()),
     case::rust_crypto_MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448(Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP521_AES256GCM_SHA512_P521(Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448(Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP384_AES256GCM_SHA384_P384(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384, &OpenMlsRustCrypto::default()),
   )
 ]
 #[allow(non_snake_case)]
@@ -301,6 +317,10 @@ pub fn ciphersuites_and_backends(ciphersuite: Ciphersuite, backend: &impl OpenMl
     case::rust_crypto_MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
     case::rust_crypto_MLS_128_DHKEMP256_AES128GCM_SHA256_P256(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256, &OpenMlsRustCrypto::default()),
     case::rust_crypto_MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448(Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP521_AES256GCM_SHA512_P521(Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448(Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP384_AES256GCM_SHA384_P384(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384, &OpenMlsRustCrypto::default()),
     case::evercrypt_MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519, &openmls_evercrypt::OpenMlsEvercrypt::default()),
     case::evercrypt_MLS_128_DHKEMP256_AES128GCM_SHA256_P256(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256, &openmls_evercrypt::OpenMlsEvercrypt::default()),
     case::evercrypt_MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519, &openmls_evercrypt::OpenMlsEvercrypt::default()),
@@ -33,7 +33,7 @@ use crate::{
 };
 use ::rand::{rngs::OsRng, RngCore};
 use openmls_basic_credential::SignatureKeyPair;
-use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls_rust_crypto::{MemoryKeyStoreSnapshot, OpenMlsRustCrypto};
 use openmls_traits::{
     crypto::OpenMlsCrypto,
     key_store::OpenMlsKeyStore,
@@ -96,6 +96,27 @@ pub enum CodecUse {
     StructMessages,
 }
 
+/// The state of a single client in a [`SetupSnapshot`].
+#[derive(Clone)]
+struct ClientSnapshot {
+    key_store: MemoryKeyStoreSnapshot,
+    // The serialized group states. The configuration is kept alongside, as
+    // some of its parts are not serialized.
+    groups: HashMap<GroupId, (Vec<u8>, MlsGroupConfig)>,
+}
+
+/// A `SetupSnapshot` holds the state of all clients and groups of an
+/// `MlsGroupTestSetup` at a point in time. It is created with
+/// `MlsGroupTestSetup::snapshot` and can be restored (repeatedly) with
+/// `MlsGroupTestSetup::restore`, which allows branching several scenarios off
+/// the same group state without setting up the group again.
+#[derive(Clone)]
+pub struct SetupSnapshot {
+    clients: HashMap<Vec<u8>, ClientSnapshot>,
+    groups: HashMap<GroupId, Group>,
+    waiting_for_welcome: HashMap<Vec<u8>, Vec<u8>>,
+}
+
 /// `MlsGroupTestSetup` is the main struct of the framework. It contains the
 /// state of all clients, as well as the global `KeyStore` containing the
 /// clients' `CredentialBundles`. The `waiting_for_welcome` field acts as a
@@ -708,4 +729,86 @@ impl MlsGroupTestSetup {
         };
         Ok(())
     }
+
+    /// Take a snapshot of the key stores and group states of all clients, as
+    /// well as of the global group state. The snapshot can be restored with
+    /// `restore`.
+    pub fn snapshot(&self) -> SetupSnapshot {
+        let clients = self.clients.read().expect("An unexpected error occurred.");
+        let clients = clients
+            .iter()
+            .map(|(id, client)| {
+                let client = client.read().expect("An unexpected error occurred.");
+                let groups = client
+                    .groups
+                    .read()
+                    .expect("An unexpected error occurred.")
+                    .iter()
+                    .map(|(group_id, group)| {
+                        let serialized_group =
+                            serde_json::to_vec(group).expect("An unexpected error occurred.");
+                        (
+                            group_id.clone(),
+                            (serialized_group, group.configuration().clone()),
+                        )
+                    })
+                    .collect();
+                let client_snapshot = ClientSnapshot {
+                    key_store: client.crypto.key_store().snapshot(),
+                    groups,
+                };
+                (id.clone(), client_snapshot)
+            })
+            .collect();
+        SetupSnapshot {
+            clients,
+            groups: self
+                .groups
+                .read()
+                .expect("An unexpected error occurred.")
+                .clone(),
+            waiting_for_welcome: self
+                .waiting_for_welcome
+                .read()
+                .expect("An unexpected error occurred.")
+                .clone(),
+        }
+    }
+
+    /// Restore the state of all clients and groups from `snapshot`, which
+    /// has to be taken from this `MlsGroupTestSetup`. All changes since the
+    /// snapshot was taken are discarded.
+    pub fn restore(&self, snapshot: &SetupSnapshot) {
+        let clients = self.clients.read().expect("An unexpected error occurred.");
+        for (id, client_snapshot) in &snapshot.clients {
+            let client = clients
+                .get(id)
+                .expect("The snapshot was taken from a different setup.")
+                .read()
+                .expect("An unexpected error occurred.");
+            client
+                .crypto
+                .key_store()
+                .restore(&client_snapshot.key_store);
+            let groups = client_snapshot
+                .groups
+                .iter()
+                .map(|(group_id, (serialized_group, config))| {
+                    let mut group = MlsGroup::load(serialized_group.as_slice())
+                        .expect("An unexpected error occurred.");
+                    group.set_configuration(config);
+                    (group_id.clone(), group)
+                })
+                .collect();
+            *client
+                .groups
+                .write()
+                .expect("An unexpected error occurred.") = groups;
+        }
+        *self.groups.write().expect("An unexpected error occurred.") = snapshot.groups.clone();
+        *self
+            .waiting_for_welcome
+            .write()
+            .expect("An unexpected error occurred.") = snapshot.waiting_for_welcome.clone();
+    }
 }
@@ -0,0 +1,307 @@
+//! # Test vector generation and verification
+//!
+//! This module is only available with the `test-vectors` feature. It allows
+//! generating test vectors in the format of the
+//! [MLS implementations repository](https://github.com/mlswg/mls-implementations/blob/master/test-vectors.md)
+//! for a chosen ciphersuite and scenario, so that other implementations can
+//! be cross-checked against OpenMLS.
+//!
+//! All secrets of a test vector are randomly chosen with the given backend.
+//! Test vectors can be serialized with [`to_json()`].
+//!
+//! ```ignore
+//! let key_schedule = test_vectors::key_schedule(ciphersuite, 3, &backend);
+//! let json = test_vectors::to_json(&[key_schedule])?;
+//! ```
+//!
+//! Conversely, the published test vectors can be run against this build of
+//! OpenMLS with [`verify()`], e.g., to check a packaged build against the
+//! spec. Every test vector in the file yields a [`TestVectorResult`].
+//!
+//! ```ignore
+//! let json = std::fs::read_to_string("test-vectors/key-schedule.json")?;
+//! let results = test_vectors::verify(TestVectorKind::KeySchedule, &json, &backend)?;
+//! assert!(results.iter().all(|result| !result.outcome().is_failure()));
+//! ```
+
+use std::panic::{self, AssertUnwindSafe};
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite, OpenMlsCryptoProvider};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    group::core_group::kat_welcome,
+    schedule::kat_key_schedule,
+    tree::tests_and_kats::kats::{kat_message_protection, secret_tree},
+    treesync::tests_and_kats::kats::kat_tree_operations,
+};
+pub use crate::{
+    schedule::kat_key_schedule::KeyScheduleTestVector,
+    tree::tests_and_kats::kats::secret_tree::SecretTree as SecretTreeTestVector,
+};
+
+/// Generates a key schedule test vector for `ciphersuite` that covers
+/// `n_epochs` consecutive epochs of a group.
+pub fn key_schedule(
+    ciphersuite: Ciphersuite,
+    n_epochs: u64,
+    backend: &impl OpenMlsCryptoProvider,
+) -> KeyScheduleTestVector {
+    crate::schedule::kat_key_schedule::generate_test_vector(n_epochs, ciphersuite, backend)
+}
+
+/// Generates a secret tree test vector for `ciphersuite` with a tree of
+/// `n_leaves` leaves, containing the handshake and application secrets of
+/// each leaf for the given `generations`.
+pub fn secret_tree(
+    ciphersuite: Ciphersuite,
+    n_leaves: u32,
+    generations: &[u32],
+    backend: &impl OpenMlsCryptoProvider,
+) -> SecretTreeTestVector {
+    crate::tree::tests_and_kats::kats::secret_tree::generate_test_vector(
+        ciphersuite,
+        n_leaves,
+        generations,
+        backend,
+    )
+}
+
+/// Serializes a list of test vectors to JSON, as used by the MLS
+/// implementations repository.
+pub fn to_json<T: Serialize>(test_vectors: &[T]) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(test_vectors)
+}
+
+// === Verification ===
+
+/// The kinds of published test vectors that can be checked with [`verify()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TestVectorKind {
+    /// Key schedule test vectors (`key-schedule.json`).
+    KeySchedule,
+    /// Secret tree test vectors (`secret-tree.json`).
+    SecretTree,
+    /// Message protection test vectors (`message-protection.json`).
+    MessageProtection,
+    /// Welcome test vectors (`welcome.json`).
+    Welcome,
+    /// Tree operations test vectors (`tree-operations.json`).
+    TreeOperations,
+}
+
+impl TestVectorKind {
+    /// All kinds of test vectors that can be verified.
+    pub const ALL: [TestVectorKind; 5] = [
+        TestVectorKind::KeySchedule,
+        TestVectorKind::SecretTree,
+        TestVectorKind::MessageProtection,
+        TestVectorKind::Welcome,
+        TestVectorKind::TreeOperations,
+    ];
+
+    /// Returns the name of the file with test vectors of this kind in the MLS
+    /// implementations repository.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            TestVectorKind::KeySchedule => "key-schedule.json",
+            TestVectorKind::SecretTree => "secret-tree.json",
+            TestVectorKind::MessageProtection => "message-protection.json",
+            TestVectorKind::Welcome => "welcome.json",
+            TestVectorKind::TreeOperations => "tree-operations.json",
+        }
+    }
+}
+
+/// The outcome of checking a single test vector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestVectorOutcome {
+    /// OpenMLS computed the expected values.
+    Passed,
+    /// The test vector uses a ciphersuite that isn't supported by the backend.
+    Skipped,
+    /// OpenMLS didn't compute the expected values or the test vector is
+    /// malformed. Contains a description of the failure.
+    Failed(String),
+}
+
+impl TestVectorOutcome {
+    /// Returns `true` if the test vector failed.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, TestVectorOutcome::Failed(_))
+    }
+}
+
+/// The result of checking a single test vector with [`verify()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVectorResult {
+    kind: TestVectorKind,
+    index: usize,
+    cipher_suite: Option<u16>,
+    outcome: TestVectorOutcome,
+}
+
+impl TestVectorResult {
+    /// Returns the kind of the test vector.
+    pub fn kind(&self) -> TestVectorKind {
+        self.kind
+    }
+
+    /// Returns the position of the test vector in the file.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the ciphersuite of the test vector, if the test vector has one.
+    pub fn cipher_suite(&self) -> Option<u16> {
+        self.cipher_suite
+    }
+
+    /// Returns the outcome of the check.
+    pub fn outcome(&self) -> &TestVectorOutcome {
+        &self.outcome
+    }
+}
+
+/// Checks all test vectors of the given `kind` in `json` against OpenMLS,
+/// using `backend` for the cryptographic operations.
+///
+/// Returns an error if `json` isn't a list of JSON objects. Otherwise, returns
+/// a [`TestVectorResult`] for every test vector, in the order of the list.
+/// Test vectors that can't be parsed or that cause a panic in OpenMLS are
+/// reported as [`TestVectorOutcome::Failed`]. The latter requires that the
+/// library is built with `panic = "unwind"`.
+pub fn verify(
+    kind: TestVectorKind,
+    json: &str,
+    backend: &impl OpenMlsCryptoProvider,
+) -> Result<Vec<TestVectorResult>, serde_json::Error> {
+    let test_vectors: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_str(json)?;
+
+    Ok(test_vectors
+        .into_iter()
+        .enumerate()
+        .map(|(index, test_vector)| {
+            let cipher_suite = test_vector
+                .get("cipher_suite")
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|cipher_suite| u16::try_from(cipher_suite).ok());
+            let outcome = match cipher_suite {
+                Some(cipher_suite) if !is_supported(cipher_suite, backend) => {
+                    TestVectorOutcome::Skipped
+                }
+                _ => run(kind, serde_json::Value::Object(test_vector), backend),
+            };
+            TestVectorResult {
+                kind,
+                index,
+                cipher_suite,
+                outcome,
+            }
+        })
+        .collect())
+}
+
+fn is_supported(cipher_suite: u16, backend: &impl OpenMlsCryptoProvider) -> bool {
+    Ciphersuite::try_from(cipher_suite)
+        .map(|ciphersuite| {
+            backend
+                .crypto()
+                .supported_ciphersuites()
+                .contains(&ciphersuite)
+        })
+        .unwrap_or(false)
+}
+
+/// Runs a single test vector and turns errors and panics into a
+/// [`TestVectorOutcome`].
+fn run(
+    kind: TestVectorKind,
+    test_vector: serde_json::Value,
+    backend: &impl OpenMlsCryptoProvider,
+) -> TestVectorOutcome {
+    fn parse<T: DeserializeOwned>(test_vector: serde_json::Value) -> Result<T, String> {
+        serde_json::from_value(test_vector).map_err(|e| format!("Malformed test vector: {e}"))
+    }
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| match kind {
+        TestVectorKind::KeySchedule => parse(test_vector).and_then(|test_vector| {
+            kat_key_schedule::run_test_vector(test_vector, backend).map_err(|e| e.to_string())
+        }),
+        TestVectorKind::SecretTree => parse(test_vector)
+            .and_then(|test_vector| secret_tree::run_test_vector(test_vector, backend)),
+        TestVectorKind::MessageProtection => parse(test_vector)
+            .and_then(|test_vector| kat_message_protection::run_test_vector(test_vector, backend)),
+        TestVectorKind::Welcome => parse(test_vector).and_then(|test_vector| {
+            kat_welcome::run_test_vector(test_vector).map_err(str::to_string)
+        }),
+        TestVectorKind::TreeOperations => parse(test_vector)
+            .and_then(|test_vector| kat_tree_operations::run_test_vector(test_vector, backend)),
+    }));
+
+    match result {
+        Ok(Ok(())) => TestVectorOutcome::Passed,
+        Ok(Err(e)) => TestVectorOutcome::Failed(e),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Unknown panic.".to_string());
+            TestVectorOutcome::Failed(message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openmls_rust_crypto::OpenMlsRustCrypto;
+
+    use super::*;
+
+    #[test]
+    fn verify_published_test_vectors() {
+        let backend = OpenMlsRustCrypto::default();
+
+        for kind in TestVectorKind::ALL {
+            let json = std::fs::read_to_string(format!("test_vectors/{}", kind.file_name()))
+                .expect("Couldn't read test vectors.");
+            let results = verify(kind, &json, &backend).expect("Malformed test vector file.");
+
+            assert!(!results.is_empty());
+            for result in results {
+                assert!(
+                    !result.outcome().is_failure(),
+                    "{kind:?} test vector {} failed: {:?}",
+                    result.index(),
+                    result.outcome()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn verify_reports_failures() {
+        let backend = OpenMlsRustCrypto::default();
+
+        let results = verify(
+            TestVectorKind::SecretTree,
+            r#"[{"cipher_suite": 1}]"#,
+            &backend,
+        )
+        .expect("Malformed test vector file.");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].cipher_suite(), Some(1));
+        assert!(results[0].outcome().is_failure());
+
+        let results = verify(
+            TestVectorKind::SecretTree,
+            r#"[{"cipher_suite": 4242}]"#,
+            &backend,
+        )
+        .expect("Malformed test vector file.");
+        assert_eq!(results[0].outcome(), &TestVectorOutcome::Skipped);
+
+        assert!(verify(TestVectorKind::SecretTree, "{}", &backend).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use openmls_traits::types::{Ciphersuite, CryptoError};
 use thiserror::Error;
 use tls_codec::{Error as TlsCodecError, TlsSerialize, TlsSize};
@@ -104,18 +106,105 @@ pub(crate) struct SecretTreeNode {
     pub(crate) secret: Secret,
 }
 
+/// The secret tree of an epoch.
+///
+/// Nodes and sender ratchets are stored sparsely, such that the memory
+/// consumption scales with the number of senders the client communicates
+/// with rather than with the size of the group.
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(any(feature = "test-utils", test), derive(PartialEq, Clone))]
 #[cfg_attr(feature = "crypto-debug", derive(Debug))]
 pub(crate) struct SecretTree {
     own_index: LeafNodeIndex,
-    leaf_nodes: Vec<Option<SecretTreeNode>>,
-    parent_nodes: Vec<Option<SecretTreeNode>>,
-    handshake_sender_ratchets: Vec<Option<SenderRatchet>>,
-    application_sender_ratchets: Vec<Option<SenderRatchet>>,
+    #[serde(deserialize_with = "sparse::deserialize")]
+    leaf_nodes: BTreeMap<LeafNodeIndex, SecretTreeNode>,
+    #[serde(deserialize_with = "sparse::deserialize")]
+    parent_nodes: BTreeMap<ParentNodeIndex, SecretTreeNode>,
+    #[serde(deserialize_with = "sparse::deserialize")]
+    handshake_sender_ratchets: BTreeMap<LeafNodeIndex, SenderRatchet>,
+    #[serde(deserialize_with = "sparse::deserialize")]
+    application_sender_ratchets: BTreeMap<LeafNodeIndex, SenderRatchet>,
     size: TreeSize,
 }
 
+/// Deserialization of the sparse maps of a [`SecretTree`].
+///
+/// Secret trees that were serialized by earlier versions store dense vectors
+/// with one optional entry per index instead of maps. Both representations
+/// are accepted, such that persisted groups can still be loaded.
+mod sparse {
+    use std::{collections::BTreeMap, fmt, marker::PhantomData};
+
+    use serde::{
+        de::{Error, MapAccess, SeqAccess, Visitor},
+        Deserialize, Deserializer,
+    };
+
+    use crate::binary_tree::{array_representation::ParentNodeIndex, LeafNodeIndex};
+
+    /// Indices that can be derived from a position in a dense vector.
+    pub(super) trait Index: Ord {
+        fn from_position(position: u32) -> Self;
+    }
+
+    impl Index for LeafNodeIndex {
+        fn from_position(position: u32) -> Self {
+            LeafNodeIndex::new(position)
+        }
+    }
+
+    impl Index for ParentNodeIndex {
+        fn from_position(position: u32) -> Self {
+            ParentNodeIndex::new(position)
+        }
+    }
+
+    struct SparseVisitor<K, V>(PhantomData<(K, V)>);
+
+    impl<'de, K, V> Visitor<'de> for SparseVisitor<K, V>
+    where
+        K: Deserialize<'de> + Index,
+        V: Deserialize<'de>,
+    {
+        type Value = BTreeMap<K, V>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map or a sequence of optional entries")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut entries = BTreeMap::new();
+            while let Some((index, value)) = map.next_entry()? {
+                entries.insert(index, value);
+            }
+            Ok(entries)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut entries = BTreeMap::new();
+            let mut position = 0u32;
+            while let Some(entry) = seq.next_element::<Option<V>>()? {
+                if let Some(value) = entry {
+                    entries.insert(K::from_position(position), value);
+                }
+                position = position
+                    .checked_add(1)
+                    .ok_or_else(|| A::Error::custom("Too many entries in the secret tree."))?;
+            }
+            Ok(entries)
+        }
+    }
+
+    pub(super) fn deserialize<'de, D, K, V>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+    where
+        D: Deserializer<'de>,
+        K: Deserialize<'de> + Index,
+        V: Deserialize<'de>,
+    {
+        deserializer.deserialize_any(SparseVisitor(PhantomData))
+    }
+}
+
 impl SecretTree {
     /// Creates a new SecretTree based on an `encryption_secret` and group size
     /// `size`. The inner nodes of the tree and the SenderRatchets only get
@@ -126,49 +215,28 @@ impl SecretTree {
         size: TreeSize,
         own_index: LeafNodeIndex,
     ) -> Self {
-        let mut leaf_nodes = std::iter::repeat_with(|| None)
-            .take(size.leaf_count() as usize)
-            .collect::<Vec<_>>();
-
-        let mut parent_nodes = std::iter::repeat_with(|| None)
-            .take(size.parent_count() as usize)
-            .collect::<Vec<_>>();
-
-        match root(size) {
-            TreeNodeIndex::Leaf(leaf_index) => {
-                leaf_nodes[leaf_index.usize()] = Some(SecretTreeNode {
-                    secret: encryption_secret.consume_secret(),
-                });
-            }
-            TreeNodeIndex::Parent(parent_index) => {
-                parent_nodes[parent_index.usize()] = Some(SecretTreeNode {
-                    secret: encryption_secret.consume_secret(),
-                });
-            }
-        }
-
-        let handshake_sender_ratchets = std::iter::repeat_with(|| Option::<SenderRatchet>::None)
-            .take(size.leaf_count() as usize)
-            .collect();
-
-        let application_sender_ratchets = std::iter::repeat_with(|| Option::<SenderRatchet>::None)
-            .take(size.leaf_count() as usize)
-            .collect();
+        let mut secret_tree = SecretTree {
+            own_index,
+            leaf_nodes: BTreeMap::new(),
+            parent_nodes: BTreeMap::new(),
+            handshake_sender_ratchets: BTreeMap::new(),
+            application_sender_ratchets: BTreeMap::new(),
+            size,
+        };
+        secret_tree.set_node(
+            root(size),
+            SecretTreeNode {
+                secret: encryption_secret.consume_secret(),
+            },
+        );
 
         log::trace!(
             "Created secret tree with {} leaves and {} nodes.",
-            leaf_nodes.len(),
-            parent_nodes.len()
+            size.leaf_count(),
+            size.parent_count()
         );
 
-        SecretTree {
-            own_index,
-            leaf_nodes,
-            parent_nodes,
-            handshake_sender_ratchets,
-            application_sender_ratchets,
-            size,
-        }
+        secret_tree
     }
 
     /// Get current generation for a specific SenderRatchet
@@ -198,12 +266,10 @@ impl SecretTree {
         }
         // Check if SenderRatchets are already initialized
         if self
-            .ratchet_opt(index, SecretType::HandshakeSecret)
-            .expect("Index out of bounds.")
+            .ratchet_opt(index, SecretType::HandshakeSecret)?
             .is_some()
             && self
-                .ratchet_opt(index, SecretType::ApplicationSecret)
-                .expect("Index out of bounds.")
+                .ratchet_opt(index, SecretType::ApplicationSecret)?
                 .is_some()
         {
             log::trace!("The sender ratchets are initialized already.");
@@ -211,7 +277,7 @@ impl SecretTree {
         }
 
         // If we don't have a secret in the leaf node, we derive it
-        if self.leaf_nodes[index.usize()].is_none() {
+        if !self.leaf_nodes.contains_key(&index) {
             // Collect empty nodes in the direct path until a non-empty node is
             // found
             let mut empty_nodes: Vec<ParentNodeIndex> = vec![];
@@ -219,7 +285,7 @@ impl SecretTree {
             log::trace!("Direct path for node {index:?}: {:?}", direct_path);
             for parent_node in direct_path {
                 empty_nodes.push(parent_node);
-                if self.parent_nodes[parent_node.usize()].is_some() {
+                if self.parent_nodes.contains_key(&parent_node) {
                     break;
                 }
             }
@@ -235,7 +301,7 @@ impl SecretTree {
         }
 
         // Calculate node secret and initialize SenderRatchets
-        let node_secret = match &self.leaf_nodes[index.usize()] {
+        let node_secret = match self.leaf_nodes.get(&index) {
             Some(node) => &node.secret,
             // We just derived all necessary nodes so this should not happen
             None => {
@@ -277,11 +343,13 @@ impl SecretTree {
 
             (handshake_sender_ratchet, application_sender_ratchet)
         };
-        self.handshake_sender_ratchets[index.usize()] = Some(handshake_sender_ratchet);
-        self.application_sender_ratchets[index.usize()] = Some(application_sender_ratchet);
+        self.handshake_sender_ratchets
+            .insert(index, handshake_sender_ratchet);
+        self.application_sender_ratchets
+            .insert(index, application_sender_ratchet);
 
         // Delete leaf node
-        self.leaf_nodes[index.usize()] = None;
+        self.leaf_nodes.remove(&index);
         Ok(())
     }
 
@@ -313,7 +381,7 @@ impl SecretTree {
             log::trace!("   initialize sender ratchets");
             self.initialize_sender_ratchets(ciphersuite, backend, index)?;
         }
-        match self.ratchet_mut(index, secret_type) {
+        match self.ratchet_mut(index, secret_type)? {
             SenderRatchet::EncryptionRatchet(_) => {
                 log::error!("This is the wrong ratchet type.");
                 Err(SecretTreeError::RatchetTypeError)
@@ -335,10 +403,9 @@ impl SecretTree {
         secret_type: SecretType,
     ) -> Result<(u32, RatchetKeyMaterial), SecretTreeError> {
         if self.ratchet_opt(index, secret_type)?.is_none() {
-            self.initialize_sender_ratchets(ciphersuite, backend, index)
-                .expect("Index out of bounds");
+            self.initialize_sender_ratchets(ciphersuite, backend, index)?;
         }
-        match self.ratchet_mut(index, secret_type) {
+        match self.ratchet_mut(index, secret_type)? {
             SenderRatchet::DecryptionRatchet(_) => Err(SecretTreeError::RatchetTypeError),
             SenderRatchet::EncryptionRatchet(enc_ratchet) => {
                 enc_ratchet.ratchet_forward(backend, ciphersuite)
@@ -346,18 +413,128 @@ impl SecretTree {
         }
     }
 
+    /// Deletes the key material of the own sender ratchets, such that no
+    /// further messages can be encrypted with this tree. The secrets of the
+    /// own leaf are derived first, such that they can't be derived again from
+    /// the nodes of its direct path.
+    pub(crate) fn erase_own_sender_ratchets(
+        &mut self,
+        ciphersuite: Ciphersuite,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<(), SecretTreeError> {
+        self.initialize_sender_ratchets(ciphersuite, backend, self.own_index)?;
+        self.handshake_sender_ratchets.insert(
+            self.own_index,
+            SenderRatchet::EncryptionRatchet(RatchetSecret::exhausted()),
+        );
+        self.application_sender_ratchets.insert(
+            self.own_index,
+            SenderRatchet::EncryptionRatchet(RatchetSecret::exhausted()),
+        );
+        Ok(())
+    }
+
+    /// Returns the number of node secrets that are stored in the tree. The
+    /// SenderRatchets of all members whose leaf is below one of these nodes
+    /// can still be derived.
+    pub(crate) fn node_secret_count(&self) -> usize {
+        self.leaf_nodes.len() + self.parent_nodes.len()
+    }
+
+    /// Returns the number of past generations for which the SenderRatchets
+    /// of all members retain key material.
+    pub(crate) fn retained_key_count(&self) -> usize {
+        self.sender_ratchets_iter()
+            .map(SenderRatchet::retained_key_count)
+            .sum()
+    }
+
+    /// Returns an estimate of the memory in bytes that the key material in
+    /// the tree occupies, i.e., the node secrets, the heads of the
+    /// SenderRatchets and the key material they retain for past generations.
+    pub(crate) fn key_material_size(&self, ciphersuite: Ciphersuite) -> usize {
+        let secrets = self.node_secret_count() + self.sender_ratchets_iter().count();
+        secrets * ciphersuite.hash_length()
+            + self.retained_key_count()
+                * (ciphersuite.aead_key_length() + ciphersuite.aead_nonce_length())
+    }
+
+    /// Returns an iterator over the handshake and application SenderRatchets
+    /// of all members.
+    fn sender_ratchets_iter(&self) -> impl Iterator<Item = &SenderRatchet> {
+        self.handshake_sender_ratchets
+            .values()
+            .chain(self.application_sender_ratchets.values())
+    }
+
+    /// Returns the leaf indices of the members whose SenderRatchets are
+    /// initialized, in ascending order.
+    pub(crate) fn initialized_senders(&self) -> Vec<LeafNodeIndex> {
+        let mut senders: Vec<LeafNodeIndex> = self
+            .handshake_sender_ratchets
+            .keys()
+            .chain(self.application_sender_ratchets.keys())
+            .copied()
+            .collect();
+        senders.sort();
+        senders.dedup();
+        senders
+    }
+
+    /// Returns the handshake and application SenderRatchets of the member at
+    /// `index`, if they were initialized.
+    pub(crate) fn sender_ratchets(
+        &self,
+        index: LeafNodeIndex,
+    ) -> (Option<&SenderRatchet>, Option<&SenderRatchet>) {
+        (
+            self.handshake_sender_ratchets.get(&index),
+            self.application_sender_ratchets.get(&index),
+        )
+    }
+
+    /// Merges the given handshake and application SenderRatchets of the
+    /// member at `index` into the tree. See [`SenderRatchet::merge()`] for
+    /// details.
+    pub(crate) fn merge_sender_ratchets(
+        &mut self,
+        ciphersuite: Ciphersuite,
+        backend: &impl OpenMlsCryptoProvider,
+        index: LeafNodeIndex,
+        handshake_sender_ratchet: Option<SenderRatchet>,
+        application_sender_ratchet: Option<SenderRatchet>,
+    ) -> Result<(), SecretTreeError> {
+        if handshake_sender_ratchet.is_none() && application_sender_ratchet.is_none() {
+            return Ok(());
+        }
+        self.initialize_sender_ratchets(ciphersuite, backend, index)?;
+        if let Some(sender_ratchet) = handshake_sender_ratchet {
+            self.ratchet_mut(index, SecretType::HandshakeSecret)?
+                .merge(sender_ratchet)?;
+        }
+        if let Some(sender_ratchet) = application_sender_ratchet {
+            self.ratchet_mut(index, SecretType::ApplicationSecret)?
+                .merge(sender_ratchet)?;
+        }
+        Ok(())
+    }
+
     /// Returns a mutable reference to a specific SenderRatchet. The
-    /// SenderRatchet needs to be initialized.
-    fn ratchet_mut(&mut self, index: LeafNodeIndex, secret_type: SecretType) -> &mut SenderRatchet {
+    /// SenderRatchet needs to be initialized, otherwise a
+    /// [`SecretTreeError::LibraryError`] is returned.
+    fn ratchet_mut(
+        &mut self,
+        index: LeafNodeIndex,
+        secret_type: SecretType,
+    ) -> Result<&mut SenderRatchet, SecretTreeError> {
         let sender_ratchets = match secret_type {
             SecretType::HandshakeSecret => &mut self.handshake_sender_ratchets,
             SecretType::ApplicationSecret => &mut self.application_sender_ratchets,
         };
-        sender_ratchets
-            .get_mut(index.usize())
-            .unwrap_or_else(|| panic!("SenderRatchets not initialized: {}", index.usize()))
-            .as_mut()
-            .expect("SecretTree not initialized")
+        sender_ratchets.get_mut(&index).ok_or_else(|| {
+            log::error!("SenderRatchets not initialized: {}", index.usize());
+            SecretTreeError::LibraryError
+        })
     }
 
     /// Returns an optional reference to a specific SenderRatchet
@@ -370,10 +547,10 @@ impl SecretTree {
             SecretType::HandshakeSecret => &self.handshake_sender_ratchets,
             SecretType::ApplicationSecret => &self.application_sender_ratchets,
         };
-        match sender_ratchets.get(index.usize()) {
-            Some(sender_ratchet_option) => Ok(sender_ratchet_option.as_ref()),
-            None => Err(SecretTreeError::IndexOutOfBounds),
+        if index.u32() >= self.size.leaf_count() {
+            return Err(SecretTreeError::IndexOutOfBounds);
         }
+        Ok(sender_ratchets.get(&index))
     }
 
     /// Derives the secrets for the child nodes in a SecretTree and blanks the
@@ -390,7 +567,7 @@ impl SecretTree {
             ciphersuite
         );
         let hash_len = ciphersuite.hash_length();
-        let node_secret = match &self.parent_nodes[index_in_tree.usize()] {
+        let node_secret = match self.parent_nodes.get(&index_in_tree) {
             Some(node) => &node.secret,
             // This function only gets called top to bottom, so this should not happen
             None => {
@@ -416,33 +593,45 @@ impl SecretTree {
         );
 
         // Populate left child
-        let value = Some(SecretTreeNode {
-            secret: left_secret,
-        });
-        match left_index {
-            TreeNodeIndex::Leaf(leaf_index) => {
-                self.leaf_nodes[leaf_index.usize()] = value;
-            }
-            TreeNodeIndex::Parent(parent_index) => {
-                self.parent_nodes[parent_index.usize()] = value;
-            }
-        }
+        self.set_node(
+            left_index,
+            SecretTreeNode {
+                secret: left_secret,
+            },
+        );
 
         // Populate right child
-        let value = Some(SecretTreeNode {
-            secret: right_secret,
-        });
-        match right_index {
+        self.set_node(
+            right_index,
+            SecretTreeNode {
+                secret: right_secret,
+            },
+        );
+
+        // Delete parent node
+        self.parent_nodes.remove(&index_in_tree);
+        Ok(())
+    }
+
+    /// Sets the secret of the node at `index`.
+    fn set_node(&mut self, index: TreeNodeIndex, node: SecretTreeNode) {
+        match index {
             TreeNodeIndex::Leaf(leaf_index) => {
-                self.leaf_nodes[leaf_index.usize()] = value;
+                self.leaf_nodes.insert(leaf_index, node);
             }
             TreeNodeIndex::Parent(parent_index) => {
-                self.parent_nodes[parent_index.usize()] = value;
+                self.parent_nodes.insert(parent_index, node);
             }
         }
+    }
 
-        // Delete parent node
-        self.parent_nodes[index_in_tree.usize()] = None;
-        Ok(())
+    /// Returns the number of nodes and sender ratchets that are currently
+    /// stored in the tree.
+    #[cfg(test)]
+    pub(crate) fn stored_entries(&self) -> usize {
+        self.leaf_nodes.len()
+            + self.parent_nodes.len()
+            + self.handshake_sender_ratchets.len()
+            + self.application_sender_ratchets.len()
     }
 }
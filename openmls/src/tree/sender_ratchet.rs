@@ -28,10 +28,17 @@ pub(crate) type Generation = u32;
 ///  - maximum_forward_distance:
 /// This parameter defines how many incoming messages can be skipped. This is useful if the DS
 /// drops application messages. The default value is 1000.
+///  - maximum_skipped_keys:
+/// This parameter limits the number of decryption secrets of skipped messages that are kept per
+/// sender within the `out_of_order_tolerance` window. If the limit is reached, the secrets of the
+/// oldest skipped messages are deleted first. This bounds the memory used by senders that skip
+/// many messages when the `out_of_order_tolerance` is generous. The default is no limit.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SenderRatchetConfiguration {
     out_of_order_tolerance: Generation,
     maximum_forward_distance: Generation,
+    #[serde(default)]
+    maximum_skipped_keys: Option<Generation>,
 }
 
 impl SenderRatchetConfiguration {
@@ -40,8 +47,16 @@ impl SenderRatchetConfiguration {
         Self {
             out_of_order_tolerance,
             maximum_forward_distance,
+            maximum_skipped_keys: None,
         }
     }
+
+    /// Limit the number of decryption secrets of skipped messages that are
+    /// kept per sender to `maximum_skipped_keys`.
+    pub fn with_maximum_skipped_keys(mut self, maximum_skipped_keys: Generation) -> Self {
+        self.maximum_skipped_keys = Some(maximum_skipped_keys);
+        self
+    }
     /// Get a reference to the sender ratchet configuration's out of order tolerance.
     pub fn out_of_order_tolerance(&self) -> Generation {
         self.out_of_order_tolerance
@@ -51,6 +66,12 @@ impl SenderRatchetConfiguration {
     pub fn maximum_forward_distance(&self) -> Generation {
         self.maximum_forward_distance
     }
+
+    /// Get the sender ratchet configuration's maximum number of skipped keys
+    /// per sender, if any.
+    pub fn maximum_skipped_keys(&self) -> Option<Generation> {
+        self.maximum_skipped_keys
+    }
 }
 
 impl Default for SenderRatchetConfiguration {
@@ -78,13 +99,61 @@ pub(crate) enum SenderRatchet {
 }
 
 impl SenderRatchet {
-    #[cfg(test)]
+    /// Returns the generation of the next key material of the ratchet.
     pub(crate) fn generation(&self) -> Generation {
         match self {
             SenderRatchet::EncryptionRatchet(enc_ratchet) => enc_ratchet.generation(),
             SenderRatchet::DecryptionRatchet(dec_ratchet) => dec_ratchet.generation(),
         }
     }
+
+    /// Returns the past generations for which the ratchet still retains key
+    /// material, in descending order.
+    pub(crate) fn retained_generations(&self) -> Vec<Generation> {
+        match self {
+            SenderRatchet::EncryptionRatchet(_) => vec![],
+            SenderRatchet::DecryptionRatchet(dec_ratchet) => dec_ratchet.retained_generations(),
+        }
+    }
+
+    /// Returns the number of past generations for which the ratchet still
+    /// retains key material.
+    pub(crate) fn retained_key_count(&self) -> usize {
+        match self {
+            SenderRatchet::EncryptionRatchet(_) => 0,
+            SenderRatchet::DecryptionRatchet(dec_ratchet) => dec_ratchet
+                .past_secrets
+                .iter()
+                .filter(|past_secret| past_secret.is_some())
+                .count(),
+        }
+    }
+
+    /// Merges `other`, another copy of this ratchet (e.g. a persisted one),
+    /// into this ratchet. The ratchet is never set back to an earlier
+    /// generation. See [`DecryptionRatchet::merge()`] for how decryption
+    /// ratchets are merged.
+    ///
+    /// Returns [`SecretTreeError::RatchetTypeError`] if the ratchets are not of
+    /// the same type.
+    pub(crate) fn merge(&mut self, other: SenderRatchet) -> Result<(), SecretTreeError> {
+        match (self, other) {
+            (
+                SenderRatchet::EncryptionRatchet(current),
+                SenderRatchet::EncryptionRatchet(other),
+            ) => {
+                if other.generation() > current.generation() {
+                    *current = other;
+                }
+            }
+            (
+                SenderRatchet::DecryptionRatchet(current),
+                SenderRatchet::DecryptionRatchet(other),
+            ) => current.merge(other),
+            _ => return Err(SecretTreeError::RatchetTypeError),
+        }
+        Ok(())
+    }
 }
 
 /// The core of both types of [`SenderRatchet`]. It contains the current head of
@@ -156,6 +225,15 @@ impl RatchetSecret {
         ))
     }
 
+    /// Returns a [`RatchetSecret`] without key material that can't be
+    /// ratcheted forward anymore.
+    pub(crate) fn exhausted() -> Self {
+        Self {
+            secret: Secret::default(),
+            generation: Generation::MAX,
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn set_generation(&mut self, generation: Generation) {
         self.generation = generation
@@ -187,7 +265,21 @@ impl DecryptionRatchet {
     /// bounds determined by the [`SenderRatchetConfiguration`].
     fn prune_past_secrets(&mut self, configuration: &SenderRatchetConfiguration) {
         self.past_secrets
-            .truncate(configuration.out_of_order_tolerance() as usize)
+            .truncate(configuration.out_of_order_tolerance() as usize);
+        if let Some(maximum_skipped_keys) = configuration.maximum_skipped_keys() {
+            // The oldest secrets are at the back of the queue. Delete them,
+            // but keep the entries to keep indexing consistent.
+            let mut retained = 0;
+            for past_secret in self.past_secrets.iter_mut() {
+                if past_secret.is_some() {
+                    if retained < maximum_skipped_keys {
+                        retained += 1;
+                    } else {
+                        *past_secret = None;
+                    }
+                }
+            }
+        }
     }
 
     /// Get the generation of the ratchet head.
@@ -195,6 +287,38 @@ impl DecryptionRatchet {
         self.ratchet_head.generation()
     }
 
+    /// Returns the past generations for which key material is retained, in
+    /// descending order.
+    pub(crate) fn retained_generations(&self) -> Vec<Generation> {
+        self.past_secrets
+            .iter()
+            .enumerate()
+            .filter(|(_, past_secret)| past_secret.is_some())
+            .map(|(index, _)| self.generation() - 1 - index as Generation)
+            .collect()
+    }
+
+    /// Merges `other`, another copy of this ratchet, into this ratchet.
+    /// Afterwards, the ratchet is at the later generation of the two and the
+    /// key material of a past generation is only kept if it was not used in
+    /// either copy.
+    pub(crate) fn merge(&mut self, mut other: DecryptionRatchet) {
+        if other.generation() > self.generation() {
+            std::mem::swap(self, &mut other);
+        }
+        // `other` is now at the same or an earlier generation than `self`.
+        // The entry for generation `g` is at index `generation() - 1 - g` of
+        // the past secrets.
+        let offset = (self.generation() - other.generation()) as usize;
+        for (index, past_secret) in other.past_secrets.iter().enumerate() {
+            if past_secret.is_none() {
+                if let Some(entry) = self.past_secrets.get_mut(index + offset) {
+                    *entry = None;
+                }
+            }
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn ratchet_secret_mut(&mut self) -> &mut RatchetSecret {
         &mut self.ratchet_head
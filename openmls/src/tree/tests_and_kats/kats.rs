@@ -1,6 +1,6 @@
 //! Tree test vectors
 
 pub mod kat_encryption;
-#[cfg(test)]
+#[cfg(any(feature = "test-vectors", test))]
 pub mod kat_message_protection;
 pub mod secret_tree;
@@ -76,7 +76,7 @@
 //! The extra factor of 2 in `2*N` ensures that only chains rooted at leaf nodes
 //! are tested.  The definitions of `ratchet_key` and `ratchet_nonce` are in the
 //! [Encryption
-//! Keys](https://github.com/mlswg/mls-protocol/blob/master/draft-ietf-mls-protocol.md#encryption-keys)
+//! Keys](https://www.rfc-editor.org/rfc/rfc9420.html#section-9.1)
 //! section of the specification.
 
 use std::convert::TryFrom;
@@ -62,6 +62,7 @@
 //!     * When protecting the Commit message, add the supplied confirmation tag
 
 use openmls_basic_credential::SignatureKeyPair;
+#[cfg(test)]
 use openmls_rust_crypto::OpenMlsRustCrypto;
 use openmls_traits::{types::SignatureScheme, OpenMlsCryptoProvider};
 use serde::{self, Deserialize, Serialize};
@@ -175,7 +176,6 @@ fn receiver_group(
     (group, credential_with_key, signer)
 }
 
-#[cfg(test)]
 pub fn run_test_vector(
     test: MessageProtectionTest,
     backend: &impl OpenMlsCryptoProvider,
@@ -231,7 +231,7 @@ pub fn run_test_vector(
             private
         }
         Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256 => hex_to_bytes(&test.signature_priv),
-        _ => unimplemented!(),
+        _ => return Err(format!("Unsupported signature scheme for {ciphersuite:?}.")),
     };
     let random_own_signature_key =
         SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
@@ -459,7 +459,7 @@ pub fn run_test_vector(
 
     // Application
     {
-        eprintln!("application_priv: {}", test.application_priv);
+        log::trace!("application_priv: {}", test.application_priv);
         let application = hex_to_bytes(&test.application);
         let application_priv =
             MlsMessageIn::tls_deserialize_exact(hex_to_bytes(&test.application_priv)).unwrap();
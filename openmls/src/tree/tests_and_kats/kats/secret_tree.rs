@@ -79,7 +79,111 @@ pub struct SecretTree {
     leaves: Vec<Vec<Leaf>>,
 }
 
-#[cfg(test)]
+/// Generate a secret tree test vector for `ciphersuite` with `n_leaves`
+/// leaves and the secrets of the given `generations` for each leaf.
+#[cfg(any(feature = "test-utils", test))]
+pub fn generate_test_vector(
+    ciphersuite: Ciphersuite,
+    n_leaves: u32,
+    generations: &[u32],
+    backend: &impl OpenMlsCryptoProvider,
+) -> SecretTree {
+    use openmls_traits::random::OpenMlsRand;
+
+    use crate::{
+        binary_tree::{array_representation::TreeSize, LeafNodeIndex},
+        schedule::{EncryptionSecret, SenderDataSecret},
+        tree::secret_tree::{SecretTree as SecretTreeState, SecretType},
+        versions::ProtocolVersion,
+    };
+
+    let random_bytes = || {
+        backend
+            .rand()
+            .random_vec(ciphersuite.hash_length())
+            .expect("Not enough randomness.")
+    };
+
+    // Sender data
+    let sender_data_secret_bytes = random_bytes();
+    let sender_data_secret = SenderDataSecret::from_slice(
+        &sender_data_secret_bytes,
+        ProtocolVersion::Mls10,
+        ciphersuite,
+    );
+    let ciphertext = random_bytes();
+    let sender_data_key = sender_data_secret
+        .derive_aead_key(backend, &ciphertext)
+        .expect("An unexpected error occurred.");
+    let sender_data_nonce = sender_data_secret
+        .derive_aead_nonce(ciphersuite, backend, &ciphertext)
+        .expect("An unexpected error occurred.");
+
+    // Leaves
+    let encryption_secret = random_bytes();
+    let mut generations = generations.to_vec();
+    generations.sort_unstable();
+    generations.dedup();
+    let leaves = (0..n_leaves)
+        .map(|leaf_index| {
+            let leaf_index = LeafNodeIndex::new(leaf_index);
+            let mut secret_tree = SecretTreeState::new(
+                EncryptionSecret::from_slice(
+                    &encryption_secret,
+                    ProtocolVersion::Mls10,
+                    ciphersuite,
+                ),
+                TreeSize::new(n_leaves),
+                leaf_index,
+            );
+            let mut leaf_generations = Vec::new();
+            let mut generations = generations.iter().peekable();
+            while let Some(&&generation) = generations.peek() {
+                let (handshake_generation, (handshake_key, handshake_nonce)) = secret_tree
+                    .secret_for_encryption(
+                        ciphersuite,
+                        backend,
+                        leaf_index,
+                        SecretType::HandshakeSecret,
+                    )
+                    .expect("An unexpected error occurred.");
+                let (_, (application_key, application_nonce)) = secret_tree
+                    .secret_for_encryption(
+                        ciphersuite,
+                        backend,
+                        leaf_index,
+                        SecretType::ApplicationSecret,
+                    )
+                    .expect("An unexpected error occurred.");
+                if handshake_generation == generation {
+                    leaf_generations.push(Leaf {
+                        generation,
+                        application_key: bytes_to_hex(application_key.as_slice()),
+                        application_nonce: bytes_to_hex(application_nonce.as_slice()),
+                        handshake_key: bytes_to_hex(handshake_key.as_slice()),
+                        handshake_nonce: bytes_to_hex(handshake_nonce.as_slice()),
+                    });
+                    generations.next();
+                }
+            }
+            leaf_generations
+        })
+        .collect();
+
+    SecretTree {
+        cipher_suite: ciphersuite as u16,
+        encryption_secret: bytes_to_hex(&encryption_secret),
+        sender_data: SenderData {
+            sender_data_secret: bytes_to_hex(&sender_data_secret_bytes),
+            ciphertext: bytes_to_hex(&ciphertext),
+            key: bytes_to_hex(sender_data_key.as_slice()),
+            nonce: bytes_to_hex(sender_data_nonce.as_slice()),
+        },
+        leaves,
+    }
+}
+
+#[cfg(any(feature = "test-vectors", test))]
 pub fn run_test_vector(
     test: SecretTree,
     backend: &impl OpenMlsCryptoProvider,
@@ -205,3 +309,13 @@ fn read_test_vectors_st(backend: &impl OpenMlsCryptoProvider) {
     }
     log::trace!("Finished test vector verification");
 }
+
+#[apply(backends)]
+fn generate_and_run_test_vectors_st(backend: &impl OpenMlsCryptoProvider) {
+    use openmls_traits::crypto::OpenMlsCrypto;
+
+    for &ciphersuite in backend.crypto().supported_ciphersuites().iter() {
+        let test_vector = generate_test_vector(ciphersuite, 10, &[0, 1, 15], backend);
+        run_test_vector(test_vector, backend).expect("Error while checking generated test vector.");
+    }
+}
@@ -260,3 +260,167 @@ fn secret_tree(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
         application_secret_nonce.as_slice()
     );
 }
+
+// This tests that the secret tree only stores the nodes needed for the
+// senders that were actually used.
+#[apply(ciphersuites_and_backends)]
+fn sparse_secret_tree(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let configuration = &SenderRatchetConfiguration::default();
+    let encryption_secret = EncryptionSecret::random(ciphersuite, backend);
+    let leaf_count = 1u32 << 16;
+    let mut secret_tree = SecretTree::new(
+        encryption_secret,
+        TreeSize::from_leaf_count(leaf_count),
+        LeafNodeIndex::new(0u32),
+    );
+
+    // Only the root secret is stored initially.
+    assert_eq!(secret_tree.stored_entries(), 1);
+
+    secret_tree
+        .secret_for_decryption(
+            ciphersuite,
+            backend,
+            LeafNodeIndex::new(1_000u32),
+            SecretType::ApplicationSecret,
+            0,
+            configuration,
+        )
+        .expect("Error getting decryption secret");
+
+    // Deriving down to the leaf leaves one secret per level of the tree, i.e.,
+    // the siblings of the direct path, plus the two sender ratchets.
+    assert_eq!(secret_tree.stored_entries(), 16 + 2);
+
+    assert_eq!(
+        secret_tree.secret_for_decryption(
+            ciphersuite,
+            backend,
+            LeafNodeIndex::new(leaf_count),
+            SecretType::ApplicationSecret,
+            0,
+            configuration,
+        ),
+        Err(SecretTreeError::IndexOutOfBounds)
+    );
+}
+
+// Sender indices are taken from messages of other members. Invalid indices
+// must result in an error instead of a panic.
+#[apply(ciphersuites_and_backends)]
+fn invalid_sender_indices(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let configuration = &SenderRatchetConfiguration::default();
+    let encryption_secret = EncryptionSecret::random(ciphersuite, backend);
+    let mut secret_tree = SecretTree::new(
+        encryption_secret,
+        TreeSize::from_leaf_count(4u32),
+        LeafNodeIndex::new(0u32),
+    );
+
+    for index in [4u32, 5, u32::MAX] {
+        assert_eq!(
+            secret_tree.secret_for_encryption(
+                ciphersuite,
+                backend,
+                LeafNodeIndex::new(index),
+                SecretType::HandshakeSecret,
+            ),
+            Err(SecretTreeError::IndexOutOfBounds)
+        );
+        assert_eq!(
+            secret_tree.secret_for_decryption(
+                ciphersuite,
+                backend,
+                LeafNodeIndex::new(index),
+                SecretType::HandshakeSecret,
+                0,
+                configuration,
+            ),
+            Err(SecretTreeError::IndexOutOfBounds)
+        );
+    }
+
+    // The own ratchet can't be used for decryption and the ratchets of other
+    // members can't be used for encryption.
+    assert_eq!(
+        secret_tree.secret_for_decryption(
+            ciphersuite,
+            backend,
+            LeafNodeIndex::new(0u32),
+            SecretType::ApplicationSecret,
+            0,
+            configuration,
+        ),
+        Err(SecretTreeError::RatchetTypeError)
+    );
+    assert_eq!(
+        secret_tree.secret_for_encryption(
+            ciphersuite,
+            backend,
+            LeafNodeIndex::new(1u32),
+            SecretType::ApplicationSecret,
+        ),
+        Err(SecretTreeError::RatchetTypeError)
+    );
+}
+
+// Secret trees that were serialized before nodes and sender ratchets were
+// stored sparsely use dense vectors with one optional entry per index. They
+// must still be loadable.
+#[apply(ciphersuites_and_backends)]
+fn legacy_serialization(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let configuration = &SenderRatchetConfiguration::default();
+    let encryption_secret = EncryptionSecret::random(ciphersuite, backend);
+    let mut secret_tree = SecretTree::new(
+        encryption_secret,
+        TreeSize::from_leaf_count(4u32),
+        LeafNodeIndex::new(0u32),
+    );
+    secret_tree
+        .secret_for_encryption(
+            ciphersuite,
+            backend,
+            LeafNodeIndex::new(0u32),
+            SecretType::HandshakeSecret,
+        )
+        .expect("Error getting encryption secret");
+    secret_tree
+        .secret_for_decryption(
+            ciphersuite,
+            backend,
+            LeafNodeIndex::new(2u32),
+            SecretType::ApplicationSecret,
+            0,
+            configuration,
+        )
+        .expect("Error getting decryption secret");
+
+    // Convert the maps into the dense vectors of the previous format.
+    let mut serialized = serde_json::to_value(&secret_tree).expect("Error serializing tree");
+    for (field, length) in [
+        ("leaf_nodes", 4),
+        ("parent_nodes", 3),
+        ("handshake_sender_ratchets", 4),
+        ("application_sender_ratchets", 4),
+    ] {
+        let entries = serialized[field]
+            .as_object()
+            .expect("Entries are not stored as a map")
+            .clone();
+        let mut dense = vec![serde_json::Value::Null; length];
+        for (index, entry) in entries {
+            dense[index.parse::<usize>().expect("Invalid index")] = entry;
+        }
+        serialized[field] = serde_json::Value::Array(dense);
+    }
+
+    let deserialized: SecretTree =
+        serde_json::from_value(serialized).expect("Error deserializing legacy tree");
+    assert!(deserialized == secret_tree);
+
+    // The current format can be loaded as well.
+    let serialized = serde_json::to_string(&secret_tree).expect("Error serializing tree");
+    let deserialized: SecretTree =
+        serde_json::from_str(&serialized).expect("Error deserializing tree");
+    assert!(deserialized == secret_tree);
+}
@@ -128,6 +128,29 @@ fn test_forward_secrecy(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
     }
 }
 
+// Test the limit of skipped keys per sender
+#[apply(ciphersuites_and_backends)]
+fn test_maximum_skipped_keys(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let configuration = &SenderRatchetConfiguration::new(10, 1000).with_maximum_skipped_keys(2);
+    let secret = Secret::random(ciphersuite, backend, ProtocolVersion::Mls10)
+        .expect("Not enough randomness.");
+    let mut ratchet = DecryptionRatchet::new(secret);
+
+    // Skip generations 0 to 4. Only the keys of the newest two are kept.
+    ratchet
+        .secret_for_decryption(ciphersuite, backend, 5, configuration)
+        .expect("Error ratcheting forward.");
+    assert_eq!(ratchet.retained_generations(), vec![4, 3]);
+
+    let err = ratchet
+        .secret_for_decryption(ciphersuite, backend, 2, configuration)
+        .expect_err("Retrieved a deleted key.");
+    assert_eq!(err, SecretTreeError::SecretReuseError);
+    ratchet
+        .secret_for_decryption(ciphersuite, backend, 3, configuration)
+        .expect("Error retrieving a retained key.");
+}
+
 // Test if a sender ratchet overflow is caught
 #[test]
 fn sender_ratchet_generation_overflow() {
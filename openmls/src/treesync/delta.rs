@@ -0,0 +1,97 @@
+//! This module contains the [`RatchetTreeDelta`], which allows syncing a
+//! ratchet tree between two epochs without transferring the full tree.
+//!
+//! A delivery service that stores the ratchet tree of a group can compute the
+//! delta between a tree a client already has, e.g., from an earlier epoch, and
+//! the current tree. The client applies the delta to its tree to obtain a
+//! [`RatchetTreeIn`] of the current tree, which is verified like any other
+//! incoming ratchet tree, e.g., when joining the group.
+
+use serde::{Deserialize, Serialize};
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
+
+use super::{errors::RatchetTreeDeltaError, node::NodeIn, RatchetTree, RatchetTreeIn};
+use crate::binary_tree::array_representation::MAX_TREE_SIZE;
+
+/// The nodes of a ratchet tree that changed between two versions of the tree.
+///
+/// A delta is created with [`RatchetTree::delta()`] and applied with
+/// [`RatchetTreeIn::apply_delta()`].
+#[derive(
+    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct RatchetTreeDelta {
+    node_count: u32,
+    changed_nodes: Vec<ChangedNode>,
+}
+
+#[derive(
+    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+struct ChangedNode {
+    node_index: u32,
+    node: Option<NodeIn>,
+}
+
+impl RatchetTreeDelta {
+    /// Returns the number of nodes in the tree after applying the delta.
+    pub fn node_count(&self) -> u32 {
+        self.node_count
+    }
+
+    /// Returns the number of nodes that are changed by the delta.
+    pub fn changed_node_count(&self) -> usize {
+        self.changed_nodes.len()
+    }
+}
+
+impl RatchetTree {
+    /// Computes the delta that turns this tree into the `newer` tree, e.g., a
+    /// tree of a later epoch of the same group.
+    pub fn delta(&self, newer: &RatchetTree) -> RatchetTreeDelta {
+        let changed_nodes = newer
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(node_index, node)| self.0.get(*node_index).unwrap_or(&None) != *node)
+            .map(|(node_index, node)| ChangedNode {
+                node_index: node_index as u32,
+                node: node.clone().map(NodeIn::from),
+            })
+            .collect();
+
+        RatchetTreeDelta {
+            node_count: newer.0.len() as u32,
+            changed_nodes,
+        }
+    }
+}
+
+impl RatchetTreeIn {
+    /// Applies the given [`RatchetTreeDelta`] to this tree.
+    ///
+    /// The resulting tree has to be verified before it can be used, e.g.,
+    /// with [`RatchetTreeIn::verify()`] or by joining the group with it.
+    pub fn apply_delta(self, delta: RatchetTreeDelta) -> Result<Self, RatchetTreeDeltaError> {
+        if delta.node_count > MAX_TREE_SIZE {
+            return Err(RatchetTreeDeltaError::TreeTooLarge);
+        }
+
+        let mut nodes = self.0;
+        nodes.resize(delta.node_count as usize, None);
+
+        let mut previous_index = None;
+        for changed_node in delta.changed_nodes {
+            // Node indices must be strictly increasing and within the tree.
+            if previous_index >= Some(changed_node.node_index)
+                || changed_node.node_index >= delta.node_count
+            {
+                return Err(RatchetTreeDeltaError::InvalidNodeIndex);
+            }
+            previous_index = Some(changed_node.node_index);
+            nodes[changed_node.node_index as usize] = changed_node.node;
+        }
+
+        Ok(Self(nodes))
+    }
+}
@@ -17,6 +17,7 @@
 //! functions that are not expected to fail and throw an error, will still
 //! return a [`Result`] since they may throw a
 //! [`LibraryError`](TreeSyncDiffError::LibraryError).
+
 use std::collections::HashSet;
 
 use log::debug;
@@ -37,7 +38,7 @@ use super::{
 use crate::{
     binary_tree::{
         array_representation::{
-            LeafNodeIndex, ParentNodeIndex, TreeNodeIndex, TreeSize, MIN_TREE_SIZE,
+            subtree_leaves, LeafNodeIndex, ParentNodeIndex, TreeNodeIndex, TreeSize, MIN_TREE_SIZE,
         },
         MlsBinaryTreeDiff, StagedMlsBinaryTreeDiff,
     },
@@ -164,13 +165,13 @@ impl<'a> TreeSyncDiff<'a> {
 
     /// Returns the index of the last full leaf in the tree.
     fn rightmost_full_leaf(&self) -> LeafNodeIndex {
-        let mut index = LeafNodeIndex::new(0);
-        for (leaf_index, leaf) in self.diff.leaves() {
-            if leaf.node().as_ref().is_some() {
-                index = leaf_index;
-            }
-        }
-        index
+        // Search from the right, since the rightmost full leaf is usually
+        // close to the end of the tree.
+        (0..self.diff.leaf_count())
+            .rev()
+            .map(LeafNodeIndex::new)
+            .find(|&leaf_index| self.diff.leaf(leaf_index).node().is_some())
+            .unwrap_or_else(|| LeafNodeIndex::new(0))
     }
 
     /// Returns the number of leaves in the tree that would result from merging
@@ -198,7 +199,7 @@ impl<'a> TreeSyncDiff<'a> {
     /// Find and return the index of either the left-most blank leaf, or, if
     /// there are no blank leaves, the leaf count.
     pub(crate) fn free_leaf_index(&self) -> LeafNodeIndex {
-        let leaf_count = self.diff.leaves().count() as u32;
+        let leaf_count = self.diff.leaf_count();
 
         // Search for blank leaves in existing leaves
         for (leaf_index, leaf_id) in self.diff.leaves() {
@@ -238,7 +239,7 @@ impl<'a> TreeSyncDiff<'a> {
         for parent_index in self.diff.direct_path(leaf_index) {
             // We know that the nodes from the direct path are in the tree
             let tsn = self.diff.parent_mut(parent_index);
-            if let Some(ref mut parent_node) = tsn.node_mut() {
+            if let Some(parent_node) = tsn.node_mut() {
                 parent_node.add_unmerged_leaf(leaf_index);
             }
         }
@@ -295,7 +296,7 @@ impl<'a> TreeSyncDiff<'a> {
         ciphersuite: Ciphersuite,
         group_id: GroupId,
         leaf_index: LeafNodeIndex,
-    ) -> Result<UpdatePathResult, LibraryError> {
+    ) -> Result<UpdatePathResult, PublicTreeError> {
         debug_assert!(
             self.leaf(leaf_index).is_some(),
             "Tree diff is missing own leaf"
@@ -490,14 +491,14 @@ impl<'a> TreeSyncDiff<'a> {
                         for leaf_index in parent.unmerged_leaves() {
                             if !excluded_indices.contains(&leaf_index) {
                                 let leaf = self.diff.leaf(*leaf_index);
-                                // TODO #800: unmerged leaves should be checked
+                                // TODO #800: unmerged leaves should be checked.
+                                // Until then, blank unmerged leaves in trees
+                                // received from other members are skipped.
                                 if let Some(leaf_node) = leaf.node() {
                                     resolution.push((
                                         TreeNodeIndex::Leaf(*leaf_index),
                                         NodeReference::Leaf(leaf_node),
                                     ))
-                                } else {
-                                    debug_assert!(false, "Unmerged leaves should not be blank.");
                                 }
                             }
                         }
@@ -682,9 +683,12 @@ impl<'a> TreeSyncDiff<'a> {
         })
     }
 
-    /// Helper function to compute and set the tree hash of the given node and
-    /// all nodes below it in the tree. The leaf nodes in `exclusion_list` are
-    /// not included in the tree hash.
+    /// Helper function to compute the tree hash of the given node. The leaf
+    /// nodes in `exclusion_list` are not included in the tree hash.
+    ///
+    /// The cached tree hashes of subtrees that were not changed in this diff
+    /// and that don't contain any excluded leaves are used instead of
+    /// recomputing them.
     pub(super) fn compute_tree_hash(
         &self,
         backend: &impl OpenMlsCryptoProvider,
@@ -692,6 +696,15 @@ impl<'a> TreeSyncDiff<'a> {
         node_index: TreeNodeIndex,
         exclusion_list: &HashSet<&LeafNodeIndex>,
     ) -> Result<Vec<u8>, LibraryError> {
+        if let Some(tree_hash) = self.cached_tree_hash(node_index) {
+            let (first_leaf, last_leaf) = subtree_leaves(node_index);
+            if !exclusion_list
+                .iter()
+                .any(|&&leaf_index| first_leaf <= leaf_index && leaf_index <= last_leaf)
+            {
+                return Ok(tree_hash.to_vec());
+            }
+        }
         match node_index {
             TreeNodeIndex::Leaf(leaf_index) => {
                 let leaf = self.diff.leaf(leaf_index);
@@ -719,23 +732,78 @@ impl<'a> TreeSyncDiff<'a> {
         }
     }
 
+    /// Returns the cached tree hash of the given node if neither the node nor
+    /// any node below it was changed in this diff.
+    fn cached_tree_hash(&self, node_index: TreeNodeIndex) -> Option<&[u8]> {
+        if !self.diff.is_subtree_unchanged(node_index) {
+            return None;
+        }
+        match node_index {
+            TreeNodeIndex::Leaf(leaf_index) => self.diff.leaf(leaf_index).tree_hash(),
+            TreeNodeIndex::Parent(parent_index) => self.diff.parent(parent_index).tree_hash(),
+        }
+    }
+
+    /// Helper function to compute the tree hash of the given node and all
+    /// nodes below it and to cache the results in the nodes. Only the tree
+    /// hashes of nodes that were changed in this diff, that are above a
+    /// changed node or that don't have a cached tree hash yet are computed.
+    fn compute_and_cache_tree_hash(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        ciphersuite: Ciphersuite,
+        node_index: TreeNodeIndex,
+    ) -> Result<Vec<u8>, LibraryError> {
+        if let Some(tree_hash) = self.cached_tree_hash(node_index) {
+            return Ok(tree_hash.to_vec());
+        }
+        match node_index {
+            TreeNodeIndex::Leaf(leaf_index) => {
+                let leaf = self.diff.leaf_mut(leaf_index);
+                let tree_hash = leaf.compute_tree_hash(backend, ciphersuite, leaf_index)?;
+                leaf.set_tree_hash(tree_hash.clone());
+                Ok(tree_hash)
+            }
+            TreeNodeIndex::Parent(parent_index) => {
+                let left_child = self.diff.left_child(parent_index);
+                let left_hash =
+                    self.compute_and_cache_tree_hash(backend, ciphersuite, left_child)?;
+                let right_child = self.diff.right_child(parent_index);
+                let right_hash =
+                    self.compute_and_cache_tree_hash(backend, ciphersuite, right_child)?;
+
+                let node = self.diff.parent_mut(parent_index);
+                let tree_hash = node.compute_tree_hash(
+                    backend,
+                    ciphersuite,
+                    left_hash,
+                    right_hash,
+                    &HashSet::new(),
+                )?;
+                node.set_tree_hash(tree_hash.clone());
+                Ok(tree_hash)
+            }
+        }
+    }
+
     /// Return a reference to the leaf with the given index.
     pub(crate) fn leaf(&self, index: LeafNodeIndex) -> Option<&LeafNode> {
-        self.diff.leaf(index).node().as_ref()
+        self.diff.leaf(index).node()
     }
 
     /// Return a mutable reference to the leaf with the given index.
     pub(crate) fn leaf_mut(&mut self, index: LeafNodeIndex) -> Option<&mut LeafNode> {
-        self.diff.leaf_mut(index).node_mut().as_mut()
+        self.diff.leaf_mut(index).node_mut()
     }
 
-    /// Compute and set the tree hash of all nodes in the tree.
+    /// Compute and set the tree hash of all nodes in the tree. Tree hashes of
+    /// subtrees that were not changed in this diff are taken from the cache.
     pub(crate) fn compute_tree_hashes(
         &mut self,
         backend: &impl OpenMlsCryptoProvider,
         ciphersuite: Ciphersuite,
     ) -> Result<Vec<u8>, LibraryError> {
-        self.compute_tree_hash(backend, ciphersuite, self.diff.root(), &HashSet::new())
+        self.compute_and_cache_tree_hash(backend, ciphersuite, self.diff.root())
     }
 
     /// Returns the position of the subtree root shared by both given indices in
@@ -823,7 +891,7 @@ impl<'a> TreeSyncDiff<'a> {
 
         // Get the first leaf.
         if let Some(leaf) = leaves.next() {
-            nodes.push(leaf.node().clone().map(Node::LeafNode));
+            nodes.push(leaf.node().cloned().map(Node::LeafNode));
         } else {
             // The tree was empty.
             return RatchetTree::trimmed(vec![]);
@@ -848,8 +916,8 @@ impl<'a> TreeSyncDiff<'a> {
 
         // Interleave the leaves and parents.
         for (leaf, parent) in leaves.zip(parents) {
-            nodes.push(parent.node().clone().map(Node::ParentNode));
-            nodes.push(leaf.node().clone().map(Node::LeafNode));
+            nodes.push(parent.node().cloned().map(Node::ParentNode));
+            nodes.push(leaf.node().cloned().map(Node::LeafNode));
         }
 
         RatchetTree::trimmed(nodes)
@@ -898,7 +966,7 @@ impl<'a> TreeSyncDiff<'a> {
                 .into_iter()
                 .filter_map(move |parent_index| {
                     // Filter out all blanks.
-                    if let Some(node) = self.diff.parent(parent_index).node().as_ref() {
+                    if let Some(node) = self.diff.parent(parent_index).node() {
                         // Filter all nodes where our leaf is an unmerged leaf.
                         if !node.unmerged_leaves().contains(&leaf_index) {
                             Some(node.encryption_key())
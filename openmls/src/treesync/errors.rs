@@ -153,6 +153,35 @@ pub enum TreeSyncFromNodesError {
     RatchetTreeError(#[from] RatchetTreeError),
 }
 
+/// Ratchet tree verification error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum RatchetTreeVerificationError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`RatchetTreeError`] for more details.
+    #[error(transparent)]
+    RatchetTreeError(#[from] RatchetTreeError),
+    /// See [`TreeSyncFromNodesError`] for more details.
+    #[error(transparent)]
+    TreeSyncFromNodesError(#[from] TreeSyncFromNodesError),
+    /// Found two leaves with the same signature key.
+    #[error("Found two leaves with the same signature key.")]
+    DuplicateSignatureKey,
+    /// Found two nodes with the same encryption key.
+    #[error("Found two nodes with the same encryption key.")]
+    DuplicateEncryptionKey,
+    /// The signer of the group info is not in the tree.
+    #[error("The signer of the group info is not in the tree.")]
+    UnknownSigner,
+    /// The signature of the group info is invalid.
+    #[error("The signature of the group info is invalid.")]
+    InvalidGroupInfoSignature,
+    /// The tree hash doesn't match the one in the group info.
+    #[error("The tree hash doesn't match the one in the group info.")]
+    TreeHashMismatch,
+}
+
 /// TreeSync parent hash error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub(crate) enum TreeSyncParentHashError {
@@ -185,6 +214,17 @@ pub(crate) enum TreeSyncDiffError {
     CreationError(#[from] MlsBinaryTreeError),
 }
 
+/// Leaf node creation error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub(crate) enum LeafNodeCreationError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+}
+
 /// TreeKem error
 #[derive(Error, Debug, PartialEq, Clone)]
 #[allow(clippy::enum_variant_names)]
@@ -264,3 +304,14 @@ pub enum UpdatePathError {
     #[error(transparent)]
     SignatureError(#[from] SignatureError),
 }
+
+/// Errors that can happen when applying a [`RatchetTreeDelta`](super::RatchetTreeDelta).
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum RatchetTreeDeltaError {
+    /// The delta exceeds the maximum tree size.
+    #[error("The delta exceeds the maximum tree size.")]
+    TreeTooLarge,
+    /// The node indices in the delta are not sorted or out of range.
+    #[error("The node indices in the delta are not sorted or out of range.")]
+    InvalidNodeIndex,
+}
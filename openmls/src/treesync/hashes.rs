@@ -14,7 +14,7 @@ use super::{node::parent_node::ParentNode, LeafNode};
 /// computation.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 /// HPKEPublicKey encryption_key;
 ///     opaque parent_hash<V>;
@@ -61,7 +61,7 @@ impl<'a> ParentHashInput<'a> {
 /// Helper struct that can be serialized in the course of tree hash computation.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// enum {
 ///     reserved(0),
 ///     leaf(1),
@@ -81,7 +81,7 @@ enum NodeType<'a> {
 /// Helper struct that can be serialized in the course of tree hash computation.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// struct {
 ///   NodeType node_type;
 /// select (TreeHashInput.node_type) {
@@ -140,7 +140,7 @@ impl<'a> TreeHashInput<'a> {
 /// Helper struct that can be serialized in the course of tree hash computation.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// struct {
 ///     uint32 leaf_index;
 ///     optional<LeafNode> leaf_node;
@@ -155,7 +155,7 @@ struct LeafNodeHashInput<'a> {
 /// Helper struct that can be serialized in the course of tree hash computation.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// struct {
 ///     optional<ParentNode> parent_node;
 ///     opaque left_hash<V>;
@@ -0,0 +1,62 @@
+//! This module contains the [`MemberIndex`], which maps the identities and
+//! signature keys of the members of a [`TreeSync`] instance to their leaf
+//! indices.
+//!
+//! The index is rebuilt whenever a diff is merged into the tree, so that
+//! roster lookups don't have to walk the tree.
+
+use std::collections::HashMap;
+
+use crate::binary_tree::LeafNodeIndex;
+
+use super::LeafNode;
+
+#[cfg(doc)]
+use super::TreeSync;
+
+/// Index from the identity and the signature key of each member to its leaf
+/// index.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct MemberIndex {
+    // A client can be a member multiple times, e.g., with different devices.
+    // The leaf indices of an identity are in ascending order.
+    by_identity: HashMap<Vec<u8>, Vec<LeafNodeIndex>>,
+    // Signature keys are unique within a group.
+    by_signature_key: HashMap<Vec<u8>, LeafNodeIndex>,
+}
+
+impl MemberIndex {
+    /// Builds the index from the full leaves of a tree in ascending order of
+    /// their leaf indices.
+    pub(crate) fn new<'a>(leaves: impl Iterator<Item = (LeafNodeIndex, &'a LeafNode)>) -> Self {
+        let mut index = Self::default();
+        for (leaf_index, leaf) in leaves {
+            index
+                .by_identity
+                .entry(leaf.credential().identity().to_vec())
+                .or_default()
+                .push(leaf_index);
+            index
+                .by_signature_key
+                .insert(leaf.signature_key().as_slice().to_vec(), leaf_index);
+        }
+        index
+    }
+
+    /// Returns the leaf indices of all members with the given identity in
+    /// ascending order.
+    pub(crate) fn leaf_indices_by_identity(&self, identity: &[u8]) -> &[LeafNodeIndex] {
+        self.by_identity
+            .get(identity)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the leaf index of the member with the given signature key.
+    pub(crate) fn leaf_index_by_signature_key(
+        &self,
+        signature_key: &[u8],
+    ) -> Option<LeafNodeIndex> {
+        self.by_signature_key.get(signature_key).copied()
+    }
+}
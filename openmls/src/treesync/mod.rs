@@ -24,6 +24,7 @@ use openmls_rust_crypto::OpenMlsRustCrypto;
 use rstest::*;
 #[cfg(test)]
 use rstest_reuse::apply;
+use std::collections::HashSet;
 #[cfg(any(feature = "test-utils", test))]
 use std::fmt;
 
@@ -33,12 +34,14 @@ use openmls_traits::{
     types::{Ciphersuite, CryptoError},
     OpenMlsCryptoProvider,
 };
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
 
 use self::{
     diff::{StagedTreeSyncDiff, TreeSyncDiff},
+    member_index::MemberIndex,
     node::{
         leaf_node::{
             Capabilities, LeafNodeSource, Lifetime, NewLeafNodeParams, TreeInfoTbs, TreePosition,
@@ -65,13 +68,19 @@ use crate::{
     error::LibraryError,
     extensions::Extensions,
     framing::SenderError,
-    group::{config::CryptoConfig, GroupId, Member},
-    messages::{PathSecret, PathSecretError},
+    group::{config::CryptoConfig, GroupEpoch, GroupId, Member},
+    messages::{
+        group_info::{GroupInfo, VerifiableGroupInfo},
+        PathSecret, PathSecretError,
+    },
     schedule::CommitSecret,
 };
 
 // Private
+mod delta;
 mod hashes;
+mod member_index;
+mod view;
 use errors::*;
 
 // Crate
@@ -89,7 +98,9 @@ pub use node::encryption_keys::test_utils;
 pub use node::encryption_keys::EncryptionKey;
 
 // Public re-exports
+pub use delta::RatchetTreeDelta;
 pub use node::{leaf_node::LeafNode, parent_node::ParentNode, Node};
+pub use view::{RatchetTreeView, TreeNodeView};
 
 // Tests
 #[cfg(any(feature = "test-utils", test))]
@@ -140,7 +151,7 @@ impl RatchetTree {
     /// Create a new [`RatchetTree`] from a vector of nodes.
     pub(crate) fn try_from_nodes(
         ciphersuite: Ciphersuite,
-        crypto: &impl OpenMlsCrypto,
+        crypto: &(impl OpenMlsCrypto + Sync),
         nodes: Vec<Option<NodeIn>>,
         group_id: &GroupId,
     ) -> Result<Self, RatchetTreeError> {
@@ -159,58 +170,126 @@ impl RatchetTree {
             Some(Some(_)) => {
                 // The ratchet tree is not empty, i.e., has a last node, and the last node is not blank.
 
-                // Verify the nodes.
-                let mut verified_nodes = Vec::new();
-                for (index, node) in nodes.into_iter().enumerate() {
-                    let verified_node = match (index % 2, node) {
-                        // Even indices must be leaf nodes.
-                        (0, Some(NodeIn::LeafNode(leaf_node))) => {
-                            let tree_position = TreePosition::new(
-                                group_id.clone(),
-                                LeafNodeIndex::new((index / 2) as u32),
-                            );
-                            let verifiable_leaf_node = leaf_node.into_verifiable_leaf_node();
-                            let signature_key = verifiable_leaf_node
-                                .signature_key()
-                                .clone()
-                                .into_signature_public_key_enriched(
-                                    ciphersuite.signature_algorithm(),
+                // Verify the nodes. The leaf node signatures are independent
+                // of each other and are thus verified in parallel.
+                let verified_nodes = nodes
+                    .into_par_iter()
+                    .enumerate()
+                    .map(|(index, node)| {
+                        Ok(match (index % 2, node) {
+                            // Even indices must be leaf nodes.
+                            (0, Some(NodeIn::LeafNode(leaf_node))) => {
+                                let tree_position = TreePosition::new(
+                                    group_id.clone(),
+                                    LeafNodeIndex::new((index / 2) as u32),
                                 );
-                            Some(Node::LeafNode(match verifiable_leaf_node {
-                                VerifiableLeafNode::KeyPackage(leaf_node) => leaf_node
-                                    .verify(crypto, &signature_key)
-                                    .map_err(|_| RatchetTreeError::InvalidNodeSignature)?,
-                                VerifiableLeafNode::Update(mut leaf_node) => {
-                                    leaf_node.add_tree_position(tree_position);
-                                    leaf_node
-                                        .verify(crypto, &signature_key)
-                                        .map_err(|_| RatchetTreeError::InvalidNodeSignature)?
-                                }
-                                VerifiableLeafNode::Commit(mut leaf_node) => {
-                                    leaf_node.add_tree_position(tree_position);
-                                    leaf_node
+                                let verifiable_leaf_node = leaf_node.into_verifiable_leaf_node();
+                                let signature_key = verifiable_leaf_node
+                                    .signature_key()
+                                    .clone()
+                                    .into_signature_public_key_enriched(
+                                        ciphersuite.signature_algorithm(),
+                                    );
+                                Some(Node::LeafNode(match verifiable_leaf_node {
+                                    VerifiableLeafNode::KeyPackage(leaf_node) => leaf_node
                                         .verify(crypto, &signature_key)
-                                        .map_err(|_| RatchetTreeError::InvalidNodeSignature)?
-                                }
-                            }))
-                        }
-                        // Odd indices must be parent nodes.
-                        (1, Some(NodeIn::ParentNode(parent_node))) => {
-                            Some(Node::ParentNode(parent_node))
-                        }
-                        // Blank nodes.
-                        (_, None) => None,
-                        // All other cases are invalid.
-                        _ => {
-                            return Err(RatchetTreeError::WrongNodeType);
-                        }
-                    };
-                    verified_nodes.push(verified_node);
-                }
+                                        .map_err(|_| RatchetTreeError::InvalidNodeSignature)?,
+                                    VerifiableLeafNode::Update(mut leaf_node) => {
+                                        leaf_node.add_tree_position(tree_position);
+                                        leaf_node
+                                            .verify(crypto, &signature_key)
+                                            .map_err(|_| RatchetTreeError::InvalidNodeSignature)?
+                                    }
+                                    VerifiableLeafNode::Commit(mut leaf_node) => {
+                                        leaf_node.add_tree_position(tree_position);
+                                        leaf_node
+                                            .verify(crypto, &signature_key)
+                                            .map_err(|_| RatchetTreeError::InvalidNodeSignature)?
+                                    }
+                                }))
+                            }
+                            // Odd indices must be parent nodes.
+                            (1, Some(NodeIn::ParentNode(parent_node))) => {
+                                Some(Node::ParentNode(parent_node))
+                            }
+                            // Blank nodes.
+                            (_, None) => None,
+                            // All other cases are invalid.
+                            _ => {
+                                return Err(RatchetTreeError::WrongNodeType);
+                            }
+                        })
+                    })
+                    .collect::<Result<Vec<_>, RatchetTreeError>>()?;
                 Ok(Self::trimmed(verified_nodes))
             }
         }
     }
+
+    /// Verify the ratchet tree against the given [`VerifiableGroupInfo`]
+    /// without joining the group.
+    ///
+    /// See [`RatchetTreeIn::verify()`] for the checks that are performed.
+    pub fn verify(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        group_info: &VerifiableGroupInfo,
+    ) -> Result<(), RatchetTreeVerificationError> {
+        // The leaf node signatures are re-verified, since the tree might have
+        // been loaded from storage.
+        RatchetTreeIn::from(self.clone())
+            .verify(backend, group_info)
+            .map(|_| ())
+    }
+
+    /// Perform all checks of [`RatchetTreeIn::verify()`] that go beyond the
+    /// verification of the leaf node signatures.
+    fn verify_against_group_info(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        group_info: &VerifiableGroupInfo,
+    ) -> Result<(), RatchetTreeVerificationError> {
+        let ciphersuite = group_info.ciphersuite();
+
+        // Signature keys must be unique among all leaves and encryption keys
+        // must be unique among all non-blank nodes.
+        let mut signature_keys = HashSet::new();
+        let mut encryption_keys = HashSet::new();
+        for node in self.0.iter().flatten() {
+            let encryption_key = match node {
+                Node::LeafNode(leaf_node) => {
+                    if !signature_keys.insert(leaf_node.signature_key().as_slice()) {
+                        return Err(RatchetTreeVerificationError::DuplicateSignatureKey);
+                    }
+                    leaf_node.encryption_key()
+                }
+                Node::ParentNode(parent_node) => parent_node.encryption_key(),
+            };
+            if !encryption_keys.insert(encryption_key.as_slice()) {
+                return Err(RatchetTreeVerificationError::DuplicateEncryptionKey);
+            }
+        }
+
+        // This verifies the parent hashes and computes the tree hash.
+        let treesync = TreeSync::from_ratchet_tree(backend, ciphersuite, self.clone())?;
+
+        let signer_signature_key = treesync
+            .leaf(group_info.signer())
+            .ok_or(RatchetTreeVerificationError::UnknownSigner)?
+            .signature_key()
+            .clone()
+            .into_signature_public_key_enriched(ciphersuite.signature_algorithm());
+        let group_info: GroupInfo = group_info
+            .clone()
+            .verify(backend.crypto(), &signer_signature_key)
+            .map_err(|_| RatchetTreeVerificationError::InvalidGroupInfoSignature)?;
+
+        if treesync.tree_hash() != group_info.group_context().tree_hash() {
+            return Err(RatchetTreeVerificationError::TreeHashMismatch);
+        }
+
+        Ok(())
+    }
 }
 
 /// A ratchet tree made of unverified nodes. This is used for deserialization
@@ -218,6 +297,7 @@ impl RatchetTree {
 #[derive(
     PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RatchetTreeIn(Vec<Option<NodeIn>>);
 
 impl RatchetTreeIn {
@@ -226,12 +306,34 @@ impl RatchetTreeIn {
     pub fn into_verified(
         self,
         ciphersuite: Ciphersuite,
-        crypto: &impl OpenMlsCrypto,
+        crypto: &(impl OpenMlsCrypto + Sync),
         group_id: &GroupId,
     ) -> Result<RatchetTree, RatchetTreeError> {
         RatchetTree::try_from_nodes(ciphersuite, crypto, self.0, group_id)
     }
 
+    /// Verify the ratchet tree against the given [`VerifiableGroupInfo`]
+    /// without joining the group, e.g., when a delivery service stores the
+    /// tree on behalf of its clients.
+    ///
+    /// This verifies the signatures of all leaf nodes, the parent hashes,
+    /// the uniqueness of signature and encryption keys in the tree, the
+    /// signature of the group info and that the tree hash matches the one in
+    /// the group info. Returns the verified [`RatchetTree`] on success.
+    pub fn verify(
+        self,
+        backend: &impl OpenMlsCryptoProvider,
+        group_info: &VerifiableGroupInfo,
+    ) -> Result<RatchetTree, RatchetTreeVerificationError> {
+        let ratchet_tree = self.into_verified(
+            group_info.ciphersuite(),
+            backend.crypto(),
+            group_info.group_id(),
+        )?;
+        ratchet_tree.verify_against_group_info(backend, group_info)?;
+        Ok(ratchet_tree)
+    }
+
     fn from_ratchet_tree(ratchet_tree: RatchetTree) -> Self {
         let nodes = ratchet_tree
             .0
@@ -241,7 +343,7 @@ impl RatchetTreeIn {
         Self(nodes)
     }
 
-    #[cfg(test)]
+    #[cfg(any(feature = "test-vectors", test))]
     pub(crate) fn from_nodes(nodes: Vec<Option<NodeIn>>) -> Self {
         Self(nodes)
     }
@@ -253,6 +355,33 @@ impl From<RatchetTree> for RatchetTreeIn {
     }
 }
 
+/// A source for ratchet trees of groups that don't use the ratchet tree
+/// extension.
+///
+/// When joining a group from a [`Welcome`](crate::messages::Welcome) that
+/// doesn't contain a ratchet tree extension, OpenMLS asks the provider for the
+/// ratchet tree of the group with the given [`GroupId`] in the given epoch,
+/// e.g. to fetch it from the delivery service. The tree is verified against
+/// the tree hash in the [`GroupInfo`](crate::messages::group_info::GroupInfo)
+/// before it is used.
+///
+/// The trait is implemented for closures with the same signature as
+/// [`RatchetTreeProvider::ratchet_tree()`].
+pub trait RatchetTreeProvider {
+    /// Returns the ratchet tree of the group with `group_id` in `epoch` or
+    /// `None` if it is not available.
+    fn ratchet_tree(&self, group_id: &GroupId, epoch: GroupEpoch) -> Option<RatchetTreeIn>;
+}
+
+impl<F> RatchetTreeProvider for F
+where
+    F: Fn(&GroupId, GroupEpoch) -> Option<RatchetTreeIn>,
+{
+    fn ratchet_tree(&self, group_id: &GroupId, epoch: GroupEpoch) -> Option<RatchetTreeIn> {
+        self(group_id, epoch)
+    }
+}
+
 // The following `From` implementation breaks abstraction layers and MUST
 // NOT be made available outside of tests or "test-utils".
 #[cfg(any(feature = "test-utils", test))]
@@ -352,9 +481,32 @@ impl fmt::Display for RatchetTree {
 /// merging a diff.
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq, Clone))]
+#[serde(from = "SerializedTreeSync")]
 pub(crate) struct TreeSync {
     tree: MlsBinaryTree<TreeSyncLeafNode, TreeSyncParentNode>,
     tree_hash: Vec<u8>,
+    // The index is not persisted, but rebuilt when loading the tree.
+    #[serde(skip_serializing)]
+    member_index: MemberIndex,
+}
+
+/// The persisted state of a [`TreeSync`] instance.
+#[derive(Deserialize)]
+struct SerializedTreeSync {
+    tree: MlsBinaryTree<TreeSyncLeafNode, TreeSyncParentNode>,
+    tree_hash: Vec<u8>,
+}
+
+impl From<SerializedTreeSync> for TreeSync {
+    fn from(serialized: SerializedTreeSync) -> Self {
+        let mut tree_sync = Self {
+            tree: serialized.tree,
+            tree_hash: serialized.tree_hash,
+            member_index: MemberIndex::default(),
+        };
+        tree_sync.rebuild_member_index();
+        tree_sync
+    }
 }
 
 impl TreeSync {
@@ -370,7 +522,7 @@ impl TreeSync {
         life_time: Lifetime,
         capabilities: Capabilities,
         extensions: Extensions,
-    ) -> Result<(Self, CommitSecret, EncryptionKeyPair), LibraryError> {
+    ) -> Result<(Self, CommitSecret, EncryptionKeyPair), LeafNodeCreationError> {
         let new_leaf_node_params = NewLeafNodeParams {
             config,
             credential_with_key,
@@ -395,6 +547,7 @@ impl TreeSync {
         let mut tree_sync = Self {
             tree,
             tree_hash: vec![],
+            member_index: MemberIndex::default(),
         };
         // Populate tree hash caches.
         tree_sync.populate_parent_hashes(backend, config.ciphersuite)?;
@@ -407,12 +560,23 @@ impl TreeSync {
         self.tree_hash.as_slice()
     }
 
+    /// Returns a read-only [`RatchetTreeView`] of this tree.
+    pub(crate) fn view(&self) -> RatchetTreeView<'_> {
+        RatchetTreeView::new(self)
+    }
+
     /// Merge the given diff into this `TreeSync` instance, refreshing the
-    /// `tree_hash` value in the process.
+    /// `tree_hash` value and the member index in the process.
     pub(crate) fn merge_diff(&mut self, tree_sync_diff: StagedTreeSyncDiff) {
         let (diff, new_tree_hash) = tree_sync_diff.into_parts();
         self.tree_hash = new_tree_hash;
         self.tree.merge_diff(diff);
+        self.rebuild_member_index();
+    }
+
+    /// Rebuild the index from identities and signature keys to leaf indices.
+    fn rebuild_member_index(&mut self) {
+        self.member_index = MemberIndex::new(self.full_leaves_indexed());
     }
 
     /// Create an empty diff based on this [`TreeSync`] instance all operations
@@ -436,10 +600,7 @@ impl TreeSync {
         // Set the leaf indices in all the leaves and convert the node types.
         for (node_index, node_option) in ratchet_tree.0.into_iter().enumerate() {
             let ts_node_option: TreeNode<TreeSyncLeafNode, TreeSyncParentNode> = match node_option {
-                Some(node) => {
-                    let node = node.clone();
-                    TreeSyncNode::from(node).into()
-                }
+                Some(node) => TreeSyncNode::from(node).into(),
                 None => {
                     if node_index % 2 == 0 {
                         TreeNode::Leaf(TreeSyncLeafNode::blank())
@@ -454,6 +615,7 @@ impl TreeSync {
         let mut tree_sync = Self {
             tree,
             tree_hash: vec![],
+            member_index: MemberIndex::default(),
         };
         // Verify all parent hashes.
         tree_sync
@@ -527,16 +689,22 @@ impl TreeSync {
 
     /// Returns a list of [`LeafNodeIndex`]es containing only full nodes.
     pub(crate) fn full_leaves(&self) -> impl Iterator<Item = &LeafNode> {
+        self.tree.leaves().filter_map(|(_, tsn)| tsn.node())
+    }
+
+    /// Returns an indexed [`LeafNode`] iterator over all leaves in the tree
+    /// that are not blank.
+    pub(crate) fn full_leaves_indexed(&self) -> impl Iterator<Item = (LeafNodeIndex, &LeafNode)> {
         self.tree
             .leaves()
-            .filter_map(|(_, tsn)| tsn.node().as_ref())
+            .filter_map(|(index, tsn)| tsn.node().map(|leaf| (index, leaf)))
     }
 
     /// Returns the index of the last full leaf in the tree.
     fn rightmost_full_leaf(&self) -> LeafNodeIndex {
         let mut index = LeafNodeIndex::new(0);
         for (leaf_index, leaf) in self.tree.leaves() {
-            if leaf.node().as_ref().is_some() {
+            if leaf.node().is_some() {
                 index = leaf_index;
             }
         }
@@ -551,16 +719,39 @@ impl TreeSync {
         self.tree
             .leaves()
             // Filter out blank nodes
-            .filter_map(|(index, tsn)| tsn.node().as_ref().map(|node| (index, node)))
+            .filter_map(|(index, tsn)| tsn.node().map(|node| (index, node)))
             // Map to `Member`
-            .map(|(index, leaf_node)| {
-                Member::new(
-                    index,
-                    leaf_node.encryption_key().as_slice().to_vec(),
-                    leaf_node.signature_key().as_slice().to_vec(),
-                    leaf_node.credential().clone(),
-                )
-            })
+            .map(|(index, leaf_node)| Self::member_from_leaf(index, leaf_node))
+    }
+
+    /// Returns the [`Member`] at the given leaf index or `None` if the leaf is
+    /// blank.
+    pub(crate) fn member(&self, leaf_index: LeafNodeIndex) -> Option<Member> {
+        self.leaf(leaf_index)
+            .map(|leaf_node| Self::member_from_leaf(leaf_index, leaf_node))
+    }
+
+    fn member_from_leaf(index: LeafNodeIndex, leaf_node: &LeafNode) -> Member {
+        Member::new(
+            index,
+            leaf_node.encryption_key().as_slice().to_vec(),
+            leaf_node.signature_key().as_slice().to_vec(),
+            leaf_node.credential().clone(),
+        )
+    }
+
+    /// Returns the leaf indices of all members with the given identity in
+    /// ascending order.
+    pub(crate) fn leaf_indices_by_identity(&self, identity: &[u8]) -> &[LeafNodeIndex] {
+        self.member_index.leaf_indices_by_identity(identity)
+    }
+
+    /// Returns the leaf index of the member with the given signature key.
+    pub(crate) fn leaf_index_by_signature_key(
+        &self,
+        signature_key: &[u8],
+    ) -> Option<LeafNodeIndex> {
+        self.member_index.leaf_index_by_signature_key(signature_key)
     }
 
     /// Returns the nodes in the tree ordered according to the
@@ -581,7 +772,7 @@ impl TreeSync {
 
         // Get the first leaf.
         if let Some(leaf) = leaves.next() {
-            nodes.push(leaf.node().clone().map(Node::LeafNode));
+            nodes.push(leaf.node().cloned().map(Node::LeafNode));
         } else {
             // The tree was empty.
             return RatchetTree::trimmed(vec![]);
@@ -606,8 +797,8 @@ impl TreeSync {
 
         // Interleave the leaves and parents.
         for (leaf, parent) in leaves.zip(parents) {
-            nodes.push(parent.node().clone().map(Node::ParentNode));
-            nodes.push(leaf.node().clone().map(Node::LeafNode));
+            nodes.push(parent.node().cloned().map(Node::ParentNode));
+            nodes.push(leaf.node().cloned().map(Node::LeafNode));
         }
 
         RatchetTree::trimmed(nodes)
@@ -617,7 +808,7 @@ impl TreeSync {
     /// leaf is blank.
     pub(crate) fn leaf(&self, leaf_index: LeafNodeIndex) -> Option<&LeafNode> {
         let tsn = self.tree.leaf(leaf_index);
-        tsn.node().as_ref()
+        tsn.node()
     }
 
     /// Returns a [`TreeSyncError`] if the `leaf_index` is not a leaf in this
@@ -664,7 +855,7 @@ impl TreeSync {
             // We know the node is in the tree, since it is in the subtree path
             let tsn = self.tree.parent_by_index(parent_index);
             // We only care about non-blank nodes.
-            if let Some(ref parent_node) = tsn.node() {
+            if let Some(parent_node) = tsn.node() {
                 // If our own leaf index is not in the list of unmerged leaves
                 // then we should have the secret for this node.
                 if !parent_node.unmerged_leaves().contains(&leaf_index) {
@@ -698,7 +889,7 @@ impl TreeSync {
     /// `None` if the node is blank.
     pub(crate) fn parent(&self, node_index: ParentNodeIndex) -> Option<&ParentNode> {
         let tsn = self.tree.parent(node_index);
-        tsn.node().as_ref()
+        tsn.node()
     }
 }
 
@@ -16,7 +16,7 @@ pub(crate) mod parent_node;
 /// Container enum for leaf and parent nodes.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     NodeType node_type;
 ///     select (Node.node_type) {
@@ -40,6 +40,7 @@ pub enum Node {
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsSize, TlsDeserialize, TlsSerialize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum NodeIn {
     /// A leaf node.
     #[tls_codec(discriminant = 1)]
@@ -21,6 +21,7 @@ use crate::{
 #[derive(
     Debug, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize, PartialEq, Eq,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct EncryptionKey {
     key: HpkePublicKey,
 }
@@ -122,6 +123,29 @@ impl EncryptionPrivateKey {
         )
         .map(|secret_bytes| Secret::from_slice(&secret_bytes, version, ciphersuite))
     }
+
+    /// Decrypt a given `HpkeCiphertext` using this [`EncryptionPrivateKey`],
+    /// the given `label` and `context`.
+    ///
+    /// Returns the decrypted bytes. Returns an error if the decryption was
+    /// unsuccessful.
+    pub(crate) fn decrypt_with_label(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        ciphersuite: Ciphersuite,
+        label: &str,
+        context: &[u8],
+        ciphertext: &HpkeCiphertext,
+    ) -> Result<Vec<u8>, hpke::Error> {
+        hpke::decrypt_with_label(
+            self.key.as_slice(),
+            label,
+            context,
+            ciphertext,
+            ciphersuite,
+            backend.crypto(),
+        )
+    }
 }
 
 #[cfg(test)]
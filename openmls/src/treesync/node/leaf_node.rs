@@ -1,7 +1,10 @@
 //! This module contains the [`LeafNode`] struct and its implementation.
 use openmls_traits::{signatures::Signer, types::Ciphersuite, OpenMlsCryptoProvider};
 use serde::{Deserialize, Serialize};
-use tls_codec::{Serialize as TlsSerializeTrait, TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+use tls_codec::{
+    Serialize as TlsSerializeTrait, Size as TlsSizeTrait, TlsDeserialize, TlsSerialize, TlsSize,
+    VLBytes,
+};
 
 #[cfg(test)]
 use openmls_traits::key_store::OpenMlsKeyStore;
@@ -12,16 +15,24 @@ use super::encryption_keys::{EncryptionKey, EncryptionKeyPair};
 use crate::{
     binary_tree::array_representation::LeafNodeIndex,
     ciphersuite::{
-        signable::{Signable, SignedStruct, Verifiable, VerifiedStruct},
+        signable::{
+            sign_with_label, signature_labels, Signable, SignatureError, SignedStruct, Verifiable,
+            VerifiedStruct,
+        },
         Signature, SignaturePublicKey,
     },
     credentials::{Credential, CredentialType, CredentialWithKey},
     error::LibraryError,
-    extensions::{Extension, ExtensionType, Extensions, RequiredCapabilitiesExtension},
+    extensions::{
+        Extension, ExtensionType, Extensions, RequiredCapabilitiesExtension,
+        RequiredMediaTypesExtension,
+    },
     group::{config::CryptoConfig, GroupId},
     key_packages::KeyPackage,
     messages::proposals::ProposalType,
-    treesync::errors::{LeafNodeValidationError, LifetimeError, PublicTreeError},
+    treesync::errors::{
+        LeafNodeCreationError, LeafNodeValidationError, LifetimeError, PublicTreeError,
+    },
     versions::ProtocolVersion,
 };
 
@@ -30,7 +41,7 @@ mod codec;
 mod lifetime;
 
 pub use capabilities::*;
-pub use lifetime::Lifetime;
+pub use lifetime::{Lifetime, SystemTimeProvider, TimeProvider};
 
 /// Private module to ensure protection.
 mod private_mod {
@@ -47,10 +58,68 @@ pub(crate) struct NewLeafNodeParams {
     pub(crate) tree_info_tbs: TreeInfoTbs,
 }
 
+/// The members of a group that a [`LeafNode`] is validated against, see
+/// [`LeafNode::validate()`].
+pub(crate) struct LeafNodeValidationContext<'a> {
+    required_capabilities: Option<&'a RequiredCapabilitiesExtension>,
+    signature_keys: Vec<SignaturePublicKey>,
+    encryption_keys: Vec<EncryptionKey>,
+    members_supported_credentials: Vec<&'a [CredentialType]>,
+    currently_in_use: Vec<CredentialType>,
+    time_provider: &'a dyn TimeProvider,
+    skew_tolerance: u64,
+}
+
+impl<'a> LeafNodeValidationContext<'a> {
+    /// Create a new context from the `required_capabilities` of the group
+    /// and the leaf nodes of the `members` the leaf node is checked against.
+    pub(crate) fn new(
+        required_capabilities: Option<&'a RequiredCapabilitiesExtension>,
+        members: impl Iterator<Item = &'a LeafNode>,
+    ) -> Self {
+        let mut context = Self {
+            required_capabilities,
+            signature_keys: vec![],
+            encryption_keys: vec![],
+            members_supported_credentials: vec![],
+            currently_in_use: vec![],
+            time_provider: &SystemTimeProvider,
+            skew_tolerance: 0,
+        };
+        for member in members {
+            context.signature_keys.push(member.signature_key().clone());
+            context
+                .encryption_keys
+                .push(member.encryption_key().clone());
+            context
+                .members_supported_credentials
+                .push(member.capabilities().credentials());
+            let credential_type = member.credential().credential_type();
+            if !context.currently_in_use.contains(&credential_type) {
+                context.currently_in_use.push(credential_type);
+            }
+        }
+        context
+    }
+
+    /// Check lifetimes against the current time of the `time_provider`
+    /// instead of the system clock, tolerating a clock skew of
+    /// `skew_tolerance` seconds.
+    pub(crate) fn with_time(
+        mut self,
+        time_provider: &'a dyn TimeProvider,
+        skew_tolerance: u64,
+    ) -> Self {
+        self.time_provider = time_provider;
+        self.skew_tolerance = skew_tolerance;
+        self
+    }
+}
+
 /// This struct implements the MLS leaf node.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     HPKEPublicKey encryption_key;
 ///     SignaturePublicKey signature_key;
@@ -93,7 +162,7 @@ impl LeafNode {
         backend: &impl OpenMlsCryptoProvider,
         signer: &impl Signer,
         new_leaf_node_params: NewLeafNodeParams,
-    ) -> Result<(Self, EncryptionKeyPair), LibraryError> {
+    ) -> Result<(Self, EncryptionKeyPair), LeafNodeCreationError> {
         let NewLeafNodeParams {
             config,
             credential_with_key,
@@ -129,7 +198,7 @@ impl LeafNode {
         extensions: Extensions,
         tree_info_tbs: TreeInfoTbs,
         signer: &impl Signer,
-    ) -> Result<Self, LibraryError> {
+    ) -> Result<Self, LeafNodeCreationError> {
         let leaf_node_tbs = LeafNodeTbs::new(
             encryption_key,
             credential_with_key,
@@ -139,9 +208,35 @@ impl LeafNode {
             tree_info_tbs,
         )?;
 
-        leaf_node_tbs
-            .sign(signer)
-            .map_err(|_| LibraryError::custom("Signing failed"))
+        Ok(leaf_node_tbs.sign(signer)?)
+    }
+
+    /// Create a new, unsigned leaf node for the own leaf of an external
+    /// commit, along with a fresh HPKE encryption key pair.
+    ///
+    /// The leaf node is signed only once, when the parent hash is set in
+    /// [`LeafNode::update_parent_hash()`]. The caller is responsible for
+    /// storing the private key.
+    pub(crate) fn new_for_external_commit(
+        backend: &impl OpenMlsCryptoProvider,
+        config: CryptoConfig,
+        credential_with_key: CredentialWithKey,
+    ) -> Result<(Self, EncryptionKeyPair), LibraryError> {
+        let encryption_key_pair = EncryptionKeyPair::random(backend, config)?;
+        let payload = LeafNodePayload {
+            encryption_key: encryption_key_pair.public_key().clone(),
+            signature_key: credential_with_key.signature_key,
+            credential: credential_with_key.credential,
+            capabilities: Capabilities::default_for(backend.crypto()),
+            leaf_node_source: LeafNodeSource::Commit(Vec::new().into()),
+            extensions: Extensions::empty(),
+        };
+        let leaf_node = Self {
+            payload,
+            signature: Signature::from(Vec::new()),
+        };
+
+        Ok((leaf_node, encryption_key_pair))
     }
 
     /// Update the parent hash of this [`LeafNode`].
@@ -153,20 +248,27 @@ impl LeafNode {
         group_id: GroupId,
         leaf_index: LeafNodeIndex,
         signer: &impl Signer,
-    ) -> Result<(), LibraryError> {
+    ) -> Result<(), SignatureError> {
         self.payload.leaf_node_source = LeafNodeSource::Commit(parent_hash.into());
-        let tbs = LeafNodeTbs::from(
-            self.clone(), // TODO: With a better setup we wouldn't have to clone here.
-            TreeInfoTbs::Commit(TreePosition {
-                group_id,
-                leaf_index,
-            }),
-        );
-        let leaf_node = tbs
-            .sign(signer)
-            .map_err(|_| LibraryError::custom("Signing failed"))?;
-        self.payload = leaf_node.payload;
-        self.signature = leaf_node.signature;
+        let tree_info_tbs = TreeInfoTbs::Commit(TreePosition {
+            group_id,
+            leaf_index,
+        });
+        self.re_sign(&tree_info_tbs, signer)
+    }
+
+    /// Re-sign this [`LeafNode`] after its payload was changed.
+    ///
+    /// The [`LeafNodeTbs`] is serialized from a reference to the payload, so
+    /// that the payload doesn't have to be cloned for signing.
+    fn re_sign(
+        &mut self,
+        tree_info_tbs: &TreeInfoTbs,
+        signer: &impl Signer,
+    ) -> Result<(), SignatureError> {
+        let leaf_node_tbs = serialize_leaf_node_tbs(&self.payload, tree_info_tbs)
+            .map_err(|_| SignatureError::SigningError)?;
+        self.signature = sign_with_label(signer, LEAF_NODE_SIGNATURE_LABEL, &leaf_node_tbs)?;
 
         Ok(())
     }
@@ -229,7 +331,13 @@ impl LeafNode {
             tree_info_tbs,
         };
 
-        let (leaf_node, encryption_key_pair) = Self::new(backend, signer, new_leaf_node_params)?;
+        let (leaf_node, encryption_key_pair) = Self::new(backend, signer, new_leaf_node_params)
+            .map_err(|e| match e {
+                LeafNodeCreationError::LibraryError(e) => LeafNodeGenerationError::LibraryError(e),
+                LeafNodeCreationError::SignatureError(e) => {
+                    LeafNodeGenerationError::SignatureError(e)
+                }
+            })?;
 
         // Store the encryption key pair in the key store.
         encryption_key_pair
@@ -252,30 +360,26 @@ impl LeafNode {
         signer: &impl Signer,
     ) -> Result<(), PublicTreeError> {
         let tree_info = TreeInfoTbs::Update(TreePosition::new(group_id, leaf_index));
-        // TODO: If we could take out the leaf_node without cloning, this would all be nicer.
-        let mut leaf_node_tbs = LeafNodeTbs::from(self.clone(), tree_info);
 
         // Update credential
         if let Some(leaf_node) = leaf_node.into() {
-            leaf_node_tbs.payload.credential = leaf_node.credential().clone();
-            leaf_node_tbs.payload.encryption_key = leaf_node.encryption_key().clone();
-            leaf_node_tbs.payload.leaf_node_source = LeafNodeSource::Update;
+            self.payload.credential = leaf_node.payload.credential;
+            self.payload.encryption_key = leaf_node.payload.encryption_key;
+            self.payload.leaf_node_source = LeafNodeSource::Update;
         } else if let Some(new_encryption_key) = new_encryption_key.into() {
-            leaf_node_tbs.payload.leaf_node_source = LeafNodeSource::Update;
+            self.payload.leaf_node_source = LeafNodeSource::Update;
 
             // If there's no new leaf, the encryption key must be provided
             // explicitly.
-            leaf_node_tbs.payload.encryption_key = new_encryption_key;
+            self.payload.encryption_key = new_encryption_key;
         } else {
             debug_assert!(false, "update_and_re_sign needs to be called with a new leaf node or a new encryption key. Neither was the case.");
             return Err(LibraryError::custom(
                 "update_and_re_sign needs to be called with a new leaf node or a new encryption key. Neither was the case.").into());
         }
 
-        // Set the new signed leaf node with the new encryption key
-        let leaf_node = leaf_node_tbs.sign(signer)?;
-        self.payload = leaf_node.payload;
-        self.signature = leaf_node.signature;
+        // Sign the leaf node with the new encryption key
+        self.re_sign(&tree_info, signer)?;
 
         Ok(())
     }
@@ -291,6 +395,25 @@ impl LeafNode {
         protocol_version: ProtocolVersion,
         backend: &impl OpenMlsCryptoProvider,
         signer: &impl Signer,
+    ) -> Result<EncryptionKeyPair, PublicTreeError> {
+        let key_pair = self.rekey_unsigned(ciphersuite, protocol_version, backend)?;
+        let tree_info_tbs = TreeInfoTbs::Update(TreePosition::new(group_id.clone(), leaf_index));
+        self.re_sign(&tree_info_tbs, signer)?;
+
+        Ok(key_pair)
+    }
+
+    /// Replace the encryption key in this leaf with a random one without
+    /// signing the leaf node.
+    ///
+    /// This is used when the leaf node is signed later anyway, e.g., when the
+    /// parent hash is set after applying an update path, such that the
+    /// signer is only invoked once.
+    pub(crate) fn rekey_unsigned(
+        &mut self,
+        ciphersuite: Ciphersuite,
+        protocol_version: ProtocolVersion,
+        backend: &impl OpenMlsCryptoProvider,
     ) -> Result<EncryptionKeyPair, PublicTreeError> {
         if !self
             .payload
@@ -317,14 +440,8 @@ impl LeafNode {
                 version: protocol_version,
             },
         )?;
-
-        self.update_and_re_sign(
-            key_pair.public_key().clone(),
-            None,
-            group_id.clone(),
-            leaf_index,
-            signer,
-        )?;
+        self.payload.leaf_node_source = LeafNodeSource::Update;
+        self.payload.encryption_key = key_pair.public_key().clone();
 
         Ok(key_pair)
     }
@@ -380,23 +497,23 @@ impl LeafNode {
     // ----- Validation ----------------------------------------------------------------------------
 
     /// Validate the leaf node in the context of a key package.
-    // TODO(#1186)
-    #[allow(unused)]
-    pub(crate) fn validate_in_key_package(&self) -> Result<&Self, LeafNodeValidationError> {
-        // TODO(#1186)
-        // self.validate()?;
+    pub(crate) fn validate_in_key_package(
+        &self,
+        context: &LeafNodeValidationContext,
+    ) -> Result<&Self, LeafNodeValidationError> {
+        self.validate(context)?;
 
         match self.payload.leaf_node_source {
             LeafNodeSource::KeyPackage(lifetime) => {
-                /// Check that lifetime range is acceptable.
+                // Check that lifetime range is acceptable.
                 if !lifetime.has_acceptable_range() {
                     return Err(LeafNodeValidationError::Lifetime(
                         LifetimeError::RangeTooBig,
                     ));
                 }
 
-                /// Check that current time is between `Lifetime.not_before` and `Lifetime.not_after`.
-                if !lifetime.is_valid() {
+                // Check that current time is between `Lifetime.not_before` and `Lifetime.not_after`.
+                if !lifetime.is_valid_at(context.time_provider, context.skew_tolerance) {
                     return Err(LeafNodeValidationError::Lifetime(LifetimeError::NotCurrent));
                 }
 
@@ -407,11 +524,11 @@ impl LeafNode {
     }
 
     /// Validate the leaf node in the context of an update.
-    // TODO(#1186)
-    #[allow(unused)]
-    pub(crate) fn validate_in_update(&self) -> Result<&Self, LeafNodeValidationError> {
-        // TODO(#1186)
-        // self.validate()?;
+    pub(crate) fn validate_in_update(
+        &self,
+        context: &LeafNodeValidationContext,
+    ) -> Result<&Self, LeafNodeValidationError> {
+        self.validate(context)?;
 
         match self.payload.leaf_node_source {
             LeafNodeSource::Update => Ok(self),
@@ -420,11 +537,11 @@ impl LeafNode {
     }
 
     /// Validate the leaf node in the context of a commit.
-    // TODO(#1186)
-    #[allow(unused)]
-    pub(crate) fn validate_in_commit(&self) -> Result<&Self, LeafNodeValidationError> {
-        // TODO(#1186)
-        // self.validate()?;
+    pub(crate) fn validate_in_commit(
+        &self,
+        context: &LeafNodeValidationContext,
+    ) -> Result<&Self, LeafNodeValidationError> {
+        self.validate(context)?;
 
         match self.payload.leaf_node_source {
             LeafNodeSource::Commit(_) => Ok(self),
@@ -433,23 +550,20 @@ impl LeafNode {
     }
 
     /// Basic validation of leaf node called in all `validate_in_*` methods.
-    // TODO(#1186)
-    #[allow(unused)]
-    fn validate<'a>(
+    /// Leaf nodes in a ratchet tree received with a Welcome message are only
+    /// subject to this validation, since their lifetime might have expired
+    /// after they were added to the group.
+    pub(crate) fn validate(
         &self,
-        required_capabilities: impl Into<Option<&'a RequiredCapabilitiesExtension>>,
-        signature_keys: &[SignaturePublicKey],
-        encryption_keys: &[EncryptionKey],
-        members_supported_credentials: &[&[CredentialType]],
-        currently_in_use: &[CredentialType],
+        context: &LeafNodeValidationContext,
     ) -> Result<&Self, LeafNodeValidationError> {
-        self.validate_required_capabilities(required_capabilities)?
+        self.validate_required_capabilities(context.required_capabilities)?
             .validate_that_capabilities_contain_extension_types()?
             .validate_that_capabilities_contain_credential_type()?
-            .validate_that_signature_key_is_unique(signature_keys)?
-            .validate_that_encryption_key_is_unique(encryption_keys)?
-            .validate_against_group_credentials(members_supported_credentials)?
-            .validate_credential_in_use(currently_in_use)?;
+            .validate_that_signature_key_is_unique(&context.signature_keys)?
+            .validate_that_encryption_key_is_unique(&context.encryption_keys)?
+            .validate_against_group_credentials(&context.members_supported_credentials)?
+            .validate_credential_in_use(&context.currently_in_use)?;
 
         Ok(self)
     }
@@ -582,13 +696,12 @@ impl LeafNode {
             .capabilities
             .extensions
             .contains(extension_type)
-            || default_extensions().iter().any(|et| et == extension_type)
+            || extension_type.is_default()
     }
 
     /// Returns `true` if the [`ProposalType`] is supported by this leaf node.
     pub(crate) fn supports_proposal(&self, proposal_type: &ProposalType) -> bool {
-        self.payload.capabilities.proposals.contains(proposal_type)
-            || default_proposals().iter().any(|pt| pt == proposal_type)
+        self.payload.capabilities.proposals.contains(proposal_type) || proposal_type.is_default()
     }
 
     /// Returns `true` if the [`CredentialType`] is supported by this leaf node.
@@ -598,6 +711,21 @@ impl LeafNode {
             .credentials
             .contains(credential_type)
     }
+
+    /// Returns `true` if the accepted media types of this leaf node contain
+    /// all `required_media_types`.
+    pub(crate) fn supports_media_types(
+        &self,
+        required_media_types: Option<&RequiredMediaTypesExtension>,
+    ) -> bool {
+        let Some(required_media_types) = required_media_types else {
+            return true;
+        };
+        match self.extensions().accepted_media_types() {
+            Some(accepted_media_types) => accepted_media_types.contains_all(required_media_types),
+            None => required_media_types.media_types().is_empty(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -611,7 +739,7 @@ impl LeafNode {
         extensions: Extensions,
         tree_info_tbs: TreeInfoTbs,
         signer: &impl Signer,
-    ) -> Result<Self, LibraryError> {
+    ) -> Result<Self, LeafNodeCreationError> {
         Self::new_with_key(
             encryption_key,
             credential_with_key,
@@ -628,6 +756,11 @@ impl LeafNode {
         &mut self.payload.capabilities
     }
 
+    /// Return a mutable reference to the leaf node [`Extensions`].
+    pub(crate) fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.payload.extensions
+    }
+
     /// Check whether the this leaf node supports all the required extensions
     /// in the provided list.
     pub(crate) fn check_extension_support(
@@ -662,22 +795,9 @@ impl LeafNode {
         credential_with_key: CredentialWithKey,
         tree_info_tbs: TreeInfoTbs,
     ) {
-        let leaf_node_tbs = LeafNodeTbs::new(
-            self.payload.encryption_key.clone(),
-            credential_with_key,
-            self.payload.capabilities.clone(),
-            self.payload.leaf_node_source.clone(),
-            self.payload.extensions.clone(),
-            tree_info_tbs,
-        )
-        .unwrap();
-
-        let leaf_node = leaf_node_tbs
-            .sign(signer)
-            .map_err(|_| LibraryError::custom("Signing failed"))
-            .unwrap();
-        self.payload = leaf_node.payload;
-        self.signature = leaf_node.signature;
+        self.payload.credential = credential_with_key.credential;
+        self.payload.signature_key = credential_with_key.signature_key;
+        self.re_sign(&tree_info_tbs, signer).unwrap();
     }
 
     /// Re-signs a leaf node with a specific tree position.
@@ -689,10 +809,7 @@ impl LeafNode {
         signer: &impl Signer,
     ) {
         let tree_info_tbs = TreeInfoTbs::commit(group_id, leaf_index);
-        let leaf_node_tbs = LeafNodeTbs::from(self.clone(), tree_info_tbs);
-        let leaf_node = leaf_node_tbs.sign(signer).unwrap();
-        self.payload = leaf_node.payload;
-        self.signature = leaf_node.signature;
+        self.re_sign(&tree_info_tbs, signer).unwrap();
     }
 }
 
@@ -724,6 +841,7 @@ impl LeafNode {
 #[derive(
     Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 struct LeafNodePayload {
     encryption_key: EncryptionKey,
     signature_key: SignaturePublicKey,
@@ -737,6 +855,7 @@ struct LeafNodePayload {
     Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum LeafNodeSource {
     #[tls_codec(discriminant = 1)]
     KeyPackage(Lifetime),
@@ -749,7 +868,7 @@ pub type ParentHash = VLBytes;
 /// To-be-signed leaf node.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     HPKEPublicKey encryption_key;
 ///     SignaturePublicKey signature_key;
@@ -780,15 +899,6 @@ pub struct LeafNodeTbs {
 }
 
 impl LeafNodeTbs {
-    /// Build a [`LeafNodeTbs`] from a [`LeafNode`] and a [`TreeInfo`]
-    /// to update a leaf node.
-    pub(crate) fn from(leaf_node: LeafNode, tree_info_tbs: TreeInfoTbs) -> Self {
-        Self {
-            payload: leaf_node.payload,
-            tree_info_tbs,
-        }
-    }
-
     /// Build a new [`LeafNodeTbs`] from a [`KeyPackage`] and [`Credential`].
     /// To get the [`LeafNode`] call [`LeafNode::sign`].
     pub(crate) fn new(
@@ -818,7 +928,7 @@ impl LeafNodeTbs {
 /// Helper struct that holds additional information required to sign a leaf node.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-17
+/// // RFC 9420
 /// struct {
 ///     // ... continued from [`LeafNodeTbs`] ...
 ///
@@ -868,11 +978,25 @@ impl TreePosition {
     }
 }
 
-const LEAF_NODE_SIGNATURE_LABEL: &str = "LeafNodeTBS";
+const LEAF_NODE_SIGNATURE_LABEL: &str = signature_labels::LEAF_NODE;
+
+/// Serialize the [`LeafNodeTbs`] for the given `payload` and `tree_info_tbs`
+/// without moving or cloning them into a [`LeafNodeTbs`].
+fn serialize_leaf_node_tbs(
+    payload: &LeafNodePayload,
+    tree_info_tbs: &TreeInfoTbs,
+) -> Result<Vec<u8>, tls_codec::Error> {
+    let mut bytes =
+        Vec::with_capacity(payload.tls_serialized_len() + tree_info_tbs.tls_serialized_len());
+    payload.tls_serialize(&mut bytes)?;
+    tree_info_tbs.tls_serialize(&mut bytes)?;
+    Ok(bytes)
+}
 
 #[derive(
     Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct LeafNodeIn {
     payload: LeafNodePayload,
     signature: Signature,
@@ -1020,11 +1144,7 @@ impl Verifiable for VerifiableUpdateLeafNode {
             Some(tree_position) => TreeInfoTbs::Commit(tree_position.clone()),
             None => return Err(tls_codec::Error::InvalidInput),
         };
-        let leaf_node_tbs = LeafNodeTbs {
-            payload: self.payload.clone(),
-            tree_info_tbs,
-        };
-        leaf_node_tbs.tls_serialize_detached()
+        serialize_leaf_node_tbs(&self.payload, &tree_info_tbs)
     }
 
     fn signature(&self) -> &Signature {
@@ -1070,12 +1190,7 @@ impl Verifiable for VerifiableCommitLeafNode {
             Some(tree_position) => TreeInfoTbs::Commit(tree_position.clone()),
             None => return Err(tls_codec::Error::InvalidInput),
         };
-        let leaf_node_tbs = LeafNodeTbs {
-            payload: self.payload.clone(),
-            tree_info_tbs,
-        };
-
-        leaf_node_tbs.tls_serialize_detached()
+        serialize_leaf_node_tbs(&self.payload, &tree_info_tbs)
     }
 
     fn signature(&self) -> &Signature {
@@ -1128,4 +1243,7 @@ pub enum LeafNodeGenerationError<KeyStoreError> {
     /// Error storing leaf private key in key store.
     #[error("Error storing leaf private key in key store.")]
     KeyStoreError(KeyStoreError),
+    /// See [`SignatureError`] for more details.
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
 }
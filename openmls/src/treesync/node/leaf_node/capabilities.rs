@@ -1,4 +1,7 @@
-use openmls_traits::types::{Ciphersuite, VerifiableCiphersuite};
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    types::{Ciphersuite, VerifiableCiphersuite},
+};
 use serde::{Deserialize, Serialize};
 use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
 
@@ -25,6 +28,7 @@ use crate::{
 #[derive(
     Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Capabilities {
     pub(super) versions: Vec<ProtocolVersion>,
     pub(super) ciphersuites: Vec<VerifiableCiphersuite>,
@@ -113,8 +117,11 @@ impl Capabilities {
     // ---------------------------------------------------------------------------------------------
 
     /// Check if these [`Capabilities`] support all the capabilities
-    /// required by the given [`RequiredCapabilities`] extension. Returns
+    /// required by the given [`RequiredCapabilitiesExtension`] extension. Returns
     /// `true` if that is the case and `false` otherwise.
+    ///
+    /// Default extension and proposal types (see Section 7.2 of RFC 9420) are
+    /// supported implicitly and don't need to be listed.
     pub(crate) fn supports_required_capabilities(
         &self,
         required_capabilities: &RequiredCapabilitiesExtension,
@@ -123,7 +130,7 @@ impl Capabilities {
         if required_capabilities
             .extension_types()
             .iter()
-            .any(|e| !self.extensions().contains(e))
+            .any(|e| !e.is_default() && !self.extensions().contains(e))
         {
             return false;
         }
@@ -131,7 +138,15 @@ impl Capabilities {
         if required_capabilities
             .proposal_types()
             .iter()
-            .any(|p| !self.proposals().contains(p))
+            .any(|p| !p.is_default() && !self.proposals().contains(p))
+        {
+            return false;
+        }
+        // Check if all required credentials are supported.
+        if required_capabilities
+            .credential_types()
+            .iter()
+            .any(|c| !self.credentials().contains(c))
         {
             return false;
         }
@@ -152,6 +167,21 @@ impl Capabilities {
     }
 }
 
+impl Capabilities {
+    /// Returns the default [`Capabilities`] with the ciphersuites that are
+    /// supported by the `crypto` backend instead of the default ciphersuites.
+    pub(crate) fn default_for(crypto: &impl OpenMlsCrypto) -> Self {
+        Self {
+            ciphersuites: crypto
+                .supported_ciphersuites()
+                .into_iter()
+                .map(VerifiableCiphersuite::from)
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
 impl Default for Capabilities {
     fn default() -> Self {
         Capabilities {
@@ -208,8 +238,8 @@ mod tests {
 
     use super::Capabilities;
     use crate::{
-        credentials::CredentialType, messages::proposals::ProposalType, prelude::ExtensionType,
-        versions::ProtocolVersion,
+        credentials::CredentialType, extensions::RequiredCapabilitiesExtension,
+        messages::proposals::ProposalType, prelude::ExtensionType, versions::ProtocolVersion,
     };
 
     #[test]
@@ -259,4 +289,60 @@ mod tests {
 
         assert_eq!(expected, got);
     }
+
+    #[test]
+    fn that_default_types_are_supported_implicitly() {
+        let capabilities = Capabilities::empty();
+
+        // Default extension and proposal types don't need to be listed.
+        let required_capabilities = RequiredCapabilitiesExtension::new(
+            &[ExtensionType::RatchetTree, ExtensionType::ExternalSenders],
+            &[
+                ProposalType::ExternalInit,
+                ProposalType::GroupContextExtensions,
+            ],
+            &[],
+        );
+        assert!(capabilities.supports_required_capabilities(&required_capabilities));
+
+        // Non-default types have to be listed.
+        let required_capabilities =
+            RequiredCapabilitiesExtension::new(&[ExtensionType::Unknown(0xff10)], &[], &[]);
+        assert!(!capabilities.supports_required_capabilities(&required_capabilities));
+        let required_capabilities =
+            RequiredCapabilitiesExtension::new(&[], &[ProposalType::AppAck], &[]);
+        assert!(!capabilities.supports_required_capabilities(&required_capabilities));
+
+        // Credential types always have to be listed.
+        let required_capabilities =
+            RequiredCapabilitiesExtension::new(&[], &[], &[CredentialType::Basic]);
+        assert!(!capabilities.supports_required_capabilities(&required_capabilities));
+        assert!(Capabilities::default().supports_required_capabilities(&required_capabilities));
+    }
+
+    #[test]
+    fn that_backend_ciphersuites_are_advertised() {
+        use openmls_rust_crypto::OpenMlsRustCrypto;
+        use openmls_traits::OpenMlsCryptoProvider;
+
+        let backend = OpenMlsRustCrypto::default();
+        let capabilities = Capabilities::default_for(backend.crypto());
+
+        assert_eq!(
+            capabilities.ciphersuites(),
+            backend
+                .crypto()
+                .supported_ciphersuites()
+                .into_iter()
+                .map(VerifiableCiphersuite::from)
+                .collect::<Vec<_>>()
+        );
+        assert!(capabilities
+            .ciphersuites()
+            .contains(&Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521.into()));
+        assert_eq!(
+            capabilities.extensions(),
+            Capabilities::default().extensions()
+        );
+    }
 }
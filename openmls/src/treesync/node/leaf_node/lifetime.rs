@@ -28,7 +28,7 @@ const MAX_LEAF_NODE_LIFETIME_RANGE_SECONDS: u64 =
 /// than this duration.This extension MUST always be present in a KeyPackage.
 ///
 /// ```c
-/// // draft-ietf-mls-protocol-16
+/// // RFC 9420
 /// struct {
 ///     uint64 not_before;
 ///     uint64 not_after;
@@ -37,43 +37,72 @@ const MAX_LEAF_NODE_LIFETIME_RANGE_SECONDS: u64 =
 #[derive(
     PartialEq, Eq, Copy, Clone, Debug, TlsSerialize, TlsSize, TlsDeserialize, Serialize, Deserialize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Lifetime {
     not_before: u64,
     not_after: u64,
 }
 
 impl Lifetime {
-    /// Create a new lifetime with lifetime `t` (in seconds).
+    /// Create a new lifetime with lifetime `t` (in seconds), starting at the
+    /// current time of the system clock.
     /// Note that the lifetime is extended 1h into the past to adapt to skewed
     /// clocks, i.e. `not_before` is set to now - 1h.
     pub fn new(t: u64) -> Self {
+        Self::new_with_time(t, &SystemTimeProvider).expect("SystemTime before UNIX EPOCH!")
+    }
+
+    /// Create a new lifetime with lifetime `t` (in seconds), starting at the
+    /// current time of the `time_provider`, e.g., the
+    /// [`TimeProvider`] of the [`MlsGroupConfig`](crate::group::MlsGroupConfig).
+    /// Like with [`Lifetime::new()`], the lifetime is extended 1h into the
+    /// past.
+    ///
+    /// Returns `None` if the `time_provider` doesn't know the current time.
+    pub fn new_with_time(t: u64, time_provider: &(impl TimeProvider + ?Sized)) -> Option<Self> {
         let lifetime_margin: u64 = DEFAULT_KEY_PACKAGE_LIFETIME_MARGIN_SECONDS;
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("SystemTime before UNIX EPOCH!")
-            .as_secs();
-        let not_before = now - lifetime_margin;
-        let not_after = now + t;
-        Self {
+        let now = time_provider.unix_now()?;
+        let not_before = now.saturating_sub(lifetime_margin);
+        let not_after = now.saturating_add(t);
+        Some(Self {
             not_before,
             not_after,
-        }
+        })
     }
 
-    /// Returns true if this lifetime is valid.
-    pub(crate) fn is_valid(&self) -> bool {
-        match SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|duration| duration.as_secs())
-        {
-            Ok(elapsed) => self.not_before < elapsed && elapsed < self.not_after,
-            Err(_) => {
-                log::error!("SystemTime before UNIX EPOCH.");
+    /// Returns true if this lifetime is valid at the current time of the
+    /// `time_provider`. The lifetime is extended by `skew_tolerance` seconds
+    /// into the past and the future to accept leaf nodes of clients with
+    /// skewed clocks.
+    pub fn is_valid_at(
+        &self,
+        time_provider: &(impl TimeProvider + ?Sized),
+        skew_tolerance: u64,
+    ) -> bool {
+        match time_provider.unix_now() {
+            Some(now) => {
+                self.not_before.saturating_sub(skew_tolerance) < now
+                    && now < self.not_after.saturating_add(skew_tolerance)
+            }
+            None => {
+                log::error!("No current time available.");
                 false
             }
         }
     }
 
+    /// Returns the time (in seconds since the UNIX epoch) before which the
+    /// lifetime is not valid.
+    pub fn not_before(&self) -> u64 {
+        self.not_before
+    }
+
+    /// Returns the time (in seconds since the UNIX epoch) after which the
+    /// lifetime is not valid.
+    pub fn not_after(&self) -> u64 {
+        self.not_after
+    }
+
     /// ValSem(openmls/annotations#32):
     /// Applications MUST define a maximum total lifetime that is acceptable for a LeafNode,
     /// and reject any LeafNode where the total lifetime is longer than this duration.
@@ -82,6 +111,28 @@ impl Lifetime {
     }
 }
 
+/// A source of the current time, e.g., to check the [`Lifetime`] of leaf
+/// nodes. Applications can provide their own time source, e.g., a network
+/// time on devices with unreliable clocks or a fixed time in tests.
+pub trait TimeProvider: Send + Sync {
+    /// Returns the current time in seconds since the UNIX epoch, or `None` if
+    /// the time is not available.
+    fn unix_now(&self) -> Option<u64>;
+}
+
+/// A [`TimeProvider`] that reads the system clock.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SystemTimeProvider;
+
+impl TimeProvider for SystemTimeProvider {
+    fn unix_now(&self) -> Option<u64> {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .ok()
+    }
+}
+
 impl Default for Lifetime {
     fn default() -> Self {
         Lifetime::new(DEFAULT_KEY_PACKAGE_LIFETIME_SECONDS)
@@ -92,18 +143,18 @@ impl Default for Lifetime {
 mod tests {
     use tls_codec::{Deserialize, Serialize};
 
-    use crate::treesync::node::leaf_node::Lifetime;
+    use crate::treesync::node::leaf_node::{Lifetime, SystemTimeProvider, TimeProvider};
 
     #[test]
     fn lifetime() {
         // A freshly created extensions must be valid.
         let ext = Lifetime::default();
-        assert!(ext.is_valid());
+        assert!(ext.is_valid_at(&SystemTimeProvider, 0));
 
         // An extension without lifetime is invalid (waiting for 1 second).
         let ext = Lifetime::new(0);
         std::thread::sleep(std::time::Duration::from_secs(1));
-        assert!(!ext.is_valid());
+        assert!(!ext.is_valid_at(&SystemTimeProvider, 0));
 
         // Test (de)serializing invalid extension
         let serialized = ext
@@ -111,6 +162,37 @@ mod tests {
             .expect("error encoding life time extension");
         let ext_deserialized = Lifetime::tls_deserialize(&mut serialized.as_slice())
             .expect("Error deserializing lifetime");
-        assert!(!ext_deserialized.is_valid());
+        assert!(!ext_deserialized.is_valid_at(&SystemTimeProvider, 0));
+    }
+
+    #[test]
+    fn lifetime_skew_tolerance() {
+        struct FixedTime(u64);
+
+        impl TimeProvider for FixedTime {
+            fn unix_now(&self) -> Option<u64> {
+                Some(self.0)
+            }
+        }
+
+        let ext = Lifetime::new(60);
+        let now = SystemTimeProvider.unix_now().expect("No system time.");
+
+        // The lifetime starts 1h in the past and ends 60s in the future.
+        assert!(ext.is_valid_at(&FixedTime(now), 0));
+        assert!(!ext.is_valid_at(&FixedTime(ext.not_before() - 10), 0));
+        assert!(!ext.is_valid_at(&FixedTime(ext.not_after() + 10), 0));
+
+        // Clocks that are slightly off are tolerated.
+        assert!(ext.is_valid_at(&FixedTime(ext.not_before() - 10), 60));
+        assert!(ext.is_valid_at(&FixedTime(ext.not_after() + 10), 60));
+        assert!(!ext.is_valid_at(&FixedTime(ext.not_after() + 120), 60));
+
+        // Lifetimes can start at the time of any time provider.
+        let ext = Lifetime::new_with_time(60, &FixedTime(10_000)).expect("No time.");
+        assert_eq!(ext.not_before(), 10_000 - 60 * 60);
+        assert_eq!(ext.not_after(), 10_060);
+        assert!(ext.is_valid_at(&FixedTime(10_000), 0));
+        assert!(!ext.is_valid_at(&SystemTimeProvider, 0));
     }
 }
@@ -26,6 +26,7 @@ use crate::{
 #[derive(
     Debug, Eq, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ParentNode {
     pub(super) encryption_key: EncryptionKey,
     pub(super) parent_hash: VLBytes,
@@ -161,13 +162,13 @@ impl ParentNode {
         self.encryption_key.key()
     }
 
-    /// Return a reference to the `public_key` of this node.
-    pub(crate) fn encryption_key(&self) -> &EncryptionKey {
+    /// Return a reference to the `encryption_key` of this node.
+    pub fn encryption_key(&self) -> &EncryptionKey {
         &self.encryption_key
     }
 
     /// Get the list of unmerged leaves.
-    pub(crate) fn unmerged_leaves(&self) -> &[LeafNodeIndex] {
+    pub fn unmerged_leaves(&self) -> &[LeafNodeIndex] {
         self.unmerged_leaves.list()
     }
 
@@ -202,13 +203,14 @@ impl ParentNode {
     }
 
     /// Get the parent hash value of this node.
-    pub(crate) fn parent_hash(&self) -> &[u8] {
+    pub fn parent_hash(&self) -> &[u8] {
         self.parent_hash.as_slice()
     }
 }
 
 /// A helper struct that maintains a sorted list of unmerged leaves.
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize, TlsSize, TlsSerialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(in crate::treesync) struct UnmergedLeaves {
     list: Vec<LeafNodeIndex>,
 }
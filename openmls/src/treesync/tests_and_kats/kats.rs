@@ -1,6 +1,6 @@
 // FIXME: 624 The tree kem test vectors have to be regenerated after #541.
 // pub mod kat_tree_kem;
-#[cfg(test)]
+#[cfg(any(feature = "test-vectors", test))]
 pub mod kat_tree_operations;
 #[cfg(test)]
 pub mod kat_tree_validation;
@@ -38,7 +38,7 @@ use crate::{
 };
 
 #[derive(Deserialize)]
-struct TestElement {
+pub(crate) struct TestElement {
     #[serde(with = "hex")]
     tree_before: Vec<u8>,
     #[serde(with = "hex")]
@@ -48,7 +48,10 @@ struct TestElement {
     tree_after: Vec<u8>,
 }
 
-fn run_test_vector(test: TestElement, backend: &impl OpenMlsCryptoProvider) -> Result<(), String> {
+pub(crate) fn run_test_vector(
+    test: TestElement,
+    backend: &impl OpenMlsCryptoProvider,
+) -> Result<(), String> {
     let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
 
     let group_id = GroupId::random(backend);
@@ -23,14 +23,14 @@
 //! ```
 //!
 //! `tree` contains a TLS-serialized ratchet tree, as in
-//! [the `ratchet_tree` extension](https://tools.ietf.org/html/draft-ietf-mls-protocol-17#section-12.4.3.3)
+//! [the `ratchet_tree` extension](https://www.rfc-editor.org/rfc/rfc9420.html#section-12.4.3.3)
 //!
 //! Verification:
 //! * Verify that the resolution of each node in tree with node index `i` matches
 //!   `resolutions[i]`.
 //! * Verify that the tree hash of each node in tree with node index `i` matches
 //!   `tree_hashes[i]`.
-//! * [Verify the parent hashes](https://tools.ietf.org/html/draft-ietf-mls-protocol-17#section-7.9.2)
+//! * [Verify the parent hashes](https://www.rfc-editor.org/rfc/rfc9420.html#section-7.9.2)
 //!   of `tree` as when joining the group.
 //! * Verify the signatures on all leaves of `tree` using the provided `group_id`
 //!   as context.
@@ -56,7 +56,7 @@
 //! * A tree with unmerged leaves: start with `get_tree(7)`, then the leaf
 //!   with index `0` adds a member.
 //! * A tree with unmerged leaves and skipping blanks in the parent hash links:
-//!   the tree from [Figure 20](https://tools.ietf.org/html/draft-ietf-mls-protocol-17#appendix-A).
+//!   the tree from [Appendix A](https://www.rfc-editor.org/rfc/rfc9420.html#appendix-A).
 
 use std::collections::HashSet;
 
@@ -94,6 +94,7 @@ pub fn run_test_vector(test: TreeKemTest, backend: &impl OpenMlsCryptoProvider)
         let ratchet_tree = RatchetTreeExtension::tls_deserialize_exact(test.ratchet_tree)
             .unwrap()
             .ratchet_tree()
+            .unwrap()
             .clone()
             .into_verified(ciphersuite, backend.crypto(), group_id)
             .unwrap();
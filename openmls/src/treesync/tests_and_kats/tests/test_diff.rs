@@ -4,6 +4,7 @@ use rstest::*;
 use rstest_reuse::apply;
 
 use crate::{
+    binary_tree::LeafNodeIndex,
     credentials::{test_utils::new_credential, CredentialType},
     key_packages::KeyPackageBundle,
     treesync::{node::Node, RatchetTree, TreeSync},
@@ -66,3 +67,158 @@ fn test_free_leaf_computation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCr
 
     assert_eq!(free_leaf_index.u32(), 2u32);
 }
+
+// Verifies that the cached tree hashes are used and updated correctly when
+// a diff is merged.
+#[apply(ciphersuites_and_backends)]
+fn test_cached_tree_hash(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let key_package_bundles: Vec<KeyPackageBundle> = (0..4)
+        .map(|i| {
+            let (credential, signer) = new_credential(
+                backend,
+                format!("leaf{i}").as_bytes(),
+                CredentialType::Basic,
+                ciphersuite.signature_algorithm(),
+            );
+            KeyPackageBundle::new(backend, &signer, ciphersuite, credential)
+        })
+        .collect();
+
+    // Build a tree with two leaves.
+    let ratchet_tree = RatchetTree::trimmed(vec![
+        Some(Node::LeafNode(
+            key_package_bundles[0].key_package().leaf_node().clone(),
+        )),
+        None,
+        Some(Node::LeafNode(
+            key_package_bundles[1].key_package().leaf_node().clone(),
+        )),
+    ]);
+    let mut tree = TreeSync::from_ratchet_tree(backend, ciphersuite, ratchet_tree)
+        .expect("error generating tree");
+
+    // Add two leaves, which grows the tree, and blank the first one.
+    let mut diff = tree.empty_diff();
+    for key_package_bundle in &key_package_bundles[2..] {
+        diff.add_leaf(key_package_bundle.key_package().leaf_node().clone())
+            .expect("error adding leaf");
+    }
+    diff.blank_leaf(LeafNodeIndex::new(0));
+    let staged_diff = diff
+        .into_staged_diff(backend, ciphersuite)
+        .expect("error staging diff");
+    tree.merge_diff(staged_diff);
+
+    // The tree hash must match the one of a tree without cached tree hashes.
+    let fresh_tree = TreeSync::from_ratchet_tree(backend, ciphersuite, tree.export_ratchet_tree())
+        .expect("error generating tree");
+    assert_eq!(tree.tree_hash(), fresh_tree.tree_hash());
+
+    // An empty diff doesn't change the tree hash.
+    let staged_diff = tree
+        .empty_diff()
+        .into_staged_diff(backend, ciphersuite)
+        .expect("error staging diff");
+    tree.merge_diff(staged_diff);
+    assert_eq!(tree.tree_hash(), fresh_tree.tree_hash());
+}
+
+// Verifies that runs of blank nodes are stored compactly when a tree is
+// serialized and restored when it is deserialized.
+#[apply(ciphersuites_and_backends)]
+fn test_blank_runs_serialization(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (c_0, sk_0) = new_credential(
+        backend,
+        b"leaf0",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let kpb_0 = KeyPackageBundle::new(backend, &sk_0, ciphersuite, c_0);
+    let (c_7, sk_7) = new_credential(
+        backend,
+        b"leaf7",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let kpb_7 = KeyPackageBundle::new(backend, &sk_7, ciphersuite, c_7);
+
+    // Build a tree with eight leaves, of which only the first and the last
+    // one are populated.
+    let mut nodes = vec![None; 15];
+    nodes[0] = Some(Node::LeafNode(kpb_0.key_package().leaf_node().clone()));
+    nodes[14] = Some(Node::LeafNode(kpb_7.key_package().leaf_node().clone()));
+    let tree = TreeSync::from_ratchet_tree(backend, ciphersuite, RatchetTree::trimmed(nodes))
+        .expect("error generating tree");
+
+    let serialized = serde_json::to_value(&tree).expect("error serializing tree");
+    // Leaves 1 to 6 are stored as a single run.
+    assert_eq!(
+        serialized["tree"]["leaf_nodes"][1],
+        serde_json::json!({ "Blanks": 6 })
+    );
+    // All parent nodes are blank.
+    assert_eq!(
+        serialized["tree"]["parent_nodes"],
+        serde_json::json!([{ "Blanks": 7 }])
+    );
+
+    let deserialized: TreeSync =
+        serde_json::from_value(serialized).expect("error deserializing tree");
+    assert_eq!(deserialized, tree);
+    assert_eq!(
+        deserialized.export_ratchet_tree(),
+        tree.export_ratchet_tree()
+    );
+    assert_eq!(deserialized.tree_hash(), tree.tree_hash());
+}
+
+// Trees that were serialized before runs of blank nodes were stored compactly
+// contain every node as is. They must still be loadable.
+#[apply(ciphersuites_and_backends)]
+fn test_legacy_tree_serialization(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (c_0, sk_0) = new_credential(
+        backend,
+        b"leaf0",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let kpb_0 = KeyPackageBundle::new(backend, &sk_0, ciphersuite, c_0);
+    let (c_3, sk_3) = new_credential(
+        backend,
+        b"leaf3",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let kpb_3 = KeyPackageBundle::new(backend, &sk_3, ciphersuite, c_3);
+
+    let mut nodes = vec![None; 7];
+    nodes[0] = Some(Node::LeafNode(kpb_0.key_package().leaf_node().clone()));
+    nodes[6] = Some(Node::LeafNode(kpb_3.key_package().leaf_node().clone()));
+    let tree = TreeSync::from_ratchet_tree(backend, ciphersuite, RatchetTree::trimmed(nodes))
+        .expect("error generating tree");
+
+    // Expand the runs into the plain nodes of the previous format.
+    let mut serialized = serde_json::to_value(&tree).expect("error serializing tree");
+    let blank = serde_json::json!({ "node": null, "tree_hash": null });
+    for field in ["leaf_nodes", "parent_nodes"] {
+        let mut expanded = Vec::new();
+        for run in serialized["tree"][field]
+            .as_array()
+            .expect("nodes are not stored as a sequence")
+        {
+            if let Some(count) = run["Blanks"].as_u64() {
+                expanded.extend(std::iter::repeat(blank.clone()).take(count as usize));
+            } else {
+                expanded.push(run["Node"].clone());
+            }
+        }
+        serialized["tree"][field] = serde_json::Value::Array(expanded);
+    }
+    serialized["tree"]["default_leaf"] = blank.clone();
+    serialized["tree"]["default_parent"] = blank;
+
+    let deserialized: TreeSync =
+        serde_json::from_value(serialized).expect("error deserializing legacy tree");
+    assert_eq!(deserialized, tree);
+    assert_eq!(deserialized.tree_hash(), tree.tree_hash());
+}
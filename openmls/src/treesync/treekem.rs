@@ -4,6 +4,7 @@
 //!
 //! This module contains structs and functions to encrypt and decrypt path
 //! updates for a [`TreeSyncDiff`] instance.
+
 use std::collections::HashSet;
 
 use openmls_traits::{
@@ -175,52 +176,66 @@ impl<'a> TreeSyncDiff<'a> {
         backend: &impl OpenMlsCryptoProvider,
         encryptor_leaf_index: LeafNodeIndex,
     ) -> Result<Vec<EncryptedGroupSecrets>, LibraryError> {
-        let mut encrypted_group_secrets_vec = vec![];
-        for (leaf_index, add_proposal) in invited_members {
-            let key_package = add_proposal.key_package;
-
-            let direct_path_position = self
-                .subtree_root_position(encryptor_leaf_index, leaf_index)
-                // This can only fail if the nodes are outside the tree or identical
-                .map_err(|_| LibraryError::custom("Unexpected error in subtree_root_position"))?;
-
-            // If a plain path was given, there have to be secrets for every new member.
-            let path_secret_option = if let Some(plain_path) = plain_path_option {
-                Some(
-                    plain_path
-                        .get(direct_path_position)
-                        .map(|pupn| pupn.path_secret())
-                        // This only fails if the supplied plain path is invalid
-                        .ok_or_else(|| LibraryError::custom("Invalid plain path"))?,
-                )
-            } else {
-                None
-            };
-
-            // Create the GroupSecrets object for the respective member.
-            let group_secrets_bytes =
-                GroupSecrets::new_encoded(joiner_secret, path_secret_option, presharedkeys)
-                    .map_err(LibraryError::missing_bound_check)?;
-            let ciphertext = hpke::encrypt_with_label(
-                key_package.hpke_init_key().as_slice(),
-                "Welcome",
-                encrypted_group_info,
-                &group_secrets_bytes,
-                key_package.ciphersuite(),
-                backend.crypto(),
-            )
-            .map_err(|_| {
-                LibraryError::custom(
-                    "Error while encrypting group secrets. \
-                     This could have really only been a missing bounds check in \
-                     the serialization",
+        // Collect the key packages of the new members along with the path
+        // secrets they need to receive.
+        let recipients = invited_members
+            .into_iter()
+            .map(|(leaf_index, add_proposal)| {
+                let key_package = add_proposal.key_package;
+
+                let direct_path_position = self
+                    .subtree_root_position(encryptor_leaf_index, leaf_index)
+                    // This can only fail if the nodes are outside the tree or identical
+                    .map_err(|_| {
+                        LibraryError::custom("Unexpected error in subtree_root_position")
+                    })?;
+
+                // If a plain path was given, there have to be secrets for every new member.
+                let path_secret_option = if let Some(plain_path) = plain_path_option {
+                    Some(
+                        plain_path
+                            .get(direct_path_position)
+                            .map(|pupn| pupn.path_secret())
+                            // This only fails if the supplied plain path is invalid
+                            .ok_or_else(|| LibraryError::custom("Invalid plain path"))?,
+                    )
+                } else {
+                    None
+                };
+                Ok((key_package, path_secret_option))
+            })
+            .collect::<Result<Vec<_>, LibraryError>>()?;
+
+        // The group secrets are encrypted independently for each new member,
+        // so we can do this in parallel.
+        recipients
+            .into_par_iter()
+            .map(|(key_package, path_secret_option)| {
+                // Create the GroupSecrets object for the respective member.
+                let group_secrets_bytes =
+                    GroupSecrets::new_encoded(joiner_secret, path_secret_option, presharedkeys)
+                        .map_err(LibraryError::missing_bound_check)?;
+                let ciphertext = hpke::encrypt_with_label(
+                    key_package.hpke_init_key().as_slice(),
+                    "Welcome",
+                    encrypted_group_info,
+                    &group_secrets_bytes,
+                    key_package.ciphersuite(),
+                    backend.crypto(),
                 )
-            })?;
-            let encrypted_group_secrets =
-                EncryptedGroupSecrets::new(key_package.hash_ref(backend.crypto())?, ciphertext);
-            encrypted_group_secrets_vec.push(encrypted_group_secrets);
-        }
-        Ok(encrypted_group_secrets_vec)
+                .map_err(|_| {
+                    LibraryError::custom(
+                        "Error while encrypting group secrets. \
+                         This could have really only been a missing bounds check in \
+                         the serialization",
+                    )
+                })?;
+                Ok(EncryptedGroupSecrets::new(
+                    key_package.hash_ref(backend.crypto())?,
+                    ciphertext,
+                ))
+            })
+            .collect()
     }
 }
 
@@ -243,6 +258,7 @@ pub(crate) struct DecryptPathParams<'a> {
 #[derive(
     Debug, Eq, PartialEq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct UpdatePathNode {
     pub(super) public_key: EncryptionKey,
     pub(super) encrypted_path_secrets: Vec<HpkeCiphertext>,
@@ -367,6 +383,7 @@ impl UpdatePath {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct UpdatePathIn {
     leaf_node: LeafNodeIn,
     nodes: Vec<UpdatePathNode>,
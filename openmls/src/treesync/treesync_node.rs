@@ -7,7 +7,10 @@ use serde::{Deserialize, Serialize};
 use tls_codec::VLByteSlice;
 
 use crate::{
-    binary_tree::array_representation::{tree::TreeNode, LeafNodeIndex},
+    binary_tree::array_representation::{
+        tree::{BlankNode, TreeNode},
+        LeafNodeIndex,
+    },
     error::LibraryError,
 };
 
@@ -56,12 +59,27 @@ impl From<TreeSyncNode> for TreeNode<TreeSyncLeafNode, TreeSyncParentNode> {
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq))]
 /// This intermediate struct on top of `Option<Node>` allows us to cache tree
 /// hash values. Blank nodes are represented by [`TreeSyncNode`] instances where
 /// `node = None`.
 pub(crate) struct TreeSyncLeafNode {
-    node: Option<LeafNode>,
+    node: Option<Box<LeafNode>>,
+    #[serde(default)]
+    tree_hash: Option<Vec<u8>>,
+}
+
+impl BlankNode for TreeSyncLeafNode {
+    fn is_blank(&self) -> bool {
+        self.node.is_none()
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for TreeSyncLeafNode {
+    fn eq(&self, other: &Self) -> bool {
+        // The cached tree hash is not part of the content of the node.
+        self.node == other.node
+    }
 }
 
 impl TreeSyncLeafNode {
@@ -70,25 +88,37 @@ impl TreeSyncLeafNode {
         Self::default()
     }
 
-    /// Return a reference to the contained `Option<Node>`.
-    pub(in crate::treesync) fn node(&self) -> &Option<LeafNode> {
-        &self.node
+    /// Return a reference to the contained node or `None` if it is blank.
+    pub(in crate::treesync) fn node(&self) -> Option<&LeafNode> {
+        self.node.as_deref()
     }
 
-    /// Return a mutable reference to the contained `Option<Node>`.
-    pub(in crate::treesync) fn node_mut(&mut self) -> &mut Option<LeafNode> {
-        &mut self.node
+    /// Return a mutable reference to the contained node or `None` if it is
+    /// blank. This clears the cached tree hash.
+    pub(in crate::treesync) fn node_mut(&mut self) -> Option<&mut LeafNode> {
+        self.tree_hash = None;
+        self.node.as_deref_mut()
     }
 
-    /// Compute the tree hash for this node, thus populating the `tree_hash`
-    /// field.
+    /// Return the cached tree hash of this node, if there is one.
+    pub(in crate::treesync) fn tree_hash(&self) -> Option<&[u8]> {
+        self.tree_hash.as_deref()
+    }
+
+    /// Cache the tree hash of this node.
+    pub(in crate::treesync) fn set_tree_hash(&mut self, tree_hash: Vec<u8>) {
+        self.tree_hash = Some(tree_hash);
+    }
+
+    /// Compute the tree hash for this node. The result is not cached, see
+    /// [`Self::set_tree_hash()`].
     pub(in crate::treesync) fn compute_tree_hash(
         &self,
         backend: &impl OpenMlsCryptoProvider,
         ciphersuite: Ciphersuite,
         leaf_index: LeafNodeIndex,
     ) -> Result<Vec<u8>, LibraryError> {
-        let hash_input = TreeHashInput::new_leaf(&leaf_index, self.node.as_ref());
+        let hash_input = TreeHashInput::new_leaf(&leaf_index, self.node());
         let hash = hash_input.hash(backend, ciphersuite)?;
 
         Ok(hash)
@@ -97,23 +127,41 @@ impl TreeSyncLeafNode {
 
 impl From<LeafNode> for TreeSyncLeafNode {
     fn from(node: LeafNode) -> Self {
-        Self { node: Some(node) }
+        Self {
+            node: Some(Box::new(node)),
+            tree_hash: None,
+        }
     }
 }
 
 impl From<TreeSyncLeafNode> for Option<Node> {
     fn from(tsln: TreeSyncLeafNode) -> Self {
-        tsln.node.map(Node::LeafNode)
+        tsln.node.map(|node| Node::LeafNode(*node))
     }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq))]
 /// This intermediate struct on top of `Option<Node>` allows us to cache tree
 /// hash values. Blank nodes are represented by [`TreeSyncNode`] instances where
 /// `node = None`.
 pub(crate) struct TreeSyncParentNode {
-    node: Option<ParentNode>,
+    node: Option<Box<ParentNode>>,
+    #[serde(default)]
+    tree_hash: Option<Vec<u8>>,
+}
+
+impl BlankNode for TreeSyncParentNode {
+    fn is_blank(&self) -> bool {
+        self.node.is_none()
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for TreeSyncParentNode {
+    fn eq(&self, other: &Self) -> bool {
+        // The cached tree hash is not part of the content of the node.
+        self.node == other.node
+    }
 }
 
 impl TreeSyncParentNode {
@@ -122,14 +170,26 @@ impl TreeSyncParentNode {
         Self::default()
     }
 
-    /// Return a reference to the contained `Option<Node>`.
-    pub(in crate::treesync) fn node(&self) -> &Option<ParentNode> {
-        &self.node
+    /// Return a reference to the contained node or `None` if it is blank.
+    pub(in crate::treesync) fn node(&self) -> Option<&ParentNode> {
+        self.node.as_deref()
     }
 
-    /// Return a mutable reference to the contained `Option<Node>`.
-    pub(in crate::treesync) fn node_mut(&mut self) -> &mut Option<ParentNode> {
-        &mut self.node
+    /// Return a mutable reference to the contained node or `None` if it is
+    /// blank. This clears the cached tree hash.
+    pub(in crate::treesync) fn node_mut(&mut self) -> Option<&mut ParentNode> {
+        self.tree_hash = None;
+        self.node.as_deref_mut()
+    }
+
+    /// Return the cached tree hash of this node, if there is one.
+    pub(in crate::treesync) fn tree_hash(&self) -> Option<&[u8]> {
+        self.tree_hash.as_deref()
+    }
+
+    /// Cache the tree hash of this node.
+    pub(in crate::treesync) fn set_tree_hash(&mut self, tree_hash: Vec<u8>) {
+        self.tree_hash = Some(tree_hash);
     }
 
     /// Compute the tree hash for this node. Leaf nodes from the exclusion list
@@ -145,12 +205,12 @@ impl TreeSyncParentNode {
         let hash = if exclusion_list.is_empty() {
             // If the exclusion list is empty, we can just use the parent node
             TreeHashInput::new_parent(
-                self.node.as_ref(),
+                self.node(),
                 VLByteSlice(&left_hash),
                 VLByteSlice(&right_hash),
             )
             .hash(backend, ciphersuite)?
-        } else if let Some(parent_node) = self.node.as_ref() {
+        } else if let Some(parent_node) = self.node() {
             // If the exclusion list is not empty, we need to create a new
             // parent node without the excluded indices in the unmerged leaves.
             let mut new_node = parent_node.clone();
@@ -179,12 +239,15 @@ impl TreeSyncParentNode {
 
 impl From<ParentNode> for TreeSyncParentNode {
     fn from(node: ParentNode) -> Self {
-        Self { node: Some(node) }
+        Self {
+            node: Some(Box::new(node)),
+            tree_hash: None,
+        }
     }
 }
 
 impl From<TreeSyncParentNode> for Option<Node> {
     fn from(tspn: TreeSyncParentNode) -> Self {
-        tspn.node.map(Node::ParentNode)
+        tspn.node.map(|node| Node::ParentNode(*node))
     }
 }
@@ -0,0 +1,144 @@
+//! This module contains the [`RatchetTreeView`], a read-only view of the
+//! ratchet tree of a group.
+//!
+//! The view allows inspecting the structure of the tree, e.g. to render it
+//! in debugging tools, without exporting and re-parsing the tree. Nodes are
+//! identified by their index in the array representation of the tree as
+//! specified in Appendix C of the MLS specification, i.e., the leaf with leaf
+//! index `i` has node index `2 * i` and parent nodes have odd node indices.
+
+use std::iter;
+
+use super::{LeafNode, ParentNode, TreeSync};
+use crate::binary_tree::LeafNodeIndex;
+
+/// A read-only view of the ratchet tree of a group.
+#[derive(Debug, Clone, Copy)]
+pub struct RatchetTreeView<'a> {
+    treesync: &'a TreeSync,
+}
+
+impl<'a> RatchetTreeView<'a> {
+    pub(crate) fn new(treesync: &'a TreeSync) -> Self {
+        Self { treesync }
+    }
+
+    /// Returns the tree hash of the tree.
+    pub fn tree_hash(&self) -> &'a [u8] {
+        self.treesync.tree_hash()
+    }
+
+    /// Returns the number of leaves in the tree, including blank leaves.
+    pub fn leaf_count(&self) -> u32 {
+        self.treesync.tree.leaf_count()
+    }
+
+    /// Returns the number of nodes in the tree, including blank nodes.
+    pub fn node_count(&self) -> u32 {
+        self.treesync.tree_size().u32()
+    }
+
+    /// Returns the leaf node at the given `leaf_index` or `None` if the leaf
+    /// is blank or not in the tree.
+    pub fn leaf(&self, leaf_index: LeafNodeIndex) -> Option<&'a LeafNode> {
+        self.treesync.leaf(leaf_index)
+    }
+
+    /// Returns an iterator over all leaves of the tree from left to right,
+    /// including blank leaves.
+    pub fn leaves(&self) -> impl Iterator<Item = (LeafNodeIndex, Option<&'a LeafNode>)> + 'a {
+        self.treesync
+            .tree
+            .leaves()
+            .map(|(leaf_index, leaf)| (leaf_index, leaf.node()))
+    }
+
+    /// Returns an iterator over all nodes of the tree from left to right,
+    /// i.e., in ascending order of their node index.
+    pub fn nodes(&self) -> impl Iterator<Item = TreeNodeView<'a>> + 'a {
+        let parents = self
+            .treesync
+            .tree
+            .parents()
+            .map(|(parent_index, parent)| {
+                Some(TreeNodeView::Parent {
+                    node_index: parent_index.u32() * 2 + 1,
+                    parent_node: parent.node(),
+                })
+            })
+            .chain(iter::once(None));
+
+        self.leaves()
+            .map(|(leaf_index, leaf_node)| TreeNodeView::Leaf {
+                leaf_index,
+                leaf_node,
+            })
+            .zip(parents)
+            .flat_map(|(leaf, parent)| iter::once(leaf).chain(parent))
+    }
+
+    /// Returns the number of blank nodes in the tree.
+    pub fn blank_node_count(&self) -> usize {
+        self.nodes().filter(TreeNodeView::is_blank).count()
+    }
+}
+
+/// A node in a [`RatchetTreeView`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeNodeView<'a> {
+    /// A leaf of the tree. The `leaf_node` is `None` if the leaf is blank.
+    Leaf {
+        /// The leaf index of the leaf.
+        leaf_index: LeafNodeIndex,
+        /// The leaf node, if the leaf is not blank.
+        leaf_node: Option<&'a LeafNode>,
+    },
+    /// A parent node of the tree. The `parent_node` is `None` if the node is
+    /// blank.
+    Parent {
+        /// The node index of the parent node.
+        node_index: u32,
+        /// The parent node, if the node is not blank.
+        parent_node: Option<&'a ParentNode>,
+    },
+}
+
+impl<'a> TreeNodeView<'a> {
+    /// Returns the index of the node in the array representation of the
+    /// tree.
+    pub fn node_index(&self) -> u32 {
+        match *self {
+            TreeNodeView::Leaf { leaf_index, .. } => leaf_index.u32() * 2,
+            TreeNodeView::Parent { node_index, .. } => node_index,
+        }
+    }
+
+    /// Returns `true` if the node is blank.
+    pub fn is_blank(&self) -> bool {
+        match *self {
+            TreeNodeView::Leaf { leaf_node, .. } => leaf_node.is_none(),
+            TreeNodeView::Parent { parent_node, .. } => parent_node.is_none(),
+        }
+    }
+
+    /// Returns the parent hash of the node, if it is not blank. The parent
+    /// hash of leaf nodes is only set if they were created in a commit.
+    pub fn parent_hash(&self) -> Option<&'a [u8]> {
+        match *self {
+            TreeNodeView::Leaf { leaf_node, .. } => leaf_node.and_then(LeafNode::parent_hash),
+            TreeNodeView::Parent { parent_node, .. } => parent_node.map(ParentNode::parent_hash),
+        }
+    }
+
+    /// Returns the unmerged leaves of a parent node. The list is empty for
+    /// leaves and blank nodes.
+    pub fn unmerged_leaves(&self) -> &'a [LeafNodeIndex] {
+        match *self {
+            TreeNodeView::Parent {
+                parent_node: Some(parent_node),
+                ..
+            } => parent_node.unmerged_leaves(),
+            _ => &[],
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! # MLS versions
 //!
-//! Only MLS 1.0 is currently supported.
+//! Only MLS 1.0 as published in [RFC 9420](https://www.rfc-editor.org/rfc/rfc9420.html)
+//! is currently supported.
 
 use serde::{Deserialize, Serialize};
 use std::{convert::TryFrom, fmt};
@@ -34,12 +35,15 @@ use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
 )]
 #[repr(u16)]
 #[allow(missing_docs)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ProtocolVersion {
     Mls10 = 1,
-    Mls10Draft11 = 200, // pre RFC version
+    /// A pre-RFC version. It is only kept so that previously serialized
+    /// state can still be read. Groups with this version can't be joined.
+    Mls10Draft11 = 200,
 }
 
-/// There's only one version right now, which is the default.
+/// The default is MLS 1.0 as published in RFC 9420.
 impl Default for ProtocolVersion {
     fn default() -> Self {
         ProtocolVersion::Mls10
@@ -0,0 +1,126 @@
+//! Allocation counting for message processing.
+//!
+//! This test is opt-in, since it measures allocations with a custom global
+//! allocator. Run it with
+//!
+//! ```text
+//! cargo test -p openmls --test allocations -- --ignored --nocapture
+//! ```
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use openmls::prelude::{config::CryptoConfig, test_utils::new_credential, *};
+use openmls_rust_crypto::OpenMlsRustCrypto;
+
+/// The maximum number of allocations for processing an application message.
+/// Lower this bound when removing allocations from the processing path.
+const MAX_ALLOCATIONS: usize = 250;
+
+/// A global allocator that counts the allocations of the current thread.
+struct CountingAllocator;
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.with(Cell::get) {
+            ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the result of `f` and the number of allocations it performed.
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    ALLOCATIONS.with(|allocations| allocations.set(0));
+    COUNTING.with(|counting| counting.set(true));
+    let result = f();
+    COUNTING.with(|counting| counting.set(false));
+    (result, ALLOCATIONS.with(Cell::get))
+}
+
+#[test]
+#[ignore]
+fn application_message_allocations() {
+    let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+    let backend = &OpenMlsRustCrypto::default();
+
+    let (alice_credential, alice_signer) = new_credential(
+        backend,
+        b"Alice",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let (bob_credential, bob_signer) = new_credential(
+        backend,
+        b"Bob",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let bob_key_package = KeyPackage::builder()
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signer,
+            bob_credential,
+        )
+        .unwrap();
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+    let mut alice_group =
+        MlsGroup::new(backend, &alice_signer, &mls_group_config, alice_credential)
+            .expect("An unexpected error occurred.");
+    let (_, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_key_package])
+        .expect("An unexpected error occurred.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("An unexpected error occurred.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("An unexpected error occurred.");
+
+    // The first message initializes the sender's ratchet, so we measure the
+    // second one.
+    let mut create_message = || {
+        alice_group
+            .create_message(backend, &alice_signer, b"Hello, Bob!")
+            .expect("An unexpected error occurred.")
+            .into_protocol_message()
+            .expect("Unexpected message type.")
+    };
+    let first = create_message();
+    let second = create_message();
+    bob_group
+        .process_message(backend, first)
+        .expect("An unexpected error occurred.");
+
+    let (processed_message, allocations) =
+        count_allocations(|| bob_group.process_message(backend, second));
+    processed_message.expect("An unexpected error occurred.");
+
+    assert!(
+        allocations <= MAX_ALLOCATIONS,
+        "{allocations} allocations exceed the maximum of {MAX_ALLOCATIONS}."
+    );
+}
@@ -48,3 +48,69 @@ fn test_mls_group_api(ciphersuite: Ciphersuite) {
     // Check that all group members agree on the same group state.
     setup.check_group_states(group);
 }
+
+#[apply(ciphersuites)]
+fn test_setup_snapshot(ciphersuite: Ciphersuite) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let number_of_clients = 10;
+    let setup = MlsGroupTestSetup::new(
+        mls_group_config,
+        number_of_clients,
+        CodecUse::SerializedMessages,
+    );
+
+    let group_id = setup
+        .create_random_group(3, ciphersuite)
+        .expect("An unexpected error occurred.");
+    let epoch_of = |setup: &MlsGroupTestSetup, client_id: &[u8]| {
+        let clients = setup.clients.read().expect("An unexpected error occurred.");
+        let client = clients
+            .get(client_id)
+            .expect("An unexpected error occurred.")
+            .read()
+            .expect("An unexpected error occurred.");
+        let groups = client.groups.read().expect("An unexpected error occurred.");
+        groups.get(&group_id).map(|group| group.epoch())
+    };
+    let (_, member_id) = setup.groups.read().unwrap()[&group_id]
+        .members()
+        .next()
+        .unwrap();
+    let epoch = epoch_of(&setup, &member_id);
+
+    let snapshot = setup.snapshot();
+
+    // First scenario: Add two new members.
+    {
+        let mut groups = setup.groups.write().expect("An unexpected error occurred.");
+        let group = groups
+            .get_mut(&group_id)
+            .expect("An unexpected error occurred.");
+        let new_members = setup
+            .random_new_members_for_group(group, 2)
+            .expect("An unexpected error occurred.");
+        setup
+            .add_clients(ActionType::Commit, group, &member_id, new_members)
+            .expect("An unexpected error occurred.");
+        assert_eq!(group.members().count(), 5);
+        setup.check_group_states(group);
+    }
+    assert_ne!(epoch_of(&setup, &member_id), epoch);
+
+    setup.restore(&snapshot);
+    assert_eq!(epoch_of(&setup, &member_id), epoch);
+
+    // Second scenario: Branch off the same state with an update.
+    {
+        let mut groups = setup.groups.write().expect("An unexpected error occurred.");
+        let group = groups
+            .get_mut(&group_id)
+            .expect("An unexpected error occurred.");
+        assert_eq!(group.members().count(), 3);
+        setup.check_group_states(group);
+        setup
+            .self_update(ActionType::Commit, group, &member_id, None)
+            .expect("An unexpected error occurred.");
+        setup.check_group_states(group);
+    }
+}
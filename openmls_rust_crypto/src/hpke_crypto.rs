@@ -0,0 +1,162 @@
+//! # HPKE with RustCrypto
+//!
+//! `hpke-rs-rust-crypto` only implements the DH-KEMs on P-256 and X25519.
+//! [`HpkeCryptoProvider`] adds the DH-KEMs on P-384, P-521 and X448 that are
+//! needed for the remaining ciphersuites of RFC 9420 and uses
+//! [`HpkeRustCrypto`] for everything else.
+
+use hpke_rs_crypto::{
+    error::Error,
+    types::{AeadAlgorithm, KdfAlgorithm, KemAlgorithm},
+    HpkeCrypto,
+};
+use hpke_rs_rust_crypto::HpkeRustCrypto;
+use p384::elliptic_curve::sec1::ToEncodedPoint as _;
+use p521::elliptic_curve::sec1::ToEncodedPoint as _;
+use rand::RngCore;
+
+/// The length of X448 keys and shared secrets in bytes.
+const X448_LENGTH: usize = 56;
+
+#[derive(Debug)]
+pub(crate) struct HpkeCryptoProvider;
+
+impl HpkeCrypto for HpkeCryptoProvider {
+    type HpkePrng = <HpkeRustCrypto as HpkeCrypto>::HpkePrng;
+
+    fn name() -> String {
+        "RustCrypto with P-384, P-521 and X448".into()
+    }
+
+    fn kdf_extract(alg: KdfAlgorithm, salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+        HpkeRustCrypto::kdf_extract(alg, salt, ikm)
+    }
+
+    fn kdf_expand(
+        alg: KdfAlgorithm,
+        prk: &[u8],
+        info: &[u8],
+        output_size: usize,
+    ) -> Result<Vec<u8>, Error> {
+        HpkeRustCrypto::kdf_expand(alg, prk, info, output_size)
+    }
+
+    fn kem_derive(alg: KemAlgorithm, pk: &[u8], sk: &[u8]) -> Result<Vec<u8>, Error> {
+        match alg {
+            KemAlgorithm::DhKemP384 => {
+                let pk =
+                    p384::PublicKey::from_sec1_bytes(pk).map_err(|_| Error::KemInvalidPublicKey)?;
+                let sk =
+                    p384::SecretKey::from_be_bytes(sk).map_err(|_| Error::KemInvalidSecretKey)?;
+                let shared_secret =
+                    p384::ecdh::diffie_hellman(sk.to_nonzero_scalar(), pk.as_affine());
+                Ok(shared_secret.raw_secret_bytes().to_vec())
+            }
+            KemAlgorithm::DhKemP521 => {
+                let pk =
+                    p521::PublicKey::from_sec1_bytes(pk).map_err(|_| Error::KemInvalidPublicKey)?;
+                let sk = p521::SecretKey::from_slice(sk).map_err(|_| Error::KemInvalidSecretKey)?;
+                let shared_secret =
+                    p521::ecdh::diffie_hellman(sk.to_nonzero_scalar(), pk.as_affine());
+                Ok(shared_secret.raw_secret_bytes().to_vec())
+            }
+            KemAlgorithm::DhKem448 => {
+                let pk = x448::PublicKey::from_bytes(pk).ok_or(Error::KemInvalidPublicKey)?;
+                let sk = x448::Secret::from_bytes(sk).ok_or(Error::KemInvalidSecretKey)?;
+                // Fails if the shared secret is all zeros, i.e., `pk` has low order.
+                let shared_secret = sk
+                    .as_diffie_hellman(&pk)
+                    .ok_or(Error::KemInvalidPublicKey)?;
+                Ok(shared_secret.as_bytes().to_vec())
+            }
+            _ => HpkeRustCrypto::kem_derive(alg, pk, sk),
+        }
+    }
+
+    fn kem_derive_base(alg: KemAlgorithm, sk: &[u8]) -> Result<Vec<u8>, Error> {
+        match alg {
+            KemAlgorithm::DhKemP384 => {
+                let sk =
+                    p384::SecretKey::from_be_bytes(sk).map_err(|_| Error::KemInvalidSecretKey)?;
+                Ok(sk.public_key().to_encoded_point(false).as_bytes().to_vec())
+            }
+            KemAlgorithm::DhKemP521 => {
+                let sk = p521::SecretKey::from_slice(sk).map_err(|_| Error::KemInvalidSecretKey)?;
+                Ok(sk.public_key().to_encoded_point(false).as_bytes().to_vec())
+            }
+            KemAlgorithm::DhKem448 => {
+                let sk = x448::Secret::from_bytes(sk).ok_or(Error::KemInvalidSecretKey)?;
+                Ok(x448::PublicKey::from(&sk).as_bytes().to_vec())
+            }
+            _ => HpkeRustCrypto::kem_derive_base(alg, sk),
+        }
+    }
+
+    fn kem_key_gen(alg: KemAlgorithm, prng: &mut Self::HpkePrng) -> Result<Vec<u8>, Error> {
+        match alg {
+            KemAlgorithm::DhKemP384 => Ok(p384::SecretKey::random(prng).to_be_bytes().to_vec()),
+            KemAlgorithm::DhKemP521 => Ok(p521::SecretKey::random(prng).to_bytes().to_vec()),
+            KemAlgorithm::DhKem448 => {
+                let mut sk = vec![0u8; X448_LENGTH];
+                prng.try_fill_bytes(&mut sk)
+                    .map_err(|_| Error::InsufficientRandomness)?;
+                Ok(sk)
+            }
+            _ => HpkeRustCrypto::kem_key_gen(alg, prng),
+        }
+    }
+
+    fn kem_validate_sk(alg: KemAlgorithm, sk: &[u8]) -> Result<Vec<u8>, Error> {
+        match alg {
+            KemAlgorithm::DhKemP384 => p384::SecretKey::from_be_bytes(sk)
+                .map(|_| sk.to_vec())
+                .map_err(|_| Error::KemInvalidSecretKey),
+            KemAlgorithm::DhKemP521 => p521::SecretKey::from_slice(sk)
+                .map(|_| sk.to_vec())
+                .map_err(|_| Error::KemInvalidSecretKey),
+            // Every byte string of the right length is a valid X448 key.
+            KemAlgorithm::DhKem448 if sk.len() == X448_LENGTH => Ok(sk.to_vec()),
+            KemAlgorithm::DhKem448 => Err(Error::KemInvalidSecretKey),
+            _ => HpkeRustCrypto::kem_validate_sk(alg, sk),
+        }
+    }
+
+    fn aead_seal(
+        alg: AeadAlgorithm,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        msg: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        HpkeRustCrypto::aead_seal(alg, key, nonce, aad, msg)
+    }
+
+    fn aead_open(
+        alg: AeadAlgorithm,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        msg: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        HpkeRustCrypto::aead_open(alg, key, nonce, aad, msg)
+    }
+
+    fn prng() -> Self::HpkePrng {
+        HpkeRustCrypto::prng()
+    }
+
+    fn supports_kdf(alg: KdfAlgorithm) -> Result<(), Error> {
+        HpkeRustCrypto::supports_kdf(alg)
+    }
+
+    fn supports_kem(alg: KemAlgorithm) -> Result<(), Error> {
+        match alg {
+            KemAlgorithm::DhKemP384 | KemAlgorithm::DhKemP521 | KemAlgorithm::DhKem448 => Ok(()),
+            _ => HpkeRustCrypto::supports_kem(alg),
+        }
+    }
+
+    fn supports_aead(alg: AeadAlgorithm) -> Result<(), Error> {
+        HpkeRustCrypto::supports_aead(alg)
+    }
+}
@@ -3,12 +3,18 @@
 //! This is an implementation of the [`OpenMlsCryptoProvider`] trait to use with
 //! OpenMLS.
 
-pub use openmls_memory_keystore::{MemoryKeyStore, MemoryKeyStoreError};
+pub use openmls_memory_keystore::{MemoryKeyStore, MemoryKeyStoreError, MemoryKeyStoreSnapshot};
 use openmls_traits::OpenMlsCryptoProvider;
 
+mod hpke_crypto;
 mod provider;
 pub use provider::*;
 
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
+
 #[derive(Default, Debug)]
 pub struct OpenMlsRustCrypto {
     crypto: RustCrypto,
@@ -0,0 +1,102 @@
+//! # OpenMLS Crypto Provider with SQLite Key Store
+//!
+//! An implementation of the [`OpenMlsCryptoProvider`] trait that uses the
+//! RustCrypto primitives and persists all key material and group states in
+//! an SQLite database.
+//!
+//! Several operations of OpenMLS, e.g., processing and merging a commit, can
+//! be persisted atomically with [`OpenMlsRustCryptoSqlite::transaction()`].
+
+use std::path::Path;
+
+pub use openmls_sqlite_keystore::{SqliteKeyStore, SqliteKeyStoreError, SqliteTransaction};
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::RustCrypto;
+
+#[derive(Debug)]
+pub struct OpenMlsRustCryptoSqlite {
+    crypto: RustCrypto,
+    key_store: SqliteKeyStore,
+}
+
+impl OpenMlsRustCryptoSqlite {
+    /// Creates a provider that uses the given `key_store`.
+    pub fn new(key_store: SqliteKeyStore) -> Self {
+        Self {
+            crypto: RustCrypto::default(),
+            key_store,
+        }
+    }
+
+    /// Creates a provider with the key store in the SQLite database at
+    /// `path`. The database is created if it doesn't exist.
+    ///
+    /// Returns an error if the database can't be opened.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SqliteKeyStoreError> {
+        Ok(Self::new(SqliteKeyStore::open(path)?))
+    }
+
+    /// Runs `f` in a transaction of the key store. `f` gets a provider whose
+    /// key store is the transaction, see [`SqliteKeyStore::transaction()`].
+    /// All changes made by `f` through this provider are committed if `f`
+    /// returns `Ok`, and rolled back otherwise.
+    ///
+    /// Returns an error if the transaction can't be started or committed, or
+    /// the error returned by `f`.
+    pub fn transaction<T, E: From<SqliteKeyStoreError>>(
+        &self,
+        f: impl FnOnce(&OpenMlsRustCryptoSqliteTransaction) -> Result<T, E>,
+    ) -> Result<T, E> {
+        self.key_store.transaction(|key_store| {
+            f(&OpenMlsRustCryptoSqliteTransaction {
+                crypto: &self.crypto,
+                key_store,
+            })
+        })
+    }
+}
+
+impl OpenMlsCryptoProvider for OpenMlsRustCryptoSqlite {
+    type CryptoProvider = RustCrypto;
+    type RandProvider = RustCrypto;
+    type KeyStoreProvider = SqliteKeyStore;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        &self.crypto
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        &self.crypto
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
+
+/// The provider that is used in a transaction, see
+/// [`OpenMlsRustCryptoSqlite::transaction()`].
+#[derive(Debug)]
+pub struct OpenMlsRustCryptoSqliteTransaction<'a, 'b> {
+    crypto: &'a RustCrypto,
+    key_store: &'a SqliteTransaction<'b>,
+}
+
+impl<'b> OpenMlsCryptoProvider for OpenMlsRustCryptoSqliteTransaction<'_, 'b> {
+    type CryptoProvider = RustCrypto;
+    type RandProvider = RustCrypto;
+    type KeyStoreProvider = SqliteTransaction<'b>;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        self.crypto
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        self.crypto
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        self.key_store
+    }
+}
@@ -0,0 +1,293 @@
+//! # SQLite Keystore
+//!
+//! A persistent [`OpenMlsKeyStore`] on top of SQLite. All entities managed by
+//! OpenMLS, i.e., key material as well as the states of the groups, are
+//! stored in a single table, indexed by their ID in the key store.
+//!
+//! Every call to the [`OpenMlsKeyStore`] is atomic. Several calls, e.g., all
+//! changes of processing and merging a commit, can be grouped into a single
+//! transaction with [`SqliteKeyStore::transaction()`], such that they are
+//! either all persisted or none of them. The transaction holds the lock of the
+//! database connection, i.e., other users of the key store wait until the
+//! transaction is finished.
+//!
+//! ```
+//! use openmls_sqlite_keystore::SqliteKeyStore;
+//!
+//! let key_store = SqliteKeyStore::open_in_memory().expect("Error opening the key store.");
+//! ```
+
+use std::{path::Path, sync::Mutex};
+
+use openmls_traits::key_store::{MlsEntity, MlsEntityId, OpenMlsKeyStore};
+use rusqlite::{params, Connection, OptionalExtension};
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS openmls_key_store (
+    id BLOB PRIMARY KEY,
+    entity TEXT NOT NULL,
+    value BLOB NOT NULL
+)";
+
+/// A key store that persists all values in an SQLite database.
+#[derive(Debug)]
+pub struct SqliteKeyStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteKeyStore {
+    /// Opens the key store in the SQLite database at `path`. The database is
+    /// created if it doesn't exist.
+    ///
+    /// Returns an error if the database can't be opened.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SqliteKeyStoreError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Opens a key store in a new in-memory SQLite database, e.g., for tests.
+    ///
+    /// Returns an error if the database can't be opened.
+    pub fn open_in_memory() -> Result<Self, SqliteKeyStoreError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Creates a key store from an open `connection`, e.g., to share the
+    /// database with the application. The table of the key store is created
+    /// if it doesn't exist.
+    ///
+    /// Returns an error if the table can't be created.
+    pub fn from_connection(connection: Connection) -> Result<Self, SqliteKeyStoreError> {
+        connection.execute(CREATE_TABLE, [])?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Runs `f` in a transaction. All changes made by `f` to the
+    /// [`SqliteTransaction`] are committed if `f` returns `Ok`, and rolled
+    /// back otherwise.
+    ///
+    /// The lock of the database connection is held until the transaction is
+    /// finished, i.e., other users of the key store, including other threads,
+    /// wait until then. Thus, `f` must only access the key store through the
+    /// [`SqliteTransaction`]. Using the [`SqliteKeyStore`] in `f` deadlocks.
+    ///
+    /// Returns an error if the transaction can't be started or committed, or
+    /// the error returned by `f`.
+    pub fn transaction<T, E: From<SqliteKeyStoreError>>(
+        &self,
+        f: impl FnOnce(&SqliteTransaction) -> Result<T, E>,
+    ) -> Result<T, E> {
+        // We unwrap here, because the connection should not panic while the
+        // lock is held.
+        let mut connection = self.connection.lock().unwrap();
+        connection
+            .execute_batch("BEGIN IMMEDIATE")
+            .map_err(SqliteKeyStoreError::from)?;
+        let result = f(&SqliteTransaction {
+            connection: Mutex::new(&mut *connection),
+        });
+        match result {
+            Ok(value) => {
+                connection
+                    .execute_batch("COMMIT")
+                    .map_err(SqliteKeyStoreError::from)?;
+                Ok(value)
+            }
+            Err(e) => {
+                // The error of `f` is more relevant than a failing rollback.
+                let _ = connection.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+}
+
+/// A transaction of a [`SqliteKeyStore`], see
+/// [`SqliteKeyStore::transaction()`]. It implements the [`OpenMlsKeyStore`],
+/// e.g., to use it in an
+/// [`OpenMlsCryptoProvider`](openmls_traits::OpenMlsCryptoProvider) while the
+/// transaction runs.
+#[derive(Debug)]
+pub struct SqliteTransaction<'a> {
+    connection: Mutex<&'a mut Connection>,
+}
+
+/// Returns the name of the entity in the table of the key store.
+fn entity_name(id: MlsEntityId) -> &'static str {
+    match id {
+        MlsEntityId::SignatureKeyPair => "SignatureKeyPair",
+        MlsEntityId::HpkePrivateKey => "HpkePrivateKey",
+        MlsEntityId::KeyPackage => "KeyPackage",
+        MlsEntityId::PskBundle => "PskBundle",
+        MlsEntityId::EncryptionKeyPair => "EncryptionKeyPair",
+        MlsEntityId::GroupState => "GroupState",
+        MlsEntityId::ClientState => "ClientState",
+    }
+}
+
+/// Stores the value `v` for ID `k` in the database of `connection`.
+fn store<V: MlsEntity>(
+    connection: &Connection,
+    k: &[u8],
+    v: &V,
+) -> Result<(), SqliteKeyStoreError> {
+    let value = serde_json::to_vec(v).map_err(|_| SqliteKeyStoreError::SerializationError)?;
+    connection.execute(
+        "INSERT OR REPLACE INTO openmls_key_store (id, entity, value) VALUES (?1, ?2, ?3)",
+        params![k, entity_name(V::ID), value],
+    )?;
+    Ok(())
+}
+
+/// Reads the value for ID `k` from the database of `connection`.
+fn read<V: MlsEntity>(connection: &Connection, k: &[u8]) -> Option<V> {
+    let value: Vec<u8> = connection
+        .query_row(
+            "SELECT value FROM openmls_key_store WHERE id = ?1",
+            params![k],
+            |row| row.get(0),
+        )
+        .optional()
+        .ok()??;
+    serde_json::from_slice(&value).ok()
+}
+
+/// Deletes the value for ID `k` from the database of `connection`.
+fn delete(connection: &Connection, k: &[u8]) -> Result<(), SqliteKeyStoreError> {
+    connection.execute("DELETE FROM openmls_key_store WHERE id = ?1", params![k])?;
+    Ok(())
+}
+
+impl OpenMlsKeyStore for SqliteKeyStore {
+    /// The error type returned by the [`OpenMlsKeyStore`].
+    type Error = SqliteKeyStoreError;
+
+    /// Store a value `v` that implements the [`MlsEntity`] trait for
+    /// serialization for ID `k`, replacing a value that was stored for `k`
+    /// before.
+    ///
+    /// Returns an error if storing fails.
+    fn store<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), Self::Error> {
+        store(&self.connection.lock().unwrap(), k, v)
+    }
+
+    /// Read and return a value stored for ID `k` that implements the
+    /// [`MlsEntity`] trait for deserialization.
+    ///
+    /// Returns [`None`] if no value is stored for `k` or reading fails.
+    fn read<V: MlsEntity>(&self, k: &[u8]) -> Option<V> {
+        read(&self.connection.lock().unwrap(), k)
+    }
+
+    /// Delete a value stored for ID `k`.
+    ///
+    /// Returns an error if deleting fails.
+    fn delete<V: MlsEntity>(&self, k: &[u8]) -> Result<(), Self::Error> {
+        delete(&self.connection.lock().unwrap(), k)
+    }
+}
+
+impl OpenMlsKeyStore for SqliteTransaction<'_> {
+    /// The error type returned by the [`OpenMlsKeyStore`].
+    type Error = SqliteKeyStoreError;
+
+    /// Store a value `v` that implements the [`MlsEntity`] trait for
+    /// serialization for ID `k` in the transaction, replacing a value that
+    /// was stored for `k` before.
+    ///
+    /// Returns an error if storing fails.
+    fn store<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), Self::Error> {
+        store(&self.connection.lock().unwrap(), k, v)
+    }
+
+    /// Read and return a value stored for ID `k` that implements the
+    /// [`MlsEntity`] trait for deserialization, including the changes of the
+    /// transaction.
+    ///
+    /// Returns [`None`] if no value is stored for `k` or reading fails.
+    fn read<V: MlsEntity>(&self, k: &[u8]) -> Option<V> {
+        read(&self.connection.lock().unwrap(), k)
+    }
+
+    /// Delete a value stored for ID `k` in the transaction.
+    ///
+    /// Returns an error if deleting fails.
+    fn delete<V: MlsEntity>(&self, k: &[u8]) -> Result<(), Self::Error> {
+        delete(&self.connection.lock().unwrap(), k)
+    }
+}
+
+/// Errors thrown by the key store.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum SqliteKeyStoreError {
+    #[error("Error accessing the database: {0}")]
+    SqliteError(String),
+    #[error("Error serializing value.")]
+    SerializationError,
+}
+
+impl From<rusqlite::Error> for SqliteKeyStoreError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::SqliteError(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct GroupState(Vec<u8>);
+
+    impl MlsEntity for GroupState {
+        const ID: MlsEntityId = MlsEntityId::GroupState;
+    }
+
+    #[test]
+    fn store_read_delete() {
+        let key_store = SqliteKeyStore::open_in_memory().unwrap();
+        assert!(key_store.read::<GroupState>(b"id").is_none());
+
+        key_store.store(b"id", &GroupState(vec![1])).unwrap();
+        key_store.store(b"id", &GroupState(vec![2])).unwrap();
+        assert_eq!(
+            key_store.read::<GroupState>(b"id"),
+            Some(GroupState(vec![2]))
+        );
+
+        key_store.delete::<GroupState>(b"id").unwrap();
+        assert!(key_store.read::<GroupState>(b"id").is_none());
+    }
+
+    #[test]
+    fn transaction() {
+        let key_store = SqliteKeyStore::open_in_memory().unwrap();
+
+        key_store
+            .transaction(|transaction| {
+                transaction.store(b"a", &GroupState(vec![1]))?;
+                assert!(transaction.read::<GroupState>(b"a").is_some());
+                transaction.store(b"b", &GroupState(vec![2]))
+            })
+            .unwrap();
+        assert!(key_store.read::<GroupState>(b"a").is_some());
+        assert!(key_store.read::<GroupState>(b"b").is_some());
+
+        // All changes are rolled back if the transaction fails. Changes of
+        // other threads wait for the transaction and are not rolled back.
+        std::thread::scope(|scope| {
+            let result: Result<(), SqliteKeyStoreError> = key_store.transaction(|transaction| {
+                scope.spawn(|| key_store.store(b"d", &GroupState(vec![4])).unwrap());
+                transaction.delete::<GroupState>(b"a")?;
+                transaction.store(b"c", &GroupState(vec![3]))?;
+                Err(SqliteKeyStoreError::SerializationError)
+            });
+            assert_eq!(result, Err(SqliteKeyStoreError::SerializationError));
+        });
+        assert!(key_store.read::<GroupState>(b"a").is_some());
+        assert!(key_store.read::<GroupState>(b"c").is_none());
+        assert!(key_store.read::<GroupState>(b"d").is_some());
+    }
+}
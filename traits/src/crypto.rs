@@ -96,6 +96,9 @@ pub trait OpenMlsCrypto {
     // === HPKE === //
 
     /// HPKE single-shot encryption of `ptxt` to `pk_r`, using `info` and `aad`.
+    ///
+    /// Returns an error if the HPKE configuration is not supported or the
+    /// encryption fails, e.g., because `pk_r` is invalid.
     fn hpke_seal(
         &self,
         config: HpkeConfig,
@@ -103,7 +106,7 @@ pub trait OpenMlsCrypto {
         info: &[u8],
         aad: &[u8],
         ptxt: &[u8],
-    ) -> HpkeCiphertext;
+    ) -> Result<HpkeCiphertext, CryptoError>;
 
     /// HPKE single-shot decryption of `input` with `sk_r`, using `info` and
     /// `aad`.
@@ -1,9 +1,20 @@
 use crate::types::{Error, SignatureScheme};
 
 /// Sign the provided payload and return a signature.
+///
+/// The signer may be backed by a key that never leaves dedicated hardware,
+/// e.g., a secure element that requires the user's presence for every
+/// signature. OpenMLS therefore calls [`Signer::sign()`] at most once per
+/// signed structure of an operation, and returns the [`Error`] of the signer
+/// to the caller, such that, e.g., [`Error::SignerUnavailable`] can be
+/// retried.
 pub trait Signer {
     /// Sign the provided payload.
     ///
+    /// The payload is the serialized `SignContent` of RFC 9420, i.e., the
+    /// label of the signed structure prefixed with `"MLS 1.0 "` and the
+    /// serialized structure, each with a variable-length prefix.
+    ///
     /// Returns a signature on success or an Error.
     fn sign(&self, payload: &[u8]) -> Result<Vec<u8>, Error>;
 
@@ -14,7 +14,9 @@ pub mod types;
 /// An implementation of this trait must be passed in to the public OpenMLS API
 /// to perform randomness generation, cryptographic operations, and key storage.
 pub trait OpenMlsCryptoProvider: Send + Sync {
-    type CryptoProvider: crypto::OpenMlsCrypto;
+    /// The crypto provider is shared between threads when OpenMLS performs
+    /// independent operations, e.g., signature verifications, in parallel.
+    type CryptoProvider: crypto::OpenMlsCrypto + Sync;
     type RandProvider: random::OpenMlsRand;
     type KeyStoreProvider: key_store::OpenMlsKeyStore;
 
@@ -121,6 +121,12 @@ pub enum Error {
     CryptoError(CryptoError),
     InvalidSignature,
     SigningError,
+    /// The signer is temporarily unavailable, e.g., because the hardware
+    /// holding the key is locked or busy. Retrying the operation may succeed.
+    SignerUnavailable,
+    /// The signing was cancelled, e.g., because the user didn't confirm it
+    /// when the signer requires user presence.
+    SigningCancelled,
 }
 
 /// Crypto errors.
@@ -146,6 +152,8 @@ pub enum CryptoError {
     ExporterError,
     UnsupportedCiphersuite,
     TlsSerializationError,
+    UnsupportedKem,
+    HpkeEncryptionError,
 }
 
 impl std::fmt::Display for CryptoError {
@@ -180,6 +188,9 @@ pub enum HpkeKemType {
 
     /// DH KEM on x448
     DhKem448 = 0x0021,
+
+    /// Hybrid KEM combining DH KEM on x25519 and Kyber768 (draft 00)
+    X25519Kyber768Draft00 = 0x0030,
 }
 
 /// KDF Types for HPKE
@@ -224,6 +235,7 @@ pub enum HpkeAeadType {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct HpkeCiphertext {
     pub kem_output: VLBytes,
     pub ciphertext: VLBytes,
@@ -245,6 +257,7 @@ pub type KemOutput = Vec<u8>;
 #[derive(
     Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct VerifiableCiphersuite(u16);
 
 impl VerifiableCiphersuite {
@@ -278,6 +291,7 @@ impl From<Ciphersuite> for VerifiableCiphersuite {
     TlsSize,
 )]
 #[repr(u16)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Ciphersuite {
     /// DH KEM x25519 | AES-GCM 128 | SHA2-256 | Ed25519
     MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519 = 0x0001,
@@ -299,6 +313,13 @@ pub enum Ciphersuite {
 
     /// DH KEM P384 | AES-GCM 256 | SHA2-384 | EcDSA P384
     MLS_256_DHKEMP384_AES256GCM_SHA384_P384 = 0x0007,
+
+    /// X25519Kyber768 hybrid KEM | AES-GCM 128 | SHA2-256 | Ed25519
+    ///
+    /// **WARNING**: This ciphersuite is experimental and uses a value from the
+    /// private use range until a code point is assigned.
+    #[cfg(feature = "hybrid-kem")]
+    MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519 = 0xF031,
 }
 
 impl core::fmt::Display for Ciphersuite {
@@ -334,6 +355,8 @@ impl TryFrom<u16> for Ciphersuite {
             0x0005 => Ok(Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521),
             0x0006 => Ok(Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448),
             0x0007 => Ok(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384),
+            #[cfg(feature = "hybrid-kem")]
+            0xF031 => Ok(Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519),
             _ => Err(Self::Error::DecodingError(format!(
                 "{v} is not a valid ciphersuite value"
             ))),
@@ -397,6 +420,10 @@ impl Ciphersuite {
             Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448
             | Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
             | Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448 => HashType::Sha2_512,
+            #[cfg(feature = "hybrid-kem")]
+            Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519 => {
+                HashType::Sha2_256
+            }
         }
     }
 
@@ -421,6 +448,10 @@ impl Ciphersuite {
             Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => {
                 SignatureScheme::ECDSA_SECP384R1_SHA384
             }
+            #[cfg(feature = "hybrid-kem")]
+            Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519 => {
+                SignatureScheme::ED25519
+            }
         }
     }
 
@@ -437,6 +468,10 @@ impl Ciphersuite {
             Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448
             | Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
             | Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => AeadType::Aes256Gcm,
+            #[cfg(feature = "hybrid-kem")]
+            Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519 => {
+                AeadType::Aes128Gcm
+            }
         }
     }
 
@@ -455,6 +490,10 @@ impl Ciphersuite {
             | Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448 => {
                 HpkeKdfType::HkdfSha512
             }
+            #[cfg(feature = "hybrid-kem")]
+            Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519 => {
+                HpkeKdfType::HkdfSha256
+            }
         }
     }
 
@@ -471,6 +510,10 @@ impl Ciphersuite {
             | Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448 => HpkeKemType::DhKem448,
             Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => HpkeKemType::DhKemP384,
             Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521 => HpkeKemType::DhKemP521,
+            #[cfg(feature = "hybrid-kem")]
+            Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519 => {
+                HpkeKemType::X25519Kyber768Draft00
+            }
         }
     }
 
@@ -489,6 +532,10 @@ impl Ciphersuite {
             Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448 => {
                 HpkeAeadType::ChaCha20Poly1305
             }
+            #[cfg(feature = "hybrid-kem")]
+            Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519 => {
+                HpkeAeadType::AesGcm128
+            }
         }
     }
 
@@ -0,0 +1,239 @@
+//! The client object of the bindings.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use openmls::prelude::*;
+
+use crate::{
+    error::FfiError,
+    group::Group,
+    key_store::{ForeignKeyStore, KeyStore},
+    message::ReceivedMessage,
+    provider::Backend,
+    signer::{ForeignSigner, Signer},
+};
+
+/// A client that manages any number of groups.
+///
+/// The client uses a basic credential with the given identity. Its state is
+/// persisted in the foreign [`KeyStore`] with [`Client::save()`] and can be
+/// restored with [`Client::load()`]. No other operation persists the state of
+/// the client or its groups, so [`Client::save()`] has to be called after
+/// every change that should survive a restart.
+#[derive(uniffi::Object)]
+pub struct Client {
+    client: Mutex<MlsClient>,
+    backend: Backend,
+    signer: ForeignSigner,
+}
+
+#[uniffi::export]
+impl Client {
+    /// Creates a new client with the given `identity` that uses the
+    /// ciphersuite with the numeric value `ciphersuite` for all its groups.
+    ///
+    /// The identity is also used to index the client state in the key store.
+    #[uniffi::constructor]
+    pub fn new(
+        identity: Vec<u8>,
+        ciphersuite: u16,
+        key_store: Box<dyn KeyStore>,
+        signer: Box<dyn Signer>,
+    ) -> Result<Arc<Self>, FfiError> {
+        let ciphersuite = Ciphersuite::try_from(ciphersuite)?;
+        let credential_with_key = CredentialWithKey {
+            credential: Credential::new(identity.clone(), CredentialType::Basic)
+                .map_err(FfiError::mls)?,
+            signature_key: signer.public_key().into(),
+        };
+        let mls_group_config = MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .use_ratchet_tree_extension(true)
+            .build();
+
+        Ok(Arc::new(Self {
+            client: Mutex::new(MlsClient::new(
+                identity,
+                credential_with_key,
+                mls_group_config,
+            )),
+            backend: Backend::new(ForeignKeyStore(key_store)),
+            signer: ForeignSigner {
+                signer,
+                signature_scheme: ciphersuite.signature_algorithm(),
+            },
+        }))
+    }
+
+    /// Loads the client with the given `identity` and all its groups from
+    /// the key store.
+    #[uniffi::constructor]
+    pub fn load(
+        identity: Vec<u8>,
+        key_store: Box<dyn KeyStore>,
+        signer: Box<dyn Signer>,
+    ) -> Result<Arc<Self>, FfiError> {
+        let backend = Backend::new(ForeignKeyStore(key_store));
+        let client = MlsClient::load(&backend, identity).map_err(FfiError::mls)?;
+        let signature_scheme = client
+            .mls_group_config()
+            .crypto_config()
+            .ciphersuite
+            .signature_algorithm();
+
+        Ok(Arc::new(Self {
+            client: Mutex::new(client),
+            backend,
+            signer: ForeignSigner {
+                signer,
+                signature_scheme,
+            },
+        }))
+    }
+
+    /// Persists the state of the client and of all its groups in the key
+    /// store.
+    pub fn save(&self) -> Result<(), FfiError> {
+        self.lock().save(&self.backend).map_err(FfiError::mls)
+    }
+
+    /// Returns the identity of the client.
+    pub fn identity(&self) -> Vec<u8> {
+        self.lock()
+            .credential_with_key()
+            .credential
+            .identity()
+            .to_vec()
+    }
+
+    /// Generates a new key package and returns it as a TLS-serialized
+    /// `MLSMessage`.
+    pub fn generate_key_package(&self) -> Result<Vec<u8>, FfiError> {
+        let mut client = self.lock();
+        let ciphersuite = client.mls_group_config().crypto_config().ciphersuite;
+        let key_package = client
+            .generate_key_package(&self.backend, &self.signer, ciphersuite)
+            .map_err(FfiError::mls)?;
+        MlsMessageOut::from(key_package)
+            .to_bytes()
+            .map_err(FfiError::mls)
+    }
+
+    /// Creates a new group with the client as the only member.
+    pub fn create_group(self: Arc<Self>) -> Result<Arc<Group>, FfiError> {
+        let group_id = self
+            .lock()
+            .create_group(&self.backend, &self.signer)
+            .map_err(FfiError::mls)?;
+        Ok(Arc::new(Group::new(self, group_id)))
+    }
+
+    /// Returns the group with the given `group_id`, if it is managed by the
+    /// client.
+    pub fn group(self: Arc<Self>, group_id: Vec<u8>) -> Option<Arc<Group>> {
+        let group_id = GroupId::from_slice(&group_id);
+        if self.lock().group(&group_id).is_none() {
+            return None;
+        }
+        Some(Arc::new(Group::new(self, group_id)))
+    }
+
+    /// Returns the IDs of all groups managed by the client.
+    pub fn group_ids(&self) -> Vec<Vec<u8>> {
+        self.lock()
+            .groups()
+            .map(|group| group.group_id().as_slice().to_vec())
+            .collect()
+    }
+
+    /// Processes the TLS-serialized `MLSMessage` `message`.
+    ///
+    /// Proposals are stored in the proposal queue of their group and commits
+    /// are merged right away. A welcome is used to join a new group, where
+    /// `ratchet_tree` is the TLS-serialized ratchet tree of the group if it
+    /// is not contained in the welcome.
+    ///
+    /// The new state of the client is not persisted. Call [`Client::save()`]
+    /// once the message is processed.
+    pub fn process_message(
+        &self,
+        message: Vec<u8>,
+        ratchet_tree: Option<Vec<u8>>,
+    ) -> Result<ReceivedMessage, FfiError> {
+        let message = MlsMessageIn::tls_deserialize(&mut message.as_slice())?;
+        let ratchet_tree = ratchet_tree
+            .map(|ratchet_tree| RatchetTreeIn::tls_deserialize(&mut ratchet_tree.as_slice()))
+            .transpose()?;
+
+        let mut client = self.lock();
+        let processed_message = match client
+            .process_message(&self.backend, message, ratchet_tree)
+            .map_err(FfiError::mls)?
+        {
+            DispatchedMessage::Processed(processed_message) => processed_message,
+            DispatchedMessage::Joined(group_id) => {
+                return Ok(ReceivedMessage::Joined {
+                    group_id: group_id.as_slice().to_vec(),
+                })
+            }
+            DispatchedMessage::GroupInfo(_) => return Ok(ReceivedMessage::GroupInfo),
+            DispatchedMessage::KeyPackage(_) => return Ok(ReceivedMessage::KeyPackage),
+        };
+
+        let group_id = processed_message.group_id().clone();
+        let sender = match processed_message.sender() {
+            Sender::Member(leaf_index) => Some(leaf_index.u32()),
+            _ => None,
+        };
+        let group = client.group_mut(&group_id).ok_or(FfiError::UnknownGroup)?;
+        let group_id = group_id.as_slice().to_vec();
+
+        Ok(match processed_message.into_content() {
+            ProcessedMessageContent::ApplicationMessage(application_message) => {
+                ReceivedMessage::Application {
+                    group_id,
+                    sender,
+                    data: application_message.into_bytes(),
+                }
+            }
+            ProcessedMessageContent::ProposalMessage(proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(proposal) => {
+                group.store_pending_proposal(*proposal);
+                ReceivedMessage::Proposal { group_id }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                group
+                    .merge_staged_commit(&self.backend, *staged_commit)
+                    .map_err(FfiError::mls)?;
+                ReceivedMessage::Commit {
+                    group_id,
+                    active: group.is_active(),
+                }
+            }
+        })
+    }
+}
+
+impl Client {
+    /// Calls `f` with the group with the given `group_id`, the backend and
+    /// the signer of the client.
+    pub(crate) fn with_group<T>(
+        &self,
+        group_id: &GroupId,
+        f: impl FnOnce(&mut MlsGroup, &Backend, &ForeignSigner) -> Result<T, FfiError>,
+    ) -> Result<T, FfiError> {
+        let mut client = self.lock();
+        let group = client.group_mut(group_id).ok_or(FfiError::UnknownGroup)?;
+        f(group, &self.backend, &self.signer)
+    }
+
+    pub(crate) fn backend(&self) -> &Backend {
+        &self.backend
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MlsClient> {
+        // A panic while holding the lock leaves the client in an unknown
+        // state, so we don't try to recover from a poisoned lock.
+        self.client.lock().expect("The client lock is poisoned.")
+    }
+}
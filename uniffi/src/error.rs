@@ -0,0 +1,109 @@
+//! Errors of the bindings.
+
+use std::fmt::Display;
+
+use openmls::prelude::HasErrorCode;
+use thiserror::Error;
+
+/// The category of an [`FfiError::Mls`].
+///
+/// See [`openmls::prelude::ErrorCategory`] for more details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum ErrorCategory {
+    /// Invalid input.
+    Validation,
+    /// A cryptographic check failed.
+    Crypto,
+    /// The key store returned an error.
+    Storage,
+    /// An internal error of OpenMLS.
+    Library,
+}
+
+impl From<openmls::prelude::ErrorCategory> for ErrorCategory {
+    fn from(category: openmls::prelude::ErrorCategory) -> Self {
+        match category {
+            openmls::prelude::ErrorCategory::Validation => Self::Validation,
+            openmls::prelude::ErrorCategory::Crypto => Self::Crypto,
+            openmls::prelude::ErrorCategory::Storage => Self::Storage,
+            openmls::prelude::ErrorCategory::Library => Self::Library,
+        }
+    }
+}
+
+/// Errors returned by the bindings.
+#[derive(Error, Debug, PartialEq, Clone, uniffi::Error)]
+pub enum FfiError {
+    /// An error returned by OpenMLS. The `code` is the stable error code of
+    /// the error, see [`HasErrorCode`].
+    #[error("{message}")]
+    Mls {
+        code: u32,
+        category: ErrorCategory,
+        message: String,
+    },
+    /// A message could not be encoded or decoded.
+    #[error("A message could not be encoded or decoded: {message}")]
+    Codec { message: String },
+    /// The input is not of the expected type, e.g., a welcome was passed
+    /// where a key package was expected.
+    #[error("The input is not of the expected type: {message}")]
+    InvalidInput { message: String },
+    /// The group is not managed by the client.
+    #[error("The group is not managed by the client.")]
+    UnknownGroup,
+    /// A callback into the foreign language failed.
+    #[error("A callback failed: {message}")]
+    Callback { message: String },
+}
+
+impl FfiError {
+    /// Converts an OpenMLS error into an [`FfiError::Mls`].
+    pub(crate) fn mls(error: impl HasErrorCode + Display) -> Self {
+        Self::Mls {
+            code: error.code(),
+            category: error.category().into(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<tls_codec::Error> for FfiError {
+    fn from(error: tls_codec::Error) -> Self {
+        Self::Codec {
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Errors returned by the [`KeyStore`](crate::KeyStore) and
+/// [`Signer`](crate::Signer) callback interfaces.
+#[derive(Error, Debug, PartialEq, Clone, uniffi::Error)]
+pub enum CallbackError {
+    /// The callback failed.
+    #[error("{message}")]
+    Failed { message: String },
+    /// The signer is temporarily unavailable, e.g., because the key is
+    /// locked. Retrying the operation may succeed.
+    #[error("The signer is temporarily unavailable.")]
+    SignerUnavailable,
+    /// The user didn't confirm the signing.
+    #[error("The signing was cancelled.")]
+    SigningCancelled,
+}
+
+impl From<uniffi::UnexpectedUniFFICallbackError> for CallbackError {
+    fn from(error: uniffi::UnexpectedUniFFICallbackError) -> Self {
+        Self::Failed {
+            message: error.reason,
+        }
+    }
+}
+
+impl From<CallbackError> for FfiError {
+    fn from(error: CallbackError) -> Self {
+        Self::Callback {
+            message: error.to_string(),
+        }
+    }
+}
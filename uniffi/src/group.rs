@@ -0,0 +1,205 @@
+//! The group object of the bindings.
+
+use std::sync::Arc;
+
+use openmls::prelude::{
+    GroupId, KeyPackage, LeafNodeIndex, MlsMessageIn, MlsMessageInBody, OpenMlsCryptoProvider,
+    TlsDeserializeTrait, TlsSerializeTrait,
+};
+
+use crate::{client::Client, error::FfiError, message::CommitBundle};
+
+/// A member of a [`Group`].
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct Member {
+    /// The leaf index of the member.
+    pub leaf_index: u32,
+    /// The identity of the credential of the member.
+    pub identity: Vec<u8>,
+    /// The signature public key of the member.
+    pub signature_key: Vec<u8>,
+    /// The DER-encoded certificates of the member's X.509 credential,
+    /// starting with the leaf certificate. Empty for all other credentials.
+    pub certificate_chain: Vec<Vec<u8>>,
+}
+
+/// A group managed by a [`Client`].
+///
+/// All messages returned by the group are TLS-serialized `MLSMessage`s that
+/// have to be sent to the other members. Commits created by the group have
+/// to be merged with [`Group::merge_pending_commit()`] once they were
+/// accepted by the delivery service.
+#[derive(uniffi::Object)]
+pub struct Group {
+    client: Arc<Client>,
+    group_id: GroupId,
+}
+
+impl Group {
+    pub(crate) fn new(client: Arc<Client>, group_id: GroupId) -> Self {
+        Self { client, group_id }
+    }
+}
+
+#[uniffi::export]
+impl Group {
+    /// Returns the ID of the group.
+    pub fn group_id(&self) -> Vec<u8> {
+        self.group_id.as_slice().to_vec()
+    }
+
+    /// Returns the current epoch of the group.
+    pub fn epoch(&self) -> Result<u64, FfiError> {
+        self.client
+            .with_group(&self.group_id, |group, _, _| Ok(group.epoch().as_u64()))
+    }
+
+    /// Returns `true` if the client is still a member of the group.
+    pub fn is_active(&self) -> Result<bool, FfiError> {
+        self.client
+            .with_group(&self.group_id, |group, _, _| Ok(group.is_active()))
+    }
+
+    /// Returns the members of the group.
+    pub fn members(&self) -> Result<Vec<Member>, FfiError> {
+        self.client.with_group(&self.group_id, |group, _, _| {
+            Ok(group
+                .members()
+                .map(|member| Member {
+                    leaf_index: member.index.u32(),
+                    identity: member.credential.identity().to_vec(),
+                    certificate_chain: member
+                        .certificate_chain()
+                        .map(|certificate_chain| {
+                            certificate_chain
+                                .certificates()
+                                .iter()
+                                .map(|certificate| certificate.cert_data().to_vec())
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    signature_key: member.signature_key,
+                })
+                .collect())
+        })
+    }
+
+    /// Adds the members with the given TLS-serialized key packages to the
+    /// group.
+    pub fn add_members(&self, key_packages: Vec<Vec<u8>>) -> Result<CommitBundle, FfiError> {
+        let key_packages = key_packages
+            .iter()
+            .map(|key_package| self.key_package(key_package))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.client
+            .with_group(&self.group_id, |group, backend, signer| {
+                let (commit, welcome, group_info) = group
+                    .add_members(backend, signer, &key_packages)
+                    .map_err(FfiError::mls)?;
+                CommitBundle::new(commit, Some(welcome), group_info)
+            })
+    }
+
+    /// Removes the members at the given leaf indices from the group.
+    pub fn remove_members(&self, leaf_indices: Vec<u32>) -> Result<CommitBundle, FfiError> {
+        let members: Vec<LeafNodeIndex> =
+            leaf_indices.into_iter().map(LeafNodeIndex::new).collect();
+
+        self.client
+            .with_group(&self.group_id, |group, backend, signer| {
+                let (commit, welcome, group_info) = group
+                    .remove_members(backend, signer, &members)
+                    .map_err(FfiError::mls)?;
+                CommitBundle::new(commit, welcome, group_info)
+            })
+    }
+
+    /// Updates the own leaf of the client with fresh key material.
+    pub fn self_update(&self) -> Result<CommitBundle, FfiError> {
+        self.client
+            .with_group(&self.group_id, |group, backend, signer| {
+                let (commit, welcome, group_info) =
+                    group.self_update(backend, signer).map_err(FfiError::mls)?;
+                CommitBundle::new(commit, welcome, group_info)
+            })
+    }
+
+    /// Creates a proposal to remove the client from the group. The client
+    /// leaves the group once another member commits the proposal.
+    pub fn leave(&self) -> Result<Vec<u8>, FfiError> {
+        self.client
+            .with_group(&self.group_id, |group, backend, signer| {
+                group
+                    .leave_group(backend, signer)
+                    .map_err(FfiError::mls)?
+                    .to_bytes()
+                    .map_err(FfiError::mls)
+            })
+    }
+
+    /// Merges the pending commit of the client.
+    pub fn merge_pending_commit(&self) -> Result<(), FfiError> {
+        self.client.with_group(&self.group_id, |group, backend, _| {
+            group.merge_pending_commit(backend).map_err(FfiError::mls)
+        })
+    }
+
+    /// Discards the pending commit of the client, e.g., because the delivery
+    /// service rejected it.
+    pub fn clear_pending_commit(&self) -> Result<(), FfiError> {
+        self.client.with_group(&self.group_id, |group, _, _| {
+            group.clear_pending_commit();
+            Ok(())
+        })
+    }
+
+    /// Encrypts the application message `data` for the group.
+    pub fn create_message(&self, data: Vec<u8>) -> Result<Vec<u8>, FfiError> {
+        self.client
+            .with_group(&self.group_id, |group, backend, signer| {
+                group
+                    .create_message(backend, signer, &data)
+                    .map_err(FfiError::mls)?
+                    .to_bytes()
+                    .map_err(FfiError::mls)
+            })
+    }
+
+    /// Exports a secret of `length` bytes from the current epoch of the
+    /// group.
+    pub fn export_secret(
+        &self,
+        label: String,
+        context: Vec<u8>,
+        length: u32,
+    ) -> Result<Vec<u8>, FfiError> {
+        self.client.with_group(&self.group_id, |group, backend, _| {
+            group
+                .export_secret(backend, &label, &context, length as usize)
+                .map_err(FfiError::mls)
+        })
+    }
+
+    /// Returns the TLS-serialized ratchet tree of the group, which new
+    /// members need to join the group if the welcome doesn't contain it.
+    pub fn export_ratchet_tree(&self) -> Result<Vec<u8>, FfiError> {
+        self.client.with_group(&self.group_id, |group, _, _| {
+            Ok(group.export_ratchet_tree().tls_serialize_detached()?)
+        })
+    }
+}
+
+impl Group {
+    /// Decodes and validates a TLS-serialized key package.
+    fn key_package(&self, bytes: &[u8]) -> Result<KeyPackage, FfiError> {
+        match MlsMessageIn::tls_deserialize(&mut &*bytes)?.extract() {
+            MlsMessageInBody::KeyPackage(key_package) => key_package
+                .validate(self.client.backend().crypto())
+                .map_err(FfiError::mls),
+            _ => Err(FfiError::InvalidInput {
+                message: "Expected a key package.".into(),
+            }),
+        }
+    }
+}
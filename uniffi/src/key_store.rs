@@ -0,0 +1,46 @@
+//! The key store callback interface.
+
+use openmls_traits::key_store::{MlsEntity, OpenMlsKeyStore};
+
+use crate::error::CallbackError;
+
+/// A key value store implemented in the foreign language.
+///
+/// Values are serialized by the bindings, the key store only has to persist
+/// opaque bytes.
+#[uniffi::export(callback_interface)]
+pub trait KeyStore: Send + Sync {
+    /// Stores the `value` for the `key`, replacing any previous value.
+    fn store(&self, key: Vec<u8>, value: Vec<u8>) -> Result<(), CallbackError>;
+
+    /// Returns the value stored for the `key`, if any.
+    fn read(&self, key: Vec<u8>) -> Option<Vec<u8>>;
+
+    /// Deletes the value stored for the `key`.
+    fn delete(&self, key: Vec<u8>) -> Result<(), CallbackError>;
+}
+
+/// Adapter that implements [`OpenMlsKeyStore`] on top of a foreign
+/// [`KeyStore`].
+pub(crate) struct ForeignKeyStore(pub(crate) Box<dyn KeyStore>);
+
+impl OpenMlsKeyStore for ForeignKeyStore {
+    type Error = CallbackError;
+
+    fn store<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), Self::Error> {
+        let value = serde_json::to_vec(v).map_err(|e| CallbackError::Failed {
+            message: e.to_string(),
+        })?;
+        self.0.store(k.to_vec(), value)
+    }
+
+    fn read<V: MlsEntity>(&self, k: &[u8]) -> Option<V> {
+        self.0
+            .read(k.to_vec())
+            .and_then(|value| serde_json::from_slice(&value).ok())
+    }
+
+    fn delete<V: MlsEntity>(&self, k: &[u8]) -> Result<(), Self::Error> {
+        self.0.delete(k.to_vec())
+    }
+}
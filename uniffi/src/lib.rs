@@ -0,0 +1,33 @@
+//! # OpenMLS UniFFI
+//!
+//! [UniFFI](https://mozilla.github.io/uniffi-rs/) scaffolding over a
+//! simplified client API of OpenMLS, which is used to generate Kotlin and
+//! Swift bindings.
+//!
+//! The API is built around a [`Client`] that wraps an
+//! [`MlsClient`](openmls::prelude::MlsClient) and hands out [`Group`]
+//! objects for the groups it manages. All messages are passed as
+//! TLS-serialized `MLSMessage`s. The key store and the signer of the client
+//! are implemented in the foreign language through the [`KeyStore`] and
+//! [`Signer`] callback interfaces, so that private key material never has to
+//! cross the FFI boundary.
+
+mod client;
+mod error;
+mod group;
+mod key_store;
+mod message;
+mod provider;
+mod signer;
+
+#[cfg(test)]
+mod tests;
+
+pub use client::Client;
+pub use error::{CallbackError, ErrorCategory, FfiError};
+pub use group::{Group, Member};
+pub use key_store::KeyStore;
+pub use message::{CommitBundle, ReceivedMessage};
+pub use signer::Signer;
+
+uniffi::setup_scaffolding!();
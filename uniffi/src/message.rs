@@ -0,0 +1,62 @@
+//! Message types of the bindings.
+
+use openmls::prelude::{GroupInfo, MlsMessageOut};
+
+use crate::error::FfiError;
+
+/// The messages created by a commit. All messages are TLS-serialized
+/// `MLSMessage`s.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct CommitBundle {
+    /// The commit that has to be sent to the group.
+    pub commit: Vec<u8>,
+    /// The welcome for new members, if the commit adds members.
+    pub welcome: Option<Vec<u8>>,
+    /// The group info of the new epoch, if the group uses the ratchet tree
+    /// extension.
+    pub group_info: Option<Vec<u8>>,
+}
+
+impl CommitBundle {
+    pub(crate) fn new(
+        commit: MlsMessageOut,
+        welcome: Option<MlsMessageOut>,
+        group_info: Option<GroupInfo>,
+    ) -> Result<Self, FfiError> {
+        Ok(Self {
+            commit: commit.to_bytes().map_err(FfiError::mls)?,
+            welcome: welcome
+                .map(|welcome| welcome.to_bytes())
+                .transpose()
+                .map_err(FfiError::mls)?,
+            group_info: group_info
+                .map(|group_info| MlsMessageOut::from(group_info).to_bytes())
+                .transpose()
+                .map_err(FfiError::mls)?,
+        })
+    }
+}
+
+/// The result of [`Client::process_message()`](crate::Client::process_message).
+#[derive(Debug, Clone, PartialEq, uniffi::Enum)]
+pub enum ReceivedMessage {
+    /// An application message. The `sender` is the leaf index of the sender,
+    /// or `None` if the sender is not a member of the group.
+    Application {
+        group_id: Vec<u8>,
+        sender: Option<u32>,
+        data: Vec<u8>,
+    },
+    /// A proposal that was stored in the proposal queue of the group. It is
+    /// covered by the next commit of the group.
+    Proposal { group_id: Vec<u8> },
+    /// A commit that was merged into the group. `active` is `false` if the
+    /// client was removed from the group by the commit.
+    Commit { group_id: Vec<u8>, active: bool },
+    /// A welcome that was used to join the group.
+    Joined { group_id: Vec<u8> },
+    /// A group info that is not bound to any group of the client.
+    GroupInfo,
+    /// A key package that is not bound to any group of the client.
+    KeyPackage,
+}
@@ -0,0 +1,40 @@
+//! The crypto provider of the bindings.
+
+use openmls_rust_crypto::RustCrypto;
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::key_store::ForeignKeyStore;
+
+/// Crypto provider that uses [`RustCrypto`] for the cryptographic primitives
+/// and the foreign key store.
+pub(crate) struct Backend {
+    crypto: RustCrypto,
+    key_store: ForeignKeyStore,
+}
+
+impl Backend {
+    pub(crate) fn new(key_store: ForeignKeyStore) -> Self {
+        Self {
+            crypto: RustCrypto::default(),
+            key_store,
+        }
+    }
+}
+
+impl OpenMlsCryptoProvider for Backend {
+    type CryptoProvider = RustCrypto;
+    type RandProvider = RustCrypto;
+    type KeyStoreProvider = ForeignKeyStore;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        &self.crypto
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        &self.crypto
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
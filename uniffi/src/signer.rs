@@ -0,0 +1,44 @@
+//! The signer callback interface.
+
+use openmls_traits::types::{Error, SignatureScheme};
+
+use crate::error::CallbackError;
+
+/// A signer implemented in the foreign language, e.g., backed by the
+/// platform key store.
+///
+/// The signature scheme of the signer has to match the ciphersuite of the
+/// client. A signer that requires the user's presence, e.g., one backed by a
+/// secure element, returns [`CallbackError::SignerUnavailable`] or
+/// [`CallbackError::SigningCancelled`], which are passed on to the caller of
+/// the operation.
+#[uniffi::export(callback_interface)]
+pub trait Signer: Send + Sync {
+    /// Signs the `payload` and returns the signature.
+    fn sign(&self, payload: Vec<u8>) -> Result<Vec<u8>, CallbackError>;
+
+    /// Returns the public key of the signer.
+    fn public_key(&self) -> Vec<u8>;
+}
+
+/// Adapter that implements the OpenMLS
+/// [`Signer`](openmls_traits::signatures::Signer) on top of a foreign
+/// [`Signer`].
+pub(crate) struct ForeignSigner {
+    pub(crate) signer: Box<dyn Signer>,
+    pub(crate) signature_scheme: SignatureScheme,
+}
+
+impl openmls_traits::signatures::Signer for ForeignSigner {
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>, Error> {
+        self.signer.sign(payload.to_vec()).map_err(|e| match e {
+            CallbackError::SignerUnavailable => Error::SignerUnavailable,
+            CallbackError::SigningCancelled => Error::SigningCancelled,
+            CallbackError::Failed { .. } => Error::SigningError,
+        })
+    }
+
+    fn signature_scheme(&self) -> SignatureScheme {
+        self.signature_scheme
+    }
+}
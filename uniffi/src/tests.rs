@@ -0,0 +1,203 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use openmls_basic_credential::SignatureKeyPair;
+use openmls_traits::types::Ciphersuite;
+
+use crate::{CallbackError, Client, Group, KeyStore, ReceivedMessage, Signer};
+
+const CIPHERSUITE: Ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+
+/// A key store that can be shared between several instances of a client.
+#[derive(Clone, Default)]
+struct TestKeyStore(Arc<RwLock<HashMap<Vec<u8>, Vec<u8>>>>);
+
+impl KeyStore for TestKeyStore {
+    fn store(&self, key: Vec<u8>, value: Vec<u8>) -> Result<(), CallbackError> {
+        self.0.write().unwrap().insert(key, value);
+        Ok(())
+    }
+
+    fn read(&self, key: Vec<u8>) -> Option<Vec<u8>> {
+        self.0.read().unwrap().get(&key).cloned()
+    }
+
+    fn delete(&self, key: Vec<u8>) -> Result<(), CallbackError> {
+        self.0.write().unwrap().remove(&key);
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct TestSigner(Arc<SignatureKeyPair>);
+
+impl TestSigner {
+    fn new() -> Self {
+        Self(Arc::new(
+            SignatureKeyPair::new(CIPHERSUITE.signature_algorithm()).unwrap(),
+        ))
+    }
+}
+
+impl Signer for TestSigner {
+    fn sign(&self, payload: Vec<u8>) -> Result<Vec<u8>, CallbackError> {
+        openmls_traits::signatures::Signer::sign(self.0.as_ref(), &payload).map_err(|e| {
+            CallbackError::Failed {
+                message: format!("{e:?}"),
+            }
+        })
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        self.0.to_public_vec()
+    }
+}
+
+fn new_client(identity: &str) -> (Arc<Client>, TestKeyStore, TestSigner) {
+    let key_store = TestKeyStore::default();
+    let signer = TestSigner::new();
+    let client = Client::new(
+        identity.into(),
+        CIPHERSUITE.into(),
+        Box::new(key_store.clone()),
+        Box::new(signer.clone()),
+    )
+    .expect("Could not create client.");
+    (client, key_store, signer)
+}
+
+/// Alice creates a group and adds Bob, who joins with the Welcome. Returns
+/// the group of both clients.
+fn create_group(alice: &Arc<Client>, bob: &Arc<Client>) -> (Arc<Group>, Arc<Group>) {
+    let key_package = bob
+        .generate_key_package()
+        .expect("Could not generate key package.");
+    let alice_group = alice
+        .clone()
+        .create_group()
+        .expect("Could not create group.");
+    let commit_bundle = alice_group
+        .add_members(vec![key_package])
+        .expect("Could not add Bob.");
+    alice_group
+        .merge_pending_commit()
+        .expect("Could not merge commit.");
+
+    let received = bob
+        .process_message(commit_bundle.welcome.expect("Missing Welcome."), None)
+        .expect("Could not process Welcome.");
+    assert_eq!(
+        received,
+        ReceivedMessage::Joined {
+            group_id: alice_group.group_id()
+        }
+    );
+    let bob_group = bob
+        .clone()
+        .group(alice_group.group_id())
+        .expect("Bob is not in the group.");
+    (alice_group, bob_group)
+}
+
+#[test]
+fn round_trip() {
+    let (alice, _alice_key_store, _alice_signer) = new_client("Alice");
+    let (bob, _bob_key_store, _bob_signer) = new_client("Bob");
+    let (alice_group, bob_group) = create_group(&alice, &bob);
+    assert_eq!(bob_group.epoch().unwrap(), alice_group.epoch().unwrap());
+    assert_eq!(bob_group.members().unwrap(), alice_group.members().unwrap());
+
+    // === Alice and Bob exchange application messages ===
+    let message = alice_group.create_message(b"Hello Bob".to_vec()).unwrap();
+    assert_eq!(
+        bob.process_message(message, None).unwrap(),
+        ReceivedMessage::Application {
+            group_id: alice_group.group_id(),
+            sender: Some(0),
+            data: b"Hello Bob".to_vec(),
+        }
+    );
+    let message = bob_group.create_message(b"Hello Alice".to_vec()).unwrap();
+    assert_eq!(
+        alice.process_message(message, None).unwrap(),
+        ReceivedMessage::Application {
+            group_id: alice_group.group_id(),
+            sender: Some(1),
+            data: b"Hello Alice".to_vec(),
+        }
+    );
+}
+
+#[test]
+fn process_message_merges_commits() {
+    let (alice, _alice_key_store, _alice_signer) = new_client("Alice");
+    let (bob, _bob_key_store, _bob_signer) = new_client("Bob");
+    let (alice_group, bob_group) = create_group(&alice, &bob);
+
+    // === Bob merges Alice's update right away ===
+    let commit_bundle = alice_group.self_update().unwrap();
+    alice_group.merge_pending_commit().unwrap();
+    assert_eq!(
+        bob.process_message(commit_bundle.commit, None).unwrap(),
+        ReceivedMessage::Commit {
+            group_id: alice_group.group_id(),
+            active: true,
+        }
+    );
+    assert_eq!(bob_group.epoch().unwrap(), alice_group.epoch().unwrap());
+    assert_eq!(
+        bob_group.export_secret("test".into(), vec![], 32).unwrap(),
+        alice_group
+            .export_secret("test".into(), vec![], 32)
+            .unwrap()
+    );
+
+    // === Bob is removed by Alice ===
+    let commit_bundle = alice_group.remove_members(vec![1]).unwrap();
+    alice_group.merge_pending_commit().unwrap();
+    assert_eq!(
+        bob.process_message(commit_bundle.commit, None).unwrap(),
+        ReceivedMessage::Commit {
+            group_id: alice_group.group_id(),
+            active: false,
+        }
+    );
+    assert!(!bob_group.is_active().unwrap());
+}
+
+#[test]
+fn process_message_does_not_persist_state() {
+    let (alice, _alice_key_store, _alice_signer) = new_client("Alice");
+    let (bob, bob_key_store, bob_signer) = new_client("Bob");
+    let (alice_group, bob_group) = create_group(&alice, &bob);
+    bob.save().expect("Could not save client.");
+    let saved_epoch = bob_group.epoch().unwrap();
+
+    let commit_bundle = alice_group.self_update().unwrap();
+    alice_group.merge_pending_commit().unwrap();
+    bob.process_message(commit_bundle.commit, None).unwrap();
+    assert_ne!(bob_group.epoch().unwrap(), saved_epoch);
+
+    // The key store still contains the state from before the commit.
+    let load = || {
+        Client::load(
+            b"Bob".to_vec(),
+            Box::new(bob_key_store.clone()),
+            Box::new(bob_signer.clone()),
+        )
+        .expect("Could not load client.")
+    };
+    let loaded_group = load()
+        .group(alice_group.group_id())
+        .expect("Bob is not in the group.");
+    assert_eq!(loaded_group.epoch().unwrap(), saved_epoch);
+
+    // The new state is only persisted with `save`.
+    bob.save().expect("Could not save client.");
+    let loaded_group = load()
+        .group(alice_group.group_id())
+        .expect("Bob is not in the group.");
+    assert_eq!(loaded_group.epoch().unwrap(), bob_group.epoch().unwrap());
+}